@@ -9,6 +9,8 @@ use sdl2::{
     EventPump, TimerSubsystem,
 };
 
+use crate::crash_report::{self, log, ActorCounts};
+
 const THICKNESS: u32 = 15;
 const PADDLE_HEIGHT: f32 = 100.0;
 
@@ -17,14 +19,90 @@ struct Vector2 {
     y: f32,
 }
 
+/// Paddle and ball, updated without SDL
+struct Pong {
+    paddle_position: Vector2,
+    ball_position: Vector2,
+    ball_velocity: Vector2,
+}
+
+impl Pong {
+    fn new() -> Self {
+        let paddle_position = Vector2 {
+            x: 10.0,
+            y: 768.0 / 2.0,
+        };
+
+        let ball_position = Vector2 {
+            x: 1024.0 / 2.0,
+            y: 768.0 / 2.0,
+        };
+
+        let ball_velocity = Vector2 {
+            x: -200.0,
+            y: 235.0,
+        };
+
+        Self {
+            paddle_position,
+            ball_position,
+            ball_velocity,
+        }
+    }
+
+    /// Returns false when the ball went past the paddle
+    fn update(&mut self, delta_time: f32, paddle_dir: i32) -> bool {
+        if paddle_dir != 0 {
+            self.paddle_position.y += paddle_dir as f32 * 300.0 * delta_time;
+            self.paddle_position.y = self.paddle_position.y.clamp(
+                PADDLE_HEIGHT / 2.0 + THICKNESS as f32,
+                768.0 - PADDLE_HEIGHT / 2.0 - THICKNESS as f32,
+            );
+        }
+
+        self.ball_position.x += self.ball_velocity.x * delta_time;
+        self.ball_position.y += self.ball_velocity.y * delta_time;
+
+        let diff = (self.paddle_position.y - self.ball_position.y).abs();
+
+        if diff <= PADDLE_HEIGHT / 2.0
+            && self.ball_position.x <= 25.0
+            && self.ball_position.x >= 20.0
+            && self.ball_velocity.x < 0.0
+        {
+            self.ball_velocity.x *= -1.0;
+        } else if self.ball_position.x <= 0.0 {
+            return false;
+        } else if self.ball_position.x >= 1024.0 - THICKNESS as f32 && self.ball_velocity.x > 0.0 {
+            self.ball_velocity.x *= -1.0;
+        } else if self.ball_position.y <= THICKNESS as f32 && self.ball_velocity.y < 0.0 {
+            self.ball_velocity.y *= -1.0;
+        } else if self.ball_position.y >= 768.0 - THICKNESS as f32 && self.ball_velocity.y > 0.0 {
+            self.ball_velocity.y *= -1.0;
+        }
+
+        true
+    }
+
+    /// The paddle and the ball as actors, the ball is dead once it went past the paddle
+    fn get_actor_counts(&self) -> ActorCounts {
+        let mut counts = ActorCounts::new();
+        counts.active += 1;
+        if self.ball_position.x <= 0.0 {
+            counts.dead += 1;
+        } else {
+            counts.active += 1;
+        }
+        counts
+    }
+}
+
 pub struct Game {
     canvas: Canvas<Window>,
     event_pump: EventPump,
     timer: TimerSubsystem,
     is_running: bool,
-    paddle_position: Vector2,
-    ball_position: Vector2,
-    ball_velocity: Vector2,
+    pong: Pong,
     tick_count: u64,
     paddle_dir: i32,
 }
@@ -47,29 +125,12 @@ impl Game {
 
         let timer = sdl.timer().map_err(|e| anyhow!(e))?;
 
-        let paddle_position = Vector2 {
-            x: 10.0,
-            y: 768.0 / 2.0,
-        };
-
-        let ball_position = Vector2 {
-            x: 1024.0 / 2.0,
-            y: 768.0 / 2.0,
-        };
-
-        let ball_velocity = Vector2 {
-            x: -200.0,
-            y: 235.0,
-        };
-
         Ok(Game {
             canvas,
             event_pump,
             timer,
             is_running: true,
-            paddle_position,
-            ball_position,
-            ball_velocity,
+            pong: Pong::new(),
             tick_count: 0,
             paddle_dir: 0,
         })
@@ -81,6 +142,7 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            crash_report::end_frame(self.pong.get_actor_counts());
         }
     }
 
@@ -119,33 +181,9 @@ impl Game {
 
         self.tick_count = self.timer.ticks64();
 
-        if self.paddle_dir != 0 {
-            self.paddle_position.y += self.paddle_dir as f32 * 300.0 * delta_time;
-            self.paddle_position.y = self.paddle_position.y.clamp(
-                PADDLE_HEIGHT / 2.0 + THICKNESS as f32,
-                768.0 - PADDLE_HEIGHT / 2.0 - THICKNESS as f32,
-            );
-        }
-
-        self.ball_position.x += self.ball_velocity.x * delta_time;
-        self.ball_position.y += self.ball_velocity.y * delta_time;
-
-        let diff = (self.paddle_position.y - self.ball_position.y).abs();
-
-        if diff <= PADDLE_HEIGHT / 2.0
-            && self.ball_position.x <= 25.0
-            && self.ball_position.x >= 20.0
-            && self.ball_velocity.x < 0.0
-        {
-            self.ball_velocity.x *= -1.0;
-        } else if self.ball_position.x <= 0.0 {
+        if !self.pong.update(delta_time, self.paddle_dir) {
+            log!("Ball missed the paddle");
             self.is_running = false;
-        } else if self.ball_position.x >= 1024.0 - THICKNESS as f32 && self.ball_velocity.x > 0.0 {
-            self.ball_velocity.x *= -1.0;
-        } else if self.ball_position.y <= THICKNESS as f32 && self.ball_velocity.y < 0.0 {
-            self.ball_velocity.y *= -1.0;
-        } else if self.ball_position.y >= 768.0 - THICKNESS as f32 && self.ball_velocity.y > 0.0 {
-            self.ball_velocity.y *= -1.0;
         }
     }
 
@@ -170,8 +208,8 @@ impl Game {
 
         // Draw paddle
         let paddle = Rect::new(
-            self.paddle_position.x as i32,
-            self.paddle_position.y as i32 - PADDLE_HEIGHT as i32 / 2,
+            self.pong.paddle_position.x as i32,
+            self.pong.paddle_position.y as i32 - PADDLE_HEIGHT as i32 / 2,
             THICKNESS,
             PADDLE_HEIGHT as u32,
         );
@@ -179,8 +217,8 @@ impl Game {
 
         // Draw ball
         let ball = Rect::new(
-            self.ball_position.x as i32 - THICKNESS as i32 / 2,
-            self.ball_position.y as i32 - THICKNESS as i32 / 2,
+            self.pong.ball_position.x as i32 - THICKNESS as i32 / 2,
+            self.pong.ball_position.y as i32 - THICKNESS as i32 / 2,
             THICKNESS,
             THICKNESS,
         );
@@ -189,3 +227,48 @@ impl Game {
         self.canvas.present();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Pong, Vector2};
+
+    const DELTA_TIME: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn test_ball_bounces_off_walls_and_paddle() {
+        let mut pong = Pong::new();
+        pong.ball_velocity = Vector2 { x: 200.0, y: 235.0 };
+
+        // Bounces off the bottom wall, then the right wall
+        for _ in 0..200 {
+            assert!(pong.update(DELTA_TIME, 0));
+        }
+        assert!(pong.ball_velocity.y < 0.0);
+        assert!(pong.ball_velocity.x < 0.0);
+
+        // The paddle waits where the ball comes back
+        pong.ball_position = Vector2 { x: 100.0, y: 300.0 };
+        pong.ball_velocity = Vector2 { x: -200.0, y: 0.0 };
+        pong.paddle_position.y = 300.0;
+        for _ in 0..30 {
+            assert!(pong.update(DELTA_TIME, 0));
+        }
+        assert!(pong.ball_velocity.x > 0.0);
+    }
+
+    #[test]
+    fn test_missed_ball_ends_game() {
+        let mut pong = Pong::new();
+        pong.ball_velocity = Vector2 { x: -200.0, y: 0.0 };
+        pong.ball_position.y = 100.0;
+        // Moving away from the ball
+        let mut frames = 0;
+        while pong.update(DELTA_TIME, 1) {
+            frames += 1;
+            assert!(frames < 300, "The missed ball never ended the game");
+        }
+        assert!(pong.ball_position.x <= 0.0);
+        assert_eq!(1, pong.get_actor_counts().active);
+        assert_eq!(1, pong.get_actor_counts().dead);
+    }
+}
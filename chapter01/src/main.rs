@@ -1,9 +1,12 @@
+mod crash_report;
 mod game;
 
 use anyhow::Result;
 use game::Game;
 
 fn main() -> Result<()> {
+    crash_report::install();
+
     let mut game = Game::initialize()?;
     game.run_loop();
 
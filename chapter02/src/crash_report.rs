@@ -0,0 +1,169 @@
+use std::{
+    backtrace::Backtrace,
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    panic,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::actors::actor::State;
+
+// Lines of the log kept for the report
+const LOG_CAPACITY: usize = 64;
+
+thread_local! {
+    // Last lines logged, oldest first
+    static RECENT_LOG: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+    static FRAME: Cell<u64> = const { Cell::new(0) };
+    // Actors by state at the end of the last frame
+    static ACTOR_COUNTS: Cell<ActorCounts> = const { Cell::new(ActorCounts::new()) };
+}
+
+/// Number of actors in each state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActorCounts {
+    pub active: usize,
+    pub paused: usize,
+    pub dead: usize,
+}
+
+impl ActorCounts {
+    pub const fn new() -> Self {
+        Self {
+            active: 0,
+            paused: 0,
+            dead: 0,
+        }
+    }
+
+    pub fn add(&mut self, state: &State) {
+        match state {
+            State::Active => self.active += 1,
+            State::Paused => self.paused += 1,
+            State::Dead => self.dead += 1,
+        }
+    }
+}
+
+/// println! that also keeps the line for the crash report
+macro_rules! log {
+    ($($arg:tt)*) => {
+        $crate::crash_report::write_log(format!($($arg)*))
+    };
+}
+
+pub(crate) use log;
+
+/// Print the line and keep it, dropping the oldest past the capacity
+pub fn write_log(line: String) {
+    println!("{}", line);
+    RECENT_LOG.with(|recent_log| {
+        let mut recent_log = recent_log.borrow_mut();
+        if recent_log.len() == LOG_CAPACITY {
+            recent_log.pop_front();
+        }
+        recent_log.push_back(line);
+    });
+}
+
+/// Called once per frame, with the actors as they are at the end of it
+pub fn end_frame(actor_counts: ActorCounts) {
+    FRAME.with(|frame| frame.set(frame.get() + 1));
+    ACTOR_COUNTS.with(|counts| counts.set(actor_counts));
+}
+
+/// Write a crash file in the working directory when the game panics, then panic as usual.
+/// The state is only read from what was recorded, the actors may be borrowed at that point
+pub fn install() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let message = match info.payload().downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match info.payload().downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => "Unknown panic".to_string(),
+            },
+        };
+        let location = info
+            .location()
+            .map(|location| location.to_string())
+            .unwrap_or_else(|| "unknown location".to_string());
+        let backtrace = Backtrace::force_capture().to_string();
+
+        let report = format_report(&message, &location, &backtrace);
+        let path = get_crash_path();
+        match std::fs::write(&path, report) {
+            Ok(_) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+
+        default_hook(info);
+    }));
+}
+
+fn get_crash_path() -> PathBuf {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    PathBuf::from(format!("crash-{}-{}.txt", env!("CARGO_PKG_NAME"), seconds))
+}
+
+fn format_report(message: &str, location: &str, backtrace: &str) -> String {
+    let frame = FRAME.with(|frame| frame.get());
+    let counts = ACTOR_COUNTS.with(|counts| counts.get());
+    let recent_log = RECENT_LOG.with(|recent_log| {
+        recent_log
+            .borrow()
+            .iter()
+            .map(|line| format!("  {}\n", line))
+            .collect::<String>()
+    });
+
+    format!(
+        "{} {} crashed\n\
+         Panic: {} at {}\n\n\
+         Frame {}: {} actors active, {} paused, {} dead\n\n\
+         Recent log (oldest first):\n{}\n\
+         Backtrace:\n{}\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        message,
+        location,
+        frame,
+        counts.active,
+        counts.paused,
+        counts.dead,
+        recent_log,
+        backtrace
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actors::actor::State;
+
+    use super::{end_frame, format_report, write_log, ActorCounts, LOG_CAPACITY};
+
+    #[test]
+    fn test_format_report() {
+        for i in 0..LOG_CAPACITY + 2 {
+            write_log(format!("line {}", i));
+        }
+        let mut counts = ActorCounts::new();
+        for state in [State::Active, State::Active, State::Paused, State::Dead] {
+            counts.add(&state);
+        }
+        end_frame(counts);
+
+        let report = format_report("already borrowed", "src/game.rs:1:1", "0: main");
+        assert!(report.contains("Panic: already borrowed at src/game.rs:1:1"));
+        assert!(report.contains("Frame 1: 2 actors active, 1 paused, 1 dead"));
+        assert!(report.contains("0: main"));
+        // Only the last lines are kept
+        assert!(!report.contains("line 1\n"));
+        assert!(report.contains("line 2\n"));
+        assert!(report.contains(&format!("line {}\n", LOG_CAPACITY + 1)));
+    }
+}
@@ -16,6 +16,7 @@ use crate::{
     actors::ship::Ship,
     components::bg_sprite_component::BGSpriteComponent,
     components::sprite_component::SpriteComponent,
+    crash_report::{self, log, ActorCounts},
     math::Vector2,
 };
 
@@ -86,9 +87,19 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
         }
     }
 
+    /// Bookkeeping once the frame is over
+    fn end_frame(&self) {
+        let mut actor_counts = ActorCounts::new();
+        for actor in &self.actors {
+            actor_counts.add(actor.borrow().get_state());
+        }
+        crash_report::end_frame(actor_counts);
+    }
+
     fn load_data(this: Rc<RefCell<Game>>) {
         let ship = Ship::new(this.clone());
         {
@@ -147,6 +158,7 @@ impl Game {
             .expect(&format!("Failed to load texture {}", file_name));
         let result = Rc::new(texture);
         self.textures.insert(file_name.to_string(), result.clone());
+        log!("Loaded texture {}", file_name);
         result
     }
 
@@ -1,5 +1,6 @@
 mod actors;
 mod components;
+mod crash_report;
 mod game;
 mod math;
 
@@ -7,6 +8,8 @@ use crate::game::*;
 use anyhow::Result;
 
 fn main() -> Result<()> {
+    crash_report::install();
+
     let game = Game::initialize()?;
     game.borrow_mut().run_loop();
 
@@ -8,7 +8,7 @@ use crate::{
         move_component::{DefaultMoveComponent, MoveComponent},
         sprite_component::{DefaultSpriteComponent, SpriteComponent},
     },
-    math::vector2::Vector2,
+    math::{rect::Rect, vector2::Vector2},
     system::{entity_manager::EntityManager, texture_manager::TextureManager},
 };
 
@@ -26,6 +26,10 @@ pub struct Asteroid {
 }
 
 impl Asteroid {
+    const RADIUS: f32 = 40.0;
+    // Around where the ship starts, so it isn't hit right away
+    const SAFE_ZONE: Rect = Rect::new(Vector2::new(0.0, 284.0), Vector2::new(300.0, 484.0));
+
     pub fn new(
         texture_manager: Rc<RefCell<TextureManager>>,
         entity_manager: Rc<RefCell<EntityManager>>,
@@ -45,7 +49,11 @@ impl Asteroid {
         {
             let mut borrowed_entity_manager = entity_manager.borrow_mut();
             let random = borrowed_entity_manager.get_random();
-            let random_position = random.get_vector2(Vector2::ZERO, Vector2::new(1024.0, 768.0));
+            let mut random_position =
+                random.get_vector2(Vector2::ZERO, Vector2::new(1024.0, 768.0));
+            while Asteroid::SAFE_ZONE.intersects_circle(&random_position, Asteroid::RADIUS) {
+                random_position = random.get_vector2(Vector2::ZERO, Vector2::new(1024.0, 768.0));
+            }
             let random_rotation = random.get_float_range(0.0, f32::consts::TAU);
             this.set_position(random_position);
             this.set_rotation(random_rotation);
@@ -68,7 +76,7 @@ impl Asteroid {
 
         // Create a circle component (for collision)
         let circle = CircleComponent::new(result.clone());
-        circle.borrow_mut().set_radius(40.0);
+        circle.borrow_mut().set_radius(Asteroid::RADIUS);
         result.borrow_mut().circle = Some(circle);
 
         result
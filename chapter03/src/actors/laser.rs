@@ -2,7 +2,7 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     components::{
-        circle_component::CircleComponent,
+        circle_component::{CircleComponent, Colliders},
         component::{Component, State as ComponentState},
         move_component::{DefaultMoveComponent, MoveComponent},
         sprite_component::{DefaultSpriteComponent, SpriteComponent},
@@ -74,20 +74,12 @@ impl Actor for Laser {
             return;
         }
 
-        let mut is_dead = false;
         let binding = self.circle.clone().unwrap();
-        let circle = binding.borrow();
-
-        for asteroid in self.entity_manager.borrow().get_asteroids() {
-            let mut borrowed_asteroid = asteroid.borrow_mut();
-            if circle.intersect(borrowed_asteroid.get_circle()) {
-                is_dead = true;
-                borrowed_asteroid.set_state(State::Dead);
-                break;
-            }
-        }
-
-        if is_dead {
+        let entity_manager = self.entity_manager.clone();
+        if destroy_first_hit(
+            &binding.borrow(),
+            entity_manager.borrow().get_asteroid_colliders(),
+        ) {
             self.set_state(State::Dead);
         }
     }
@@ -100,3 +92,74 @@ impl Actor for Laser {
 impl Drop for Laser {
     actor::impl_drop! {}
 }
+
+/// Kill the owner of the first target the circle touches, returns whether one was hit
+pub fn destroy_first_hit<T: Actor + ?Sized>(
+    circle: &CircleComponent,
+    targets: &Colliders<T>,
+) -> bool {
+    let hits = targets.query(circle.get_center(), circle.get_radius());
+    match hits.first() {
+        Some(target) => {
+            target.borrow_mut().set_state(State::Dead);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor, State},
+        components::{
+            circle_component::{CircleComponent, Colliders},
+            move_component::{DefaultMoveComponent, MoveComponent},
+        },
+        math::vector2::Vector2,
+    };
+
+    use super::destroy_first_hit;
+
+    fn create_actor(
+        position: Vector2,
+        radius: f32,
+    ) -> (Rc<RefCell<dyn Actor>>, Rc<RefCell<CircleComponent>>) {
+        let mut test_actor = TestActor::new();
+        test_actor.set_position(position);
+        let actor: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(test_actor));
+        let circle = CircleComponent::new(actor.clone());
+        circle.borrow_mut().set_radius(radius);
+        (actor, circle)
+    }
+
+    #[test]
+    fn test_laser_destroys_asteroid() {
+        // Same sizes and speed as Laser and Asteroid, flying to the right
+        let (laser, laser_circle) = create_actor(Vector2::new(100.0, 300.0), 11.0);
+        DefaultMoveComponent::new(laser.clone())
+            .borrow_mut()
+            .set_forward_speed(800.0);
+        let (asteroid, asteroid_circle) = create_actor(Vector2::new(400.0, 300.0), 40.0);
+        let (missed, missed_circle) = create_actor(Vector2::new(400.0, 500.0), 40.0);
+        let mut targets = Colliders::new();
+        targets.add(missed_circle, missed.clone());
+        targets.add(asteroid_circle, asteroid.clone());
+
+        let mut frames = 0;
+        loop {
+            laser.borrow_mut().update(1.0 / 60.0);
+            frames += 1;
+            if destroy_first_hit(&laser_circle.borrow(), &targets) {
+                break;
+            }
+            assert_eq!(State::Active, *asteroid.borrow().get_state());
+            assert!(frames < 60, "The laser never hit the asteroid");
+        }
+
+        assert_eq!(State::Dead, *asteroid.borrow().get_state());
+        assert_eq!(State::Active, *missed.borrow().get_state());
+    }
+}
@@ -1,7 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
 use sdl2::{
-    rect::Rect,
     render::{Canvas, Texture},
     video::Window,
 };
@@ -9,7 +8,7 @@ use sdl2::{
 use crate::{
     actors::actor::Actor,
     components::component::Component,
-    math::{self, vector2::Vector2},
+    math::{self, rect::Rect, vector2::Vector2},
 };
 
 pub trait SpriteComponent: Component {
@@ -18,18 +17,20 @@ pub trait SpriteComponent: Component {
             let owner = self.get_owner().borrow();
             let width = self.get_texture_width() as f32 * owner.get_scale();
             let height = self.get_texture_height() as f32 * owner.get_scale();
-            let rect = Rect::new(
-                (owner.get_position().x - width / 2.0) as i32,
-                (owner.get_position().y - height / 2.0) as i32,
-                width as u32,
-                height as u32,
-            );
+            let rect = Rect::from_center(owner.get_position(), &Vector2::new(width, height));
+
+            // Skip sprites off the screen, whichever way they are rotated
+            let diagonal = width.hypot(height);
+            let bounds = Rect::from_center(owner.get_position(), &Vector2::new(diagonal, diagonal));
+            if !Rect::from(canvas.viewport()).intersects(&bounds) {
+                return;
+            }
 
             canvas
                 .copy_ex(
                     &texture,
                     None,
-                    Some(rect),
+                    Some(rect.to_sdl_rect()),
                     -math::basic::to_degrees(owner.get_rotation()) as f64,
                     None,
                     false,
@@ -11,7 +11,11 @@ use sdl2::{
     EventPump, TimerSubsystem,
 };
 
-use crate::system::{entity_manager::EntityManager, texture_manager::TextureManager};
+use crate::system::{
+    crash_report::{self, ActorCounts},
+    entity_manager::EntityManager,
+    texture_manager::TextureManager,
+};
 
 pub struct Game {
     canvas: Canvas<Window>,
@@ -67,7 +71,17 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
+        }
+    }
+
+    /// Bookkeeping once the frame is over
+    fn end_frame(&self) {
+        let mut actor_counts = ActorCounts::new();
+        for actor in self.entity_manager.borrow().get_actors() {
+            actor_counts.add(actor.borrow().get_state());
         }
+        crash_report::end_frame(actor_counts);
     }
 
     /// Herlper functions for the game loop
@@ -4,10 +4,12 @@ mod game;
 mod math;
 mod system;
 
-use crate::game::*;
+use crate::{game::*, system::crash_report};
 use anyhow::Result;
 
 fn main() -> Result<()> {
+    crash_report::install();
+
     let mut game = Game::initialize()?;
     game.run_loop();
 
@@ -1,4 +1,5 @@
 pub mod basic;
 pub mod random;
+pub mod rect;
 pub mod vector2;
 pub mod vector3;
@@ -0,0 +1,93 @@
+use super::vector2::Vector2;
+
+/// Axis aligned rectangle in window coordinates, y down
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rect {
+    pub min: Vector2,
+    pub max: Vector2,
+}
+
+impl Rect {
+    pub const fn new(min: Vector2, max: Vector2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: &Vector2, size: &Vector2) -> Self {
+        let half = size.clone() / 2.0;
+        Self::new(center.clone() - half.clone(), center.clone() + half)
+    }
+
+    pub fn get_size(&self) -> Vector2 {
+        self.max.clone() - self.min.clone()
+    }
+
+    /// Only touching edges don't count
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min.x < other.max.x
+            && other.min.x < self.max.x
+            && self.min.y < other.max.y
+            && other.min.y < self.max.y
+    }
+
+    pub fn intersects_circle(&self, center: &Vector2, radius: f32) -> bool {
+        let closest = center.clamp(&self.min, &self.max);
+        closest.distance_sq(center) <= radius * radius
+    }
+
+    /// In whole pixels, for drawing
+    pub fn to_sdl_rect(&self) -> sdl2::rect::Rect {
+        let size = self.get_size();
+        sdl2::rect::Rect::new(
+            self.min.x as i32,
+            self.min.y as i32,
+            size.x.max(0.0) as u32,
+            size.y.max(0.0) as u32,
+        )
+    }
+}
+
+impl From<sdl2::rect::Rect> for Rect {
+    fn from(rect: sdl2::rect::Rect) -> Self {
+        Rect::new(
+            Vector2::new(rect.left() as f32, rect.top() as f32),
+            Vector2::new(rect.right() as f32, rect.bottom() as f32),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::vector2::Vector2;
+
+    use super::Rect;
+
+    #[test]
+    fn test_from_center() {
+        let rect = Rect::from_center(&Vector2::new(10.0, 20.0), &Vector2::new(4.0, 6.0));
+        assert_eq!(Vector2::new(8.0, 17.0), rect.min);
+        assert_eq!(Vector2::new(12.0, 23.0), rect.max);
+        assert_eq!(Vector2::new(4.0, 6.0), rect.get_size());
+        assert_eq!(sdl2::rect::Rect::new(8, 17, 4, 6), rect.to_sdl_rect());
+        assert_eq!(rect, Rect::from(rect.to_sdl_rect()));
+    }
+
+    #[test]
+    fn test_intersects() {
+        let rect = Rect::new(Vector2::ZERO, Vector2::new(10.0, 10.0));
+        let overlapping = Rect::new(Vector2::new(5.0, 5.0), Vector2::new(15.0, 15.0));
+        let touching = Rect::new(Vector2::new(10.0, 0.0), Vector2::new(20.0, 10.0));
+        assert!(rect.intersects(&overlapping));
+        assert!(overlapping.intersects(&rect));
+        assert!(!rect.intersects(&touching));
+    }
+
+    #[test]
+    fn test_intersects_circle() {
+        let rect = Rect::new(Vector2::ZERO, Vector2::new(10.0, 10.0));
+        assert!(rect.intersects_circle(&Vector2::new(5.0, 5.0), 1.0));
+        assert!(rect.intersects_circle(&Vector2::new(13.0, 5.0), 3.0));
+        // Near the corner, but not close enough
+        assert!(!rect.intersects_circle(&Vector2::new(13.0, 13.0), 4.0));
+        assert!(rect.intersects_circle(&Vector2::new(13.0, 13.0), 5.0));
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
         asteroid::Asteroid,
         ship::Ship,
     },
+    components::circle_component::Colliders,
     math::{random::Random, vector2::Vector2},
     system::texture_manager::TextureManager,
 };
@@ -16,6 +17,7 @@ pub struct EntityManager {
     updating_actors: bool,
     ship: Option<Rc<RefCell<Ship>>>,
     asteroids: Vec<Rc<RefCell<Asteroid>>>,
+    asteroid_colliders: Colliders<Asteroid>,
     random: Random,
 }
 
@@ -27,6 +29,7 @@ impl EntityManager {
             updating_actors: false,
             ship: None,
             asteroids: vec![],
+            asteroid_colliders: Colliders::new(),
             random: Random::new(),
         };
 
@@ -64,6 +67,16 @@ impl EntityManager {
                 false
             }
         });
+        self.update_colliders();
+    }
+
+    /// Collide as the asteroids left at the end of the frame
+    fn update_colliders(&mut self) {
+        self.asteroid_colliders.clear();
+        for asteroid in &self.asteroids {
+            let circle = asteroid.borrow().get_circle();
+            self.asteroid_colliders.add(circle, asteroid.clone());
+        }
     }
 
     pub fn load_data(
@@ -91,12 +104,13 @@ impl EntityManager {
         &self.actors
     }
 
-    pub fn get_asteroids(&self) -> &Vec<Rc<RefCell<Asteroid>>> {
-        &self.asteroids
-    }
-
     pub fn set_asteroids(&mut self, asteroids: Vec<Rc<RefCell<Asteroid>>>) {
         self.asteroids = asteroids;
+        self.update_colliders();
+    }
+
+    pub fn get_asteroid_colliders(&self) -> &Colliders<Asteroid> {
+        &self.asteroid_colliders
     }
 
     pub fn get_random(&mut self) -> &mut Random {
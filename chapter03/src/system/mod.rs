@@ -1,2 +1,3 @@
+pub mod crash_report;
 pub mod entity_manager;
 pub mod texture_manager;
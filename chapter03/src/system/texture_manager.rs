@@ -6,7 +6,10 @@ use sdl2::{
     video::WindowContext,
 };
 
-use crate::components::{component::State, sprite_component::SpriteComponent};
+use crate::{
+    components::{component::State, sprite_component::SpriteComponent},
+    system::crash_report::log,
+};
 
 pub struct TextureManager {
     texture_creator: TextureCreator<WindowContext>,
@@ -36,6 +39,7 @@ impl TextureManager {
             .expect(&format!("Failed to load texture {}", file_name));
         let result = Rc::new(texture);
         self.textures.insert(file_name.to_string(), result.clone());
+        log!("Loaded texture {}", file_name);
         result
     }
 
@@ -0,0 +1,33 @@
+// The game is a binary crate, so the modules under test are compiled in directly
+#![allow(dead_code, unused_imports)]
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+#[path = "../src/math"]
+mod math {
+    pub mod search;
+}
+
+use math::search::{a_ster, AStarMap, WeightedGraph};
+
+fn a_star(c: &mut Criterion) {
+    let mut group = c.benchmark_group("A*");
+    for size in [16, 32, 64] {
+        let graph = WeightedGraph::grid(size, size);
+        // Corner to corner
+        let start = graph.get_node(0);
+        let goal = graph.get_node(size * size - 1);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |bencher, _| {
+            bencher.iter(|| {
+                let mut map = AStarMap::new();
+                black_box(a_ster(start.clone(), goal.clone(), &mut map))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, a_star);
+criterion_main!(benches);
@@ -1,4 +1,7 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
 
 use crate::{
     components::{
@@ -14,8 +17,21 @@ use crate::{
 use super::{
     actor::{self, generate_id, Actor, State},
     enemy::Enemy,
+    status_effect::StatusEffect,
 };
 
+/// How a bullet flies and what it does on hit
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulletConfig {
+    pub speed: f32,
+    pub damage: f32,
+    // Damages every enemy this close to the hit, 0 for the hit enemy only
+    pub splash_radius: f32,
+    pub scale: f32,
+    // Put on every enemy damaged
+    pub effect: Option<StatusEffect>,
+}
+
 pub struct Bullet {
     id: u32,
     state: State,
@@ -26,6 +42,9 @@ pub struct Bullet {
     texture_manager: Rc<RefCell<TextureManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
     circle: Option<Rc<RefCell<CircleComponent>>>,
+    config: BulletConfig,
+    // Followed while it's alive
+    target: Option<Weak<RefCell<Enemy>>>,
     live_time: f32,
 }
 
@@ -33,17 +52,20 @@ impl Bullet {
     pub fn new(
         texture_manager: Rc<RefCell<TextureManager>>,
         entity_manager: Rc<RefCell<EntityManager>>,
+        config: BulletConfig,
     ) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
             position: Vector2::ZERO,
-            scale: 1.0,
+            scale: config.scale,
             rotation: 0.0,
             components: vec![],
             texture_manager: texture_manager.clone(),
             entity_manager: entity_manager.clone(),
             circle: None,
+            config: config.clone(),
+            target: None,
             live_time: 1.0,
         };
 
@@ -56,31 +78,78 @@ impl Bullet {
         sprite_component.borrow_mut().set_texture(texture);
 
         let move_component = DefaultMoveComponent::new(result.clone());
-        move_component.borrow_mut().set_forward_speed(400.0);
+        move_component.borrow_mut().set_forward_speed(config.speed);
 
         let circle = CircleComponent::new(result.clone());
-        circle.borrow_mut().set_radius(5.0);
+        circle.borrow_mut().set_radius(5.0 * config.scale);
         result.borrow_mut().circle = Some(circle);
 
         entity_manager.borrow_mut().add_actor(result.clone());
 
         result
     }
+
+    pub fn set_target(&mut self, target: &Rc<RefCell<Enemy>>) {
+        self.target = Some(Rc::downgrade(target));
+    }
+
+    fn explode(&self, hit_enemy: &Rc<RefCell<Enemy>>) {
+        if self.config.splash_radius <= 0.0 {
+            self.hit(&mut hit_enemy.borrow_mut(), self.config.damage);
+            return;
+        }
+
+        let splashed = self
+            .entity_manager
+            .borrow()
+            .get_enemy_colliders()
+            .query(&self.position, self.config.splash_radius);
+        for enemy in splashed {
+            let mut enemy = enemy.borrow_mut();
+            let distance = (enemy.get_position().clone() - self.position.clone()).length();
+            let damage = splash_damage(self.config.damage, distance, self.config.splash_radius);
+            if damage > 0.0 {
+                self.hit(&mut enemy, damage);
+            }
+        }
+    }
+
+    fn hit(&self, enemy: &mut Enemy, damage: f32) {
+        enemy.take_damage(damage);
+        if let Some(effect) = &self.config.effect {
+            enemy.apply_effect(effect.clone());
+        }
+    }
+}
+
+/// Full damage at the center of the splash, down to half at its edge
+fn splash_damage(damage: f32, distance: f32, radius: f32) -> f32 {
+    if distance > radius {
+        return 0.0;
+    }
+    damage * (1.0 - 0.5 * distance / radius)
 }
 
 impl Actor for Bullet {
     fn update_actor(&mut self, delta_time: f32) {
-        let mut result = None;
-        let circle = self.circle.clone().unwrap();
-        for enemy in self.entity_manager.borrow().get_enemies() {
-            if circle.borrow().intersect(enemy.borrow().get_circle()) {
-                result = Some(enemy.clone());
-                break;
+        // Home in on the target, flying straight on once it's gone
+        let target = self.target.as_ref().and_then(Weak::upgrade);
+        if let Some(target) = target {
+            if *target.borrow().get_state() == State::Active {
+                let direction = target.borrow().get_position().clone() - self.position.clone();
+                self.set_rotation((-direction.y).atan2(direction.x));
             }
         }
 
+        let circle = self.circle.clone().unwrap();
+        let result = self
+            .entity_manager
+            .borrow()
+            .get_enemy_colliders()
+            .first_hit(&circle.borrow());
+
         if let Some(enemy) = result {
-            enemy.borrow_mut().set_state(State::Dead);
+            self.explode(&enemy);
             self.set_state(State::Dead);
         }
 
@@ -98,3 +167,18 @@ impl Actor for Bullet {
 impl Drop for Bullet {
     actor::impl_drop! {}
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_near_eq;
+
+    use super::splash_damage;
+
+    #[test]
+    fn test_splash_damage() {
+        assert_near_eq!(80.0, splash_damage(80.0, 0.0, 50.0), 0.001);
+        assert_near_eq!(60.0, splash_damage(80.0, 25.0, 50.0), 0.001);
+        assert_near_eq!(40.0, splash_damage(80.0, 50.0, 50.0), 0.001);
+        assert_near_eq!(0.0, splash_damage(80.0, 51.0, 50.0), 0.001);
+    }
+}
@@ -1,5 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
+use sdl2::pixels::Color;
+
 use crate::{
     components::{
         circle_component::CircleComponent,
@@ -8,15 +10,76 @@ use crate::{
         nav_component::NavComponent,
         sprite_component::{DefaultSpriteComponent, SpriteComponent},
     },
-    math::{self, vector2::Vector2},
+    math::{self, path_grid::PathNode, vector2::Vector2},
     system::{entity_manager::EntityManager, texture_manager::TextureManager},
 };
 
 use super::{
     actor::{self, generate_id, Actor, State},
-    tile::Tile,
+    status_effect::{StatusEffect, StatusEffects},
 };
 
+/// Enemies the grid sends, see get_config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyKind {
+    Normal,
+    Fast,
+    Armored,
+    // Flies straight to the base over the towers
+    Flying,
+}
+
+impl EnemyKind {
+    pub fn get_config(&self) -> EnemyConfig {
+        match self {
+            EnemyKind::Normal => EnemyConfig {
+                speed: 150.0,
+                health: 100.0,
+                armor: 0.0,
+                scale: 1.0,
+                color: Color::WHITE,
+                is_flying: false,
+            },
+            EnemyKind::Fast => EnemyConfig {
+                speed: 250.0,
+                health: 60.0,
+                armor: 0.0,
+                scale: 0.75,
+                color: Color::RGB(255, 255, 120),
+                is_flying: false,
+            },
+            EnemyKind::Armored => EnemyConfig {
+                speed: 100.0,
+                health: 200.0,
+                armor: 30.0,
+                scale: 1.25,
+                color: Color::RGB(150, 150, 150),
+                is_flying: false,
+            },
+            EnemyKind::Flying => EnemyConfig {
+                speed: 120.0,
+                health: 80.0,
+                armor: 0.0,
+                scale: 1.0,
+                color: Color::RGB(170, 230, 255),
+                is_flying: true,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnemyConfig {
+    pub speed: f32,
+    pub health: f32,
+    // Taken off the damage of each hit, burns go through
+    pub armor: f32,
+    pub scale: f32,
+    // Tint without status effects
+    pub color: Color,
+    pub is_flying: bool,
+}
+
 pub struct Enemy {
     id: u32,
     state: State,
@@ -27,24 +90,42 @@ pub struct Enemy {
     texture_manager: Rc<RefCell<TextureManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
     circle: Option<Rc<RefCell<CircleComponent>>>,
+    nav_component: Option<Rc<RefCell<NavComponent>>>,
+    sprite_component: Option<Rc<RefCell<DefaultSpriteComponent>>>,
+    config: EnemyConfig,
+    health: f32,
+    status_effects: StatusEffects,
 }
 
 impl Enemy {
+    // Least damage a hit does through the armor
+    const MIN_DAMAGE_RATIO: f32 = 0.1;
+    const SLOWED_COLOR: Color = Color::RGB(110, 150, 255);
+    const BURNING_COLOR: Color = Color::RGB(255, 110, 60);
+
     pub fn new(
         texture_manager: Rc<RefCell<TextureManager>>,
         entity_manager: Rc<RefCell<EntityManager>>,
-        start_tile: Rc<RefCell<Tile>>,
+        start_node: Rc<RefCell<PathNode>>,
+        end_node: Rc<RefCell<PathNode>>,
+        kind: EnemyKind,
     ) -> Rc<RefCell<Self>> {
+        let config = kind.get_config();
         let this = Self {
             id: generate_id(),
             state: State::Active,
             position: Vector2::ZERO,
-            scale: 1.0,
+            scale: config.scale,
             rotation: 0.0,
             components: vec![],
             texture_manager: texture_manager.clone(),
             entity_manager: entity_manager.clone(),
             circle: None,
+            nav_component: None,
+            sprite_component: None,
+            health: config.health,
+            config: config.clone(),
+            status_effects: StatusEffects::new(),
         };
 
         let result = Rc::new(RefCell::new(this));
@@ -54,16 +135,29 @@ impl Enemy {
             .borrow_mut()
             .get_texture("Assets/Airplane.png");
         sprite_component.borrow_mut().set_texture(texture);
+        sprite_component.borrow_mut().set_tint(config.color);
+        result.borrow_mut().sprite_component = Some(sprite_component);
 
-        let position = start_tile.borrow().get_position().clone();
-        result.borrow_mut().set_position(position);
+        let position = start_node.borrow().get_position().clone();
+        result.borrow_mut().set_position(position.clone());
 
         let nav_component = NavComponent::new(result.clone(), 10);
-        nav_component.borrow_mut().set_forward_speed(150.0);
-        nav_component.borrow_mut().start_path(start_tile.clone());
+        nav_component.borrow_mut().set_forward_speed(config.speed);
+        if config.is_flying {
+            // No path to follow, keeps heading to the base
+            let to_base = end_node.borrow().get_position().clone() - position;
+            result
+                .borrow_mut()
+                .set_rotation((-to_base.y).atan2(to_base.x));
+        } else {
+            nav_component.borrow_mut().start_path(start_node);
+        }
+        result.borrow_mut().nav_component = Some(nav_component);
 
         let circle_component = CircleComponent::new(result.clone());
-        circle_component.borrow_mut().set_radius(25.0);
+        circle_component
+            .borrow_mut()
+            .set_radius(25.0 * config.scale);
         result.borrow_mut().circle = Some(circle_component);
 
         entity_manager.borrow_mut().add_actor(result.clone());
@@ -75,10 +169,57 @@ impl Enemy {
     pub fn get_circle(&self) -> Rc<RefCell<CircleComponent>> {
         self.circle.clone().unwrap()
     }
+
+    pub fn is_flying(&self) -> bool {
+        self.config.is_flying
+    }
+
+    /// Damage of a hit, lowered by the armor
+    pub fn take_damage(&mut self, amount: f32) {
+        let damage = (amount - self.config.armor).max(amount * Enemy::MIN_DAMAGE_RATIO);
+        self.lose_health(damage);
+    }
+
+    pub fn apply_effect(&mut self, effect: StatusEffect) {
+        self.status_effects.apply(effect);
+    }
+
+    fn lose_health(&mut self, amount: f32) {
+        self.health -= amount;
+        if self.health <= 0.0 {
+            self.set_state(State::Dead);
+        }
+    }
+
+    fn get_tint(&self) -> Color {
+        if self.status_effects.get_burn_stacks() > 0 {
+            Enemy::BURNING_COLOR
+        } else if self.status_effects.is_slowed() {
+            Enemy::SLOWED_COLOR
+        } else {
+            self.config.color
+        }
+    }
 }
 
 impl Actor for Enemy {
-    fn update_actor(&mut self, _delta_time: f32) {
+    fn update_actor(&mut self, delta_time: f32) {
+        let burn_damage = self.status_effects.update(delta_time);
+        if burn_damage > 0.0 {
+            self.lose_health(burn_damage);
+        }
+        self.nav_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_forward_speed(self.config.speed * self.status_effects.get_speed_factor());
+        let tint = self.get_tint();
+        self.sprite_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_tint(tint);
+
         let grid = self.entity_manager.borrow().get_grid();
         let binding = grid.borrow();
         let end_tile = binding.get_end_tile();
@@ -86,6 +227,32 @@ impl Actor for Enemy {
         if math::basic::near_zero(diff.length(), 10.0) {
             self.set_state(State::Dead);
         }
+        drop(binding);
+
+        if self.config.is_flying {
+            return;
+        }
+
+        // Fails for this enemy, which is being updated
+        let neighbors = self
+            .entity_manager
+            .borrow()
+            .get_enemies()
+            .iter()
+            .filter_map(|enemy| {
+                let enemy = enemy.try_borrow().ok()?;
+                // Only the ones on the path are in the way
+                if enemy.is_flying() {
+                    return None;
+                }
+                Some(enemy.get_position().clone())
+            })
+            .collect();
+        self.nav_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_neighbors(neighbors);
     }
 
     actor::impl_getters_setters! {}
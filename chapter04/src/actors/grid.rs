@@ -2,15 +2,18 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     components::component::{Component, State as ComponentState},
-    math::vector2::Vector2,
-    system::{entity_manager::EntityManager, texture_manager::TextureManager},
+    math::{path_grid::PathGrid, vector2::Vector2},
+    system::{
+        crash_report::log, entity_manager::EntityManager, texture_manager::TextureManager,
+        timer_manager::TimerManager,
+    },
 };
 
 use super::{
     actor::{self, generate_id, Actor, State},
-    enemy::Enemy,
-    tile::{Tile, TileState},
-    tower::Tower,
+    enemy::{Enemy, EnemyKind},
+    tile::{Highlight, Tile, TileState},
+    tower::{Tower, TowerKind},
 };
 
 pub struct Grid {
@@ -23,8 +26,11 @@ pub struct Grid {
     texture_manager: Rc<RefCell<TextureManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
     selected_tile: Option<Rc<RefCell<Tile>>>,
+    hovered_tile: Option<Rc<RefCell<Tile>>>,
+    // Built by build_tower
+    tower_kind: TowerKind,
     tiles: Vec<Vec<Rc<RefCell<Tile>>>>,
-    next_enemy: f32,
+    path_grid: PathGrid,
 }
 
 impl Grid {
@@ -33,10 +39,21 @@ impl Grid {
     const START_Y: f32 = 192.0;
     const TILE_SIZE: f32 = 64.0;
     const ENEMY_TIME: f32 = 1.5;
+    const ENEMY_PATTERN: [EnemyKind; 8] = [
+        EnemyKind::Normal,
+        EnemyKind::Normal,
+        EnemyKind::Fast,
+        EnemyKind::Normal,
+        EnemyKind::Armored,
+        EnemyKind::Fast,
+        EnemyKind::Normal,
+        EnemyKind::Flying,
+    ];
 
     pub fn new(
         texture_manager: Rc<RefCell<TextureManager>>,
         entity_manager: Rc<RefCell<EntityManager>>,
+        timer_manager: Rc<RefCell<TimerManager>>,
     ) -> Rc<RefCell<Self>> {
         let mut this = Self {
             id: generate_id(),
@@ -48,18 +65,26 @@ impl Grid {
             texture_manager: texture_manager.clone(),
             entity_manager: entity_manager.clone(),
             selected_tile: None,
+            hovered_tile: None,
+            tower_kind: TowerKind::Basic,
             tiles: vec![],
-            next_enemy: 0.0,
+            path_grid: PathGrid::new(
+                Grid::NUM_ROW,
+                Grid::NUM_COLUMN,
+                Vector2::new(Grid::TILE_SIZE / 2.0, Grid::START_Y),
+                Grid::TILE_SIZE,
+            ),
         };
 
         // Create tiles
         for i in 0..Grid::NUM_ROW {
             let mut temps = vec![];
             for j in 0..Grid::NUM_COLUMN {
-                let tile = Tile::new(texture_manager.clone(), entity_manager.clone());
-                let x = Grid::TILE_SIZE / 2.0 + j as f32 * Grid::TILE_SIZE;
-                let y = Grid::START_Y + i as f32 * Grid::TILE_SIZE;
-                tile.borrow_mut().set_position(Vector2::new(x, y));
+                let tile = Tile::new(
+                    texture_manager.clone(),
+                    entity_manager.clone(),
+                    this.path_grid.get_node(i, j).clone(),
+                );
                 temps.push(tile);
             }
             this.tiles.push(temps);
@@ -73,37 +98,11 @@ impl Grid {
             .borrow_mut()
             .set_tile_state(TileState::Base);
 
-        // Set up adjacency lists
-        for i in 0..Grid::NUM_ROW {
-            for j in 0..Grid::NUM_COLUMN {
-                if i > 0 {
-                    this.tiles[i][j]
-                        .borrow_mut()
-                        .add_adjacent(this.tiles[i - 1][j].clone());
-                }
-                if i < Grid::NUM_ROW - 1 {
-                    this.tiles[i][j]
-                        .borrow_mut()
-                        .add_adjacent(this.tiles[i + 1][j].clone());
-                }
-                if j > 0 {
-                    this.tiles[i][j]
-                        .borrow_mut()
-                        .add_adjacent(this.tiles[i][j - 1].clone());
-                }
-                if j < Grid::NUM_COLUMN - 1 {
-                    this.tiles[i][j]
-                        .borrow_mut()
-                        .add_adjacent(this.tiles[i][j + 1].clone());
-                }
-            }
-        }
-
         // Find path (in reverse)
-        this.find_path(this.get_end_tile().clone(), this.get_start_tile().clone());
+        this.find_path();
         this.update_path_tiles(this.get_start_tile().clone());
 
-        this.next_enemy = Grid::ENEMY_TIME;
+        this.schedule_enemies(&timer_manager);
 
         let result = Rc::new(RefCell::new(this));
 
@@ -112,104 +111,101 @@ impl Grid {
         result
     }
 
-    pub fn process_click(&mut self, mut x: i32, mut y: i32) {
-        y -= (Grid::START_Y - Grid::TILE_SIZE / 2.0) as i32;
-
-        if x < 0 || y < 0 {
-            return;
-        }
-
-        x /= Grid::TILE_SIZE as i32;
-        y /= Grid::TILE_SIZE as i32;
+    /// Send an enemy down the path every ENEMY_TIME, picking its kind in ENEMY_PATTERN
+    fn schedule_enemies(&self, timer_manager: &Rc<RefCell<TimerManager>>) {
+        let texture_manager = self.texture_manager.clone();
+        let entity_manager = self.entity_manager.clone();
+        let start_node = self.get_start_tile().borrow().get_node().clone();
+        let end_node = self.get_end_tile().borrow().get_node().clone();
+        // Enemies sent so far
+        let mut enemy_count = 0;
+        timer_manager.borrow_mut().schedule(
+            Grid::ENEMY_TIME,
+            Some(Grid::ENEMY_TIME),
+            Box::new(move || {
+                let kind = Grid::ENEMY_PATTERN[enemy_count % Grid::ENEMY_PATTERN.len()];
+                let _ = Enemy::new(
+                    texture_manager.clone(),
+                    entity_manager.clone(),
+                    start_node.clone(),
+                    end_node.clone(),
+                    kind,
+                );
+                enemy_count += 1;
+            }),
+        );
+    }
 
-        if x < Grid::NUM_COLUMN as i32 && y < Grid::NUM_ROW as i32 {
-            self.select_tile(y, x);
+    pub fn process_click(&mut self, x: i32, y: i32) {
+        if let Some((row, column)) = self.get_tile_index(x, y) {
+            self.select_tile(row as i32, column as i32);
         }
     }
 
-    pub fn find_path(&self, start: Rc<RefCell<Tile>>, goal: Rc<RefCell<Tile>>) -> bool {
-        for i in 0..Grid::NUM_ROW {
-            for j in 0..Grid::NUM_COLUMN {
-                self.tiles[i][j].borrow_mut().g = 0.0;
-                self.tiles[i][j].borrow_mut().in_open_set = false;
-                self.tiles[i][j].borrow_mut().in_closed_set = false;
-            }
+    /// Highlight the tile under the mouse and preview the path with a tower on it
+    pub fn process_mouse_move(&mut self, x: i32, y: i32) {
+        let hovered_tile = self
+            .get_tile_index(x, y)
+            .map(|(row, column)| self.tiles[row][column].clone());
+        let is_same = match (&self.hovered_tile, &hovered_tile) {
+            (Some(current), Some(hovered)) => Rc::ptr_eq(current, hovered),
+            (None, None) => true,
+            _ => false,
+        };
+        if is_same {
+            return;
         }
 
-        let mut open_set = vec![];
-
-        let mut current = start;
-        current.borrow_mut().in_closed_set = true;
-
-        let goal_pos = goal.borrow().get_position().clone();
-
-        let mut is_first = true;
-        while is_first || current.borrow().get_id() != goal.borrow().get_id() {
-            for neighbor in current.borrow().adjacent.clone() {
-                let mut borrowed_neighbor = neighbor.borrow_mut();
-                if borrowed_neighbor.blocked {
-                    continue;
-                }
-
-                if !borrowed_neighbor.in_closed_set {
-                    if !borrowed_neighbor.in_open_set {
-                        borrowed_neighbor.parent = Some(current.clone());
-                        borrowed_neighbor.h =
-                            (borrowed_neighbor.get_position().clone() - goal_pos.clone()).length();
-                        borrowed_neighbor.g = current.borrow().g + Grid::TILE_SIZE;
-                        borrowed_neighbor.f = borrowed_neighbor.g + borrowed_neighbor.h;
-                        borrowed_neighbor.in_open_set = true;
-                        open_set.push(neighbor.clone());
-                    } else {
-                        let new_g = current.borrow().g + Grid::TILE_SIZE;
-                        if new_g < borrowed_neighbor.g {
-                            borrowed_neighbor.parent = Some(current.clone());
-                            borrowed_neighbor.g = new_g;
-                            borrowed_neighbor.f = borrowed_neighbor.g + borrowed_neighbor.h;
-                        }
-                    }
-                }
-            }
-
-            if open_set.is_empty() {
-                break;
-            }
+        self.hovered_tile = hovered_tile;
+        self.update_preview();
+    }
 
-            let min = open_set
-                .clone()
-                .into_iter()
-                .min_by(|a, b| a.borrow().f.partial_cmp(&b.borrow().f).unwrap())
-                .unwrap();
-
-            current = min.clone();
-            open_set.retain(|tile| tile.borrow().get_id() != min.borrow().get_id());
-            current.borrow_mut().in_open_set = false;
-            current.borrow_mut().in_closed_set = true;
-            is_first = false;
-        }
+    /// Row and column of the tile at the window position
+    fn get_tile_index(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        self.path_grid
+            .get_node_at(&Vector2::new(x as f32, y as f32))
+            .map(|node| (node.borrow().row, node.borrow().column))
+    }
 
-        let found = current.borrow().get_id() == goal.borrow().get_id();
+    /// Find the path from the start tile to the base, in reverse so that
+    /// the parents lead enemies anywhere on it to the base
+    fn find_path(&self) -> bool {
+        self.path_grid.find_path(
+            self.get_end_tile().borrow().get_node().clone(),
+            self.get_start_tile().borrow().get_node().clone(),
+        )
+    }
 
-        found
+    pub fn set_tower_kind(&mut self, tower_kind: TowerKind) {
+        self.tower_kind = tower_kind;
     }
 
     pub fn build_tower(&mut self) {
-        if self.selected_tile.is_none() || self.selected_tile.clone().unwrap().borrow().blocked {
+        let selected_node = match &self.selected_tile {
+            Some(selected_tile) => selected_tile.borrow().get_node().clone(),
+            None => return,
+        };
+        if selected_node.borrow().blocked {
             return;
         }
 
-        let selected_tile = self.selected_tile.clone().unwrap();
-        selected_tile.borrow_mut().blocked = true;
-        if self.find_path(self.get_end_tile().clone(), self.get_start_tile().clone()) {
-            let tower = Tower::new(self.texture_manager.clone(), self.entity_manager.clone());
+        selected_node.borrow_mut().blocked = true;
+        if self.find_path() {
+            let tower = Tower::new(
+                self.texture_manager.clone(),
+                self.entity_manager.clone(),
+                self.tower_kind,
+            );
             let position = self.get_selected_tile().borrow().get_position().clone();
             tower.borrow_mut().set_position(position);
         } else {
             // This tower would block the path, so don't allow build
-            selected_tile.borrow_mut().blocked = false;
-            self.find_path(self.get_end_tile().clone(), self.get_start_tile().clone());
+            log!("A tower there would block the path");
+            selected_node.borrow_mut().blocked = false;
+            self.find_path();
         }
         self.update_path_tiles(self.get_start_tile().clone());
+        self.update_preview();
     }
 
     pub fn get_start_tile(&self) -> &Rc<RefCell<Tile>> {
@@ -239,28 +235,78 @@ impl Grid {
             }
         }
 
-        let mut tile = start.borrow().parent.clone().unwrap();
-
-        while tile.borrow().get_id() != self.get_end_tile().borrow().get_id() {
+        for tile in self.collect_path(start) {
             tile.borrow_mut().set_tile_state(TileState::Path);
-            let parent = tile.borrow().parent.clone().unwrap();
-            tile = parent;
         }
     }
-}
 
-impl Actor for Grid {
-    fn update_actor(&mut self, delta_time: f32) {
-        self.next_enemy -= delta_time;
-        if self.next_enemy <= 0.0 {
-            let _ = Enemy::new(
-                self.texture_manager.clone(),
-                self.entity_manager.clone(),
-                self.get_start_tile().clone(),
-            );
-            self.next_enemy += Grid::ENEMY_TIME;
+    /// Tiles between start and the end tile, following the parents set by find_path
+    fn collect_path(&self, start: Rc<RefCell<Tile>>) -> Vec<Rc<RefCell<Tile>>> {
+        self.path_grid
+            .collect_path(
+                start.borrow().get_node().clone(),
+                self.get_end_tile().borrow().get_node(),
+            )
+            .into_iter()
+            .map(|node| {
+                let node = node.borrow();
+                self.tiles[node.row][node.column].clone()
+            })
+            .collect()
+    }
+
+    /// Recompute the highlights, nothing stays changed until a tower is built
+    fn update_preview(&mut self) {
+        for row in self.tiles.iter() {
+            for tile in row {
+                if *tile.borrow().get_highlight() != Highlight::None {
+                    tile.borrow_mut().set_highlight(Highlight::None);
+                }
+            }
+        }
+
+        let hovered_tile = match self.hovered_tile.clone() {
+            Some(hovered_tile) => hovered_tile,
+            None => return,
+        };
+        let can_build = {
+            let borrowed_tile = hovered_tile.borrow();
+            !borrowed_tile.get_node().borrow().blocked
+                && *borrowed_tile.get_tile_state() != TileState::Start
+                && *borrowed_tile.get_tile_state() != TileState::Base
+        };
+        if !can_build {
+            hovered_tile.borrow_mut().set_highlight(Highlight::Hovered);
+            return;
+        }
+
+        // Try the path as if the tower was there
+        let hovered_node = hovered_tile.borrow().get_node().clone();
+        hovered_node.borrow_mut().blocked = true;
+        let found = self.find_path();
+        let preview_tiles = if found {
+            self.collect_path(self.get_start_tile().clone())
+        } else {
+            vec![]
+        };
+
+        // Put the parents back for the enemies on the current path
+        hovered_node.borrow_mut().blocked = false;
+        self.find_path();
+
+        for tile in preview_tiles {
+            tile.borrow_mut().set_highlight(Highlight::Preview);
         }
+        hovered_tile.borrow_mut().set_highlight(if found {
+            Highlight::Hovered
+        } else {
+            Highlight::Blocking
+        });
     }
+}
+
+impl Actor for Grid {
+    fn update_actor(&mut self, _delta_time: f32) {}
 
     actor::impl_getters_setters! {}
 
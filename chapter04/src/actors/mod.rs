@@ -2,5 +2,6 @@ pub mod actor;
 pub mod bullet;
 pub mod enemy;
 pub mod grid;
+pub mod status_effect;
 pub mod tile;
 pub mod tower;
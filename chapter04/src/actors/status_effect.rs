@@ -0,0 +1,182 @@
+use std::mem;
+
+/// Effect a tower puts on an enemy for a while
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatusEffect {
+    // Move at factor of the speed, the strongest slow wins
+    Slow {
+        factor: f32,
+        duration: f32,
+    },
+    // Damage over time for each stack, hitting again adds a stack up to max_stacks
+    Burn {
+        damage_per_second: f32,
+        duration: f32,
+        max_stacks: u32,
+    },
+}
+
+impl StatusEffect {
+    fn get_duration(&self) -> f32 {
+        match self {
+            StatusEffect::Slow { duration, .. } | StatusEffect::Burn { duration, .. } => *duration,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ActiveEffect {
+    effect: StatusEffect,
+    remaining: f32,
+    stacks: u32,
+}
+
+/// Effects on an enemy, one entry for each kind
+#[derive(Debug, Default)]
+pub struct StatusEffects {
+    active: Vec<ActiveEffect>,
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self { active: vec![] }
+    }
+
+    /// Add the effect, or refresh the one of the same kind
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let current = self
+            .active
+            .iter_mut()
+            .find(|active| mem::discriminant(&active.effect) == mem::discriminant(&effect));
+        let current = match current {
+            Some(current) => current,
+            None => {
+                self.active.push(ActiveEffect {
+                    remaining: effect.get_duration(),
+                    effect,
+                    stacks: 1,
+                });
+                return;
+            }
+        };
+
+        current.remaining = current.remaining.max(effect.get_duration());
+        match (&mut current.effect, effect) {
+            (
+                StatusEffect::Slow { factor, .. },
+                StatusEffect::Slow {
+                    factor: new_factor, ..
+                },
+            ) => {
+                *factor = factor.min(new_factor);
+            }
+            (
+                StatusEffect::Burn {
+                    damage_per_second,
+                    max_stacks,
+                    ..
+                },
+                StatusEffect::Burn {
+                    damage_per_second: new_damage,
+                    max_stacks: new_max_stacks,
+                    ..
+                },
+            ) => {
+                *damage_per_second = damage_per_second.max(new_damage);
+                *max_stacks = (*max_stacks).max(new_max_stacks);
+                current.stacks = (current.stacks + 1).min(*max_stacks);
+            }
+            _ => {}
+        }
+    }
+
+    /// Advance the timers, returns the damage taken this frame
+    pub fn update(&mut self, delta_time: f32) -> f32 {
+        let mut damage = 0.0;
+        for active in self.active.iter_mut() {
+            if let StatusEffect::Burn {
+                damage_per_second, ..
+            } = active.effect
+            {
+                damage +=
+                    damage_per_second * active.stacks as f32 * delta_time.min(active.remaining);
+            }
+            active.remaining -= delta_time;
+        }
+        self.active.retain(|active| active.remaining > 0.0);
+        damage
+    }
+
+    /// Fraction of the speed left
+    pub fn get_speed_factor(&self) -> f32 {
+        self.active
+            .iter()
+            .fold(1.0, |speed_factor, active| match active.effect {
+                StatusEffect::Slow { factor, .. } => speed_factor * factor,
+                _ => speed_factor,
+            })
+    }
+
+    pub fn is_slowed(&self) -> bool {
+        self.active
+            .iter()
+            .any(|active| matches!(active.effect, StatusEffect::Slow { .. }))
+    }
+
+    /// Number of burn stacks, 0 when not burning
+    pub fn get_burn_stacks(&self) -> u32 {
+        self.active
+            .iter()
+            .find(|active| matches!(active.effect, StatusEffect::Burn { .. }))
+            .map_or(0, |active| active.stacks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_near_eq;
+
+    use super::{StatusEffect, StatusEffects};
+
+    #[test]
+    fn test_slow() {
+        let mut effects = StatusEffects::new();
+        effects.apply(StatusEffect::Slow {
+            factor: 0.5,
+            duration: 1.0,
+        });
+        // Weaker and longer keeps the factor but lasts longer
+        effects.apply(StatusEffect::Slow {
+            factor: 0.8,
+            duration: 2.0,
+        });
+        assert_near_eq!(0.5, effects.get_speed_factor(), 0.001);
+
+        assert_near_eq!(0.0, effects.update(1.5), 0.001);
+        assert!(effects.is_slowed());
+        effects.update(1.0);
+        assert!(!effects.is_slowed());
+        assert_near_eq!(1.0, effects.get_speed_factor(), 0.001);
+    }
+
+    #[test]
+    fn test_burn_stacks() {
+        let burn = StatusEffect::Burn {
+            damage_per_second: 10.0,
+            duration: 2.0,
+            max_stacks: 2,
+        };
+        let mut effects = StatusEffects::new();
+        effects.apply(burn.clone());
+        assert_near_eq!(5.0, effects.update(0.5), 0.001);
+
+        effects.apply(burn.clone());
+        effects.apply(burn);
+        assert_eq!(2, effects.get_burn_stacks());
+        assert_near_eq!(20.0, effects.update(1.0), 0.001);
+
+        // Only the time left burns
+        assert_near_eq!(20.0, effects.update(5.0), 0.001);
+        assert_eq!(0, effects.get_burn_stacks());
+    }
+}
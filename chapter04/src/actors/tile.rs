@@ -1,11 +1,13 @@
 use std::{cell::RefCell, rc::Rc};
 
+use sdl2::pixels::Color;
+
 use crate::{
     components::{
         component::{Component, State as ComponentState},
         sprite_component::{DefaultSpriteComponent, SpriteComponent},
     },
-    math::vector2::Vector2,
+    math::{path_grid::PathNode, vector2::Vector2},
     system::{entity_manager::EntityManager, texture_manager::TextureManager},
 };
 
@@ -19,6 +21,18 @@ pub enum TileState {
     Base,
 }
 
+/// Feedback while the mouse is over the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    None,
+    // Under the mouse
+    Hovered,
+    // Under the mouse, but a tower here would block the path
+    Blocking,
+    // On the path enemies would take with a tower on the hovered tile
+    Preview,
+}
+
 pub struct Tile {
     id: u32,
     state: State,
@@ -29,44 +43,34 @@ pub struct Tile {
     texture_manager: Rc<RefCell<TextureManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
     // for path finding
-    pub adjacent: Vec<Rc<RefCell<Tile>>>,
-    pub parent: Option<Rc<RefCell<Tile>>>,
-    pub f: f32,
-    pub g: f32,
-    pub h: f32,
-    pub in_open_set: bool,
-    pub in_closed_set: bool,
-    pub blocked: bool,
+    node: Rc<RefCell<PathNode>>,
     sprite: Option<Rc<RefCell<DefaultSpriteComponent>>>,
     pub tile_state: TileState,
     pub selected: bool,
+    highlight: Highlight,
 }
 
 impl Tile {
     pub fn new(
         texture_manager: Rc<RefCell<TextureManager>>,
         entity_manager: Rc<RefCell<EntityManager>>,
+        node: Rc<RefCell<PathNode>>,
     ) -> Rc<RefCell<Self>> {
+        let position = node.borrow().get_position().clone();
         let this = Self {
             id: generate_id(),
             state: State::Active,
-            position: Vector2::ZERO,
+            position,
             scale: 1.0,
             rotation: 0.0,
             components: vec![],
             texture_manager,
             entity_manager: entity_manager.clone(),
-            adjacent: vec![],
-            parent: None,
-            f: 0.0,
-            g: 0.0,
-            h: 0.0,
-            in_open_set: false,
-            in_closed_set: false,
-            blocked: false,
+            node,
             sprite: None,
             tile_state: TileState::Default,
             selected: false,
+            highlight: Highlight::None,
         };
 
         let result = Rc::new(RefCell::new(this));
@@ -78,8 +82,8 @@ impl Tile {
         result
     }
 
-    pub fn add_adjacent(&mut self, adjacent: Rc<RefCell<Tile>>) {
-        self.adjacent.push(adjacent);
+    pub fn get_node(&self) -> &Rc<RefCell<PathNode>> {
+        &self.node
     }
 
     pub fn set_tile_state(&mut self, state: TileState) {
@@ -96,6 +100,22 @@ impl Tile {
         self.update_texture();
     }
 
+    pub fn set_highlight(&mut self, highlight: Highlight) {
+        self.highlight = highlight;
+        let tint = match highlight {
+            Highlight::None => Color::WHITE,
+            Highlight::Hovered => Color::RGB(255, 255, 170),
+            Highlight::Blocking => Color::RGB(255, 110, 110),
+            // Ghost of the rerouted path
+            Highlight::Preview => Color::RGB(160, 200, 255),
+        };
+        self.sprite.as_ref().unwrap().borrow_mut().set_tint(tint);
+    }
+
+    pub fn get_highlight(&self) -> &Highlight {
+        &self.highlight
+    }
+
     fn update_texture(&mut self) {
         let text = match self.tile_state {
             TileState::Start => "Assets/TileTan.png",
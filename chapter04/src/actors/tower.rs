@@ -2,6 +2,7 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     components::{
+        aim_component::AimComponent,
         component::{Component, State as ComponentState},
         move_component::DefaultMoveComponent,
         sprite_component::{DefaultSpriteComponent, SpriteComponent},
@@ -12,9 +13,79 @@ use crate::{
 
 use super::{
     actor::{self, generate_id, Actor, State},
-    bullet::Bullet,
+    bullet::{Bullet, BulletConfig},
+    status_effect::StatusEffect,
 };
 
+/// Towers the grid can build, chosen with the number keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TowerKind {
+    Basic,
+    Slow,
+    Splash,
+}
+
+impl TowerKind {
+    pub fn get_config(&self) -> TowerConfig {
+        match self {
+            TowerKind::Basic => TowerConfig {
+                attack_time: 2.5,
+                attack_range: 100.0,
+                scale: 1.0,
+                attack: TowerAttack::Shoot(BulletConfig {
+                    speed: 400.0,
+                    damage: 100.0,
+                    splash_radius: 0.0,
+                    scale: 1.0,
+                    effect: None,
+                }),
+            },
+            TowerKind::Slow => TowerConfig {
+                attack_time: 1.0,
+                attack_range: 120.0,
+                scale: 0.8,
+                attack: TowerAttack::Pulse(StatusEffect::Slow {
+                    factor: 0.5,
+                    duration: 1.5,
+                }),
+            },
+            TowerKind::Splash => TowerConfig {
+                attack_time: 3.5,
+                attack_range: 160.0,
+                scale: 1.25,
+                attack: TowerAttack::Shoot(BulletConfig {
+                    speed: 250.0,
+                    damage: 80.0,
+                    splash_radius: 75.0,
+                    scale: 2.0,
+                    effect: Some(StatusEffect::Burn {
+                        damage_per_second: 10.0,
+                        duration: 3.0,
+                        max_stacks: 3,
+                    }),
+                }),
+            },
+        }
+    }
+}
+
+/// What a tower does every attack_time
+#[derive(Debug, Clone, PartialEq)]
+pub enum TowerAttack {
+    // Fire a bullet at the nearest enemy
+    Shoot(BulletConfig),
+    // Put the effect on every enemy on the ground in range
+    Pulse(StatusEffect),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TowerConfig {
+    pub attack_time: f32,
+    pub attack_range: f32,
+    pub scale: f32,
+    pub attack: TowerAttack,
+}
+
 pub struct Tower {
     id: u32,
     state: State,
@@ -25,28 +96,31 @@ pub struct Tower {
     texture_manager: Rc<RefCell<TextureManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
     movement: Option<Rc<RefCell<DefaultMoveComponent>>>,
+    aim: Option<Rc<RefCell<AimComponent>>>,
+    config: TowerConfig,
     next_attack: f32,
 }
 
 impl Tower {
-    const ATTACK_TIME: f32 = 2.5;
-    const ATTACK_RANGE: f32 = 100.0;
-
     pub fn new(
         texture_manager: Rc<RefCell<TextureManager>>,
         entity_manager: Rc<RefCell<EntityManager>>,
+        kind: TowerKind,
     ) -> Rc<RefCell<Self>> {
+        let config = kind.get_config();
         let this = Self {
             id: generate_id(),
             state: State::Active,
             position: Vector2::ZERO,
-            scale: 1.0,
+            scale: config.scale,
             rotation: 0.0,
             components: vec![],
             texture_manager: texture_manager.clone(),
             entity_manager: entity_manager.clone(),
             movement: None,
-            next_attack: Tower::ATTACK_TIME,
+            aim: None,
+            next_attack: config.attack_time,
+            config,
         };
 
         let result = Rc::new(RefCell::new(this));
@@ -58,34 +132,83 @@ impl Tower {
         let movement = DefaultMoveComponent::new(result.clone());
         result.borrow_mut().movement = Some(movement);
 
+        if let TowerAttack::Shoot(bullet_config) = &result.borrow().config.attack {
+            let aim = AimComponent::new(result.clone(), bullet_config.speed);
+            result.borrow_mut().aim = Some(aim);
+        }
+
         entity_manager.borrow_mut().add_actor(result.clone());
 
         result
     }
+
+    fn shoot(&mut self, bullet_config: BulletConfig) {
+        let enemy = match self
+            .entity_manager
+            .borrow()
+            .get_nearest_enemy(&self.position)
+        {
+            Some(enemy) => enemy,
+            None => return,
+        };
+
+        let tower_to_enemy = enemy.borrow().get_position().clone() - self.position.clone();
+        if tower_to_enemy.length() >= self.config.attack_range {
+            return;
+        }
+
+        // Lead the enemy, or shoot straight at it if it can't be caught
+        let direction = match self.aim.as_ref().unwrap().borrow().get_aim_point() {
+            Some(aim_point) => aim_point.clone() - self.position.clone(),
+            None => tower_to_enemy,
+        };
+        self.set_rotation((-direction.y).atan2(direction.x));
+
+        let bullet = Bullet::new(
+            self.texture_manager.clone(),
+            self.entity_manager.clone(),
+            bullet_config,
+        );
+        bullet.borrow_mut().set_position(self.position.clone());
+        bullet.borrow_mut().set_rotation(self.rotation);
+        bullet.borrow_mut().set_target(&enemy);
+    }
+
+    fn pulse(&self, effect: &StatusEffect) {
+        let in_range = self
+            .entity_manager
+            .borrow()
+            .get_enemy_colliders()
+            .query(&self.position, self.config.attack_range);
+        for enemy in in_range {
+            let mut enemy = enemy.borrow_mut();
+            if !enemy.is_flying() {
+                enemy.apply_effect(effect.clone());
+            }
+        }
+    }
 }
 
 impl Actor for Tower {
     fn update_actor(&mut self, delta_time: f32) {
-        self.next_attack -= delta_time;
-        if self.next_attack <= 0.0 {
+        if let Some(aim) = &self.aim {
+            // Keep tracking the nearest enemy so its velocity is known when firing
             let enemy = self
                 .entity_manager
                 .borrow()
                 .get_nearest_enemy(&self.position);
+            aim.borrow_mut()
+                .set_target(enemy.map(|enemy| enemy as Rc<RefCell<dyn Actor>>));
+        }
 
-            if let Some(enemy) = enemy {
-                let tower_to_enemy = enemy.borrow().get_position().clone() - self.position.clone();
-                let distance = tower_to_enemy.length();
-                if distance < Tower::ATTACK_RANGE {
-                    self.set_rotation((-tower_to_enemy.y).atan2(tower_to_enemy.x));
-                    let bullet =
-                        Bullet::new(self.texture_manager.clone(), self.entity_manager.clone());
-                    bullet.borrow_mut().set_position(self.position.clone());
-                    bullet.borrow_mut().set_rotation(self.rotation);
-                }
+        self.next_attack -= delta_time;
+        if self.next_attack <= 0.0 {
+            match self.config.attack.clone() {
+                TowerAttack::Shoot(bullet_config) => self.shoot(bullet_config),
+                TowerAttack::Pulse(effect) => self.pulse(&effect),
             }
 
-            self.next_attack += Tower::ATTACK_TIME;
+            self.next_attack += self.config.attack_time;
         }
     }
 
@@ -1,5 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
+use crate::system::crash_report::log;
+
 pub trait AIState {
     fn update(&mut self, delta_time: f32) -> Option<String>;
 
@@ -26,7 +28,7 @@ impl AIPatrol {
 
 impl AIState for AIPatrol {
     fn update(&mut self, _delta_time: f32) -> Option<String> {
-        println!("Updating {} state", self.get_name());
+        log!("Updating {} state", self.get_name());
         let dead = true;
         if dead {
             Some(String::from("Death"))
@@ -36,11 +38,11 @@ impl AIState for AIPatrol {
     }
 
     fn on_enter(&mut self) {
-        println!("Entering {} state", self.get_name());
+        log!("Entering {} state", self.get_name());
     }
 
     fn on_exit(&mut self) {
-        println!("Exiting {} state", self.get_name());
+        log!("Exiting {} state", self.get_name());
     }
 
     fn get_name(&self) -> &String {
@@ -64,16 +66,16 @@ impl AIDeath {
 
 impl AIState for AIDeath {
     fn update(&mut self, _delta_time: f32) -> Option<String> {
-        println!("Updating {} state", self.get_name());
+        log!("Updating {} state", self.get_name());
         None
     }
 
     fn on_enter(&mut self) {
-        println!("Entering {} state", self.get_name());
+        log!("Entering {} state", self.get_name());
     }
 
     fn on_exit(&mut self) {
-        println!("Exiting {} state", self.get_name());
+        log!("Exiting {} state", self.get_name());
     }
 
     fn get_name(&self) -> &String {
@@ -97,16 +99,16 @@ impl AIAttack {
 
 impl AIState for AIAttack {
     fn update(&mut self, _delta_time: f32) -> Option<String> {
-        println!("Updating {} state", self.get_name());
+        log!("Updating {} state", self.get_name());
         None
     }
 
     fn on_enter(&mut self) {
-        println!("Entering {} state", self.get_name());
+        log!("Entering {} state", self.get_name());
     }
 
     fn on_exit(&mut self) {
-        println!("Exiting {} state", self.get_name());
+        log!("Exiting {} state", self.get_name());
     }
 
     fn get_name(&self) -> &String {
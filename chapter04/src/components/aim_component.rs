@@ -0,0 +1,143 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    actors::actor::Actor,
+    math::{intercept, vector2::Vector2},
+};
+
+use super::component::{self, generate_id, Component, State};
+
+/// Leads a moving target, aiming where a projectile will meet it
+pub struct AimComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    projectile_speed: f32,
+    target: Option<Weak<RefCell<dyn Actor>>>,
+    // Velocity of the target, from its move since the last frame
+    last_target_position: Option<Vector2>,
+    target_velocity: Vector2,
+    aim_point: Option<Vector2>,
+}
+
+impl AimComponent {
+    pub fn new(owner: Rc<RefCell<dyn Actor>>, projectile_speed: f32) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 10,
+            state: State::Active,
+            projectile_speed,
+            target: None,
+            last_target_position: None,
+            target_velocity: Vector2::ZERO,
+            aim_point: None,
+        };
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    pub fn set_target(&mut self, target: Option<Rc<RefCell<dyn Actor>>>) {
+        let is_same = match (&self.target, &target) {
+            (Some(current), Some(target)) => {
+                std::ptr::addr_eq(current.as_ptr(), Rc::as_ptr(target))
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if is_same {
+            return;
+        }
+
+        self.target = target.as_ref().map(Rc::downgrade);
+        self.last_target_position = None;
+        self.target_velocity = Vector2::ZERO;
+        self.aim_point = None;
+    }
+
+    /// Where to shoot at, None without a target or when it can't be caught
+    pub fn get_aim_point(&self) -> Option<&Vector2> {
+        self.aim_point.as_ref()
+    }
+}
+
+impl Component for AimComponent {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        owner_info: &(Vector2, f32, Vector2),
+    ) -> (Option<Vector2>, Option<f32>) {
+        let target = match self.target.as_ref().and_then(Weak::upgrade) {
+            Some(target) => target,
+            None => {
+                self.aim_point = None;
+                return (None, None);
+            }
+        };
+        let target_position = target.borrow().get_position().clone();
+
+        if let Some(last_position) = &self.last_target_position {
+            if delta_time > 0.0 {
+                self.target_velocity =
+                    (target_position.clone() - last_position.clone()) * (1.0 / delta_time);
+            }
+        }
+        self.last_target_position = Some(target_position.clone());
+
+        self.aim_point = intercept::solve_intercept(
+            &owner_info.0,
+            &target_position,
+            &self.target_velocity,
+            self.projectile_speed,
+        );
+
+        (None, None)
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        components::component::Component,
+        math::vector2::Vector2,
+    };
+
+    use super::AimComponent;
+
+    #[test]
+    fn test_lead_moving_target() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let aim = AimComponent::new(owner, 100.0);
+
+        let target: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        target.borrow_mut().set_position(Vector2::new(200.0, 0.0));
+        aim.borrow_mut().set_target(Some(target.clone()));
+
+        let owner_info = (Vector2::ZERO, 0.0, Vector2::new(1.0, 0.0));
+        aim.borrow_mut().update(0.1, &owner_info);
+        // No velocity known yet
+        assert_eq!(
+            Some(&Vector2::new(200.0, 0.0)),
+            aim.borrow().get_aim_point()
+        );
+
+        target.borrow_mut().set_position(Vector2::new(200.0, 5.0));
+        aim.borrow_mut().update(0.1, &owner_info);
+        // Ahead of the target, which moves toward +y
+        assert!(aim.borrow().get_aim_point().unwrap().y > 5.0);
+
+        aim.borrow_mut().set_target(None);
+        aim.borrow_mut().update(0.1, &owner_info);
+        assert_eq!(None, aim.borrow().get_aim_point());
+    }
+}
@@ -39,16 +39,60 @@ impl CircleComponent {
     pub fn get_center(&self) -> &Vector2 {
         &self.center
     }
+}
+
+/// Whether the circles touch
+pub fn intersect(a: &CircleComponent, b: &CircleComponent) -> bool {
+    circles_touch(&a.center, a.radius, &b.center, b.radius)
+}
+
+fn circles_touch(center_a: &Vector2, radius_a: f32, center_b: &Vector2, radius_b: f32) -> bool {
+    let radius_sum = radius_a + radius_b;
+    center_a.distance_sq(center_b) <= radius_sum * radius_sum
+}
+
+/// Actors of one kind with their circles, for the other actors to find what they touch.
+/// The EntityManager rebuilds it once per frame, the circles follow their owners in between
+pub struct Colliders<T: ?Sized> {
+    entries: Vec<Collider<T>>,
+}
+
+struct Collider<T: ?Sized> {
+    circle: Rc<RefCell<CircleComponent>>,
+    actor: Rc<RefCell<T>>,
+}
 
-    pub fn intersect(&self, other: Rc<RefCell<CircleComponent>>) -> bool {
-        let borrowed_other = other.borrow();
-        let diff = self.center.clone() - borrowed_other.center.clone();
-        let distance_sq = diff.length_sq();
+impl<T: ?Sized> Colliders<T> {
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
 
-        let radius_diff = self.radius + borrowed_other.radius;
-        let radius_sq = radius_diff * radius_diff;
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
 
-        distance_sq <= radius_sq
+    pub fn add(&mut self, circle: Rc<RefCell<CircleComponent>>, actor: Rc<RefCell<T>>) {
+        self.entries.push(Collider { circle, actor });
+    }
+
+    /// Actors touching the circle at center, in the order they were added
+    pub fn query(&self, center: &Vector2, radius: f32) -> Vec<Rc<RefCell<T>>> {
+        self.entries
+            .iter()
+            .filter(|collider| {
+                let circle = collider.circle.borrow();
+                circles_touch(center, radius, &circle.center, circle.radius)
+            })
+            .map(|collider| collider.actor.clone())
+            .collect()
+    }
+
+    /// First actor the circle touches
+    pub fn first_hit(&self, circle: &CircleComponent) -> Option<Rc<RefCell<T>>> {
+        self.entries
+            .iter()
+            .find(|collider| intersect(circle, &collider.circle.borrow()))
+            .map(|collider| collider.actor.clone())
     }
 }
 
@@ -74,7 +118,7 @@ mod tests {
         math::vector2::Vector2,
     };
 
-    use super::CircleComponent;
+    use super::{intersect, CircleComponent, Colliders};
 
     #[test]
     fn test_intersect_true() {
@@ -90,7 +134,7 @@ mod tests {
         let circle2 = CircleComponent::new(owner2);
         circle2.borrow_mut().set_radius(5.0);
 
-        let result = circle1.borrow().intersect(circle2);
+        let result = intersect(&circle1.borrow(), &circle2.borrow());
 
         assert!(result);
     }
@@ -109,8 +153,38 @@ mod tests {
         let circle2 = CircleComponent::new(owner2);
         circle2.borrow_mut().set_radius(5.0);
 
-        let result = !circle1.borrow().intersect(circle2);
+        let result = !intersect(&circle1.borrow(), &circle2.borrow());
 
         assert!(result);
     }
+
+    #[test]
+    fn test_colliders() {
+        let mut colliders = Colliders::<dyn Actor>::new();
+        for x in [0.0, 30.0, 60.0] {
+            let mut test_actor = TestActor::new();
+            test_actor.set_position(Vector2::new(x, 0.0));
+            let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(test_actor));
+            let circle = CircleComponent::new(owner.clone());
+            circle.borrow_mut().set_radius(10.0);
+            colliders.add(circle, owner);
+        }
+
+        let found = colliders.query(&Vector2::new(45.0, 0.0), 10.0);
+        assert_eq!(2, found.len());
+        assert_eq!(30.0, found[0].borrow().get_position().x);
+        assert_eq!(60.0, found[1].borrow().get_position().x);
+        assert!(colliders.query(&Vector2::new(0.0, 50.0), 10.0).is_empty());
+
+        let mut test_actor = TestActor::new();
+        test_actor.set_position(Vector2::new(45.0, 5.0));
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(test_actor));
+        let circle = CircleComponent::new(owner);
+        circle.borrow_mut().set_radius(10.0);
+        let hit = colliders.first_hit(&circle.borrow()).unwrap();
+        assert_eq!(30.0, hit.borrow().get_position().x);
+
+        colliders.clear();
+        assert!(colliders.first_hit(&circle.borrow()).is_none());
+    }
 }
@@ -1,5 +1,6 @@
 pub mod ai_component;
 mod ai_state;
+pub mod aim_component;
 pub mod circle_component;
 pub mod component;
 pub mod input_component;
@@ -1,8 +1,13 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::{actor::Actor, tile::Tile},
-    math::{self, vector2::Vector2},
+    actors::actor::Actor,
+    math::{
+        self,
+        path_grid::PathNode,
+        steering::{Seek, Separation, Steering, SteeringInput},
+        vector2::Vector2,
+    },
 };
 
 use super::{
@@ -18,7 +23,10 @@ pub struct NavComponent {
     state: State,
     angular_speed: f32,
     forward_speed: f32,
-    next_node: Option<Rc<RefCell<Tile>>>,
+    next_node: Option<Rc<RefCell<PathNode>>>,
+    // Seeks the next node while keeping away from the neighbors
+    steering: Steering,
+    neighbors: Vec<Vector2>,
 }
 
 impl NavComponent {
@@ -32,6 +40,10 @@ impl NavComponent {
             angular_speed: 0.0,
             forward_speed: 0.0,
             next_node: None,
+            steering: Steering::new()
+                .with(Seek, 1.0)
+                .with(Separation { radius: 50.0 }, 0.75),
+            neighbors: vec![],
         };
 
         let result = Rc::new(RefCell::new(this));
@@ -48,7 +60,12 @@ impl NavComponent {
         angle
     }
 
-    pub fn start_path(&mut self, start: Rc<RefCell<Tile>>) {
+    /// Positions of the other agents on the path this frame
+    pub fn set_neighbors(&mut self, neighbors: Vec<Vector2>) {
+        self.neighbors = neighbors;
+    }
+
+    pub fn start_path(&mut self, start: Rc<RefCell<PathNode>>) {
         let binding = start.borrow();
         let parent = binding.parent.clone();
         self.next_node = parent.clone();
@@ -75,9 +92,25 @@ impl Component for NavComponent {
         if let Some(next_node) = self.next_node.clone() {
             let diff = owner_info.0.clone() - next_node.borrow().get_position().clone();
             if math::basic::near_zero(diff.length(), 3.0) {
+                // The goal has no parent, stay on it
                 self.next_node = next_node.borrow().parent.clone();
-                let angle = self.turn_to(self.next_node.clone().unwrap().borrow().get_position());
-                result.1 = Some(angle);
+                if let Some(next_node) = self.next_node.clone() {
+                    result.1 = Some(self.turn_to(next_node.borrow().get_position()));
+                }
+            } else if !self.neighbors.is_empty() {
+                // Steer around the neighbors on the way to the node
+                let input = SteeringInput {
+                    position: owner_info.0.clone(),
+                    velocity: owner_info.2.clone() * self.forward_speed,
+                    max_speed: self.forward_speed,
+                    target: Some(next_node.borrow().get_position().clone()),
+                    threat: None,
+                    neighbors: std::mem::take(&mut self.neighbors),
+                };
+                let velocity = self.steering.compute(&input);
+                if velocity.length_sq() > 0.0 {
+                    result.1 = Some((-velocity.y).atan2(velocity.x));
+                }
             }
         }
 
@@ -86,3 +119,53 @@ impl Component for NavComponent {
 
     component::impl_getters_setters! {}
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        components::move_component::MoveComponent,
+        math::{path_grid::PathGrid, vector2::Vector2},
+    };
+
+    use super::NavComponent;
+
+    #[test]
+    fn test_follow_path_to_base() {
+        let path_grid = PathGrid::new(7, 16, Vector2::new(32.0, 192.0), 64.0);
+        // A wall with a gap at the bottom, like towers built across the grid
+        for row in 0..6 {
+            path_grid.get_node(row, 8).borrow_mut().blocked = true;
+        }
+        let start = path_grid.get_node(3, 0).clone();
+        let base = path_grid.get_node(3, 15).clone();
+        let gap = path_grid.get_node(6, 8).borrow().get_position().clone();
+        assert!(path_grid.find_path(base.clone(), start.clone()));
+
+        let enemy: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        enemy
+            .borrow_mut()
+            .set_position(start.borrow().get_position().clone());
+        let nav_component = NavComponent::new(enemy.clone(), 10);
+        nav_component.borrow_mut().set_forward_speed(150.0);
+        nav_component.borrow_mut().start_path(start);
+
+        let mut passed_gap = false;
+        let mut reached_base = false;
+        for _ in 0..1200 {
+            enemy.borrow_mut().update(1.0 / 60.0);
+            let position = enemy.borrow().get_position().clone();
+            if (position.clone() - gap.clone()).length() < 5.0 {
+                passed_gap = true;
+            }
+            if (position - base.borrow().get_position().clone()).length() < 5.0 {
+                reached_base = true;
+                break;
+            }
+        }
+        assert!(passed_gap);
+        assert!(reached_base);
+    }
+}
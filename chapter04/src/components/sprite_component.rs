@@ -1,7 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use sdl2::{
-    rect::Rect,
+    pixels::Color,
     render::{Canvas, Texture},
     video::Window,
 };
@@ -9,7 +9,7 @@ use sdl2::{
 use crate::{
     actors::actor::Actor,
     components::component::Component,
-    math::{self, vector2::Vector2},
+    math::{self, rect::Rect, vector2::Vector2},
 };
 
 pub trait SpriteComponent: Component {
@@ -18,24 +18,41 @@ pub trait SpriteComponent: Component {
             let owner = self.get_owner().borrow();
             let width = self.get_texture_width() as f32 * owner.get_scale();
             let height = self.get_texture_height() as f32 * owner.get_scale();
-            let rect = Rect::new(
-                (owner.get_position().x - width / 2.0) as i32,
-                (owner.get_position().y - height / 2.0) as i32,
-                width as u32,
-                height as u32,
-            );
+            let rect = Rect::from_center(owner.get_position(), &Vector2::new(width, height));
+
+            // Skip sprites off the screen, whichever way they are rotated
+            let diagonal = width.hypot(height);
+            let bounds = Rect::from_center(owner.get_position(), &Vector2::new(diagonal, diagonal));
+            if !Rect::from(canvas.viewport()).intersects(&bounds) {
+                return;
+            }
+
+            // The texture is shared with other sprites, so the tint is only set while drawing
+            let tint = self.get_tint();
+            let is_tinted = *tint != Color::WHITE;
+            if is_tinted {
+                unsafe {
+                    sdl2::sys::SDL_SetTextureColorMod(texture.raw(), tint.r, tint.g, tint.b);
+                }
+            }
 
             canvas
                 .copy_ex(
                     &texture,
                     None,
-                    Some(rect),
+                    Some(rect.to_sdl_rect()),
                     -math::basic::to_degrees(owner.get_rotation()) as f64,
                     None,
                     false,
                     false,
                 )
                 .unwrap();
+
+            if is_tinted {
+                unsafe {
+                    sdl2::sys::SDL_SetTextureColorMod(texture.raw(), 255, 255, 255);
+                }
+            }
         }
     }
 
@@ -48,6 +65,11 @@ pub trait SpriteComponent: Component {
     fn get_texture_height(&self) -> u32;
 
     fn get_texture_width(&self) -> u32;
+
+    /// Color multiplied with the texture, white draws it as is
+    fn get_tint(&self) -> &Color;
+
+    fn set_tint(&mut self, tint: Color);
 }
 
 macro_rules! impl_getters_setters {
@@ -74,6 +96,14 @@ macro_rules! impl_getters_setters {
         fn get_texture_width(&self) -> u32 {
             self.texture_width
         }
+
+        fn get_tint(&self) -> &Color {
+            &self.tint
+        }
+
+        fn set_tint(&mut self, tint: Color) {
+            self.tint = tint;
+        }
     };
 }
 
@@ -90,6 +120,7 @@ pub struct DefaultSpriteComponent {
     draw_order: i32,
     texture_width: u32,
     texture_height: u32,
+    tint: Color,
 }
 
 impl DefaultSpriteComponent {
@@ -103,6 +134,7 @@ impl DefaultSpriteComponent {
             draw_order,
             texture_height: 0,
             texture_width: 0,
+            tint: Color::WHITE,
         };
 
         let result = Rc::new(RefCell::new(this));
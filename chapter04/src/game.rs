@@ -12,7 +12,15 @@ use sdl2::{
     EventPump, TimerSubsystem,
 };
 
-use crate::system::{entity_manager::EntityManager, texture_manager::TextureManager};
+use crate::{
+    actors::tower::TowerKind,
+    system::{
+        crash_report::{self, ActorCounts},
+        entity_manager::EntityManager,
+        texture_manager::TextureManager,
+        timer_manager::TimerManager,
+    },
+};
 
 pub struct Game {
     canvas: Canvas<Window>,
@@ -20,6 +28,7 @@ pub struct Game {
     timer: TimerSubsystem,
     texture_manager: Rc<RefCell<TextureManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
+    timer_manager: Rc<RefCell<TimerManager>>,
     is_running: bool,
     tick_count: u64,
 }
@@ -47,7 +56,12 @@ impl Game {
         let texture_manager = TextureManager::new(texture_creator);
 
         let entity_manager = EntityManager::new();
-        EntityManager::load_data(entity_manager.clone(), texture_manager.clone());
+        let timer_manager = TimerManager::new();
+        EntityManager::load_data(
+            entity_manager.clone(),
+            texture_manager.clone(),
+            timer_manager.clone(),
+        );
 
         let game = Game {
             canvas,
@@ -55,6 +69,7 @@ impl Game {
             timer,
             texture_manager,
             entity_manager,
+            timer_manager,
             is_running: true,
             tick_count: 0,
         };
@@ -68,9 +83,19 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
         }
     }
 
+    /// Bookkeeping once the frame is over
+    fn end_frame(&self) {
+        let mut actor_counts = ActorCounts::new();
+        for actor in self.entity_manager.borrow().get_actors() {
+            actor_counts.add(actor.borrow().get_state());
+        }
+        crash_report::end_frame(actor_counts);
+    }
+
     /// Herlper functions for the game loop
     fn process_input(&mut self) {
         for event in self.event_pump.poll_iter() {
@@ -88,6 +113,18 @@ impl Game {
             self.is_running = false;
         }
 
+        // Choose the tower to build
+        for (scancode, tower_kind) in [
+            (Scancode::Num1, TowerKind::Basic),
+            (Scancode::Num2, TowerKind::Slow),
+            (Scancode::Num3, TowerKind::Splash),
+        ] {
+            if state.is_scancode_pressed(scancode) {
+                let grid = self.entity_manager.borrow().get_grid();
+                grid.borrow_mut().set_tower_kind(tower_kind);
+            }
+        }
+
         if state.is_scancode_pressed(Scancode::B) {
             let grid = self.entity_manager.borrow().get_grid();
             grid.borrow_mut().build_tower();
@@ -95,6 +132,10 @@ impl Game {
 
         // process mouse
         let button = self.event_pump.mouse_state();
+        {
+            let grid = self.entity_manager.borrow().get_grid();
+            grid.borrow_mut().process_mouse_move(button.x(), button.y());
+        }
         if button.is_mouse_button_pressed(MouseButton::Left) {
             let grid = self.entity_manager.borrow().get_grid();
             grid.borrow_mut().process_click(button.x(), button.y());
@@ -117,6 +158,8 @@ impl Game {
 
         self.tick_count = self.timer.ticks64();
 
+        TimerManager::update(&self.timer_manager, delta_time);
+
         self.entity_manager.borrow_mut().set_updating_actors(true);
         let actors = self.entity_manager.borrow().get_actors().clone();
         for actor in actors {
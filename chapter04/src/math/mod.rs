@@ -1,5 +1,9 @@
 pub mod basic;
+pub mod intercept;
+pub mod path_grid;
 pub mod random;
+pub mod rect;
 pub mod search;
+pub mod steering;
 pub mod vector2;
 pub mod vector3;
@@ -0,0 +1,242 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{rect::Rect, vector2::Vector2};
+
+/// Cell of a PathGrid, its parent is the next cell on the path found last
+pub struct PathNode {
+    pub row: usize,
+    pub column: usize,
+    position: Vector2,
+    pub adjacent: Vec<Rc<RefCell<PathNode>>>,
+    pub parent: Option<Rc<RefCell<PathNode>>>,
+    pub f: f32,
+    pub g: f32,
+    pub h: f32,
+    pub in_open_set: bool,
+    pub in_closed_set: bool,
+    pub blocked: bool,
+}
+
+impl PathNode {
+    pub fn get_position(&self) -> &Vector2 {
+        &self.position
+    }
+}
+
+/// Square cells connected to their four neighbors, searched with A*
+pub struct PathGrid {
+    nodes: Vec<Vec<Rc<RefCell<PathNode>>>>,
+    tile_size: f32,
+}
+
+impl PathGrid {
+    /// origin is the center of the top left cell
+    pub fn new(num_row: usize, num_column: usize, origin: Vector2, tile_size: f32) -> Self {
+        let mut nodes = vec![];
+        for i in 0..num_row {
+            let mut temps = vec![];
+            for j in 0..num_column {
+                let position = Vector2::new(
+                    origin.x + j as f32 * tile_size,
+                    origin.y + i as f32 * tile_size,
+                );
+                temps.push(Rc::new(RefCell::new(PathNode {
+                    row: i,
+                    column: j,
+                    position,
+                    adjacent: vec![],
+                    parent: None,
+                    f: 0.0,
+                    g: 0.0,
+                    h: 0.0,
+                    in_open_set: false,
+                    in_closed_set: false,
+                    blocked: false,
+                })));
+            }
+            nodes.push(temps);
+        }
+
+        // Set up adjacency lists
+        for i in 0..num_row {
+            for j in 0..num_column {
+                let mut node = nodes[i][j].borrow_mut();
+                if i > 0 {
+                    node.adjacent.push(nodes[i - 1][j].clone());
+                }
+                if i < num_row - 1 {
+                    node.adjacent.push(nodes[i + 1][j].clone());
+                }
+                if j > 0 {
+                    node.adjacent.push(nodes[i][j - 1].clone());
+                }
+                if j < num_column - 1 {
+                    node.adjacent.push(nodes[i][j + 1].clone());
+                }
+            }
+        }
+
+        Self { nodes, tile_size }
+    }
+
+    pub fn get_node(&self, row: usize, column: usize) -> &Rc<RefCell<PathNode>> {
+        &self.nodes[row][column]
+    }
+
+    /// Area covered by the cell
+    pub fn get_cell_rect(&self, row: usize, column: usize) -> Rect {
+        let size = Vector2::new(self.tile_size, self.tile_size);
+        Rect::from_center(self.nodes[row][column].borrow().get_position(), &size)
+    }
+
+    /// Area covered by the whole grid
+    pub fn get_bounds(&self) -> Rect {
+        let last_row = self.nodes.len() - 1;
+        let last_column = self.nodes[last_row].len() - 1;
+        self.get_cell_rect(0, 0)
+            .union(&self.get_cell_rect(last_row, last_column))
+    }
+
+    /// Node of the cell containing the point, if it is on the grid
+    pub fn get_node_at(&self, point: &Vector2) -> Option<&Rc<RefCell<PathNode>>> {
+        let bounds = self.get_bounds();
+        if !bounds.contains(point) {
+            return None;
+        }
+        let offset = point.clone() - bounds.min;
+        let row = (offset.y / self.tile_size) as usize;
+        let column = (offset.x / self.tile_size) as usize;
+        Some(&self.nodes[row][column])
+    }
+
+    /// Search from start to goal, setting the parents toward start.
+    /// Searching from the goal gives every node on the way a parent leading to the goal
+    pub fn find_path(&self, start: Rc<RefCell<PathNode>>, goal: Rc<RefCell<PathNode>>) -> bool {
+        for row in self.nodes.iter() {
+            for node in row {
+                let mut node = node.borrow_mut();
+                node.g = 0.0;
+                node.in_open_set = false;
+                node.in_closed_set = false;
+            }
+        }
+
+        let mut open_set: Vec<Rc<RefCell<PathNode>>> = vec![];
+
+        let mut current = start;
+        current.borrow_mut().in_closed_set = true;
+
+        let goal_pos = goal.borrow().get_position().clone();
+
+        let mut is_first = true;
+        while is_first || !Rc::ptr_eq(&current, &goal) {
+            for neighbor in current.borrow().adjacent.clone() {
+                let mut borrowed_neighbor = neighbor.borrow_mut();
+                if borrowed_neighbor.blocked {
+                    continue;
+                }
+
+                if !borrowed_neighbor.in_closed_set {
+                    if !borrowed_neighbor.in_open_set {
+                        borrowed_neighbor.parent = Some(current.clone());
+                        borrowed_neighbor.h =
+                            (borrowed_neighbor.get_position().clone() - goal_pos.clone()).length();
+                        borrowed_neighbor.g = current.borrow().g + self.tile_size;
+                        borrowed_neighbor.f = borrowed_neighbor.g + borrowed_neighbor.h;
+                        borrowed_neighbor.in_open_set = true;
+                        open_set.push(neighbor.clone());
+                    } else {
+                        let new_g = current.borrow().g + self.tile_size;
+                        if new_g < borrowed_neighbor.g {
+                            borrowed_neighbor.parent = Some(current.clone());
+                            borrowed_neighbor.g = new_g;
+                            borrowed_neighbor.f = borrowed_neighbor.g + borrowed_neighbor.h;
+                        }
+                    }
+                }
+            }
+
+            if open_set.is_empty() {
+                break;
+            }
+
+            let min = open_set
+                .clone()
+                .into_iter()
+                .min_by(|a, b| a.borrow().f.partial_cmp(&b.borrow().f).unwrap())
+                .unwrap();
+
+            current = min.clone();
+            open_set.retain(|node| !Rc::ptr_eq(node, &min));
+            current.borrow_mut().in_open_set = false;
+            current.borrow_mut().in_closed_set = true;
+            is_first = false;
+        }
+
+        Rc::ptr_eq(&current, &goal)
+    }
+
+    /// Nodes between start and end, following the parents set by find_path
+    pub fn collect_path(
+        &self,
+        start: Rc<RefCell<PathNode>>,
+        end: &Rc<RefCell<PathNode>>,
+    ) -> Vec<Rc<RefCell<PathNode>>> {
+        let mut path = vec![];
+        let mut node = start.borrow().parent.clone().unwrap();
+
+        while !Rc::ptr_eq(&node, end) {
+            path.push(node.clone());
+            let parent = node.borrow().parent.clone().unwrap();
+            node = parent;
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::vector2::Vector2;
+
+    use super::PathGrid;
+
+    #[test]
+    fn test_find_path() {
+        let path_grid = PathGrid::new(3, 5, Vector2::ZERO, 10.0);
+        let start = path_grid.get_node(1, 0).clone();
+        let end = path_grid.get_node(1, 4).clone();
+
+        assert!(path_grid.find_path(end.clone(), start.clone()));
+        assert_eq!(3, path_grid.collect_path(start.clone(), &end).len());
+
+        // Around a wall with a gap at the bottom
+        path_grid.get_node(0, 2).borrow_mut().blocked = true;
+        path_grid.get_node(1, 2).borrow_mut().blocked = true;
+        assert!(path_grid.find_path(end.clone(), start.clone()));
+        let path = path_grid.collect_path(start.clone(), &end);
+        assert_eq!(5, path.len());
+        assert!(path
+            .iter()
+            .any(|node| node.borrow().row == 2 && node.borrow().column == 2));
+
+        path_grid.get_node(2, 2).borrow_mut().blocked = true;
+        assert!(!path_grid.find_path(end, start));
+    }
+
+    #[test]
+    fn test_get_node_at() {
+        // Cells are 10 wide, the top left one centered on the origin
+        let path_grid = PathGrid::new(3, 5, Vector2::ZERO, 10.0);
+        let bounds = path_grid.get_bounds();
+        assert_eq!(Vector2::new(-5.0, -5.0), bounds.min);
+        assert_eq!(Vector2::new(45.0, 25.0), bounds.max);
+        assert_eq!(Vector2::new(25.0, 15.0), path_grid.get_cell_rect(1, 2).max);
+
+        let node = path_grid.get_node_at(&Vector2::new(16.0, 4.9)).unwrap();
+        assert_eq!((0, 2), (node.borrow().row, node.borrow().column));
+        let node = path_grid.get_node_at(&Vector2::new(-5.0, 24.0)).unwrap();
+        assert_eq!((2, 0), (node.borrow().row, node.borrow().column));
+        assert!(path_grid.get_node_at(&Vector2::new(45.0, 0.0)).is_none());
+        assert!(path_grid.get_node_at(&Vector2::new(0.0, -6.0)).is_none());
+    }
+}
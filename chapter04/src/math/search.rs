@@ -85,6 +85,45 @@ pub struct WeightedGraph {
     nodes: Vec<Rc<RefCell<WeightedGraphNode>>>,
 }
 
+#[cfg(feature = "bench")]
+impl WeightedGraph {
+    /// Nodes in rows of width, each linked to its 4 neighbors with a weight of 1.0
+    pub fn grid(width: usize, height: usize) -> Self {
+        let nodes: Vec<_> = (0..width * height)
+            .map(|_| Rc::new(RefCell::new(WeightedGraphNode::new())))
+            .collect();
+
+        for i in 0..height {
+            for j in 0..width {
+                let node = nodes[i * width + j].clone();
+                let mut neighbors = vec![];
+                if i > 0 {
+                    neighbors.push(nodes[(i - 1) * width + j].clone());
+                }
+                if i < height - 1 {
+                    neighbors.push(nodes[(i + 1) * width + j].clone());
+                }
+                if j > 0 {
+                    neighbors.push(nodes[i * width + j - 1].clone());
+                }
+                if j < width - 1 {
+                    neighbors.push(nodes[i * width + j + 1].clone());
+                }
+                for to in neighbors {
+                    let edge = WeightedEdge::new(node.clone(), to, 1.0);
+                    node.borrow_mut().edges.push(Rc::new(RefCell::new(edge)));
+                }
+            }
+        }
+
+        Self { nodes }
+    }
+
+    pub fn get_node(&self, index: usize) -> Rc<RefCell<WeightedGraphNode>> {
+        self.nodes[index].clone()
+    }
+}
+
 type NodeToParentMap = HashMap<u32, Rc<RefCell<GraphNode>>>;
 
 pub fn bfs(
@@ -179,9 +218,8 @@ pub fn gbfs(
             break;
         }
 
-        let cloned_open_set = open_set.clone();
-        let result = cloned_open_set
-            .into_iter()
+        let result = open_set
+            .iter()
             .min_by(|a, b| {
                 let a_heuristic = out_map
                     .get(&a.borrow().id)
@@ -193,7 +231,8 @@ pub fn gbfs(
                     .unwrap_or(0.0);
                 a_heuristic.partial_cmp(&b_heuristic).unwrap()
             })
-            .unwrap();
+            .unwrap()
+            .clone();
 
         current = result.clone();
         open_set.retain(|node| node.borrow().id != result.borrow().id);
@@ -232,7 +271,7 @@ impl AStartScratch {
     }
 }
 
-type AStarMap = HashMap<u32, Rc<RefCell<AStartScratch>>>;
+pub type AStarMap = HashMap<u32, Rc<RefCell<AStartScratch>>>;
 
 pub fn a_ster(
     start: Rc<RefCell<WeightedGraphNode>>,
@@ -280,9 +319,8 @@ pub fn a_ster(
             break;
         }
 
-        let cloned_open_set = open_set.clone();
-        let result = cloned_open_set
-            .into_iter()
+        let result = open_set
+            .iter()
             .min_by(|a, b| {
                 let a_value = out_map
                     .get(&a.borrow().id)
@@ -294,7 +332,8 @@ pub fn a_ster(
                     .unwrap_or(0.0);
                 a_value.partial_cmp(&b_value).unwrap()
             })
-            .unwrap();
+            .unwrap()
+            .clone();
 
         current = result.clone();
         open_set.retain(|node| node.borrow().id != result.borrow().id);
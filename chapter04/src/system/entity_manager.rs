@@ -6,8 +6,9 @@ use crate::{
         enemy::Enemy,
         grid::Grid,
     },
+    components::circle_component::Colliders,
     math::{random::Random, vector2::Vector2},
-    system::texture_manager::TextureManager,
+    system::{texture_manager::TextureManager, timer_manager::TimerManager},
 };
 
 pub struct EntityManager {
@@ -15,6 +16,7 @@ pub struct EntityManager {
     pending_actors: Vec<Rc<RefCell<dyn Actor>>>,
     updating_actors: bool,
     enemies: Vec<Rc<RefCell<Enemy>>>,
+    enemy_colliders: Colliders<Enemy>,
     grid: Option<Rc<RefCell<Grid>>>,
     random: Random,
 }
@@ -26,6 +28,7 @@ impl EntityManager {
             pending_actors: vec![],
             updating_actors: false,
             enemies: vec![],
+            enemy_colliders: Colliders::new(),
             grid: None,
             random: Random::new(),
         };
@@ -43,7 +46,10 @@ impl EntityManager {
         }
     }
 
+    /// Collides from now on, not only from the next frame
     pub fn add_enemy(&mut self, enemy: Rc<RefCell<Enemy>>) {
+        let circle = enemy.borrow().get_circle();
+        self.enemy_colliders.add(circle, enemy.clone());
         self.enemies.push(enemy);
     }
 
@@ -70,13 +76,21 @@ impl EntityManager {
                 false
             }
         });
+
+        // Collide as the enemies left at the end of the frame
+        self.enemy_colliders.clear();
+        for enemy in &self.enemies {
+            let circle = enemy.borrow().get_circle();
+            self.enemy_colliders.add(circle, enemy.clone());
+        }
     }
 
     pub fn load_data(
         this: Rc<RefCell<EntityManager>>,
         texture_manager: Rc<RefCell<TextureManager>>,
+        timer_manager: Rc<RefCell<TimerManager>>,
     ) {
-        let grid = Grid::new(texture_manager, this.clone());
+        let grid = Grid::new(texture_manager, this.clone(), timer_manager);
         this.borrow_mut().grid = Some(grid);
     }
 
@@ -88,6 +102,10 @@ impl EntityManager {
         &self.enemies
     }
 
+    pub fn get_enemy_colliders(&self) -> &Colliders<Enemy> {
+        &self.enemy_colliders
+    }
+
     pub fn get_nearest_enemy(&self, position: &Vector2) -> Option<Rc<RefCell<Enemy>>> {
         if self.enemies.is_empty() {
             return None;
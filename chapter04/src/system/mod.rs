@@ -1,2 +1,4 @@
+pub mod crash_report;
 pub mod entity_manager;
 pub mod texture_manager;
+pub mod timer_manager;
@@ -8,8 +8,19 @@ out vec4 outColor;
 
 // This is used for the texture sampling
 uniform sampler2D uTexture;
+// Tint and opacity multiplied with the texture color
+uniform vec4 uColor;
+// Seconds since the game started
+uniform float uTime;
+// How much the color pulses toward a cycling hue, 0.0 turns it off
+uniform float uPulseStrength;
 
 void main() {
-    // Sample color from texture
-    outColor = texture(uTexture, fragTexCoord);
+    // Sample color from texture and modulate it
+    outColor = texture(uTexture, fragTexCoord) * uColor;
+
+    // Hue going around the color wheel, blended in and out
+    vec3 hue = 0.5 + 0.5 * cos(uTime + vec3(0.0, 2.094, 4.189));
+    float pulse = (0.5 + 0.5 * sin(uTime * 3.0)) * uPulseStrength;
+    outColor.rgb = mix(outColor.rgb, outColor.rgb * hue, pulse);
 }
@@ -3,6 +3,14 @@
 // Uniforms for world transform and view-proj
 uniform mat4 uWorldTransform;
 uniform mat4 uViewProj;
+// Offset added to the texture coordinate (for scrolling textures)
+uniform vec2 uTexOffset;
+// 1.0 flips the texture coordinate on that axis
+uniform vec2 uTexFlip;
+// Seconds since the game started
+uniform float uTime;
+// Height of the wave moving the vertices up and down, 0.0 turns it off
+uniform float uWaveAmplitude;
 
 // Attribute 0 is position, 1 is tex coords.
 layout(location = 0) in vec3 inPosition;
@@ -13,9 +21,12 @@ out vec2 fragTexCoord;
 
 void main() {
     // Convert position to homogeneous coordinates. Transform position to world space, then clip space
-    vec4 pos = vec4(inPosition, 1.0);
-    gl_Position = pos * uWorldTransform * uViewProj;
+    vec4 pos = vec4(inPosition, 1.0) * uWorldTransform;
+
+    // Wave traveling along x, in world space so neighboring sprites line up
+    pos.y += uWaveAmplitude * sin(uTime * 4.0 + pos.x * 0.02);
+    gl_Position = pos * uViewProj;
     
     // Pass along the texture coordinate to frag shader
-    fragTexCoord = inTexCoord;
+    fragTexCoord = mix(inTexCoord, 1.0 - inTexCoord, uTexFlip) + uTexOffset;
 }
@@ -47,8 +47,8 @@ impl Asteroid {
             circle: None,
         };
 
-        // Initialize to random position/orientation
-        {
+        // Initialize to random position/orientation, and a random look
+        let (brightness, flip_x, flip_y) = {
             let mut borrowed_entity_manager = entity_manager.borrow_mut();
             let random = borrowed_entity_manager.get_random();
             let random_position =
@@ -56,7 +56,12 @@ impl Asteroid {
             let random_rotation = random.get_float_range(0.0, f32::consts::TAU);
             this.set_position(random_position);
             this.set_rotation(random_rotation);
-        }
+            (
+                random.get_float_range(0.6, 1.0),
+                random.get_float() < 0.5,
+                random.get_float() < 0.5,
+            )
+        };
 
         let result = Rc::new(RefCell::new(this));
         entity_manager.borrow_mut().add_actor(result.clone());
@@ -64,7 +69,14 @@ impl Asteroid {
         // Create a sprite component
         let sprite_component = DefaultSpriteComponent::new(result.clone(), 100);
         let texture = texture_manager.borrow_mut().get_texture("Asteroid.png");
-        sprite_component.borrow_mut().set_texture(texture);
+        {
+            let mut borrowed_sprite = sprite_component.borrow_mut();
+            borrowed_sprite.set_texture(texture);
+            // Shaded and mirrored, so they don't all look the same
+            borrowed_sprite.set_color([brightness, brightness, brightness, 1.0]);
+            borrowed_sprite.set_flip_x(flip_x);
+            borrowed_sprite.set_flip_y(flip_y);
+        }
 
         // Create a move component, and set a forward speed
         let move_component: Rc<RefCell<dyn MoveComponent>> =
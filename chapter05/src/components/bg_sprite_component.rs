@@ -0,0 +1,158 @@
+use std::{cell::RefCell, ptr::null, rc::Rc};
+
+use gl::{TRIANGLES, UNSIGNED_INT};
+
+use crate::{
+    actors::actor::Actor,
+    graphics::{shader::Shader, texture::Texture},
+    math::{matrix4::Matrix4, vector2::Vector2},
+};
+
+use super::{
+    component::{self, Component, State},
+    sprite_component::{self, SpriteComponent},
+};
+
+struct BGLayer {
+    texture: Rc<Texture>,
+    // Pixels per second, negative scrolls to the left
+    scroll_speed: f32,
+    // Horizontal texture coordinate offset in [0.0, 1.0)
+    offset: f32,
+}
+
+/// Screen sized background made of scrolling layers (parallax).
+/// Each layer is a single quad whose texture coordinates scroll in the shader,
+/// relying on the default GL_REPEAT wrap mode of the texture.
+pub struct BGSpriteComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    texture: Option<Rc<Texture>>,
+    draw_order: i32,
+    texture_width: u32,
+    texture_height: u32,
+    color: [f32; 4],
+    flip_x: bool,
+    flip_y: bool,
+    // Drawn in order, so the farthest layer comes first
+    layers: Vec<BGLayer>,
+    screen_size: Vector2,
+}
+
+impl BGSpriteComponent {
+    pub fn new(owner: Rc<RefCell<dyn Actor>>, draw_order: i32) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: component::generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            texture: None,
+            draw_order,
+            texture_height: 0,
+            texture_width: 0,
+            color: sprite_component::WHITE,
+            flip_x: false,
+            flip_y: false,
+            layers: vec![],
+            screen_size: Vector2::new(1024.0, 768.0),
+        };
+
+        let result = Rc::new(RefCell::new(this));
+
+        owner.borrow_mut().add_component(result.clone());
+
+        owner
+            .borrow()
+            .get_texture_manager()
+            .borrow_mut()
+            .add_sprite(result.clone());
+
+        result
+    }
+
+    pub fn add_layer(&mut self, texture: Rc<Texture>, scroll_speed: f32) {
+        self.layers.push(BGLayer {
+            texture,
+            scroll_speed,
+            offset: 0.0,
+        });
+    }
+
+    pub fn set_screen_size(&mut self, size: Vector2) {
+        self.screen_size = size;
+    }
+
+    /// Moving the picture to the left means sampling further to the right
+    fn compute_offset(offset: f32, scroll_speed: f32, screen_width: f32, delta_time: f32) -> f32 {
+        if screen_width <= 0.0 {
+            return offset;
+        }
+        (offset - scroll_speed * delta_time / screen_width).rem_euclid(1.0)
+    }
+}
+
+impl SpriteComponent for BGSpriteComponent {
+    fn draw(&self, shader: &Shader) {
+        let scale_mat = Matrix4::create_scale_xyz(self.screen_size.x, self.screen_size.y, 1.0);
+        let world = scale_mat * self.get_owner().borrow().get_world_transform().clone();
+        shader.set_matrix_uniform("uWorldTransform", world);
+        self.set_color_uniforms(shader);
+
+        for layer in &self.layers {
+            shader.set_vector2_uniform("uTexOffset", &Vector2::new(layer.offset, 0.0));
+            layer.texture.set_active();
+
+            unsafe {
+                gl::DrawElements(TRIANGLES, 6, UNSIGNED_INT, null());
+            }
+        }
+
+        // Other sprites don't scroll
+        shader.set_vector2_uniform("uTexOffset", &Vector2::ZERO);
+    }
+
+    sprite_component::impl_getters_setters! {}
+}
+
+impl Component for BGSpriteComponent {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        _owner_info: &(Vector2, f32, Vector2),
+    ) -> (Option<Vector2>, Option<f32>) {
+        let screen_width = self.screen_size.x;
+        for layer in &mut self.layers {
+            layer.offset = BGSpriteComponent::compute_offset(
+                layer.offset,
+                layer.scroll_speed,
+                screen_width,
+                delta_time,
+            );
+        }
+        (None, None)
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_near_eq;
+
+    use super::BGSpriteComponent;
+
+    #[test]
+    fn test_compute_offset() {
+        // Half a screen to the left
+        let offset = BGSpriteComponent::compute_offset(0.0, -512.0, 1024.0, 1.0);
+        assert_near_eq!(0.5, offset, 0.001);
+
+        // Wraps around in both directions
+        let offset = BGSpriteComponent::compute_offset(0.75, -512.0, 1024.0, 1.0);
+        assert_near_eq!(0.25, offset, 0.001);
+        let offset = BGSpriteComponent::compute_offset(0.25, 512.0, 1024.0, 1.0);
+        assert_near_eq!(0.75, offset, 0.001);
+    }
+}
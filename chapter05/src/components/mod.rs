@@ -1,3 +1,4 @@
+pub mod bg_sprite_component;
 pub mod circle_component;
 pub mod component;
 pub mod input_component;
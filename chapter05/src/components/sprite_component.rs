@@ -9,6 +9,9 @@ use crate::{
     math::{matrix4::Matrix4, vector2::Vector2},
 };
 
+/// Color that leaves the texture as it is (RGBA)
+pub const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
 pub trait SpriteComponent: Component {
     fn draw(&self, shader: &Shader) {
         if let Some(texture) = self.get_texture() {
@@ -23,6 +26,7 @@ pub trait SpriteComponent: Component {
 
             // Set world transform
             shader.set_matrix_uniform("uWorldTransform", world);
+            self.set_color_uniforms(shader);
             // Set current texture
             texture.set_active();
 
@@ -33,6 +37,16 @@ pub trait SpriteComponent: Component {
         }
     }
 
+    /// Set tint and flip of this sprite
+    fn set_color_uniforms(&self, shader: &Shader) {
+        shader.set_vector4_uniform("uColor", self.get_color());
+        let flip = Vector2::new(
+            if self.is_flip_x() { 1.0 } else { 0.0 },
+            if self.is_flip_y() { 1.0 } else { 0.0 },
+        );
+        shader.set_vector2_uniform("uTexFlip", &flip);
+    }
+
     fn get_texture(&self) -> Option<&Rc<Texture>>;
 
     fn set_texture(&mut self, texture: Rc<Texture>);
@@ -42,6 +56,19 @@ pub trait SpriteComponent: Component {
     fn get_texture_height(&self) -> u32;
 
     fn get_texture_width(&self) -> u32;
+
+    fn get_color(&self) -> &[f32; 4];
+
+    /// Multiply the texture color by RGBA (alpha is the opacity)
+    fn set_color(&mut self, color: [f32; 4]);
+
+    fn is_flip_x(&self) -> bool;
+
+    fn set_flip_x(&mut self, flip_x: bool);
+
+    fn is_flip_y(&self) -> bool;
+
+    fn set_flip_y(&mut self, flip_y: bool);
 }
 
 macro_rules! impl_getters_setters {
@@ -67,6 +94,30 @@ macro_rules! impl_getters_setters {
         fn get_texture_width(&self) -> u32 {
             self.texture_width
         }
+
+        fn get_color(&self) -> &[f32; 4] {
+            &self.color
+        }
+
+        fn set_color(&mut self, color: [f32; 4]) {
+            self.color = color;
+        }
+
+        fn is_flip_x(&self) -> bool {
+            self.flip_x
+        }
+
+        fn set_flip_x(&mut self, flip_x: bool) {
+            self.flip_x = flip_x;
+        }
+
+        fn is_flip_y(&self) -> bool {
+            self.flip_y
+        }
+
+        fn set_flip_y(&mut self, flip_y: bool) {
+            self.flip_y = flip_y;
+        }
     };
 }
 
@@ -83,6 +134,9 @@ pub struct DefaultSpriteComponent {
     draw_order: i32,
     texture_width: u32,
     texture_height: u32,
+    color: [f32; 4],
+    flip_x: bool,
+    flip_y: bool,
 }
 
 impl DefaultSpriteComponent {
@@ -96,6 +150,9 @@ impl DefaultSpriteComponent {
             draw_order,
             texture_height: 0,
             texture_width: 0,
+            color: WHITE,
+            flip_x: false,
+            flip_y: false,
         };
 
         let result = Rc::new(RefCell::new(this));
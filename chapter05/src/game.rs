@@ -11,7 +11,11 @@ use sdl2::{
     EventPump, TimerSubsystem,
 };
 
-use crate::system::{entity_manager::EntityManager, texture_manager::TextureManager};
+use crate::system::{
+    crash_report::{self, log, ActorCounts},
+    entity_manager::EntityManager,
+    texture_manager::TextureManager,
+};
 
 pub struct Game {
     context: GLContext,
@@ -22,9 +26,17 @@ pub struct Game {
     entity_manager: Rc<RefCell<EntityManager>>,
     is_running: bool,
     tick_count: u64,
+    // Seconds since the start, animates the sprite shader
+    shader_time: f32,
+    pulse_enabled: bool,
+    wave_enabled: bool,
 }
 
 impl Game {
+    // Strength of the shader effects while enabled
+    const PULSE_STRENGTH: f32 = 0.6;
+    const WAVE_AMPLITUDE: f32 = 10.0;
+
     /// Initialize game
     pub fn initialize() -> Result<Game> {
         let sdl = sdl2::init().map_err(|e| anyhow!(e))?;
@@ -69,6 +81,9 @@ impl Game {
             entity_manager,
             is_running: true,
             tick_count: 0,
+            shader_time: 0.0,
+            pulse_enabled: false,
+            wave_enabled: false,
         };
 
         Ok(game)
@@ -80,7 +95,17 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
+        }
+    }
+
+    /// Bookkeeping once the frame is over
+    fn end_frame(&self) {
+        let mut actor_counts = ActorCounts::new();
+        for actor in self.entity_manager.borrow().get_actors() {
+            actor_counts.add(actor.borrow().get_state());
         }
+        crash_report::end_frame(actor_counts);
     }
 
     /// Herlper functions for the game loop
@@ -91,6 +116,22 @@ impl Game {
                     self.is_running = false;
                     break;
                 }
+                Event::KeyDown {
+                    scancode: Some(Scancode::Num1),
+                    repeat: false,
+                    ..
+                } => {
+                    self.pulse_enabled = !self.pulse_enabled;
+                    log!("Pulsing color: {}", self.pulse_enabled);
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::Num2),
+                    repeat: false,
+                    ..
+                } => {
+                    self.wave_enabled = !self.wave_enabled;
+                    log!("Wavy sprites: {}", self.wave_enabled);
+                }
                 _ => {}
             }
         }
@@ -131,6 +172,34 @@ impl Game {
 
         self.entity_manager.borrow_mut().flush_actors();
         self.texture_manager.borrow_mut().flush_sprites();
+
+        self.update_shader_uniforms(delta_time);
+    }
+
+    /// Animate the sprite shader effects
+    fn update_shader_uniforms(&mut self, delta_time: f32) {
+        self.shader_time += delta_time;
+
+        let texture_manager = self.texture_manager.borrow();
+        let shader = &texture_manager.sprite_shader;
+        shader.set_active();
+        shader.set_float_uniform("uTime", self.shader_time);
+        shader.set_float_uniform(
+            "uPulseStrength",
+            if self.pulse_enabled {
+                Game::PULSE_STRENGTH
+            } else {
+                0.0
+            },
+        );
+        shader.set_float_uniform(
+            "uWaveAmplitude",
+            if self.wave_enabled {
+                Game::WAVE_AMPLITUDE
+            } else {
+                0.0
+            },
+        );
     }
 
     fn generate_output(&mut self) {
@@ -6,7 +6,7 @@ use gl::{
     COMPILE_STATUS, FRAGMENT_SHADER, LINK_STATUS, TRUE, VERTEX_SHADER,
 };
 
-use crate::math::matrix4::Matrix4;
+use crate::math::{matrix4::Matrix4, vector2::Vector2};
 
 pub struct Shader {
     // OpenGL IDs of the vertex shader
@@ -70,6 +70,31 @@ impl Shader {
         }
     }
 
+    /// Shader has to be active, e.g. for values changing every frame
+    pub fn set_float_uniform(&self, name: &str, value: f32) {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let location_id = gl::GetUniformLocation(self.shader_program, name.as_ptr());
+            gl::Uniform1f(location_id, value);
+        }
+    }
+
+    pub fn set_vector2_uniform(&self, name: &str, vector: &Vector2) {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let location_id = gl::GetUniformLocation(self.shader_program, name.as_ptr());
+            gl::Uniform2f(location_id, vector.x, vector.y);
+        }
+    }
+
+    pub fn set_vector4_uniform(&self, name: &str, vector: &[f32; 4]) {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let location_id = gl::GetUniformLocation(self.shader_program, name.as_ptr());
+            gl::Uniform4f(location_id, vector[0], vector[1], vector[2], vector[3]);
+        }
+    }
+
     fn compile_shader(&mut self, file_name: &str, shader_type: GLenum) -> Result<u32> {
         // Read all the text into a string
         let path = Path::new(env!("OUT_DIR"))
@@ -3,11 +3,12 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     actors::{
-        actor::{self, Actor, State as ActorState},
+        actor::{self, Actor, DefaultActor, State as ActorState},
         asteroid::Asteroid,
         ship::Ship,
     },
-    math::random::Random,
+    components::bg_sprite_component::BGSpriteComponent,
+    math::{random::Random, vector2::Vector2},
     system::texture_manager::TextureManager,
 };
 
@@ -78,6 +79,20 @@ impl EntityManager {
         }
         this.borrow_mut().ship = Some(ship);
 
+        // Create the background, the stars scroll faster than the far back behind them
+        let background = DefaultActor::new(texture_manager.clone(), this.clone());
+        let bg_sprite = BGSpriteComponent::new(background, 10);
+        {
+            let mut bg = bg_sprite.borrow_mut();
+            bg.set_screen_size(Vector2::new(1024.0, 768.0));
+            let mut borrowed_texture_manager = texture_manager.borrow_mut();
+            bg.add_layer(
+                borrowed_texture_manager.get_texture("Farback01.png"),
+                -100.0,
+            );
+            bg.add_layer(borrowed_texture_manager.get_texture("Stars.png"), -200.0);
+        }
+
         // Create asteroids
         const NUM_ASTEROIDS: i32 = 20;
         let asteroids: Vec<Rc<RefCell<Asteroid>>> = (0..NUM_ASTEROIDS)
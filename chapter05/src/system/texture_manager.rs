@@ -3,7 +3,10 @@ use std::{cell::RefCell, collections::HashMap, rc::Rc};
 use anyhow::{Ok, Result};
 
 use crate::{
-    components::{component::State, sprite_component::SpriteComponent},
+    components::{
+        component::State,
+        sprite_component::{self, SpriteComponent},
+    },
     graphics::{shader::Shader, texture::Texture, vertex_array::VertexArray},
     math::matrix4::Matrix4,
 };
@@ -50,6 +53,8 @@ impl TextureManager {
         let view_proj = Matrix4::create_simple_view_proj(1024.0, 768.0);
         self.sprite_shader
             .set_matrix_uniform("uViewProj", view_proj);
+        self.sprite_shader
+            .set_vector4_uniform("uColor", &sprite_component::WHITE);
 
         Ok(())
     }
@@ -3,4 +3,5 @@ pub mod component;
 pub mod input_component;
 pub mod mesh_component;
 pub mod move_component;
+pub mod rotation_tween;
 pub mod sprite_component;
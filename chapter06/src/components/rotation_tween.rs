@@ -0,0 +1,137 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::actor::Actor,
+    math::{quaternion::Quaternion, vector3::Vector3},
+};
+
+use super::component::{self, generate_id, Component, State};
+
+/// Turns its owner to a rotation over a few seconds, along the shortest arc
+pub struct RotationTween {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    // Rotation of the owner when the turn started, taken on the first update
+    from: Option<Quaternion>,
+    to: Quaternion,
+    duration: f32,
+    elapsed: f32,
+    rotating: bool,
+}
+
+impl RotationTween {
+    pub fn new(owner: Rc<RefCell<dyn Actor>>) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            from: None,
+            to: Quaternion::IDENTITY,
+            duration: 0.0,
+            elapsed: 0.0,
+            rotating: false,
+        };
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    /// Turn from wherever the owner is to the rotation in seconds,
+    /// replacing the turn in progress
+    pub fn rotate_to(&mut self, rotation: Quaternion, seconds: f32) {
+        self.from = None;
+        self.to = rotation;
+        self.duration = seconds;
+        self.elapsed = 0.0;
+        self.rotating = true;
+    }
+
+    pub fn is_rotating(&self) -> bool {
+        self.rotating
+    }
+}
+
+/// Rotation elapsed seconds into a turn lasting duration, easing in and out
+pub fn rotation_at(from: &Quaternion, to: &Quaternion, elapsed: f32, duration: f32) -> Quaternion {
+    if elapsed >= duration {
+        return to.clone();
+    }
+    let t = (elapsed / duration).max(0.0);
+    Quaternion::slerp(from, to, t * t * (3.0 - 2.0 * t))
+}
+
+impl Component for RotationTween {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        owner_info: &(Vector3, Quaternion, Vector3),
+    ) -> (Option<Vector3>, Option<Quaternion>) {
+        if !self.rotating {
+            return (None, None);
+        }
+
+        let from = self.from.get_or_insert_with(|| owner_info.1.clone());
+        self.elapsed = (self.elapsed + delta_time).min(self.duration);
+        let rotation = rotation_at(from, &self.to, self.elapsed, self.duration);
+        if self.elapsed >= self.duration {
+            self.rotating = false;
+        }
+        (None, Some(rotation))
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        assert_near_eq,
+        components::component::Component,
+        math::{quaternion::Quaternion, vector3::Vector3},
+    };
+
+    use super::{rotation_at, RotationTween};
+
+    #[test]
+    fn test_rotation_at() {
+        let from = Quaternion::IDENTITY;
+        let to = Quaternion::from_axis_angle(&Vector3::UNIT_Z, std::f32::consts::FRAC_PI_2);
+
+        assert_eq!(from, rotation_at(&from, &to, 0.0, 2.0));
+        assert_eq!(to, rotation_at(&from, &to, 5.0, 2.0));
+        assert_eq!(to, rotation_at(&from, &to, 0.0, 0.0));
+
+        // Halfway in time is halfway in angle
+        let half = Quaternion::from_axis_angle(&Vector3::UNIT_Z, std::f32::consts::FRAC_PI_4);
+        let actual = rotation_at(&from, &to, 1.0, 2.0);
+        assert_near_eq!(half.z, actual.z, 0.0001);
+        assert_near_eq!(half.w, actual.w, 0.0001);
+    }
+
+    #[test]
+    fn test_update() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let rotation_tween = RotationTween::new(owner);
+        let owner_info = (Vector3::ZERO, Quaternion::IDENTITY, Vector3::UNIT_X);
+        assert_eq!(
+            (None, None),
+            rotation_tween.borrow_mut().update(0.5, &owner_info)
+        );
+
+        let to = Quaternion::from_axis_angle(&Vector3::UNIT_X, 1.0);
+        rotation_tween.borrow_mut().rotate_to(to.clone(), 1.0);
+        let (_, rotation) = rotation_tween.borrow_mut().update(0.5, &owner_info);
+        assert_ne!(Some(to.clone()), rotation);
+        assert!(rotation_tween.borrow().is_rotating());
+
+        let (_, rotation) = rotation_tween.borrow_mut().update(0.5, &owner_info);
+        assert_eq!(Some(to), rotation);
+        assert!(!rotation_tween.borrow().is_rotating());
+    }
+}
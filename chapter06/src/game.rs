@@ -10,7 +10,10 @@ use sdl2::{
 };
 
 use crate::system::{
-    asset_manager::AssetManager, entity_manager::EntityManager, renderer::Renderer,
+    asset_manager::AssetManager,
+    crash_report::{self, log, ActorCounts},
+    entity_manager::EntityManager,
+    renderer::Renderer,
 };
 
 pub struct Game {
@@ -62,9 +65,19 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
         }
     }
 
+    /// Bookkeeping once the frame is over
+    fn end_frame(&self) {
+        let mut actor_counts = ActorCounts::new();
+        for actor in self.entity_manager.borrow().get_actors() {
+            actor_counts.add(actor.borrow().get_state());
+        }
+        crash_report::end_frame(actor_counts);
+    }
+
     /// Herlper functions for the game loop
     fn process_input(&mut self) {
         for event in self.event_pump.poll_iter() {
@@ -73,6 +86,22 @@ impl Game {
                     self.is_running = false;
                     break;
                 }
+                Event::KeyDown {
+                    scancode: Some(Scancode::F1),
+                    repeat: false,
+                    ..
+                } => {
+                    // Cycle through the debug views of the meshes
+                    let mut renderer = self.renderer.borrow_mut();
+                    let debug_view = renderer.get_debug_view().next();
+                    renderer.set_debug_view(debug_view);
+                    log!("Debug view: {:?}", debug_view);
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::R),
+                    repeat: false,
+                    ..
+                } => self.entity_manager.borrow_mut().rotate_cube(),
                 _ => {}
             }
         }
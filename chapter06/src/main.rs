@@ -5,10 +5,12 @@ mod graphics;
 mod math;
 mod system;
 
-use crate::game::*;
+use crate::{game::*, system::crash_report};
 use anyhow::Result;
 
 fn main() -> Result<()> {
+    crash_report::install();
+
     let mut game = Game::initialize()?;
     game.run_loop();
 
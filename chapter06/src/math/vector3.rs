@@ -1,4 +1,6 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
 use super::quaternion::Quaternion;
 
@@ -72,6 +74,33 @@ impl Vector3 {
         temp.z = self.x * other.y - self.y * other.x;
         temp
     }
+
+    pub fn distance_sq(&self, other: &Vector3) -> f32 {
+        (self.clone() - other.clone()).length_sq()
+    }
+
+    pub fn distance(&self, other: &Vector3) -> f32 {
+        self.distance_sq(other).sqrt()
+    }
+
+    /// Linear interpolation from self (f = 0.0) to other (f = 1.0)
+    pub fn lerp(&self, other: &Vector3, f: f32) -> Vector3 {
+        self.clone() + (other.clone() - self.clone()) * f
+    }
+
+    /// Reflect about the normal, which must be normalized
+    pub fn reflect(&self, normal: &Vector3) -> Vector3 {
+        self.clone() - normal.clone() * 2.0 * self.dot(normal)
+    }
+
+    /// Clamp each component between the ones of min and max
+    pub fn clamp(&self, min: &Vector3, max: &Vector3) -> Vector3 {
+        Vector3::new(
+            self.x.clamp(min.x, max.x),
+            self.y.clamp(min.y, max.y),
+            self.z.clamp(min.z, max.z),
+        )
+    }
 }
 
 impl Add for Vector3 {
@@ -147,8 +176,68 @@ impl MulAssign<f32> for Vector3 {
     }
 }
 
+/// Scalar division
+impl Div<f32> for Vector3 {
+    type Output = Self;
+
+    fn div(self, rhs: f32) -> Self::Output {
+        Self {
+            x: self.x / rhs,
+            y: self.y / rhs,
+            z: self.z / rhs,
+        }
+    }
+}
+
+impl DivAssign<f32> for Vector3 {
+    fn div_assign(&mut self, rhs: f32) {
+        self.x /= rhs;
+        self.y /= rhs;
+        self.z /= rhs;
+    }
+}
+
+impl Neg for Vector3 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+/// Component by index, 0 is x, 1 is y, 2 is z
+impl Index<usize> for Vector3 {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vector3 index out of range: {}", index),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vector3 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Vector3 index out of range: {}", index),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use crate::{assert_near_eq, math::vector3::Vector3};
 
     #[test]
@@ -299,4 +388,100 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_div_scalar() {
+        let expected = Vector3::new(5.0, 3.0, 1.0);
+
+        let a = Vector3::new(10.0, 6.0, 2.0);
+        let actual = a / 2.0;
+
+        assert_eq!(expected, actual);
+
+        let mut actual = Vector3::new(10.0, 6.0, 2.0);
+        actual /= 2.0;
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neg() {
+        let expected = Vector3::new(-5.0, -3.0, -1.0);
+
+        let actual = -Vector3::new(5.0, 3.0, 1.0);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut a = Vector3::new(1.0, 2.0, 3.0);
+        assert_eq!(1.0, a[0]);
+        assert_eq!(2.0, a[1]);
+        assert_eq!(3.0, a[2]);
+
+        a[2] = 10.0;
+        assert_eq!(10.0, a.z);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_range() {
+        let _ = Vector3::ZERO[3];
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Vector3::new(1.0, 2.0, 3.0);
+        let b = Vector3::new(4.0, 6.0, 3.0);
+
+        assert_eq!(25.0, a.distance_sq(&b));
+        assert_eq!(5.0, a.distance(&b));
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = Vector3::new(0.0, 0.0, 0.0);
+        let b = Vector3::new(10.0, 20.0, -10.0);
+
+        assert_eq!(a, a.lerp(&b, 0.0));
+        assert_eq!(Vector3::new(5.0, 10.0, -5.0), a.lerp(&b, 0.5));
+        assert_eq!(b, a.lerp(&b, 1.0));
+    }
+
+    #[test]
+    fn test_reflect() {
+        let expected = Vector3::new(1.0, 1.0, 0.0);
+
+        let a = Vector3::new(1.0, -1.0, 0.0);
+        let actual = a.reflect(&Vector3::UNIT_Y);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let expected = Vector3::new(1.0, -1.0, 0.5);
+
+        let a = Vector3::new(3.0, -2.0, 0.5);
+        let actual = a.clamp(
+            &Vector3::new(-1.0, -1.0, -1.0),
+            &Vector3::new(1.0, 1.0, 1.0),
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_normalize_is_unit_length(
+            x in -1000.0f32..1000.0,
+            y in -1000.0f32..1000.0,
+            z in -1000.0f32..1000.0,
+        ) {
+            let v = Vector3::new(x, y, z);
+            prop_assume!(v.length() > 0.001);
+            assert_near_eq!(1.0, v.normalize().length(), 0.0001);
+        }
+    }
 }
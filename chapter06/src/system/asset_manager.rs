@@ -17,6 +17,8 @@ pub struct AssetManager {
     pub sprite_shader: Shader,
     meshes: HashMap<String, Rc<Mesh>>,
     pub mesh_shader: Shader,
+    // Unlit views of the meshes
+    pub debug_shader: Shader,
     mesh_components: Vec<Rc<RefCell<MeshComponent>>>,
 }
 
@@ -29,6 +31,7 @@ impl AssetManager {
             sprite_shader: Shader::new(),
             meshes: HashMap::new(),
             mesh_shader: Shader::new(),
+            debug_shader: Shader::new(),
             mesh_components: vec![],
         };
 
@@ -79,6 +82,9 @@ impl AssetManager {
         self.mesh_shader
             .set_matrix_uniform("uViewProj", view.clone() * projection.clone());
 
+        // Same vertex outputs as the mesh shader
+        self.debug_shader.load("Phong.vert", "Debug.frag")?;
+
         Ok((view, projection))
     }
 
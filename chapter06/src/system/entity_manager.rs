@@ -9,6 +9,7 @@ use crate::{
     },
     components::{
         mesh_component::MeshComponent,
+        rotation_tween::RotationTween,
         sprite_component::{DefaultSpriteComponent, SpriteComponent},
     },
     math::{quaternion::Quaternion, random::Random, vector3::Vector3},
@@ -20,16 +21,22 @@ pub struct EntityManager {
     pending_actors: Vec<Rc<RefCell<dyn Actor>>>,
     updating_actors: bool,
     camera_actor: Option<Rc<RefCell<CameraActor>>>,
+    // Turns the cube to random rotations
+    cube_rotation: Option<Rc<RefCell<RotationTween>>>,
     random: Random,
 }
 
 impl EntityManager {
+    // Seconds the cube takes to turn
+    const CUBE_ROTATE_TIME: f32 = 1.5;
+
     pub fn new() -> Rc<RefCell<Self>> {
         let this = Self {
             actors: vec![],
             pending_actors: vec![],
             updating_actors: false,
             camera_actor: None,
+            cube_rotation: None,
             random: Random::new(),
         };
 
@@ -80,6 +87,7 @@ impl EntityManager {
         let mesh = MeshComponent::new(a.clone());
         mesh.borrow_mut()
             .set_mesh(asset_manager.borrow_mut().get_mesh("Cube.gpmesh"));
+        this.borrow_mut().cube_rotation = Some(RotationTween::new(a.clone()));
 
         let b = DefaultActor::new(asset_manager.clone(), this.clone());
         b.borrow_mut().set_position(Vector3::new(200.0, -75.0, 0.0));
@@ -164,6 +172,30 @@ impl EntityManager {
         sprite_component.borrow_mut().set_texture(texture);
     }
 
+    /// Turn the cube smoothly to a random rotation, once the last turn is over
+    pub fn rotate_cube(&mut self) {
+        let cube_rotation = match &self.cube_rotation {
+            Some(cube_rotation) if !cube_rotation.borrow().is_rotating() => cube_rotation.clone(),
+            _ => return,
+        };
+        let axis = loop {
+            let axis = self
+                .random
+                .get_vector3(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0));
+            // Too short to normalize
+            if axis.length_sq() > 0.01 {
+                break axis.normalize();
+            }
+        };
+        let angle = self
+            .random
+            .get_float_range(-f32::consts::PI, f32::consts::PI);
+        cube_rotation.borrow_mut().rotate_to(
+            Quaternion::from_axis_angle(&axis, angle),
+            EntityManager::CUBE_ROTATE_TIME,
+        );
+    }
+
     pub fn get_actors(&self) -> &Vec<Rc<RefCell<dyn Actor>>> {
         &self.actors
     }
@@ -1,3 +1,4 @@
 pub mod asset_manager;
+pub mod crash_report;
 pub mod entity_manager;
 pub mod renderer;
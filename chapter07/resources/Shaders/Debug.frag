@@ -0,0 +1,28 @@
+#version 330
+
+// Tex coord input from vertex shader
+in vec2 fragTexCoord;
+
+// Normal (in world space)
+in vec3 fragNormal;
+
+// This corresponds to the output color to the color buffer
+out vec4 outColor;
+
+// This is used for the texture sampling
+uniform sampler2D uTexture;
+
+// 1 is the texture color without lighting, 2 is the normal,
+// 3 is a dim constant color added up per layer to show overdraw
+uniform int uDebugView;
+
+void main() {
+    if (uDebugView == 1) {
+        outColor = texture(uTexture, fragTexCoord);
+    } else if (uDebugView == 2) {
+        // Map the normal from [-1, 1] to [0, 1]
+        outColor = vec4(normalize(fragNormal) * 0.5 + 0.5, 1.0);
+    } else {
+        outColor = vec4(0.1, 0.04, 0.02, 1.0);
+    }
+}
@@ -12,8 +12,12 @@ use sdl2::{
 use crate::{
     actors::camera_actor::{self, CameraActor},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        renderer::Renderer, sound_event::SoundEvent,
+        asset_manager::AssetManager,
+        audio_system::AudioSystem,
+        crash_report::{self, log, ActorCounts},
+        entity_manager::EntityManager,
+        renderer::Renderer,
+        sound_event::SoundEvent,
     },
 };
 
@@ -46,7 +50,7 @@ impl Game {
         let asset_manager = renderer.borrow().get_asset_manager().clone();
         let entity_manager = EntityManager::new();
 
-        let audio_system = AudioSystem::initialize(asset_manager.clone())?;
+        let audio_system = AudioSystem::initialize(asset_manager.clone());
         let music_event = audio_system.borrow_mut().play_event("event:/Music");
 
         let camera_actor = EntityManager::load_data(
@@ -79,9 +83,19 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
         }
     }
 
+    /// Bookkeeping once the frame is over
+    fn end_frame(&self) {
+        let mut actor_counts = ActorCounts::new();
+        for actor in self.entity_manager.borrow().get_actors() {
+            actor_counts.add(actor.borrow().get_state());
+        }
+        crash_report::end_frame(actor_counts);
+    }
+
     /// Herlper functions for the game loop
     fn process_input(&mut self) {
         for event in self.event_pump.poll_iter() {
@@ -90,6 +104,17 @@ impl Game {
                     self.is_running = false;
                     break;
                 }
+                Event::KeyDown {
+                    scancode: Some(Scancode::F1),
+                    repeat: false,
+                    ..
+                } => {
+                    // Cycle through the debug views of the meshes
+                    let mut renderer = self.renderer.borrow_mut();
+                    let debug_view = renderer.get_debug_view().next();
+                    renderer.set_debug_view(debug_view);
+                    log!("Debug view: {:?}", debug_view);
+                }
                 Event::KeyDown {
                     scancode, repeat, ..
                 } => {
@@ -148,7 +173,7 @@ impl Game {
                 music_event.set_paused(!music_event.get_paused());
             }
             Scancode::R => {
-                // FIXME: An error will happen when switching four times...
+                // The handle turns invalid once the stopped snapshot is released
                 if let Some(reverb) = reverb_snap {
                     if reverb.is_valid() {
                         reverb.stop(true);
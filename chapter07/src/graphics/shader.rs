@@ -86,6 +86,14 @@ impl Shader {
         }
     }
 
+    pub fn set_int_uniform(&self, name: &str, value: i32) {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let location_id = gl::GetUniformLocation(self.shader_program, name.as_ptr());
+            gl::Uniform1i(location_id, value);
+        }
+    }
+
     fn compile_shader(&mut self, file_name: &str, shader_type: GLenum) -> Result<u32> {
         // Read all the text into a string
         let path = Path::new(env!("OUT_DIR"))
@@ -137,7 +137,14 @@ impl Quaternion {
 
 #[cfg(test)]
 mod tests {
-    use crate::{assert_near_eq, math::vector3::Vector3};
+    use std::f32::consts::PI;
+
+    use proptest::prelude::*;
+
+    use crate::{
+        assert_near_eq,
+        math::{matrix4::Matrix4, vector3::Vector3},
+    };
 
     use super::Quaternion;
 
@@ -164,4 +171,36 @@ mod tests {
         assert_near_eq!(actual.z, 0.0, 0.000001);
         assert_near_eq!(actual.w, 0.923879564, 0.000001);
     }
+
+    /// Rotation about any axis by any angle
+    fn rotation() -> impl Strategy<Value = Quaternion> {
+        (-1.0f32..1.0, -1.0f32..1.0, -1.0f32..1.0, -PI..PI)
+            .prop_filter("axis too short to normalize", |(x, y, z, _)| {
+                Vector3::new(*x, *y, *z).length() > 0.01
+            })
+            .prop_map(|(x, y, z, angle)| {
+                Quaternion::from_axis_angle(&Vector3::new(x, y, z).normalize(), angle)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_concatenate_matches_matrix_multiply(q in rotation(), p in rotation()) {
+            let expected =
+                Matrix4::create_from_quaternion(&q) * Matrix4::create_from_quaternion(&p);
+            let actual = Matrix4::create_from_quaternion(&q.concatenate(&p));
+            for row in 0..4 {
+                for column in 0..4 {
+                    assert_near_eq!(expected.mat[row][column], actual.mat[row][column], 0.0001);
+                }
+            }
+        }
+
+        #[test]
+        fn prop_slerp_endpoints_match_inputs(a in rotation(), b in rotation()) {
+            // q and -q are the same rotation, slerp takes the shorter way to either
+            assert_near_eq!(1.0, a.slerp(&b, 0.0).dot(&a).abs(), 0.0001);
+            assert_near_eq!(1.0, a.slerp(&b, 1.0).dot(&b).abs(), 0.0001);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 pub mod asset_manager;
 pub mod audio_system;
+pub mod crash_report;
 pub mod entity_manager;
 pub mod renderer;
 pub mod sound_event;
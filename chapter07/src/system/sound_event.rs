@@ -1,4 +1,4 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Weak};
 
 use libfmod::{
     ffi::{FMOD_STUDIO_STOP_ALLOWFADEOUT, FMOD_STUDIO_STOP_IMMEDIATE},
@@ -9,23 +9,46 @@ use crate::math::{matrix4::Matrix4, vector3::Vector3};
 
 use super::audio_system::AudioSystem;
 
+/// Handle to a playing event. The instance is looked up by id in the instances of
+/// AudioSystem, so the handle turns invalid once the instance is released, and every
+/// call on an invalid handle does nothing
 pub struct SoundEvent {
     id: u32,
-    event_instance: Rc<RefCell<EventInstance>>,
+    event_instances: Weak<RefCell<HashMap<u32, EventInstance>>>,
 }
 
 impl SoundEvent {
-    pub fn new(id: u32, event_instance: Rc<RefCell<EventInstance>>) -> Self {
-        Self { id, event_instance }
+    pub fn new(id: u32, event_instances: Weak<RefCell<HashMap<u32, EventInstance>>>) -> Self {
+        Self {
+            id,
+            event_instances,
+        }
+    }
+
+    /// Plays nothing, never valid
+    pub fn inert() -> Self {
+        Self::new(0, Weak::new())
+    }
+
+    /// None once AudioSystem released the instance, or dropped
+    fn get_event_instance(&self) -> Option<EventInstance> {
+        let event_instances = self.event_instances.upgrade()?;
+        let event_instance = event_instances.borrow().get(&self.id).copied();
+        event_instance
     }
 
     pub fn is_valid(&self) -> bool {
-        let state = self.event_instance.borrow().get_playback_state().unwrap();
-        state != PlaybackState::Stopped
+        self.get_event_instance().is_some_and(|event_instance| {
+            event_instance
+                .get_playback_state()
+                .is_ok_and(|state| state != PlaybackState::Stopped)
+        })
     }
 
     pub fn restart(&mut self) {
-        self.event_instance.borrow_mut().start().unwrap();
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.start().unwrap();
+        }
     }
 
     pub fn stop(&mut self, allow_fade_out: bool) {
@@ -34,57 +57,74 @@ impl SoundEvent {
         } else {
             StopMode::Immediate
         };
-        self.event_instance.borrow_mut().stop(mode).unwrap();
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.stop(mode).unwrap();
+        }
     }
 
     pub fn set_paused(&mut self, pause: bool) {
-        self.event_instance.borrow_mut().set_paused(pause).unwrap();
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.set_paused(pause).unwrap();
+        }
     }
 
     pub fn set_volume(&mut self, value: f32) {
-        self.event_instance.borrow_mut().set_volume(value).unwrap();
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.set_volume(value).unwrap();
+        }
     }
 
     pub fn set_pitch(&mut self, value: f32) {
-        self.event_instance.borrow_mut().set_pitch(value).unwrap();
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.set_pitch(value).unwrap();
+        }
     }
 
     pub fn set_parameter(&mut self, name: &str, value: f32) {
-        self.event_instance
-            .borrow_mut()
-            .set_parameter_by_name(name, value, false)
-            .unwrap();
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance
+                .set_parameter_by_name(name, value, false)
+                .unwrap();
+        }
     }
 
     pub fn get_paused(&self) -> bool {
-        self.event_instance.borrow().get_paused().unwrap()
+        self.get_event_instance()
+            .is_some_and(|event_instance| event_instance.get_paused().unwrap())
     }
 
+    /// Invalid handles are silent
     pub fn get_volume(&self) -> f32 {
-        self.event_instance.borrow().get_volume().unwrap().0
+        self.get_event_instance()
+            .map_or(0.0, |event_instance| event_instance.get_volume().unwrap().0)
     }
 
     pub fn get_pitch(&self) -> f32 {
-        self.event_instance.borrow().get_pitch().unwrap().0
+        self.get_event_instance()
+            .map_or(1.0, |event_instance| event_instance.get_pitch().unwrap().0)
     }
 
     pub fn get_parameter(&self, name: &str) -> f32 {
-        self.event_instance
-            .borrow()
-            .get_parameter_by_name(name)
-            .unwrap()
-            .0
+        self.get_event_instance().map_or(0.0, |event_instance| {
+            event_instance.get_parameter_by_name(name).unwrap().0
+        })
     }
 
     pub fn is_3d(&self) -> bool {
-        self.event_instance
-            .borrow()
-            .get_description()
-            .and_then(|description| description.is_3d())
-            .is_ok_and(|is_3d| is_3d)
+        self.get_event_instance().is_some_and(|event_instance| {
+            event_instance
+                .get_description()
+                .and_then(|description| description.is_3d())
+                .is_ok_and(|is_3d| is_3d)
+        })
     }
 
     pub fn set_3d_attributes(&mut self, world_trans: &Matrix4) {
+        let event_instance = match self.get_event_instance() {
+            Some(event_instance) => event_instance,
+            None => return,
+        };
+
         let attributes = Attributes3d {
             position: AudioSystem::vector_to_fmod(&world_trans.get_translation()),
             forward: AudioSystem::vector_to_fmod(&world_trans.get_x_axis()),
@@ -92,9 +132,6 @@ impl SoundEvent {
             velocity: AudioSystem::vector_to_fmod(&Vector3::ZERO),
         };
 
-        self.event_instance
-            .borrow_mut()
-            .set_3d_attributes(attributes)
-            .unwrap();
+        event_instance.set_3d_attributes(attributes).unwrap();
     }
 }
@@ -8,7 +8,9 @@ use crate::{
     components::component::{Component, State as ComponentState},
     math::{matrix4::Matrix4, vector2::Vector2, vector3::Vector3},
     system::{
-        entity_manager::EntityManager, input_system::InputState, texture_manager::TextureManager,
+        entity_manager::EntityManager,
+        input_system::{InputState, PlayerIndex},
+        texture_manager::TextureManager,
     },
 };
 
@@ -80,6 +82,11 @@ pub trait Actor {
     // Any actor-specific input code (overridable)
     fn actor_input(&mut self, _state: &InputState) {}
 
+    /// Player whose devices move this actor, None for the input of every device (overridable)
+    fn get_player_index(&self) -> Option<PlayerIndex> {
+        None
+    }
+
     fn compute_world_transform(&mut self) {
         if !self.get_recompute_world_transform() {
             return;
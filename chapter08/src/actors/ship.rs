@@ -1,7 +1,11 @@
 use core::f32;
 use std::{cell::RefCell, rc::Rc};
 
-use sdl2::keyboard::{KeyboardState, Scancode};
+use sdl2::{
+    controller::Button,
+    keyboard::{KeyboardState, Scancode},
+    mouse::MouseButton,
+};
 
 use crate::{
     actors::actor::{self, Actor, State},
@@ -10,9 +14,11 @@ use crate::{
         input_component::InputComponent,
         sprite_component::{DefaultSpriteComponent, SpriteComponent},
     },
-    math::{matrix4::Matrix4, vector2::Vector2},
+    math::{self, matrix4::Matrix4, vector2::Vector2},
     system::{
-        entity_manager::EntityManager, input_system::InputState, texture_manager::TextureManager,
+        entity_manager::EntityManager,
+        input_system::{ButtonState, InputState, PlayerIndex},
+        texture_manager::TextureManager,
     },
 };
 
@@ -29,13 +35,33 @@ pub struct Ship {
     components: Vec<Rc<RefCell<dyn Component>>>,
     texture_manager: Rc<RefCell<TextureManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
+    input_component: Option<Rc<RefCell<InputComponent>>>,
+    player_index: PlayerIndex,
     laser_cooldown: f32,
+    // From the left stick, moves the ship without turning it
+    velocity_dir: Vector2,
+    speed: f32,
+    // To notice when the mouse moves
+    last_mouse_position: Vector2,
 }
 
 impl Ship {
+    const LASER_COOLDOWN: f32 = 0.25;
+    const DEFAULT_SPEED: f32 = 300.0;
+    // Share of the stick speed taken away by the fully pulled left trigger
+    const TRIGGER_SLOW_DOWN: f32 = 0.5;
+    const MIN_SPEED: f32 = 100.0;
+    const MAX_SPEED: f32 = 600.0;
+    // Speed change for each notch of the scroll wheel or shoulder press
+    const SPEED_STEP: f32 = 50.0;
+    // Same as the window created by Game
+    const WINDOW_WIDTH: f32 = 1024.0;
+    const WINDOW_HEIGHT: f32 = 768.0;
+
     pub fn new(
         texture_manager: Rc<RefCell<TextureManager>>,
         entity_manager: Rc<RefCell<EntityManager>>,
+        player_index: PlayerIndex,
     ) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
@@ -48,7 +74,12 @@ impl Ship {
             components: vec![],
             texture_manager: texture_manager.clone(),
             entity_manager: entity_manager.clone(),
+            input_component: None,
+            player_index,
             laser_cooldown: 0.0,
+            velocity_dir: Vector2::ZERO,
+            speed: Ship::DEFAULT_SPEED,
+            last_mouse_position: Vector2::ZERO,
         };
 
         let result = Rc::new(RefCell::new(this));
@@ -58,45 +89,109 @@ impl Ship {
         sprite_component.borrow_mut().set_texture(texture);
 
         let input_component = InputComponent::new(result.clone());
-        let mut borrowed_input = input_component.borrow_mut();
-        borrowed_input.set_forward_key(Scancode::W);
-        borrowed_input.set_back_key(Scancode::S);
-        borrowed_input.set_clockwise_key(Scancode::A);
-        borrowed_input.set_counter_clockwise_key(Scancode::D);
-        borrowed_input.set_max_forward_speed(300.0);
-        borrowed_input.set_max_angular_speed(f32::consts::TAU);
+        {
+            let mut borrowed_input = input_component.borrow_mut();
+            borrowed_input.set_forward_key(Scancode::W);
+            borrowed_input.set_back_key(Scancode::S);
+            borrowed_input.set_clockwise_key(Scancode::A);
+            borrowed_input.set_counter_clockwise_key(Scancode::D);
+            borrowed_input.set_max_forward_speed(Ship::DEFAULT_SPEED);
+            borrowed_input.set_max_angular_speed(f32::consts::TAU);
+        }
+        result.borrow_mut().input_component = Some(input_component);
 
         entity_manager.borrow_mut().add_actor(result.clone());
 
         result
     }
+
+    fn fire_laser(&mut self) {
+        let laser = Laser::new(self.texture_manager.clone(), self.entity_manager.clone());
+        let mut borrowed_laser = laser.borrow_mut();
+        borrowed_laser.set_position(self.position.clone());
+        borrowed_laser.set_rotation(self.rotation);
+
+        self.laser_cooldown = Ship::LASER_COOLDOWN;
+    }
+
+    fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.clamp(Ship::MIN_SPEED, Ship::MAX_SPEED);
+        self.input_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_max_forward_speed(self.speed);
+    }
+
+    /// Face the direction, which is left as is if it's zero
+    fn face(&mut self, direction: &Vector2) {
+        if !math::basic::near_zero(direction.length(), 0.001) {
+            self.set_rotation(direction.y.atan2(direction.x));
+        }
+    }
 }
 
 impl Actor for Ship {
     fn update_actor(&mut self, delta_time: f32) {
         self.laser_cooldown -= delta_time;
+
+        if self.velocity_dir.length_sq() > 0.0 {
+            let position =
+                self.position.clone() + self.velocity_dir.clone() * self.speed * delta_time;
+            self.set_position(position);
+        }
     }
 
     fn actor_input(&mut self, state: &InputState) {
-        if state.keyboard.get_key_value(Scancode::Space) && self.laser_cooldown <= 0.0 {
-            let laser = Laser::new(self.texture_manager.clone(), self.entity_manager.clone());
-            let mut borrowed_laser = laser.borrow_mut();
-            borrowed_laser.set_position(self.position.clone());
-            borrowed_laser.set_rotation(self.rotation);
-
-            self.laser_cooldown = 0.25;
-            return;
+        // Scroll wheel changes the speed of both the keys and the stick,
+        // right click puts it back
+        let scroll = state.mouse.get_scroll_wheel().y;
+        if scroll != 0.0 {
+            self.set_speed(self.speed + scroll * Ship::SPEED_STEP);
+        }
+        if state.mouse.get_button_state(MouseButton::Right) == ButtonState::Pressed {
+            self.set_speed(Ship::DEFAULT_SPEED);
         }
 
-        if state.controller.get_right_trigger() > 0.25 && self.laser_cooldown <= 0.0 {
-            let laser = Laser::new(self.texture_manager.clone(), self.entity_manager.clone());
-            let mut borrowed_laser = laser.borrow_mut();
-            borrowed_laser.set_position(self.position.clone());
-            borrowed_laser.set_rotation(self.rotation);
+        if state.controller.get_is_connected() {
+            let controller = &state.controller;
+            // Shoulders change the speed like the scroll wheel
+            if controller.get_button_state(Button::RightShoulder) == ButtonState::Pressed {
+                self.set_speed(self.speed + Ship::SPEED_STEP);
+            }
+            if controller.get_button_state(Button::LeftShoulder) == ButtonState::Pressed {
+                self.set_speed(self.speed - Ship::SPEED_STEP);
+            }
+
+            // Left stick moves, slower with the left trigger, right stick aims
+            let slow_down = 1.0 - controller.get_left_trigger() * Ship::TRIGGER_SLOW_DOWN;
+            self.velocity_dir = controller.get_left_stick().clone() * slow_down;
+            let right_stick = controller.get_right_stick().clone();
+            self.face(&right_stick);
+        }
 
-            self.laser_cooldown = 0.25;
-            return;
+        // Aim at the mouse while it moves or the left button is held
+        let mouse_position = state.mouse.get_position().clone();
+        let is_mouse_held = state.mouse.get_button_value(MouseButton::Left);
+        if is_mouse_held || mouse_position != self.last_mouse_position {
+            let target = state
+                .mouse
+                .get_world_position(Ship::WINDOW_WIDTH, Ship::WINDOW_HEIGHT);
+            let direction = target - self.position.clone();
+            self.face(&direction);
         }
+        self.last_mouse_position = mouse_position;
+
+        let wants_fire = state.keyboard.get_key_value(Scancode::Space)
+            || state.controller.get_right_trigger() > 0.25
+            || is_mouse_held;
+        if wants_fire && self.laser_cooldown <= 0.0 {
+            self.fire_laser();
+        }
+    }
+
+    fn get_player_index(&self) -> Option<PlayerIndex> {
+        Some(self.player_index)
     }
 
     actor::impl_getters_setters! {}
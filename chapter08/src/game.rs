@@ -5,6 +5,7 @@ use std::{cell::RefCell, rc::Rc};
 use anyhow::{anyhow, Result};
 use gl::{BLEND, ONE_MINUS_SRC_ALPHA, SRC_ALPHA};
 use sdl2::{
+    controller::Button,
     event::Event,
     keyboard::Scancode,
     video::{GLContext, Window},
@@ -12,6 +13,7 @@ use sdl2::{
 };
 
 use crate::system::{
+    crash_report::{self, log, ActorCounts},
     entity_manager::EntityManager,
     input_system::{ButtonState, InputSystem},
     texture_manager::TextureManager,
@@ -61,14 +63,20 @@ impl Game {
 
         let controller_subsystem = sdl.game_controller().map_err(|e| anyhow!(e))?;
         let controller = controller_subsystem.open(0).ok();
+        // A second player joins with the controller
+        let has_controller = controller.is_some();
+        let input_system = InputSystem::initialize(controller)?;
+        input_system.borrow_mut().set_co_op(has_controller);
+        let is_co_op = input_system.borrow().is_co_op();
+        if is_co_op {
+            log!("Controller found, starting co-op");
+        }
 
         let texture_manager = TextureManager::new();
         texture_manager.borrow_mut().load_shaders()?;
 
         let entity_manager = EntityManager::new();
-        EntityManager::load_data(entity_manager.clone(), texture_manager.clone());
-
-        let input_system = InputSystem::initialize(controller)?;
+        EntityManager::load_data(entity_manager.clone(), texture_manager.clone(), is_co_op);
 
         let game = Game {
             context,
@@ -91,7 +99,17 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
+        }
+    }
+
+    /// Bookkeeping once the frame is over
+    fn end_frame(&self) {
+        let mut actor_counts = ActorCounts::new();
+        for actor in self.entity_manager.borrow().get_actors() {
+            actor_counts.add(actor.borrow().get_state());
         }
+        crash_report::end_frame(actor_counts);
     }
 
     /// Herlper functions for the game loop
@@ -114,14 +132,22 @@ impl Game {
         let borrowed_input_system = self.input_system.borrow();
         let state = borrowed_input_system.get_state();
 
-        if state.keyboard.get_key_state(Scancode::Escape) == ButtonState::Released {
+        if state.keyboard.get_key_state(Scancode::Escape) == ButtonState::Released
+            || state.controller.get_button_state(Button::Back) == ButtonState::Released
+        {
             self.is_running = false;
         }
 
         self.entity_manager.borrow_mut().set_updating_actors(true);
         let actors = self.entity_manager.borrow().get_actors().clone();
         for actor in actors {
-            actor.borrow_mut().process_input(&state);
+            // Players only see their own devices
+            let player_index = actor.borrow().get_player_index();
+            let actor_state = match player_index {
+                Some(player_index) => borrowed_input_system.get_player_state(player_index),
+                None => state,
+            };
+            actor.borrow_mut().process_input(actor_state);
         }
         self.entity_manager.borrow_mut().set_updating_actors(false);
     }
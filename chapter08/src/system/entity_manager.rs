@@ -7,15 +7,15 @@ use crate::{
         asteroid::Asteroid,
         ship::Ship,
     },
-    math::random::Random,
-    system::texture_manager::TextureManager,
+    math::{random::Random, vector2::Vector2},
+    system::{input_system::PlayerIndex, texture_manager::TextureManager},
 };
 
 pub struct EntityManager {
     actors: Vec<Rc<RefCell<dyn Actor>>>,
     pending_actors: Vec<Rc<RefCell<dyn Actor>>>,
     updating_actors: bool,
-    ship: Option<Rc<RefCell<Ship>>>,
+    ships: Vec<Rc<RefCell<Ship>>>,
     asteroids: Vec<Rc<RefCell<Asteroid>>>,
     random: Random,
 }
@@ -26,7 +26,7 @@ impl EntityManager {
             actors: vec![],
             pending_actors: vec![],
             updating_actors: false,
-            ship: None,
+            ships: vec![],
             asteroids: vec![],
             random: Random::new(),
         };
@@ -70,13 +70,25 @@ impl EntityManager {
     pub fn load_data(
         this: Rc<RefCell<EntityManager>>,
         texture_manager: Rc<RefCell<TextureManager>>,
+        is_co_op: bool,
     ) {
-        let ship = Ship::new(texture_manager.clone(), this.clone());
-        {
-            let mut borrowed_ship = ship.borrow_mut();
-            borrowed_ship.set_rotation(f32::consts::TAU);
+        let players = if is_co_op {
+            vec![
+                (PlayerIndex::One, Vector2::new(-150.0, 0.0)),
+                (PlayerIndex::Two, Vector2::new(150.0, 0.0)),
+            ]
+        } else {
+            vec![(PlayerIndex::One, Vector2::ZERO)]
+        };
+        for (player_index, position) in players {
+            let ship = Ship::new(texture_manager.clone(), this.clone(), player_index);
+            {
+                let mut borrowed_ship = ship.borrow_mut();
+                borrowed_ship.set_position(position);
+                borrowed_ship.set_rotation(f32::consts::TAU);
+            }
+            this.borrow_mut().ships.push(ship);
         }
-        this.borrow_mut().ship = Some(ship);
 
         // Create asteroids
         const NUM_ASTEROIDS: i32 = 20;
@@ -22,6 +22,7 @@ pub enum ButtonState {
 }
 
 /// Helper for keyboard input
+#[derive(Clone)]
 pub struct KeyboardState {
     current_state: Vec<bool>,
     previous_state: [bool; Scancode::Num as usize],
@@ -77,6 +78,7 @@ impl KeyboardState {
 }
 
 /// Helper for mouse input
+#[derive(Clone)]
 pub struct MouseState {
     mouse_position: Vector2,
     current_buttons: Vec<MouseButton>,
@@ -119,6 +121,11 @@ impl MouseState {
         &self.mouse_position
     }
 
+    /// Position in world coordinates, with the origin at the center of the window and y up
+    pub fn get_world_position(&self, window_width: f32, window_height: f32) -> Vector2 {
+        window_to_world(&self.mouse_position, window_width, window_height)
+    }
+
     pub fn get_scroll_wheel(&self) -> &Vector2 {
         &self.scroll_wheel
     }
@@ -150,7 +157,13 @@ impl MouseState {
     }
 }
 
+/// Window position (origin at the top left, y down) to world coordinates
+pub fn window_to_world(point: &Vector2, window_width: f32, window_height: f32) -> Vector2 {
+    Vector2::new(point.x - window_width / 2.0, window_height / 2.0 - point.y)
+}
+
 /// Helper for controller input
+#[derive(Clone)]
 pub struct ControllerState {
     current_buttons: [bool; SDL_GameControllerButton::SDL_CONTROLLER_BUTTON_MAX as usize],
     previous_buttons: [bool; SDL_GameControllerButton::SDL_CONTROLLER_BUTTON_MAX as usize],
@@ -186,13 +199,13 @@ impl ControllerState {
         self.right_trigger =
             InputSystem::filter_1d(game_controller.axis(Axis::TriggerRight) as i32);
 
-        // Sticks
+        // Sticks, with y negated so that up is positive like in the world
         let x = game_controller.axis(Axis::LeftX) as i32;
-        let y = game_controller.axis(Axis::LeftY) as i32;
+        let y = -(game_controller.axis(Axis::LeftY) as i32);
         self.left_stick = InputSystem::filter_2d(x, y);
 
         let x = game_controller.axis(Axis::RightX) as i32;
-        let y = game_controller.axis(Axis::RightY) as i32;
+        let y = -(game_controller.axis(Axis::RightY) as i32);
         self.right_stick = InputSystem::filter_2d(x, y);
     }
 
@@ -220,6 +233,14 @@ impl ControllerState {
         self.is_connected
     }
 
+    pub fn get_left_stick(&self) -> &Vector2 {
+        &self.left_stick
+    }
+
+    pub fn get_right_stick(&self) -> &Vector2 {
+        &self.right_stick
+    }
+
     pub fn get_left_trigger(&self) -> f32 {
         self.left_trigger
     }
@@ -234,15 +255,51 @@ impl ControllerState {
 }
 
 /// Wrapper that contains current state of input
+#[derive(Clone)]
 pub struct InputState {
     pub keyboard: KeyboardState,
     pub mouse: MouseState,
     pub controller: ControllerState,
 }
 
+impl InputState {
+    /// No device, nothing is ever pressed
+    fn empty() -> Self {
+        Self {
+            keyboard: KeyboardState::new(),
+            mouse: MouseState::new(),
+            controller: ControllerState::new(&None),
+        }
+    }
+}
+
+/// Players sharing the machine in co-op
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerIndex {
+    // Keyboard and mouse, and the controller when playing alone
+    One,
+    // The controller
+    Two,
+}
+
+/// Split the devices between the players
+fn route_player_states(state: &InputState, is_co_op: bool, player_states: &mut [InputState; 2]) {
+    let [player_one, player_two] = player_states;
+    player_one.keyboard.clone_from(&state.keyboard);
+    player_one.mouse.clone_from(&state.mouse);
+    if is_co_op {
+        player_two.controller.clone_from(&state.controller);
+    } else {
+        player_one.controller.clone_from(&state.controller);
+    }
+}
+
 pub struct InputSystem {
     state: InputState,
     game_controller: Option<GameController>,
+    is_co_op: bool,
+    // Devices of each player, rebuilt from state every frame
+    player_states: [InputState; 2],
 }
 
 impl InputSystem {
@@ -262,6 +319,8 @@ impl InputSystem {
         let this = Self {
             state,
             game_controller,
+            is_co_op: false,
+            player_states: [InputState::empty(), InputState::empty()],
         };
 
         Ok(Rc::new(RefCell::new(this)))
@@ -285,6 +344,32 @@ impl InputSystem {
         if let Some(game_controller) = &self.game_controller {
             self.state.controller.update(game_controller);
         }
+
+        self.route_player_states();
+    }
+
+    fn route_player_states(&mut self) {
+        route_player_states(&self.state, self.is_co_op, &mut self.player_states);
+    }
+
+    /// Give the controller to the second player, or back to the first
+    pub fn set_co_op(&mut self, is_co_op: bool) {
+        self.is_co_op = is_co_op;
+        // Nothing left from the device the player lost
+        self.player_states = [InputState::empty(), InputState::empty()];
+        self.route_player_states();
+    }
+
+    pub fn is_co_op(&self) -> bool {
+        self.is_co_op
+    }
+
+    /// Input of the devices the player controls
+    pub fn get_player_state(&self, player: PlayerIndex) -> &InputState {
+        match player {
+            PlayerIndex::One => &self.player_states[0],
+            PlayerIndex::Two => &self.player_states[1],
+        }
     }
 
     pub fn process_event(&mut self, event: &Event) {
@@ -346,3 +431,52 @@ impl InputSystem {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sdl2::keyboard::Scancode;
+
+    use crate::math::vector2::Vector2;
+
+    use super::{route_player_states, window_to_world, InputState};
+
+    #[test]
+    fn test_window_to_world() {
+        assert_eq!(
+            Vector2::ZERO,
+            window_to_world(&Vector2::new(512.0, 384.0), 1024.0, 768.0)
+        );
+        assert_eq!(
+            Vector2::new(-512.0, 384.0),
+            window_to_world(&Vector2::ZERO, 1024.0, 768.0)
+        );
+        assert_eq!(
+            Vector2::new(512.0, -384.0),
+            window_to_world(&Vector2::new(1024.0, 768.0), 1024.0, 768.0)
+        );
+    }
+
+    #[test]
+    fn test_player_states() {
+        let mut state = InputState::empty();
+        state.keyboard.current_state[Scancode::W as usize] = true;
+        state.controller.is_connected = true;
+        state.controller.right_trigger = 1.0;
+
+        // Alone, the first player has every device
+        let mut player_states = [InputState::empty(), InputState::empty()];
+        route_player_states(&state, false, &mut player_states);
+        let [player_one, player_two] = &player_states;
+        assert!(player_one.keyboard.get_key_value(Scancode::W));
+        assert_eq!(1.0, player_one.controller.get_right_trigger());
+        assert!(!player_two.controller.get_is_connected());
+
+        let mut player_states = [InputState::empty(), InputState::empty()];
+        route_player_states(&state, true, &mut player_states);
+        let [player_one, player_two] = &player_states;
+        assert!(player_one.keyboard.get_key_value(Scancode::W));
+        assert!(!player_one.controller.get_is_connected());
+        assert!(!player_two.keyboard.get_key_value(Scancode::W));
+        assert_eq!(1.0, player_two.controller.get_right_trigger());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod crash_report;
 pub mod entity_manager;
 pub mod input_system;
 pub mod texture_manager;
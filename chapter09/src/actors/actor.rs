@@ -7,9 +7,14 @@ use std::{
 use crate::{
     components::component::{Component, State as ComponentState},
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{asset_manager::AssetManager, entity_manager::EntityManager},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        input_system::InputState,
+    },
 };
 
+use super::actor_builder::ActorBuilder;
+
 static ID: AtomicU32 = AtomicU32::new(0);
 
 pub fn generate_id() -> u32 {
@@ -67,18 +72,18 @@ pub trait Actor {
     fn update_actor(&mut self, delta_time: f32);
 
     // ProcessInput function called from Game (not overridable)
-    fn process_input(&mut self, key_state: &KeyboardState, mouse_state: &RelativeMouseState) {
+    fn process_input(&mut self, state: &InputState) {
         if *self.get_state() != State::Active {
             return;
         }
         for component in self.get_cocmponents() {
-            component.borrow_mut().process_input(&key_state);
+            component.borrow_mut().process_input(state);
         }
-        self.actor_input(&key_state, &mouse_state);
+        self.actor_input(state);
     }
 
     // Any actor-specific input code (overridable)
-    fn actor_input(&mut self, _key_state: &KeyboardState, _mouse_state: &RelativeMouseState) {}
+    fn actor_input(&mut self, _state: &InputState) {}
 
     fn compute_world_transform(&mut self) {
         if !self.get_recompute_world_transform() {
@@ -221,7 +226,7 @@ macro_rules! impl_getters_setters {
         fn get_asset_manager(&self) -> &Rc<RefCell<AssetManager>> {
             cfg_if::cfg_if! {
                 if #[cfg(not(test))] {
-                    &self.asset_manager
+                    &self.context.asset_manager
                 } else {
                     panic!();
                 }
@@ -231,7 +236,7 @@ macro_rules! impl_getters_setters {
         fn get_entity_manager(&self) -> &Rc<RefCell<EntityManager>> {
             cfg_if::cfg_if! {
                 if #[cfg(not(test))] {
-                    &self.entity_manager
+                    &self.context.entity_manager
                 } else {
                     panic!();
                 }
@@ -279,7 +284,6 @@ macro_rules! impl_drop {
 }
 
 pub(crate) use impl_drop;
-use sdl2::{keyboard::KeyboardState, mouse::RelativeMouseState};
 
 pub struct DefaultActor {
     id: u32,
@@ -290,15 +294,11 @@ pub struct DefaultActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
+    context: EngineContext,
 }
 
 impl DefaultActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -308,15 +308,10 @@ impl DefaultActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager,
-            entity_manager: entity_manager.clone(),
+            context: context.clone(),
         };
 
-        let result = Rc::new(RefCell::new(this));
-
-        entity_manager.borrow_mut().add_actor(result.clone());
-
-        result
+        ActorBuilder::new(context, this).build()
     }
 }
 
@@ -0,0 +1,56 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{components::component::Component, system::engine_context::EngineContext};
+
+use super::actor::Actor;
+
+/// Component that can be added with ActorBuilder::with
+pub trait BuildComponent: Component + Sized + 'static {
+    /// What the component needs besides the owner and the engine systems
+    type Params;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>>;
+}
+
+/// Adds components to a new actor, then adds the actor to the EntityManager
+pub struct ActorBuilder<'a, T: Actor + 'static> {
+    actor: Rc<RefCell<T>>,
+    context: &'a EngineContext,
+}
+
+impl<'a, T: Actor + 'static> ActorBuilder<'a, T> {
+    pub fn new(context: &'a EngineContext, actor: T) -> Self {
+        Self {
+            actor: Rc::new(RefCell::new(actor)),
+            context,
+        }
+    }
+
+    /// Add a component the actor doesn't need a handle to
+    pub fn with<C: BuildComponent>(self, params: C::Params) -> Self {
+        self.with_handle::<C>(params, |_, _| {})
+    }
+
+    /// Add a component, then configure it or keep its handle in the actor
+    pub fn with_handle<C: BuildComponent>(
+        self,
+        params: C::Params,
+        handle: impl FnOnce(&mut T, Rc<RefCell<C>>),
+    ) -> Self {
+        let component = C::build(self.actor.clone(), self.context, params);
+        handle(&mut self.actor.borrow_mut(), component);
+        self
+    }
+
+    pub fn build(self) -> Rc<RefCell<T>> {
+        self.context
+            .entity_manager
+            .borrow_mut()
+            .add_actor(self.actor.clone());
+        self.actor
+    }
+}
@@ -1,10 +1,7 @@
 use core::f32;
 use std::{cell::RefCell, rc::Rc};
 
-use sdl2::{
-    keyboard::{KeyboardState, Scancode},
-    mouse::RelativeMouseState,
-};
+use sdl2::keyboard::Scancode;
 
 use crate::{
     components::{
@@ -14,12 +11,15 @@ use crate::{
     },
     math::{self, matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        renderer::Renderer, sound_event::SoundEvent,
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        input_system::InputState, sound_event::SoundEvent,
     },
 };
 
-use super::actor::{self, generate_id, Actor, State};
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
 
 pub struct CameraActor {
     id: u32,
@@ -30,10 +30,7 @@ pub struct CameraActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
-    renderer: Rc<RefCell<Renderer>>,
-    audio_system: Rc<RefCell<AudioSystem>>,
+    context: EngineContext,
     move_component: Option<Rc<RefCell<DefaultMoveComponent>>>,
     audio_component: Option<Rc<RefCell<AudioComponent>>>,
     foot_step: Option<Rc<RefCell<SoundEvent>>>,
@@ -41,12 +38,7 @@ pub struct CameraActor {
 }
 
 impl CameraActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        renderer: Rc<RefCell<Renderer>>,
-        audio_system: Rc<RefCell<AudioSystem>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -56,29 +48,28 @@ impl CameraActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager,
-            entity_manager: entity_manager.clone(),
-            renderer,
-            audio_system: audio_system.clone(),
+            context: context.clone(),
             move_component: None,
             audio_component: None,
             foot_step: None,
             last_foot_step: 0.0,
         };
 
-        let result = Rc::new(RefCell::new(this));
-
-        let move_component = DefaultMoveComponent::new(result.clone());
-        result.borrow_mut().move_component = Some(move_component);
-
-        let audio_component = AudioComponent::new(result.clone(), audio_system);
+        let result = ActorBuilder::new(context, this)
+            .with_handle::<DefaultMoveComponent>((), |camera, move_component| {
+                camera.move_component = Some(move_component);
+            })
+            .with_handle::<AudioComponent>((), |camera, audio_component| {
+                camera.audio_component = Some(audio_component);
+            })
+            .build();
+
+        // Playing a 3D event reads the owner, so not while the builder holds it
+        let audio_component = result.borrow().audio_component.clone().unwrap();
         let sound_event = audio_component.borrow_mut().play_event("event:/Footstep");
         sound_event.borrow_mut().set_paused(true);
-        result.borrow_mut().audio_component = Some(audio_component);
         result.borrow_mut().foot_step = Some(sound_event);
 
-        entity_manager.borrow_mut().add_actor(result.clone());
-
         result
     }
 
@@ -117,24 +108,24 @@ impl Actor for CameraActor {
 
         let view = Matrix4::create_look_at(&camera_position, &target, &up);
 
-        self.audio_system.borrow_mut().set_listener(&view);
-        self.renderer.borrow_mut().set_view_matrix(view);
+        self.context.audio_system.borrow_mut().set_listener(&view);
+        self.context.renderer.borrow_mut().set_view_matrix(view);
     }
 
-    fn actor_input(&mut self, key_state: &KeyboardState, _mouse_state: &RelativeMouseState) {
+    fn actor_input(&mut self, state: &InputState) {
         let mut forward_speed = 0.0;
         let mut angular_speed = 0.0;
 
-        if key_state.is_scancode_pressed(Scancode::W) {
+        if state.keyboard.get_key_value(Scancode::W) {
             forward_speed += 300.0;
         }
-        if key_state.is_scancode_pressed(Scancode::S) {
+        if state.keyboard.get_key_value(Scancode::S) {
             forward_speed -= 300.0;
         }
-        if key_state.is_scancode_pressed(Scancode::A) {
+        if state.keyboard.get_key_value(Scancode::A) {
             angular_speed -= f32::consts::TAU;
         }
-        if key_state.is_scancode_pressed(Scancode::D) {
+        if state.keyboard.get_key_value(Scancode::D) {
             angular_speed += f32::consts::TAU;
         }
 
@@ -1,10 +1,7 @@
 use core::f32;
 use std::{cell::RefCell, rc::Rc};
 
-use sdl2::{
-    keyboard::{KeyboardState, Scancode},
-    mouse::RelativeMouseState,
-};
+use sdl2::keyboard::Scancode;
 
 use crate::{
     components::{
@@ -15,12 +12,15 @@ use crate::{
     },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        renderer::Renderer,
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        input_system::InputState,
     },
 };
 
-use super::actor::{self, generate_id, Actor, State};
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
 
 pub struct FollowActor {
     id: u32,
@@ -31,21 +31,14 @@ pub struct FollowActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
-    audio_system: Rc<RefCell<AudioSystem>>,
+    context: EngineContext,
     move_component: Option<Rc<RefCell<DefaultMoveComponent>>>,
     camera_component: Option<Rc<RefCell<FollowCamera>>>,
     mesh_component: Option<Rc<RefCell<MeshComponent>>>,
 }
 
 impl FollowActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        audio_system: Rc<RefCell<AudioSystem>>,
-        renderer: Rc<RefCell<Renderer>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let mut this = Self {
             id: generate_id(),
             state: State::Active,
@@ -55,9 +48,7 @@ impl FollowActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
-            audio_system: audio_system.clone(),
+            context: context.clone(),
             move_component: None,
             camera_component: None,
             mesh_component: None,
@@ -65,22 +56,25 @@ impl FollowActor {
 
         this.set_position(Vector3::new(0.0, 0.0, -100.0));
 
-        let result = Rc::new(RefCell::new(this));
-
-        let move_component = DefaultMoveComponent::new(result.clone());
-        result.borrow_mut().move_component = Some(move_component);
-
-        let follow_camera = FollowCamera::new(result.clone(), renderer, audio_system);
+        let mesh = context
+            .asset_manager
+            .borrow_mut()
+            .get_mesh("RacingCar.gpmesh");
+        let result = ActorBuilder::new(context, this)
+            .with_handle::<DefaultMoveComponent>((), |follow, move_component| {
+                follow.move_component = Some(move_component);
+            })
+            .with_handle::<FollowCamera>((), |follow, follow_camera| {
+                follow.camera_component = Some(follow_camera);
+            })
+            .with_handle::<MeshComponent>(mesh, |follow, mesh_component| {
+                follow.mesh_component = Some(mesh_component);
+            })
+            .build();
+
+        // Reads the owner, so not while the builder holds it
+        let follow_camera = result.borrow().camera_component.clone().unwrap();
         follow_camera.borrow_mut().snap_to_ideal();
-        result.borrow_mut().camera_component = Some(follow_camera);
-
-        let mesh_component = MeshComponent::new(result.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("RacingCar.gpmesh");
-        mesh_component.borrow_mut().set_mesh(mesh);
-
-        result.borrow_mut().mesh_component = Some(mesh_component);
-
-        entity_manager.borrow_mut().add_actor(result.clone());
 
         result
     }
@@ -94,23 +88,29 @@ impl FollowActor {
 impl Actor for FollowActor {
     fn update_actor(&mut self, _delta_time: f32) {}
 
-    fn actor_input(&mut self, key_state: &KeyboardState, _mouse_state: &RelativeMouseState) {
+    fn actor_input(&mut self, state: &InputState) {
         let mut forward_speed = 0.0;
         let mut angular_speed = 0.0;
 
-        if key_state.is_scancode_pressed(Scancode::W) {
+        if state.keyboard.get_key_value(Scancode::W) {
             forward_speed += 400.0;
         }
-        if key_state.is_scancode_pressed(Scancode::S) {
+        if state.keyboard.get_key_value(Scancode::S) {
             forward_speed -= 400.0;
         }
-        if key_state.is_scancode_pressed(Scancode::A) {
+        if state.keyboard.get_key_value(Scancode::A) {
             angular_speed -= f32::consts::PI;
         }
-        if key_state.is_scancode_pressed(Scancode::D) {
+        if state.keyboard.get_key_value(Scancode::D) {
             angular_speed += f32::consts::PI;
         }
 
+        // Left stick drives (stick up is negative y)
+        let left_stick = state.controller.get_left_stick();
+        forward_speed = (forward_speed - left_stick.y * 400.0).clamp(-400.0, 400.0);
+        angular_speed = (angular_speed + left_stick.x * f32::consts::PI)
+            .clamp(-f32::consts::PI, f32::consts::PI);
+
         let move_component = self.move_component.clone().unwrap();
         move_component.borrow_mut().set_forward_speed(forward_speed);
         move_component.borrow_mut().set_angular_speed(angular_speed);
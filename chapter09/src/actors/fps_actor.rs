@@ -1,10 +1,7 @@
 use core::f32;
 use std::{cell::RefCell, rc::Rc};
 
-use sdl2::{
-    keyboard::{KeyboardState, Scancode},
-    mouse::RelativeMouseState,
-};
+use sdl2::keyboard::Scancode;
 
 use crate::{
     components::{
@@ -16,12 +13,15 @@ use crate::{
     },
     math::{self, matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        renderer::Renderer, sound_event::SoundEvent,
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        input_system::InputState, sound_event::SoundEvent,
     },
 };
 
-use super::actor::{self, generate_id, Actor, DefaultActor, State};
+use super::{
+    actor::{self, generate_id, Actor, DefaultActor, State},
+    actor_builder::{ActorBuilder, BuildComponent},
+};
 
 pub struct FPSActor {
     id: u32,
@@ -32,9 +32,7 @@ pub struct FPSActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
-    audio_system: Rc<RefCell<AudioSystem>>,
+    context: EngineContext,
     move_component: Option<Rc<RefCell<DefaultMoveComponent>>>,
     camera_component: Option<Rc<RefCell<FPSCamera>>>,
     mesh_component: Option<Rc<RefCell<MeshComponent>>>,
@@ -42,15 +40,13 @@ pub struct FPSActor {
     fps_model: Option<Rc<RefCell<DefaultActor>>>,
     foot_step: Option<Rc<RefCell<SoundEvent>>>,
     last_foot_step: f32,
+    // Multiplier of the right stick aim speed
+    stick_sensitivity: f32,
+    invert_y: bool,
 }
 
 impl FPSActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        audio_system: Rc<RefCell<AudioSystem>>,
-        renderer: Rc<RefCell<Renderer>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -60,9 +56,7 @@ impl FPSActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
-            audio_system: audio_system.clone(),
+            context: context.clone(),
             move_component: None,
             camera_component: None,
             mesh_component: None,
@@ -70,37 +64,48 @@ impl FPSActor {
             fps_model: None,
             foot_step: None,
             last_foot_step: 0.0,
+            stick_sensitivity: 1.0,
+            invert_y: false,
         };
 
-        let result = Rc::new(RefCell::new(this));
-
-        let move_component = DefaultMoveComponent::new(result.clone());
-        result.borrow_mut().move_component = Some(move_component);
-
-        let audio_component = AudioComponent::new(result.clone(), audio_system.clone());
+        let result = ActorBuilder::new(context, this)
+            .with_handle::<DefaultMoveComponent>((), |fps, move_component| {
+                fps.move_component = Some(move_component);
+            })
+            .with_handle::<AudioComponent>((), |fps, audio_component| {
+                fps.audio_component = Some(audio_component);
+            })
+            .with_handle::<FPSCamera>((), |fps, fps_camera| {
+                fps.camera_component = Some(fps_camera);
+            })
+            .build();
+
+        // Playing a 3D event reads the owner, so not while the builder holds it
+        let audio_component = result.borrow().audio_component.clone().unwrap();
         let sound_event = audio_component.borrow_mut().play_event("event:/Footstep");
         sound_event.borrow_mut().set_paused(true);
-        result.borrow_mut().audio_component = Some(audio_component);
         result.borrow_mut().foot_step = Some(sound_event);
 
-        let fps_camera = FPSCamera::new(result.clone(), renderer, audio_system);
-        result.borrow_mut().camera_component = Some(fps_camera);
-
-        let fps_model = DefaultActor::new(asset_manager.clone(), entity_manager.clone());
+        let fps_model = DefaultActor::new(context);
         fps_model.borrow_mut().set_scale(0.75);
 
-        let mesh_component = MeshComponent::new(fps_model.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Rifle.gpmesh");
-        mesh_component.borrow_mut().set_mesh(mesh);
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Rifle.gpmesh");
+        let mesh_component = MeshComponent::build(fps_model.clone(), context, mesh);
 
         result.borrow_mut().fps_model = Some(fps_model);
         result.borrow_mut().mesh_component = Some(mesh_component);
 
-        entity_manager.borrow_mut().add_actor(result.clone());
-
         result
     }
 
+    pub fn set_stick_sensitivity(&mut self, stick_sensitivity: f32) {
+        self.stick_sensitivity = stick_sensitivity;
+    }
+
+    pub fn toggle_invert_y(&mut self) {
+        self.invert_y = !self.invert_y;
+    }
+
     pub fn set_foot_step_surface(&mut self, value: f32) {
         // Pause here because the way I setup the parameter in FMOD
         // changing it will play a footstep
@@ -156,31 +161,36 @@ impl Actor for FPSActor {
         fps_model.borrow_mut().set_rotation(q);
     }
 
-    fn actor_input(&mut self, key_state: &KeyboardState, mouse_state: &RelativeMouseState) {
+    fn actor_input(&mut self, state: &InputState) {
         let mut forward_speed = 0.0;
         let mut strafe_speed = 0.0;
 
-        if key_state.is_scancode_pressed(Scancode::W) {
+        if state.keyboard.get_key_value(Scancode::W) {
             forward_speed += 400.0;
         }
-        if key_state.is_scancode_pressed(Scancode::S) {
+        if state.keyboard.get_key_value(Scancode::S) {
             forward_speed -= 400.0;
         }
-        if key_state.is_scancode_pressed(Scancode::A) {
+        if state.keyboard.get_key_value(Scancode::A) {
             strafe_speed -= 400.0;
         }
-        if key_state.is_scancode_pressed(Scancode::D) {
+        if state.keyboard.get_key_value(Scancode::D) {
             strafe_speed += 400.0;
         }
 
+        // Left stick moves (stick up is negative y)
+        let left_stick = state.controller.get_left_stick();
+        forward_speed = (forward_speed - left_stick.y * 400.0).clamp(-400.0, 400.0);
+        strafe_speed = (strafe_speed + left_stick.x * 400.0).clamp(-400.0, 400.0);
+
         let move_component = self.move_component.clone().unwrap();
         move_component.borrow_mut().set_forward_speed(forward_speed);
         move_component.borrow_mut().set_strafe_speed(strafe_speed);
 
         // Mouse movement
         // Get relative movement from SDL
-        let x = mouse_state.x();
-        let y = mouse_state.y();
+        let x = state.mouse.get_position().x;
+        let y = state.mouse.get_position().y;
 
         // Assume mouse movement is usually between -500 and +500
         let max_mouse_speed = 500.0;
@@ -189,22 +199,31 @@ impl Actor for FPSActor {
         let max_angular_speed = f32::consts::PI * 8.0;
 
         let mut angular_speed = 0.0;
-        if x != 0 {
+        if x != 0.0 {
             // Convert to ~[-1.0, 1.0]
-            angular_speed = x as f32 / max_mouse_speed;
+            angular_speed = x / max_mouse_speed;
             // Multiply by rotation/sec
             angular_speed *= max_angular_speed;
         }
+
+        // Right stick aims, already in [-1.0, 1.0]
+        let right_stick = state.controller.get_right_stick();
+        let max_stick_speed = f32::consts::PI * 2.0 * self.stick_sensitivity;
+        angular_speed += right_stick.x * max_stick_speed;
         move_component.borrow_mut().set_angular_speed(angular_speed);
 
         // Compute pitch
         let max_pitch_speed = f32::consts::PI * 8.0;
         let mut pitch_speed = 0.0;
-        if y != 0 {
+        if y != 0.0 {
             // Convert to [-1.0, 1.0]
-            pitch_speed = y as f32 / max_mouse_speed;
+            pitch_speed = y / max_mouse_speed;
             pitch_speed *= max_pitch_speed;
         }
+        pitch_speed += right_stick.y * max_stick_speed;
+        if self.invert_y {
+            pitch_speed = -pitch_speed;
+        }
 
         let camera_component = self.camera_component.clone().unwrap();
         camera_component.borrow_mut().set_pitch_speed(pitch_speed);
@@ -1,7 +1,9 @@
 pub mod actor;
+pub mod actor_builder;
 pub mod camera_actor;
 pub mod follow_actor;
 pub mod fps_actor;
 pub mod orbit_actor;
 pub mod plane_actor;
+pub mod reverb_zone_actor;
 pub mod spline_actor;
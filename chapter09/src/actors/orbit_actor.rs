@@ -1,10 +1,7 @@
 use core::f32;
 use std::{cell::RefCell, rc::Rc};
 
-use sdl2::{
-    keyboard::KeyboardState,
-    mouse::{MouseButton, RelativeMouseState},
-};
+use sdl2::mouse::MouseButton;
 
 use crate::{
     components::{
@@ -14,12 +11,15 @@ use crate::{
     },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        renderer::Renderer,
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        input_system::InputState,
     },
 };
 
-use super::actor::{self, generate_id, Actor, State};
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
 
 pub struct OrbitActor {
     id: u32,
@@ -30,20 +30,13 @@ pub struct OrbitActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
-    audio_system: Rc<RefCell<AudioSystem>>,
+    context: EngineContext,
     camera_component: Option<Rc<RefCell<OrbitCamera>>>,
     mesh_component: Option<Rc<RefCell<MeshComponent>>>,
 }
 
 impl OrbitActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        audio_system: Rc<RefCell<AudioSystem>>,
-        renderer: Rc<RefCell<Renderer>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let mut this = Self {
             id: generate_id(),
             state: State::Active,
@@ -53,28 +46,25 @@ impl OrbitActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
-            audio_system: audio_system.clone(),
+            context: context.clone(),
             camera_component: None,
             mesh_component: None,
         };
 
         this.set_position(Vector3::new(0.0, 0.0, -100.0));
 
-        let result = Rc::new(RefCell::new(this));
-
-        let orbit_camera = OrbitCamera::new(result.clone(), renderer, audio_system);
-        result.borrow_mut().camera_component = Some(orbit_camera);
-
-        let mesh_component = MeshComponent::new(result.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("RacingCar.gpmesh");
-        mesh_component.borrow_mut().set_mesh(mesh);
-        result.borrow_mut().mesh_component = Some(mesh_component);
-
-        entity_manager.borrow_mut().add_actor(result.clone());
-
-        result
+        let mesh = context
+            .asset_manager
+            .borrow_mut()
+            .get_mesh("RacingCar.gpmesh");
+        ActorBuilder::new(context, this)
+            .with_handle::<OrbitCamera>((), |orbit, orbit_camera| {
+                orbit.camera_component = Some(orbit_camera);
+            })
+            .with_handle::<MeshComponent>(mesh, |orbit, mesh_component| {
+                orbit.mesh_component = Some(mesh_component);
+            })
+            .build()
     }
 
     pub fn set_visible(&mut self, visible: bool) {
@@ -86,18 +76,18 @@ impl OrbitActor {
 impl Actor for OrbitActor {
     fn update_actor(&mut self, _delta_time: f32) {}
 
-    fn actor_input(&mut self, _key_state: &KeyboardState, mouse_state: &RelativeMouseState) {
-        let x = mouse_state.x();
-        let y = mouse_state.y();
+    fn actor_input(&mut self, state: &InputState) {
+        let x = state.mouse.get_position().x;
+        let y = state.mouse.get_position().y;
 
-        if mouse_state.is_mouse_button_pressed(MouseButton::Right) {
+        if state.mouse.get_button_value(MouseButton::Right) {
             let max_mouse_speed = 500.0;
             let max_orbit_speed = f32::consts::PI * 8.0;
 
             let mut yaw_speed = 0.0;
-            if x != 0 {
+            if x != 0.0 {
                 // Convert to ~[-1.0, 1.0]
-                yaw_speed = x as f32 / max_mouse_speed;
+                yaw_speed = x / max_mouse_speed;
                 // Multiply by rotation/sec
                 yaw_speed *= max_orbit_speed;
             }
@@ -106,9 +96,9 @@ impl Actor for OrbitActor {
             camera_component.borrow_mut().set_yaw_speed(-yaw_speed);
 
             let mut pitch_speed = 0.0;
-            if y != 0 {
+            if y != 0.0 {
                 // Convert to ~[-1.0, 1.0]
-                pitch_speed = y as f32 / max_mouse_speed;
+                pitch_speed = y / max_mouse_speed;
                 // Multiply by rotation/sec
                 pitch_speed *= max_orbit_speed;
             }
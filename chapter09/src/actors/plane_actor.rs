@@ -6,10 +6,15 @@ use crate::{
         mesh_component::MeshComponent,
     },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{asset_manager::AssetManager, entity_manager::EntityManager},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
 };
 
-use super::actor::{self, generate_id, Actor, State};
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
 
 pub struct PlaneActor {
     id: u32,
@@ -20,15 +25,11 @@ pub struct PlaneActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
+    context: EngineContext,
 }
 
 impl PlaneActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -38,19 +39,13 @@ impl PlaneActor {
             scale: 10.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
+            context: context.clone(),
         };
 
-        let result = Rc::new(RefCell::new(this));
-
-        let mesh_component = MeshComponent::new(result.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Plane.gpmesh");
-        mesh_component.borrow_mut().set_mesh(mesh);
-
-        entity_manager.borrow_mut().add_actor(result.clone());
-
-        result
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Plane.gpmesh");
+        ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh)
+            .build()
     }
 }
 
@@ -0,0 +1,129 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    components::component::{Component, State as ComponentState},
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        sound_event::SoundEvent,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Volume applying an FMOD snapshot (e.g. reverb) while the listener is inside it.
+/// The snapshot fades out on exit instead of being cut
+pub struct ReverbZoneActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    context: EngineContext,
+    // Half size of the volume, scaled with the actor
+    half_extents: Vector3,
+    snapshot_name: String,
+    // Playing while the listener is inside
+    snapshot: Option<SoundEvent>,
+}
+
+impl ReverbZoneActor {
+    pub fn new(
+        context: &EngineContext,
+        snapshot_name: &str,
+        half_extents: Vector3,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 1.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            context: context.clone(),
+            half_extents,
+            snapshot_name: snapshot_name.to_string(),
+            snapshot: None,
+        };
+
+        ActorBuilder::new(context, this).build()
+    }
+
+    pub fn is_listener_inside(&self) -> bool {
+        self.snapshot.is_some()
+    }
+}
+
+impl Actor for ReverbZoneActor {
+    fn update_actor(&mut self, _delta_time: f32) {
+        let listener_position = self
+            .context
+            .audio_system
+            .borrow()
+            .get_listener_position()
+            .clone();
+        let is_inside = is_inside_volume(
+            &self.world_transform.get_translation(),
+            &(self.half_extents.clone() * self.scale),
+            &listener_position,
+        );
+
+        if is_inside && self.snapshot.is_none() {
+            let snapshot = self
+                .context
+                .audio_system
+                .borrow_mut()
+                .play_event(&self.snapshot_name);
+            self.snapshot = Some(snapshot);
+        } else if !is_inside {
+            if let Some(mut snapshot) = self.snapshot.take() {
+                // Let the snapshot fade out
+                snapshot.stop(true);
+            }
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for ReverbZoneActor {
+    actor::impl_drop! {}
+}
+
+/// Whether point is in the box around center, which is not rotated
+fn is_inside_volume(center: &Vector3, half_extents: &Vector3, point: &Vector3) -> bool {
+    (point.x - center.x).abs() <= half_extents.x
+        && (point.y - center.y).abs() <= half_extents.y
+        && (point.z - center.z).abs() <= half_extents.z
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::vector3::Vector3;
+
+    use super::is_inside_volume;
+
+    #[test]
+    fn test_is_inside_volume() {
+        let center = Vector3::new(-750.0, -750.0, 0.0);
+        let half_extents = Vector3::new(500.0, 500.0, 500.0);
+
+        assert!(is_inside_volume(&center, &half_extents, &center));
+        assert!(is_inside_volume(
+            &center,
+            &half_extents,
+            &Vector3::new(-300.0, -1200.0, 100.0)
+        ));
+        assert!(!is_inside_volume(&center, &half_extents, &Vector3::ZERO));
+    }
+}
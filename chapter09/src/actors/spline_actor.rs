@@ -8,12 +8,14 @@ use crate::{
     },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        renderer::Renderer,
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
     },
 };
 
-use super::actor::{self, generate_id, Actor, State};
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
 
 pub struct SplineActor {
     id: u32,
@@ -24,19 +26,12 @@ pub struct SplineActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
-    audio_system: Rc<RefCell<AudioSystem>>,
+    context: EngineContext,
     camera_component: Option<Rc<RefCell<SplineCamera>>>,
 }
 
 impl SplineActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        audio_system: Rc<RefCell<AudioSystem>>,
-        renderer: Rc<RefCell<Renderer>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -46,14 +41,10 @@ impl SplineActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
-            audio_system: audio_system.clone(),
+            context: context.clone(),
             camera_component: None,
         };
 
-        let result = Rc::new(RefCell::new(this));
-
         // Create a spline
         let mut path = Spline::new();
         for i in 0..5 {
@@ -66,14 +57,13 @@ impl SplineActor {
             }
         }
 
-        let spline_camera = SplineCamera::new(result.clone(), renderer, audio_system);
-        spline_camera.borrow_mut().set_spline(path);
-        spline_camera.borrow_mut().set_paused(false);
-        result.borrow_mut().camera_component = Some(spline_camera);
-
-        entity_manager.borrow_mut().add_actor(result.clone());
-
-        result
+        ActorBuilder::new(context, this)
+            .with_handle::<SplineCamera>((), |spline, spline_camera| {
+                spline_camera.borrow_mut().set_spline(path);
+                spline_camera.borrow_mut().set_paused(false);
+                spline.camera_component = Some(spline_camera);
+            })
+            .build()
     }
 
     pub fn restart_spline(&mut self) {
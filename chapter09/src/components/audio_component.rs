@@ -1,10 +1,11 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{actor::Actor, actor_builder::BuildComponent},
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
     system::{
         audio_system::{self, AudioSystem},
+        engine_context::EngineContext,
         sound_event::SoundEvent,
     },
 };
@@ -42,9 +43,27 @@ impl AudioComponent {
         result
     }
 
+    /// 2D or 3D as authored in FMOD Studio
     pub fn play_event(&mut self, name: &str) -> Rc<RefCell<SoundEvent>> {
-        let mut event = self.audio_system.borrow_mut().play_event(name);
-        let result = if event.is_3d() {
+        let event = self.audio_system.borrow_mut().play_event(name);
+        let is_3d = event.is_3d();
+        self.add_event(event, is_3d)
+    }
+
+    /// Never follows the owner
+    pub fn play_event_2d(&mut self, name: &str) -> Rc<RefCell<SoundEvent>> {
+        let event = self.audio_system.borrow_mut().play_event(name);
+        self.add_event(event, false)
+    }
+
+    /// Placed at the owner and follows it
+    pub fn play_event_3d(&mut self, name: &str) -> Rc<RefCell<SoundEvent>> {
+        let event = self.audio_system.borrow_mut().play_event(name);
+        self.add_event(event, true)
+    }
+
+    fn add_event(&mut self, mut event: SoundEvent, is_3d: bool) -> Rc<RefCell<SoundEvent>> {
+        let result = if is_3d {
             event.set_3d_attributes(self.owner.borrow().get_world_transform());
             let event_ref = Rc::new(RefCell::new(event));
             self.events_3d.push(event_ref.clone());
@@ -70,6 +89,18 @@ impl AudioComponent {
     }
 }
 
+impl BuildComponent for AudioComponent {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        AudioComponent::new(owner, context.audio_system.clone())
+    }
+}
+
 impl Component for AudioComponent {
     fn update(
         &mut self,
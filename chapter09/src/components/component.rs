@@ -7,6 +7,7 @@ use std::{
 use crate::{
     actors::actor::Actor,
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::input_system::InputState,
 };
 
 static ID: AtomicU32 = AtomicU32::new(0);
@@ -24,7 +25,7 @@ pub trait Component {
         owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3), // 0:location, 1:rotation, 2:forward, 3:world_transform, 4: right
     ) -> (Option<Vector3>, Option<Quaternion>);
 
-    fn process_input(&mut self, _key_state: &KeyboardState) {}
+    fn process_input(&mut self, _state: &InputState) {}
 
     fn on_update_world_transform(&mut self) {}
 
@@ -70,7 +71,6 @@ macro_rules! impl_getters_setters {
 }
 
 pub(crate) use impl_getters_setters;
-use sdl2::keyboard::KeyboardState;
 
 pub fn remove_component(this: Rc<RefCell<dyn Component>>) {
     debug_assert!(*this.borrow().get_state() == State::Active, "not active");
@@ -1,9 +1,9 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{actor::Actor, actor_builder::BuildComponent},
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{audio_system::AudioSystem, renderer::Renderer},
+    system::{audio_system::AudioSystem, engine_context::EngineContext, renderer::Renderer},
 };
 
 use super::{
@@ -98,6 +98,22 @@ impl CameraComponent for FollowCamera {
     camera_component::impl_getters! {}
 }
 
+impl BuildComponent for FollowCamera {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        FollowCamera::new(
+            owner,
+            context.renderer.clone(),
+            context.audio_system.clone(),
+        )
+    }
+}
+
 impl Component for FollowCamera {
     fn update(
         &mut self,
@@ -3,9 +3,10 @@ use std::{cell::RefCell, ptr::null, rc::Rc};
 use gl::{TRIANGLES, UNSIGNED_INT};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{actor::Actor, actor_builder::BuildComponent},
     graphics::{mesh::Mesh, shader::Shader},
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::engine_context::EngineContext,
 };
 
 use super::component::{self, generate_id, Component, State};
@@ -93,6 +94,20 @@ impl MeshComponent {
     }
 }
 
+impl BuildComponent for MeshComponent {
+    type Params = Rc<Mesh>;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        let this = MeshComponent::new(owner);
+        this.borrow_mut().set_mesh(params);
+        this
+    }
+}
+
 impl Component for MeshComponent {
     fn update(
         &mut self,
@@ -1,8 +1,9 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{actor::Actor, actor_builder::BuildComponent},
     math::{self, matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::engine_context::EngineContext,
 };
 
 use super::component::{self, generate_id, Component, State};
@@ -115,6 +116,18 @@ impl MoveComponent for DefaultMoveComponent {
     impl_getters_setters! {}
 }
 
+impl BuildComponent for DefaultMoveComponent {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        DefaultMoveComponent::new(owner)
+    }
+}
+
 impl Component for DefaultMoveComponent {
     fn update(
         &mut self,
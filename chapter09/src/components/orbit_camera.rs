@@ -1,9 +1,9 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{actor::Actor, actor_builder::BuildComponent},
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{audio_system::AudioSystem, renderer::Renderer},
+    system::{audio_system::AudioSystem, engine_context::EngineContext, renderer::Renderer},
 };
 
 use super::{
@@ -68,6 +68,22 @@ impl CameraComponent for OrbitCamera {
     camera_component::impl_getters! {}
 }
 
+impl BuildComponent for OrbitCamera {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        OrbitCamera::new(
+            owner,
+            context.renderer.clone(),
+            context.audio_system.clone(),
+        )
+    }
+}
+
 impl Component for OrbitCamera {
     fn update(
         &mut self,
@@ -78,7 +94,7 @@ impl Component for OrbitCamera {
         self.offset = Vector3::transform(&self.offset, &yaw);
         self.up = Vector3::transform(&self.up, &yaw);
 
-        let mut forward = self.offset.clone() * -1.0;
+        let mut forward = -self.offset.clone();
         forward.normalize_mut();
         let mut right = Vector3::cross(&self.up, &forward);
         right.normalize_mut();
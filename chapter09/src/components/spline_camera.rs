@@ -1,9 +1,9 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{actor::Actor, actor_builder::BuildComponent},
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{audio_system::AudioSystem, renderer::Renderer},
+    system::{audio_system::AudioSystem, engine_context::EngineContext, renderer::Renderer},
 };
 
 use super::{
@@ -47,9 +47,9 @@ impl Spline {
 
         // Compute position according to Catmull-Rom equation
         let position = ((p1.clone() * 2.0)
-            + (p0.clone() * -1.0 + p2.clone()) * t
+            + (-p0.clone() + p2.clone()) * t
             + (p0.clone() * 2.0 - p1.clone() * 5.0 + p2.clone() * 4.0 - p3.clone()) * t * t
-            + (p0.clone() * -1.0 + p1.clone() * 3.0 - p2.clone() * 3.0 + p3.clone()) * t * t * t)
+            + (-p0.clone() + p1.clone() * 3.0 - p2.clone() * 3.0 + p3.clone()) * t * t * t)
             * 0.5;
 
         position
@@ -117,6 +117,22 @@ impl CameraComponent for SplineCamera {
     camera_component::impl_getters! {}
 }
 
+impl BuildComponent for SplineCamera {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        SplineCamera::new(
+            owner,
+            context.renderer.clone(),
+            context.audio_system.clone(),
+        )
+    }
+}
+
 impl Component for SplineCamera {
     fn update(
         &mut self,
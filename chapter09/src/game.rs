@@ -4,8 +4,9 @@ use std::{cell::RefCell, rc::Rc};
 
 use anyhow::{anyhow, Result};
 use sdl2::{
-    event::Event,
-    keyboard::{KeyboardState, Scancode},
+    event::{Event, WindowEvent},
+    keyboard::Scancode,
+    mouse::MouseUtil,
     EventPump, TimerSubsystem,
 };
 
@@ -19,8 +20,14 @@ use crate::{
     },
     math::vector3::Vector3,
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        renderer::Renderer, sound_event::SoundEvent,
+        asset_manager::AssetManager,
+        audio_system::AudioSystem,
+        crash_report::{self, log, ActorCounts},
+        engine_context::EngineContext,
+        entity_manager::EntityManager,
+        input_system::InputSystem,
+        renderer::Renderer,
+        sound_event::SoundEvent,
     },
 };
 
@@ -31,10 +38,14 @@ pub struct Game {
     asset_manager: Rc<RefCell<AssetManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
     audio_system: Rc<RefCell<AudioSystem>>,
+    input_system: Rc<RefCell<InputSystem>>,
+    mouse: MouseUtil,
+    // Whether the player wants the mouse captured (toggled with Tab)
+    capture_mouse: bool,
+    has_focus: bool,
     is_running: bool,
     tick_count: u64,
     music_event: SoundEvent,
-    reverb_snap: Option<SoundEvent>,
     fps_actor: Rc<RefCell<FPSActor>>,
     follow_actor: Rc<RefCell<FollowActor>>,
     orbit_actor: Rc<RefCell<OrbitActor>>,
@@ -53,21 +64,32 @@ impl Game {
 
         let event_pump = sdl.event_pump().map_err(|e| anyhow!(e))?;
 
+        let mouse = sdl.mouse();
+        mouse.set_relative_mouse_mode(true);
+
         let timer = sdl.timer().map_err(|e| anyhow!(e))?;
 
+        let controller_subsystem = sdl.game_controller().map_err(|e| anyhow!(e))?;
+        let controller = controller_subsystem.open(0).ok();
+
         let asset_manager = renderer.borrow().get_asset_manager().clone();
         let entity_manager = EntityManager::new();
 
-        let audio_system = AudioSystem::initialize(asset_manager.clone())?;
+        let audio_system = AudioSystem::initialize(asset_manager.clone());
         let music_event = audio_system.borrow_mut().play_event("event:/Music");
 
+        let context = EngineContext {
+            asset_manager: asset_manager.clone(),
+            entity_manager: entity_manager.clone(),
+            audio_system: audio_system.clone(),
+            renderer: renderer.clone(),
+        };
+
         let (fps_actor, follow_actor, orbit_actor, spline_actor, start_sphere, end_sphere) =
-            EntityManager::load_data(
-                entity_manager.clone(),
-                asset_manager.clone(),
-                renderer.clone(),
-                audio_system.clone(),
-            );
+            EntityManager::load_data(&context);
+
+        let input_system = InputSystem::initialize(controller)?;
+        input_system.borrow_mut().set_relative_mouse_mode(true);
 
         let mut game = Game {
             renderer,
@@ -76,10 +98,13 @@ impl Game {
             asset_manager,
             entity_manager,
             audio_system,
+            input_system,
+            mouse,
+            capture_mouse: true,
+            has_focus: true,
             is_running: true,
             tick_count: 0,
             music_event,
-            reverb_snap: None,
             fps_actor,
             follow_actor,
             orbit_actor,
@@ -99,11 +124,23 @@ impl Game {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
         }
     }
 
+    /// Bookkeeping once the frame is over
+    fn end_frame(&self) {
+        let mut actor_counts = ActorCounts::new();
+        for actor in self.entity_manager.borrow().get_actors() {
+            actor_counts.add(actor.borrow().get_state());
+        }
+        crash_report::end_frame(actor_counts);
+    }
+
     /// Herlper functions for the game loop
     fn process_input(&mut self) {
+        self.input_system.borrow_mut().prepare_for_update();
+
         let mut scancodes = vec![];
         for event in self.event_pump.poll_iter() {
             match event {
@@ -111,6 +148,11 @@ impl Game {
                     self.is_running = false;
                     break;
                 }
+                Event::Window { win_event, .. } => match win_event {
+                    WindowEvent::FocusGained => self.has_focus = true,
+                    WindowEvent::FocusLost => self.has_focus = false,
+                    _ => {}
+                },
                 Event::KeyDown {
                     scancode, repeat, ..
                 } => {
@@ -118,6 +160,7 @@ impl Game {
                         scancodes.push(scancode.unwrap());
                     }
                 }
+                Event::MouseWheel { .. } => self.input_system.borrow_mut().process_event(&event),
                 _ => {}
             }
         }
@@ -126,17 +169,33 @@ impl Game {
             self.handle_key_pressed(scancode);
         }
 
-        let state = KeyboardState::new(&self.event_pump);
-        if state.is_scancode_pressed(Scancode::Escape) {
+        self.input_system.borrow_mut().update(&self.event_pump);
+
+        if self
+            .input_system
+            .borrow()
+            .get_state()
+            .keyboard
+            .get_key_value(Scancode::Escape)
+        {
             self.is_running = false;
         }
 
-        let mouse_state = self.event_pump.relative_mouse_state();
+        // Release the mouse while unfocused or when the player asked for it
+        let is_captured = self.capture_mouse && self.has_focus;
+        self.mouse.set_relative_mouse_mode(is_captured);
+        if !is_captured {
+            // Actors get an empty state so the camera doesn't keep spinning
+            self.input_system.borrow_mut().clear();
+        }
+
+        let borrowed_input_system = self.input_system.borrow();
+        let state = borrowed_input_system.get_state();
 
         self.entity_manager.borrow_mut().set_updating_actors(true);
         let actors = self.entity_manager.borrow().get_actors().clone();
         for actor in actors {
-            actor.borrow_mut().process_input(&state, &mouse_state);
+            actor.borrow_mut().process_input(state);
         }
     }
 
@@ -166,19 +225,18 @@ impl Game {
             Scancode::M => {
                 self.music_event.set_paused(!self.music_event.get_paused());
             }
-            Scancode::R => {
-                // FIXME: An error will happen when switching four times...
-                if let Some(reverb) = &mut self.reverb_snap {
-                    if reverb.is_valid() {
-                        reverb.stop(true);
-                        return;
-                    }
-                }
-                let reverb = self
-                    .audio_system
-                    .borrow_mut()
-                    .play_event("snapshot:/WithReverb");
-                self.reverb_snap = Some(reverb);
+            Scancode::I => {
+                self.fps_actor.borrow_mut().toggle_invert_y();
+            }
+            Scancode::Tab => {
+                self.capture_mouse = !self.capture_mouse;
+            }
+            Scancode::F1 => {
+                // Cycle through the debug views of the meshes
+                let mut renderer = self.renderer.borrow_mut();
+                let debug_view = renderer.get_debug_view().next();
+                renderer.set_debug_view(debug_view);
+                log!("Debug view: {:?}", debug_view);
             }
             Scancode::Num1 | Scancode::Num2 | Scancode::Num3 | Scancode::Num4 => {
                 self.change_camera(key as i32 - 29);
@@ -196,6 +196,26 @@ impl Matrix4 {
         Vector3::new(self.mat[2][0], self.mat[2][1], self.mat[2][2]).normalize()
     }
 
+    // Transform a point, translation included (w = 1)
+    pub fn transform_point(&self, point: &Vector3) -> Vector3 {
+        self.transform(point, 1.0)
+    }
+
+    // Transform a direction, without the translation (w = 0)
+    pub fn transform_direction(&self, direction: &Vector3) -> Vector3 {
+        self.transform(direction, 0.0)
+    }
+
+    fn transform(&self, v: &Vector3, w: f32) -> Vector3 {
+        let row = |column: usize| {
+            v.x * self.mat[0][column]
+                + v.y * self.mat[1][column]
+                + v.z * self.mat[2][column]
+                + w * self.mat[3][column]
+        };
+        Vector3::new(row(0), row(1), row(2))
+    }
+
     // Invert the matrix - super slow
     pub fn invert(&mut self) {
         let mut tmp = [0.0; 12];
@@ -339,3 +359,79 @@ impl MulAssign for Matrix4 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use proptest::prelude::*;
+
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::Matrix4;
+
+    proptest! {
+        #[test]
+        fn prop_invert_times_original_is_identity(
+            scale in 0.5f32..5.0,
+            pitch in -PI..PI,
+            yaw in -PI..PI,
+            x in -100.0f32..100.0,
+            y in -100.0f32..100.0,
+            z in -100.0f32..100.0,
+        ) {
+            let matrix = Matrix4::create_scale(scale)
+                * Matrix4::create_rotation_x(pitch)
+                * Matrix4::create_rotation_z(yaw)
+                * Matrix4::create_translation(&Vector3::new(x, y, z));
+            let mut inverted = matrix.clone();
+            inverted.invert();
+
+            let product = inverted * matrix;
+            for row in 0..4 {
+                for column in 0..4 {
+                    assert_near_eq!(
+                        Matrix4::IDENTITY.mat[row][column],
+                        product.mat[row][column],
+                        0.001
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_axes() {
+        let matrix = Matrix4::create_scale(2.0)
+            * Matrix4::create_rotation_z(FRAC_PI_2)
+            * Matrix4::create_translation(&Vector3::new(10.0, 20.0, 30.0));
+
+        // Normalized, the scale doesn't show
+        assert_vector_near_eq(&Vector3::new(0.0, 1.0, 0.0), &matrix.get_x_axis());
+        assert_vector_near_eq(&Vector3::new(-1.0, 0.0, 0.0), &matrix.get_y_axis());
+        assert_vector_near_eq(&Vector3::new(0.0, 0.0, 1.0), &matrix.get_z_axis());
+    }
+
+    #[test]
+    fn test_transform() {
+        let matrix = Matrix4::create_scale(2.0)
+            * Matrix4::create_rotation_z(FRAC_PI_2)
+            * Matrix4::create_translation(&Vector3::new(10.0, 20.0, 30.0));
+
+        assert_vector_near_eq(
+            &Vector3::new(10.0, 22.0, 30.0),
+            &matrix.transform_point(&Vector3::new(1.0, 0.0, 0.0)),
+        );
+        // Directions don't move
+        assert_vector_near_eq(
+            &Vector3::new(0.0, 2.0, 0.0),
+            &matrix.transform_direction(&Vector3::new(1.0, 0.0, 0.0)),
+        );
+    }
+
+    fn assert_vector_near_eq(expected: &Vector3, actual: &Vector3) {
+        assert_near_eq!(expected.x, actual.x, 0.001);
+        assert_near_eq!(expected.y, actual.y, 0.001);
+        assert_near_eq!(expected.z, actual.z, 0.001);
+    }
+}
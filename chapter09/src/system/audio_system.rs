@@ -6,7 +6,7 @@ use std::{
     sync::atomic::{AtomicU32, Ordering},
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use libfmod::{
     ffi::{FMOD_INIT_NORMAL, FMOD_STUDIO_INIT_NORMAL},
     Attributes3d, Bank, Bus, EventDescription, EventInstance, LoadBank, PlaybackState, Studio,
@@ -15,7 +15,7 @@ use libfmod::{
 
 use crate::math::{matrix4::Matrix4, vector3::Vector3};
 
-use super::{asset_manager::AssetManager, sound_event::SoundEvent};
+use super::{asset_manager::AssetManager, crash_report::log, sound_event::SoundEvent};
 
 static ID: AtomicU32 = AtomicU32::new(0);
 
@@ -27,37 +27,72 @@ fn generate_id() -> u32 {
 
 pub struct AudioSystem {
     asset_manager: Rc<RefCell<AssetManager>>,
-    system: Studio,
-    low_level_system: System,
+    // None in null-audio mode, when FMOD or the banks failed to load
+    system: Option<Studio>,
+    low_level_system: Option<System>,
     banks: HashMap<String, Bank>,
     events: HashMap<String, EventDescription>,
-    event_instances: HashMap<u32, Rc<RefCell<EventInstance>>>,
+    // Shared with the SoundEvent handles, which look their instance up by id
+    event_instances: Rc<RefCell<HashMap<u32, EventInstance>>>,
     buses: HashMap<String, Bus>,
+    // Kept in null-audio mode too, reverb zones check it
+    listener_position: Vector3,
+    // Min/max attenuation distances applied to new instances of the event
+    event_distances: HashMap<String, (f32, f32)>,
 }
 
 impl AudioSystem {
-    pub fn initialize(
-        asset_manager: Rc<RefCell<AssetManager>>,
-    ) -> Result<Rc<RefCell<AudioSystem>>> {
-        let system = Studio::create()?;
-        system.initialize(512, FMOD_STUDIO_INIT_NORMAL, FMOD_INIT_NORMAL, None)?;
-
-        let low_level_system = system.get_core_system()?;
-
+    pub fn initialize(asset_manager: Rc<RefCell<AssetManager>>) -> Rc<RefCell<AudioSystem>> {
         let mut this = Self {
             asset_manager,
-            system,
-            low_level_system,
+            system: None,
+            low_level_system: None,
             banks: HashMap::new(),
             events: HashMap::new(),
-            event_instances: HashMap::new(),
+            event_instances: Rc::new(RefCell::new(HashMap::new())),
             buses: HashMap::new(),
+            listener_position: Vector3::ZERO,
+            event_distances: HashMap::new(),
         };
 
-        this.load_bank("Master Bank.strings.bank")?;
-        this.load_bank("Master Bank.bank")?;
+        // The game still runs without sound, e.g. without the FMOD banks
+        if let Err(e) = this.start_fmod() {
+            log!("Audio is disabled: {}", e);
+            this.release();
+        }
+
+        Rc::new(RefCell::new(this))
+    }
+
+    fn start_fmod(&mut self) -> Result<()> {
+        let system = Studio::create()?;
+        self.system = Some(system);
+        system.initialize(512, FMOD_STUDIO_INIT_NORMAL, FMOD_INIT_NORMAL, None)?;
+
+        self.low_level_system = Some(system.get_core_system()?);
+
+        self.load_bank("Master Bank.strings.bank")?;
+        self.load_bank("Master Bank.bank")?;
 
-        Ok(Rc::new(RefCell::new(this)))
+        Ok(())
+    }
+
+    /// Back to null audio, where every event is inert
+    fn release(&mut self) {
+        self.event_instances.borrow_mut().clear();
+        self.events.clear();
+        self.banks.clear();
+        if let Some(system) = self.system.take() {
+            let _ = system.release();
+        }
+        if let Some(low_level_system) = self.low_level_system.take() {
+            let _ = low_level_system.release();
+        }
+    }
+
+    /// False in null-audio mode
+    pub fn is_enabled(&self) -> bool {
+        self.system.is_some()
     }
 
     pub fn load_bank(&mut self, name: &str) -> Result<()> {
@@ -73,7 +108,10 @@ impl AudioSystem {
             .join(name);
         let file_name = path.to_str().unwrap();
 
-        let bank = self.system.load_bank_file(file_name, LoadBank::NORMAL)?;
+        let system = self
+            .system
+            .ok_or_else(|| anyhow!("FMOD is not initialized"))?;
+        let bank = system.load_bank_file(file_name, LoadBank::NORMAL)?;
         self.banks.insert(name.to_string(), bank);
         bank.load_sample_data()?;
 
@@ -92,35 +130,83 @@ impl AudioSystem {
     }
 
     pub fn play_event(&mut self, name: &str) -> SoundEvent {
-        let event_description = self.events.get(name).unwrap();
+        let event_description = match self.events.get(name) {
+            Some(event_description) => event_description,
+            None => {
+                // Null audio has no events at all
+                if self.is_enabled() {
+                    log!("Unknown sound event {}", name);
+                }
+                return SoundEvent::inert();
+            }
+        };
         let event_instance = event_description.create_instance().unwrap();
         event_instance.start().unwrap();
         let id = generate_id();
-        let result = Rc::new(RefCell::new(event_instance));
-        self.event_instances.insert(id, result.clone());
-        SoundEvent::new(id, result)
+        self.event_instances.borrow_mut().insert(id, event_instance);
+        let mut sound_event = SoundEvent::new(id, Rc::downgrade(&self.event_instances));
+        if let Some((min_distance, max_distance)) = self.event_distances.get(name) {
+            sound_event.set_distance_range(*min_distance, *max_distance);
+        }
+        sound_event
+    }
+
+    /// Attenuate every instance of the event played from now on between the distances
+    pub fn set_event_distance(&mut self, name: &str, min_distance: f32, max_distance: f32) {
+        debug_assert!(
+            0.0 <= min_distance && min_distance <= max_distance,
+            "min distance should be between zero and max distance"
+        );
+        self.event_distances
+            .insert(name.to_string(), (min_distance, max_distance));
+    }
+
+    /// Scale of the pitch shift from relative velocity (1.0 is realistic, 0.0 disables it)
+    pub fn set_doppler_scale(&mut self, doppler_scale: f32) -> Result<()> {
+        let low_level_system = match self.low_level_system {
+            Some(low_level_system) => low_level_system,
+            None => return Ok(()),
+        };
+        let (_, distance_factor, rolloff_scale) = low_level_system.get_3d_settings()?;
+        low_level_system.set_3d_settings(doppler_scale, distance_factor, rolloff_scale)?;
+        Ok(())
+    }
+
+    pub fn get_doppler_scale(&self) -> f32 {
+        self.low_level_system
+            .and_then(|low_level_system| low_level_system.get_3d_settings().ok())
+            .map_or(1.0, |settings| settings.0)
     }
 
     pub fn update(&mut self, _delta_time: f32) {
         let mut done = vec![];
-        for (id, instance) in self.event_instances.clone() {
-            let state = instance.borrow().get_playback_state().unwrap();
+        for (id, instance) in self.event_instances.borrow().iter() {
+            let state = instance.get_playback_state().unwrap();
             if state == PlaybackState::Stopped {
-                instance.borrow_mut().release().unwrap();
-                done.push(id);
+                instance.release().unwrap();
+                done.push(*id);
             }
         }
 
+        // Handles to the released instances turn invalid
         for id in done {
-            self.event_instances.remove(&id);
+            self.event_instances.borrow_mut().remove(&id);
         }
 
-        self.system.update().unwrap();
+        if let Some(system) = self.system {
+            system.update().unwrap();
+        }
     }
 
     pub fn set_listener(&mut self, view_matrix: &Matrix4) {
         let mut inverted_view = view_matrix.clone();
         inverted_view.invert();
+        self.listener_position = inverted_view.get_translation();
+
+        let system = match self.system {
+            Some(system) => system,
+            None => return,
+        };
 
         let attributes = Attributes3d {
             position: AudioSystem::vector_to_fmod(&inverted_view.get_translation()),
@@ -130,7 +216,11 @@ impl AudioSystem {
         };
 
         // FIXME: when using spline camera, an error happens by unwrap()...
-        let _ = self.system.set_listener_attributes(0, attributes, None);
+        let _ = system.set_listener_attributes(0, attributes, None);
+    }
+
+    pub fn get_listener_position(&self) -> &Vector3 {
+        &self.listener_position
     }
 
     pub fn vector_to_fmod(in_vector: &Vector3) -> Vector {
@@ -167,7 +257,6 @@ impl AudioSystem {
 
 impl Drop for AudioSystem {
     fn drop(&mut self) {
-        let _ = self.system.release();
-        let _ = self.low_level_system.release();
+        self.release();
     }
 }
@@ -0,0 +1,15 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{
+    asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
+    renderer::Renderer,
+};
+
+/// Shared systems actors and components are created with
+#[derive(Clone)]
+pub struct EngineContext {
+    pub asset_manager: Rc<RefCell<AssetManager>>,
+    pub entity_manager: Rc<RefCell<EntityManager>>,
+    pub audio_system: Rc<RefCell<AudioSystem>>,
+    pub renderer: Rc<RefCell<Renderer>>,
+}
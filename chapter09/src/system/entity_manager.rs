@@ -9,6 +9,7 @@ use crate::{
         fps_actor::FPSActor,
         orbit_actor::{self, OrbitActor},
         plane_actor::PlaneActor,
+        reverb_zone_actor::ReverbZoneActor,
         spline_actor::SplineActor,
     },
     components::{
@@ -17,10 +18,9 @@ use crate::{
         sprite_component::{DefaultSpriteComponent, SpriteComponent},
     },
     math::{quaternion::Quaternion, random::Random, vector3::Vector3},
-    system::{asset_manager::AssetManager, renderer::Renderer},
 };
 
-use super::audio_system::AudioSystem;
+use super::engine_context::EngineContext;
 
 pub struct EntityManager {
     actors: Vec<Rc<RefCell<dyn Actor>>>,
@@ -68,10 +68,7 @@ impl EntityManager {
     }
 
     pub fn load_data(
-        this: Rc<RefCell<EntityManager>>,
-        asset_manager: Rc<RefCell<AssetManager>>,
-        renderer: Rc<RefCell<Renderer>>,
-        audio_system: Rc<RefCell<AudioSystem>>,
+        context: &EngineContext,
     ) -> (
         Rc<RefCell<FPSActor>>,
         Rc<RefCell<FollowActor>>,
@@ -81,7 +78,7 @@ impl EntityManager {
         Rc<RefCell<DefaultActor>>,
     ) {
         // Create actors
-        let a = DefaultActor::new(asset_manager.clone(), this.clone());
+        let a = DefaultActor::new(context);
         a.borrow_mut().set_position(Vector3::new(200.0, 75.0, 0.0));
         a.borrow_mut().set_scale(100.0);
 
@@ -94,21 +91,21 @@ impl EntityManager {
 
         let mesh = MeshComponent::new(a.clone());
         mesh.borrow_mut()
-            .set_mesh(asset_manager.borrow_mut().get_mesh("Cube.gpmesh"));
+            .set_mesh(context.asset_manager.borrow_mut().get_mesh("Cube.gpmesh"));
 
-        let b = DefaultActor::new(asset_manager.clone(), this.clone());
+        let b = DefaultActor::new(context);
         b.borrow_mut().set_position(Vector3::new(200.0, -75.0, 0.0));
         b.borrow_mut().set_scale(3.0);
         let mesh = MeshComponent::new(b.clone());
         mesh.borrow_mut()
-            .set_mesh(asset_manager.borrow_mut().get_mesh("Sphere.gpmesh"));
+            .set_mesh(context.asset_manager.borrow_mut().get_mesh("Sphere.gpmesh"));
 
         // Setup floor
         let start = -1250.0;
         let size = 250.0;
         for i in 0..10 {
             for j in 0..10 {
-                let p = PlaneActor::new(asset_manager.clone(), this.clone());
+                let p = PlaneActor::new(context);
                 p.borrow_mut().set_position(Vector3::new(
                     start + i as f32 * size,
                     start + j as f32 * size,
@@ -120,12 +117,12 @@ impl EntityManager {
         // Left/right walls
         let q = Quaternion::from_axis_angle(&Vector3::UNIT_X, std::f32::consts::FRAC_PI_2);
         for i in 0..10 {
-            let p = PlaneActor::new(asset_manager.clone(), this.clone());
+            let p = PlaneActor::new(context);
             p.borrow_mut()
                 .set_position(Vector3::new(start + i as f32 * size, start - size, 0.0));
             p.borrow_mut().set_rotation(q.clone());
 
-            let p = PlaneActor::new(asset_manager.clone(), this.clone());
+            let p = PlaneActor::new(context);
             p.borrow_mut()
                 .set_position(Vector3::new(start + i as f32 * size, -start + size, 0.0));
             p.borrow_mut().set_rotation(q.clone());
@@ -137,12 +134,12 @@ impl EntityManager {
             &Quaternion::from_axis_angle(&Vector3::UNIT_Z, std::f32::consts::FRAC_PI_2),
         );
         for i in 0..10 {
-            let p = PlaneActor::new(asset_manager.clone(), this.clone());
+            let p = PlaneActor::new(context);
             p.borrow_mut()
                 .set_position(Vector3::new(start - size, start + i as f32 * size, 0.0));
             p.borrow_mut().set_rotation(q.clone());
 
-            let p = PlaneActor::new(asset_manager.clone(), this.clone());
+            let p = PlaneActor::new(context);
             p.borrow_mut()
                 .set_position(Vector3::new(-start + size, start + i as f32 * size, 0.0));
             p.borrow_mut().set_rotation(q.clone());
@@ -150,7 +147,7 @@ impl EntityManager {
 
         // Setup lights
         {
-            let mut borrowed_renderer = renderer.borrow_mut();
+            let mut borrowed_renderer = context.renderer.borrow_mut();
             borrowed_renderer.set_ambient_light(Vector3::new(0.2, 0.2, 0.2));
             let directional_light = borrowed_renderer.get_directional_light_mut();
             directional_light.direction = Vector3::new(0.0, -0.707, -0.707);
@@ -158,74 +155,72 @@ impl EntityManager {
             directional_light.spec_color = Vector3::new(0.8, 0.8, 0.8);
         }
 
+        // The back left corner of the room echoes
+        let reverb_zone = ReverbZoneActor::new(
+            context,
+            "snapshot:/WithReverb",
+            Vector3::new(500.0, 500.0, 500.0),
+        );
+        reverb_zone
+            .borrow_mut()
+            .set_position(Vector3::new(-750.0, -750.0, 0.0));
+
         // UI elements
-        let ui = DefaultActor::new(asset_manager.clone(), this.clone());
+        let ui = DefaultActor::new(context);
         ui.borrow_mut()
             .set_position(Vector3::new(-350.0, -350.0, 0.0));
         let sprite_component = DefaultSpriteComponent::new(ui.clone(), 100);
-        let texture = asset_manager.borrow_mut().get_texture("HealthBar.png");
+        let texture = context
+            .asset_manager
+            .borrow_mut()
+            .get_texture("HealthBar.png");
         sprite_component.borrow_mut().set_texture(texture);
 
-        let ui = DefaultActor::new(asset_manager.clone(), this.clone());
+        let ui = DefaultActor::new(context);
         ui.borrow_mut()
             .set_position(Vector3::new(375.0, -275.0, 0.0));
         ui.borrow_mut().set_scale(0.75);
         let sprite_component = DefaultSpriteComponent::new(ui.clone(), 100);
-        let texture = asset_manager.borrow_mut().get_texture("Radar.png");
+        let texture = context.asset_manager.borrow_mut().get_texture("Radar.png");
         sprite_component.borrow_mut().set_texture(texture);
 
         // Create spheres with audio components playing different sounds
-        let m = DefaultActor::new(asset_manager.clone(), this.clone());
+        let m = DefaultActor::new(context);
         m.borrow_mut().set_position(Vector3::new(500.0, -75.0, 0.0));
         m.borrow_mut().set_scale(1.0);
         let mc = MeshComponent::new(m.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
         mc.borrow_mut().set_mesh(mesh);
-        let ac = AudioComponent::new(m, audio_system.clone());
-        ac.borrow_mut().play_event("event:/FireLoop");
+        // Audible across the room, fading out from 1.5 meters away
+        context
+            .audio_system
+            .borrow_mut()
+            .set_event_distance("event:/FireLoop", 150.0, 2000.0);
+        let ac = AudioComponent::new(m, context.audio_system.clone());
+        ac.borrow_mut().play_event_3d("event:/FireLoop");
 
         // Different camera actors
-        let fps_actor = FPSActor::new(
-            asset_manager.clone(),
-            this.clone(),
-            audio_system.clone(),
-            renderer.clone(),
-        );
-        let follow_actor = FollowActor::new(
-            asset_manager.clone(),
-            this.clone(),
-            audio_system.clone(),
-            renderer.clone(),
-        );
-        let orbit_actor = OrbitActor::new(
-            asset_manager.clone(),
-            this.clone(),
-            audio_system.clone(),
-            renderer.clone(),
-        );
-        let spline_actor = SplineActor::new(
-            asset_manager.clone(),
-            this.clone(),
-            audio_system.clone(),
-            renderer.clone(),
-        );
+        let fps_actor = FPSActor::new(context);
+        let follow_actor = FollowActor::new(context);
+        let orbit_actor = OrbitActor::new(context);
+        let spline_actor = SplineActor::new(context);
 
         // Spheres for demonstrating unprojection
-        let start_sphere = DefaultActor::new(asset_manager.clone(), this.clone());
+        let start_sphere = DefaultActor::new(context);
         start_sphere
             .borrow_mut()
             .set_position(Vector3::new(10000.0, 0.0, 0.0));
         start_sphere.borrow_mut().set_scale(0.25);
         let mesh_component = MeshComponent::new(start_sphere.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
         mesh_component.borrow_mut().set_mesh(mesh);
 
-        let end_sphere = DefaultActor::new(asset_manager.clone(), this.clone());
+        let end_sphere = DefaultActor::new(context);
         end_sphere
             .borrow_mut()
             .set_position(Vector3::new(10000.0, 0.0, 0.0));
         let mesh_component = MeshComponent::new(end_sphere.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
         mesh_component.borrow_mut().set_mesh(mesh);
         mesh_component.borrow_mut().set_texture_index(1);
 
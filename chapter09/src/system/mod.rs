@@ -1,5 +1,8 @@
 pub mod asset_manager;
 pub mod audio_system;
+pub mod crash_report;
+pub mod engine_context;
 pub mod entity_manager;
+pub mod input_system;
 pub mod renderer;
 pub mod sound_event;
@@ -1,7 +1,10 @@
 use std::{cell::RefCell, rc::Rc};
 
 use anyhow::{anyhow, Result};
-use gl::{BLEND, DEPTH_TEST, FUNC_ADD, ONE, ONE_MINUS_SRC_ALPHA, SRC_ALPHA, ZERO};
+use gl::{
+    BLEND, DEPTH_TEST, FILL, FRONT_AND_BACK, FUNC_ADD, LINE, ONE, ONE_MINUS_SRC_ALPHA, SRC_ALPHA,
+    ZERO,
+};
 use sdl2::{
     video::{GLContext, Window},
     VideoSubsystem,
@@ -14,6 +17,40 @@ use crate::{
 
 use super::asset_manager::AssetManager;
 
+/// How meshes are drawn, to diagnose mesh and lighting issues
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugView {
+    Lit,
+    Wireframe,
+    // Texture color without lighting
+    Albedo,
+    Normals,
+    // Brighter where more layers are drawn on top of each other
+    Overdraw,
+}
+
+impl DebugView {
+    pub fn next(self) -> Self {
+        match self {
+            DebugView::Lit => DebugView::Wireframe,
+            DebugView::Wireframe => DebugView::Albedo,
+            DebugView::Albedo => DebugView::Normals,
+            DebugView::Normals => DebugView::Overdraw,
+            DebugView::Overdraw => DebugView::Lit,
+        }
+    }
+
+    /// Mode of Debug.frag, None if drawn with the usual shader
+    fn shader_mode(self) -> Option<i32> {
+        match self {
+            DebugView::Lit | DebugView::Wireframe => None,
+            DebugView::Albedo => Some(1),
+            DebugView::Normals => Some(2),
+            DebugView::Overdraw => Some(3),
+        }
+    }
+}
+
 pub struct Renderer {
     asset_manager: Rc<RefCell<AssetManager>>,
 
@@ -29,6 +66,8 @@ pub struct Renderer {
     ambient_light: Vector3,
     directional_light: DirectionalLight,
 
+    debug_view: DebugView,
+
     // Window
     window: Window,
 
@@ -76,6 +115,7 @@ impl Renderer {
             screen_height,
             ambient_light: Vector3::ZERO,
             directional_light: DirectionalLight::new(),
+            debug_view: DebugView::Lit,
             window,
             context,
         };
@@ -93,24 +133,32 @@ impl Renderer {
             gl::Disable(BLEND);
         }
 
-        // Set the mesh shader active
+        // Set the mesh shader active (or the debug one)
         let asset_manager = self.asset_manager.borrow_mut();
-        asset_manager.mesh_shader.set_active();
+        let debug_mode = self.debug_view.shader_mode();
+        let mesh_shader = match debug_mode {
+            Some(_) => &asset_manager.debug_shader,
+            None => &asset_manager.mesh_shader,
+        };
+        mesh_shader.set_active();
+        if let Some(mode) = debug_mode {
+            mesh_shader.set_int_uniform("uDebugView", mode);
+        }
+        self.begin_debug_view();
 
         // Update view-projection matrix
-        asset_manager
-            .mesh_shader
-            .set_matrix_uniform("uViewProj", self.view.clone() * self.projection.clone());
+        mesh_shader.set_matrix_uniform("uViewProj", self.view.clone() * self.projection.clone());
 
         // Update lighting uniforms
-        self.set_light_uniforms(&asset_manager.mesh_shader);
+        self.set_light_uniforms(mesh_shader);
 
         // Draw mesh components
         for mesh_component in asset_manager.get_mesh_components() {
             if mesh_component.borrow().get_visible() {
-                mesh_component.borrow().draw(&asset_manager.mesh_shader);
+                mesh_component.borrow().draw(mesh_shader);
             }
         }
+        self.end_debug_view();
 
         unsafe {
             // Draw all sprite components. Disable depth buffering
@@ -132,6 +180,37 @@ impl Renderer {
         self.window.gl_swap_window();
     }
 
+    pub fn set_debug_view(&mut self, debug_view: DebugView) {
+        self.debug_view = debug_view;
+    }
+
+    pub fn get_debug_view(&self) -> DebugView {
+        self.debug_view
+    }
+
+    /// Set the render state of the debug view, undone by end_debug_view
+    fn begin_debug_view(&self) {
+        unsafe {
+            match self.debug_view {
+                DebugView::Wireframe => gl::PolygonMode(FRONT_AND_BACK, LINE),
+                DebugView::Overdraw => {
+                    // Hidden layers have to count too
+                    gl::Disable(DEPTH_TEST);
+                    gl::Enable(BLEND);
+                    gl::BlendEquation(FUNC_ADD);
+                    gl::BlendFunc(ONE, ONE);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn end_debug_view(&self) {
+        unsafe {
+            gl::PolygonMode(FRONT_AND_BACK, FILL);
+        }
+    }
+
     pub fn set_light_uniforms(&self, shader: &Shader) {
         // Camera position is from inverted view
         let mut inverted_view = self.view.clone();
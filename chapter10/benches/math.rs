@@ -0,0 +1,73 @@
+// The game is a binary crate, so the modules under test are compiled in directly
+#![allow(dead_code, unused_imports)]
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+#[path = "../src/collision/mod.rs"]
+mod collision;
+#[path = "../src/math/mod.rs"]
+mod math;
+#[path = "../src/system"]
+mod system {
+    pub mod engine_error;
+    pub mod scratch_pool;
+}
+
+use collision::{aabb::AABB, line_segment::LineSegment};
+use math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3};
+
+fn matrix4(c: &mut Criterion) {
+    let a = Matrix4::create_from_quaternion(&Quaternion::from_axis_angle(&Vector3::UNIT_Z, 0.5))
+        * Matrix4::create_translation(&Vector3::new(10.0, -20.0, 30.0));
+    let b = Matrix4::create_scale(2.0) * Matrix4::create_rotation_x(1.2);
+
+    c.bench_function("Matrix4 multiply", |bencher| {
+        bencher.iter(|| black_box(a.clone()) * black_box(b.clone()))
+    });
+    c.bench_function("Matrix4 invert", |bencher| {
+        bencher.iter(|| {
+            let mut matrix = black_box(a.clone());
+            matrix.invert();
+            matrix
+        })
+    });
+}
+
+fn quaternion(c: &mut Criterion) {
+    let a = Quaternion::from_axis_angle(&Vector3::UNIT_Z, 0.5);
+    let b = Quaternion::from_axis_angle(&Vector3::UNIT_X, 2.0);
+
+    c.bench_function("Quaternion slerp", |bencher| {
+        bencher.iter(|| black_box(&a).slerp(black_box(&b), black_box(0.3)))
+    });
+    c.bench_function("Quaternion concatenate", |bencher| {
+        bencher.iter(|| black_box(&a).concatenate(black_box(&b)))
+    });
+}
+
+fn line_segment(c: &mut Criterion) {
+    let aabb = AABB::new(
+        Vector3::new(-100.0, -100.0, -100.0),
+        Vector3::new(100.0, 100.0, 100.0),
+    );
+    let hit = LineSegment::new(
+        Vector3::new(-500.0, 20.0, 30.0),
+        Vector3::new(500.0, -20.0, 10.0),
+    );
+    let miss = LineSegment::new(
+        Vector3::new(-500.0, 300.0, 30.0),
+        Vector3::new(500.0, 300.0, 10.0),
+    );
+
+    c.bench_function("LineSegment intersect_aabb hit", |bencher| {
+        bencher.iter(|| black_box(&hit).intersect_aabb(black_box(&aabb)))
+    });
+    c.bench_function("LineSegment intersect_aabb miss", |bencher| {
+        bencher.iter(|| black_box(&miss).intersect_aabb(black_box(&aabb)))
+    });
+}
+
+criterion_group!(benches, matrix4, quaternion, line_segment);
+criterion_main!(benches);
@@ -0,0 +1,2 @@
+// Only there so OUT_DIR is set, the loaders look for resources under it
+fn main() {}
@@ -0,0 +1,32 @@
+// The game is a binary crate, so the loaders are compiled in directly
+#![no_main]
+#![allow(dead_code, unused_imports)]
+
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+
+#[path = "../../src/collision"]
+mod collision {
+    pub mod aabb;
+}
+#[path = "../../src/math/mod.rs"]
+mod math;
+#[path = "../../src/system"]
+mod system {
+    pub mod engine_error;
+    pub mod level_file;
+    pub mod scratch_pool;
+}
+#[path = "../../src/components"]
+mod components {
+    pub mod powerup;
+}
+
+use system::level_file::LevelFile;
+
+// Any JSON must give a level or an error, never a panic
+fuzz_target!(|data: &[u8]| {
+    if let Ok(json) = serde_json::from_slice::<Value>(data) {
+        let _ = LevelFile::parse(&json);
+    }
+});
@@ -0,0 +1,28 @@
+// The game is a binary crate, so the loaders are compiled in directly
+#![no_main]
+#![allow(dead_code, unused_imports)]
+
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+
+#[path = "../../src/collision"]
+mod collision {
+    pub mod aabb;
+}
+#[path = "../../src/math/mod.rs"]
+mod math;
+#[path = "../../src/system"]
+mod system {
+    pub mod engine_error;
+    pub mod prefab_node;
+    pub mod scratch_pool;
+}
+
+use system::prefab_node::PrefabNode;
+
+// Any JSON must give a prefab or an error, never a panic
+fuzz_target!(|data: &[u8]| {
+    if let Ok(json) = serde_json::from_slice::<Value>(data) {
+        let _ = PrefabNode::parse(&json);
+    }
+});
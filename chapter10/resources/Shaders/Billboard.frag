@@ -0,0 +1,23 @@
+#version 330
+
+// Tex coord input from vertex shader
+in vec2 fragTexCoord;
+
+// This corresponds to the output color to the color buffer
+out vec4 outColor;
+
+// This is used for the texture sampling
+uniform sampler2D uTexture;
+
+// Fades the whole billboard, e.g. a puff of smoke dying out
+uniform float uAlpha;
+
+void main() {
+    outColor = texture(uTexture, fragTexCoord);
+    outColor.a *= uAlpha;
+
+    // Fully transparent texels would still hide billboards drawn later
+    if (outColor.a < 0.01) {
+        discard;
+    }
+}
@@ -0,0 +1,29 @@
+#version 330
+
+// Tex coord input from vertex shader
+in vec2 fragTexCoord;
+
+// This corresponds to the output color to the color buffer
+out vec4 outColor;
+
+// Image to blur
+uniform sampler2D uImage;
+
+// Blur along x if true, along y otherwise
+uniform int uHorizontal;
+
+// Gaussian weights of the center texel and the ones next to it
+uniform float uWeights[5];
+
+void main() {
+    vec2 texelSize = 1.0 / vec2(textureSize(uImage, 0));
+    vec2 step = uHorizontal != 0 ? vec2(texelSize.x, 0.0) : vec2(0.0, texelSize.y);
+
+    vec3 result = texture(uImage, fragTexCoord).rgb * uWeights[0];
+    for (int i = 1; i < 5; ++i) {
+        result += texture(uImage, fragTexCoord + step * i).rgb * uWeights[i];
+        result += texture(uImage, fragTexCoord - step * i).rgb * uWeights[i];
+    }
+
+    outColor = vec4(result, 1.0);
+}
@@ -0,0 +1,22 @@
+#version 330
+
+// Tex coord input from vertex shader
+in vec2 fragTexCoord;
+
+// This corresponds to the output color to the color buffer
+out vec4 outColor;
+
+// HDR scene color
+uniform sampler2D uScene;
+
+// Brightness above which pixels glow
+uniform float uThreshold;
+
+void main() {
+    vec3 color = texture(uScene, fragTexCoord).rgb;
+
+    // Perceived brightness
+    float brightness = dot(color, vec3(0.2126, 0.7152, 0.0722));
+
+    outColor = brightness > uThreshold ? vec4(color, 1.0) : vec4(0.0, 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,11 @@
+#version 330
+
+// Color input from vertex shader
+in vec3 fragColor;
+
+// This corresponds to the output color to the color buffer
+out vec4 outColor;
+
+void main() {
+    outColor = vec4(fragColor, 1.0);
+}
@@ -0,0 +1,16 @@
+#version 330
+
+// Uniform for view-proj, line vertices are in world space
+uniform mat4 uViewProj;
+
+// Attribute 0 is position, 1 is color
+layout(location = 0) in vec3 inPosition;
+layout(location = 1) in vec3 inColor;
+
+out vec3 fragColor;
+
+void main() {
+    gl_Position = vec4(inPosition, 1.0) * uViewProj;
+
+    fragColor = inColor;
+}
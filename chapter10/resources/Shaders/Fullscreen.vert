@@ -0,0 +1,14 @@
+#version 330
+
+// Sprite quad, stretched to cover the whole screen
+layout(location = 0) in vec3 inPosition;
+
+// Tex coord of the frame buffer (origin at bottom left)
+out vec2 fragTexCoord;
+
+void main() {
+    // Quad corners are at +-0.5, clip space corners at +-1
+    gl_Position = vec4(inPosition.xy * 2.0, 0.0, 1.0);
+
+    fragTexCoord = inPosition.xy + vec2(0.5, 0.5);
+}
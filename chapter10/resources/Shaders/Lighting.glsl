@@ -0,0 +1,60 @@
+// Lighting and fog shared by the lit shaders, pulled in with #include after #version
+
+// Create a struct for directional light
+struct DirectionalLight {
+    // Direction of light
+    vec3 mDirection;
+    // Diffuse color
+    vec3 mDiffuseColor;
+    // Specular color
+    vec3 mSpecColor;
+};
+
+// Uniforms for lighting
+// Camera position (in world space)
+uniform vec3 uCameraPos;
+
+// Ambient light level
+uniform vec3 uAmbientLight;
+
+// Directional Light
+uniform DirectionalLight uDirLight;
+
+// Distance fog, on if not 0
+uniform int uFogEnabled;
+uniform vec3 uFogColor;
+// Distances from the camera where the fog starts and where it hides everything
+uniform float uFogStart;
+uniform float uFogEnd;
+
+// Phong light reaching the eye from a surface with normal N at worldPos
+vec3 ComputePhong(vec3 N, vec3 worldPos, float specPower) {
+    // Vector from surface to light
+    vec3 L = normalize(-uDirLight.mDirection);
+
+    // Vector from surface to camera
+    vec3 V = normalize(uCameraPos - worldPos);
+
+    // Reflection of -L and N
+    vec3 R = normalize(reflect(-L, N));
+
+    // Compute phong reflection
+    vec3 Phong = uAmbientLight;
+    float NDotL = dot(N, L);
+    if (NDotL > 0) {
+        vec3 Diffuse = uDirLight.mDiffuseColor * NDotL;
+        vec3 Specular = uDirLight.mSpecColor * pow(max(0.0, dot(R, V)), specPower);
+        Phong += Diffuse + Specular;
+    }
+    return Phong;
+}
+
+// Blend towards the fog color with the distance
+vec3 ApplyFog(vec3 color, vec3 worldPos) {
+    if (uFogEnabled == 0) {
+        return color;
+    }
+    float distance = length(uCameraPos - worldPos);
+    float fog = clamp((distance - uFogStart) / (uFogEnd - uFogStart), 0.0, 1.0);
+    return mix(color, uFogColor, fog);
+}
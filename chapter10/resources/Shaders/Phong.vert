@@ -4,6 +4,9 @@
 uniform mat4 uWorldTransform;
 uniform mat4 uViewProj;
 
+// Drawing the water reflection clips everything below this height
+uniform float uClipHeight;
+
 // Attribute 0 is position, 1 is normal, 2 is tex coords.
 layout(location = 0) in vec3 inPosition;
 layout(location = 1) in vec3 inNormal;
@@ -28,6 +31,9 @@ void main() {
     // Save world position
     fragWorldPos = pos.xyz;
 
+    // Only used while GL_CLIP_DISTANCE0 is enabled
+    gl_ClipDistance[0] = pos.z - uClipHeight;
+
     // Transform to position world space, then clip space
     gl_Position = pos * uViewProj;
 
@@ -0,0 +1,41 @@
+#version 330
+
+// Uniform for view-proj
+uniform mat4 uViewProj;
+
+// Attribute 0 is position, 1 is normal, 2 is tex coords.
+layout(location = 0) in vec3 inPosition;
+layout(location = 1) in vec3 inNormal;
+layout(location = 2) in vec2 inTexCoord;
+// World transform of the instance (takes locations 3 to 6).
+// The rows of the matrix arrive as columns, so multiply from the left
+layout(location = 3) in mat4 inWorldTransform;
+
+// Any vertex outputs (other than position)
+out vec2 fragTexCoord;
+
+// Normal (in world space)
+out vec3 fragNormal;
+
+// Position (in world space)
+out vec3 fragWorldPos;
+
+void main() {
+    // Convert position to homogeneous coordinates
+    vec4 pos = vec4(inPosition, 1.0);
+
+    // Transform position to world space
+    pos = inWorldTransform * pos;
+
+    // Save world position
+    fragWorldPos = pos.xyz;
+
+    // Transform to position world space, then clip space
+    gl_Position = pos * uViewProj;
+
+    // Transform normal into world space (w = 0)
+    fragNormal = (inWorldTransform * vec4(inNormal, 0.0)).xyz;
+
+    // Pass along the texture coordinate to frag shader
+    fragTexCoord = inTexCoord;
+}
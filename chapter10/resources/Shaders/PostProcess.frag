@@ -0,0 +1,39 @@
+#version 330
+
+// Tex coord input from vertex shader
+in vec2 fragTexCoord;
+
+// This corresponds to the output color to the color buffer
+out vec4 outColor;
+
+// HDR scene color and its blurred bright parts
+uniform sampler2D uScene;
+uniform sampler2D uBloom;
+
+// Effects are on if not 0
+uniform int uBloomEnabled;
+uniform int uTonemapEnabled;
+uniform int uGammaEnabled;
+
+uniform float uBloomIntensity;
+uniform float uExposure;
+uniform float uGamma;
+
+void main() {
+    vec3 color = texture(uScene, fragTexCoord).rgb;
+
+    if (uBloomEnabled != 0) {
+        color += texture(uBloom, fragTexCoord).rgb * uBloomIntensity;
+    }
+
+    // Exposure tonemapping brings HDR colors back into [0, 1]
+    if (uTonemapEnabled != 0) {
+        color = vec3(1.0) - exp(-color * uExposure);
+    }
+
+    if (uGammaEnabled != 0) {
+        color = pow(color, vec3(1.0 / uGamma));
+    }
+
+    outColor = vec4(color, 1.0);
+}
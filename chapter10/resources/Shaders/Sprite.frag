@@ -8,8 +8,11 @@ out vec4 outColor;
 
 // This is used for the texture sampling
 uniform sampler2D uTexture;
+// Multiplies the texture color, alpha for fading
+uniform vec4 uTint;
 
 void main() {
     // Sample color from texture
     outColor = texture(uTexture, fragTexCoord);
+    outColor *= uTint;
 }
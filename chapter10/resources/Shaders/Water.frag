@@ -0,0 +1,56 @@
+#version 330
+
+#include "Lighting.glsl"
+
+// Position (in world space)
+in vec3 fragWorldPos;
+
+out vec4 outColor;
+
+// Tiling normal map, sampled twice scrolling in different directions
+uniform sampler2D uNormalMap;
+// Offsets of the two samples, animated by the component
+uniform vec2 uScroll0;
+uniform vec2 uScroll1;
+// World units covered by one repeat of the normal map
+uniform float uTileSize;
+
+// Scene drawn mirrored across the water plane, on if uReflectionEnabled is not 0
+uniform int uReflectionEnabled;
+uniform sampler2D uReflection;
+// Size of the drawable, to find the pixel in the reflection
+uniform vec2 uScreenSize;
+// How far the waves bend the reflection, in pixels
+uniform float uDistortion;
+
+uniform vec3 uWaterColor;
+// Seen in place of the reflection without it
+uniform vec3 uSkyColor;
+
+void main() {
+    // Average the two scrolling normals, the map is stored in [0, 1]
+    vec2 uv = fragWorldPos.xy / uTileSize;
+    vec3 n0 = texture(uNormalMap, uv + uScroll0).xyz * 2.0 - 1.0;
+    vec3 n1 = texture(uNormalMap, uv * 0.7 + uScroll1).xyz * 2.0 - 1.0;
+    vec3 N = normalize(n0 + n1);
+
+    vec3 V = normalize(uCameraPos - fragWorldPos);
+    vec3 L = normalize(-uDirLight.mDirection);
+
+    // Schlick's approximation, water reflects more at grazing angles
+    float fresnel = 0.02 + 0.98 * pow(1.0 - max(dot(N, V), 0.0), 5.0);
+
+    vec3 reflected = uSkyColor;
+    if (uReflectionEnabled != 0) {
+        vec2 screenUV = (gl_FragCoord.xy + N.xy * uDistortion) / uScreenSize;
+        reflected = texture(uReflection, screenUV).rgb;
+    }
+
+    vec3 diffuse = uWaterColor * (uAmbientLight + uDirLight.mDiffuseColor * max(dot(N, L), 0.0));
+    vec3 specular = uDirLight.mSpecColor * pow(max(dot(reflect(-L, N), V), 0.0), 200.0);
+    outColor.rgb = mix(diffuse, reflected, fresnel) + specular;
+    // See through more when looking down
+    outColor.a = mix(0.6, 1.0, fresnel);
+
+    outColor.rgb = ApplyFog(outColor.rgb, fragWorldPos);
+}
@@ -0,0 +1,17 @@
+#version 330
+
+// Uniforms for world transform and view-proj
+uniform mat4 uWorldTransform;
+uniform mat4 uViewProj;
+
+// Same layout as the meshes, only the position is used
+layout(location = 0) in vec3 inPosition;
+
+// Position (in world space)
+out vec3 fragWorldPos;
+
+void main() {
+    vec4 pos = vec4(inPosition, 1.0) * uWorldTransform;
+    fragWorldPos = pos.xyz;
+    gl_Position = pos * uViewProj;
+}
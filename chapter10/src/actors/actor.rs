@@ -1,16 +1,26 @@
 use core::f32;
 use std::{
+    any::Any,
     cell::RefCell,
-    rc::Rc,
+    rc::{Rc, Weak},
     sync::atomic::{AtomicU32, Ordering},
 };
 
 use crate::{
-    components::component::{Component, State as ComponentState},
+    components::{
+        command_buffer::{CommandBuffer, OwnerCommand, OwnerSnapshot},
+        component::{self, Component, State as ComponentState},
+        health_component::HealthComponent,
+    },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{asset_manager::AssetManager, entity_manager::EntityManager},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        input_system::InputState, transform_changes, world_bounds::BoundsPolicy,
+    },
 };
 
+use super::actor_builder::ActorBuilder;
+
 static ID: AtomicU32 = AtomicU32::new(0);
 
 pub fn generate_id() -> u32 {
@@ -28,44 +38,32 @@ pub enum State {
 
 pub trait Actor {
     /// Update function called from Game (not overridable)
-    fn update(&mut self, delta_time: f32) {
+    fn update(&mut self, delta_time: f32, commands: &mut CommandBuffer) {
         if *self.get_state() == State::Active {
             self.compute_world_transform();
-            self.update_component(delta_time);
+            self.update_component(delta_time, commands);
             self.update_actor(delta_time);
             self.compute_world_transform();
         }
     }
 
     /// Updates all the components attached to the actor (not overridable)
-    fn update_component(&mut self, delta_time: f32) {
-        let mut changes = vec![];
-        let actor_info = (
-            self.get_position().clone(),
-            self.get_rotation().clone(),
-            self.get_forward(),
-            self.get_world_transform().clone(),
-            self.get_right(),
-        );
+    /// Components read a snapshot of the actor and queue their changes, which are applied
+    /// to the actor once they all updated. Changes of other actors are left in commands
+    fn update_component(&mut self, delta_time: f32, commands: &mut CommandBuffer) {
+        let snapshot = OwnerSnapshot::new(self);
 
         for component in self.get_cocmponents() {
-            let change = component.borrow_mut().update(delta_time, &actor_info);
-            changes.push(change);
+            component
+                .borrow_mut()
+                .update(delta_time, &snapshot, commands);
         }
 
-        for change in changes {
-            let (position, rotation, new_forward, hit_actors) = change;
-            if let Some(forward) = new_forward {
-                self.rotate_to_new_forward(forward);
-            }
-            if let Some(pos) = position {
-                self.set_position(pos);
-            }
-            if let Some(rot) = rotation {
-                self.set_rotation(rot);
-            }
-            for hit_actor in hit_actors {
-                hit_actor.borrow().hit_target();
+        for command in commands.drain_owner_commands() {
+            match command {
+                OwnerCommand::SetPosition(position) => self.set_position(position),
+                OwnerCommand::SetRotation(rotation) => self.set_rotation(rotation),
+                OwnerCommand::RotateToNewForward(forward) => self.rotate_to_new_forward(forward),
             }
         }
     }
@@ -74,31 +72,46 @@ pub trait Actor {
     fn update_actor(&mut self, delta_time: f32);
 
     // ProcessInput function called from Game (not overridable)
-    fn process_input(&mut self, key_state: &KeyboardState, mouse_state: &RelativeMouseState) {
+    fn process_input(&mut self, state: &InputState) {
         if *self.get_state() != State::Active {
             return;
         }
         for component in self.get_cocmponents() {
-            component.borrow_mut().process_input(&key_state);
+            component.borrow_mut().process_input(state);
         }
-        self.actor_input(key_state, mouse_state);
+        self.actor_input(state);
     }
 
     // Any actor-specific input code (overridable)
-    fn actor_input(&mut self, _key_state: &KeyboardState, _mouse_state: &RelativeMouseState) {}
+    fn actor_input(&mut self, _state: &InputState) {}
 
+    /// Does nothing unless the position, rotation, scale or parent changed,
+    /// so actors which never move, like the floor, are computed once
     fn compute_world_transform(&mut self) {
         if !self.get_recompute_world_transform() {
             return;
         }
 
+        let parent_transform = self
+            .get_parent()
+            .map(|parent| parent.borrow().get_world_transform().clone());
+        self.compute_world_transform_from(parent_transform);
+    }
+
+    /// Computes the world transform relative to the parent world transform (root if None)
+    fn compute_world_transform_from(&mut self, parent_transform: Option<Matrix4>) {
         self.set_recompute_world_transform(false);
 
         // Scale, then rotate, then translate
         let mut world_transform = Matrix4::create_scale(self.get_scale());
         world_transform *= Matrix4::create_from_quaternion(self.get_rotation());
         world_transform *= Matrix4::create_translation(self.get_position());
-        self.set_world_transform(world_transform);
+        // Then apply the parent transform
+        if let Some(parent_transform) = parent_transform {
+            world_transform *= parent_transform;
+        }
+        self.set_world_transform(world_transform.clone());
+        transform_changes::record(self.get_id());
 
         // Inform components world transform updated
         for component in self.get_cocmponents() {
@@ -111,6 +124,13 @@ pub trait Actor {
                 .borrow_mut()
                 .on_update_world_transform(&owner_info);
         }
+
+        // Children follow the new transform
+        for child in self.get_children() {
+            child
+                .borrow_mut()
+                .compute_world_transform_from(Some(world_transform.clone()));
+        }
     }
 
     fn rotate_to_new_forward(&mut self, forward: Vector3) {
@@ -172,6 +192,18 @@ pub trait Actor {
 
     fn get_cocmponents(&self) -> &Vec<Rc<RefCell<dyn Component>>>;
 
+    /// Position/rotation/scale are relative to the parent if there is one
+    fn get_parent(&self) -> Option<Rc<RefCell<dyn Actor>>>;
+
+    fn set_parent(&mut self, parent: Option<Weak<RefCell<dyn Actor>>>);
+
+    fn get_children(&self) -> &Vec<Rc<RefCell<dyn Actor>>>;
+
+    /// Use attach/detach instead to keep parent and child in sync
+    fn add_child(&mut self, child: Rc<RefCell<dyn Actor>>);
+
+    fn remove_child(&mut self, child_id: u32);
+
     fn clear_components(&mut self);
 
     /// Add/remove components
@@ -180,6 +212,33 @@ pub trait Actor {
     fn remove_component(&mut self, component: Rc<RefCell<dyn Component>>);
 
     fn hit_target(&self) {}
+
+    /// Called when a weapon damages the actor (overridable)
+    fn take_damage(&self, _amount: f32, _source: &Vector3) {}
+
+    /// Actors which can be damaged return their health (overridable)
+    fn get_health_component(&self) -> Option<&Rc<RefCell<HealthComponent>>> {
+        None
+    }
+
+    /// Static actors never move once placed, like the floor and the walls (overridable).
+    /// Their boxes are kept apart and never tested against each other
+    fn is_static(&self) -> bool {
+        false
+    }
+
+    /// What happens when it leaves the world bounds (overridable)
+    fn get_bounds_policy(&self) -> BoundsPolicy {
+        BoundsPolicy::Destroy
+    }
+
+    /// Name of the concrete type, for debug listings
+    fn get_type_name(&self) -> &'static str {
+        std::any::type_name::<Self>().rsplit("::").next().unwrap()
+    }
+
+    /// Concrete actor, to find actors by type (see downcast)
+    fn as_any(&self) -> &dyn Any;
 }
 
 macro_rules! impl_getters_setters {
@@ -188,6 +247,10 @@ macro_rules! impl_getters_setters {
             self.id
         }
 
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
         fn get_forward(&self) -> Vector3 {
             Vector3::transform(&Vector3::UNIT_X, &self.rotation)
         }
@@ -202,7 +265,6 @@ macro_rules! impl_getters_setters {
 
         fn set_world_transform(&mut self, world_transform: Matrix4) {
             self.world_transform = world_transform;
-            self.recompute_world_transform = true;
         }
 
         fn get_recompute_world_transform(&self) -> bool {
@@ -256,10 +318,32 @@ macro_rules! impl_getters_setters {
             self.components.clear();
         }
 
+        fn get_parent(&self) -> Option<Rc<RefCell<dyn Actor>>> {
+            self.parent.as_ref().and_then(|parent| parent.upgrade())
+        }
+
+        fn set_parent(&mut self, parent: Option<Weak<RefCell<dyn Actor>>>) {
+            self.parent = parent;
+            self.recompute_world_transform = true;
+        }
+
+        fn get_children(&self) -> &Vec<Rc<RefCell<dyn Actor>>> {
+            &self.children
+        }
+
+        fn add_child(&mut self, child: Rc<RefCell<dyn Actor>>) {
+            self.children.push(child);
+        }
+
+        fn remove_child(&mut self, child_id: u32) {
+            self.children
+                .retain(|child| child.borrow().get_id() != child_id);
+        }
+
         fn get_asset_manager(&self) -> &Rc<RefCell<AssetManager>> {
             cfg_if::cfg_if! {
                 if #[cfg(not(test))] {
-                    &self.asset_manager
+                    &self.context.asset_manager
                 } else {
                     panic!();
                 }
@@ -269,7 +353,7 @@ macro_rules! impl_getters_setters {
         fn get_entity_manager(&self) -> &Rc<RefCell<EntityManager>> {
             cfg_if::cfg_if! {
                 if #[cfg(not(test))] {
-                    &self.entity_manager
+                    &self.context.entity_manager
                 } else {
                     panic!();
                 }
@@ -297,14 +381,54 @@ macro_rules! impl_component_operation {
 
 pub(crate) use impl_component_operation;
 
+impl dyn Actor {
+    /// First component of type T attached to the actor
+    pub fn get_component<T: Component + 'static>(&self) -> Option<Rc<RefCell<T>>> {
+        self.get_cocmponents().iter().find_map(component::downcast)
+    }
+}
+
+/// Actor as its concrete type T, or None if it is of another type.
+/// An actor which is mutably borrowed, e.g. being updated, can't be checked and gives None
+pub fn downcast<T: Actor + 'static>(actor: &Rc<RefCell<dyn Actor>>) -> Option<Rc<RefCell<T>>> {
+    match actor.try_borrow() {
+        Ok(borrowed) if borrowed.as_any().is::<T>() => {}
+        _ => return None,
+    }
+
+    // The allocation holds a RefCell<T>, so only the vtable is dropped from the pointer
+    let raw = Rc::into_raw(actor.clone()) as *const RefCell<T>;
+    Some(unsafe { Rc::from_raw(raw) })
+}
+
 pub fn remove_actor(actor: Rc<RefCell<dyn Actor>>) {
     actor.borrow_mut().set_state(State::Dead);
+    // Children die with their parent
+    for child in actor.borrow().get_children() {
+        child.borrow_mut().set_state(State::Dead);
+    }
     for component in actor.borrow().get_cocmponents() {
         component.borrow_mut().set_state(ComponentState::Dead);
     }
     actor.borrow_mut().clear_components();
 }
 
+/// Attach child to parent. The child transform becomes relative to the parent
+pub fn attach(parent: Rc<RefCell<dyn Actor>>, child: Rc<RefCell<dyn Actor>>) {
+    detach(child.clone());
+    child.borrow_mut().set_parent(Some(Rc::downgrade(&parent)));
+    parent.borrow_mut().add_child(child);
+}
+
+/// Detach child from its parent. The child transform becomes relative to the world
+pub fn detach(child: Rc<RefCell<dyn Actor>>) {
+    let parent = child.borrow().get_parent();
+    if let Some(parent) = parent {
+        parent.borrow_mut().remove_child(child.borrow().get_id());
+    }
+    child.borrow_mut().set_parent(None);
+}
+
 macro_rules! impl_drop {
     () => {
         fn drop(&mut self) {
@@ -317,7 +441,6 @@ macro_rules! impl_drop {
 }
 
 pub(crate) use impl_drop;
-use sdl2::{keyboard::KeyboardState, mouse::RelativeMouseState};
 
 pub struct DefaultActor {
     id: u32,
@@ -328,15 +451,13 @@ pub struct DefaultActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
 }
 
 impl DefaultActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -346,15 +467,12 @@ impl DefaultActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager,
-            entity_manager: entity_manager.clone(),
+            parent: None,
+            children: vec![],
+            context: context.clone(),
         };
 
-        let result = Rc::new(RefCell::new(this));
-
-        entity_manager.borrow_mut().add_actor(result.clone());
-
-        result
+        ActorBuilder::new(context, this).build()
     }
 }
 
@@ -372,18 +490,25 @@ impl Drop for DefaultActor {
 
 #[cfg(test)]
 pub mod test {
-    use std::{cell::RefCell, rc::Rc};
+    use std::{
+        cell::RefCell,
+        rc::{Rc, Weak},
+    };
 
     use crate::{
         assert_near_eq,
-        components::component::{tests::TestComponent, Component, State as ComponentState},
+        components::{
+            circle_component::CircleComponent,
+            component::{tests::TestComponent, Component, State as ComponentState},
+            health_component::HealthComponent,
+        },
         math::{
             self, matrix4::Matrix4, quaternion::Quaternion, vector2::Vector2, vector3::Vector3,
         },
-        system::{asset_manager::AssetManager, entity_manager::EntityManager},
+        system::{asset_manager::AssetManager, entity_manager::EntityManager, transform_changes},
     };
 
-    use super::{generate_id, Actor, State};
+    use super::{attach, detach, generate_id, Actor, State};
 
     pub struct TestActor {
         id: u32,
@@ -394,6 +519,9 @@ pub mod test {
         scale: f32,
         rotation: Quaternion,
         components: Vec<Rc<RefCell<dyn Component>>>,
+        parent: Option<Weak<RefCell<dyn Actor>>>,
+        children: Vec<Rc<RefCell<dyn Actor>>>,
+        is_static: bool,
     }
 
     impl TestActor {
@@ -407,13 +535,26 @@ pub mod test {
                 scale: 1.0,
                 rotation: Quaternion::new(),
                 components: vec![],
+                parent: None,
+                children: vec![],
+                is_static: false,
             }
         }
+
+        pub fn new_static() -> Self {
+            let mut this = TestActor::new();
+            this.is_static = true;
+            this
+        }
     }
 
     impl Actor for TestActor {
         fn update_actor(&mut self, _delta_time: f32) {}
 
+        fn is_static(&self) -> bool {
+            self.is_static
+        }
+
         impl_getters_setters! {}
 
         impl_component_operation! {}
@@ -440,6 +581,22 @@ pub mod test {
         assert_eq!(test_component1.borrow().get_id(), actual.get_id());
     }
 
+    #[test]
+    fn test_get_component() {
+        let mut owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let _test_component = TestComponent::new(&mut owner, 100);
+        let health_component = HealthComponent::new(owner.clone(), 50.0);
+
+        let actual = owner.borrow().get_component::<HealthComponent>().unwrap();
+        assert!(Rc::ptr_eq(&health_component, &actual));
+        assert!(owner.borrow().get_component::<CircleComponent>().is_none());
+
+        // Components being updated can't be checked
+        let borrowed = health_component.borrow_mut();
+        assert!(owner.borrow().get_component::<HealthComponent>().is_none());
+        drop(borrowed);
+    }
+
     #[test]
     fn test_get_forward() {
         let expected = Vector2::new(1.0 / 2.0, 3.0_f32.sqrt() / 2.0);
@@ -454,4 +611,72 @@ pub mod test {
         assert_near_eq!(expected.x, actual.x, 0.001);
         assert_near_eq!(expected.y, actual.y, 0.001);
     }
+
+    #[test]
+    fn test_attach() {
+        let parent: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let child: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        parent
+            .borrow_mut()
+            .set_position(Vector3::new(100.0, 0.0, 0.0));
+        parent
+            .borrow_mut()
+            .set_rotation(Quaternion::from_axis_angle(
+                &Vector3::UNIT_Z,
+                math::basic::to_radians(90.0),
+            ));
+        child
+            .borrow_mut()
+            .set_position(Vector3::new(10.0, 0.0, 0.0));
+
+        attach(parent.clone(), child.clone());
+        parent.borrow_mut().compute_world_transform();
+
+        // Child offset is rotated with the parent
+        let actual = child.borrow().get_world_transform().get_translation();
+        assert_near_eq!(100.0, actual.x, 0.001);
+        assert_near_eq!(10.0, actual.y, 0.001);
+        assert_eq!(1, parent.borrow().get_children().len());
+
+        detach(child.clone());
+        child.borrow_mut().compute_world_transform();
+
+        let actual = child.borrow().get_world_transform().get_translation();
+        assert_near_eq!(10.0, actual.x, 0.001);
+        assert_near_eq!(0.0, actual.y, 0.001);
+        assert!(parent.borrow().get_children().is_empty());
+        assert!(child.borrow().get_parent().is_none());
+    }
+
+    #[test]
+    fn test_compute_world_transform_once() {
+        let parent: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let child: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        attach(parent.clone(), child.clone());
+        let parent_id = parent.borrow().get_id();
+        let child_id = child.borrow().get_id();
+
+        parent.borrow_mut().compute_world_transform();
+        assert!(transform_changes::was_changed(parent_id));
+        assert!(transform_changes::was_changed(child_id));
+
+        // Nothing moved, so nothing is computed again
+        transform_changes::clear();
+        parent.borrow_mut().compute_world_transform();
+        child.borrow_mut().compute_world_transform();
+        assert!(!parent.borrow().get_recompute_world_transform());
+        assert_eq!(0, transform_changes::get_changed_count());
+
+        // The child follows its parent
+        parent
+            .borrow_mut()
+            .set_position(Vector3::new(0.0, 50.0, 0.0));
+        parent.borrow_mut().compute_world_transform();
+        assert!(transform_changes::was_changed(child_id));
+        assert_near_eq!(
+            50.0,
+            child.borrow().get_world_transform().get_translation().y,
+            0.001
+        );
+    }
 }
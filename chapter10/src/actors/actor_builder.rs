@@ -0,0 +1,130 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{components::component::Component, system::engine_context::EngineContext};
+
+use super::actor::Actor;
+
+/// Component that can be added with ActorBuilder::with
+pub trait BuildComponent: Component + Sized + 'static {
+    /// What the component needs besides the owner and the engine systems
+    type Params;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>>;
+}
+
+/// Component whose allocation is kept in a pool and reused for new actors
+pub trait ReuseComponent: BuildComponent {
+    /// Reset the released component in place for the new owner and register it again
+    fn reuse(
+        this: &Rc<RefCell<Self>>,
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    );
+
+    /// Called once when the pool takes the component back, to stop what it still does
+    fn release(_this: &Rc<RefCell<Self>>) {}
+}
+
+/// Adds components to a new actor, then adds the actor to the EntityManager
+pub struct ActorBuilder<'a, T: Actor + 'static> {
+    actor: Rc<RefCell<T>>,
+    context: &'a EngineContext,
+}
+
+impl<'a, T: Actor + 'static> ActorBuilder<'a, T> {
+    pub fn new(context: &'a EngineContext, actor: T) -> Self {
+        Self {
+            actor: Rc::new(RefCell::new(actor)),
+            context,
+        }
+    }
+
+    /// Like new, but reuses a dead actor of the same type if one was released
+    pub fn pooled(context: &'a EngineContext, actor: T) -> Self {
+        let pooled = context
+            .entity_manager
+            .borrow_mut()
+            .get_actor_pool::<T>()
+            .acquire();
+        let actor = match pooled {
+            Some(pooled) => {
+                *pooled.borrow_mut() = actor;
+                pooled
+            }
+            None => {
+                let actor = Rc::new(RefCell::new(actor));
+                context
+                    .entity_manager
+                    .borrow_mut()
+                    .get_actor_pool::<T>()
+                    .add(actor.clone());
+                actor
+            }
+        };
+        Self { actor, context }
+    }
+
+    /// Add a component the actor doesn't need a handle to
+    pub fn with<C: BuildComponent>(self, params: C::Params) -> Self {
+        self.with_handle::<C>(params, |_, _| {})
+    }
+
+    /// Add a component, then configure it or keep its handle in the actor
+    pub fn with_handle<C: BuildComponent>(
+        self,
+        params: C::Params,
+        handle: impl FnOnce(&mut T, Rc<RefCell<C>>),
+    ) -> Self {
+        let component = C::build(self.actor.clone(), self.context, params);
+        handle(&mut self.actor.borrow_mut(), component);
+        self
+    }
+
+    /// Like with, but reuses a released component if there is one
+    pub fn with_pooled<C: ReuseComponent>(self, params: C::Params) -> Self {
+        self.with_pooled_handle::<C>(params, |_, _| {})
+    }
+
+    pub fn with_pooled_handle<C: ReuseComponent>(
+        self,
+        params: C::Params,
+        handle: impl FnOnce(&mut T, Rc<RefCell<C>>),
+    ) -> Self {
+        let pooled = self
+            .context
+            .entity_manager
+            .borrow_mut()
+            .get_component_pool::<C>()
+            .acquire();
+        let component = match pooled {
+            Some(component) => {
+                C::reuse(&component, self.actor.clone(), self.context, params);
+                component
+            }
+            None => {
+                let component = C::build(self.actor.clone(), self.context, params);
+                self.context
+                    .entity_manager
+                    .borrow_mut()
+                    .get_component_pool::<C>()
+                    .add(component.clone());
+                component
+            }
+        };
+        handle(&mut self.actor.borrow_mut(), component);
+        self
+    }
+
+    pub fn build(self) -> Rc<RefCell<T>> {
+        self.context
+            .entity_manager
+            .borrow_mut()
+            .add_actor(self.actor.clone());
+        self.actor
+    }
+}
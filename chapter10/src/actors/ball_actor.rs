@@ -1,4 +1,7 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
 
 use crate::{
     components::{
@@ -10,12 +13,15 @@ use crate::{
     },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        phys_world::PhysWorld,
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
     },
 };
 
-use super::actor::{self, generate_id, Actor, State};
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+    decal_actor::DecalActor,
+};
 
 pub struct BallActor {
     id: u32,
@@ -26,21 +32,22 @@ pub struct BallActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
     audio_component: Option<Rc<RefCell<AudioComponent>>>,
     ball_move: Option<Rc<RefCell<BallMove>>>,
     life_span: f32,
+    // The ball dies once it runs out of bounces
+    bounces_left: u32,
 }
 
 impl BallActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        phys_world: Rc<RefCell<PhysWorld>>,
-        player_id: u32,
-        audio_system: Rc<RefCell<AudioSystem>>,
-    ) -> Rc<RefCell<Self>> {
+    pub const SPEED: f32 = 1500.0;
+    const MAX_BOUNCES: u32 = 3;
+    const BOUNCE_EVENT: &'static str = "event:/Ding";
+
+    pub fn new(context: &EngineContext, player_id: u32) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -50,29 +57,56 @@ impl BallActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
+            parent: None,
+            children: vec![],
+            context: context.clone(),
             audio_component: None,
             ball_move: None,
             life_span: 2.0,
+            bounces_left: BallActor::MAX_BOUNCES,
         };
 
-        let result = Rc::new(RefCell::new(this));
-
-        let mesh_component = MeshComponent::new(result.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
-        mesh_component.borrow_mut().set_mesh(mesh);
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
+        ActorBuilder::pooled(context, this)
+            .with_pooled::<MeshComponent>(mesh)
+            .with_pooled_handle::<BallMove>(player_id, |ball, ball_move| {
+                ball_move.borrow_mut().set_forward_speed(BallActor::SPEED);
+                ball.ball_move = Some(ball_move);
+            })
+            .with_pooled_handle::<AudioComponent>((), |ball, audio_component| {
+                ball.audio_component = Some(audio_component);
+            })
+            .build()
+    }
 
-        let ball_move = BallMove::new(result.clone(), phys_world, player_id);
-        ball_move.borrow_mut().set_forward_speed(1500.0);
-        result.borrow_mut().ball_move = Some(ball_move);
+    /// Projectile factory for the standard ball launcher
+    pub fn spawn(context: &EngineContext, player_id: u32) -> Rc<RefCell<dyn Actor>> {
+        BallActor::new(context, player_id)
+    }
 
-        let audio_component = AudioComponent::new(result.clone(), audio_system);
-        result.borrow_mut().audio_component = Some(audio_component);
+    /// Projectile factory for a big, slow ball
+    pub fn spawn_heavy(context: &EngineContext, player_id: u32) -> Rc<RefCell<dyn Actor>> {
+        let ball = BallActor::new(context, player_id);
+        ball.borrow_mut().set_scale(2.5);
+        ball.borrow_mut().set_forward_speed(800.0);
+        ball.borrow_mut().set_damage(100.0);
+        ball
+    }
 
-        entity_manager.borrow_mut().add_actor(result.clone());
+    pub fn set_damage(&mut self, damage: f32) {
+        self.ball_move
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_damage(damage);
+    }
 
-        result
+    pub fn set_forward_speed(&mut self, speed: f32) {
+        self.ball_move
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_forward_speed(speed);
     }
 }
 
@@ -82,6 +116,27 @@ impl Actor for BallActor {
         if self.life_span < 0.0 {
             self.set_state(State::Dead);
         }
+
+        let bounces = self.ball_move.as_ref().unwrap().borrow_mut().take_bounces();
+        for bounce in bounces {
+            let decal = DecalActor::new(&self.context);
+            decal.borrow_mut().place(&bounce.point, &bounce.normal);
+
+            self.audio_component
+                .as_ref()
+                .unwrap()
+                .borrow_mut()
+                .play_event(
+                    BallActor::BOUNCE_EVENT,
+                    &Matrix4::create_translation(&bounce.point),
+                );
+
+            self.bounces_left = self.bounces_left.saturating_sub(1);
+            if self.bounces_left == 0 {
+                self.set_state(State::Dead);
+                break;
+            }
+        }
     }
 
     fn hit_target(&self) {
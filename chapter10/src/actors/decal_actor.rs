@@ -0,0 +1,131 @@
+use core::f32;
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        component::{Component, State as ComponentState},
+        mesh_component::MeshComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Flat mark left on a surface where a ball bounced, fading after a while
+pub struct DecalActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    life_span: f32,
+}
+
+impl DecalActor {
+    const LIFE_SPAN: f32 = 3.0;
+    // Time spent shrinking at the end of the life span
+    const FADE_TIME: f32 = 0.5;
+    // Plane mesh is 100 units wide
+    const START_SCALE: f32 = 0.15;
+    // Lift from the surface to avoid z-fighting
+    const SURFACE_OFFSET: f32 = 0.5;
+
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: DecalActor::START_SCALE,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            life_span: DecalActor::LIFE_SPAN,
+        };
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Plane.gpmesh");
+        ActorBuilder::pooled(context, this)
+            .with_pooled::<MeshComponent>(mesh)
+            .build()
+    }
+
+    /// Place the decal flat on the surface at point
+    pub fn place(&mut self, point: &Vector3, normal: &Vector3) {
+        self.set_position(point.clone() + normal.clone() * DecalActor::SURFACE_OFFSET);
+        self.set_rotation(compute_surface_rotation(normal));
+    }
+}
+
+/// Rotation turning the plane mesh (facing +z) to face along normal
+fn compute_surface_rotation(normal: &Vector3) -> Quaternion {
+    let dot = Vector3::dot(&Vector3::UNIT_Z, normal);
+    if dot > 0.9999 {
+        Quaternion::IDENTITY
+    } else if dot < -0.9999 {
+        Quaternion::from_axis_angle(&Vector3::UNIT_X, f32::consts::PI)
+    } else {
+        let axis = Vector3::cross(&Vector3::UNIT_Z, normal).normalize();
+        Quaternion::from_axis_angle(&axis, dot.acos())
+    }
+}
+
+impl Actor for DecalActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        self.life_span -= delta_time;
+        if self.life_span < 0.0 {
+            self.set_state(State::Dead);
+            return;
+        }
+
+        if self.life_span < DecalActor::FADE_TIME {
+            self.set_scale(DecalActor::START_SCALE * self.life_span / DecalActor::FADE_TIME);
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for DecalActor {
+    actor::impl_drop! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::compute_surface_rotation;
+
+    #[test]
+    fn test_compute_surface_rotation() {
+        for normal in [
+            Vector3::UNIT_Z,
+            Vector3::NEGATIVE_UNIT_Z,
+            Vector3::NEGATIVE_UNIT_X,
+            Vector3::UNIT_Y,
+        ] {
+            let rotation = compute_surface_rotation(&normal);
+            let facing = Vector3::transform(&Vector3::UNIT_Z, &rotation);
+            assert_near_eq!(1.0, Vector3::dot(&facing, &normal), 0.001);
+        }
+    }
+}
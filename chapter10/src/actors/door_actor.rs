@@ -0,0 +1,304 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    collision::aabb::AABB,
+    components::{
+        audio_component::AudioComponent,
+        box_component::BoxComponent,
+        component::{Component, State as ComponentState},
+        interactable_component::InteractableComponent,
+        mesh_component::MeshComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        timer_manager::TimerHandle,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// How a door gets out of the way
+#[derive(Debug, Clone, PartialEq)]
+pub enum DoorMotion {
+    // Moves by the offset, e.g. up into the ceiling
+    Slide(Vector3),
+    // Turns about +z around its position by the angle in radians
+    Swing(f32),
+}
+
+/// Block opening while the player stands in its trigger volume, or after the player uses it.
+/// Its box moves with it, so it blocks the player and shots wherever it is
+pub struct DoorActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    audio_component: Option<Rc<RefCell<AudioComponent>>>,
+    interactable_component: Option<Rc<RefCell<InteractableComponent>>>,
+    motion: DoorMotion,
+    closed_position: Vector3,
+    // Half size of the volume around the closed position, None if it only opens when used
+    trigger_extents: Option<Vector3>,
+    // Goes from 0.0 (closed) to 1.0 (open)
+    openness: f32,
+    // Toggled each time the player uses the door
+    used_open: bool,
+    // Seconds it stays open after being used, None to stay until used again
+    auto_close_time: Option<f32>,
+    auto_close_timer: Option<TimerHandle>,
+    // Whether it went toward open last frame, to play a sound when that changes
+    opening: bool,
+}
+
+impl DoorActor {
+    // Seconds to fully open or close
+    const OPEN_TIME: f32 = 0.75;
+    const OPEN_EVENT: &'static str = "event:/DoorOpen";
+    const CLOSE_EVENT: &'static str = "event:/DoorClose";
+
+    pub fn new(context: &EngineContext, motion: DoorMotion) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 200.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            audio_component: None,
+            interactable_component: None,
+            motion,
+            closed_position: Vector3::ZERO,
+            trigger_extents: None,
+            openness: 0.0,
+            used_open: false,
+            auto_close_time: None,
+            auto_close_timer: None,
+            opening: false,
+        };
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Cube.gpmesh");
+        let result = ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with::<BoxComponent>(mesh.get_box().clone())
+            .with_handle::<AudioComponent>((), |door, audio_component| {
+                door.audio_component = Some(audio_component);
+            })
+            .with_handle::<InteractableComponent>(
+                (
+                    "Open door",
+                    Box::new(|owner| {
+                        if let Some(door) = actor::downcast::<DoorActor>(owner) {
+                            DoorActor::use_door(&door);
+                        }
+                    }),
+                ),
+                |door, interactable_component| {
+                    door.interactable_component = Some(interactable_component);
+                },
+            )
+            .build();
+        context
+            .entity_manager
+            .borrow_mut()
+            .add_tag(result.clone(), EntityManager::SOLID_TAG);
+
+        result
+    }
+
+    /// Place the door, closed
+    pub fn set_closed_position(&mut self, position: Vector3) {
+        self.set_position(position.clone());
+        self.closed_position = position;
+        self.openness = 0.0;
+    }
+
+    /// Open while the player is within extents of the closed position
+    pub fn set_trigger(&mut self, extents: Vector3) {
+        self.trigger_extents = Some(extents);
+    }
+
+    pub fn set_auto_close(&mut self, auto_close_time: Option<f32>) {
+        self.auto_close_time = auto_close_time;
+    }
+
+    /// Toggle it as the player does, then schedule closing it if it opened
+    fn use_door(door: &Rc<RefCell<DoorActor>>) {
+        let mut this = door.borrow_mut();
+        this.toggle();
+
+        let timer_manager = this.context.timer_manager.clone();
+        let mut timer_manager = timer_manager.borrow_mut();
+        if let Some(handle) = this.auto_close_timer.take() {
+            timer_manager.cancel(handle);
+        }
+        let auto_close_time = match this.auto_close_time {
+            Some(auto_close_time) if this.used_open => auto_close_time,
+            _ => return,
+        };
+        let weak_door = Rc::downgrade(door);
+        let handle = timer_manager.schedule(
+            auto_close_time,
+            None,
+            Box::new(move || {
+                if let Some(door) = weak_door.upgrade() {
+                    let mut door = door.borrow_mut();
+                    door.auto_close_timer = None;
+                    if door.used_open {
+                        door.toggle();
+                    }
+                }
+            }),
+        );
+        this.auto_close_timer = Some(handle);
+    }
+
+    /// Open or close it as if the player used it, nothing happens if it already is
+    pub fn set_used_open(&mut self, open: bool) {
+        if self.used_open != open {
+            self.toggle();
+        }
+    }
+
+    /// Open if it was used closed, close if it was used open
+    pub fn toggle(&mut self) {
+        self.used_open = !self.used_open;
+        let prompt = if self.used_open {
+            "Close door"
+        } else {
+            "Open door"
+        };
+        self.interactable_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_prompt(prompt);
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.openness >= 1.0
+    }
+
+    fn is_player_in_trigger(&self) -> bool {
+        let extents = match &self.trigger_extents {
+            Some(extents) => extents,
+            None => return false,
+        };
+        let fps_actor = match self.context.entity_manager.borrow().get_fps_actor() {
+            Some(fps_actor) => fps_actor.clone(),
+            None => return false,
+        };
+        let trigger = AABB::new(
+            self.closed_position.clone() - extents.clone(),
+            self.closed_position.clone() + extents.clone(),
+        );
+        let player_position = fps_actor.borrow().get_position().clone();
+        trigger.contains(&player_position)
+    }
+
+    fn apply_openness(&mut self) {
+        let f = ease_openness(self.openness);
+        match &self.motion {
+            DoorMotion::Slide(offset) => {
+                let position = self.closed_position.clone() + offset.clone() * f;
+                self.set_position(position);
+            }
+            DoorMotion::Swing(angle) => {
+                let rotation = Quaternion::from_axis_angle(&Vector3::UNIT_Z, angle * f);
+                self.set_rotation(rotation);
+            }
+        }
+    }
+}
+
+/// Openness after moving toward open or closed for delta_time
+fn step_openness(openness: f32, opening: bool, delta_time: f32, open_time: f32) -> f32 {
+    let step = delta_time / open_time;
+    if opening {
+        (openness + step).min(1.0)
+    } else {
+        (openness - step).max(0.0)
+    }
+}
+
+/// Starts and stops smoothly
+fn ease_openness(openness: f32) -> f32 {
+    openness * openness * (3.0 - 2.0 * openness)
+}
+
+impl Actor for DoorActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        let opening = self.used_open || self.is_player_in_trigger();
+        if opening != self.opening {
+            self.opening = opening;
+            let event = if opening {
+                DoorActor::OPEN_EVENT
+            } else {
+                DoorActor::CLOSE_EVENT
+            };
+            self.audio_component
+                .as_ref()
+                .unwrap()
+                .borrow_mut()
+                .play_event(event, self.get_world_transform());
+        }
+
+        let openness = step_openness(self.openness, opening, delta_time, DoorActor::OPEN_TIME);
+        // Can't be used again until it stops
+        let is_moving = openness != self.openness;
+        self.interactable_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_enabled(!is_moving);
+        if is_moving {
+            self.openness = openness;
+            // BoxComponent follows when the world transform is recomputed
+            self.apply_openness();
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for DoorActor {
+    actor::impl_drop! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_near_eq;
+
+    use super::{ease_openness, step_openness};
+
+    #[test]
+    fn test_step_openness() {
+        assert_near_eq!(0.5, step_openness(0.0, true, 0.5, 1.0), 0.001);
+        assert_eq!(1.0, step_openness(0.9, true, 0.5, 1.0));
+        assert_eq!(0.0, step_openness(0.1, false, 0.5, 1.0));
+
+        assert_eq!(0.0, ease_openness(0.0));
+        assert_near_eq!(0.5, ease_openness(0.5), 0.001);
+        assert_eq!(1.0, ease_openness(1.0));
+    }
+}
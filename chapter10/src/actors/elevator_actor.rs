@@ -0,0 +1,168 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        audio_component::AudioComponent,
+        box_component::BoxComponent,
+        component::{Component, State as ComponentState},
+        mesh_component::MeshComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Platform going up and down between floors, waiting a bit at each one.
+/// The player standing on it rides along, see FPSActor::stand_on_ground
+pub struct ElevatorActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    audio_component: Option<Rc<RefCell<AudioComponent>>>,
+    // Heights of the platform at each floor, visited in order then back to the first
+    floors: Vec<f32>,
+    // Floor it is at or going to
+    floor: usize,
+    // In units/sec
+    speed: f32,
+    // Seconds left before leaving the floor, moving once it reaches 0.0
+    wait: f32,
+}
+
+impl ElevatorActor {
+    // Seconds spent at each floor
+    const WAIT_TIME: f32 = 3.0;
+    const START_EVENT: &'static str = "event:/ElevatorStart";
+    const STOP_EVENT: &'static str = "event:/ElevatorStop";
+
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 2.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            audio_component: None,
+            floors: vec![],
+            floor: 0,
+            speed: 150.0,
+            wait: ElevatorActor::WAIT_TIME,
+        };
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Plane.gpmesh");
+        let result = ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with::<BoxComponent>(mesh.get_box().clone())
+            .with_handle::<AudioComponent>((), |elevator, audio_component| {
+                elevator.audio_component = Some(audio_component);
+            })
+            .build();
+        context
+            .entity_manager
+            .borrow_mut()
+            .add_tag(result.clone(), EntityManager::SOLID_TAG);
+
+        result
+    }
+
+    /// Stand at the first floor at x/y, waiting to go to the next one
+    pub fn set_floors(&mut self, x: f32, y: f32, floors: Vec<f32>) {
+        let z = floors.first().copied().unwrap_or(0.0);
+        self.set_position(Vector3::new(x, y, z));
+        self.floors = floors;
+        self.floor = 0;
+        self.wait = ElevatorActor::WAIT_TIME;
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    fn play_event(&self, name: &str) {
+        self.audio_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .play_event(name, self.get_world_transform());
+    }
+}
+
+/// Height after moving toward target by at most step, without passing it
+fn move_toward(height: f32, target: f32, step: f32) -> f32 {
+    if (target - height).abs() <= step {
+        target
+    } else {
+        height + step * (target - height).signum()
+    }
+}
+
+impl Actor for ElevatorActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        if self.floors.len() < 2 {
+            return;
+        }
+
+        if self.wait > 0.0 {
+            self.wait -= delta_time;
+            if self.wait <= 0.0 {
+                self.floor = (self.floor + 1) % self.floors.len();
+                self.play_event(ElevatorActor::START_EVENT);
+            }
+            return;
+        }
+
+        // BoxComponent follows when the world transform is recomputed
+        let position = self.get_position().clone();
+        let target = self.floors[self.floor];
+        let z = move_toward(position.z, target, self.speed * delta_time);
+        self.set_position(Vector3::new(position.x, position.y, z));
+        if z == target {
+            self.wait = ElevatorActor::WAIT_TIME;
+            self.play_event(ElevatorActor::STOP_EVENT);
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for ElevatorActor {
+    actor::impl_drop! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::move_toward;
+
+    #[test]
+    fn test_move_toward() {
+        assert_eq!(10.0, move_toward(0.0, 100.0, 10.0));
+        assert_eq!(90.0, move_toward(100.0, 0.0, 10.0));
+        // Stops at the target instead of passing it
+        assert_eq!(100.0, move_toward(95.0, 100.0, 10.0));
+    }
+}
@@ -0,0 +1,294 @@
+use core::f32;
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        box_component::BoxComponent,
+        component::{Component, State as ComponentState},
+        health_component::HealthComponent,
+        mesh_component::MeshComponent,
+        move_component::{DefaultMoveComponent, MoveComponent},
+        perception_component::PerceptionComponent,
+    },
+    math::{
+        matrix4::Matrix4,
+        quaternion::Quaternion,
+        steering::{Arrive, Separation, Steering, SteeringInput},
+        vector3::Vector3,
+    },
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        world_bounds::BoundsPolicy,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Walks toward the target once it sees it and hits it when close enough
+pub struct EnemyActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    move_component: Option<Rc<RefCell<DefaultMoveComponent>>>,
+    perception_component: Option<Rc<RefCell<PerceptionComponent>>>,
+    health_component: Option<Rc<RefCell<HealthComponent>>>,
+    // Time until the next attack
+    attack_cooldown: f32,
+    // Arrives at the goal while keeping away from the other enemies
+    steering: Steering,
+}
+
+impl EnemyActor {
+    const MOVE_SPEED: f32 = 250.0;
+    // Radians/sec
+    const TURN_SPEED: f32 = f32::consts::PI;
+    const ATTACK_RANGE: f32 = 150.0;
+    const ATTACK_DAMAGE: f32 = 10.0;
+    const ATTACK_INTERVAL: f32 = 1.0;
+    // Enemies closer than this push each other away
+    const SEPARATION_RADIUS: f32 = 150.0;
+
+    pub fn new(context: &EngineContext, target: Rc<RefCell<dyn Actor>>) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 100.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            move_component: None,
+            perception_component: None,
+            health_component: None,
+            attack_cooldown: 0.0,
+            steering: Steering::new()
+                .with(
+                    Arrive {
+                        slowing_distance: EnemyActor::ATTACK_RANGE * 2.0,
+                    },
+                    1.0,
+                )
+                .with(
+                    Separation {
+                        radius: EnemyActor::SEPARATION_RADIUS,
+                    },
+                    1.5,
+                ),
+        };
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Cube.gpmesh");
+        let result = ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with::<BoxComponent>(mesh.get_box().clone())
+            .with_handle::<DefaultMoveComponent>((), |enemy, move_component| {
+                enemy.move_component = Some(move_component);
+            })
+            .with_handle::<PerceptionComponent>(target, |enemy, perception_component| {
+                enemy.perception_component = Some(perception_component);
+            })
+            .with_handle::<HealthComponent>(60.0, |enemy, health_component| {
+                enemy.health_component = Some(health_component);
+            })
+            .build();
+        context
+            .entity_manager
+            .borrow_mut()
+            .add_tag(result.clone(), EntityManager::ENEMY_TAG);
+
+        result
+    }
+
+    pub fn get_perception_component(&self) -> &Rc<RefCell<PerceptionComponent>> {
+        self.perception_component.as_ref().unwrap()
+    }
+
+    /// Steer toward the goal around the other enemies and walk until in attack range
+    fn pursue(&mut self, goal: &Vector3) {
+        let mut to_goal = goal.clone() - self.get_position().clone();
+        // Stay on the floor
+        to_goal.z = 0.0;
+        let distance = to_goal.length();
+
+        let position = self.get_position().clone();
+        let forward = self.get_forward();
+        let mut velocity = self.steering.compute(&SteeringInput {
+            position: position.clone(),
+            velocity: forward.clone() * EnemyActor::MOVE_SPEED,
+            max_speed: EnemyActor::MOVE_SPEED,
+            target: Some(Vector3::new(goal.x, goal.y, position.z)),
+            threat: None,
+            neighbors: self.get_neighbors(),
+        });
+        velocity.z = 0.0;
+
+        // Face the goal when stopped in range, the steering when walking
+        let (direction, forward_speed) = if distance > EnemyActor::ATTACK_RANGE {
+            (velocity.clone(), velocity.length())
+        } else {
+            (to_goal, 0.0)
+        };
+        let angle = compute_turn_angle(&forward, &direction);
+        // Slow down the turn when almost facing the goal to avoid overshooting
+        let angular_speed = (angle * 5.0).clamp(-EnemyActor::TURN_SPEED, EnemyActor::TURN_SPEED);
+
+        let mut move_component = self.move_component.as_ref().unwrap().borrow_mut();
+        move_component.set_angular_speed(angular_speed);
+        move_component.set_forward_speed(forward_speed);
+    }
+
+    /// Positions of the other enemies
+    fn get_neighbors(&self) -> Vec<Vector3> {
+        // Fails for this enemy, which is being updated
+        self.context
+            .entity_manager
+            .borrow()
+            .actors_with_tag(EntityManager::ENEMY_TAG)
+            .iter()
+            .filter_map(|enemy| Some(enemy.try_borrow().ok()?.get_position().clone()))
+            .collect()
+    }
+
+    fn stop(&mut self) {
+        let mut move_component = self.move_component.as_ref().unwrap().borrow_mut();
+        move_component.set_angular_speed(0.0);
+        move_component.set_forward_speed(0.0);
+    }
+}
+
+/// Signed angle around the z axis from forward to direction, in [-PI, PI]
+fn compute_turn_angle(forward: &Vector3, direction: &Vector3) -> f32 {
+    let cross = forward.x * direction.y - forward.y * direction.x;
+    let dot = forward.x * direction.x + forward.y * direction.y;
+    cross.atan2(dot)
+}
+
+impl Actor for EnemyActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        if self.get_health_component().unwrap().borrow().is_dead() {
+            self.set_state(State::Dead);
+            return;
+        }
+
+        self.attack_cooldown -= delta_time;
+
+        let (can_see_target, goal, target) = {
+            let perception = self.perception_component.as_ref().unwrap().borrow();
+            (
+                perception.can_see_target(),
+                perception.get_last_known_position().cloned(),
+                perception.get_target(),
+            )
+        };
+        let goal = match goal {
+            Some(goal) => goal,
+            None => {
+                self.stop();
+                return;
+            }
+        };
+
+        let mut to_goal = goal.clone() - self.get_position().clone();
+        to_goal.z = 0.0;
+        let in_range = to_goal.length() <= EnemyActor::ATTACK_RANGE;
+
+        match target {
+            Some(target) if can_see_target && in_range => {
+                if self.attack_cooldown <= 0.0 {
+                    target
+                        .borrow()
+                        .take_damage(EnemyActor::ATTACK_DAMAGE, self.get_position());
+                    self.attack_cooldown = EnemyActor::ATTACK_INTERVAL;
+                }
+                self.pursue(&goal);
+            }
+            _ if !can_see_target && in_range => {
+                // Reached where the target was last seen without finding it
+                self.perception_component
+                    .as_ref()
+                    .unwrap()
+                    .borrow_mut()
+                    .forget();
+                self.stop();
+            }
+            _ => self.pursue(&goal),
+        }
+    }
+
+    fn hit_target(&self) {
+        if let Some(hud) = self.context.entity_manager.borrow().get_hud() {
+            hud.borrow_mut().notify_hit();
+        }
+    }
+
+    fn take_damage(&self, amount: f32, source: &Vector3) {
+        self.get_health_component()
+            .unwrap()
+            .borrow_mut()
+            .take_damage(amount, source);
+    }
+
+    fn get_health_component(&self) -> Option<&Rc<RefCell<HealthComponent>>> {
+        self.health_component.as_ref()
+    }
+
+    fn get_bounds_policy(&self) -> BoundsPolicy {
+        BoundsPolicy::Clamp
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for EnemyActor {
+    actor::impl_drop! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::compute_turn_angle;
+
+    #[test]
+    fn test_compute_turn_angle() {
+        let forward = Vector3::UNIT_X;
+        assert_near_eq!(0.0, compute_turn_angle(&forward, &Vector3::UNIT_X), 0.001);
+        // Turning toward +y is positive
+        assert_near_eq!(
+            PI * 0.5,
+            compute_turn_angle(&forward, &Vector3::new(0.0, 10.0, 0.0)),
+            0.001
+        );
+        assert_near_eq!(
+            -PI * 0.5,
+            compute_turn_angle(&forward, &Vector3::new(0.0, -10.0, 0.0)),
+            0.001
+        );
+        assert_near_eq!(
+            PI,
+            compute_turn_angle(&forward, &Vector3::new(-10.0, 0.0, 0.0)).abs(),
+            0.001
+        );
+    }
+}
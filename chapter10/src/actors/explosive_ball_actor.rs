@@ -0,0 +1,185 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        audio_component::AudioComponent,
+        ball_move::BallMove,
+        camera_shake,
+        component::{Component, State as ComponentState},
+        mesh_component::MeshComponent,
+        move_component::MoveComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+    impact_actor::ImpactActor,
+};
+
+/// Ball exploding on the first impact (or at the end of its life span),
+/// damaging every actor within the blast radius
+pub struct ExplosiveBallActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    audio_component: Option<Rc<RefCell<AudioComponent>>>,
+    ball_move: Option<Rc<RefCell<BallMove>>>,
+    player_id: u32,
+    life_span: f32,
+}
+
+impl ExplosiveBallActor {
+    const BLAST_RADIUS: f32 = 300.0;
+    // Damage at the center, falling off linearly to the edge of the blast
+    const BLAST_DAMAGE: f32 = 100.0;
+    const EXPLOSION_EVENT: &'static str = "event:/Explosion2D";
+    // Trauma of the player's camera right at the blast, none past the shake radius
+    const EXPLOSION_TRAUMA: f32 = 0.8;
+    const SHAKE_RADIUS: f32 = 1500.0;
+
+    pub fn new(context: &EngineContext, player_id: u32) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 1.5,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            audio_component: None,
+            ball_move: None,
+            player_id,
+            life_span: 2.0,
+        };
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
+        ActorBuilder::pooled(context, this)
+            .with_pooled::<MeshComponent>(mesh)
+            .with_pooled_handle::<BallMove>(player_id, |ball, ball_move| {
+                // The blast deals the damage, not the impact itself
+                ball_move.borrow_mut().set_forward_speed(1000.0);
+                ball_move.borrow_mut().set_damage(0.0);
+                ball.ball_move = Some(ball_move);
+            })
+            .with_pooled_handle::<AudioComponent>((), |ball, audio_component| {
+                ball.audio_component = Some(audio_component);
+            })
+            .build()
+    }
+
+    /// Projectile factory for the grenade launcher
+    pub fn spawn(context: &EngineContext, player_id: u32) -> Rc<RefCell<dyn Actor>> {
+        ExplosiveBallActor::new(context, player_id)
+    }
+
+    fn explode(&mut self, center: Vector3) {
+        self.set_state(State::Dead);
+
+        let actors = self
+            .context
+            .phys_world
+            .borrow()
+            .query_sphere(&center, ExplosiveBallActor::BLAST_RADIUS);
+        for actor in actors {
+            let borrowed_actor = actor.borrow();
+            if borrowed_actor.get_id() == self.player_id {
+                continue;
+            }
+            let distance = (borrowed_actor.get_position().clone() - center.clone()).length();
+            let damage = compute_blast_damage(
+                distance,
+                ExplosiveBallActor::BLAST_RADIUS,
+                ExplosiveBallActor::BLAST_DAMAGE,
+            );
+            borrowed_actor.take_damage(damage, &center);
+            borrowed_actor.hit_target();
+        }
+
+        let impact = ImpactActor::new(&self.context);
+        impact.borrow_mut().set_position(center.clone());
+
+        if let Some(fps_actor) = self.context.entity_manager.borrow().get_fps_actor() {
+            let fps_actor = fps_actor.borrow();
+            let distance = (fps_actor.get_position().clone() - center.clone()).length();
+            fps_actor.add_camera_trauma(camera_shake::trauma_at_distance(
+                ExplosiveBallActor::EXPLOSION_TRAUMA,
+                distance,
+                ExplosiveBallActor::SHAKE_RADIUS,
+            ));
+        }
+
+        self.audio_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .play_event(
+                ExplosiveBallActor::EXPLOSION_EVENT,
+                &Matrix4::create_translation(&center),
+            );
+    }
+}
+
+/// Damage at distance from the center of the blast.
+/// Actors touching the blast take at least a quarter of the damage.
+fn compute_blast_damage(distance: f32, radius: f32, damage: f32) -> f32 {
+    let falloff = 1.0 - (distance / radius).clamp(0.0, 1.0);
+    damage * falloff.max(0.25)
+}
+
+impl Actor for ExplosiveBallActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        let bounces = self.ball_move.as_ref().unwrap().borrow_mut().take_bounces();
+        if let Some(bounce) = bounces.first() {
+            self.explode(bounce.point.clone());
+            return;
+        }
+
+        self.life_span -= delta_time;
+        if self.life_span < 0.0 {
+            self.explode(self.get_position().clone());
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for ExplosiveBallActor {
+    actor::impl_drop! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_near_eq;
+
+    use super::compute_blast_damage;
+
+    #[test]
+    fn test_compute_blast_damage() {
+        assert_near_eq!(100.0, compute_blast_damage(0.0, 300.0, 100.0), 0.001);
+        assert_near_eq!(50.0, compute_blast_damage(150.0, 300.0, 100.0), 0.001);
+        // Box overlaps the blast while the center is out of it
+        assert_near_eq!(25.0, compute_blast_damage(400.0, 300.0, 100.0), 0.001);
+    }
+}
@@ -1,31 +1,49 @@
 use core::f32;
-use std::{cell::RefCell, rc::Rc};
-
-use sdl2::{
-    keyboard::{KeyboardState, Scancode},
-    mouse::RelativeMouseState,
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
 };
 
+use sdl2::{controller::Button, keyboard::Scancode, mouse::MouseButton};
+
 use crate::{
-    collision::aabb::AABB,
+    collision::{aabb::AABB, line_segment::LineSegment},
     components::{
         audio_component::AudioComponent,
-        box_component::BoxComponent,
+        box_component::{BoxComponent, Material},
         component::{Component, State as ComponentState},
+        first_person_model_component::FirstPersonModelComponent,
         fps_camera::FPSCamera,
+        health_component::HealthComponent,
+        interactable_component::InteractableComponent,
+        inventory_component::InventoryComponent,
         mesh_component::MeshComponent,
         move_component::{DefaultMoveComponent, MoveComponent},
+        powerup::Powerup,
+        rigid_body_component::RigidBodyComponent,
+        weapon_component::{FireMode, Weapon, WeaponComponent},
     },
-    math::{self, matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    math::{self, matrix4::Matrix4, quaternion::Quaternion, vector2::Vector2, vector3::Vector3},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        phys_world::PhysWorld, renderer::Renderer, sound_event::SoundEvent,
+        asset_manager::AssetManager,
+        config::KeyBindings,
+        crash_report::log,
+        engine_context::EngineContext,
+        entity_manager::EntityManager,
+        input_system::{ButtonState, InputState},
+        sound_event::SoundEvent,
+        timer_manager::TimerHandle,
+        world_bounds::BoundsPolicy,
     },
 };
 
 use super::{
     actor::{self, generate_id, Actor, DefaultActor, State},
+    actor_builder::{ActorBuilder, BuildComponent},
     ball_actor::BallActor,
+    explosive_ball_actor::ExplosiveBallActor,
+    impact_actor::ImpactActor,
+    plane_actor::PlaneActor,
 };
 
 pub struct FPSActor {
@@ -37,29 +55,65 @@ pub struct FPSActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
-    audio_system: Rc<RefCell<AudioSystem>>,
-    renderer: Rc<RefCell<Renderer>>,
-    phys_world: Rc<RefCell<PhysWorld>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
     move_component: Option<Rc<RefCell<DefaultMoveComponent>>>,
     camera_component: Option<Rc<RefCell<FPSCamera>>>,
     mesh_component: Option<Rc<RefCell<MeshComponent>>>,
     audio_component: Option<Rc<RefCell<AudioComponent>>>,
     box_component: Option<Rc<RefCell<BoxComponent>>>,
+    weapon_component: Option<Rc<RefCell<WeaponComponent>>>,
+    health_component: Option<Rc<RefCell<HealthComponent>>>,
+    inventory_component: Option<Rc<RefCell<InventoryComponent>>>,
     fps_model: Option<Rc<RefCell<DefaultActor>>>,
+    fps_model_component: Option<Rc<RefCell<FirstPersonModelComponent>>>,
     foot_step: Option<Rc<RefCell<SoundEvent>>>,
-    last_foot_step: f32,
+    // Repeats the footstep while walking
+    foot_step_timer: Option<TimerHandle>,
+    // Material the footstep sound is set for
+    foot_step_material: Material,
+    // Multiplier of the right stick aim speed
+    stick_sensitivity: f32,
+    invert_y: bool,
+    // Multiplier of the mouse look speed
+    mouse_sensitivity: f32,
+    key_bindings: KeyBindings,
+    // Interactable under the cross-hair and within reach
+    focused_interactable: Option<Rc<RefCell<InteractableComponent>>>,
+    // Body carried in front of the camera while E is held
+    carried: Option<Rc<RefCell<RigidBodyComponent>>>,
 }
 
 impl FPSActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        audio_system: Rc<RefCell<AudioSystem>>,
-        renderer: Rc<RefCell<Renderer>>,
-        phys_world: Rc<RefCell<PhysWorld>>,
-    ) -> Rc<RefCell<Self>> {
+    // Below the center, a bit past the bottom of the collision box
+    const GROUND_CHECK_DISTANCE: f32 = 150.0;
+    // Seconds between footsteps
+    const FOOT_STEP_INTERVAL: f32 = 0.5;
+    // Height of the actor position above the terrain
+    const STAND_HEIGHT: f32 = 100.0;
+    // How far above or below the feet the top of a solid can be to be stood on
+    const STEP_HEIGHT: f32 = 20.0;
+    // Pitch the view kicks up by with each shot, in radians
+    const SHOT_RECOIL: f32 = 0.02;
+    const SHOT_TRAUMA: f32 = 0.15;
+    // Field of view multiplier while aiming down sights
+    const ADS_ZOOM: f32 = 0.6;
+    // How far from the camera interactables can be used
+    const INTERACT_RANGE: f32 = 200.0;
+    // Move speed multiplier of the haste powerup
+    const HASTE_MULTIPLIER: f32 = 1.5;
+    // Hitscan damage multiplier of the double damage powerup
+    const DOUBLE_DAMAGE_MULTIPLIER: f32 = 2.0;
+    // Carried bodies are held this far in front of the camera
+    const CARRY_DISTANCE: f32 = 150.0;
+    const CARRY_STIFFNESS: f32 = 12.0;
+    const CARRY_MAX_SPEED: f32 = 1500.0;
+    // A carried body stuck this far from where it's held is dropped
+    const CARRY_BREAK_DISTANCE: f32 = 300.0;
+    const THROW_IMPULSE: f32 = 2000.0;
+
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -69,67 +123,186 @@ impl FPSActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
-            audio_system: audio_system.clone(),
-            renderer: renderer.clone(),
-            phys_world: phys_world.clone(),
+            parent: None,
+            children: vec![],
+            context: context.clone(),
             move_component: None,
             camera_component: None,
             mesh_component: None,
             audio_component: None,
             box_component: None,
+            weapon_component: None,
+            health_component: None,
+            inventory_component: None,
             fps_model: None,
+            fps_model_component: None,
             foot_step: None,
-            last_foot_step: 0.0,
+            foot_step_timer: None,
+            foot_step_material: Material::Default,
+            stick_sensitivity: 1.0,
+            invert_y: false,
+            mouse_sensitivity: 1.0,
+            key_bindings: KeyBindings::new(),
+            focused_interactable: None,
+            carried: None,
         };
 
-        let result = Rc::new(RefCell::new(this));
+        let weapons = vec![
+            Weapon::new(
+                "Ball Launcher",
+                FireMode::Projectile(BallActor::spawn),
+                4.0,
+                50,
+                "event:/Shot",
+            ),
+            Weapon::new(
+                "Rifle",
+                FireMode::Hitscan {
+                    range: 5000.0,
+                    damage: 20.0,
+                },
+                8.0,
+                120,
+                "event:/Shot",
+            ),
+            Weapon::new(
+                "Heavy Launcher",
+                FireMode::Projectile(BallActor::spawn_heavy),
+                1.0,
+                10,
+                "event:/Shot",
+            ),
+            Weapon::new(
+                "Grenade Launcher",
+                FireMode::Projectile(ExplosiveBallActor::spawn),
+                1.0,
+                8,
+                "event:/Shot",
+            ),
+        ];
+        let collision = AABB::new(
+            Vector3::new(-25.0, -25.0, -87.5),
+            Vector3::new(25.0, 25.0, 87.5),
+        );
 
-        let move_component = DefaultMoveComponent::new(result.clone());
-        result.borrow_mut().move_component = Some(move_component);
+        let result = ActorBuilder::new(context, this)
+            .with_handle::<DefaultMoveComponent>((), |fps, move_component| {
+                fps.move_component = Some(move_component);
+            })
+            .with_handle::<AudioComponent>((), |fps, audio_component| {
+                let sound_event = audio_component
+                    .borrow_mut()
+                    .play_event("event:/Footstep", fps.get_world_transform());
+                if let Err(e) = sound_event.borrow_mut().set_paused(true) {
+                    log!("Failed to pause footstep: {}", e);
+                }
+                fps.audio_component = Some(audio_component);
+                fps.foot_step = Some(sound_event);
+            })
+            .with_handle::<FPSCamera>((), |fps, fps_camera| {
+                fps.camera_component = Some(fps_camera);
+            })
+            .with_handle::<BoxComponent>(collision, |fps, box_component| {
+                box_component.borrow_mut().set_should_rotate(false);
+                fps.box_component = Some(box_component);
+            })
+            .with_handle::<WeaponComponent>(weapons, |fps, weapon_component| {
+                fps.weapon_component = Some(weapon_component);
+            })
+            .with_handle::<HealthComponent>(100.0, |fps, health_component| {
+                fps.health_component = Some(health_component);
+            })
+            .with_handle::<InventoryComponent>((), |fps, inventory_component| {
+                fps.inventory_component = Some(inventory_component);
+            })
+            .build();
+
+        let fps_model = DefaultActor::new(context);
+        fps_model.borrow_mut().set_scale(0.75);
+        actor::attach(result.clone(), fps_model.clone());
 
-        let audio_component = AudioComponent::new(result.clone(), audio_system.clone());
-        let sound_event = audio_component
-            .borrow_mut()
-            .play_event("event:/Footstep", &result.borrow().get_world_transform());
-        sound_event.borrow_mut().set_paused(true);
-        result.borrow_mut().audio_component = Some(audio_component);
-        result.borrow_mut().foot_step = Some(sound_event);
+        // Positions of FPS model relative to actor position, at the hip and while aiming
+        let fps_model_component = FirstPersonModelComponent::new(
+            fps_model.clone(),
+            Vector3::new(10.0, 10.0, -10.0),
+            Vector3::new(10.0, 0.0, -5.0),
+        );
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Rifle.gpmesh");
+        let mesh_component = MeshComponent::build(fps_model.clone(), context, mesh);
 
-        let fps_camera = FPSCamera::new(result.clone(), renderer, audio_system);
-        result.borrow_mut().camera_component = Some(fps_camera);
+        let mut borrowed_result = result.borrow_mut();
+        borrowed_result.fps_model_component = Some(fps_model_component);
+        borrowed_result.fps_model = Some(fps_model);
+        borrowed_result.mesh_component = Some(mesh_component);
+        drop(borrowed_result);
 
-        let fps_model = DefaultActor::new(asset_manager.clone(), entity_manager.clone());
-        fps_model.borrow_mut().set_scale(0.75);
+        result
+    }
 
-        let mesh_component = MeshComponent::new(fps_model.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Rifle.gpmesh");
-        mesh_component.borrow_mut().set_mesh(mesh);
+    pub fn set_stick_sensitivity(&mut self, stick_sensitivity: f32) {
+        self.stick_sensitivity = stick_sensitivity;
+    }
 
-        result.borrow_mut().fps_model = Some(fps_model);
-        result.borrow_mut().mesh_component = Some(mesh_component);
+    pub fn set_mouse_sensitivity(&mut self, mouse_sensitivity: f32) {
+        self.mouse_sensitivity = mouse_sensitivity;
+    }
 
-        let box_component = BoxComponent::new(result.clone(), phys_world);
-        let collision = AABB::new(
-            Vector3::new(-25.0, -25.0, -87.5),
-            Vector3::new(25.0, 25.0, 87.5),
-        );
-        box_component.borrow_mut().set_object_box(collision);
-        box_component.borrow_mut().set_should_rotate(false);
-        result.borrow_mut().box_component = Some(box_component);
+    pub fn get_mouse_sensitivity(&self) -> f32 {
+        self.mouse_sensitivity
+    }
 
-        entity_manager.borrow_mut().add_actor(result.clone());
+    pub fn set_key_bindings(&mut self, key_bindings: KeyBindings) {
+        self.key_bindings = key_bindings;
+    }
 
-        result
+    pub fn get_key_bindings(&self) -> &KeyBindings {
+        &self.key_bindings
+    }
+
+    pub fn toggle_invert_y(&mut self) {
+        self.invert_y = !self.invert_y;
     }
 
     pub fn set_foot_step_surface(&mut self, value: f32) {
         // Pause here because the way I setup the parameter in FMOD
         // changing it will play a footstep
-        let foot_step = self.foot_step.clone().unwrap();
-        foot_step.borrow_mut().set_paused(true);
-        foot_step.borrow_mut().set_parameter("Surface", value);
+        let mut foot_step = self.foot_step.as_ref().unwrap().borrow_mut();
+        if let Err(e) = foot_step
+            .set_paused(true)
+            .and_then(|_| foot_step.set_parameter("Surface", value))
+        {
+            log!("Failed to set footstep surface: {}", e);
+        }
+    }
+
+    fn stop_foot_steps(&mut self) {
+        if let Some(handle) = self.foot_step_timer.take() {
+            self.context.timer_manager.borrow_mut().cancel(handle);
+        }
+    }
+
+    /// Match the footstep sound to the material of the box beneath the feet
+    fn update_foot_step_surface(&mut self) {
+        let position = self.get_position().clone();
+        let line = LineSegment::new(
+            position.clone(),
+            position - Vector3::UNIT_Z * FPSActor::GROUND_CHECK_DISTANCE,
+        );
+        let material = match self
+            .context
+            .phys_world
+            .borrow()
+            .segment_cast_ignoring(&line, self.get_id())
+        {
+            Some(info) => info.material,
+            // In the air, keep the last surface
+            None => return,
+        };
+
+        if material != self.foot_step_material {
+            self.foot_step_material = material;
+            self.set_foot_step_surface(material.get_foot_step_surface());
+        }
     }
 
     pub fn fix_collision(&mut self) {
@@ -142,35 +315,21 @@ impl FPSActor {
         let position = self.get_position();
         let mut new_positions = vec![];
 
-        let planes = self.entity_manager.borrow().get_planes().clone();
-        for plane in planes {
-            // Do we collide with this PlaneActor ?
-            let borrowed_plane = plane.borrow();
-            let plane_box = borrowed_plane.get_box().borrow().get_world_box().clone();
+        let mut obstacles: Vec<AABB> = self
+            .context
+            .entity_manager
+            .borrow()
+            .actors_of_type::<PlaneActor>()
+            .iter()
+            .map(|plane| plane.borrow().get_box().borrow().get_world_box().clone())
+            .collect();
+        obstacles.extend(self.get_solid_boxes());
+        for plane_box in obstacles {
+            // Do we collide with this PlaneActor or solid ?
             if AABB::intersect(&player_box, &plane_box) {
-                // Calculate all our differences
-                let dx1 = plane_box.max.x - player_box.min.x;
-                let dx2 = plane_box.min.x - player_box.max.x;
-                let dy1 = plane_box.max.y - player_box.min.y;
-                let dy2 = plane_box.min.y - player_box.max.y;
-                let dz1 = plane_box.max.z - player_box.min.z;
-                let dz2 = plane_box.min.z - player_box.max.z;
-
-                // Set dx to whichever of dx1/dx2 dy1/dy2 dz1/dz2 have a lower abs
-                let dx = if dx1.abs() < dx2.abs() { dx1 } else { dx2 };
-                let dy = if dy1.abs() < dy2.abs() { dy1 } else { dy2 };
-                let dz = if dz1.abs() < dz2.abs() { dz1 } else { dz2 };
-
                 // Whichever is closest, adjust x/y position
-                let mut new_position = position.clone();
-                if dx.abs() <= dy.abs() && dx.abs() <= dz.abs() {
-                    new_position.x += dx;
-                } else if dy.abs() <= dx.abs() && dy.abs() <= dz.abs() {
-                    new_position.y += dy;
-                } else {
-                    new_position.z += dz;
-                }
-                new_positions.push(new_position);
+                let separation = player_box.get_min_separation(&plane_box);
+                new_positions.push(position.clone() + separation);
             }
         }
 
@@ -186,131 +345,544 @@ impl FPSActor {
         }
     }
 
+    /// World boxes of the moving solids, like doors and elevators
+    fn get_solid_boxes(&self) -> Vec<AABB> {
+        self.context
+            .entity_manager
+            .borrow()
+            .actors_with_tag(EntityManager::SOLID_TAG)
+            .iter()
+            .filter_map(|solid| solid.borrow().get_component::<BoxComponent>())
+            .map(|box_component| box_component.borrow().get_world_box().clone())
+            .collect()
+    }
+
+    /// Top of the highest solid under the player within a step of the feet, e.g. an elevator
+    fn get_platform_height(&self, position: &Vector3) -> Option<f32> {
+        let feet = position.z - FPSActor::STAND_HEIGHT;
+        self.get_solid_boxes()
+            .iter()
+            .filter(|solid_box| {
+                (solid_box.min.x..=solid_box.max.x).contains(&position.x)
+                    && (solid_box.min.y..=solid_box.max.y).contains(&position.y)
+                    && (solid_box.max.z - feet).abs() <= FPSActor::STEP_HEIGHT
+            })
+            .map(|solid_box| solid_box.max.z)
+            .reduce(f32::max)
+    }
+
+    /// Follow the height of the terrain or of the platform beneath, if there is one.
+    /// Platforms moving up or down carry the player along this way
+    fn stand_on_ground(&mut self) {
+        let position = self.get_position().clone();
+        let terrain_height = self
+            .context
+            .phys_world
+            .borrow()
+            .get_ground_height(position.x, position.y);
+        let platform_height = self.get_platform_height(&position);
+        let ground_height = match terrain_height
+            .into_iter()
+            .chain(platform_height)
+            .reduce(f32::max)
+        {
+            Some(ground_height) => ground_height,
+            None => return,
+        };
+
+        self.set_position(Vector3::new(
+            position.x,
+            position.y,
+            ground_height + FPSActor::STAND_HEIGHT,
+        ));
+    }
+
     pub fn shoot(&mut self) {
-        // Get start point (in center of screen on near plane)
-        let mut screen_point = Vector3::ZERO;
-        let start = self.renderer.borrow().unproject(screen_point.clone());
-        // Get end point (in center of screen, between near and far)
-        screen_point.z = 0.9;
-        let end = self.renderer.borrow().unproject(screen_point.clone());
-        // Get direction vector
-        let mut dir = end.clone() - start.clone();
-        dir.normalize_mut();
-        // Spawn a ball
-        let ball = BallActor::new(
-            self.asset_manager.clone(),
-            self.entity_manager.clone(),
-            self.phys_world.clone(),
-            self.get_id(),
-            self.audio_system.clone(),
-        );
-        ball.borrow_mut().set_position(start + dir.clone() * 20.0);
-        // Rotate the ball to face new direction
-        ball.borrow_mut().rotate_to_new_forward(dir);
+        // Paused while driving
+        if *self.get_state() != State::Active {
+            return;
+        }
+
+        // Carrying something, throw it instead
+        if self.carried.is_some() {
+            let (_, dir) = self
+                .context
+                .renderer
+                .borrow()
+                .get_screen_direction(&Vector2::ZERO);
+            self.release(dir * FPSActor::THROW_IMPULSE);
+            return;
+        }
+
+        let weapon_component = self.weapon_component.clone().unwrap();
+        let mut borrowed_weapon = weapon_component.borrow_mut();
+        let weapon = match borrowed_weapon.try_fire() {
+            Some(weapon) => weapon,
+            None => return,
+        };
+
+        // Through the center of the screen
+        let (start, dir) = self
+            .context
+            .renderer
+            .borrow()
+            .get_screen_direction(&Vector2::ZERO);
+
+        match weapon.get_fire_mode() {
+            FireMode::Projectile(factory) => {
+                // Spawn a projectile
+                let projectile = factory(&self.context, self.get_id());
+                projectile
+                    .borrow_mut()
+                    .set_position(start + dir.clone() * 20.0);
+                // Rotate the projectile to face new direction
+                projectile.borrow_mut().rotate_to_new_forward(dir);
+            }
+            FireMode::Hitscan { range, damage } => {
+                self.fire_hitscan(start, dir, *range, *damage);
+            }
+        }
+
+        self.fps_model_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .kick();
+
+        // Kick the view up, it settles back by itself
+        let mut camera_component = self.camera_component.as_ref().unwrap().borrow_mut();
+        let shake = camera_component.get_shake_mut();
+        shake.add_recoil(FPSActor::SHOT_RECOIL);
+        shake.add_trauma(FPSActor::SHOT_TRAUMA);
+
         // Play shooting sound
         self.audio_component
             .as_ref()
             .unwrap()
             .borrow_mut()
-            .play_event("event:/Shot", &self.get_world_transform());
+            .play_event(weapon.get_fire_event(), &self.get_world_transform());
+    }
+
+    /// Shake the view, e.g. from an explosion nearby
+    pub fn add_camera_trauma(&self, amount: f32) {
+        if let Some(camera_component) = &self.camera_component {
+            camera_component
+                .borrow_mut()
+                .get_shake_mut()
+                .add_trauma(amount);
+        }
+    }
+
+    /// Use the interactable under the cross-hair, if any
+    pub fn interact(&mut self) {
+        if let Some(interactable) = &self.focused_interactable {
+            interactable.borrow_mut().interact();
+        }
+    }
+
+    /// Leave the world while driving a vehicle, which takes the input and the view.
+    /// The player stops updating and its box and rifle are taken out
+    pub fn enter_vehicle(&mut self) {
+        self.release(Vector3::ZERO);
+        if let Some(interactable) = self.focused_interactable.take() {
+            interactable.borrow_mut().set_highlighted(false);
+        }
+        self.stop_foot_steps();
+        if let Err(e) = self
+            .foot_step
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_paused(true)
+        {
+            log!("Failed to pause footstep: {}", e);
+        }
+        self.mesh_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_visible(false);
+        self.context
+            .phys_world
+            .borrow_mut()
+            .remove_box(self.box_component.as_ref().unwrap());
+        self.context.renderer.borrow_mut().set_zoom(1.0);
+        self.set_state(State::Paused);
+    }
+
+    /// Back in the world at position, the ground height is found on the next update
+    pub fn exit_vehicle(&mut self, position: Vector3) {
+        self.set_position(position);
+        self.compute_world_transform();
+        self.context
+            .phys_world
+            .borrow_mut()
+            .add_box(self.box_component.clone().unwrap());
+        self.mesh_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_visible(true);
+        self.set_state(State::Active);
+    }
+
+    /// Pick up the rigid body under the cross-hair within reach, if any
+    pub fn grab(&mut self) {
+        let (start, dir) = self
+            .context
+            .renderer
+            .borrow()
+            .get_screen_direction(&Vector2::ZERO);
+        let line = LineSegment::new(start.clone(), start + dir * FPSActor::INTERACT_RANGE);
+        let collision_info = self
+            .context
+            .phys_world
+            .borrow()
+            .segment_cast_ignoring(&line, self.get_id());
+        let rigid_body = match collision_info
+            .and_then(|info| info.actor.borrow().get_component::<RigidBodyComponent>())
+        {
+            Some(rigid_body) => rigid_body,
+            None => return,
+        };
+
+        rigid_body.borrow_mut().set_held(true);
+        self.carried = Some(rigid_body);
+    }
+
+    /// Let go of the carried body, if any, with an impulse to throw it
+    pub fn release(&mut self, impulse: Vector3) {
+        if let Some(rigid_body) = self.carried.take() {
+            let mut rigid_body = rigid_body.borrow_mut();
+            rigid_body.set_held(false);
+            rigid_body.apply_impulse(&impulse);
+        }
+    }
+
+    /// Pull the carried body to the point in front of the camera
+    fn update_carried(&mut self) {
+        let rigid_body = match &self.carried {
+            Some(rigid_body) => rigid_body.clone(),
+            None => return,
+        };
+        let position = {
+            let rigid_body = rigid_body.borrow();
+            let owner = rigid_body.get_owner().borrow();
+            if *owner.get_state() == State::Dead {
+                None
+            } else {
+                Some(owner.get_position().clone())
+            }
+        };
+        let position = match position {
+            Some(position) => position,
+            None => {
+                self.carried = None;
+                return;
+            }
+        };
+
+        let (start, dir) = self
+            .context
+            .renderer
+            .borrow()
+            .get_screen_direction(&Vector2::ZERO);
+        let target = start + dir * FPSActor::CARRY_DISTANCE;
+        // Stuck behind something
+        if (target.clone() - position).length() > FPSActor::CARRY_BREAK_DISTANCE {
+            self.release(Vector3::ZERO);
+            return;
+        }
+        rigid_body.borrow_mut().pull_toward(
+            &target,
+            FPSActor::CARRY_STIFFNESS,
+            FPSActor::CARRY_MAX_SPEED,
+        );
+    }
+
+    pub fn get_focused_interactable(&self) -> Option<&Rc<RefCell<InteractableComponent>>> {
+        self.focused_interactable.as_ref()
+    }
+
+    /// Find the enabled interactable the camera looks at within reach and highlight it
+    fn update_focused_interactable(&mut self) {
+        let (start, dir) = self
+            .context
+            .renderer
+            .borrow()
+            .get_screen_direction(&Vector2::ZERO);
+        let line = LineSegment::new(start.clone(), start + dir * FPSActor::INTERACT_RANGE);
+        let collision_info = self
+            .context
+            .phys_world
+            .borrow()
+            .segment_cast_ignoring(&line, self.get_id());
+        let focused = collision_info
+            .and_then(|info| info.actor.borrow().get_component::<InteractableComponent>())
+            .filter(|interactable| interactable.borrow().is_enabled());
+
+        let previous = self.focused_interactable.take();
+        if let Some(previous) = &previous {
+            previous.borrow_mut().set_highlighted(false);
+        }
+        if let Some(interactable) = &focused {
+            interactable.borrow_mut().set_highlighted(true);
+            let is_new = previous.map_or(true, |previous| !Rc::ptr_eq(&previous, interactable));
+            if is_new {
+                log!("[E] {}", interactable.borrow().get_prompt());
+            }
+        }
+        self.focused_interactable = focused;
+    }
+
+    fn fire_hitscan(&self, start: Vector3, dir: Vector3, range: f32, damage: f32) {
+        let damage = if self.has_powerup(Powerup::DoubleDamage) {
+            damage * FPSActor::DOUBLE_DAMAGE_MULTIPLIER
+        } else {
+            damage
+        };
+        let line = LineSegment::new(start.clone(), start.clone() + dir * range);
+        let collision_info = self
+            .context
+            .phys_world
+            .borrow()
+            .segment_cast_ignoring(&line, self.get_id());
+
+        if let Some(info) = collision_info {
+            // Show impact where the shot landed
+            let impact = ImpactActor::new(&self.context);
+            impact.borrow_mut().set_position(info.point);
+            info.actor.borrow().take_damage(damage, &start);
+            info.actor.borrow().hit_target();
+        }
+    }
+
+    pub fn get_inventory_component(&self) -> Option<&Rc<RefCell<InventoryComponent>>> {
+        self.inventory_component.as_ref()
+    }
+
+    fn has_powerup(&self, powerup: Powerup) -> bool {
+        self.inventory_component
+            .as_ref()
+            .unwrap()
+            .borrow()
+            .has_powerup(powerup)
+    }
+
+    pub fn switch_weapon(&mut self, index: usize) {
+        self.weapon_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .switch_weapon(index);
+    }
+
+    pub fn cycle_weapon(&mut self, step: i32) {
+        self.weapon_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .cycle_weapon(step);
+    }
+}
+
+fn play_foot_step(foot_step: &RefCell<SoundEvent>) {
+    let mut foot_step = foot_step.borrow_mut();
+    if let Err(e) = foot_step
+        .set_paused(false)
+        .and_then(|_| foot_step.restart())
+    {
+        log!("Failed to play footstep: {}", e);
     }
 }
 
 impl Actor for FPSActor {
-    fn update_actor(&mut self, delta_time: f32) {
+    fn update_actor(&mut self, _delta_time: f32) {
         self.fix_collision();
+        self.stand_on_ground();
+        self.update_focused_interactable();
+        self.update_carried();
 
-        // Play the footstep if we're moving and haven't recently
-        self.last_foot_step -= delta_time;
-        if !math::basic::near_zero(
+        // Footsteps from the first step on, as long as we're moving
+        let walking = !math::basic::near_zero(
             self.move_component
                 .clone()
                 .unwrap()
                 .borrow()
                 .get_forward_speed(),
             0.001,
-        ) && self.last_foot_step <= 0.0
-        {
-            let foot_step = self.foot_step.clone().unwrap();
-            foot_step.borrow_mut().set_paused(false);
-            foot_step.borrow_mut().restart();
-            self.last_foot_step = 0.5;
+        );
+        if walking {
+            self.update_foot_step_surface();
+            if self.foot_step_timer.is_none() {
+                let foot_step = self.foot_step.clone().unwrap();
+                play_foot_step(&foot_step);
+                self.foot_step_timer = Some(self.context.timer_manager.borrow_mut().schedule(
+                    FPSActor::FOOT_STEP_INTERVAL,
+                    Some(FPSActor::FOOT_STEP_INTERVAL),
+                    Box::new(move || play_foot_step(&foot_step)),
+                ));
+            }
+        } else {
+            self.stop_foot_steps();
         }
 
-        // Update position of FPS model relative to actor position
-        let model_offset = Vector3::new(10.0, 10.0, -10.0);
-        let mut model_position = self.get_position().clone();
-        model_position += self.get_forward() * model_offset.x;
-        model_position += self.get_right() * model_offset.y;
-        model_position.z += model_offset.z;
-
-        let fps_model = self.fps_model.as_ref().unwrap();
-        fps_model.borrow_mut().set_position(model_position);
+        // Bob the FPS model while walking
+        let move_component = self.move_component.as_ref().unwrap();
+        let move_speed = {
+            let borrowed_move = move_component.borrow();
+            borrowed_move
+                .get_forward_speed()
+                .hypot(borrowed_move.get_strafe_speed())
+        };
+        self.fps_model_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_move_speed(move_speed);
 
-        // Initialize rotation to actor rotation
-        let mut q = self.get_rotation().clone();
+        // Narrow the view while aiming down sights
+        let is_aiming = self
+            .fps_model_component
+            .as_ref()
+            .unwrap()
+            .borrow()
+            .is_aiming();
+        let zoom = if is_aiming { FPSActor::ADS_ZOOM } else { 1.0 };
+        self.context.renderer.borrow_mut().set_zoom(zoom);
 
-        // Rotate by pitch from camera
+        // FPS model follows the actor as a child, only pitch from camera is applied here
+        let fps_model = self.fps_model.as_ref().unwrap();
         let camera_component = self.camera_component.as_ref().unwrap();
-        q = Quaternion::concatenate(
-            &q,
-            &Quaternion::from_axis_angle(&self.get_right(), camera_component.borrow().get_pitch()),
-        );
-        fps_model.borrow_mut().set_rotation(q);
+        fps_model
+            .borrow_mut()
+            .set_rotation(Quaternion::from_axis_angle(
+                &Vector3::UNIT_Y,
+                camera_component.borrow().get_pitch(),
+            ));
     }
 
-    fn actor_input(&mut self, key_state: &KeyboardState, mouse_state: &RelativeMouseState) {
+    fn actor_input(&mut self, state: &InputState) {
         let mut forward_speed = 0.0;
         let mut strafe_speed = 0.0;
 
-        if key_state.is_scancode_pressed(Scancode::W) {
+        let key_bindings = &self.key_bindings;
+        if state.keyboard.get_key_value(key_bindings.forward) {
             forward_speed += 400.0;
         }
-        if key_state.is_scancode_pressed(Scancode::S) {
+        if state.keyboard.get_key_value(key_bindings.back) {
             forward_speed -= 400.0;
         }
-        if key_state.is_scancode_pressed(Scancode::A) {
+        if state.keyboard.get_key_value(key_bindings.left) {
             strafe_speed -= 400.0;
         }
-        if key_state.is_scancode_pressed(Scancode::D) {
+        if state.keyboard.get_key_value(key_bindings.right) {
             strafe_speed += 400.0;
         }
 
+        // Left stick moves (stick up is negative y)
+        let left_stick = state.controller.get_left_stick();
+        forward_speed = (forward_speed - left_stick.y * 400.0).clamp(-400.0, 400.0);
+        strafe_speed = (strafe_speed + left_stick.x * 400.0).clamp(-400.0, 400.0);
+        if self.has_powerup(Powerup::Haste) {
+            forward_speed *= FPSActor::HASTE_MULTIPLIER;
+            strafe_speed *= FPSActor::HASTE_MULTIPLIER;
+        }
+
         let move_component = self.move_component.clone().unwrap();
         move_component.borrow_mut().set_forward_speed(forward_speed);
         move_component.borrow_mut().set_strafe_speed(strafe_speed);
 
         // Mouse movement
         // Get relative movement from SDL
-        let x = mouse_state.x();
-        let y = mouse_state.y();
+        let x = state.mouse.get_position().x;
+        let y = state.mouse.get_position().y;
 
         // Assume mouse movement is usually between -500 and +500
         let max_mouse_speed = 500.0;
 
+        // Slower while zoomed in, so the aim moves as fast on screen
+        let mouse_sensitivity = self.mouse_sensitivity * self.context.renderer.borrow().get_zoom();
+
         // Rotation/sec at maximum speed
-        let max_angular_speed = f32::consts::PI * 8.0;
+        let max_angular_speed = f32::consts::PI * 8.0 * mouse_sensitivity;
 
         let mut angular_speed = 0.0;
-        if x != 0 {
+        if x != 0.0 {
             // Convert to ~[-1.0, 1.0]
-            angular_speed = x as f32 / max_mouse_speed;
+            angular_speed = x / max_mouse_speed;
             // Multiply by rotation/sec
             angular_speed *= max_angular_speed;
         }
+
+        // Right stick aims, already in [-1.0, 1.0]
+        let right_stick = state.controller.get_right_stick();
+        let max_stick_speed = f32::consts::PI * 2.0 * self.stick_sensitivity;
+        angular_speed += right_stick.x * max_stick_speed;
         move_component.borrow_mut().set_angular_speed(angular_speed);
 
         // Compute pitch
-        let max_pitch_speed = f32::consts::PI * 8.0;
+        let max_pitch_speed = f32::consts::PI * 8.0 * mouse_sensitivity;
         let mut pitch_speed = 0.0;
-        if y != 0 {
+        if y != 0.0 {
             // Convert to [-1.0, 1.0]
-            pitch_speed = y as f32 / max_mouse_speed;
+            pitch_speed = y / max_mouse_speed;
             pitch_speed *= max_pitch_speed;
         }
+        pitch_speed += right_stick.y * max_stick_speed;
+        if self.invert_y {
+            pitch_speed = -pitch_speed;
+        }
 
         let camera_component = self.camera_component.clone().unwrap();
         camera_component.borrow_mut().set_pitch_speed(pitch_speed);
+
+        // Right mouse toggles aim down sights
+        if state.mouse.get_button_state(MouseButton::Right) == ButtonState::Pressed {
+            self.fps_model_component
+                .as_ref()
+                .unwrap()
+                .borrow_mut()
+                .toggle_aim();
+        }
+
+        // E or the X button uses what the player looks at, or carries it while held
+        let interact_state = match state.keyboard.get_key_state(Scancode::E) {
+            ButtonState::None => state.controller.get_button_state(Button::X),
+            key_state => key_state,
+        };
+        match interact_state {
+            ButtonState::Pressed if self.focused_interactable.is_some() => self.interact(),
+            ButtonState::Pressed => self.grab(),
+            ButtonState::Released => self.release(Vector3::ZERO),
+            _ => {}
+        }
+
+        // Right trigger fires, shoulder buttons switch weapons
+        if state.controller.get_right_trigger() > 0.5 {
+            self.shoot();
+        }
+        if state.controller.get_button_state(Button::RightShoulder) == ButtonState::Pressed {
+            self.cycle_weapon(1);
+        }
+        if state.controller.get_button_state(Button::LeftShoulder) == ButtonState::Pressed {
+            self.cycle_weapon(-1);
+        }
+    }
+
+    fn take_damage(&self, amount: f32, source: &Vector3) {
+        self.get_health_component()
+            .unwrap()
+            .borrow_mut()
+            .take_damage(amount, source);
+    }
+
+    fn get_health_component(&self) -> Option<&Rc<RefCell<HealthComponent>>> {
+        self.health_component.as_ref()
+    }
+
+    fn get_bounds_policy(&self) -> BoundsPolicy {
+        BoundsPolicy::Respawn
     }
 
     actor::impl_getters_setters! {}
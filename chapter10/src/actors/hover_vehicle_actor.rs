@@ -0,0 +1,212 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use sdl2::{controller::Button, keyboard::Scancode};
+
+use crate::{
+    collision::aabb::AABB,
+    components::{
+        box_component::BoxComponent,
+        component::{Component, State as ComponentState},
+        follow_camera::FollowCamera,
+        hover_move::HoverMove,
+        interactable_component::InteractableComponent,
+        mesh_component::MeshComponent,
+        move_component::MoveComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager,
+        config::KeyBindings,
+        engine_context::EngineContext,
+        entity_manager::EntityManager,
+        input_system::{ButtonState, InputState},
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Hovercraft the player can get in with E and drive around, seen from behind.
+/// While driving, the player is out of the world and the vehicle takes the input and the view
+pub struct HoverVehicleActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    move_component: Option<Rc<RefCell<HoverMove>>>,
+    camera_component: Option<Rc<RefCell<FollowCamera>>>,
+    interactable_component: Option<Rc<RefCell<InteractableComponent>>>,
+    // Of the player, taken when getting in
+    key_bindings: KeyBindings,
+    driving: bool,
+    // Switched to on the next update, the player may be borrowed when asking
+    wants_driver: bool,
+}
+
+impl HoverVehicleActor {
+    const SIZE: f32 = 100.0;
+    const MAX_THRUST: f32 = 900.0;
+    const MAX_TURN_SPEED: f32 = std::f32::consts::PI;
+    // The player gets out this far to the right
+    const EXIT_DISTANCE: f32 = 150.0;
+
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: HoverVehicleActor::SIZE,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            move_component: None,
+            camera_component: None,
+            interactable_component: None,
+            key_bindings: KeyBindings::new(),
+            driving: false,
+            wants_driver: false,
+        };
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Cube.gpmesh");
+        let half = HoverVehicleActor::SIZE / 2.0;
+        let world_box = AABB::new(
+            Vector3::new(-half, -half, -half),
+            Vector3::new(half, half, half),
+        );
+        ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with_handle::<BoxComponent>(mesh.get_box().clone(), |_, box_component| {
+                box_component.borrow_mut().set_should_rotate(false);
+            })
+            .with_handle::<HoverMove>(world_box, |vehicle, move_component| {
+                vehicle.move_component = Some(move_component);
+            })
+            .with_handle::<FollowCamera>((), |vehicle, camera_component| {
+                camera_component.borrow_mut().set_active(false);
+                vehicle.camera_component = Some(camera_component);
+            })
+            .with_handle::<InteractableComponent>(
+                (
+                    "Drive hovercraft",
+                    Box::new(|owner| {
+                        if let Some(vehicle) = actor::downcast::<HoverVehicleActor>(owner) {
+                            vehicle.borrow_mut().wants_driver = true;
+                        }
+                    }),
+                ),
+                |vehicle, interactable_component| {
+                    vehicle.interactable_component = Some(interactable_component);
+                },
+            )
+            .build()
+    }
+
+    /// Take the player in or let them out, switching between their camera and this one
+    fn switch_driver(&mut self, driving: bool) {
+        let fps_actor = match self.context.entity_manager.borrow().get_fps_actor() {
+            Some(fps_actor) => fps_actor.clone(),
+            None => {
+                self.wants_driver = false;
+                return;
+            }
+        };
+
+        if driving {
+            self.key_bindings = fps_actor.borrow().get_key_bindings().clone();
+            fps_actor.borrow_mut().enter_vehicle();
+        } else {
+            let exit =
+                self.get_position().clone() + self.get_right() * HoverVehicleActor::EXIT_DISTANCE;
+            fps_actor.borrow_mut().exit_vehicle(exit);
+            // Drifts to a stop without a driver
+            let mut move_component = self.move_component.as_ref().unwrap().borrow_mut();
+            move_component.set_forward_speed(0.0);
+            move_component.set_angular_speed(0.0);
+        }
+
+        self.camera_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_active(driving);
+        self.interactable_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_enabled(!driving);
+        self.driving = driving;
+    }
+}
+
+impl Actor for HoverVehicleActor {
+    fn update_actor(&mut self, _delta_time: f32) {
+        if self.wants_driver != self.driving {
+            self.switch_driver(self.wants_driver);
+        }
+    }
+
+    fn actor_input(&mut self, state: &InputState) {
+        if !self.driving {
+            return;
+        }
+
+        let mut thrust = 0.0;
+        let mut turn = 0.0;
+        let key_bindings = &self.key_bindings;
+        if state.keyboard.get_key_value(key_bindings.forward) {
+            thrust += 1.0;
+        }
+        if state.keyboard.get_key_value(key_bindings.back) {
+            thrust -= 1.0;
+        }
+        if state.keyboard.get_key_value(key_bindings.left) {
+            turn -= 1.0;
+        }
+        if state.keyboard.get_key_value(key_bindings.right) {
+            turn += 1.0;
+        }
+
+        // Left stick drives (stick up is negative y)
+        let left_stick = state.controller.get_left_stick();
+        thrust = (thrust - left_stick.y).clamp(-1.0, 1.0);
+        turn = (turn + left_stick.x).clamp(-1.0, 1.0);
+
+        let mut move_component = self.move_component.as_ref().unwrap().borrow_mut();
+        move_component.set_forward_speed(thrust * HoverVehicleActor::MAX_THRUST);
+        move_component.set_angular_speed(turn * HoverVehicleActor::MAX_TURN_SPEED);
+        drop(move_component);
+
+        // E or the X button gets out
+        let exit_state = match state.keyboard.get_key_state(Scancode::E) {
+            ButtonState::None => state.controller.get_button_state(Button::X),
+            key_state => key_state,
+        };
+        if exit_state == ButtonState::Pressed {
+            self.wants_driver = false;
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for HoverVehicleActor {
+    actor::impl_drop! {}
+}
@@ -0,0 +1,98 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        billboard_component::{BillboardComponent, BillboardMode},
+        component::{Component, State as ComponentState},
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Short-lived puff drawn where a hitscan shot hits a surface
+pub struct ImpactActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    life_span: f32,
+    billboard: Option<Rc<RefCell<BillboardComponent>>>,
+}
+
+impl ImpactActor {
+    const LIFE_SPAN: f32 = 0.4;
+    const START_SCALE: f32 = 0.5;
+    // The puff spreads out while it fades
+    const END_SCALE: f32 = 1.5;
+
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: ImpactActor::START_SCALE,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            life_span: ImpactActor::LIFE_SPAN,
+            billboard: None,
+        };
+
+        let texture = context.asset_manager.borrow_mut().get_texture("Smoke.png");
+        ActorBuilder::pooled(context, this)
+            .with_pooled_handle::<BillboardComponent>(
+                (texture, BillboardMode::Spherical),
+                |actor, billboard| actor.billboard = Some(billboard),
+            )
+            .build()
+    }
+}
+
+impl Actor for ImpactActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        self.life_span -= delta_time;
+        if self.life_span < 0.0 {
+            self.set_state(State::Dead);
+            return;
+        }
+
+        // Grow and fade out over the life span
+        let remaining = self.life_span / ImpactActor::LIFE_SPAN;
+        self.set_scale(
+            ImpactActor::END_SCALE
+                + (ImpactActor::START_SCALE - ImpactActor::END_SCALE) * remaining,
+        );
+        if let Some(billboard) = &self.billboard {
+            billboard.borrow_mut().set_alpha(remaining);
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for ImpactActor {
+    actor::impl_drop! {}
+}
@@ -1,5 +1,21 @@
 pub mod actor;
+pub mod actor_builder;
 pub mod ball_actor;
+pub mod decal_actor;
+pub mod door_actor;
+pub mod elevator_actor;
+pub mod enemy_actor;
+pub mod explosive_ball_actor;
 pub mod fps_actor;
+pub mod hover_vehicle_actor;
+pub mod impact_actor;
+pub mod moving_target_actor;
+pub mod physics_crate_actor;
+pub mod pickup_actor;
 pub mod plane_actor;
+pub mod pop_up_target_actor;
+pub mod reverb_zone_actor;
+pub mod spawn_point_actor;
 pub mod target_actor;
+pub mod terrain_actor;
+pub mod turret_actor;
@@ -0,0 +1,176 @@
+use core::f32;
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        box_component::BoxComponent,
+        component::{Component, State as ComponentState},
+        health_component::HealthComponent,
+        mesh_component::MeshComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Target going back and forth between two points
+pub struct MovingTargetActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    health_component: Option<Rc<RefCell<HealthComponent>>>,
+    start: Vector3,
+    end: Vector3,
+    // Average speed in units/sec
+    speed: f32,
+    // Goes from 0.0 (start) to PI (end) and back to 2PI (start)
+    phase: f32,
+}
+
+impl MovingTargetActor {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
+        let mut this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 1.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            health_component: None,
+            start: Vector3::ZERO,
+            end: Vector3::ZERO,
+            speed: 200.0,
+            phase: 0.0,
+        };
+
+        this.set_rotation(Quaternion::from_axis_angle(
+            &Vector3::UNIT_Z,
+            f32::consts::PI,
+        ));
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Target.gpmesh");
+        let result = ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with::<BoxComponent>(mesh.get_box().clone())
+            .with_handle::<HealthComponent>(100.0, |target, health_component| {
+                target.health_component = Some(health_component);
+            })
+            .build();
+        context
+            .entity_manager
+            .borrow_mut()
+            .add_tag(result.clone(), EntityManager::TARGET_TAG);
+
+        result
+    }
+
+    /// Move between start and end, starting at start
+    pub fn set_path(&mut self, start: Vector3, end: Vector3) {
+        self.set_position(start.clone());
+        self.start = start;
+        self.end = end;
+        self.phase = 0.0;
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+}
+
+/// Position on the path, easing in and out at both ends
+fn compute_path_position(start: &Vector3, end: &Vector3, phase: f32) -> Vector3 {
+    let f = (1.0 - phase.cos()) * 0.5;
+    start.clone() + (end.clone() - start.clone()) * f
+}
+
+impl Actor for MovingTargetActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        if self.get_health_component().unwrap().borrow().is_dead() {
+            self.set_state(State::Dead);
+            return;
+        }
+
+        let distance = (self.end.clone() - self.start.clone()).length();
+        if distance <= 0.0 {
+            return;
+        }
+
+        // One way takes distance / speed seconds
+        self.phase += delta_time * self.speed * f32::consts::PI / distance;
+        self.phase %= f32::consts::PI * 2.0;
+        // BoxComponent follows when the world transform is recomputed
+        let position = compute_path_position(&self.start, &self.end, self.phase);
+        self.set_position(position);
+    }
+
+    fn hit_target(&self) {
+        if let Some(hud) = self.context.entity_manager.borrow().get_hud() {
+            hud.borrow_mut().notify_hit();
+        }
+    }
+
+    fn take_damage(&self, amount: f32, source: &Vector3) {
+        self.get_health_component()
+            .unwrap()
+            .borrow_mut()
+            .take_damage(amount, source);
+    }
+
+    fn get_health_component(&self) -> Option<&Rc<RefCell<HealthComponent>>> {
+        self.health_component.as_ref()
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for MovingTargetActor {
+    actor::impl_drop! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::compute_path_position;
+
+    #[test]
+    fn test_compute_path_position() {
+        let start = Vector3::new(100.0, -500.0, 200.0);
+        let end = Vector3::new(100.0, 500.0, 200.0);
+
+        assert_eq!(start, compute_path_position(&start, &end, 0.0));
+        assert_near_eq!(0.0, compute_path_position(&start, &end, PI * 0.5).y, 0.001);
+        assert_near_eq!(500.0, compute_path_position(&start, &end, PI).y, 0.001);
+        assert_near_eq!(
+            -500.0,
+            compute_path_position(&start, &end, PI * 2.0).y,
+            0.001
+        );
+    }
+}
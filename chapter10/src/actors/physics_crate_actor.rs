@@ -0,0 +1,87 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    collision::aabb::AABB,
+    components::{
+        box_component::BoxComponent,
+        component::{Component, State as ComponentState},
+        mesh_component::MeshComponent,
+        rigid_body_component::RigidBodyComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Crate the player can pick up and throw
+pub struct PhysicsCrateActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+}
+
+impl PhysicsCrateActor {
+    const SIZE: f32 = 60.0;
+    const MASS: f32 = 2.0;
+
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: PhysicsCrateActor::SIZE,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+        };
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Cube.gpmesh");
+        let half = PhysicsCrateActor::SIZE / 2.0;
+        let world_box = AABB::new(
+            Vector3::new(-half, -half, -half),
+            Vector3::new(half, half, half),
+        );
+        ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with_handle::<BoxComponent>(mesh.get_box().clone(), |_, box_component| {
+                box_component.borrow_mut().set_should_rotate(false);
+            })
+            .with_handle::<RigidBodyComponent>(world_box, |_, rigid_body| {
+                rigid_body.borrow_mut().set_mass(PhysicsCrateActor::MASS);
+            })
+            .build()
+    }
+}
+
+impl Actor for PhysicsCrateActor {
+    fn update_actor(&mut self, _delta_time: f32) {}
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for PhysicsCrateActor {
+    actor::impl_drop! {}
+}
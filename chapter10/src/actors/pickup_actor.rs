@@ -0,0 +1,295 @@
+use core::f32;
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        audio_component::AudioComponent,
+        box_component::BoxComponent,
+        component::{Component, State as ComponentState},
+        health_component::HealthComponent,
+        inventory_component::InventoryComponent,
+        mesh_component::MeshComponent,
+        weapon_component::WeaponComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, crash_report::log, engine_context::EngineContext,
+        entity_manager::EntityManager, level_file::PickupKind,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+impl PickupKind {
+    fn get_mesh_name(&self) -> &'static str {
+        match self {
+            PickupKind::Health(_) => "Sphere.gpmesh",
+            PickupKind::Ammo(_) | PickupKind::Item(_) => "Cube.gpmesh",
+            PickupKind::Powerup(..) => "Target.gpmesh",
+        }
+    }
+
+    fn get_scale(&self) -> f32 {
+        match self {
+            PickupKind::Health(_) => 1.5,
+            PickupKind::Ammo(_) | PickupKind::Item(_) => 30.0,
+            PickupKind::Powerup(..) => 0.2,
+        }
+    }
+
+    /// Give it to the player through its components.
+    /// Returns false if the player can't use it, e.g. health at full health, so it stays
+    fn grant(&self, player: &(dyn Actor + 'static)) -> bool {
+        match self {
+            PickupKind::Health(amount) => {
+                let health_component = match player.get_component::<HealthComponent>() {
+                    Some(health_component) => health_component,
+                    None => return false,
+                };
+                let mut health_component = health_component.borrow_mut();
+                if health_component.get_health() >= health_component.get_max_health() {
+                    return false;
+                }
+                health_component.heal(*amount);
+                true
+            }
+            PickupKind::Ammo(amount) => {
+                player
+                    .get_component::<WeaponComponent>()
+                    .is_some_and(|weapon_component| {
+                        weapon_component.borrow_mut().add_ammo_to_all(*amount)
+                    })
+            }
+            PickupKind::Powerup(powerup, duration) => player
+                .get_component::<InventoryComponent>()
+                .map(|inventory| inventory.borrow_mut().add_powerup(*powerup, *duration))
+                .is_some(),
+            PickupKind::Item(name) => player
+                .get_component::<InventoryComponent>()
+                .map(|inventory| inventory.borrow_mut().add_item(name, 1))
+                .is_some(),
+        }
+    }
+}
+
+/// Bobbing and spinning item given to the player walking into it.
+/// It comes back after the respawn time, or is gone for good without one
+pub struct PickupActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    mesh_component: Option<Rc<RefCell<MeshComponent>>>,
+    audio_component: Option<Rc<RefCell<AudioComponent>>>,
+    kind: PickupKind,
+    // Where it bobs around
+    home: Vector3,
+    respawn_time: Option<f32>,
+    // Cleared while it is taken, set back by the respawn timer
+    available: Rc<Cell<bool>>,
+    // Drives the bobbing and the spin
+    time: f32,
+}
+
+impl PickupActor {
+    // Distance from the player box it is picked up within
+    const PICKUP_RADIUS: f32 = 40.0;
+    const BOB_HEIGHT: f32 = 10.0;
+    // Bobs per second
+    const BOB_FREQUENCY: f32 = 0.75;
+    // In radians/sec
+    const SPIN_SPEED: f32 = f32::consts::PI;
+    const PICKUP_EVENT: &'static str = "event:/Pickup";
+
+    pub fn new(context: &EngineContext, kind: PickupKind) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: kind.get_scale(),
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            mesh_component: None,
+            audio_component: None,
+            kind: kind.clone(),
+            home: Vector3::ZERO,
+            respawn_time: None,
+            available: Rc::new(Cell::new(true)),
+            time: 0.0,
+        };
+
+        let mesh = context
+            .asset_manager
+            .borrow_mut()
+            .get_mesh(kind.get_mesh_name());
+        ActorBuilder::new(context, this)
+            .with_handle::<MeshComponent>(mesh, |pickup, mesh_component| {
+                pickup.mesh_component = Some(mesh_component);
+            })
+            .with_handle::<AudioComponent>((), |pickup, audio_component| {
+                pickup.audio_component = Some(audio_component);
+            })
+            .build()
+    }
+
+    /// Place it, it bobs around the position
+    pub fn set_home(&mut self, position: Vector3) {
+        self.set_position(position.clone());
+        self.home = position;
+    }
+
+    /// Seconds until it comes back after being picked up, None to never come back
+    pub fn set_respawn_time(&mut self, respawn_time: Option<f32>) {
+        self.respawn_time = respawn_time;
+    }
+
+    pub fn get_kind(&self) -> &PickupKind {
+        &self.kind
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.available.get()
+    }
+
+    /// Whether the box of the player is within reach
+    fn is_player_overlapping(&self, player: &(dyn Actor + 'static)) -> bool {
+        let box_component = match player.get_component::<BoxComponent>() {
+            Some(box_component) => box_component,
+            None => return false,
+        };
+        let distance_sq = box_component
+            .borrow()
+            .get_world_box()
+            .min_dist_sq(&self.position);
+        distance_sq <= PickupActor::PICKUP_RADIUS * PickupActor::PICKUP_RADIUS
+    }
+
+    fn on_picked_up(&mut self) {
+        log!("Picked up {:?}", self.kind);
+        self.audio_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .play_event(PickupActor::PICKUP_EVENT, self.get_world_transform());
+
+        match self.respawn_time {
+            Some(respawn_time) => {
+                self.available.set(false);
+                self.set_visible(false);
+
+                let available = self.available.clone();
+                // Weak, the pickup may be gone by then
+                let mesh_component = Rc::downgrade(self.mesh_component.as_ref().unwrap());
+                self.context.timer_manager.borrow_mut().schedule(
+                    respawn_time,
+                    None,
+                    Box::new(move || {
+                        available.set(true);
+                        if let Some(mesh_component) = mesh_component.upgrade() {
+                            mesh_component.borrow_mut().set_visible(true);
+                        }
+                    }),
+                );
+            }
+            None => self.set_state(State::Dead),
+        }
+    }
+
+    fn set_visible(&self, visible: bool) {
+        self.mesh_component
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .set_visible(visible);
+    }
+}
+
+impl Actor for PickupActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        if !self.is_available() {
+            return;
+        }
+
+        self.time += delta_time;
+        let bob = (self.time * PickupActor::BOB_FREQUENCY * f32::consts::PI * 2.0).sin();
+        let position = self.home.clone() + Vector3::UNIT_Z * (bob * PickupActor::BOB_HEIGHT);
+        self.set_position(position);
+        self.set_rotation(Quaternion::from_axis_angle(
+            &Vector3::UNIT_Z,
+            self.time * PickupActor::SPIN_SPEED,
+        ));
+
+        let player = match self.context.entity_manager.borrow().get_fps_actor() {
+            Some(player) => player.clone(),
+            None => return,
+        };
+        let player = player.borrow();
+        if self.is_player_overlapping(&*player) && self.kind.grant(&*player) {
+            self.on_picked_up();
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for PickupActor {
+    actor::impl_drop! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        components::{
+            health_component::HealthComponent, inventory_component::InventoryComponent,
+            powerup::Powerup,
+        },
+        math::vector3::Vector3,
+        system::level_file::PickupKind,
+    };
+
+    #[test]
+    fn test_grant() {
+        let player: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        // Nothing to give it to
+        assert!(!PickupKind::Health(25.0).grant(&*player.borrow()));
+
+        let health_component = HealthComponent::new(player.clone(), 100.0);
+        let inventory = InventoryComponent::new(player.clone());
+
+        // Stays while at full health
+        assert!(!PickupKind::Health(25.0).grant(&*player.borrow()));
+        health_component
+            .borrow_mut()
+            .take_damage(50.0, &Vector3::ZERO);
+        assert!(PickupKind::Health(25.0).grant(&*player.borrow()));
+        assert_eq!(75.0, health_component.borrow().get_health());
+
+        assert!(PickupKind::Powerup(Powerup::Haste, 10.0).grant(&*player.borrow()));
+        assert!(inventory.borrow().has_powerup(Powerup::Haste));
+        assert!(PickupKind::Item("Key".to_string()).grant(&*player.borrow()));
+        assert_eq!(1, inventory.borrow().get_item_count("Key"));
+    }
+}
@@ -1,16 +1,24 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
 
 use crate::{
     components::{
-        box_component::BoxComponent,
+        box_component::{BoxComponent, Material},
         component::{Component, State as ComponentState},
         mesh_component::MeshComponent,
     },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{asset_manager::AssetManager, entity_manager::EntityManager, phys_world::PhysWorld},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
 };
 
-use super::actor::{self, generate_id, Actor, State};
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
 
 pub struct PlaneActor {
     id: u32,
@@ -21,17 +29,14 @@ pub struct PlaneActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
     box_component: Option<Rc<RefCell<BoxComponent>>>,
 }
 
 impl PlaneActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        phys_world: Rc<RefCell<PhysWorld>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let this = Self {
             id: generate_id(),
             state: State::Active,
@@ -41,36 +46,37 @@ impl PlaneActor {
             scale: 10.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
+            parent: None,
+            children: vec![],
+            context: context.clone(),
             box_component: None,
         };
 
-        let result = Rc::new(RefCell::new(this));
-
-        let mesh_component = MeshComponent::new(result.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Plane.gpmesh");
-        mesh_component.borrow_mut().set_mesh(mesh.clone());
-
-        let box_component = BoxComponent::new(result.clone(), phys_world);
-        box_component
-            .borrow_mut()
-            .set_object_box(mesh.get_box().clone());
-        result.borrow_mut().box_component = Some(box_component);
-
-        entity_manager.borrow_mut().add_actor(result.clone());
-
-        result
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Plane.gpmesh");
+        ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with_handle::<BoxComponent>(mesh.get_box().clone(), |plane, box_component| {
+                plane.box_component = Some(box_component);
+            })
+            .build()
     }
 
     pub fn get_box(&self) -> &Rc<RefCell<BoxComponent>> {
         self.box_component.as_ref().unwrap()
     }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.get_box().borrow_mut().set_material(material);
+    }
 }
 
 impl Actor for PlaneActor {
     fn update_actor(&mut self, _delta_time: f32) {}
 
+    fn is_static(&self) -> bool {
+        true
+    }
+
     actor::impl_getters_setters! {}
 
     actor::impl_component_operation! {}
@@ -0,0 +1,170 @@
+use core::f32;
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        box_component::BoxComponent,
+        component::{Component, State as ComponentState},
+        health_component::HealthComponent,
+        mesh_component::MeshComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Target that stands up and lies down on a timer.
+/// It can only be damaged while standing.
+pub struct PopUpTargetActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    health_component: Option<Rc<RefCell<HealthComponent>>>,
+    is_up: bool,
+    // Time until the target switches between up and down
+    timer: f32,
+    up_time: f32,
+    down_time: f32,
+    // Tilt backwards, 0.0 is standing
+    tilt: f32,
+}
+
+impl PopUpTargetActor {
+    const DOWN_TILT: f32 = f32::consts::FRAC_PI_2;
+    // Radians/sec
+    const TILT_SPEED: f32 = f32::consts::PI * 2.0;
+
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
+        let mut this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 1.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            health_component: None,
+            is_up: false,
+            timer: 1.5,
+            up_time: 2.0,
+            down_time: 1.5,
+            tilt: PopUpTargetActor::DOWN_TILT,
+        };
+
+        this.set_rotation(compute_rotation(this.tilt));
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Target.gpmesh");
+        let result = ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with::<BoxComponent>(mesh.get_box().clone())
+            .with_handle::<HealthComponent>(100.0, |target, health_component| {
+                target.health_component = Some(health_component);
+            })
+            .build();
+        context
+            .entity_manager
+            .borrow_mut()
+            .add_tag(result.clone(), EntityManager::TARGET_TAG);
+
+        result
+    }
+
+    /// Seconds spent standing and lying down
+    pub fn set_times(&mut self, up_time: f32, down_time: f32) {
+        self.up_time = up_time;
+        self.down_time = down_time;
+        self.timer = if self.is_up { up_time } else { down_time };
+    }
+}
+
+/// Facing the player (-x), tilted backwards around the local y axis
+fn compute_rotation(tilt: f32) -> Quaternion {
+    Quaternion::concatenate(
+        &Quaternion::from_axis_angle(&Vector3::UNIT_Y, -tilt),
+        &Quaternion::from_axis_angle(&Vector3::UNIT_Z, f32::consts::PI),
+    )
+}
+
+impl Actor for PopUpTargetActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        if self.get_health_component().unwrap().borrow().is_dead() {
+            self.set_state(State::Dead);
+            return;
+        }
+
+        self.timer -= delta_time;
+        if self.timer <= 0.0 {
+            self.is_up = !self.is_up;
+            self.timer = if self.is_up {
+                self.up_time
+            } else {
+                self.down_time
+            };
+        }
+
+        let target_tilt = if self.is_up {
+            0.0
+        } else {
+            PopUpTargetActor::DOWN_TILT
+        };
+        let step = PopUpTargetActor::TILT_SPEED * delta_time;
+        let tilt = self.tilt + (target_tilt - self.tilt).clamp(-step, step);
+        if tilt != self.tilt {
+            self.tilt = tilt;
+            // BoxComponent follows when the world transform is recomputed
+            self.set_rotation(compute_rotation(tilt));
+        }
+    }
+
+    fn hit_target(&self) {
+        if !self.is_up {
+            return;
+        }
+        if let Some(hud) = self.context.entity_manager.borrow().get_hud() {
+            hud.borrow_mut().notify_hit();
+        }
+    }
+
+    fn take_damage(&self, amount: f32, source: &Vector3) {
+        if !self.is_up {
+            return;
+        }
+        self.get_health_component()
+            .unwrap()
+            .borrow_mut()
+            .take_damage(amount, source);
+    }
+
+    fn get_health_component(&self) -> Option<&Rc<RefCell<HealthComponent>>> {
+        self.health_component.as_ref()
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for PopUpTargetActor {
+    actor::impl_drop! {}
+}
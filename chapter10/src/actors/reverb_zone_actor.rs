@@ -0,0 +1,112 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    collision::aabb::AABB,
+    components::component::{Component, State as ComponentState},
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+        sound_event::SoundEvent,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Volume applying an FMOD snapshot (e.g. reverb) while the listener is inside it.
+/// The snapshot fades out on exit instead of being cut
+pub struct ReverbZoneActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    // Half size of the volume, scaled with the actor
+    half_extents: Vector3,
+    snapshot_name: String,
+    // Playing while the listener is inside
+    snapshot: Option<SoundEvent>,
+}
+
+impl ReverbZoneActor {
+    pub fn new(
+        context: &EngineContext,
+        snapshot_name: &str,
+        half_extents: Vector3,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 1.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            half_extents,
+            snapshot_name: snapshot_name.to_string(),
+            snapshot: None,
+        };
+
+        ActorBuilder::new(context, this).build()
+    }
+
+    /// Volume in world space, not rotated
+    pub fn get_volume(&self) -> AABB {
+        let center = self.world_transform.get_translation();
+        let half_extents = self.half_extents.clone() * self.scale;
+        AABB::new(center.clone() - half_extents.clone(), center + half_extents)
+    }
+
+    pub fn is_listener_inside(&self) -> bool {
+        self.snapshot.is_some()
+    }
+}
+
+impl Actor for ReverbZoneActor {
+    fn update_actor(&mut self, _delta_time: f32) {
+        let listener_position = self
+            .context
+            .audio_system
+            .borrow()
+            .get_listener_position()
+            .clone();
+        let is_inside = self.get_volume().contains(&listener_position);
+
+        if is_inside && self.snapshot.is_none() {
+            let snapshot = self
+                .context
+                .audio_system
+                .borrow_mut()
+                .play_event(&self.snapshot_name);
+            self.snapshot = Some(snapshot);
+        } else if !is_inside {
+            if let Some(mut snapshot) = self.snapshot.take() {
+                // Let the snapshot fade out
+                let _ = snapshot.stop(true);
+            }
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for ReverbZoneActor {
+    actor::impl_drop! {}
+}
@@ -1,17 +1,26 @@
 use core::f32;
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
 
 use crate::{
     components::{
         box_component::BoxComponent,
         component::{Component, State as ComponentState},
+        health_component::HealthComponent,
         mesh_component::MeshComponent,
     },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{asset_manager::AssetManager, entity_manager::EntityManager, phys_world::PhysWorld},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
 };
 
-use super::actor::{self, generate_id, Actor, State};
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
 
 pub struct TargetActor {
     id: u32,
@@ -22,16 +31,14 @@ pub struct TargetActor {
     scale: f32,
     rotation: Quaternion,
     components: Vec<Rc<RefCell<dyn Component>>>,
-    asset_manager: Rc<RefCell<AssetManager>>,
-    entity_manager: Rc<RefCell<EntityManager>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    health_component: Option<Rc<RefCell<HealthComponent>>>,
 }
 
 impl TargetActor {
-    pub fn new(
-        asset_manager: Rc<RefCell<AssetManager>>,
-        entity_manager: Rc<RefCell<EntityManager>>,
-        phys_world: Rc<RefCell<PhysWorld>>,
-    ) -> Rc<RefCell<Self>> {
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
         let mut this = Self {
             id: generate_id(),
             state: State::Active,
@@ -41,8 +48,10 @@ impl TargetActor {
             scale: 1.0,
             rotation: Quaternion::new(),
             components: vec![],
-            asset_manager: asset_manager.clone(),
-            entity_manager: entity_manager.clone(),
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            health_component: None,
         };
 
         this.set_rotation(Quaternion::from_axis_angle(
@@ -50,25 +59,50 @@ impl TargetActor {
             f32::consts::PI,
         ));
 
-        let result = Rc::new(RefCell::new(this));
-
-        let mesh_component = MeshComponent::new(result.clone());
-        let mesh = asset_manager.borrow_mut().get_mesh("Target.gpmesh");
-        mesh_component.borrow_mut().set_mesh(mesh.clone());
-
-        let box_component = BoxComponent::new(result.clone(), phys_world);
-        box_component
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Target.gpmesh");
+        let result = ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh.clone())
+            .with::<BoxComponent>(mesh.get_box().clone())
+            .with_handle::<HealthComponent>(100.0, |target, health_component| {
+                target.health_component = Some(health_component);
+            })
+            .build();
+        context
+            .entity_manager
             .borrow_mut()
-            .set_object_box(mesh.get_box().clone());
-
-        entity_manager.borrow_mut().add_actor(result.clone());
+            .add_tag(result.clone(), EntityManager::TARGET_TAG);
 
         result
     }
 }
 
 impl Actor for TargetActor {
-    fn update_actor(&mut self, _delta_time: f32) {}
+    fn update_actor(&mut self, _delta_time: f32) {
+        if self.get_health_component().unwrap().borrow().is_dead() {
+            self.set_state(State::Dead);
+        }
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    fn hit_target(&self) {
+        if let Some(hud) = self.context.entity_manager.borrow().get_hud() {
+            hud.borrow_mut().notify_hit();
+        }
+    }
+
+    fn take_damage(&self, amount: f32, source: &Vector3) {
+        self.get_health_component()
+            .unwrap()
+            .borrow_mut()
+            .take_damage(amount, source);
+    }
+
+    fn get_health_component(&self) -> Option<&Rc<RefCell<HealthComponent>>> {
+        self.health_component.as_ref()
+    }
 
     actor::impl_getters_setters! {}
 
@@ -0,0 +1,131 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    collision::heightmap::Heightmap,
+    components::{
+        box_component::Material,
+        component::{Component, State as ComponentState},
+        mesh_component::MeshComponent,
+    },
+    graphics::mesh::Mesh,
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, crash_report::log, engine_context::EngineContext,
+        entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+};
+
+/// Ground built from a heightmap image, split into chunk meshes.
+/// Registered with PhysWorld so it can be walked on and shot at
+pub struct TerrainActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    heightmap: Rc<Heightmap>,
+}
+
+impl TerrainActor {
+    // Distance between two samples of the heightmap
+    const SPACING: f32 = 50.0;
+    // Height of a white pixel
+    const MAX_HEIGHT: f32 = 400.0;
+    // Cells along x and y of each mesh
+    const CHUNK_CELLS: usize = 10;
+    // The texture repeats every this many cells
+    const TEXTURE_CELLS: f32 = 5.0;
+    // Samples of the flat fallback
+    const FALLBACK_SAMPLES: usize = 51;
+
+    /// The heightmap is centered on position, which is never rotated nor scaled
+    pub fn new(context: &EngineContext, file_name: &str, position: Vector3) -> Rc<RefCell<Self>> {
+        let heightmap =
+            match Heightmap::load(file_name, TerrainActor::SPACING, TerrainActor::MAX_HEIGHT) {
+                Ok(heightmap) => heightmap,
+                Err(e) => {
+                    log!("{}, using a flat terrain", e);
+                    let samples = TerrainActor::FALLBACK_SAMPLES;
+                    Heightmap::new(
+                        samples,
+                        samples,
+                        vec![0.0; samples * samples],
+                        TerrainActor::SPACING,
+                    )
+                }
+            };
+        let heightmap = Rc::new(heightmap);
+
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: position.clone(),
+            scale: 1.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            heightmap: heightmap.clone(),
+        };
+
+        let texture = context.asset_manager.borrow_mut().get_texture("Plane.png");
+        let mut builder = ActorBuilder::new(context, this);
+        let (columns, rows) = heightmap.get_cell_counts();
+        for first_row in (0..rows).step_by(TerrainActor::CHUNK_CELLS) {
+            for first_column in (0..columns).step_by(TerrainActor::CHUNK_CELLS) {
+                let (vertices, indices) = heightmap.build_chunk(
+                    (first_column, first_row),
+                    (TerrainActor::CHUNK_CELLS, TerrainActor::CHUNK_CELLS),
+                    TerrainActor::TEXTURE_CELLS,
+                );
+                let mesh = Mesh::from_geometry(&vertices, &indices, texture.clone(), "BasicMesh");
+                builder = builder.with::<MeshComponent>(Rc::new(mesh));
+            }
+        }
+        let result = builder.build();
+
+        let actor: Rc<RefCell<dyn Actor>> = result.clone();
+        context
+            .phys_world
+            .borrow_mut()
+            .set_terrain(heightmap, position, Material::Grass, &actor);
+
+        result
+    }
+
+    /// Height of the ground under the world (x, y), None outside of the terrain
+    pub fn height_at(&self, x: f32, y: f32) -> Option<f32> {
+        self.heightmap
+            .height_at(x - self.position.x, y - self.position.y)
+            .map(|height| height + self.position.z)
+    }
+}
+
+impl Actor for TerrainActor {
+    fn update_actor(&mut self, _delta_time: f32) {}
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for TerrainActor {
+    actor::impl_drop! {}
+}
@@ -0,0 +1,123 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    components::{
+        aim_component::AimComponent,
+        component::{Component, State as ComponentState},
+        mesh_component::MeshComponent,
+    },
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    system::{
+        asset_manager::AssetManager, engine_context::EngineContext, entity_manager::EntityManager,
+    },
+};
+
+use super::{
+    actor::{self, generate_id, Actor, State},
+    actor_builder::ActorBuilder,
+    ball_actor::BallActor,
+};
+
+/// Shoots balls at the nearest enemy, leading it so moving enemies are hit
+pub struct TurretActor {
+    id: u32,
+    state: State,
+    world_transform: Matrix4,
+    recompute_world_transform: bool,
+    position: Vector3,
+    scale: f32,
+    rotation: Quaternion,
+    components: Vec<Rc<RefCell<dyn Component>>>,
+    parent: Option<Weak<RefCell<dyn Actor>>>,
+    children: Vec<Rc<RefCell<dyn Actor>>>,
+    context: EngineContext,
+    aim_component: Option<Rc<RefCell<AimComponent>>>,
+    // Time until the next shot
+    fire_cooldown: f32,
+}
+
+impl TurretActor {
+    const RANGE: f32 = 1500.0;
+    const FIRE_INTERVAL: f32 = 1.5;
+    // Balls start outside of the turret
+    const MUZZLE_OFFSET: f32 = 60.0;
+
+    pub fn new(context: &EngineContext) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            state: State::Active,
+            world_transform: Matrix4::new(),
+            recompute_world_transform: true,
+            position: Vector3::ZERO,
+            scale: 50.0,
+            rotation: Quaternion::new(),
+            components: vec![],
+            parent: None,
+            children: vec![],
+            context: context.clone(),
+            aim_component: None,
+            fire_cooldown: TurretActor::FIRE_INTERVAL,
+        };
+
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Cube.gpmesh");
+        ActorBuilder::new(context, this)
+            .with::<MeshComponent>(mesh)
+            .with_handle::<AimComponent>(BallActor::SPEED, |turret, aim_component| {
+                turret.aim_component = Some(aim_component);
+            })
+            .build()
+    }
+
+    fn find_nearest_enemy(&self) -> Option<Rc<RefCell<dyn Actor>>> {
+        let mut nearest = None;
+        let mut nearest_distance_sq = TurretActor::RANGE * TurretActor::RANGE;
+        let entity_manager = self.context.entity_manager.borrow();
+        for enemy in entity_manager.actors_with_tag(EntityManager::ENEMY_TAG) {
+            let distance_sq =
+                (enemy.borrow().get_position().clone() - self.position.clone()).length_sq();
+            if distance_sq < nearest_distance_sq {
+                nearest_distance_sq = distance_sq;
+                nearest = Some(enemy.clone());
+            }
+        }
+        nearest
+    }
+}
+
+impl Actor for TurretActor {
+    fn update_actor(&mut self, delta_time: f32) {
+        // Keep tracking the nearest enemy so its velocity is known when firing
+        let enemy = self.find_nearest_enemy();
+        let aim_component = self.aim_component.clone().unwrap();
+        aim_component.borrow_mut().set_target(enemy);
+
+        self.fire_cooldown -= delta_time;
+        let aim_point = match aim_component.borrow().get_aim_point() {
+            Some(aim_point) => aim_point.clone(),
+            None => return,
+        };
+
+        let direction = (aim_point - self.position.clone()).normalize();
+        self.rotate_to_new_forward(direction.clone());
+
+        if self.fire_cooldown <= 0.0 {
+            let ball = BallActor::new(&self.context, self.id);
+            ball.borrow_mut().set_position(
+                self.position.clone() + direction.clone() * TurretActor::MUZZLE_OFFSET,
+            );
+            ball.borrow_mut().rotate_to_new_forward(direction);
+            self.fire_cooldown = TurretActor::FIRE_INTERVAL;
+        }
+    }
+
+    actor::impl_getters_setters! {}
+
+    actor::impl_component_operation! {}
+}
+
+impl Drop for TurretActor {
+    actor::impl_drop! {}
+}
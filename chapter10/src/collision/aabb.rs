@@ -1,6 +1,6 @@
 use crate::math::{quaternion::Quaternion, vector3::Vector3};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AABB {
     pub min: Vector3,
     pub max: Vector3,
@@ -77,13 +77,37 @@ impl AABB {
     }
 
     pub fn min_dist_sq(&self, point: &Vector3) -> f32 {
-        // Compute differences for each axis
-        let dx = (self.min.x - point.x).max(0.0).max(point.x - self.max.x);
-        let dy = (self.min.y - point.y).max(0.0).max(point.y - self.max.y);
-        let dz = (self.min.z - point.z).max(0.0).max(point.z - self.max.z);
+        // The closest point of the box is the point clamped to it
+        point.distance_sq(&point.clamp(&self.min, &self.max))
+    }
+
+    /// Shortest move of self out of other, along a single axis. Zero if they don't intersect
+    pub fn get_min_separation(&self, other: &AABB) -> Vector3 {
+        if !self.intersect(other) {
+            return Vector3::ZERO;
+        }
 
-        // Distance squared formula
-        dx * dx + dy * dy + dz * dz
+        // Calculate all our differences
+        let dx1 = other.max.x - self.min.x;
+        let dx2 = other.min.x - self.max.x;
+        let dy1 = other.max.y - self.min.y;
+        let dy2 = other.min.y - self.max.y;
+        let dz1 = other.max.z - self.min.z;
+        let dz2 = other.min.z - self.max.z;
+
+        // Set dx to whichever of dx1/dx2 dy1/dy2 dz1/dz2 have a lower abs
+        let dx = if dx1.abs() < dx2.abs() { dx1 } else { dx2 };
+        let dy = if dy1.abs() < dy2.abs() { dy1 } else { dy2 };
+        let dz = if dz1.abs() < dz2.abs() { dz1 } else { dz2 };
+
+        // Whichever is closest
+        if dx.abs() <= dy.abs() && dx.abs() <= dz.abs() {
+            Vector3::new(dx, 0.0, 0.0)
+        } else if dy.abs() <= dx.abs() && dy.abs() <= dz.abs() {
+            Vector3::new(0.0, dy, 0.0)
+        } else {
+            Vector3::new(0.0, 0.0, dz)
+        }
     }
 }
 
@@ -126,4 +150,17 @@ mod tests {
 
         assert!(!actual);
     }
+
+    #[test]
+    fn test_get_min_separation() {
+        let floor = AABB::new(
+            Vector3::new(-100.0, -100.0, -10.0),
+            Vector3::new(100.0, 100.0, 0.0),
+        );
+        let sunk = AABB::new(Vector3::new(0.0, 0.0, -3.0), Vector3::new(10.0, 10.0, 7.0));
+        assert_eq!(Vector3::new(0.0, 0.0, 3.0), sunk.get_min_separation(&floor));
+
+        let apart = AABB::new(Vector3::new(0.0, 0.0, 5.0), Vector3::new(10.0, 10.0, 15.0));
+        assert_eq!(Vector3::ZERO, apart.get_min_separation(&floor));
+    }
 }
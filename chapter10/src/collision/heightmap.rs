@@ -0,0 +1,219 @@
+use std::path::Path;
+
+use image::ImageReader;
+
+use crate::{math::vector3::Vector3, system::engine_error::EngineError};
+
+/// Grid of heights, centered on the origin. Each cell is split into two triangles
+/// along its diagonal, so heights match the generated mesh exactly
+#[derive(Debug, Clone)]
+pub struct Heightmap {
+    // Number of samples along x and y
+    columns: usize,
+    rows: usize,
+    // Row-major, row 0 at the smallest y
+    heights: Vec<f32>,
+    // Distance between two samples
+    spacing: f32,
+}
+
+impl Heightmap {
+    pub fn new(columns: usize, rows: usize, heights: Vec<f32>, spacing: f32) -> Self {
+        debug_assert!(columns >= 2 && rows >= 2, "at least one cell");
+        debug_assert_eq!(columns * rows, heights.len());
+        Self {
+            columns,
+            rows,
+            heights,
+            spacing,
+        }
+    }
+
+    /// Grayscale image where white is max_height, each pixel is one sample
+    pub fn load(file_name: &str, spacing: f32, max_height: f32) -> Result<Self, EngineError> {
+        let path = Path::new(env!("OUT_DIR"))
+            .join("resources")
+            .join("Assets")
+            .join(file_name);
+
+        let image = ImageReader::open(path)
+            .map_err(|e| EngineError::io(file_name, e))?
+            .decode()
+            .map_err(|e| EngineError::invalid_asset(file_name, e))?
+            .to_luma8();
+
+        let (columns, rows) = (image.width() as usize, image.height() as usize);
+        if columns < 2 || rows < 2 {
+            return Err(EngineError::invalid_asset(file_name, "smaller than 2x2"));
+        }
+
+        // Image rows go down, heightmap rows go toward +y
+        let mut heights = Vec::with_capacity(columns * rows);
+        for row in (0..rows).rev() {
+            for column in 0..columns {
+                let value = image.get_pixel(column as u32, row as u32).0[0];
+                heights.push(value as f32 / 255.0 * max_height);
+            }
+        }
+
+        Ok(Heightmap::new(columns, rows, heights, spacing))
+    }
+
+    /// Cells along x and y
+    pub fn get_cell_counts(&self) -> (usize, usize) {
+        (self.columns - 1, self.rows - 1)
+    }
+
+    pub fn get_spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    /// Size along x and y
+    pub fn get_size(&self) -> (f32, f32) {
+        (
+            (self.columns - 1) as f32 * self.spacing,
+            (self.rows - 1) as f32 * self.spacing,
+        )
+    }
+
+    fn get_sample(&self, column: usize, row: usize) -> f32 {
+        self.heights[row * self.columns + column]
+    }
+
+    fn get_sample_position(&self, column: usize, row: usize) -> Vector3 {
+        let (width, depth) = self.get_size();
+        Vector3::new(
+            column as f32 * self.spacing - width / 2.0,
+            row as f32 * self.spacing - depth / 2.0,
+            self.get_sample(column, row),
+        )
+    }
+
+    /// Height under (x, y), or None outside of the heightmap
+    pub fn height_at(&self, x: f32, y: f32) -> Option<f32> {
+        let (width, depth) = self.get_size();
+        let gx = (x + width / 2.0) / self.spacing;
+        let gy = (y + depth / 2.0) / self.spacing;
+        let (columns, rows) = self.get_cell_counts();
+        if !(0.0..=columns as f32).contains(&gx) || !(0.0..=rows as f32).contains(&gy) {
+            return None;
+        }
+
+        // The last sample belongs to the last cell
+        let column = (gx as usize).min(columns - 1);
+        let row = (gy as usize).min(rows - 1);
+        let fx = gx - column as f32;
+        let fy = gy - row as f32;
+
+        let h00 = self.get_sample(column, row);
+        let h10 = self.get_sample(column + 1, row);
+        let h01 = self.get_sample(column, row + 1);
+        let h11 = self.get_sample(column + 1, row + 1);
+
+        // Which triangle of the cell, split from (0, 0) to (1, 1)
+        let height = if fx >= fy {
+            h00 + fx * (h10 - h00) + fy * (h11 - h10)
+        } else {
+            h00 + fy * (h01 - h00) + fx * (h11 - h01)
+        };
+        Some(height)
+    }
+
+    /// Smoothed normal from the neighboring samples
+    pub fn normal_at(&self, x: f32, y: f32) -> Vector3 {
+        let center = self.height_at(x, y).unwrap_or(0.0);
+        let height = |x: f32, y: f32| self.height_at(x, y).unwrap_or(center);
+        let dx = height(x + self.spacing, y) - height(x - self.spacing, y);
+        let dy = height(x, y + self.spacing) - height(x, y - self.spacing);
+        Vector3::new(-dx, -dy, 2.0 * self.spacing).normalize()
+    }
+
+    /// Vertices (8 floats each) and indices of the cells [first, first + count) along x and y.
+    /// The texture repeats every uv_cells cells
+    pub fn build_chunk(
+        &self,
+        first: (usize, usize),
+        count: (usize, usize),
+        uv_cells: f32,
+    ) -> (Vec<f32>, Vec<u32>) {
+        let (columns, rows) = self.get_cell_counts();
+        let last_column = (first.0 + count.0).min(columns);
+        let last_row = (first.1 + count.1).min(rows);
+        let chunk_columns = last_column - first.0 + 1;
+
+        let mut vertices = vec![];
+        for row in first.1..=last_row {
+            for column in first.0..=last_column {
+                let position = self.get_sample_position(column, row);
+                let normal = self.normal_at(position.x, position.y);
+                vertices.extend_from_slice(&[position.x, position.y, position.z]);
+                vertices.extend_from_slice(&[normal.x, normal.y, normal.z]);
+                vertices.extend_from_slice(&[column as f32 / uv_cells, row as f32 / uv_cells]);
+            }
+        }
+
+        let mut indices = vec![];
+        for row in 0..(last_row - first.1) {
+            for column in 0..(last_column - first.0) {
+                let i00 = (row * chunk_columns + column) as u32;
+                let i10 = i00 + 1;
+                let i01 = i00 + chunk_columns as u32;
+                let i11 = i01 + 1;
+                indices.extend_from_slice(&[i00, i10, i11, i11, i01, i00]);
+            }
+        }
+
+        (vertices, indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::Heightmap;
+
+    // 2x2 cells of 10, rising toward +x
+    fn slope() -> Heightmap {
+        #[rustfmt::skip]
+        let heights = vec![
+            0.0, 5.0, 10.0,
+            0.0, 5.0, 10.0,
+            0.0, 5.0, 10.0,
+        ];
+        Heightmap::new(3, 3, heights, 10.0)
+    }
+
+    #[test]
+    fn test_height_at() {
+        let heightmap = slope();
+        assert_eq!((20.0, 20.0), heightmap.get_size());
+        assert_near_eq!(0.0, heightmap.height_at(-10.0, -10.0).unwrap(), 0.001);
+        assert_near_eq!(5.0, heightmap.height_at(0.0, 0.0).unwrap(), 0.001);
+        assert_near_eq!(7.5, heightmap.height_at(5.0, 3.0).unwrap(), 0.001);
+        assert_near_eq!(7.5, heightmap.height_at(5.0, 7.0).unwrap(), 0.001);
+        assert_near_eq!(10.0, heightmap.height_at(10.0, 10.0).unwrap(), 0.001);
+        assert!(heightmap.height_at(10.1, 0.0).is_none());
+
+        // Tilted back along -x
+        let normal = heightmap.normal_at(0.0, 0.0);
+        assert!(normal.x < 0.0);
+        assert_near_eq!(0.0, normal.y, 0.001);
+        assert_near_eq!(1.0, normal.length(), 0.001);
+    }
+
+    #[test]
+    fn test_build_chunk() {
+        let heightmap = slope();
+        let (vertices, indices) = heightmap.build_chunk((1, 0), (4, 4), 2.0);
+
+        // Clamped to the last cell: 2x3 samples, 1x2 cells
+        assert_eq!(6 * 8, vertices.len());
+        assert_eq!(2 * 6, indices.len());
+        let first = Vector3::new(vertices[0], vertices[1], vertices[2]);
+        assert_eq!(Vector3::new(0.0, -10.0, 5.0), first);
+        // uv in texture repeats
+        assert_near_eq!(0.5, vertices[6], 0.001);
+        assert!(indices.iter().all(|&index| (index as usize) < 6));
+    }
+}
@@ -1,6 +1,9 @@
-use crate::math::{self, vector3::Vector3};
+use crate::{
+    math::{self, vector3::Vector3},
+    system::scratch_pool,
+};
 
-use super::{aabb::AABB, plane::Plane, sphere::Sphere};
+use super::{aabb::AABB, heightmap::Heightmap, plane::Plane, sphere::Sphere};
 
 #[derive(Debug)]
 pub struct LineSegment {
@@ -9,18 +12,22 @@ pub struct LineSegment {
 }
 
 impl LineSegment {
+    // Step of the heightmap test, in cells
+    const HEIGHTMAP_STEP: f32 = 0.5;
+    const HEIGHTMAP_BISECTIONS: usize = 10;
+
     pub fn new(start: Vector3, end: Vector3) -> Self {
         Self { start, end }
     }
 
     pub fn point_on_segment(&self, t: f32) -> Vector3 {
-        self.start.clone() + (self.end.clone() - self.start.clone()) * t
+        self.start.lerp(&self.end, t)
     }
 
     pub fn min_dist_sq(&self, point: &Vector3) -> f32 {
         // Construct vectors
         let ab = self.end.clone() - self.start.clone();
-        let ba = ab.clone() * -1.0;
+        let ba = -ab.clone();
         let ac = point.clone() - self.start.clone();
         let bc = point.clone() - self.end.clone();
 
@@ -183,22 +190,29 @@ impl LineSegment {
     }
 
     pub fn intersect_aabb(&self, aabb: &AABB) -> Option<(f32, Vector3)> {
-        // Vector to save all possible t values for those sides
-        let mut t_values = vec![];
+        // Scratch vector to save all possible t values for those sides
+        scratch_pool::with_scratch(|t_values| self.intersect_aabb_with(aabb, t_values))
+    }
+
+    fn intersect_aabb_with(
+        &self,
+        aabb: &AABB,
+        t_values: &mut Vec<(f32, Vector3)>,
+    ) -> Option<(f32, Vector3)> {
         // Test the x planes
         LineSegment::test_side_plane(
             self.start.x,
             self.end.x,
             aabb.min.x,
             Vector3::NEGATIVE_UNIT_X,
-            &mut t_values,
+            t_values,
         );
         LineSegment::test_side_plane(
             self.start.x,
             self.end.x,
             aabb.max.x,
             Vector3::UNIT_X,
-            &mut t_values,
+            t_values,
         );
         // Test the y planes
         LineSegment::test_side_plane(
@@ -206,14 +220,14 @@ impl LineSegment {
             self.end.y,
             aabb.min.y,
             Vector3::NEGATIVE_UNIT_Y,
-            &mut t_values,
+            t_values,
         );
         LineSegment::test_side_plane(
             self.start.y,
             self.end.y,
             aabb.max.y,
             Vector3::UNIT_Y,
-            &mut t_values,
+            t_values,
         );
         // Test the z planes
         LineSegment::test_side_plane(
@@ -221,21 +235,21 @@ impl LineSegment {
             self.end.z,
             aabb.min.z,
             Vector3::NEGATIVE_UNIT_Z,
-            &mut t_values,
+            t_values,
         );
         LineSegment::test_side_plane(
             self.start.z,
             self.end.z,
             aabb.max.z,
             Vector3::UNIT_Z,
-            &mut t_values,
+            t_values,
         );
 
         // Sort the t values in ascending order
         t_values.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
         // Test if the box contains any of these points of intersection
-        for t_and_normal in t_values {
+        for t_and_normal in t_values.drain(..) {
             let point = self.point_on_segment(t_and_normal.0);
             if aabb.contains(&point) {
                 return Some(t_and_normal);
@@ -246,6 +260,50 @@ impl LineSegment {
         None
     }
 
+    /// First point where the segment goes below the heightmap, which is in the same space.
+    /// Returns t of the point and the normal there
+    pub fn intersect_heightmap(&self, heightmap: &Heightmap) -> Option<(f32, Vector3)> {
+        // Above the surface is positive, outside of the heightmap is treated as above
+        let clearance = |t: f32| {
+            let point = self.point_on_segment(t);
+            heightmap
+                .height_at(point.x, point.y)
+                .map_or(f32::INFINITY, |height| point.z - height)
+        };
+
+        // March in steps shorter than a cell, then bisect the step crossing the surface
+        let length = (self.end.clone() - self.start.clone()).length();
+        let steps = (length / (heightmap.get_spacing() * LineSegment::HEIGHTMAP_STEP))
+            .ceil()
+            .max(1.0) as usize;
+        let mut t_above = 0.0;
+        if clearance(t_above) < 0.0 {
+            return None;
+        }
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            if clearance(t) > 0.0 {
+                t_above = t;
+                continue;
+            }
+
+            let mut t_below = t;
+            for _ in 0..LineSegment::HEIGHTMAP_BISECTIONS {
+                let t_middle = (t_above + t_below) / 2.0;
+                if clearance(t_middle) > 0.0 {
+                    t_above = t_middle;
+                } else {
+                    t_below = t_middle;
+                }
+            }
+
+            let point = self.point_on_segment(t_below);
+            return Some((t_below, heightmap.normal_at(point.x, point.y)));
+        }
+
+        None
+    }
+
     fn test_side_plane(
         start: f32,
         end: f32,
@@ -273,8 +331,10 @@ impl LineSegment {
 #[cfg(test)]
 mod tests {
     use crate::{
-        collision::{aabb::AABB, plane::Plane, sphere::Sphere},
+        assert_near_eq,
+        collision::{aabb::AABB, heightmap::Heightmap, plane::Plane, sphere::Sphere},
         math::vector3::Vector3,
+        system::scratch_pool::allocation_count,
     };
 
     use super::LineSegment;
@@ -351,4 +411,46 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_intersect_aabb_allocations() {
+        // A frame of ray casts, the scratch pool warmed up by the frames before
+        const CASTS: usize = 100;
+        let segment = LineSegment::new(Vector3::ZERO, Vector3::new(0.0, 2.0, 0.0));
+        let aabb = AABB::new(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0));
+
+        let before = allocation_count();
+        for _ in 0..CASTS {
+            let mut t_values = vec![];
+            segment.intersect_aabb_with(&aabb, &mut t_values);
+        }
+        let without_pool = allocation_count() - before;
+
+        segment.intersect_aabb(&aabb);
+        let before = allocation_count();
+        for _ in 0..CASTS {
+            segment.intersect_aabb(&aabb);
+        }
+        let with_pool = allocation_count() - before;
+
+        assert!(without_pool >= CASTS);
+        assert_eq!(0, with_pool);
+    }
+
+    #[test]
+    fn test_intersect_heightmap() {
+        // Flat at 10 over [-10, 10]
+        let heightmap = Heightmap::new(3, 3, vec![10.0; 9], 10.0);
+
+        let segment = LineSegment::new(Vector3::new(0.0, 0.0, 30.0), Vector3::new(0.0, 0.0, -10.0));
+        let (t, normal) = LineSegment::intersect_heightmap(&segment, &heightmap).unwrap();
+        assert_near_eq!(0.5, t, 0.001);
+        assert_eq!(Vector3::UNIT_Z, normal);
+
+        // Passing over, or off the heightmap
+        let segment = LineSegment::new(Vector3::new(-5.0, 0.0, 20.0), Vector3::new(5.0, 0.0, 20.0));
+        assert!(LineSegment::intersect_heightmap(&segment, &heightmap).is_none());
+        let segment = LineSegment::new(Vector3::new(50.0, 0.0, 20.0), Vector3::new(50.0, 0.0, 0.0));
+        assert!(LineSegment::intersect_heightmap(&segment, &heightmap).is_none());
+    }
 }
@@ -1,6 +1,7 @@
 pub mod aabb;
 pub mod capsule;
 pub mod convex_polygon;
+pub mod heightmap;
 pub mod line_segment;
 pub mod obb;
 pub mod plane;
@@ -1,12 +1,21 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{
+        actor::Actor,
+        actor_builder::{BuildComponent, ReuseComponent},
+    },
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{audio_system::AudioSystem, sound_event::SoundEvent},
+    system::{
+        audio_system::AudioSystem, crash_report::log, engine_context::EngineContext,
+        sound_event::SoundEvent,
+    },
 };
 
-use super::component::{self, generate_id, Component, State};
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
 
 pub struct AudioComponent {
     id: u32,
@@ -17,6 +26,8 @@ pub struct AudioComponent {
     events_2d: Vec<Rc<RefCell<SoundEvent>>>,
     events_3d: Vec<Rc<RefCell<SoundEvent>>>,
     owner_world_transform: Matrix4,
+    // Owner velocity for the Doppler effect of 3D events
+    velocity: Vector3,
 }
 
 impl AudioComponent {
@@ -24,25 +35,33 @@ impl AudioComponent {
         owner: Rc<RefCell<dyn Actor>>,
         audio_system: Rc<RefCell<AudioSystem>>,
     ) -> Rc<RefCell<Self>> {
-        let this = Self {
+        let this = AudioComponent::create(owner.clone(), audio_system);
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    fn create(owner: Rc<RefCell<dyn Actor>>, audio_system: Rc<RefCell<AudioSystem>>) -> Self {
+        let owner_world_transform = owner.borrow().get_world_transform().clone();
+        Self {
             id: generate_id(),
-            owner: owner.clone(),
+            owner,
             update_order: 100,
             state: State::Active,
             audio_system,
             events_2d: vec![],
             events_3d: vec![],
-            owner_world_transform: owner.borrow().get_world_transform().clone(),
-        };
-        let result = Rc::new(RefCell::new(this));
-        owner.borrow_mut().add_component(result.clone());
-        result
+            owner_world_transform,
+            velocity: Vector3::ZERO,
+        }
     }
 
     pub fn play_event(&mut self, name: &str, world_transform: &Matrix4) -> Rc<RefCell<SoundEvent>> {
         let mut event = self.audio_system.borrow_mut().play_event(name);
         let result = if event.is_3d() {
-            event.set_3d_attributes(world_transform);
+            if let Err(e) = event.set_3d_attributes(world_transform, &self.velocity) {
+                log!("Failed to set 3D attributes of {}: {}", name, e);
+            }
             let event_ref = Rc::new(RefCell::new(event));
             self.events_3d.push(event_ref.clone());
             event_ref
@@ -55,41 +74,69 @@ impl AudioComponent {
     }
 
     pub fn stop_all_events(&mut self) {
-        self.events_2d
-            .iter_mut()
-            .for_each(|event| event.borrow_mut().stop(true));
-        self.events_3d
-            .iter_mut()
-            .for_each(|event| event.borrow_mut().stop(true));
+        self.events_2d.iter_mut().for_each(|event| {
+            let _ = event.borrow_mut().stop(true);
+        });
+        self.events_3d.iter_mut().for_each(|event| {
+            let _ = event.borrow_mut().stop(true);
+        });
 
         self.events_2d.clear();
         self.events_3d.clear();
     }
 }
 
+impl BuildComponent for AudioComponent {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        AudioComponent::new(owner, context.audio_system.clone())
+    }
+}
+
+impl ReuseComponent for AudioComponent {
+    fn reuse(
+        this: &Rc<RefCell<Self>>,
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        _params: Self::Params,
+    ) {
+        // Its events were stopped on release
+        *this.borrow_mut() = AudioComponent::create(owner.clone(), context.audio_system.clone());
+        owner.borrow_mut().add_component(this.clone());
+    }
+
+    fn release(this: &Rc<RefCell<Self>>) {
+        this.borrow_mut().stop_all_events();
+    }
+}
+
 impl Component for AudioComponent {
     fn update(
         &mut self,
-        _delta_time: f32,
-        owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        delta_time: f32,
+        owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
     ) {
-        self.owner_world_transform = owner_info.3.clone();
+        if delta_time > 0.0 {
+            let moved = owner_info.world_transform.get_translation()
+                - self.owner_world_transform.get_translation();
+            self.velocity = moved * (1.0 / delta_time);
+        }
+        self.owner_world_transform = owner_info.world_transform.clone();
         self.events_2d.retain(|event| event.borrow().is_valid());
         self.events_3d.retain(|event| event.borrow().is_valid());
-
-        (None, None, None, vec![])
     }
 
     fn on_update_world_transform(&mut self, _owner_info: &(Vector3, f32, Quaternion)) {
         let world = &self.owner_world_transform;
         for event in &mut self.events_3d {
             if event.borrow().is_valid() {
-                event.borrow_mut().set_3d_attributes(world);
+                let _ = event.borrow_mut().set_3d_attributes(world, &self.velocity);
             }
         }
     }
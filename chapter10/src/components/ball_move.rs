@@ -1,17 +1,28 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{
+        actor::Actor,
+        actor_builder::{BuildComponent, ReuseComponent},
+    },
     collision::line_segment::LineSegment,
-    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::phys_world::PhysWorld,
+    math::vector3::Vector3,
+    system::{engine_context::EngineContext, phys_world::PhysWorld},
 };
 
 use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
     component::{self, generate_id, Component, State},
     move_component::{self, MoveComponent},
 };
 
+/// Surface hit by the ball
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounce {
+    pub point: Vector3,
+    pub normal: Vector3,
+}
+
 pub struct BallMove {
     id: u32,
     owner: Rc<RefCell<dyn Actor>>,
@@ -22,6 +33,9 @@ pub struct BallMove {
     strafe_speed: f32,
     phys_world: Rc<RefCell<PhysWorld>>,
     player_id: u32,
+    damage: f32,
+    // Bounces since the last take_bounces call
+    bounces: Vec<Bounce>,
 }
 
 impl BallMove {
@@ -32,9 +46,23 @@ impl BallMove {
         phys_world: Rc<RefCell<PhysWorld>>,
         player_id: u32,
     ) -> Rc<RefCell<Self>> {
-        let this = Self {
+        let this = BallMove::create(owner.clone(), phys_world, player_id);
+        let result = Rc::new(RefCell::new(this));
+
+        let mut borrowed_onwer = owner.borrow_mut();
+        borrowed_onwer.add_component(result.clone());
+
+        result
+    }
+
+    fn create(
+        owner: Rc<RefCell<dyn Actor>>,
+        phys_world: Rc<RefCell<PhysWorld>>,
+        player_id: u32,
+    ) -> Self {
+        Self {
             id: generate_id(),
-            owner: owner.clone(),
+            owner,
             update_order: 10,
             state: State::Active,
             angular_speed: 0.0,
@@ -42,14 +70,18 @@ impl BallMove {
             strafe_speed: 0.0,
             phys_world,
             player_id,
-        };
-
-        let result = Rc::new(RefCell::new(this));
+            damage: 25.0,
+            bounces: vec![],
+        }
+    }
 
-        let mut borrowed_onwer = owner.borrow_mut();
-        borrowed_onwer.add_component(result.clone());
+    pub fn set_damage(&mut self, damage: f32) {
+        self.damage = damage;
+    }
 
-        result
+    /// Drain bounces since last call
+    pub fn take_bounces(&mut self) -> Vec<Bounce> {
+        std::mem::take(&mut self.bounces)
     }
 }
 
@@ -57,40 +89,181 @@ impl MoveComponent for BallMove {
     move_component::impl_getters_setters! {}
 }
 
+impl BuildComponent for BallMove {
+    type Params = u32;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        BallMove::new(owner, context.phys_world.clone(), params)
+    }
+}
+
+impl ReuseComponent for BallMove {
+    fn reuse(
+        this: &Rc<RefCell<Self>>,
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    ) {
+        // Keeps the capacity of the bounces
+        let mut bounces = std::mem::take(&mut this.borrow_mut().bounces);
+        bounces.clear();
+        let mut ball_move = BallMove::create(owner.clone(), context.phys_world.clone(), params);
+        ball_move.bounces = bounces;
+        *this.borrow_mut() = ball_move;
+        owner.borrow_mut().add_component(this.clone());
+    }
+}
+
 impl Component for BallMove {
     fn update(
         &mut self,
         delta_time: f32,
-        owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        owner_info: &OwnerSnapshot,
+        commands: &mut CommandBuffer,
     ) {
         // Construct segment in direction of travel
-        let start = owner_info.0.clone();
-        let mut direction = owner_info.2.clone();
+        let start = owner_info.position.clone();
+        let mut direction = owner_info.forward.clone();
         let end = start.clone() + direction.clone() * BallMove::SEGMENT_LENGTH;
 
         // Create line segment
         let line = LineSegment::new(start, end);
 
         // Test segment vs world
-        let mut hit_actors = vec![];
         if let Some(collision_info) = self.phys_world.borrow().segment_cast(&line) {
             if collision_info.actor_id != self.player_id {
                 direction = Vector3::reflect(&direction, &collision_info.normal);
-                hit_actors.push(collision_info.actor);
+                self.bounces.push(Bounce {
+                    point: collision_info.point.clone(),
+                    normal: collision_info.normal.clone(),
+                });
+                // The hit actor can be borrowed right now, e.g. when it is the owner
+                commands.take_damage(
+                    collision_info.actor.clone(),
+                    self.damage,
+                    owner_info.position.clone(),
+                );
+                commands.hit_target(collision_info.actor);
             }
         }
 
-        let mut result = move_component::update_move_component(self, delta_time, owner_info);
-        result.2 = Some(direction);
-        result.3 = hit_actors;
-
-        result
+        commands.rotate_to_new_forward(direction);
+        move_component::update_move_component(self, delta_time, owner_info, commands);
     }
 
     component::impl_getters_setters! {}
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::{Rc, Weak},
+    };
+
+    use crate::{
+        actors::actor::{self, generate_id, test::TestActor, Actor, State},
+        collision::aabb::AABB,
+        components::{
+            box_component::BoxComponent,
+            command_buffer::CommandBuffer,
+            component::{Component, State as ComponentState},
+            move_component::MoveComponent,
+        },
+        math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+        system::{
+            asset_manager::AssetManager, entity_manager::EntityManager, phys_world::PhysWorld,
+        },
+    };
+
+    use super::BallMove;
+
+    /// Counts the hits instead of notifying the HUD
+    struct HitActor {
+        id: u32,
+        state: State,
+        world_transform: Matrix4,
+        recompute_world_transform: bool,
+        position: Vector3,
+        scale: f32,
+        rotation: Quaternion,
+        components: Vec<Rc<RefCell<dyn Component>>>,
+        parent: Option<Weak<RefCell<dyn Actor>>>,
+        children: Vec<Rc<RefCell<dyn Actor>>>,
+        hits: Cell<u32>,
+    }
+
+    impl HitActor {
+        fn new(position: Vector3) -> Self {
+            Self {
+                id: generate_id(),
+                state: State::Active,
+                world_transform: Matrix4::new(),
+                recompute_world_transform: true,
+                position,
+                scale: 1.0,
+                rotation: Quaternion::new(),
+                components: vec![],
+                parent: None,
+                children: vec![],
+                hits: Cell::new(0),
+            }
+        }
+    }
+
+    impl Actor for HitActor {
+        fn update_actor(&mut self, _delta_time: f32) {}
+
+        fn hit_target(&self) {
+            self.hits.set(self.hits.get() + 1);
+        }
+
+        actor::impl_getters_setters! {}
+
+        actor::impl_component_operation! {}
+    }
+
+    impl Drop for HitActor {
+        actor::impl_drop! {}
+    }
+
+    #[test]
+    fn test_ball_hits_target() {
+        let phys_world = PhysWorld::new();
+
+        let target = Rc::new(RefCell::new(HitActor::new(Vector3::new(500.0, 0.0, 0.0))));
+        let target_actor: Rc<RefCell<dyn Actor>> = target.clone();
+        BoxComponent::new(target_actor.clone(), phys_world.clone())
+            .borrow_mut()
+            .set_object_box(AABB::new(
+                Vector3::new(-25.0, -25.0, -25.0),
+                Vector3::new(25.0, 25.0, 25.0),
+            ));
+        target_actor.borrow_mut().compute_world_transform();
+
+        // Flying along +X from the player, like BallActor
+        let player_id = generate_id();
+        let ball: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        BallMove::new(ball.clone(), phys_world, player_id)
+            .borrow_mut()
+            .set_forward_speed(1500.0);
+
+        let mut commands = CommandBuffer::new();
+        let mut frames = 0;
+        while target.borrow().hits.get() == 0 {
+            ball.borrow_mut().update(1.0 / 60.0, &mut commands);
+            ball.borrow_mut().compute_world_transform();
+            commands.apply_actor_commands();
+            frames += 1;
+            assert!(frames < 60, "The ball never hit the target");
+        }
+
+        assert_eq!(1, target.borrow().hits.get());
+        // Bounced back off the near face
+        assert!(ball.borrow().get_forward().x < 0.0);
+    }
+}
@@ -0,0 +1,240 @@
+use std::{cell::RefCell, ptr::null, rc::Rc};
+
+use gl::{TRIANGLES, UNSIGNED_INT};
+
+use crate::{
+    actors::{
+        actor::Actor,
+        actor_builder::{BuildComponent, ReuseComponent},
+    },
+    graphics::{shader::Shader, texture::Texture, vertex_array::VertexArray},
+    math::{matrix4::Matrix4, vector3::Vector3},
+    system::engine_context::EngineContext,
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, Component, State},
+};
+
+/// How the quad turns to face the camera
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BillboardMode {
+    // Always faces the camera, e.g. smoke puffs
+    Spherical,
+    // Only turns around the up axis and stays upright, e.g. pickups standing on the ground
+    Cylindrical,
+}
+
+/// Textured quad in the world at the owner position, turned toward the camera of each view.
+/// Blended with the transparent meshes, tested against the depth of the opaque ones
+pub struct BillboardComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    texture: Rc<Texture>,
+    mode: BillboardMode,
+    // World units of one texel before the owner scale
+    texel_size: f32,
+    alpha: f32,
+}
+
+impl BillboardComponent {
+    pub fn new(
+        owner: Rc<RefCell<dyn Actor>>,
+        texture: Rc<Texture>,
+        mode: BillboardMode,
+    ) -> Rc<RefCell<Self>> {
+        let result = Rc::new(RefCell::new(BillboardComponent::create(
+            owner.clone(),
+            texture,
+            mode,
+        )));
+        BillboardComponent::register(owner, &result);
+        result
+    }
+
+    fn create(owner: Rc<RefCell<dyn Actor>>, texture: Rc<Texture>, mode: BillboardMode) -> Self {
+        Self {
+            id: component::generate_id(),
+            owner,
+            update_order: 100,
+            state: State::Active,
+            texture,
+            mode,
+            texel_size: 1.0,
+            alpha: 1.0,
+        }
+    }
+
+    fn register(owner: Rc<RefCell<dyn Actor>>, this: &Rc<RefCell<Self>>) {
+        owner.borrow_mut().add_component(this.clone());
+        owner
+            .borrow()
+            .get_asset_manager()
+            .borrow_mut()
+            .add_billboard(this.clone());
+    }
+
+    /// Quad is the unit square in the xy plane, the camera axes are from the inverted view
+    pub fn draw(
+        &self,
+        shader: &Shader,
+        quad: &VertexArray,
+        camera_position: &Vector3,
+        inverted_view: &Matrix4,
+    ) {
+        let owner = self.owner.borrow();
+        let position = owner.get_world_transform().get_translation();
+        let (right, up) = get_billboard_axes(
+            self.mode,
+            &inverted_view.get_x_axis(),
+            &inverted_view.get_y_axis(),
+            &(camera_position.clone() - position.clone()),
+        );
+
+        let size = self.texel_size * owner.get_scale();
+        let right = right * (self.texture.get_width() as f32 * size);
+        let up = up * (self.texture.get_height() as f32 * size);
+        let normal = Vector3::cross(&right, &up).normalize();
+        let world = Matrix4::from([
+            [right.x, right.y, right.z, 0.0],
+            [up.x, up.y, up.z, 0.0],
+            [normal.x, normal.y, normal.z, 0.0],
+            [position.x, position.y, position.z, 1.0],
+        ]);
+
+        shader.set_matrix_uniform("uWorldTransform", world);
+        shader.set_float_uniform("uAlpha", self.alpha);
+        self.texture.set_active();
+        quad.set_active();
+
+        unsafe {
+            gl::DrawElements(
+                TRIANGLES,
+                quad.get_num_indices() as i32,
+                UNSIGNED_INT,
+                null(),
+            );
+        }
+    }
+
+    pub fn get_world_position(&self) -> Vector3 {
+        self.owner.borrow().get_world_transform().get_translation()
+    }
+
+    pub fn set_texel_size(&mut self, texel_size: f32) {
+        self.texel_size = texel_size;
+    }
+
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    pub fn set_mode(&mut self, mode: BillboardMode) {
+        self.mode = mode;
+    }
+}
+
+/// Right and up of the quad in world space. Cylindrical billboards keep +z as up and turn
+/// toward the camera horizontally, falling back to the camera axes when right above it
+fn get_billboard_axes(
+    mode: BillboardMode,
+    camera_right: &Vector3,
+    camera_up: &Vector3,
+    to_camera: &Vector3,
+) -> (Vector3, Vector3) {
+    match mode {
+        BillboardMode::Spherical => (camera_right.normalize(), camera_up.normalize()),
+        BillboardMode::Cylindrical => {
+            let right = Vector3::cross(to_camera, &Vector3::UNIT_Z);
+            if right.length_sq() < 0.0001 {
+                (camera_right.normalize(), Vector3::UNIT_Z)
+            } else {
+                (right.normalize(), Vector3::UNIT_Z)
+            }
+        }
+    }
+}
+
+impl BuildComponent for BillboardComponent {
+    type Params = (Rc<Texture>, BillboardMode);
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        let (texture, mode) = params;
+        BillboardComponent::new(owner, texture, mode)
+    }
+}
+
+impl ReuseComponent for BillboardComponent {
+    fn reuse(
+        this: &Rc<RefCell<Self>>,
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        params: Self::Params,
+    ) {
+        let (texture, mode) = params;
+        *this.borrow_mut() = BillboardComponent::create(owner.clone(), texture, mode);
+        BillboardComponent::register(owner, this);
+    }
+}
+
+impl Component for BillboardComponent {
+    fn update(
+        &mut self,
+        _delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::{get_billboard_axes, BillboardMode};
+
+    #[test]
+    fn test_get_billboard_axes() {
+        // Camera looking down at the billboard from behind along +x
+        let camera_right = Vector3::new(0.0, 1.0, 0.0);
+        let camera_up = Vector3::new(0.6, 0.0, 0.8);
+        let to_camera = Vector3::new(-100.0, 0.0, 100.0);
+
+        let (right, up) = get_billboard_axes(
+            BillboardMode::Spherical,
+            &camera_right,
+            &camera_up,
+            &to_camera,
+        );
+        assert_near_eq!(1.0, right.y, 0.001);
+        assert_near_eq!(0.6, up.x, 0.001);
+
+        // Stays upright, still facing the camera horizontally
+        let (right, up) = get_billboard_axes(
+            BillboardMode::Cylindrical,
+            &camera_right,
+            &camera_up,
+            &to_camera,
+        );
+        assert_near_eq!(1.0, right.y, 0.001);
+        assert_near_eq!(1.0, up.z, 0.001);
+
+        // Straight above, no horizontal direction to face
+        let (right, _) = get_billboard_axes(
+            BillboardMode::Cylindrical,
+            &camera_right,
+            &camera_up,
+            &Vector3::new(0.0, 0.0, 100.0),
+        );
+        assert_near_eq!(1.0, right.y, 0.001);
+    }
+}
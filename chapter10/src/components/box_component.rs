@@ -1,13 +1,42 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{actor::Actor, actor_builder::BuildComponent},
     collision::aabb::AABB,
-    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::phys_world::PhysWorld,
+    math::{quaternion::Quaternion, vector3::Vector3},
+    system::{engine_context::EngineContext, phys_world::PhysWorld},
 };
 
-use super::component::{self, generate_id, Component, State};
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
+
+/// What a box is made of, picks the footstep sound when walking on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Material {
+    Default,
+    Grass,
+}
+
+impl Material {
+    /// Material by the name used in prefabs
+    pub fn from_name(name: &str) -> Option<Material> {
+        match name {
+            "Default" => Some(Material::Default),
+            "Grass" => Some(Material::Grass),
+            _ => None,
+        }
+    }
+
+    /// Value of the "Surface" parameter of the footstep event
+    pub fn get_foot_step_surface(&self) -> f32 {
+        match self {
+            Material::Default => 0.0,
+            Material::Grass => 0.5,
+        }
+    }
+}
 
 pub struct BoxComponent {
     id: u32,
@@ -18,6 +47,9 @@ pub struct BoxComponent {
     object_box: AABB,
     world_box: AABB,
     should_rotate: bool,
+    material: Material,
+    // The owner never moves, so the world box is computed once when it is placed
+    is_static: bool,
 }
 
 impl BoxComponent {
@@ -34,6 +66,8 @@ impl BoxComponent {
             object_box: AABB::new(Vector3::ZERO, Vector3::ZERO),
             world_box: AABB::new(Vector3::ZERO, Vector3::ZERO),
             should_rotate: true,
+            material: Material::Default,
+            is_static: owner.borrow().is_static(),
         };
 
         let result = Rc::new(RefCell::new(this));
@@ -58,20 +92,41 @@ impl BoxComponent {
     pub fn get_owner_id(&self) -> u32 {
         self.owner_id
     }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    pub fn get_material(&self) -> Material {
+        self.material
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+}
+
+impl BuildComponent for BoxComponent {
+    type Params = AABB;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        let this = BoxComponent::new(owner, context.phys_world.clone());
+        this.borrow_mut().set_object_box(params);
+        this
+    }
 }
 
 impl Component for BoxComponent {
     fn update(
         &mut self,
         _delta_time: f32,
-        _owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
     ) {
-        (None, None, None, vec![])
     }
 
     fn on_update_world_transform(&mut self, owner_info: &(Vector3, f32, Quaternion)) {
@@ -94,3 +149,15 @@ impl Component for BoxComponent {
 
     component::impl_getters_setters! {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Material;
+
+    #[test]
+    fn test_material_from_name() {
+        assert_eq!(Some(Material::Grass), Material::from_name("Grass"));
+        assert_eq!(Some(Material::Default), Material::from_name("Default"));
+        assert_eq!(None, Material::from_name("grass"));
+    }
+}
@@ -2,19 +2,32 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     actors::actor::Actor,
-    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    math::matrix4::Matrix4,
     system::{audio_system::AudioSystem, renderer::Renderer},
 };
 
-use super::component::{self, generate_id, Component, State};
+use super::{
+    camera_shake::CameraShake,
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
 
 pub trait CameraComponent {
     fn set_view_matrix(&mut self, view: Matrix4) {
-        // Pass view matrix to renderer and audio system
+        // Pass view matrix to renderer and audio system, the listener doesn't shake
         self.get_audio_system().borrow_mut().set_listener(&view);
+        let view = match self.get_shake() {
+            Some(shake) => shake.apply(view),
+            None => view,
+        };
         self.get_renderer().borrow_mut().set_view_matrix(view);
     }
 
+    /// Cameras that can shake return their shake, updated before setting the view
+    fn get_shake(&self) -> Option<&CameraShake> {
+        None
+    }
+
     fn get_renderer(&self) -> &Rc<RefCell<Renderer>>;
 
     fn get_audio_system(&self) -> &Rc<RefCell<AudioSystem>>;
@@ -71,14 +84,9 @@ impl Component for DefaultCameraComponent {
     fn update(
         &mut self,
         _delta_time: f32,
-        _owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
     ) {
-        (None, None, None, vec![])
     }
 
     component::impl_getters_setters! {}
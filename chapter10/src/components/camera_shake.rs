@@ -0,0 +1,168 @@
+use crate::math::{matrix4::Matrix4, vector3::Vector3};
+
+/// How strong and how fast a camera shakes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShakeSettings {
+    // Noise samples per second, higher is more jittery
+    pub frequency: f32,
+    // Yaw/pitch/roll at full trauma, in radians
+    pub max_angle: f32,
+    // Sideways and vertical offset at full trauma, in camera space units
+    pub max_offset: f32,
+    // Trauma lost per second
+    pub decay: f32,
+    // Fraction of the recoil recovered per second
+    pub recoil_recovery: f32,
+}
+
+impl ShakeSettings {
+    pub fn new() -> Self {
+        Self {
+            frequency: 15.0,
+            max_angle: 0.05,
+            max_offset: 4.0,
+            decay: 1.5,
+            recoil_recovery: 10.0,
+        }
+    }
+}
+
+/// Trauma based shake and recoil kick applied on top of the view of a camera component.
+/// Trauma adds up from events like shots and explosions, the shake grows with its square
+/// so small hits stay subtle
+pub struct CameraShake {
+    settings: ShakeSettings,
+    // 0 is still, 1 the strongest shake
+    trauma: f32,
+    // Pitch up from shooting, in radians
+    recoil: f32,
+    // Drives the noise
+    time: f32,
+}
+
+impl CameraShake {
+    // Seeds of the noise of each yaw/pitch/roll/x/y channel, so they move independently
+    const SEEDS: [u32; 5] = [11, 23, 37, 41, 59];
+
+    pub fn new(settings: ShakeSettings) -> Self {
+        Self {
+            settings,
+            trauma: 0.0,
+            recoil: 0.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn add_recoil(&mut self, pitch: f32) {
+        self.recoil += pitch;
+    }
+
+    pub fn get_trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    pub fn get_settings_mut(&mut self) -> &mut ShakeSettings {
+        &mut self.settings
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+        self.trauma = (self.trauma - self.settings.decay * delta_time).max(0.0);
+        self.recoil *= (1.0 - self.settings.recoil_recovery * delta_time).max(0.0);
+    }
+
+    /// The view with the shake and recoil applied in camera space
+    pub fn apply(&self, view: Matrix4) -> Matrix4 {
+        let shake = self.trauma * self.trauma;
+        if shake == 0.0 && self.recoil == 0.0 {
+            return view;
+        }
+
+        let t = self.time * self.settings.frequency;
+        let [yaw, pitch, roll, x, y] = CameraShake::SEEDS.map(|seed| shake * smooth_noise(seed, t));
+        let angle = self.settings.max_angle;
+        let offset = self.settings.max_offset;
+
+        // Camera space is x right, y up, z forward, so pitching up rotates about x
+        view * Matrix4::create_rotation_y(yaw * angle)
+            * Matrix4::create_rotation_x(pitch * angle + self.recoil)
+            * Matrix4::create_rotation_z(roll * angle)
+            * Matrix4::create_translation(&Vector3::new(x * offset, y * offset, 0.0))
+    }
+}
+
+/// Trauma of an event at distance from the camera, none past the radius
+pub fn trauma_at_distance(trauma: f32, distance: f32, radius: f32) -> f32 {
+    trauma * (1.0 - distance / radius).clamp(0.0, 1.0)
+}
+
+/// Value noise in [-1, 1], smoothly interpolated between random values at each integer of t
+fn smooth_noise(seed: u32, t: f32) -> f32 {
+    let cell = t.floor();
+    let fraction = t - cell;
+    let start = hash_to_unit(seed, cell as i32);
+    let end = hash_to_unit(seed, cell as i32 + 1);
+    let blend = fraction * fraction * (3.0 - 2.0 * fraction);
+    start + (end - start) * blend
+}
+
+fn hash_to_unit(seed: u32, index: i32) -> f32 {
+    let mut hash = (index as u32).wrapping_mul(0x9E37_79B9) ^ seed.wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2C1B_3C6D);
+    hash ^= hash >> 12;
+    (hash & 0xFFFF) as f32 / 0xFFFF as f32 * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        assert_near_eq,
+        math::{matrix4::Matrix4, vector3::Vector3},
+    };
+
+    use super::{smooth_noise, trauma_at_distance, CameraShake, ShakeSettings};
+
+    #[test]
+    fn test_smooth_noise() {
+        for i in 0..100 {
+            let t = i as f32 * 0.37;
+            let value = smooth_noise(7, t);
+            assert!((-1.0..=1.0).contains(&value));
+            // Continuous, no jumps between close samples
+            assert_near_eq!(value, smooth_noise(7, t + 0.001), 0.01);
+        }
+        assert_eq!(smooth_noise(7, 3.5), smooth_noise(7, 3.5));
+    }
+
+    #[test]
+    fn test_camera_shake() {
+        let mut shake = CameraShake::new(ShakeSettings::new());
+        let view = Matrix4::new();
+        assert_eq!(view.mat, shake.apply(view.clone()).mat);
+
+        // Decays back to still
+        shake.add_trauma(2.0);
+        assert_near_eq!(1.0, shake.get_trauma(), 0.001);
+        shake.update(0.5);
+        assert_near_eq!(0.25, shake.get_trauma(), 0.001);
+        shake.update(0.5);
+        assert_near_eq!(0.0, shake.get_trauma(), 0.001);
+
+        // Recoil looks up, so what was straight ahead is now below
+        shake.add_recoil(0.1);
+        let ahead = Vector3::new(0.0, 0.0, 100.0).transform_with_pers_div(shake.apply(view), None);
+        assert!(ahead.y < -5.0);
+    }
+
+    #[test]
+    fn test_trauma_at_distance() {
+        assert_near_eq!(0.8, trauma_at_distance(0.8, 0.0, 1000.0), 0.001);
+        assert_near_eq!(0.4, trauma_at_distance(0.8, 500.0, 1000.0), 0.001);
+        assert_near_eq!(0.0, trauma_at_distance(0.8, 2000.0, 1000.0), 0.001);
+    }
+}
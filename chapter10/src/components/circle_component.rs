@@ -1,11 +1,11 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::{
-    actors::actor::Actor,
-    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-};
+use crate::{actors::actor::Actor, math::vector3::Vector3};
 
-use super::component::{self, generate_id, Component, State};
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
 
 pub struct CircleComponent {
     id: u32,
@@ -59,15 +59,10 @@ impl Component for CircleComponent {
     fn update(
         &mut self,
         _delta_time: f32,
-        owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
     ) {
-        self.center = owner_info.0.clone();
-        (None, None, None, vec![])
+        self.center = owner_info.position.clone();
     }
 
     component::impl_getters_setters! {}
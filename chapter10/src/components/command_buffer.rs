@@ -0,0 +1,147 @@
+use std::{cell::RefCell, rc::Rc, vec::Drain};
+
+use crate::{
+    actors::actor::Actor,
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+};
+
+/// Owner as it was before its components updated. Components read this
+/// instead of borrowing the owner, which is mutably borrowed during the update
+#[derive(Debug, Clone)]
+pub struct OwnerSnapshot {
+    pub position: Vector3,
+    pub rotation: Quaternion,
+    pub forward: Vector3,
+    pub world_transform: Matrix4,
+    pub right: Vector3,
+}
+
+impl OwnerSnapshot {
+    pub fn new<A: Actor + ?Sized>(owner: &A) -> Self {
+        Self {
+            position: owner.get_position().clone(),
+            rotation: owner.get_rotation().clone(),
+            forward: owner.get_forward(),
+            world_transform: owner.get_world_transform().clone(),
+            right: owner.get_right(),
+        }
+    }
+}
+
+/// Change of the owner, applied once all its components read the snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnerCommand {
+    SetPosition(Vector3),
+    SetRotation(Quaternion),
+    RotateToNewForward(Vector3),
+}
+
+/// Change of another actor (or the owner itself), applied once no actor is borrowed
+pub enum ActorCommand {
+    HitTarget(Rc<RefCell<dyn Actor>>),
+    TakeDamage {
+        target: Rc<RefCell<dyn Actor>>,
+        amount: f32,
+        source: Vector3,
+    },
+}
+
+/// Changes queued by components during their update, so they never borrow an actor
+/// that is already borrowed. Kept by Game and reused every frame
+pub struct CommandBuffer {
+    owner_commands: Vec<OwnerCommand>,
+    actor_commands: Vec<ActorCommand>,
+}
+
+impl CommandBuffer {
+    pub fn new() -> Self {
+        Self {
+            owner_commands: vec![],
+            actor_commands: vec![],
+        }
+    }
+
+    pub fn set_position(&mut self, position: Vector3) {
+        self.owner_commands
+            .push(OwnerCommand::SetPosition(position));
+    }
+
+    pub fn set_rotation(&mut self, rotation: Quaternion) {
+        self.owner_commands
+            .push(OwnerCommand::SetRotation(rotation));
+    }
+
+    pub fn rotate_to_new_forward(&mut self, forward: Vector3) {
+        self.owner_commands
+            .push(OwnerCommand::RotateToNewForward(forward));
+    }
+
+    pub fn hit_target(&mut self, target: Rc<RefCell<dyn Actor>>) {
+        self.actor_commands.push(ActorCommand::HitTarget(target));
+    }
+
+    pub fn take_damage(&mut self, target: Rc<RefCell<dyn Actor>>, amount: f32, source: Vector3) {
+        self.actor_commands.push(ActorCommand::TakeDamage {
+            target,
+            amount,
+            source,
+        });
+    }
+
+    /// Owner changes in the order they were queued
+    pub fn drain_owner_commands(&mut self) -> Drain<'_, OwnerCommand> {
+        self.owner_commands.drain(..)
+    }
+
+    /// Call once no actor is borrowed, e.g. after all the actors updated
+    pub fn apply_actor_commands(&mut self) {
+        for command in self.actor_commands.drain(..) {
+            match command {
+                ActorCommand::HitTarget(target) => target.borrow().hit_target(),
+                ActorCommand::TakeDamage {
+                    target,
+                    amount,
+                    source,
+                } => target.borrow().take_damage(amount, &source),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        math::vector3::Vector3,
+    };
+
+    use super::{CommandBuffer, OwnerCommand};
+
+    #[test]
+    fn test_command_buffer() {
+        let mut commands = CommandBuffer::new();
+        commands.set_position(Vector3::new(1.0, 2.0, 3.0));
+        commands.rotate_to_new_forward(Vector3::UNIT_Y);
+
+        let owner_commands = commands.drain_owner_commands().collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                OwnerCommand::SetPosition(Vector3::new(1.0, 2.0, 3.0)),
+                OwnerCommand::RotateToNewForward(Vector3::UNIT_Y),
+            ],
+            owner_commands
+        );
+        assert_eq!(0, commands.drain_owner_commands().count());
+
+        // The target can be borrowed by the time the commands are applied
+        let target: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let borrowed = target.borrow_mut();
+        commands.hit_target(target.clone());
+        commands.take_damage(target.clone(), 10.0, Vector3::ZERO);
+        drop(borrowed);
+        commands.apply_actor_commands();
+        assert_eq!(1, Rc::strong_count(&target));
+    }
+}
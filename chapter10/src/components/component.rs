@@ -1,4 +1,5 @@
 use std::{
+    any::Any,
     cell::RefCell,
     rc::Rc,
     sync::atomic::{AtomicU32, Ordering},
@@ -6,9 +7,12 @@ use std::{
 
 use crate::{
     actors::actor::Actor,
-    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    math::{quaternion::Quaternion, vector3::Vector3},
+    system::input_system::InputState,
 };
 
+use super::command_buffer::{CommandBuffer, OwnerSnapshot};
+
 static ID: AtomicU32 = AtomicU32::new(0);
 
 #[derive(Debug, PartialEq, Eq)]
@@ -18,18 +22,9 @@ pub enum State {
 }
 
 pub trait Component {
-    fn update(
-        &mut self,
-        delta_time: f32,
-        owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
-    );
-
-    fn process_input(&mut self, _key_state: &KeyboardState) {}
+    fn update(&mut self, delta_time: f32, owner_info: &OwnerSnapshot, commands: &mut CommandBuffer);
+
+    fn process_input(&mut self, _state: &InputState) {}
 
     fn on_update_world_transform(&mut self, _owner_info: &(Vector3, f32, Quaternion)) {}
 
@@ -42,6 +37,14 @@ pub trait Component {
     fn get_state(&self) -> &State;
 
     fn set_state(&mut self, state: State);
+
+    /// Concrete component, to find components by type (see downcast)
+    fn as_any(&self) -> &dyn Any;
+
+    /// Name of the concrete type, for debug listings
+    fn get_type_name(&self) -> &'static str {
+        std::any::type_name::<Self>().rsplit("::").next().unwrap()
+    }
 }
 
 pub fn generate_id() -> u32 {
@@ -71,11 +74,29 @@ macro_rules! impl_getters_setters {
         fn set_state(&mut self, state: State) {
             self.state = state;
         }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
     };
 }
 
 pub(crate) use impl_getters_setters;
-use sdl2::keyboard::KeyboardState;
+
+/// Component as its concrete type T, or None if it is of another type.
+/// A component which is mutably borrowed, e.g. being updated, can't be checked and gives None
+pub fn downcast<T: Component + 'static>(
+    component: &Rc<RefCell<dyn Component>>,
+) -> Option<Rc<RefCell<T>>> {
+    match component.try_borrow() {
+        Ok(borrowed) if borrowed.as_any().is::<T>() => {}
+        _ => return None,
+    }
+
+    // The allocation holds a RefCell<T>, so only the vtable is dropped from the pointer
+    let raw = Rc::into_raw(component.clone()) as *const RefCell<T>;
+    Some(unsafe { Rc::from_raw(raw) })
+}
 
 pub fn remove_component(this: Rc<RefCell<dyn Component>>) {
     debug_assert!(*this.borrow().get_state() == State::Active, "not active");
@@ -92,8 +113,10 @@ pub mod tests {
 
     use crate::{
         actors::actor::{test::TestActor, Actor},
-        components::component::remove_component,
-        math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+        components::{
+            command_buffer::{CommandBuffer, OwnerSnapshot},
+            component::remove_component,
+        },
     };
 
     use super::{generate_id, Component, State};
@@ -126,14 +149,9 @@ pub mod tests {
         fn update(
             &mut self,
             _delta_time: f32,
-            _owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-        ) -> (
-            Option<Vector3>,
-            Option<Quaternion>,
-            Option<Vector3>,
-            Vec<Rc<RefCell<dyn Actor>>>,
+            _owner_info: &OwnerSnapshot,
+            _commands: &mut CommandBuffer,
         ) {
-            (None, None, None, vec![])
         }
 
         impl_getters_setters! {}
@@ -0,0 +1,204 @@
+use std::{cell::RefCell, f32::consts::PI, rc::Rc};
+
+use crate::{
+    actors::actor::Actor,
+    math::{basic, vector3::Vector3},
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
+
+/// Procedural animation of the first person model (walk bobbing, recoil, aim down sights).
+/// Drives the local position of the owner, which is expected to be attached to the player.
+pub struct FirstPersonModelComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    // Position relative to the parent when not animated
+    hip_position: Vector3,
+    // Position relative to the parent while aiming
+    aim_position: Vector3,
+    move_speed: f32,
+    bob_time: f32,
+    // 1.0 right after a shot, back to 0.0 once recovered
+    recoil: f32,
+    is_aiming: bool,
+    // 0.0 is hip, 1.0 is fully aimed
+    aim_blend: f32,
+}
+
+impl FirstPersonModelComponent {
+    // Move speed giving the full bob
+    const MAX_MOVE_SPEED: f32 = 400.0;
+    // Bob cycles per second at full speed
+    const BOB_FREQUENCY: f32 = 2.0;
+    const BOB_HEIGHT: f32 = 1.5;
+    const BOB_SWAY: f32 = 1.0;
+    // How far the model kicks back on a shot
+    const RECOIL_DISTANCE: f32 = 6.0;
+    // Recoil recovered per second
+    const RECOIL_RECOVERY: f32 = 8.0;
+    // Aim blend per second
+    const AIM_SPEED: f32 = 6.0;
+    // Share of the bob still applied while aiming
+    const AIM_BOB_SCALE: f32 = 0.2;
+
+    pub fn new(
+        owner: Rc<RefCell<dyn Actor>>,
+        hip_position: Vector3,
+        aim_position: Vector3,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            hip_position,
+            aim_position,
+            move_speed: 0.0,
+            bob_time: 0.0,
+            recoil: 0.0,
+            is_aiming: false,
+            aim_blend: 0.0,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    /// Speed of the player, drives the bobbing
+    pub fn set_move_speed(&mut self, move_speed: f32) {
+        self.move_speed = move_speed;
+    }
+
+    /// Kick the model back (called on shoot)
+    pub fn kick(&mut self) {
+        self.recoil = 1.0;
+    }
+
+    pub fn toggle_aim(&mut self) {
+        self.is_aiming = !self.is_aiming;
+    }
+
+    pub fn is_aiming(&self) -> bool {
+        self.is_aiming
+    }
+
+    fn compute_position(&self) -> Vector3 {
+        let speed_ratio = (self.move_speed.abs() / FirstPersonModelComponent::MAX_MOVE_SPEED)
+            .clamp(0.0, 1.0)
+            * basic::lerp(
+                1.0,
+                FirstPersonModelComponent::AIM_BOB_SCALE,
+                self.aim_blend,
+            );
+        let phase = self.bob_time * PI * 2.0;
+        // Figure eight: up and down twice per side to side sway
+        let bob = Vector3::new(
+            0.0,
+            (phase * 0.5).sin() * FirstPersonModelComponent::BOB_SWAY,
+            -phase.cos().abs() * FirstPersonModelComponent::BOB_HEIGHT,
+        ) * speed_ratio;
+
+        let base = Vector3::new(
+            basic::lerp(self.hip_position.x, self.aim_position.x, self.aim_blend),
+            basic::lerp(self.hip_position.y, self.aim_position.y, self.aim_blend),
+            basic::lerp(self.hip_position.z, self.aim_position.z, self.aim_blend),
+        );
+        let kick =
+            Vector3::NEGATIVE_UNIT_X * (self.recoil * FirstPersonModelComponent::RECOIL_DISTANCE);
+
+        base + bob + kick
+    }
+}
+
+impl Component for FirstPersonModelComponent {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        commands: &mut CommandBuffer,
+    ) {
+        let speed_ratio =
+            (self.move_speed.abs() / FirstPersonModelComponent::MAX_MOVE_SPEED).clamp(0.0, 1.0);
+        if speed_ratio > 0.0 {
+            self.bob_time += delta_time * FirstPersonModelComponent::BOB_FREQUENCY * speed_ratio;
+        } else {
+            // Settle back to the rest position of the cycle
+            self.bob_time = 0.0;
+        }
+
+        self.recoil =
+            (self.recoil - delta_time * FirstPersonModelComponent::RECOIL_RECOVERY).max(0.0);
+
+        let aim_step = delta_time * FirstPersonModelComponent::AIM_SPEED;
+        self.aim_blend = if self.is_aiming {
+            (self.aim_blend + aim_step).min(1.0)
+        } else {
+            (self.aim_blend - aim_step).max(0.0)
+        };
+
+        commands.set_position(self.compute_position());
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        assert_near_eq,
+        components::{
+            command_buffer::{CommandBuffer, OwnerCommand, OwnerSnapshot},
+            component::Component,
+        },
+        math::vector3::Vector3,
+    };
+
+    use super::FirstPersonModelComponent;
+
+    // Position the model asks its owner to move to
+    fn update_position(
+        model: &mut FirstPersonModelComponent,
+        delta_time: f32,
+        owner_info: &OwnerSnapshot,
+    ) -> Vector3 {
+        let mut commands = CommandBuffer::new();
+        model.update(delta_time, owner_info, &mut commands);
+        let command = commands.drain_owner_commands().next();
+        match command {
+            Some(OwnerCommand::SetPosition(position)) => position,
+            command => panic!("Unexpected command {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_recoil_and_aim() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let hip = Vector3::new(10.0, 10.0, -10.0);
+        let aim = Vector3::new(10.0, 0.0, -5.0);
+        let owner_info = OwnerSnapshot::new(&*owner.borrow());
+        let model = FirstPersonModelComponent::new(owner, hip.clone(), aim.clone());
+        let mut model = model.borrow_mut();
+
+        // Standing still, the model rests at the hip position
+        let position = update_position(&mut model, 0.1, &owner_info);
+        assert_eq!(hip, position);
+
+        model.kick();
+        let position = update_position(&mut model, 0.0, &owner_info);
+        assert_near_eq!(hip.x - 6.0, position.x, 0.001);
+
+        // Recoil recovered and fully aimed after a second
+        model.toggle_aim();
+        let position = update_position(&mut model, 1.0, &owner_info);
+        assert_eq!(aim, position);
+    }
+}
@@ -0,0 +1,142 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    math::{matrix4::Matrix4, vector3::Vector3},
+    system::{audio_system::AudioSystem, engine_context::EngineContext, renderer::Renderer},
+};
+
+use super::{
+    camera_component::{self, CameraComponent},
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
+
+/// Camera trailing behind and above the owner, pulled to its ideal position by a spring.
+/// Only sets the view while active, so the owner can share the screen with other cameras
+pub struct FollowCamera {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    renderer: Rc<RefCell<Renderer>>,
+    audio_system: Rc<RefCell<AudioSystem>>,
+    active: bool,
+    // Jump to the ideal position on the next update, instead of flying there
+    snap_to_ideal: bool,
+    actual_position: Vector3,
+    velocity: Vector3,
+    horizontal_distance: f32,
+    vertical_distance: f32,
+    // How far in front of the owner the camera looks at
+    target_distance: f32,
+    spring_constant: f32,
+}
+
+impl FollowCamera {
+    pub fn new(
+        owner: Rc<RefCell<dyn Actor>>,
+        renderer: Rc<RefCell<Renderer>>,
+        audio_system: Rc<RefCell<AudioSystem>>,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 200,
+            state: State::Active,
+            renderer,
+            audio_system,
+            active: true,
+            snap_to_ideal: true,
+            actual_position: Vector3::ZERO,
+            velocity: Vector3::ZERO,
+            horizontal_distance: 350.0,
+            vertical_distance: 150.0,
+            target_distance: 100.0,
+            spring_constant: 64.0,
+        };
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    /// Activating jumps to the ideal position, instead of flying there from where it was left
+    pub fn set_active(&mut self, active: bool) {
+        if active && !self.active {
+            self.snap_to_ideal = true;
+        }
+        self.active = active;
+    }
+
+    fn compute_camera_position(&self, owner_position: Vector3, owner_forward: Vector3) -> Vector3 {
+        let mut camera_position = owner_position;
+        camera_position -= owner_forward * self.horizontal_distance;
+        camera_position += Vector3::UNIT_Z * self.vertical_distance;
+        camera_position
+    }
+}
+
+impl CameraComponent for FollowCamera {
+    camera_component::impl_getters! {}
+}
+
+impl BuildComponent for FollowCamera {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        FollowCamera::new(
+            owner,
+            context.renderer.clone(),
+            context.audio_system.clone(),
+        )
+    }
+}
+
+impl Component for FollowCamera {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+        if !self.active {
+            return;
+        }
+
+        // Compute dampening from spring constant
+        let dampening = 2.0 * self.spring_constant.sqrt();
+
+        // Compute ideal position
+        let ideal_position =
+            self.compute_camera_position(owner_info.position.clone(), owner_info.forward.clone());
+        if self.snap_to_ideal {
+            self.snap_to_ideal = false;
+            self.actual_position = ideal_position.clone();
+            self.velocity = Vector3::ZERO;
+        }
+
+        // Compute difference between actual and ideal
+        let diff = self.actual_position.clone() - ideal_position;
+
+        // Compute acceleration of spring
+        let acceleration = diff * -self.spring_constant - self.velocity.clone() * dampening;
+
+        // Update velocity
+        self.velocity += acceleration * delta_time;
+        // Update actual camera position
+        self.actual_position += self.velocity.clone() * delta_time;
+        // Target is target dist in front of owning actor
+        let target =
+            owner_info.position.clone() + owner_info.forward.clone() * self.target_distance;
+
+        // Use actual position here, not ideal
+        let view = Matrix4::create_look_at(&self.actual_position, &target, &Vector3::UNIT_Z);
+        self.set_view_matrix(view);
+    }
+
+    component::impl_getters_setters! {}
+}
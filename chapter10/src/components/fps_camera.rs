@@ -2,13 +2,15 @@ use core::f32;
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{actor::Actor, actor_builder::BuildComponent},
     math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-    system::{audio_system::AudioSystem, renderer::Renderer},
+    system::{audio_system::AudioSystem, engine_context::EngineContext, renderer::Renderer},
 };
 
 use super::{
     camera_component::{self, CameraComponent},
+    camera_shake::{CameraShake, ShakeSettings},
+    command_buffer::{CommandBuffer, OwnerSnapshot},
     component::{self, generate_id, Component, State},
 };
 
@@ -25,6 +27,7 @@ pub struct FPSCamera {
     max_pitch: f32,
     // Current pitch
     pitch: f32,
+    shake: CameraShake,
 }
 
 impl FPSCamera {
@@ -43,6 +46,7 @@ impl FPSCamera {
             pitch_speed: 0.0,
             max_pitch: f32::consts::PI / 3.0,
             pitch: 0.0,
+            shake: CameraShake::new(ShakeSettings::new()),
         };
         let result = Rc::new(RefCell::new(this));
         owner.borrow_mut().add_component(result.clone());
@@ -68,45 +72,65 @@ impl FPSCamera {
     pub fn set_max_pitch(&mut self, pitch: f32) {
         self.max_pitch = pitch;
     }
+
+    pub fn get_shake_mut(&mut self) -> &mut CameraShake {
+        &mut self.shake
+    }
 }
 
 impl CameraComponent for FPSCamera {
+    fn get_shake(&self) -> Option<&CameraShake> {
+        Some(&self.shake)
+    }
+
     camera_component::impl_getters! {}
 }
 
+impl BuildComponent for FPSCamera {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        FPSCamera::new(
+            owner,
+            context.renderer.clone(),
+            context.audio_system.clone(),
+        )
+    }
+}
+
 impl Component for FPSCamera {
     fn update(
         &mut self,
         delta_time: f32,
-        owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
     ) {
         // Camera position is owner position
-        let camera_position = owner_info.0.clone();
+        let camera_position = owner_info.position.clone();
 
         // Update pitch based on pitch speed
         self.pitch += self.pitch_speed * delta_time;
         // Clamp pitch to [-max, +max]
         self.pitch = self.pitch.clamp(-self.max_pitch, self.max_pitch);
         // Make a quaternion representing pitch rotation, which is about owner's right vector
-        let q = Quaternion::from_axis_angle(&owner_info.4, self.pitch);
+        let q = Quaternion::from_axis_angle(&owner_info.right, self.pitch);
 
         // Rotate owner forward by pitch quaternion
-        let view_forward = Vector3::transform(&owner_info.2, &q);
+        let view_forward = Vector3::transform(&owner_info.forward, &q);
 
         // Target position 100 units in front of view forward
         let target = camera_position.clone() + view_forward * 100.0;
         let up = Vector3::UNIT_Z;
 
+        self.shake.update(delta_time);
+
         // Create look at matrix, set as view
         let view = Matrix4::create_look_at(&camera_position, &target, &up);
         self.set_view_matrix(view);
-
-        (None, None, None, vec![])
     }
 
     component::impl_getters_setters! {}
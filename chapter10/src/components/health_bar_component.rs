@@ -0,0 +1,100 @@
+use std::{cell::RefCell, ptr::null, rc::Rc};
+
+use gl::{TRIANGLES, UNSIGNED_INT};
+
+use crate::{
+    actors::actor::Actor,
+    graphics::{shader::Shader, texture::Texture},
+    math::{matrix4::Matrix4, vector3::Vector3},
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, Component, State},
+    health_component::HealthComponent,
+    sprite_component::{self, SpriteComponent},
+};
+
+/// HUD sprite whose width follows the health of the bound HealthComponent
+pub struct HealthBarComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    texture: Option<Rc<Texture>>,
+    draw_order: i32,
+    texture_width: u32,
+    texture_height: u32,
+    health_component: Rc<RefCell<HealthComponent>>,
+}
+
+impl HealthBarComponent {
+    pub fn new(
+        owner: Rc<RefCell<dyn Actor>>,
+        draw_order: i32,
+        health_component: Rc<RefCell<HealthComponent>>,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: component::generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            texture: None,
+            draw_order,
+            texture_height: 0,
+            texture_width: 0,
+            health_component,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+
+        owner.borrow_mut().add_component(result.clone());
+
+        owner
+            .borrow()
+            .get_asset_manager()
+            .borrow_mut()
+            .add_sprite(result.clone());
+
+        result
+    }
+}
+
+impl SpriteComponent for HealthBarComponent {
+    fn draw(&self, shader: &Shader) {
+        if let Some(texture) = self.get_texture() {
+            let full_width = self.get_texture_width() as f32;
+            let width = full_width * self.health_component.borrow().get_health_fraction();
+
+            // Scale the quad by the remaining health
+            let scale_mat = Matrix4::create_scale_xyz(width, self.get_texture_height() as f32, 1.0);
+            // Keep the left edge in place while the bar shrinks
+            let offset =
+                Matrix4::create_translation(&Vector3::new((width - full_width) * 0.5, 0.0, 0.0));
+
+            let world =
+                scale_mat * offset * self.get_owner().borrow().get_world_transform().clone();
+
+            shader.set_matrix_uniform("uWorldTransform", world);
+            texture.set_active();
+
+            unsafe {
+                gl::DrawElements(TRIANGLES, 6, UNSIGNED_INT, null());
+            }
+        }
+    }
+
+    sprite_component::impl_getters_setters! {}
+}
+
+impl Component for HealthBarComponent {
+    fn update(
+        &mut self,
+        _delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+    }
+
+    component::impl_getters_setters! {}
+}
@@ -0,0 +1,188 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    math::vector3::Vector3,
+    system::engine_context::EngineContext,
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageEvent {
+    pub amount: f32,
+    // World position the damage came from
+    pub source: Vector3,
+}
+
+pub struct HealthComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    max_health: f32,
+    health: f32,
+    // Damage received since the last take_damage_events call
+    damage_events: Vec<DamageEvent>,
+    // Called once when health reaches zero.
+    // Must not borrow this component again.
+    on_death: Option<Box<dyn FnMut()>>,
+}
+
+impl HealthComponent {
+    pub fn new(owner: Rc<RefCell<dyn Actor>>, max_health: f32) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            max_health,
+            health: max_health,
+            damage_events: vec![],
+            on_death: None,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    pub fn take_damage(&mut self, amount: f32, source: &Vector3) {
+        if self.is_dead() {
+            return;
+        }
+
+        self.health = (self.health - amount).max(0.0);
+        self.damage_events.push(DamageEvent {
+            amount,
+            source: source.clone(),
+        });
+
+        if self.is_dead() {
+            if let Some(on_death) = self.on_death.as_mut() {
+                on_death();
+            }
+        }
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        if self.is_dead() {
+            return;
+        }
+        self.health = (self.health + amount).min(self.max_health);
+    }
+
+    /// Restore full health, e.g. when respawning
+    pub fn reset(&mut self) {
+        self.health = self.max_health;
+        self.damage_events.clear();
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    pub fn get_health(&self) -> f32 {
+        self.health
+    }
+
+    pub fn get_max_health(&self) -> f32 {
+        self.max_health
+    }
+
+    /// Current health in [0.0, 1.0]
+    pub fn get_health_fraction(&self) -> f32 {
+        if self.max_health <= 0.0 {
+            return 0.0;
+        }
+        self.health / self.max_health
+    }
+
+    pub fn set_on_death(&mut self, on_death: Box<dyn FnMut()>) {
+        self.on_death = Some(on_death);
+    }
+
+    /// Drain damage events received since last call
+    pub fn take_damage_events(&mut self) -> Vec<DamageEvent> {
+        std::mem::take(&mut self.damage_events)
+    }
+}
+
+impl BuildComponent for HealthComponent {
+    type Params = f32;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        HealthComponent::new(owner, params)
+    }
+}
+
+impl Component for HealthComponent {
+    fn update(
+        &mut self,
+        _delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        math::vector3::Vector3,
+    };
+
+    use super::HealthComponent;
+
+    #[test]
+    fn test_take_damage() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let health_component = HealthComponent::new(owner, 100.0);
+        let deaths = Rc::new(RefCell::new(0));
+        let counter = deaths.clone();
+        health_component
+            .borrow_mut()
+            .set_on_death(Box::new(move || *counter.borrow_mut() += 1));
+
+        let source = Vector3::new(1.0, 2.0, 3.0);
+        let mut borrowed = health_component.borrow_mut();
+        borrowed.take_damage(30.0, &source);
+        assert_eq!(70.0, borrowed.get_health());
+        assert_eq!(0.7, borrowed.get_health_fraction());
+
+        borrowed.take_damage(100.0, &source);
+        borrowed.take_damage(10.0, &source);
+        assert!(borrowed.is_dead());
+        assert_eq!(0.0, borrowed.get_health());
+        assert_eq!(1, *deaths.borrow());
+
+        // Damage after death is ignored
+        let events = borrowed.take_damage_events();
+        assert_eq!(2, events.len());
+        assert_eq!(source, events[0].source);
+        assert!(borrowed.take_damage_events().is_empty());
+    }
+
+    #[test]
+    fn test_heal() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let health_component = HealthComponent::new(owner, 50.0);
+        let mut borrowed = health_component.borrow_mut();
+
+        borrowed.take_damage(20.0, &Vector3::ZERO);
+        borrowed.heal(100.0);
+        assert_eq!(50.0, borrowed.get_health());
+    }
+}
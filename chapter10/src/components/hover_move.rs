@@ -0,0 +1,252 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    collision::{aabb::AABB, line_segment::LineSegment},
+    math::{basic, vector3::Vector3},
+    system::{engine_context::EngineContext, phys_world::PhysWorld},
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+    move_component::{self, MoveComponent},
+};
+
+/// Move floating above the ground on a damped spring.
+/// The forward and strafe speeds are the thrust, the velocity reaches them gradually,
+/// so the owner keeps drifting for a while when they change
+pub struct HoverMove {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    angular_speed: f32,
+    forward_speed: f32,
+    strafe_speed: f32,
+    // Boxes of the owner itself are neither ground nor obstacles
+    owner_id: u32,
+    phys_world: Rc<RefCell<PhysWorld>>,
+    // Box around the owner position, its bottom floats at the hover height
+    object_box: AABB,
+    velocity: Vector3,
+}
+
+impl HoverMove {
+    const GRAVITY: f32 = 980.0;
+    // Height of the bottom of the box above the ground at rest
+    const HOVER_HEIGHT: f32 = 50.0;
+    // How fast the velocity follows the thrust, per second
+    const THRUST_RESPONSE: f32 = 1.5;
+    const SPRING_CONSTANT: f32 = 60.0;
+    // Fraction of the critical damping, a bit less so it bobs once
+    const DAMPING_RATIO: f32 = 0.7;
+    // Ground further below than this many hover heights is not felt
+    const SPRING_RANGE: f32 = 3.0;
+
+    pub fn new(
+        owner: Rc<RefCell<dyn Actor>>,
+        phys_world: Rc<RefCell<PhysWorld>>,
+        object_box: AABB,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 10,
+            state: State::Active,
+            angular_speed: 0.0,
+            forward_speed: 0.0,
+            strafe_speed: 0.0,
+            owner_id: owner.borrow().get_id(),
+            phys_world,
+            object_box,
+            velocity: Vector3::ZERO,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    pub fn get_velocity(&self) -> &Vector3 {
+        &self.velocity
+    }
+
+    /// Highest of the terrain and the boxes under the bottom of the owner, within reach
+    fn sample_ground(&self, position: &Vector3) -> Option<f32> {
+        let bottom = position.z + self.object_box.min.z;
+        let reach = HoverMove::HOVER_HEIGHT * HoverMove::SPRING_RANGE;
+        let phys_world = self.phys_world.borrow();
+
+        let line = LineSegment::new(
+            Vector3::new(position.x, position.y, bottom),
+            Vector3::new(position.x, position.y, bottom - reach),
+        );
+        let box_height = phys_world
+            .segment_cast_ignoring(&line, self.owner_id)
+            .map(|info| info.point.z);
+        let terrain_height = phys_world
+            .get_ground_height(position.x, position.y)
+            .filter(|height| bottom - height <= reach);
+
+        box_height
+            .into_iter()
+            .chain(terrain_height)
+            .reduce(f32::max)
+    }
+
+    /// Push the owner out of the boxes it ran into, stopping it against them
+    fn resolve_collisions(&mut self, position: &mut Vector3) {
+        let world_box = |position: &Vector3| {
+            AABB::new(
+                self.object_box.min.clone() + position.clone(),
+                self.object_box.max.clone() + position.clone(),
+            )
+        };
+        let obstacles = self
+            .phys_world
+            .borrow()
+            .get_overlapping_boxes(&world_box(position), self.owner_id);
+        for obstacle in obstacles {
+            let separation = world_box(position).get_min_separation(&obstacle);
+            *position += separation.clone();
+            if separation.x * self.velocity.x < 0.0 {
+                self.velocity.x = 0.0;
+            }
+            if separation.y * self.velocity.y < 0.0 {
+                self.velocity.y = 0.0;
+            }
+            if separation.z * self.velocity.z < 0.0 {
+                self.velocity.z = 0.0;
+            }
+        }
+    }
+}
+
+impl MoveComponent for HoverMove {
+    move_component::impl_getters_setters! {}
+}
+
+impl BuildComponent for HoverMove {
+    type Params = AABB;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        HoverMove::new(owner, context.phys_world.clone(), params)
+    }
+}
+
+impl Component for HoverMove {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        owner_info: &OwnerSnapshot,
+        commands: &mut CommandBuffer,
+    ) {
+        move_component::update_rotation(self, delta_time, owner_info, commands);
+
+        // Horizontal velocity eases toward the thrust
+        let thrust = owner_info.forward.clone() * self.forward_speed
+            + owner_info.right.clone() * self.strafe_speed;
+        let response = (HoverMove::THRUST_RESPONSE * delta_time).min(1.0);
+        self.velocity.x += (thrust.x - self.velocity.x) * response;
+        self.velocity.y += (thrust.y - self.velocity.y) * response;
+
+        // Spring toward the hover height while the ground is close, falling otherwise
+        let position = &owner_info.position;
+        let acceleration = match self.sample_ground(position) {
+            Some(ground) => {
+                let height = position.z + self.object_box.min.z - ground;
+                let damping = 2.0 * HoverMove::SPRING_CONSTANT.sqrt() * HoverMove::DAMPING_RATIO;
+                (HoverMove::HOVER_HEIGHT - height) * HoverMove::SPRING_CONSTANT
+                    - self.velocity.z * damping
+            }
+            None => -HoverMove::GRAVITY,
+        };
+        self.velocity.z += acceleration * delta_time;
+
+        let mut position = position.clone() + self.velocity.clone() * delta_time;
+        self.resolve_collisions(&mut position);
+
+        // Hovering still keeps the transform, so nothing is recomputed
+        if !basic::near_zero(
+            (position.clone() - owner_info.position.clone()).length(),
+            0.01,
+        ) {
+            commands.set_position(position);
+        }
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        collision::aabb::AABB,
+        components::{
+            box_component::BoxComponent, command_buffer::CommandBuffer,
+            move_component::MoveComponent,
+        },
+        math::vector3::Vector3,
+        system::phys_world::PhysWorld,
+    };
+
+    use super::HoverMove;
+
+    fn simulate(actor: &Rc<RefCell<dyn Actor>>, frames: u32) {
+        let mut commands = CommandBuffer::new();
+        for _ in 0..frames {
+            actor.borrow_mut().update(1.0 / 60.0, &mut commands);
+        }
+    }
+
+    #[test]
+    fn test_hover() {
+        let phys_world = PhysWorld::new();
+        let floor: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new_static()));
+        let floor_box = BoxComponent::new(floor.clone(), phys_world.clone());
+        floor_box.borrow_mut().set_object_box(AABB::new(
+            Vector3::new(-5000.0, -5000.0, -10.0),
+            Vector3::new(5000.0, 5000.0, 0.0),
+        ));
+        floor.borrow_mut().compute_world_transform();
+
+        let vehicle: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        vehicle
+            .borrow_mut()
+            .set_position(Vector3::new(0.0, 0.0, 100.0));
+        vehicle.borrow_mut().compute_world_transform();
+        let hover_move = HoverMove::new(
+            vehicle.clone(),
+            phys_world.clone(),
+            AABB::new(
+                Vector3::new(-40.0, -40.0, -20.0),
+                Vector3::new(40.0, 40.0, 20.0),
+            ),
+        );
+
+        // Settles with its bottom at the hover height
+        simulate(&vehicle, 300);
+        assert!((vehicle.borrow().get_position().z - 70.0).abs() < 1.0);
+        assert!(hover_move.borrow().get_velocity().z.abs() < 1.0);
+
+        // Speeds up gradually along +x, its forward
+        hover_move.borrow_mut().set_forward_speed(600.0);
+        simulate(&vehicle, 10);
+        let speed = hover_move.borrow().get_velocity().x;
+        assert!(speed > 0.0 && speed < 600.0);
+
+        // Keeps drifting without thrust
+        hover_move.borrow_mut().set_forward_speed(0.0);
+        let x = vehicle.borrow().get_position().x;
+        simulate(&vehicle, 10);
+        assert!(vehicle.borrow().get_position().x > x);
+    }
+}
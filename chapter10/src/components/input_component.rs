@@ -1,13 +1,11 @@
 use std::{cell::RefCell, rc::Rc};
 
-use sdl2::keyboard::{KeyboardState, Scancode};
+use sdl2::keyboard::Scancode;
 
-use crate::{
-    actors::actor::Actor,
-    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
-};
+use crate::{actors::actor::Actor, system::input_system::InputState};
 
 use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
     component::{self, generate_id, Component, State},
     move_component::{self, impl_getters_setters, MoveComponent},
 };
@@ -112,33 +110,29 @@ impl Component for InputComponent {
     fn update(
         &mut self,
         delta_time: f32,
-        owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        owner_info: &OwnerSnapshot,
+        commands: &mut CommandBuffer,
     ) {
-        move_component::update_move_component(self, delta_time, owner_info)
+        move_component::update_move_component(self, delta_time, owner_info, commands);
     }
 
     component::impl_getters_setters! {}
 
-    fn process_input(&mut self, key_state: &KeyboardState) {
+    fn process_input(&mut self, state: &InputState) {
         let mut forward_speed = 0.0;
-        if key_state.is_scancode_pressed(self.forward_key) {
+        if state.keyboard.get_key_value(self.forward_key) {
             forward_speed += self.max_forward_speed;
         }
-        if key_state.is_scancode_pressed(self.back_key) {
+        if state.keyboard.get_key_value(self.back_key) {
             forward_speed -= self.max_forward_speed;
         }
         self.set_forward_speed(forward_speed);
 
         let mut angular_speed = 0.0;
-        if key_state.is_scancode_pressed(self.clockwise_key) {
+        if state.keyboard.get_key_value(self.clockwise_key) {
             angular_speed += self.max_angular_speed;
         }
-        if key_state.is_scancode_pressed(self.counter_clockwise_key) {
+        if state.keyboard.get_key_value(self.counter_clockwise_key) {
             angular_speed -= self.max_angular_speed;
         }
         self.set_angular_speed(angular_speed);
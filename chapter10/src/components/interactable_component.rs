@@ -0,0 +1,140 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    system::engine_context::EngineContext,
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
+
+/// Called with the owner when the player uses it
+pub type OnUse = Box<dyn FnMut(&Rc<RefCell<dyn Actor>>)>;
+
+/// Makes the owner usable by the player looking at it from close by (doors, buttons, pickups).
+/// The owner needs a BoxComponent to be found by the interaction raycast
+pub struct InteractableComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    // Shown while the player looks at it, e.g. "Open door"
+    prompt: String,
+    on_use: OnUse,
+    // Disabled ones are ignored by the raycast, e.g. a door while it moves
+    enabled: bool,
+    // Under the cross-hair of the player this frame
+    highlighted: bool,
+}
+
+impl InteractableComponent {
+    pub fn new(owner: Rc<RefCell<dyn Actor>>, prompt: &str, on_use: OnUse) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            prompt: prompt.to_string(),
+            on_use,
+            enabled: true,
+            highlighted: false,
+        };
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    /// Run the callback, unless disabled
+    pub fn interact(&mut self) {
+        if self.enabled {
+            (self.on_use)(&self.owner);
+        }
+    }
+
+    pub fn get_prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    pub fn set_prompt(&mut self, prompt: &str) {
+        self.prompt = prompt.to_string();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_highlighted(&self) -> bool {
+        self.highlighted
+    }
+
+    pub fn set_highlighted(&mut self, highlighted: bool) {
+        self.highlighted = highlighted;
+    }
+}
+
+impl BuildComponent for InteractableComponent {
+    type Params = (&'static str, OnUse);
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        let (prompt, on_use) = params;
+        InteractableComponent::new(owner, prompt, on_use)
+    }
+}
+
+impl Component for InteractableComponent {
+    fn update(
+        &mut self,
+        _delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use crate::actors::actor::{test::TestActor, Actor};
+
+    use super::InteractableComponent;
+
+    #[test]
+    fn test_interact() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let owner_id = owner.borrow().get_id();
+        let uses = Rc::new(Cell::new(0));
+        let counter = uses.clone();
+        let interactable = InteractableComponent::new(
+            owner,
+            "Press",
+            Box::new(move |owner| {
+                assert_eq!(owner_id, owner.borrow().get_id());
+                counter.set(counter.get() + 1);
+            }),
+        );
+
+        interactable.borrow_mut().interact();
+        assert_eq!(1, uses.get());
+
+        // Ignored while disabled
+        interactable.borrow_mut().set_enabled(false);
+        interactable.borrow_mut().interact();
+        assert_eq!(1, uses.get());
+    }
+}
@@ -0,0 +1,161 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::{
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    system::engine_context::EngineContext,
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+    powerup::Powerup,
+};
+
+/// Items carried by the owner, e.g. keys, and the powerups active on it
+pub struct InventoryComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    // Count of each item by name
+    items: HashMap<String, u32>,
+    // Seconds left of each active powerup
+    powerups: HashMap<Powerup, f32>,
+}
+
+impl InventoryComponent {
+    pub fn new(owner: Rc<RefCell<dyn Actor>>) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            items: HashMap::new(),
+            powerups: HashMap::new(),
+        };
+
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    pub fn add_item(&mut self, name: &str, count: u32) {
+        *self.items.entry(name.to_string()).or_default() += count;
+    }
+
+    /// Returns false if there are not enough of the item
+    pub fn remove_item(&mut self, name: &str, count: u32) -> bool {
+        match self.items.get_mut(name) {
+            Some(owned) if *owned >= count => {
+                *owned -= count;
+                if *owned == 0 {
+                    self.items.remove(name);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get_item_count(&self, name: &str) -> u32 {
+        self.items.get(name).copied().unwrap_or(0)
+    }
+
+    /// Drop the items and end the powerups
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.powerups.clear();
+    }
+
+    /// Activate the powerup for duration seconds, or extend it if it is already active
+    pub fn add_powerup(&mut self, powerup: Powerup, duration: f32) {
+        let time_left = self.powerups.entry(powerup).or_insert(0.0);
+        *time_left = time_left.max(duration);
+    }
+
+    pub fn has_powerup(&self, powerup: Powerup) -> bool {
+        self.powerups.contains_key(&powerup)
+    }
+
+    pub fn get_powerup_time_left(&self, powerup: Powerup) -> f32 {
+        self.powerups.get(&powerup).copied().unwrap_or(0.0)
+    }
+}
+
+impl BuildComponent for InventoryComponent {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        InventoryComponent::new(owner)
+    }
+}
+
+impl Component for InventoryComponent {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+        for time_left in self.powerups.values_mut() {
+            *time_left -= delta_time;
+        }
+        self.powerups.retain(|_, time_left| *time_left > 0.0);
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        components::{
+            command_buffer::{CommandBuffer, OwnerSnapshot},
+            component::Component,
+            powerup::Powerup,
+        },
+    };
+
+    use super::InventoryComponent;
+
+    #[test]
+    fn test_items() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let inventory = InventoryComponent::new(owner);
+        let mut inventory = inventory.borrow_mut();
+
+        inventory.add_item("Key", 2);
+        assert_eq!(2, inventory.get_item_count("Key"));
+        assert!(!inventory.remove_item("Key", 3));
+        assert!(inventory.remove_item("Key", 2));
+        assert_eq!(0, inventory.get_item_count("Key"));
+        assert!(!inventory.remove_item("Key", 1));
+    }
+
+    #[test]
+    fn test_powerups() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let owner_info = OwnerSnapshot::new(&*owner.borrow());
+        let mut commands = CommandBuffer::new();
+        let inventory = InventoryComponent::new(owner);
+        let mut inventory = inventory.borrow_mut();
+
+        inventory.add_powerup(Powerup::Haste, 2.0);
+        // Picking up a shorter one doesn't cut it short
+        inventory.add_powerup(Powerup::Haste, 1.0);
+        inventory.update(1.5, &owner_info, &mut commands);
+        assert!(inventory.has_powerup(Powerup::Haste));
+        assert!(!inventory.has_powerup(Powerup::DoubleDamage));
+
+        inventory.update(1.0, &owner_info, &mut commands);
+        assert!(!inventory.has_powerup(Powerup::Haste));
+        assert_eq!(0.0, inventory.get_powerup_time_left(Powerup::Haste));
+    }
+}
@@ -1,14 +1,27 @@
-use std::{cell::RefCell, ptr::null, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    hash::Hash,
+    ptr::null,
+    rc::Rc,
+};
 
 use gl::{TRIANGLES, UNSIGNED_INT};
 
 use crate::{
-    actors::actor::Actor,
+    actors::{
+        actor::Actor,
+        actor_builder::{BuildComponent, ReuseComponent},
+    },
     graphics::{mesh::Mesh, shader::Shader},
-    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    math::{matrix4::Matrix4, vector3::Vector3},
+    system::engine_context::EngineContext,
 };
 
-use super::component::{self, generate_id, Component, State};
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
 
 pub struct MeshComponent {
     id: u32,
@@ -17,32 +30,51 @@ pub struct MeshComponent {
     state: State,
     mesh: Option<Rc<Mesh>>,
     texture_index: usize,
+    // Level of detail picked for the current frame
+    lod: Cell<usize>,
+    // Hidden ones are skipped by the renderer
+    visible: bool,
 }
 
 impl MeshComponent {
+    // Screen size (radius over distance) below which each LOD level is used
+    const LOD_THRESHOLDS: [f32; 3] = [0.1, 0.04, 0.015];
+    // Fraction the size has to get back above a threshold before switching to the finer level,
+    // so objects right at the edge don't pop every frame
+    const LOD_HYSTERESIS: f32 = 0.2;
+
     pub fn new(owner: Rc<RefCell<dyn Actor>>) -> Rc<RefCell<Self>> {
-        let this = Self {
+        let result = Rc::new(RefCell::new(MeshComponent::create(owner.clone())));
+        MeshComponent::register(owner, &result);
+        result
+    }
+
+    fn create(owner: Rc<RefCell<dyn Actor>>) -> Self {
+        Self {
             id: generate_id(),
-            owner: owner.clone(),
+            owner,
             update_order: 100,
             state: State::Active,
             mesh: None,
             texture_index: 0,
-        };
-
-        let result = Rc::new(RefCell::new(this));
+            lod: Cell::new(0),
+            visible: true,
+        }
+    }
 
-        owner.borrow_mut().add_component(result.clone());
+    fn register(owner: Rc<RefCell<dyn Actor>>, this: &Rc<RefCell<Self>>) {
+        owner.borrow_mut().add_component(this.clone());
         owner
             .borrow_mut()
             .get_asset_manager()
             .borrow_mut()
-            .add_mesh_component(result.clone());
-
-        result
+            .add_mesh_component(this.clone());
     }
 
     pub fn draw(&self, shader: &Shader) {
+        if !self.visible {
+            return;
+        }
         if let Some(mesh) = &self.mesh {
             // Set the world transform
             shader.set_matrix_uniform(
@@ -58,8 +90,8 @@ impl MeshComponent {
                 texture.set_active();
             }
 
-            // Set the mesh's vertex array as active
-            let vertex_array = mesh.get_vertex_array();
+            // Set the vertex array of the current level of detail as active
+            let vertex_array = mesh.get_lod_vertex_array(self.lod.get());
             vertex_array.set_active();
 
             unsafe {
@@ -74,8 +106,188 @@ impl MeshComponent {
         }
     }
 
+    /// Draw the mesh once per world transform with a single draw call.
+    /// Expects a shader taking the world transform as an instanced attribute.
+    pub fn draw_instanced(
+        shader: &Shader,
+        mesh: &Mesh,
+        texture_index: usize,
+        lod: usize,
+        world_transforms: &[Matrix4],
+    ) {
+        shader.set_float_uniform("uSpecPower", mesh.get_spec_power());
+
+        if let Some(texture) = mesh.get_texture(texture_index) {
+            texture.set_active();
+        }
+
+        let vertex_array = mesh.get_lod_vertex_array(lod);
+        vertex_array.set_active();
+        vertex_array.set_instance_transforms(world_transforms);
+
+        unsafe {
+            gl::DrawElementsInstanced(
+                TRIANGLES,
+                vertex_array.get_num_indices() as i32,
+                UNSIGNED_INT,
+                null(),
+                world_transforms.len() as i32,
+            );
+        }
+    }
+
     pub fn set_mesh(&mut self, mesh: Rc<Mesh>) {
         self.mesh = Some(mesh);
+        // The new mesh may have fewer levels, the level is picked again when something moves
+        self.lod.set(0);
+    }
+
+    pub fn get_mesh(&self) -> Option<&Rc<Mesh>> {
+        self.mesh.as_ref()
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn get_texture_index(&self) -> usize {
+        self.texture_index
+    }
+
+    pub fn get_lod(&self) -> usize {
+        self.lod.get()
+    }
+
+    /// Middle of the mesh bounds in world space, where its distance to the camera is measured
+    pub fn get_world_center(&self) -> Vector3 {
+        let owner = self.owner.borrow();
+        match &self.mesh {
+            Some(mesh) => {
+                let bounds = mesh.get_box();
+                let center = (bounds.min.clone() + bounds.max.clone()) * 0.5;
+                center.transform_with_pers_div(owner.get_world_transform().clone(), None)
+            }
+            None => owner.get_world_transform().get_translation(),
+        }
+    }
+
+    /// Pick the level of detail from how big the mesh looks from the camera
+    pub fn update_lod(&self, camera_position: &Vector3) {
+        let mesh = match &self.mesh {
+            Some(mesh) => mesh,
+            None => return,
+        };
+        if mesh.get_lod_count() == 1 {
+            return;
+        }
+
+        let owner = self.owner.borrow();
+        let distance = (owner.get_position().clone() - camera_position.clone()).length();
+        let screen_size = if distance > 0.0 {
+            mesh.get_radius() * owner.get_scale() / distance
+        } else {
+            f32::INFINITY
+        };
+
+        self.lod.set(select_lod(
+            self.lod.get(),
+            screen_size,
+            mesh.get_lod_count(),
+        ));
+    }
+}
+
+/// Coarser levels as the screen size gets smaller. Going back to a finer level needs
+/// the size to be a bit above the threshold that was crossed.
+fn select_lod(current: usize, screen_size: f32, lod_count: usize) -> usize {
+    let thresholds = &MeshComponent::LOD_THRESHOLDS[..(lod_count - 1).min(3)];
+    let target = thresholds
+        .iter()
+        .filter(|threshold| screen_size < **threshold)
+        .count();
+
+    let mut lod = target;
+    if target < current {
+        // Only step up to the finer levels whose thresholds are cleared with margin
+        lod = current;
+        while lod > target
+            && screen_size >= thresholds[lod - 1] * (1.0 + MeshComponent::LOD_HYSTERESIS)
+        {
+            lod -= 1;
+        }
+    }
+    lod
+}
+
+/// Group mesh components drawing the same mesh with the same texture and level of detail, in order of first appearance.
+/// Components without a mesh are left out.
+pub fn group_by_mesh(
+    mesh_components: &[Rc<RefCell<MeshComponent>>],
+) -> Vec<Vec<Rc<RefCell<MeshComponent>>>> {
+    let with_mesh = mesh_components
+        .iter()
+        .filter(|mesh_component| mesh_component.borrow().mesh.is_some())
+        .collect::<Vec<_>>();
+    let keys = with_mesh
+        .iter()
+        .map(|mesh_component| {
+            let mesh_component = mesh_component.borrow();
+            let mesh = mesh_component.mesh.as_ref().unwrap();
+            (
+                Rc::as_ptr(mesh),
+                mesh_component.texture_index,
+                mesh_component.lod.get(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    group_indices(&keys)
+        .into_iter()
+        .map(|group| group.into_iter().map(|i| with_mesh[i].clone()).collect())
+        .collect()
+}
+
+fn group_indices<K: Eq + Hash>(keys: &[K]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut group_of_key = HashMap::new();
+    for (index, key) in keys.iter().enumerate() {
+        let group = *group_of_key.entry(key).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[group].push(index);
+    }
+    groups
+}
+
+impl BuildComponent for MeshComponent {
+    type Params = Rc<Mesh>;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        let this = MeshComponent::new(owner);
+        this.borrow_mut().set_mesh(params);
+        this
+    }
+}
+
+impl ReuseComponent for MeshComponent {
+    fn reuse(
+        this: &Rc<RefCell<Self>>,
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        params: Self::Params,
+    ) {
+        *this.borrow_mut() = MeshComponent::create(owner.clone());
+        this.borrow_mut().set_mesh(params);
+        MeshComponent::register(owner, this);
     }
 }
 
@@ -83,15 +295,49 @@ impl Component for MeshComponent {
     fn update(
         &mut self,
         _delta_time: f32,
-        _owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
     ) {
-        (None, None, None, vec![])
     }
 
     component::impl_getters_setters! {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{group_indices, select_lod};
+
+    #[test]
+    fn test_group_indices() {
+        let keys = [
+            ("Plane", 0),
+            ("Sphere", 0),
+            ("Plane", 0),
+            ("Plane", 1),
+            ("Sphere", 0),
+        ];
+
+        let groups = group_indices(&keys);
+
+        assert_eq!(vec![vec![0, 2], vec![1, 4], vec![3]], groups);
+    }
+
+    #[test]
+    fn test_select_lod() {
+        // Coarser as the mesh gets smaller on screen
+        assert_eq!(0, select_lod(0, 0.5, 4));
+        assert_eq!(1, select_lod(0, 0.05, 4));
+        assert_eq!(3, select_lod(0, 0.01, 4));
+
+        // Never past the last level the mesh has
+        assert_eq!(1, select_lod(0, 0.01, 2));
+        assert_eq!(0, select_lod(0, 0.01, 1));
+
+        // Just above the threshold keeps the coarser level
+        assert_eq!(1, select_lod(1, 0.105, 4));
+        assert_eq!(0, select_lod(1, 0.13, 4));
+        assert_eq!(3, select_lod(3, 0.016, 4));
+        assert_eq!(2, select_lod(3, 0.02, 4));
+        assert_eq!(1, select_lod(3, 0.05, 4));
+    }
+}
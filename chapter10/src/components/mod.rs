@@ -1,11 +1,28 @@
+pub mod aim_component;
 pub mod audio_component;
 pub mod ball_move;
+pub mod billboard_component;
 pub mod box_component;
 pub mod camera_component;
+pub mod camera_shake;
 pub mod circle_component;
+pub mod command_buffer;
 pub mod component;
+pub mod first_person_model_component;
+pub mod follow_camera;
 pub mod fps_camera;
+pub mod health_bar_component;
+pub mod health_component;
+pub mod hover_move;
 pub mod input_component;
+pub mod interactable_component;
+pub mod inventory_component;
 pub mod mesh_component;
 pub mod move_component;
+pub mod perception_component;
+pub mod powerup;
+pub mod rigid_body_component;
 pub mod sprite_component;
+pub mod water_component;
+pub mod weapon_component;
+pub mod world_space_sprite_component;
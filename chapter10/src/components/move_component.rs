@@ -1,11 +1,15 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    actors::actor::Actor,
-    math::{self, matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    math::{self, quaternion::Quaternion, vector3::Vector3},
+    system::engine_context::EngineContext,
 };
 
-use super::component::{self, generate_id, Component, State};
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
 
 pub trait MoveComponent: Component {
     fn get_angular_speed(&self) -> f32;
@@ -54,35 +58,37 @@ pub(crate) use impl_getters_setters;
 pub fn update_move_component(
     move_component: &dyn MoveComponent,
     delta_time: f32,
-    owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-) -> (
-    Option<Vector3>,
-    Option<Quaternion>,
-    Option<Vector3>,
-    Vec<Rc<RefCell<dyn Actor>>>,
+    owner_info: &OwnerSnapshot,
+    commands: &mut CommandBuffer,
 ) {
-    let mut result = (None, None, None, vec![]);
+    update_rotation(move_component, delta_time, owner_info, commands);
+
+    if !math::basic::near_zero(move_component.get_forward_speed(), 0.001)
+        || !math::basic::near_zero(move_component.get_strafe_speed(), 0.001)
+    {
+        let mut position = owner_info.position.clone();
+        position += owner_info.forward.clone() * move_component.get_forward_speed() * delta_time;
+        position += owner_info.right.clone() * move_component.get_strafe_speed() * delta_time;
+        commands.set_position(position);
+    }
+}
 
+/// Turn about +z by the angular speed, for moves handling the position themselves
+pub fn update_rotation(
+    move_component: &dyn MoveComponent,
+    delta_time: f32,
+    owner_info: &OwnerSnapshot,
+    commands: &mut CommandBuffer,
+) {
     if !math::basic::near_zero(move_component.get_angular_speed(), 0.001) {
-        let mut rotation = owner_info.1.clone();
+        let mut rotation = owner_info.rotation.clone();
         let angle = move_component.get_angular_speed() * delta_time;
 
         let increment = Quaternion::from_axis_angle(&Vector3::UNIT_Z, angle);
 
         rotation = Quaternion::concatenate(&rotation, &increment);
-        result.1 = Some(rotation);
+        commands.set_rotation(rotation);
     }
-
-    if !math::basic::near_zero(move_component.get_forward_speed(), 0.001)
-        || !math::basic::near_zero(move_component.get_strafe_speed(), 0.001)
-    {
-        let mut position = owner_info.0.clone();
-        position += owner_info.2.clone() * move_component.get_forward_speed() * delta_time;
-        position += owner_info.4.clone() * move_component.get_strafe_speed() * delta_time;
-        result.0 = Some(position);
-    }
-
-    result
 }
 
 pub struct DefaultMoveComponent {
@@ -120,18 +126,26 @@ impl MoveComponent for DefaultMoveComponent {
     impl_getters_setters! {}
 }
 
+impl BuildComponent for DefaultMoveComponent {
+    type Params = ();
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        _params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        DefaultMoveComponent::new(owner)
+    }
+}
+
 impl Component for DefaultMoveComponent {
     fn update(
         &mut self,
         delta_time: f32,
-        owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        owner_info: &OwnerSnapshot,
+        commands: &mut CommandBuffer,
     ) {
-        update_move_component(self, delta_time, owner_info)
+        update_move_component(self, delta_time, owner_info, commands);
     }
 
     component::impl_getters_setters! {}
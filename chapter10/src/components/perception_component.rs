@@ -0,0 +1,216 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    collision::line_segment::LineSegment,
+    math::{self, vector3::Vector3},
+    system::{engine_context::EngineContext, phys_world::PhysWorld},
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
+
+/// Sees a target inside a view cone in front of the owner, unless a box is in between
+pub struct PerceptionComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    owner_id: u32,
+    update_order: i32,
+    state: State,
+    phys_world: Rc<RefCell<PhysWorld>>,
+    target: Weak<RefCell<dyn Actor>>,
+    view_distance: f32,
+    // Half of the cone angle, in radians
+    half_view_angle: f32,
+    can_see_target: bool,
+    // Where the target was last seen, kept after losing sight of it
+    last_known_position: Option<Vector3>,
+}
+
+impl PerceptionComponent {
+    pub fn new(
+        owner: Rc<RefCell<dyn Actor>>,
+        phys_world: Rc<RefCell<PhysWorld>>,
+        target: &Rc<RefCell<dyn Actor>>,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            owner_id: owner.borrow().get_id(),
+            // Before the movement, which reacts to what was seen
+            update_order: 5,
+            state: State::Active,
+            phys_world,
+            target: Rc::downgrade(target),
+            view_distance: 1500.0,
+            half_view_angle: math::basic::to_radians(60.0),
+            can_see_target: false,
+            last_known_position: None,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    pub fn set_view_distance(&mut self, view_distance: f32) {
+        self.view_distance = view_distance;
+    }
+
+    /// Full angle of the view cone, in radians
+    pub fn set_view_angle(&mut self, view_angle: f32) {
+        self.half_view_angle = view_angle * 0.5;
+    }
+
+    pub fn get_target(&self) -> Option<Rc<RefCell<dyn Actor>>> {
+        self.target.upgrade()
+    }
+
+    pub fn can_see_target(&self) -> bool {
+        self.can_see_target
+    }
+
+    pub fn get_last_known_position(&self) -> Option<&Vector3> {
+        self.last_known_position.as_ref()
+    }
+
+    /// Stop looking for the target where it was last seen
+    pub fn forget(&mut self) {
+        self.last_known_position = None;
+    }
+
+    /// Nothing but the target itself is hit by the line from the eye
+    fn has_line_of_sight(&self, eye: &Vector3, target_position: &Vector3, target_id: u32) -> bool {
+        let line = LineSegment::new(eye.clone(), target_position.clone());
+        match self
+            .phys_world
+            .borrow()
+            .segment_cast_ignoring(&line, self.owner_id)
+        {
+            Some(info) => info.actor_id == target_id,
+            None => true,
+        }
+    }
+}
+
+/// Whether point is within view_distance of eye and inside the cone around forward
+fn is_in_view_cone(
+    eye: &Vector3,
+    forward: &Vector3,
+    point: &Vector3,
+    view_distance: f32,
+    half_view_angle: f32,
+) -> bool {
+    let to_point = point.clone() - eye.clone();
+    let distance = to_point.length();
+    if distance > view_distance {
+        return false;
+    }
+    if math::basic::near_zero(distance, 0.001) {
+        return true;
+    }
+    Vector3::dot(forward, &(to_point * (1.0 / distance))) >= half_view_angle.cos()
+}
+
+impl BuildComponent for PerceptionComponent {
+    // Target to look for
+    type Params = Rc<RefCell<dyn Actor>>;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        PerceptionComponent::new(owner, context.phys_world.clone(), &params)
+    }
+}
+
+impl Component for PerceptionComponent {
+    fn update(
+        &mut self,
+        _delta_time: f32,
+        owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+        let target = match self.target.upgrade() {
+            Some(target) => target,
+            None => {
+                self.can_see_target = false;
+                return;
+            }
+        };
+        let (target_position, target_id) = {
+            let borrowed_target = target.borrow();
+            (
+                borrowed_target.get_position().clone(),
+                borrowed_target.get_id(),
+            )
+        };
+
+        let (eye, forward) = (&owner_info.position, &owner_info.forward);
+        self.can_see_target = is_in_view_cone(
+            eye,
+            forward,
+            &target_position,
+            self.view_distance,
+            self.half_view_angle,
+        ) && self.has_line_of_sight(eye, &target_position, target_id);
+
+        if self.can_see_target {
+            self.last_known_position = Some(target_position);
+        }
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::{self, vector3::Vector3};
+
+    use super::is_in_view_cone;
+
+    #[test]
+    fn test_is_in_view_cone() {
+        let eye = Vector3::ZERO;
+        let forward = Vector3::UNIT_X;
+        let half_angle = math::basic::to_radians(45.0);
+
+        assert!(is_in_view_cone(
+            &eye,
+            &forward,
+            &Vector3::new(100.0, 50.0, 0.0),
+            500.0,
+            half_angle
+        ));
+        // Too far
+        assert!(!is_in_view_cone(
+            &eye,
+            &forward,
+            &Vector3::new(600.0, 0.0, 0.0),
+            500.0,
+            half_angle
+        ));
+        // Outside of the cone
+        assert!(!is_in_view_cone(
+            &eye,
+            &forward,
+            &Vector3::new(100.0, 200.0, 0.0),
+            500.0,
+            half_angle
+        ));
+        // Behind
+        assert!(!is_in_view_cone(
+            &eye,
+            &forward,
+            &Vector3::new(-100.0, 0.0, 0.0),
+            500.0,
+            half_angle
+        ));
+    }
+}
@@ -0,0 +1,19 @@
+/// Timed effect granted by a pickup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Powerup {
+    // Faster movement
+    Haste,
+    // Shots deal more damage
+    DoubleDamage,
+}
+
+impl Powerup {
+    /// Powerup by the name used in level files
+    pub fn from_name(name: &str) -> Option<Powerup> {
+        match name {
+            "Haste" => Some(Powerup::Haste),
+            "DoubleDamage" => Some(Powerup::DoubleDamage),
+            _ => None,
+        }
+    }
+}
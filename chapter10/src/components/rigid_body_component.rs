@@ -0,0 +1,285 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    collision::aabb::AABB,
+    math::{basic, vector3::Vector3},
+    system::{engine_context::EngineContext, phys_world::PhysWorld},
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
+
+/// Box falling with gravity, bouncing off the other boxes and the terrain.
+/// It doesn't rotate, so its box stays axis aligned
+pub struct RigidBodyComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    // Boxes of the owner itself are not obstacles
+    owner_id: u32,
+    phys_world: Rc<RefCell<PhysWorld>>,
+    // Box around the owner position
+    object_box: AABB,
+    velocity: Vector3,
+    mass: f32,
+    // Fraction of the speed kept when bouncing off a surface
+    restitution: f32,
+    // Carried by an actor, which sets the velocity instead of gravity
+    held: bool,
+}
+
+impl RigidBodyComponent {
+    const GRAVITY: f32 = 980.0;
+    // Fraction of the horizontal speed lost per second while on the ground
+    const FRICTION: f32 = 4.0;
+    // Slower bounces than this stop, so resting bodies don't jitter
+    const REST_SPEED: f32 = 30.0;
+
+    pub fn new(
+        owner: Rc<RefCell<dyn Actor>>,
+        phys_world: Rc<RefCell<PhysWorld>>,
+        object_box: AABB,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            // After the other components moved the owner
+            update_order: 150,
+            state: State::Active,
+            owner_id: owner.borrow().get_id(),
+            phys_world,
+            object_box,
+            velocity: Vector3::ZERO,
+            mass: 1.0,
+            restitution: 0.3,
+            held: false,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    pub fn get_velocity(&self) -> &Vector3 {
+        &self.velocity
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vector3) {
+        self.velocity = velocity;
+    }
+
+    pub fn set_mass(&mut self, mass: f32) {
+        self.mass = mass.max(0.001);
+    }
+
+    pub fn apply_impulse(&mut self, impulse: &Vector3) {
+        self.velocity += impulse.clone() * (1.0 / self.mass);
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.held
+    }
+
+    pub fn set_held(&mut self, held: bool) {
+        self.held = held;
+    }
+
+    /// Pull the body toward target, as if tied to it by a spring stiff enough to get there
+    /// within 1 / stiffness seconds. Collisions still stop it
+    pub fn pull_toward(&mut self, target: &Vector3, stiffness: f32, max_speed: f32) {
+        let position = self.owner.borrow().get_position().clone();
+        let mut velocity = (target.clone() - position) * stiffness;
+        if velocity.length() > max_speed {
+            velocity.normalize_mut();
+            velocity *= max_speed;
+        }
+        self.velocity = velocity;
+    }
+
+    fn get_world_box(&self, position: &Vector3) -> AABB {
+        AABB::new(
+            self.object_box.min.clone() + position.clone(),
+            self.object_box.max.clone() + position.clone(),
+        )
+    }
+
+    /// Move out of whatever the body ended up in, bouncing off it.
+    /// Returns whether it rests on something
+    fn resolve_collisions(&mut self, position: &mut Vector3) -> bool {
+        let phys_world = self.phys_world.clone();
+        let phys_world = phys_world.borrow();
+        let mut on_ground = false;
+
+        let obstacles =
+            phys_world.get_overlapping_boxes(&self.get_world_box(position), self.owner_id);
+        for obstacle in obstacles {
+            let separation = self.get_world_box(position).get_min_separation(&obstacle);
+            *position += separation.clone();
+            if separation.z > 0.0 {
+                on_ground = true;
+            }
+            self.bounce(&separation);
+        }
+
+        // The terrain is no box, only keep the bottom above it
+        if let Some(height) = phys_world.get_ground_height(position.x, position.y) {
+            let bottom = position.z + self.object_box.min.z;
+            if bottom < height {
+                position.z += height - bottom;
+                on_ground = true;
+                self.bounce(&Vector3::UNIT_Z);
+            }
+        }
+
+        on_ground
+    }
+
+    /// Reflect the speed along the axis of the separation
+    fn bounce(&mut self, separation: &Vector3) {
+        let restitution = if self.held { 0.0 } else { self.restitution };
+        let bounce = |speed: &mut f32, separation: f32| {
+            // Only if moving into the surface
+            if separation * *speed < 0.0 {
+                *speed = -*speed * restitution;
+                if speed.abs() < RigidBodyComponent::REST_SPEED {
+                    *speed = 0.0;
+                }
+            }
+        };
+        bounce(&mut self.velocity.x, separation.x);
+        bounce(&mut self.velocity.y, separation.y);
+        bounce(&mut self.velocity.z, separation.z);
+    }
+}
+
+impl BuildComponent for RigidBodyComponent {
+    type Params = AABB;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        RigidBodyComponent::new(owner, context.phys_world.clone(), params)
+    }
+}
+
+impl Component for RigidBodyComponent {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        owner_info: &OwnerSnapshot,
+        commands: &mut CommandBuffer,
+    ) {
+        if !self.held {
+            self.velocity.z -= RigidBodyComponent::GRAVITY * delta_time;
+        }
+
+        let mut position = owner_info.position.clone() + self.velocity.clone() * delta_time;
+        let on_ground = self.resolve_collisions(&mut position);
+
+        if on_ground && !self.held {
+            let friction = (1.0 - RigidBodyComponent::FRICTION * delta_time).max(0.0);
+            self.velocity.x *= friction;
+            self.velocity.y *= friction;
+        }
+
+        // Resting bodies keep their transform, so nothing is recomputed
+        if !basic::near_zero(
+            (position.clone() - owner_info.position.clone()).length(),
+            0.01,
+        ) {
+            commands.set_position(position);
+        }
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        collision::aabb::AABB,
+        components::{box_component::BoxComponent, command_buffer::CommandBuffer},
+        math::vector3::Vector3,
+        system::phys_world::PhysWorld,
+    };
+
+    use super::RigidBodyComponent;
+
+    fn half_box(half: f32) -> AABB {
+        AABB::new(
+            Vector3::new(-half, -half, -half),
+            Vector3::new(half, half, half),
+        )
+    }
+
+    fn create_body(phys_world: &Rc<RefCell<PhysWorld>>) -> Rc<RefCell<dyn Actor>> {
+        let body: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        body.borrow_mut()
+            .set_position(Vector3::new(0.0, 0.0, 200.0));
+        body.borrow_mut().compute_world_transform();
+        RigidBodyComponent::new(body.clone(), phys_world.clone(), half_box(25.0));
+        body
+    }
+
+    fn simulate(body: &Rc<RefCell<dyn Actor>>, frames: u32) {
+        let mut commands = CommandBuffer::new();
+        for _ in 0..frames {
+            body.borrow_mut().update(1.0 / 60.0, &mut commands);
+        }
+    }
+
+    #[test]
+    fn test_fall_to_rest() {
+        let phys_world = PhysWorld::new();
+        let floor: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new_static()));
+        let floor_box = BoxComponent::new(floor.clone(), phys_world.clone());
+        floor_box.borrow_mut().set_object_box(AABB::new(
+            Vector3::new(-500.0, -500.0, -10.0),
+            Vector3::new(500.0, 500.0, 0.0),
+        ));
+        floor.borrow_mut().compute_world_transform();
+
+        let body = create_body(&phys_world);
+        simulate(&body, 180);
+
+        // Resting on the floor
+        let rigid_body = body.borrow().get_component::<RigidBodyComponent>().unwrap();
+        assert!((body.borrow().get_position().z - 25.0).abs() < 0.5);
+        assert_eq!(&Vector3::ZERO, rigid_body.borrow().get_velocity());
+    }
+
+    #[test]
+    fn test_held() {
+        let phys_world = PhysWorld::new();
+        let body = create_body(&phys_world);
+        let rigid_body = body.borrow().get_component::<RigidBodyComponent>().unwrap();
+
+        // Pulled to the target without falling
+        rigid_body.borrow_mut().set_held(true);
+        let target = Vector3::new(100.0, 0.0, 200.0);
+        for _ in 0..60 {
+            rigid_body.borrow_mut().pull_toward(&target, 10.0, 1000.0);
+            simulate(&body, 1);
+        }
+        assert!((body.borrow().get_position().clone() - target).length() < 1.0);
+
+        // Thrown, then falling again
+        rigid_body.borrow_mut().set_held(false);
+        rigid_body
+            .borrow_mut()
+            .apply_impulse(&Vector3::new(500.0, 0.0, 0.0));
+        simulate(&body, 30);
+        let position = body.borrow().get_position().clone();
+        assert!(position.x > 300.0);
+        assert!(position.z < 200.0);
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
     actors::actor::Actor,
     components::component::Component,
     graphics::{shader::Shader, texture::Texture},
-    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+    math::matrix4::Matrix4,
 };
 
 pub trait SpriteComponent: Component {
@@ -72,7 +72,10 @@ macro_rules! impl_getters_setters {
 
 pub(crate) use impl_getters_setters;
 
-use super::component::{self, State};
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, State},
+};
 
 pub struct DefaultSpriteComponent {
     id: u32,
@@ -120,14 +123,9 @@ impl Component for DefaultSpriteComponent {
     fn update(
         &mut self,
         _delta_time: f32,
-        _owner_info: &(Vector3, Quaternion, Vector3, Matrix4, Vector3),
-    ) -> (
-        Option<Vector3>,
-        Option<Quaternion>,
-        Option<Vector3>,
-        Vec<Rc<RefCell<dyn Actor>>>,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
     ) {
-        (None, None, None, vec![])
     }
 
     component::impl_getters_setters! {}
@@ -0,0 +1,138 @@
+use std::{cell::RefCell, ptr::null, rc::Rc};
+
+use gl::{TRIANGLES, UNSIGNED_INT};
+
+use crate::{
+    actors::actor::Actor,
+    graphics::{shader::Shader, texture::Texture, vertex_array::VertexArray},
+    math::{matrix4::Matrix4, vector2::Vector2},
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, Component, State},
+};
+
+/// Flat water surface at the height of the owner, drawn after the opaque meshes.
+/// The waves come from a normal map scrolling in two directions
+pub struct WaterComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    // Half size along x and y, before the owner scale
+    half_size: Vector2,
+    normal_map: Rc<Texture>,
+    // Seconds since created, drives the scrolling
+    time: f32,
+}
+
+impl WaterComponent {
+    // Normal map repeats per second of each sample
+    const SCROLL_SPEEDS: [Vector2; 2] = [Vector2::new(0.02, 0.01), Vector2::new(-0.015, 0.025)];
+    // World units covered by one repeat of the normal map
+    const TILE_SIZE: f32 = 200.0;
+
+    pub fn new(owner: Rc<RefCell<dyn Actor>>, half_size: Vector2) -> Rc<RefCell<Self>> {
+        let asset_manager = owner.borrow().get_asset_manager().clone();
+        let normal_map = asset_manager.borrow_mut().get_texture("WaterNormal.png");
+
+        let this = Self {
+            id: component::generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            half_size,
+            normal_map,
+            time: 0.0,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+
+        owner.borrow_mut().add_component(result.clone());
+
+        asset_manager
+            .borrow_mut()
+            .add_water_component(result.clone());
+
+        result
+    }
+
+    /// Quad is the unit square in the xy plane, facing +z
+    pub fn draw(&self, shader: &Shader, quad: &VertexArray) {
+        let scale_mat =
+            Matrix4::create_scale_xyz(self.half_size.x * 2.0, self.half_size.y * 2.0, 1.0);
+        let world = scale_mat * self.owner.borrow().get_world_transform().clone();
+        shader.set_matrix_uniform("uWorldTransform", world);
+
+        let [scroll0, scroll1] = get_scroll_offsets(self.time);
+        shader.set_vector2_uniform("uScroll0", &scroll0);
+        shader.set_vector2_uniform("uScroll1", &scroll1);
+        shader.set_float_uniform("uTileSize", WaterComponent::TILE_SIZE);
+
+        self.normal_map.set_active();
+        quad.set_active();
+
+        unsafe {
+            gl::DrawElements(
+                TRIANGLES,
+                quad.get_num_indices() as i32,
+                UNSIGNED_INT,
+                null(),
+            );
+        }
+    }
+
+    /// World height of the surface, the plane the reflection is mirrored across
+    pub fn get_height(&self) -> f32 {
+        self.owner
+            .borrow()
+            .get_world_transform()
+            .get_translation()
+            .z
+    }
+}
+
+/// Offsets of both normal map samples, wrapped into [0, 1) as the map repeats
+fn get_scroll_offsets(time: f32) -> [Vector2; 2] {
+    WaterComponent::SCROLL_SPEEDS.map(|speed| {
+        Vector2::new(
+            (speed.x * time).rem_euclid(1.0),
+            (speed.y * time).rem_euclid(1.0),
+        )
+    })
+}
+
+impl Component for WaterComponent {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+        self.time += delta_time;
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_near_eq;
+
+    use super::get_scroll_offsets;
+
+    #[test]
+    fn test_get_scroll_offsets() {
+        let [scroll0, scroll1] = get_scroll_offsets(0.0);
+        assert_near_eq!(0.0, scroll0.x, 0.001);
+        assert_near_eq!(0.0, scroll1.y, 0.001);
+
+        // Wrapped, also when scrolling toward negative
+        let [scroll0, scroll1] = get_scroll_offsets(100.0);
+        assert_near_eq!(0.0, scroll0.x, 0.001);
+        assert_near_eq!(0.5, scroll1.x, 0.001);
+        assert_near_eq!(0.5, scroll1.y, 0.001);
+        assert!((0.0..1.0).contains(&scroll1.x));
+    }
+}
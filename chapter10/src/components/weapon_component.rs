@@ -0,0 +1,307 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::{actor::Actor, actor_builder::BuildComponent},
+    system::engine_context::EngineContext,
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, generate_id, Component, State},
+};
+
+/// Creates a projectile actor owned by the player with the given id
+pub type ProjectileFactory = fn(&EngineContext, u32) -> Rc<RefCell<dyn Actor>>;
+
+pub enum FireMode {
+    // Spawn a projectile actor which travels through the world
+    Projectile(ProjectileFactory),
+    // Instant line cast against the PhysWorld
+    Hitscan { range: f32, damage: f32 },
+}
+
+pub struct Weapon {
+    name: String,
+    fire_mode: FireMode,
+    // Seconds between two shots
+    fire_interval: f32,
+    ammo: u32,
+    max_ammo: u32,
+    // FMOD event played on fire
+    fire_event: String,
+}
+
+impl Weapon {
+    pub fn new(
+        name: &str,
+        fire_mode: FireMode,
+        fire_rate: f32,
+        max_ammo: u32,
+        fire_event: &str,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            fire_mode,
+            fire_interval: 1.0 / fire_rate,
+            ammo: max_ammo,
+            max_ammo,
+            fire_event: fire_event.to_string(),
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_fire_mode(&self) -> &FireMode {
+        &self.fire_mode
+    }
+
+    pub fn get_ammo(&self) -> u32 {
+        self.ammo
+    }
+
+    pub fn get_max_ammo(&self) -> u32 {
+        self.max_ammo
+    }
+
+    pub fn get_fire_event(&self) -> &str {
+        &self.fire_event
+    }
+
+    pub fn add_ammo(&mut self, amount: u32) {
+        self.ammo = (self.ammo + amount).min(self.max_ammo);
+    }
+}
+
+pub struct WeaponComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    weapons: Vec<Weapon>,
+    current_weapon: usize,
+    // Time until the current weapon can fire again
+    cooldown: f32,
+}
+
+impl WeaponComponent {
+    pub fn new(owner: Rc<RefCell<dyn Actor>>) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            weapons: vec![],
+            current_weapon: 0,
+            cooldown: 0.0,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+        owner.borrow_mut().add_component(result.clone());
+        result
+    }
+
+    pub fn add_weapon(&mut self, weapon: Weapon) {
+        self.weapons.push(weapon);
+    }
+
+    /// Select weapon by index. Returns false if there is no such weapon.
+    /// The cooldown of the last shot carries over, so switching doesn't fire faster
+    pub fn switch_weapon(&mut self, index: usize) -> bool {
+        if index >= self.weapons.len() || index == self.current_weapon {
+            return false;
+        }
+        self.current_weapon = index;
+        true
+    }
+
+    /// Select next (positive step) or previous (negative step) weapon, wrapping around
+    pub fn cycle_weapon(&mut self, step: i32) {
+        if self.weapons.is_empty() {
+            return;
+        }
+        let count = self.weapons.len() as i32;
+        let index = (self.current_weapon as i32 + step).rem_euclid(count);
+        self.switch_weapon(index as usize);
+    }
+
+    /// Consume one round of the current weapon if it is ready to fire
+    pub fn try_fire(&mut self) -> Option<&Weapon> {
+        if self.cooldown > 0.0 {
+            return None;
+        }
+
+        let weapon = self.weapons.get_mut(self.current_weapon)?;
+        if weapon.ammo == 0 {
+            return None;
+        }
+
+        weapon.ammo -= 1;
+        self.cooldown = weapon.fire_interval;
+        Some(weapon)
+    }
+
+    /// Refill every weapon by amount. Returns false if they were all full
+    pub fn add_ammo_to_all(&mut self, amount: u32) -> bool {
+        let mut added = false;
+        for weapon in self.weapons.iter_mut() {
+            added |= weapon.ammo < weapon.max_ammo;
+            weapon.add_ammo(amount);
+        }
+        added
+    }
+
+    pub fn get_current_weapon(&self) -> Option<&Weapon> {
+        self.weapons.get(self.current_weapon)
+    }
+
+    pub fn get_current_weapon_mut(&mut self) -> Option<&mut Weapon> {
+        self.weapons.get_mut(self.current_weapon)
+    }
+
+    pub fn get_current_index(&self) -> usize {
+        self.current_weapon
+    }
+}
+
+impl BuildComponent for WeaponComponent {
+    type Params = Vec<Weapon>;
+
+    fn build(
+        owner: Rc<RefCell<dyn Actor>>,
+        _context: &EngineContext,
+        params: Self::Params,
+    ) -> Rc<RefCell<Self>> {
+        let this = WeaponComponent::new(owner);
+        for weapon in params {
+            this.borrow_mut().add_weapon(weapon);
+        }
+        this
+    }
+}
+
+impl Component for WeaponComponent {
+    fn update(
+        &mut self,
+        delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+        self.cooldown = (self.cooldown - delta_time).max(0.0);
+    }
+
+    component::impl_getters_setters! {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        components::{
+            command_buffer::{CommandBuffer, OwnerSnapshot},
+            component::Component,
+        },
+    };
+
+    use super::{FireMode, Weapon, WeaponComponent};
+
+    #[test]
+    fn test_try_fire() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let owner_info = OwnerSnapshot::new(&*owner.borrow());
+        let mut commands = CommandBuffer::new();
+        let weapon_component = WeaponComponent::new(owner);
+        let mut weapon_component = weapon_component.borrow_mut();
+        weapon_component.add_weapon(Weapon::new(
+            "Rifle",
+            FireMode::Hitscan {
+                range: 100.0,
+                damage: 10.0,
+            },
+            2.0,
+            2,
+            "event:/Shot",
+        ));
+
+        assert!(weapon_component.try_fire().is_some());
+        // Still cooling down
+        assert!(weapon_component.try_fire().is_none());
+
+        weapon_component.update(0.5, &owner_info, &mut commands);
+        assert_eq!(0, weapon_component.try_fire().unwrap().get_ammo());
+
+        // Out of ammo
+        weapon_component.update(0.5, &owner_info, &mut commands);
+        assert!(weapon_component.try_fire().is_none());
+
+        // Refilled up to the max
+        assert!(weapon_component.add_ammo_to_all(5));
+        assert_eq!(2, weapon_component.get_current_weapon().unwrap().get_ammo());
+        assert!(!weapon_component.add_ammo_to_all(5));
+    }
+
+    #[test]
+    fn test_cycle_weapon() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let weapon_component = WeaponComponent::new(owner);
+        let mut weapon_component = weapon_component.borrow_mut();
+        for name in ["A", "B", "C"] {
+            weapon_component.add_weapon(Weapon::new(
+                name,
+                FireMode::Hitscan {
+                    range: 100.0,
+                    damage: 10.0,
+                },
+                1.0,
+                1,
+                "event:/Shot",
+            ));
+        }
+
+        weapon_component.cycle_weapon(-1);
+        assert_eq!(2, weapon_component.get_current_index());
+        weapon_component.cycle_weapon(1);
+        assert_eq!(0, weapon_component.get_current_index());
+        assert!(!weapon_component.switch_weapon(3));
+        assert!(weapon_component.switch_weapon(1));
+        assert_eq!(
+            "B",
+            weapon_component.get_current_weapon().unwrap().get_name()
+        );
+    }
+
+    #[test]
+    fn test_switch_weapon_keeps_cooldown() {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let owner_info = OwnerSnapshot::new(&*owner.borrow());
+        let mut commands = CommandBuffer::new();
+        let weapon_component = WeaponComponent::new(owner);
+        let mut weapon_component = weapon_component.borrow_mut();
+        for name in ["A", "B"] {
+            weapon_component.add_weapon(Weapon::new(
+                name,
+                FireMode::Hitscan {
+                    range: 100.0,
+                    damage: 10.0,
+                },
+                1.0,
+                5,
+                "event:/Shot",
+            ));
+        }
+
+        assert!(weapon_component.try_fire().is_some());
+        // Switching back and forth doesn't skip the cooldown
+        assert!(weapon_component.switch_weapon(1));
+        assert!(weapon_component.try_fire().is_none());
+        weapon_component.update(0.5, &owner_info, &mut commands);
+        assert!(weapon_component.switch_weapon(0));
+        assert!(weapon_component.try_fire().is_none());
+
+        weapon_component.update(0.5, &owner_info, &mut commands);
+        assert!(weapon_component.try_fire().is_some());
+    }
+}
@@ -0,0 +1,139 @@
+use std::{cell::RefCell, ptr::null, rc::Rc};
+
+use gl::{TRIANGLES, UNSIGNED_INT};
+
+use crate::{
+    actors::actor::Actor,
+    graphics::{shader::Shader, texture::Texture},
+    math::{matrix4::Matrix4, vector3::Vector3},
+    system::renderer::Renderer,
+};
+
+use super::{
+    command_buffer::{CommandBuffer, OwnerSnapshot},
+    component::{self, Component, State},
+    health_component::HealthComponent,
+    sprite_component::{self, SpriteComponent},
+};
+
+/// Screen space sprite that follows a position above its owner (e.g. a nameplate).
+/// Hidden while the owner is behind the camera or off screen.
+pub struct WorldSpaceSpriteComponent {
+    id: u32,
+    owner: Rc<RefCell<dyn Actor>>,
+    update_order: i32,
+    state: State,
+    texture: Option<Rc<Texture>>,
+    draw_order: i32,
+    texture_width: u32,
+    texture_height: u32,
+    renderer: Rc<RefCell<Renderer>>,
+    // World space offset from the owner position
+    offset: Vector3,
+    sprite_scale: f32,
+    // If set, the sprite width follows the health like a health bar
+    health_component: Option<Rc<RefCell<HealthComponent>>>,
+}
+
+impl WorldSpaceSpriteComponent {
+    pub fn new(
+        owner: Rc<RefCell<dyn Actor>>,
+        draw_order: i32,
+        renderer: Rc<RefCell<Renderer>>,
+    ) -> Rc<RefCell<Self>> {
+        let this = Self {
+            id: component::generate_id(),
+            owner: owner.clone(),
+            update_order: 100,
+            state: State::Active,
+            texture: None,
+            draw_order,
+            texture_height: 0,
+            texture_width: 0,
+            renderer,
+            offset: Vector3::ZERO,
+            sprite_scale: 1.0,
+            health_component: None,
+        };
+
+        let result = Rc::new(RefCell::new(this));
+
+        owner.borrow_mut().add_component(result.clone());
+
+        owner
+            .borrow()
+            .get_asset_manager()
+            .borrow_mut()
+            .add_sprite(result.clone());
+
+        result
+    }
+
+    pub fn set_offset(&mut self, offset: Vector3) {
+        self.offset = offset;
+    }
+
+    pub fn set_sprite_scale(&mut self, sprite_scale: f32) {
+        self.sprite_scale = sprite_scale;
+    }
+
+    pub fn set_health_component(&mut self, health_component: Rc<RefCell<HealthComponent>>) {
+        self.health_component = Some(health_component);
+    }
+}
+
+impl SpriteComponent for WorldSpaceSpriteComponent {
+    fn draw(&self, shader: &Shader) {
+        let texture = match self.get_texture() {
+            Some(texture) => texture,
+            None => return,
+        };
+
+        let world_point = self.get_owner().borrow().get_position().clone() + self.offset.clone();
+        let renderer = self.renderer.borrow();
+        let screen_point = match renderer.project(&world_point) {
+            Some(screen_point) => screen_point,
+            None => return,
+        };
+
+        let full_width = self.get_texture_width() as f32 * self.sprite_scale;
+        let height = self.get_texture_height() as f32 * self.sprite_scale;
+        if !renderer.is_on_screen(&screen_point, full_width.max(height)) {
+            return;
+        }
+
+        let width = match &self.health_component {
+            Some(health_component) => full_width * health_component.borrow().get_health_fraction(),
+            None => full_width,
+        };
+
+        let scale_mat = Matrix4::create_scale_xyz(width, height, 1.0);
+        // Keep the left edge in place while the bar shrinks
+        let translation = Matrix4::create_translation(&Vector3::new(
+            screen_point.x + (width - full_width) * 0.5,
+            screen_point.y,
+            0.0,
+        ));
+
+        shader.set_matrix_uniform("uWorldTransform", scale_mat * translation);
+        texture.set_active();
+
+        unsafe {
+            gl::DrawElements(TRIANGLES, 6, UNSIGNED_INT, null());
+        }
+    }
+
+    sprite_component::impl_getters_setters! {}
+}
+
+impl Component for WorldSpaceSpriteComponent {
+    fn update(
+        &mut self,
+        _delta_time: f32,
+        _owner_info: &OwnerSnapshot,
+        _commands: &mut CommandBuffer,
+    ) {
+    }
+
+    component::impl_getters_setters! {}
+}
@@ -1,15 +1,45 @@
 extern crate gl;
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, path::Path, rc::Rc, time::Instant};
 
 use anyhow::{anyhow, Result};
-use sdl2::{event::Event, keyboard::Scancode, EventPump, TimerSubsystem};
+use sdl2::{
+    controller::Button,
+    event::{Event, WindowEvent},
+    keyboard::Scancode,
+    mouse::MouseUtil,
+    EventPump, TimerSubsystem,
+};
 
 use crate::{
-    actors::fps_actor::FPSActor,
+    actors::{actor::Actor, fps_actor::FPSActor},
+    components::command_buffer::CommandBuffer,
+    graphics::texture::Texture,
+    math::{matrix4::Matrix4, vector3::Vector3},
     system::{
-        asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
-        phys_world::PhysWorld, renderer::Renderer, sound_event::SoundEvent,
+        asset_manager::AssetManager,
+        audio_system::AudioSystem,
+        benchmark::{BenchConfig, Benchmark, FrameTimes},
+        config::{Config, KeyBindings},
+        crash_report::{self, log, ActorCounts},
+        determinism::{self, DeterminismOptions},
+        engine_context::EngineContext,
+        entity_manager::EntityManager,
+        game_mode::GameMode,
+        game_state::{GameState, Transition},
+        hud::HUD,
+        input_system::{ButtonState, InputSystem},
+        inspector::Inspector,
+        music_manager::{MusicManager, MusicMood},
+        objective_manager::ObjectiveEvent,
+        phys_world::PhysWorld,
+        renderer::Renderer,
+        scratch_pool::{self, FrameStats},
+        script_manager::ScriptManager,
+        string_table::StringTable,
+        timer_manager::TimerManager,
+        transform_changes,
+        ui_screen::{UIScreen, UIState},
     },
 };
 
@@ -20,113 +50,664 @@ pub struct Game {
     asset_manager: Rc<RefCell<AssetManager>>,
     entity_manager: Rc<RefCell<EntityManager>>,
     audio_system: Rc<RefCell<AudioSystem>>,
+    input_system: Rc<RefCell<InputSystem>>,
+    mouse: MouseUtil,
+    // Whether the player wants the mouse captured (toggled with Tab)
+    capture_mouse: bool,
+    has_focus: bool,
     phys_world: Rc<RefCell<PhysWorld>>,
-    is_running: bool,
+    // Gameplay clock of the scheduled callbacks
+    timer_manager: Rc<RefCell<TimerManager>>,
+    // Sequences stepped once per frame of gameplay
+    script_manager: Rc<RefCell<ScriptManager>>,
+    state: GameState,
     tick_count: u64,
-    music_event: SoundEvent,
-    reverb_snap: Option<SoundEvent>,
+    music_manager: Rc<RefCell<MusicManager>>,
     fps_actor: Rc<RefCell<FPSActor>>,
+    hud: Rc<RefCell<HUD>>,
+    game_mode: Rc<RefCell<GameMode>>,
+    // Id of the objective shown on the HUD, announced when it changes
+    current_objective: Option<String>,
+    frame_stats: FrameStats,
+    // Changes queued by components, kept to reuse its buffers every frame
+    commands: CommandBuffer,
+    config: Config,
+    string_table: Rc<RefCell<StringTable>>,
+    // Menu of the current state
+    ui_stack: Vec<UIScreen>,
+    // Requested by the menu buttons, handled after the menu processed input
+    transitions: Rc<RefCell<Vec<Transition>>>,
+    // Debug editor, also pauses the game (toggled with F2)
+    inspector: Inspector,
 }
 
 impl Game {
+    // The music intensifies for the end of the round
+    const COMBAT_MUSIC_TIME: f32 = 15.0;
+    // Seconds each benchmark or determinism frame advances the game by, so runs are comparable
+    const FIXED_TIME_STEP: f32 = 1.0 / 60.0;
+    // Buses of the master bank faded by the menus
+    const MUSIC_BUS: &'static str = "bus:/Music";
+    const SFX_BUS: &'static str = "bus:/SFX";
+    // The music stays behind the menus, muffled
+    const MENU_MUSIC_VOLUME: f32 = 0.3;
+    const MENU_FADE_TIME: f32 = 0.5;
+    // And fades out slowly on the game over screen
+    const GAME_OVER_FADE_TIME: f32 = 2.0;
+    // Height the second view of the split screen looks down on the player from
+    const OVERHEAD_HEIGHT: f32 = 1500.0;
+
     /// Initialize game
     pub fn initialize() -> Result<Game> {
+        let config = Config::load(Path::new(Config::FILE_NAME));
+
+        let string_table = StringTable::new();
+        if let Err(e) = string_table.borrow_mut().load(&config.language) {
+            log!("Failed to load language {}: {}", config.language, e);
+            string_table.borrow_mut().load("en")?;
+        }
+
         let sdl = sdl2::init().map_err(|e| anyhow!(e))?;
         let video_system = sdl.video().map_err(|e| anyhow!(e))?;
+        let text_input = video_system.text_input();
 
-        let renderer = Renderer::initialize(video_system, (1024.0, 768.0))?;
+        let renderer = Renderer::initialize(video_system, &config)?;
 
         let event_pump = sdl.event_pump().map_err(|e| anyhow!(e))?;
 
+        let mouse = sdl.mouse();
+        mouse.set_relative_mouse_mode(true);
+
         let timer = sdl.timer().map_err(|e| anyhow!(e))?;
 
+        let controller_subsystem = sdl.game_controller().map_err(|e| anyhow!(e))?;
+        let controller = controller_subsystem.open(0).ok();
+
         let asset_manager = renderer.borrow().get_asset_manager().clone();
         let entity_manager = EntityManager::new();
 
-        let audio_system = AudioSystem::initialize(asset_manager.clone())?;
-        let music_event = audio_system.borrow_mut().play_event("event:/Music");
+        let audio_system = AudioSystem::initialize(asset_manager.clone(), &config);
+        let music_manager = MusicManager::new(audio_system.clone());
+        music_manager
+            .borrow_mut()
+            .set_playlist(MusicMood::Explore, &["event:/Music"]);
+        // Falls back to the stock music if the banks have no combat track
+        music_manager
+            .borrow_mut()
+            .set_playlist(MusicMood::Combat, &["event:/MusicCombat", "event:/Music"]);
 
         let phys_world = PhysWorld::new();
+        let timer_manager = TimerManager::new();
+        let script_manager = ScriptManager::new();
 
-        let camera_actor = EntityManager::load_data(
-            entity_manager.clone(),
-            asset_manager.clone(),
-            renderer.clone(),
-            audio_system.clone(),
-            phys_world.clone(),
-        );
+        let context = EngineContext {
+            asset_manager: asset_manager.clone(),
+            entity_manager: entity_manager.clone(),
+            audio_system: audio_system.clone(),
+            renderer: renderer.clone(),
+            phys_world: phys_world.clone(),
+            timer_manager: timer_manager.clone(),
+            script_manager: script_manager.clone(),
+        };
+
+        let camera_actor = EntityManager::load_data(&context);
 
-        let game = Game {
+        camera_actor
+            .borrow_mut()
+            .set_mouse_sensitivity(config.mouse_sensitivity);
+        camera_actor
+            .borrow_mut()
+            .set_key_bindings(config.key_bindings.clone());
+
+        let input_system = InputSystem::initialize(controller, text_input)?;
+        input_system.borrow_mut().set_relative_mouse_mode(true);
+
+        let hud = HUD::new(asset_manager.clone());
+        entity_manager.borrow_mut().set_hud(hud.clone());
+
+        let game_mode = GameMode::new(context.clone());
+        let objective_strings = string_table.clone();
+        game_mode
+            .borrow_mut()
+            .get_objective_manager_mut()
+            .add_listener(Box::new(move |objective, event| {
+                let string_table = objective_strings.borrow();
+                let description = string_table.text(&objective.description);
+                match event {
+                    ObjectiveEvent::Added => {}
+                    ObjectiveEvent::Progressed => log!(
+                        "{} ({}/{})",
+                        description,
+                        objective.progress,
+                        objective.required
+                    ),
+                    ObjectiveEvent::Completed => log!(
+                        "{}: {}",
+                        string_table.text("objectiveComplete"),
+                        description
+                    ),
+                }
+            }));
+
+        let inspector = Inspector::new(context, camera_actor.clone());
+
+        let mut game = Game {
             renderer,
             event_pump,
             timer,
             asset_manager,
             entity_manager,
             audio_system,
+            input_system,
+            mouse,
+            capture_mouse: true,
+            has_focus: true,
             phys_world,
-            is_running: true,
+            timer_manager,
+            script_manager,
+            state: GameState::MainMenu,
             tick_count: 0,
-            music_event,
-            reverb_snap: None,
+            music_manager,
             fps_actor: camera_actor,
+            hud,
+            game_mode,
+            current_objective: None,
+            frame_stats: FrameStats::new(),
+            commands: CommandBuffer::new(),
+            config,
+            string_table,
+            ui_stack: vec![],
+            transitions: Rc::new(RefCell::new(vec![])),
+            inspector,
         };
+        game.enter_state(GameState::MainMenu);
 
         Ok(game)
     }
 
+    /// Runs the stress scene for a fixed number of frames with a fixed time step,
+    /// then prints the frame time percentiles
+    pub fn run_bench(&mut self, config: BenchConfig) {
+        let context = EngineContext {
+            asset_manager: self.asset_manager.clone(),
+            entity_manager: self.entity_manager.clone(),
+            audio_system: self.audio_system.clone(),
+            renderer: self.renderer.clone(),
+            phys_world: self.phys_world.clone(),
+            timer_manager: self.timer_manager.clone(),
+            script_manager: self.script_manager.clone(),
+        };
+        self.game_mode.borrow_mut().restart();
+        self.enter_state(GameState::Gameplay);
+
+        let mut benchmark = Benchmark::new(config);
+        benchmark.spawn_actors(&context);
+        let player_id = self.fps_actor.borrow().get_id();
+        log!("Benchmark: {:?}", benchmark.get_config());
+
+        let mut frame_times = FrameTimes::new();
+        for _ in 0..benchmark.get_config().frames {
+            // Only quitting is handled, the player stands still
+            let quit = self
+                .event_pump
+                .poll_iter()
+                .any(|event| matches!(event, Event::Quit { .. }));
+            if quit {
+                break;
+            }
+
+            let start = Instant::now();
+            benchmark.update_projectiles(&context, player_id);
+            self.step(Game::FIXED_TIME_STEP);
+            // The round ending doesn't stop the benchmark
+            self.transitions.borrow_mut().clear();
+            if !benchmark.get_config().headless {
+                self.generate_output();
+            }
+            frame_times.push(start.elapsed());
+            self.end_frame();
+        }
+
+        frame_times.report();
+        log!(
+            "Average heap allocations per frame: {:.1}",
+            self.frame_stats.get_average_allocations()
+        );
+    }
+
+    /// Plays the input script with a fixed time step, hashing the transforms each frame,
+    /// then compares the hashes with the ones of a previous run. Returns whether they matched.
+    /// The random generators must be seeded before the game is initialized
+    pub fn run_determinism(&mut self, options: &DeterminismOptions) -> Result<bool> {
+        // The script is written for the default controls
+        {
+            let mut fps_actor = self.fps_actor.borrow_mut();
+            fps_actor.set_key_bindings(KeyBindings::new());
+            fps_actor.set_mouse_sensitivity(1.0);
+        }
+        self.game_mode.borrow_mut().restart();
+        self.enter_state(GameState::Gameplay);
+        log!("Determinism: {:?}", options);
+
+        let mut hashes = vec![];
+        for frame in 0..options.frames {
+            // Only quitting is handled, the input comes from the script
+            let quit = self
+                .event_pump
+                .poll_iter()
+                .any(|event| matches!(event, Event::Quit { .. }));
+            if quit {
+                break;
+            }
+
+            {
+                let mut input_system = self.input_system.borrow_mut();
+                input_system.prepare_for_update();
+                input_system.clear();
+                determinism::apply_script(frame, &mut input_system);
+            }
+            self.dispatch_input();
+            self.step(Game::FIXED_TIME_STEP);
+            // The round ending doesn't stop the run
+            self.transitions.borrow_mut().clear();
+
+            let actors = self.entity_manager.borrow().share_actors();
+            hashes.push(determinism::hash_transforms(&actors));
+            self.end_frame();
+        }
+
+        determinism::check(&hashes, options)
+    }
+
     /// Runs the game loop until the game is over
     pub fn run_loop(&mut self) {
-        while self.is_running {
+        while self.state.is_running() {
             self.process_input();
             self.update_game();
             self.generate_output();
+            self.end_frame();
+        }
+
+        log!(
+            "Average heap allocations per frame: {:.1}",
+            self.frame_stats.get_average_allocations()
+        );
+        for (name, stats) in self.entity_manager.borrow().get_pool_stats() {
+            log!(
+                "{} pool: {} created, {} reused ({:.0}%)",
+                name,
+                stats.created,
+                stats.reused,
+                stats.get_reuse_rate() * 100.0
+            );
+        }
+
+        // Keep the volume changed with -/= for the next run
+        self.config.master_volume = self.audio_system.borrow().get_bus_volume("bus:/");
+        self.config.language = self.string_table.borrow().get_language().to_string();
+        // And the field of view and sensitivity changed with [/] and ,/.
+        self.config.fov = self.renderer.borrow().get_fov();
+        self.config.mouse_sensitivity = self.fps_actor.borrow().get_mouse_sensitivity();
+        if !self.config.fullscreen {
+            // And the size the window was resized to
+            let screen_size = self.renderer.borrow().get_screen_size();
+            self.config.window_width = screen_size.x as u32;
+            self.config.window_height = screen_size.y as u32;
+        }
+        if let Err(e) = self.config.save(Path::new(Config::FILE_NAME)) {
+            let string_table = self.string_table.borrow();
+            log!("{}: {}", string_table.text("saveConfigFailed"), e);
         }
     }
 
     /// Herlper functions for the game loop
     fn process_input(&mut self) {
+        self.input_system.borrow_mut().prepare_for_update();
+
+        // Escape cancels text input instead of quitting
+        let was_typing = self.input_system.borrow().get_state().text.is_active();
+        let is_paused = !self.state.is_playing();
+
         for event in self.event_pump.poll_iter() {
+            let is_typing = self.input_system.borrow().get_state().text.is_active();
             match event {
                 Event::Quit { .. } => {
-                    self.is_running = false;
+                    self.transitions.borrow_mut().push(Transition::Quit);
                     break;
                 }
+                Event::TextInput { .. } => {
+                    self.input_system.borrow_mut().process_event(&event);
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::Return),
+                    repeat: false,
+                    ..
+                } => {
+                    // Enter opens the chat box, and sends the message when typing
+                    let mut input_system = self.input_system.borrow_mut();
+                    if is_typing {
+                        let text = input_system.stop_text_input();
+                        if !text.is_empty() {
+                            let string_table = self.string_table.borrow();
+                            log!("{}: {}", string_table.text("chat"), text);
+                        }
+                    } else {
+                        input_system.start_text_input();
+                    }
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::Escape),
+                    ..
+                } if is_typing => {
+                    self.input_system.borrow_mut().stop_text_input();
+                }
+                Event::KeyDown { .. } if is_typing => {
+                    // Keys typed into the chat box don't trigger game shortcuts
+                    self.input_system.borrow_mut().process_event(&event);
+                }
+                Event::KeyDown { .. } | Event::MouseWheel { .. } if is_paused => {
+                    // The menu reads the input state instead
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::F2),
+                    repeat: false,
+                    ..
+                } => {
+                    self.inspector.toggle();
+                }
+                Event::KeyDown {
+                    scancode: Some(key),
+                    keymod,
+                    ..
+                } if self.inspector.is_active() => {
+                    // Repeats while held, for larger edits
+                    self.inspector.handle_key(key, keymod);
+                }
+                Event::Window { win_event, .. } => match win_event {
+                    WindowEvent::FocusGained => self.has_focus = true,
+                    WindowEvent::FocusLost => self.has_focus = false,
+                    WindowEvent::SizeChanged(width, height) => {
+                        let mut renderer = self.renderer.borrow_mut();
+                        renderer.resize(width as u32, height as u32);
+                        let screen_size = renderer.get_screen_size();
+                        for ui_screen in self.ui_stack.iter_mut() {
+                            ui_screen.set_screen_size(screen_size.clone());
+                        }
+                    }
+                    _ => {}
+                },
+                Event::KeyDown {
+                    scancode: Some(Scancode::L),
+                    repeat: false,
+                    ..
+                } => {
+                    let mut string_table = self.string_table.borrow_mut();
+                    match string_table.cycle_language() {
+                        Ok(()) => log!("{}", string_table.text("language")),
+                        Err(e) => log!("Failed to switch language: {}", e),
+                    }
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::Tab),
+                    repeat: false,
+                    ..
+                } => {
+                    self.capture_mouse = !self.capture_mouse;
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::F1),
+                    repeat: false,
+                    ..
+                } => {
+                    // Cycle through the debug views of the meshes
+                    let mut renderer = self.renderer.borrow_mut();
+                    let debug_view = renderer.get_debug_view().next();
+                    renderer.set_debug_view(debug_view);
+                    log!("Debug view: {:?}", debug_view);
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::F3),
+                    repeat: false,
+                    ..
+                } => {
+                    // Split the screen with an overhead view of the player
+                    let mut renderer = self.renderer.borrow_mut();
+                    let split_screen = renderer.get_split_screen().next();
+                    renderer.set_split_screen(split_screen);
+                    log!("Split screen: {:?}", split_screen);
+                }
+                Event::KeyDown {
+                    scancode: Some(Scancode::F4),
+                    repeat: false,
+                    ..
+                } => {
+                    // Compare the water with and without the mirrored scene
+                    let mut renderer = self.renderer.borrow_mut();
+                    let enabled = !renderer.has_water_reflection();
+                    renderer.set_water_reflection(enabled);
+                    log!("Water reflection: {}", renderer.has_water_reflection());
+                }
                 Event::KeyDown {
                     scancode, repeat, ..
                 } => {
                     if !repeat && scancode.is_some() {
-                        if let Some(reverb) = Game::handle_key_pressed(
+                        Game::handle_key_pressed(
                             scancode.unwrap(),
                             self.audio_system.clone(),
+                            self.renderer.clone(),
                             self.fps_actor.clone(),
-                        ) {
-                            self.reverb_snap = Some(reverb);
-                        }
+                        );
+                    }
+                }
+                Event::MouseWheel { y, .. } => {
+                    self.input_system.borrow_mut().process_event(&event);
+                    if y != 0 {
+                        self.fps_actor.borrow_mut().cycle_weapon(-y.signum());
                     }
                 }
                 _ => {}
             }
         }
 
-        let state = self.event_pump.keyboard_state();
-        if state.is_scancode_pressed(Scancode::Escape) {
-            self.is_running = false;
+        // The menu and the inspector need the absolute mouse position
+        let is_captured =
+            self.capture_mouse && self.has_focus && !is_paused && !self.inspector.is_active();
+        self.input_system
+            .borrow_mut()
+            .set_relative_mouse_mode(is_captured);
+        self.input_system.borrow_mut().update(&self.event_pump);
+
+        if !was_typing {
+            let input_system = self.input_system.borrow();
+            let state = input_system.get_state();
+            match self.ui_stack.last_mut() {
+                Some(ui_screen) => ui_screen.process_input(state),
+                None => {
+                    if state.keyboard.get_key_state(Scancode::Escape) == ButtonState::Pressed
+                        || state.controller.get_button_state(Button::Start) == ButtonState::Pressed
+                    {
+                        self.transitions.borrow_mut().push(Transition::Pause);
+                    } else if self.inspector.is_active() {
+                        self.inspector.process_mouse(state);
+                    }
+                }
+            }
         }
+        self.handle_transitions();
 
-        let mouse_state = self.event_pump.relative_mouse_state();
+        // Release the mouse while unfocused, in a menu or when the player asked for it
+        let is_typing = self.input_system.borrow().get_state().text.is_active();
+        let is_paused = !self.state.is_playing();
+        let is_captured =
+            self.capture_mouse && self.has_focus && !is_paused && !self.inspector.is_active();
+        self.mouse.set_relative_mouse_mode(is_captured);
+        if !is_captured || is_typing {
+            // Actors get an empty state so the camera doesn't keep spinning
+            // and typed keys don't move the player
+            self.input_system.borrow_mut().clear();
+        }
+
+        self.dispatch_input();
+    }
+
+    /// Pass the input state to the actors
+    fn dispatch_input(&self) {
+        let borrowed_input_system = self.input_system.borrow();
+        let state = borrowed_input_system.get_state();
 
         self.entity_manager.borrow_mut().set_updating_actors(true);
-        let actors = self.entity_manager.borrow().get_actors().clone();
-        for actor in actors {
-            actor.borrow_mut().process_input(&state, &mouse_state);
+        let actors = self.entity_manager.borrow().share_actors();
+        for actor in actors.iter() {
+            actor.borrow_mut().process_input(state);
+        }
+        self.entity_manager.borrow_mut().set_updating_actors(false);
+    }
+
+    fn handle_transitions(&mut self) {
+        // Closing the menu with Escape goes back
+        let is_closed = self
+            .ui_stack
+            .iter()
+            .any(|ui_screen| *ui_screen.get_state() != UIState::Active);
+        if is_closed {
+            if let Some(transition) = self.state.back() {
+                self.transitions.borrow_mut().push(transition);
+            }
+        }
+
+        for transition in self.transitions.take() {
+            match self.state.next(transition) {
+                Some(state) => {
+                    if transition == Transition::Start {
+                        self.game_mode.borrow_mut().restart();
+                    }
+                    self.enter_state(state);
+                }
+                None => log!("Ignored {:?} in {:?}", transition, self.state),
+            }
+        }
+
+        self.ui_stack
+            .retain(|ui_screen| *ui_screen.get_state() == UIState::Active);
+    }
+
+    /// Replace the menu of the previous state with the one of the new state
+    fn enter_state(&mut self, state: GameState) {
+        self.state = state;
+        self.ui_stack.clear();
+        self.fade_audio(state);
+        match state {
+            GameState::MainMenu => self.open_menu(
+                "mainMenuTitle",
+                &[("start", Transition::Start), ("quit", Transition::Quit)],
+            ),
+            GameState::Paused => self.open_menu(
+                "pauseTitle",
+                &[
+                    ("resume", Transition::Resume),
+                    ("restart", Transition::Start),
+                    ("mainMenu", Transition::ToMainMenu),
+                    ("quit", Transition::Quit),
+                ],
+            ),
+            GameState::GameOver => {
+                let (score, is_mission_complete) = {
+                    let game_mode = self.game_mode.borrow();
+                    (game_mode.get_score(), game_mode.is_mission_complete())
+                };
+                let result = if is_mission_complete {
+                    "missionComplete"
+                } else {
+                    "finalScore"
+                };
+                let string_table = self.string_table.borrow();
+                log!("{}: {}", string_table.text(result), score);
+                drop(string_table);
+                self.open_menu(
+                    "gameOverTitle",
+                    &[
+                        ("restart", Transition::Start),
+                        ("mainMenu", Transition::ToMainMenu),
+                        ("quit", Transition::Quit),
+                    ],
+                );
+            }
+            GameState::Gameplay | GameState::Quit => {}
+        }
+    }
+
+    /// Fade the buses to the state, so the sound doesn't cut in or out with the menus
+    fn fade_audio(&self, state: GameState) {
+        let mut audio_system = self.audio_system.borrow_mut();
+        let (music_volume, music_fade_time) = match state {
+            GameState::Gameplay => {
+                audio_system.set_bus_paused(Game::SFX_BUS, false);
+                audio_system.fade_bus_volume(Game::SFX_BUS, 1.0, Game::MENU_FADE_TIME);
+                (1.0, Game::MENU_FADE_TIME)
+            }
+            GameState::MainMenu | GameState::Paused | GameState::GameOver => {
+                // Looping effects stay silent until the game resumes
+                audio_system.fade_bus_volume_then(
+                    Game::SFX_BUS,
+                    0.0,
+                    Game::MENU_FADE_TIME,
+                    Box::new(|audio_system| audio_system.set_bus_paused(Game::SFX_BUS, true)),
+                );
+                if state == GameState::GameOver {
+                    (0.0, Game::GAME_OVER_FADE_TIME)
+                } else {
+                    (Game::MENU_MUSIC_VOLUME, Game::MENU_FADE_TIME)
+                }
+            }
+            GameState::Quit => return,
+        };
+        audio_system.fade_bus_volume(Game::MUSIC_BUS, music_volume, music_fade_time);
+    }
+
+    fn open_menu(&mut self, title: &str, buttons: &[(&str, Transition)]) {
+        let (button_on, button_off) = {
+            let mut asset_manager = self.asset_manager.borrow_mut();
+            (
+                asset_manager.get_texture("ButtonYellow.png"),
+                asset_manager.get_texture("ButtonBlue.png"),
+            )
+        };
+        let screen_size = self.renderer.borrow().get_screen_size();
+
+        let mut menu = UIScreen::new(self.get_label(title), button_on, button_off, screen_size);
+        for (text, transition) in buttons {
+            let transitions = self.transitions.clone();
+            let transition = *transition;
+            menu.add_button(
+                self.get_label(text),
+                Box::new(move || transitions.borrow_mut().push(transition)),
+            );
+        }
+
+        log!("{}", self.string_table.borrow().text(title));
+        self.ui_stack.push(menu);
+    }
+
+    /// Text of the string table key pre-rendered to Labels/<language>/<key>.png.
+    /// Without it the text is only logged, there is no font rendering
+    fn get_label(&self, key: &str) -> Option<Rc<Texture>> {
+        let language = self.string_table.borrow().get_language().to_string();
+        let file_name = format!("Labels/{}/{}.png", language, key);
+        match self.asset_manager.borrow_mut().try_get_texture(&file_name) {
+            Ok(texture) => Some(texture),
+            Err(e) => {
+                log!("{}: {}", self.string_table.borrow().text(key), e);
+                None
+            }
         }
     }
 
     fn handle_key_pressed(
         key: Scancode,
         audio_system: Rc<RefCell<AudioSystem>>,
+        renderer: Rc<RefCell<Renderer>>,
         fps_actor: Rc<RefCell<FPSActor>>,
-    ) -> Option<SoundEvent> {
+    ) {
         match key {
             Scancode::Minus => {
                 // Reduce master volume
@@ -140,12 +721,37 @@ impl Game {
                 volume = f32::min(1.0, volume + 0.1);
                 audio_system.borrow_mut().set_bus_volume("bus:/", volume);
             }
+            Scancode::LeftBracket | Scancode::RightBracket => {
+                // Narrower/wider field of view
+                let step = if key == Scancode::LeftBracket {
+                    -5.0
+                } else {
+                    5.0
+                };
+                let fov = renderer.borrow().get_fov() + step;
+                renderer.borrow_mut().set_fov(fov);
+            }
+            Scancode::Comma | Scancode::Period => {
+                // Slower/faster mouse look
+                let step = if key == Scancode::Comma { -0.1 } else { 0.1 };
+                let sensitivity = fps_actor.borrow().get_mouse_sensitivity() + step;
+                fps_actor
+                    .borrow_mut()
+                    .set_mouse_sensitivity(sensitivity.max(0.1));
+            }
             Scancode::B => {
                 fps_actor.borrow_mut().shoot();
             }
+            Scancode::I => {
+                fps_actor.borrow_mut().toggle_invert_y();
+            }
+            Scancode::Num1 | Scancode::Num2 | Scancode::Num3 => {
+                fps_actor
+                    .borrow_mut()
+                    .switch_weapon(key as usize - Scancode::Num1 as usize);
+            }
             _ => {}
         };
-        return None;
     }
 
     fn update_game(&mut self) {
@@ -157,28 +763,129 @@ impl Game {
 
         self.tick_count = self.timer.ticks64();
 
-        self.entity_manager.borrow_mut().set_updating_actors(true);
-        let actors = self.entity_manager.borrow().get_actors().clone();
-        for actor in actors {
-            actor.borrow_mut().update(delta_time);
+        self.step(delta_time);
+    }
+
+    /// Advance the game by delta_time seconds
+    fn step(&mut self, delta_time: f32) {
+        // Nothing moves in the menus or while inspecting
+        if self.state.is_playing() && !self.inspector.is_active() {
+            if self.game_mode.borrow_mut().update(delta_time).is_some() {
+                self.transitions.borrow_mut().push(Transition::RoundOver);
+            }
+            // Before the actors, so none of them is borrowed by the callbacks
+            TimerManager::update(&self.timer_manager, delta_time);
+            ScriptManager::update(&self.script_manager, delta_time);
+
+            // Actors spawned during the update wait in the pending list
+            self.entity_manager.borrow_mut().set_updating_actors(true);
+            let actors = self.entity_manager.borrow().share_actors();
+            for actor in actors.iter() {
+                actor.borrow_mut().update(delta_time, &mut self.commands);
+            }
+            self.entity_manager.borrow_mut().set_updating_actors(false);
+
+            // No actor is borrowed anymore, so hits and damage can reach any of them
+            self.commands.apply_actor_commands();
+            self.entity_manager.borrow_mut().enforce_world_bounds();
+
+            self.renderer.borrow_mut().update_zoom(delta_time);
+            self.update_overhead_view();
         }
-        self.entity_manager.borrow_mut().set_updating_actors(false);
 
-        let pending_actors = self.entity_manager.borrow().get_pending_actors().clone();
+        let pending_actors = self.entity_manager.borrow_mut().take_pending_actors();
         for pending in pending_actors {
             pending.borrow_mut().compute_world_transform();
-            self.entity_manager.borrow_mut().add_actor(pending.clone());
+            self.entity_manager.borrow_mut().add_actor(pending);
         }
 
         self.entity_manager.borrow_mut().flush_actors();
         self.asset_manager.borrow_mut().flush_sprites();
         self.asset_manager.borrow_mut().flush_meshes();
         self.phys_world.borrow_mut().flush_boxes();
+        scratch_pool::reset();
+
+        self.hud.borrow_mut().update(
+            delta_time,
+            &self.fps_actor.borrow(),
+            self.entity_manager
+                .borrow()
+                .actors_with_tag(EntityManager::TARGET_TAG),
+        );
+        self.update_objective();
+        for ui_screen in self.ui_stack.iter_mut() {
+            ui_screen.update(delta_time);
+        }
+
+        let mood = {
+            let game_mode = self.game_mode.borrow();
+            if !game_mode.is_over() && game_mode.get_time_left() <= Game::COMBAT_MUSIC_TIME {
+                MusicMood::Combat
+            } else {
+                MusicMood::Explore
+            }
+        };
+        let mut music_manager = self.music_manager.borrow_mut();
+        music_manager.set_mood(mood);
+        music_manager.update(delta_time);
 
         self.audio_system.borrow_mut().update(delta_time);
     }
 
+    /// Camera of the second view of the split screen, above the player with its forward up
+    fn update_overhead_view(&self) {
+        let mut renderer = self.renderer.borrow_mut();
+        if renderer.get_view_count() < 2 {
+            return;
+        }
+        let fps_actor = self.fps_actor.borrow();
+        let position = fps_actor.get_position().clone();
+        let eye = position.clone() + Vector3::UNIT_Z * Game::OVERHEAD_HEIGHT;
+        let view = Matrix4::create_look_at(&eye, &position, &fps_actor.get_forward());
+        renderer.set_view_matrix_at(1, view);
+    }
+
+    /// Show the current objective on the HUD, and print it when a new one starts
+    fn update_objective(&mut self) {
+        let game_mode = self.game_mode.borrow();
+        let objective = game_mode.get_objective_manager().get_current_objective();
+        self.hud.borrow_mut().set_objective(objective);
+
+        let id = objective.map(|objective| objective.id.clone());
+        if id == self.current_objective {
+            return;
+        }
+        if let Some(objective) = objective {
+            let string_table = self.string_table.borrow();
+            log!(
+                "{}: {}",
+                string_table.text("objective"),
+                string_table.text(&objective.description)
+            );
+        }
+        self.current_objective = id;
+    }
+
     fn generate_output(&mut self) {
-        self.renderer.borrow_mut().draw();
+        if self.inspector.is_active() {
+            self.inspector.submit_gizmo();
+        }
+        // Each view shows the HUD of the player
+        let renderer = self.renderer.borrow();
+        let hud = self.hud.borrow();
+        let huds = vec![&*hud; renderer.get_view_count()];
+        renderer.draw(&huds, &self.ui_stack);
+    }
+
+    /// Bookkeeping once the frame is over, whichever loop ran it
+    fn end_frame(&mut self) {
+        transform_changes::clear();
+        self.frame_stats.end_frame();
+
+        let mut actor_counts = ActorCounts::new();
+        for actor in self.entity_manager.borrow().get_actors() {
+            actor_counts.add(actor.borrow().get_state());
+        }
+        crash_report::end_frame(actor_counts);
     }
 }
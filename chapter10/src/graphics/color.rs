@@ -0,0 +1,145 @@
+use crate::math::{basic, vector3::Vector3};
+
+/// RGBA color with components in [0, 1], in sRGB unless converted
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const RED: Color = Color::rgb(1.0, 0.0, 0.0);
+    pub const GREEN: Color = Color::rgb(0.0, 1.0, 0.0);
+    pub const BLUE: Color = Color::rgb(0.0, 0.0, 1.0);
+    pub const YELLOW: Color = Color::rgb(1.0, 1.0, 0.0);
+    pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Opaque
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self::new(r, g, b, 1.0)
+    }
+
+    /// From 0xRRGGBBAA
+    pub fn from_hex(hex: u32) -> Self {
+        let channel = |shift: u32| ((hex >> shift) & 0xFF) as f32 / 255.0;
+        Self::new(channel(24), channel(16), channel(8), channel(0))
+    }
+
+    pub fn with_alpha(&self, a: f32) -> Self {
+        Self::new(self.r, self.g, self.b, a)
+    }
+
+    pub fn lerp(&self, other: &Color, f: f32) -> Self {
+        Self::new(
+            basic::lerp(self.r, other.r, f),
+            basic::lerp(self.g, other.g, f),
+            basic::lerp(self.b, other.b, f),
+            basic::lerp(self.a, other.a, f),
+        )
+    }
+
+    /// sRGB to linear, for lighting math. Alpha is already linear
+    pub fn to_linear(&self) -> Self {
+        let channel = |c: f32| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        Self::new(channel(self.r), channel(self.g), channel(self.b), self.a)
+    }
+
+    /// Linear back to sRGB
+    pub fn to_srgb(&self) -> Self {
+        let channel = |c: f32| {
+            if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+        Self::new(channel(self.r), channel(self.g), channel(self.b), self.a)
+    }
+
+    /// RGB without alpha, for vec3 uniforms and vertex data
+    pub fn to_vector3(&self) -> Vector3 {
+        Vector3::new(self.r, self.g, self.b)
+    }
+}
+
+impl From<Color> for sdl2::pixels::Color {
+    fn from(color: Color) -> Self {
+        let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        sdl2::pixels::Color::RGBA(
+            channel(color.r),
+            channel(color.g),
+            channel(color.b),
+            channel(color.a),
+        )
+    }
+}
+
+impl From<sdl2::pixels::Color> for Color {
+    fn from(color: sdl2::pixels::Color) -> Self {
+        Color::from_hex(u32::from_be_bytes([color.r, color.g, color.b, color.a]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::Color;
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(Color::RED, Color::from_hex(0xFF0000FF));
+        assert_eq!(Color::TRANSPARENT, Color::from_hex(0x00000000));
+        let color = Color::from_hex(0x336699CC);
+        assert_near_eq!(0.2, color.r, 0.001);
+        assert_near_eq!(0.4, color.g, 0.001);
+        assert_near_eq!(0.6, color.b, 0.001);
+        assert_near_eq!(0.8, color.a, 0.001);
+    }
+
+    #[test]
+    fn test_lerp() {
+        let color = Color::BLACK.lerp(&Color::WHITE.with_alpha(0.0), 0.25);
+        assert_eq!(Color::new(0.25, 0.25, 0.25, 0.75), color);
+        assert_eq!(Vector3::new(0.25, 0.25, 0.25), color.to_vector3());
+    }
+
+    #[test]
+    fn test_linear() {
+        assert_eq!(Color::WHITE, Color::WHITE.to_linear());
+        assert_eq!(Color::BLACK, Color::BLACK.to_linear());
+        // sRGB mid grey is about a fifth of the light
+        let grey = Color::new(0.5, 0.5, 0.5, 0.5).to_linear();
+        assert_near_eq!(0.214, grey.r, 0.001);
+        assert_eq!(0.5, grey.a);
+
+        let back = grey.to_srgb();
+        assert_near_eq!(0.5, back.r, 0.0001);
+        assert_near_eq!(0.5, back.b, 0.0001);
+    }
+
+    #[test]
+    fn test_sdl() {
+        let color = Color::new(1.5, 0.5, -1.0, 1.0);
+        assert_eq!(
+            sdl2::pixels::Color::RGBA(255, 128, 0, 255),
+            sdl2::pixels::Color::from(color)
+        );
+        let color = Color::from_hex(0x336699CC);
+        assert_eq!(color, Color::from(sdl2::pixels::Color::from(color)));
+    }
+}
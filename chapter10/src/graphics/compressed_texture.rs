@@ -0,0 +1,247 @@
+use std::ops::Range;
+
+use gl::types::GLenum;
+
+// S3TC formats of EXT_texture_compression_s3tc, not part of the core profile
+pub const COMPRESSED_RGBA_S3TC_DXT1: GLenum = 0x83F1;
+pub const COMPRESSED_RGBA_S3TC_DXT3: GLenum = 0x83F2;
+pub const COMPRESSED_RGBA_S3TC_DXT5: GLenum = 0x83F3;
+// Same with sRGB colors, from EXT_texture_sRGB
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT1: GLenum = 0x8C4D;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT3: GLenum = 0x8C4E;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT5: GLenum = 0x8C4F;
+
+const DDS_MAGIC: &[u8; 4] = b"DDS ";
+// Magic and header
+const DDS_DATA_OFFSET: usize = 128;
+
+const KTX_IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+// Written by a little endian machine
+const KTX_ENDIANNESS: u32 = 0x04030201;
+const KTX_HEADER_SIZE: usize = 64;
+
+/// One mip level of a compressed image
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedLevel {
+    pub width: i32,
+    pub height: i32,
+    // Bytes of the level in the file
+    pub data: Range<usize>,
+}
+
+/// Image stored compressed in a DDS or KTX file, uploaded to the GPU as is
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedImage {
+    pub internal_format: GLenum,
+    // From the full size down
+    pub levels: Vec<CompressedLevel>,
+}
+
+impl CompressedImage {
+    /// Same format with sRGB colors, if there is one
+    pub fn to_srgb_format(internal_format: GLenum) -> GLenum {
+        match internal_format {
+            COMPRESSED_RGBA_S3TC_DXT1 => COMPRESSED_SRGB_ALPHA_S3TC_DXT1,
+            COMPRESSED_RGBA_S3TC_DXT3 => COMPRESSED_SRGB_ALPHA_S3TC_DXT3,
+            COMPRESSED_RGBA_S3TC_DXT5 => COMPRESSED_SRGB_ALPHA_S3TC_DXT5,
+            _ => internal_format,
+        }
+    }
+}
+
+/// Whether the file is loaded with parse_dds/parse_ktx instead of the image crate
+pub fn is_compressed_file(file_name: &str) -> bool {
+    let lower = file_name.to_lowercase();
+    lower.ends_with(".dds") || lower.ends_with(".ktx")
+}
+
+/// DirectDraw Surface with DXT1, DXT3 or DXT5 blocks, without the DX10 extension
+pub fn parse_dds(bytes: &[u8]) -> Result<CompressedImage, String> {
+    if bytes.len() < DDS_DATA_OFFSET || &bytes[0..4] != DDS_MAGIC {
+        return Err("not a DDS file".to_string());
+    }
+
+    let height = read_u32(bytes, 12) as i32;
+    let width = read_u32(bytes, 16) as i32;
+    let mip_count = read_u32(bytes, 28).max(1) as usize;
+    let (internal_format, block_size) = match &bytes[84..88] {
+        b"DXT1" => (COMPRESSED_RGBA_S3TC_DXT1, 8),
+        b"DXT3" => (COMPRESSED_RGBA_S3TC_DXT3, 16),
+        b"DXT5" => (COMPRESSED_RGBA_S3TC_DXT5, 16),
+        four_cc => {
+            return Err(format!(
+                "unsupported format {}",
+                String::from_utf8_lossy(four_cc)
+            ))
+        }
+    };
+
+    // Blocks of 4x4 pixels, each level half the size of the previous one
+    let mut levels = vec![];
+    let mut offset = DDS_DATA_OFFSET;
+    let (mut level_width, mut level_height) = (width, height);
+    for _ in 0..mip_count {
+        let blocks = ((level_width + 3) / 4).max(1) * ((level_height + 3) / 4).max(1);
+        let size = blocks as usize * block_size;
+        if offset + size > bytes.len() {
+            return Err("truncated mip levels".to_string());
+        }
+        levels.push(CompressedLevel {
+            width: level_width,
+            height: level_height,
+            data: offset..offset + size,
+        });
+        offset += size;
+        level_width = (level_width / 2).max(1);
+        level_height = (level_height / 2).max(1);
+    }
+
+    Ok(CompressedImage {
+        internal_format,
+        levels,
+    })
+}
+
+/// KTX 1 file of a compressed 2D texture, any format the GPU supports
+pub fn parse_ktx(bytes: &[u8]) -> Result<CompressedImage, String> {
+    if bytes.len() < KTX_HEADER_SIZE || bytes[0..12] != KTX_IDENTIFIER {
+        return Err("not a KTX file".to_string());
+    }
+    if read_u32(bytes, 12) != KTX_ENDIANNESS {
+        return Err("big endian files are not supported".to_string());
+    }
+
+    let gl_type = read_u32(bytes, 16);
+    let gl_format = read_u32(bytes, 24);
+    let internal_format = read_u32(bytes, 28);
+    let width = read_u32(bytes, 36) as i32;
+    let height = read_u32(bytes, 40) as i32;
+    let depth = read_u32(bytes, 44);
+    let array_elements = read_u32(bytes, 48);
+    let faces = read_u32(bytes, 52);
+    let mip_count = read_u32(bytes, 56).max(1) as usize;
+    let key_value_bytes = read_u32(bytes, 60) as usize;
+
+    // Type and format are 0 for compressed data
+    if gl_type != 0 || gl_format != 0 {
+        return Err("only compressed formats are supported".to_string());
+    }
+    if depth > 1 || array_elements > 0 || faces != 1 {
+        return Err("only 2D textures are supported".to_string());
+    }
+
+    // Each level is its size then its data, padded to 4 bytes
+    let mut levels = vec![];
+    let mut offset = KTX_HEADER_SIZE + key_value_bytes;
+    let (mut level_width, mut level_height) = (width, height);
+    for _ in 0..mip_count {
+        if offset + 4 > bytes.len() {
+            return Err("truncated mip levels".to_string());
+        }
+        let size = read_u32(bytes, offset) as usize;
+        offset += 4;
+        if offset + size > bytes.len() {
+            return Err("truncated mip levels".to_string());
+        }
+        levels.push(CompressedLevel {
+            width: level_width,
+            height: level_height,
+            data: offset..offset + size,
+        });
+        offset += size.div_ceil(4) * 4;
+        level_width = (level_width / 2).max(1);
+        level_height = (level_height / 2).max(1);
+    }
+
+    Ok(CompressedImage {
+        internal_format,
+        levels,
+    })
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_compressed_file, parse_dds, parse_ktx, CompressedLevel, COMPRESSED_RGBA_S3TC_DXT1,
+        COMPRESSED_RGBA_S3TC_DXT5, KTX_ENDIANNESS, KTX_IDENTIFIER,
+    };
+
+    fn write_u32(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn test_parse_dds() {
+        // 8x4 DXT1 with 2 levels: 2 blocks then 1 block
+        let mut bytes = vec![0; 128 + 16 + 8];
+        bytes[0..4].copy_from_slice(b"DDS ");
+        write_u32(&mut bytes, 12, 4);
+        write_u32(&mut bytes, 16, 8);
+        write_u32(&mut bytes, 28, 2);
+        bytes[84..88].copy_from_slice(b"DXT1");
+
+        let image = parse_dds(&bytes).unwrap();
+        assert_eq!(COMPRESSED_RGBA_S3TC_DXT1, image.internal_format);
+        assert_eq!(
+            vec![
+                CompressedLevel {
+                    width: 8,
+                    height: 4,
+                    data: 128..144
+                },
+                CompressedLevel {
+                    width: 4,
+                    height: 2,
+                    data: 144..152
+                },
+            ],
+            image.levels
+        );
+
+        assert!(parse_dds(&bytes[..150]).is_err());
+        bytes[84..88].copy_from_slice(b"DX10");
+        assert!(parse_dds(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_parse_ktx() {
+        // 4x4 DXT5 with 1 level of 16 bytes, after 4 bytes of key/value data
+        let mut bytes = vec![0; 64 + 4 + 4 + 16];
+        bytes[0..12].copy_from_slice(&KTX_IDENTIFIER);
+        write_u32(&mut bytes, 12, KTX_ENDIANNESS);
+        write_u32(&mut bytes, 28, COMPRESSED_RGBA_S3TC_DXT5);
+        write_u32(&mut bytes, 36, 4);
+        write_u32(&mut bytes, 40, 4);
+        write_u32(&mut bytes, 52, 1);
+        write_u32(&mut bytes, 56, 1);
+        write_u32(&mut bytes, 60, 4);
+        write_u32(&mut bytes, 68, 16);
+
+        let image = parse_ktx(&bytes).unwrap();
+        assert_eq!(COMPRESSED_RGBA_S3TC_DXT5, image.internal_format);
+        assert_eq!(1, image.levels.len());
+        assert_eq!(72..88, image.levels[0].data);
+
+        // Uncompressed
+        write_u32(&mut bytes, 24, gl::RGBA);
+        assert!(parse_ktx(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_is_compressed_file() {
+        assert!(is_compressed_file("Floor.dds"));
+        assert!(is_compressed_file("Floor.KTX"));
+        assert!(!is_compressed_file("Plane.png"));
+    }
+}
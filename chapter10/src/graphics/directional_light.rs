@@ -1,24 +1,26 @@
 use crate::math::vector3::Vector3;
 
+use super::color::Color;
+
 pub struct DirectionalLight {
     // Direction of light
     pub direction: Vector3,
     // Diffuse color
-    pub diffuse_color: Vector3,
+    pub diffuse_color: Color,
     // Specular color
-    pub spec_color: Vector3,
+    pub spec_color: Color,
 }
 
 impl DirectionalLight {
     pub fn new() -> Self {
         Self {
             direction: Vector3::ZERO,
-            diffuse_color: Vector3::ZERO,
-            spec_color: Vector3::ZERO,
+            diffuse_color: Color::BLACK,
+            spec_color: Color::BLACK,
         }
     }
 
-    pub fn from(direction: Vector3, diffuse_color: Vector3, spec_color: Vector3) -> Self {
+    pub fn from(direction: Vector3, diffuse_color: Color, spec_color: Color) -> Self {
         Self {
             direction,
             diffuse_color,
@@ -0,0 +1,157 @@
+use std::{ffi::c_void, ptr::null};
+
+use anyhow::{anyhow, Result};
+use gl::{
+    CLAMP_TO_EDGE, COLOR_ATTACHMENT0, DEPTH_ATTACHMENT, DEPTH_COMPONENT24, FLOAT, FRAMEBUFFER,
+    FRAMEBUFFER_COMPLETE, LINEAR, RENDERBUFFER, RGBA, RGBA16F, TEXTURE0, TEXTURE_2D,
+    TEXTURE_MAG_FILTER, TEXTURE_MIN_FILTER, TEXTURE_WRAP_S, TEXTURE_WRAP_T, UNSIGNED_BYTE,
+};
+use image::{imageops, RgbaImage};
+
+/// Offscreen render target with a floating point (HDR) color texture
+pub struct FrameBuffer {
+    // OpenGL ID of the frame buffer object
+    frame_buffer: u32,
+
+    // OpenGL ID of the color texture
+    color_texture: u32,
+
+    // OpenGL ID of the depth render buffer (0 if there isn't one)
+    depth_buffer: u32,
+
+    width: i32,
+    height: i32,
+}
+
+impl FrameBuffer {
+    pub fn new(width: i32, height: i32, with_depth: bool) -> Result<Self> {
+        let mut frame_buffer = 0;
+        let mut color_texture = 0;
+        let mut depth_buffer = 0;
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut frame_buffer);
+            gl::BindFramebuffer(FRAMEBUFFER, frame_buffer);
+
+            // Color values aren't clamped to [0, 1] so bright spots survive until tonemapping
+            gl::GenTextures(1, &mut color_texture);
+            gl::BindTexture(TEXTURE_2D, color_texture);
+            gl::TexImage2D(
+                TEXTURE_2D,
+                0,
+                RGBA16F as i32,
+                width,
+                height,
+                0,
+                RGBA,
+                FLOAT,
+                null(),
+            );
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_MIN_FILTER, LINEAR as i32);
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_MAG_FILTER, LINEAR as i32);
+            // Blurring samples past the edges
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_WRAP_S, CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_WRAP_T, CLAMP_TO_EDGE as i32);
+            gl::FramebufferTexture2D(FRAMEBUFFER, COLOR_ATTACHMENT0, TEXTURE_2D, color_texture, 0);
+
+            if with_depth {
+                gl::GenRenderbuffers(1, &mut depth_buffer);
+                gl::BindRenderbuffer(RENDERBUFFER, depth_buffer);
+                gl::RenderbufferStorage(RENDERBUFFER, DEPTH_COMPONENT24, width, height);
+                gl::FramebufferRenderbuffer(
+                    FRAMEBUFFER,
+                    DEPTH_ATTACHMENT,
+                    RENDERBUFFER,
+                    depth_buffer,
+                );
+            }
+        }
+
+        let this = Self {
+            frame_buffer,
+            color_texture,
+            depth_buffer,
+            width,
+            height,
+        };
+
+        let status = unsafe {
+            let status = gl::CheckFramebufferStatus(FRAMEBUFFER);
+            // Leave the viewport alone, it's still the one of the window
+            gl::BindFramebuffer(FRAMEBUFFER, 0);
+            status
+        };
+        if status != FRAMEBUFFER_COMPLETE {
+            this.unload();
+            return Err(anyhow!("Frame buffer is incomplete: {:#x}", status));
+        }
+
+        Ok(this)
+    }
+
+    pub fn unload(&self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.frame_buffer);
+            gl::DeleteTextures(1, &self.color_texture);
+            if self.depth_buffer != 0 {
+                gl::DeleteRenderbuffers(1, &self.depth_buffer);
+            }
+        }
+    }
+
+    /// Draw into this frame buffer from now on
+    pub fn set_active(&self) {
+        unsafe {
+            gl::BindFramebuffer(FRAMEBUFFER, self.frame_buffer);
+            gl::Viewport(0, 0, self.width, self.height);
+        }
+    }
+
+    /// Draw into the window again
+    pub fn set_default_active(screen_width: i32, screen_height: i32) {
+        unsafe {
+            gl::BindFramebuffer(FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, screen_width, screen_height);
+        }
+    }
+
+    /// Colors clamped to 8 bits, top row first. Leaves this frame buffer bound
+    pub fn read_pixels(&self) -> RgbaImage {
+        let mut pixels = vec![0u8; (self.width * self.height * 4) as usize];
+        unsafe {
+            gl::BindFramebuffer(FRAMEBUFFER, self.frame_buffer);
+            gl::ReadPixels(
+                0,
+                0,
+                self.width,
+                self.height,
+                RGBA,
+                UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut c_void,
+            );
+        }
+
+        let mut image = RgbaImage::from_raw(self.width as u32, self.height as u32, pixels)
+            .expect("pixel buffer matches the frame buffer size");
+        // OpenGL reads from the bottom row
+        imageops::flip_vertical_in_place(&mut image);
+        image
+    }
+
+    /// Bind the color texture to the texture unit
+    pub fn set_texture_active(&self, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(TEXTURE0 + unit);
+            gl::BindTexture(TEXTURE_2D, self.color_texture);
+            gl::ActiveTexture(TEXTURE0);
+        }
+    }
+
+    pub fn get_width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> i32 {
+        self.height
+    }
+}
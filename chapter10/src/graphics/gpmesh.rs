@@ -0,0 +1,152 @@
+use serde_json::Value;
+
+use crate::system::engine_error::EngineError;
+
+/// Contents of a gpmesh file, vertices are 8 floats each: position, normal and UV
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpMesh {
+    pub shader_name: String,
+    pub textures: Vec<String>,
+    pub spec_power: f32,
+    // Below 1 the mesh is blended over what is behind it
+    pub alpha: f32,
+    pub vertices: Vec<f32>,
+    pub indices: Vec<u32>,
+}
+
+impl GpMesh {
+    pub fn parse(json: &Value, file_name: &str) -> Result<Self, EngineError> {
+        // Check the version
+        if json["version"].as_i64() != Some(1) {
+            return Err(EngineError::invalid_asset(file_name, "not version 1"));
+        }
+
+        let shader_name = match json["shader"].as_str() {
+            Some(shader_name) => shader_name.to_string(),
+            None => return Err(EngineError::invalid_asset(file_name, "no shader")),
+        };
+
+        let textures_json = match json["textures"].as_array() {
+            Some(textures) if !textures.is_empty() => textures,
+            _ => {
+                return Err(EngineError::invalid_asset(
+                    file_name,
+                    "no textures, there should be at least one",
+                ))
+            }
+        };
+
+        let mut textures = vec![];
+        for texture_json in textures_json {
+            match texture_json.as_str() {
+                Some(texture_name) => textures.push(texture_name.to_string()),
+                None => return Err(EngineError::invalid_asset(file_name, "invalid texture")),
+            }
+        }
+
+        let (vertices, indices) = GpMesh::parse_geometry(json, file_name)?;
+
+        Ok(Self {
+            shader_name,
+            textures,
+            spec_power: json["specularPower"].as_f64().unwrap_or(100.0) as f32,
+            alpha: json["alpha"].as_f64().unwrap_or(1.0).clamp(0.0, 1.0) as f32,
+            vertices,
+            indices,
+        })
+    }
+
+    /// Vertices and indices alone, all a LOD file has
+    pub fn parse_geometry(
+        json: &Value,
+        file_name: &str,
+    ) -> Result<(Vec<f32>, Vec<u32>), EngineError> {
+        // Load in the vertices
+        let verts_json = match json["vertices"].as_array() {
+            Some(verts_json) if !verts_json.is_empty() => verts_json,
+            _ => return Err(EngineError::invalid_asset(file_name, "no vertices")),
+        };
+
+        let mut vertices = vec![];
+        for vert in verts_json {
+            // For now, just assume we have 8 elements
+            let vert = match vert.as_array() {
+                Some(vert) if vert.len() == 8 => vert,
+                _ => {
+                    return Err(EngineError::invalid_asset(
+                        file_name,
+                        "unexpected vertex format",
+                    ))
+                }
+            };
+
+            // Add the floats
+            for value in vert {
+                match value.as_f64() {
+                    Some(value) => vertices.push(value as f32),
+                    None => return Err(EngineError::invalid_asset(file_name, "invalid vertex")),
+                }
+            }
+        }
+
+        // Load in the indices
+        let ind_json = match json["indices"].as_array() {
+            Some(ind_json) if !ind_json.is_empty() => ind_json,
+            _ => return Err(EngineError::invalid_asset(file_name, "no indices")),
+        };
+
+        let vertex_count = (vertices.len() / 8) as u64;
+        let mut indices = vec![];
+        for ind in ind_json {
+            let ind = match ind.as_array() {
+                Some(ind) if ind.len() == 3 => ind,
+                _ => return Err(EngineError::invalid_asset(file_name, "invalid indices")),
+            };
+
+            // An index past the vertices would read out of the vertex buffer
+            for index in ind {
+                match index.as_u64() {
+                    Some(index) if index < vertex_count => indices.push(index as u32),
+                    _ => return Err(EngineError::invalid_asset(file_name, "invalid indices")),
+                }
+            }
+        }
+
+        Ok((vertices, indices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::system::engine_error::EngineError;
+
+    use super::GpMesh;
+
+    #[test]
+    fn test_parse_geometry() {
+        let json = json!({
+            "vertices": [
+                [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0],
+            ],
+            "indices": [[0, 1, 2]],
+        });
+        let (vertices, indices) = GpMesh::parse_geometry(&json, "Triangle.gpmesh").unwrap();
+        assert_eq!(24, vertices.len());
+        assert_eq!(vec![0, 1, 2], indices);
+
+        // Broken files are errors instead of panics
+        let json = json!({
+            "vertices": [[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0]],
+            "indices": [[0, 1, 2]],
+        });
+        let error = GpMesh::parse_geometry(&json, "Broken.gpmesh").unwrap_err();
+        assert!(matches!(error, EngineError::InvalidAsset { .. }));
+
+        let error = GpMesh::parse_geometry(&json!({}), "Empty.gpmesh").unwrap_err();
+        assert_eq!("Invalid asset Empty.gpmesh: no vertices", error.to_string());
+    }
+}
@@ -0,0 +1,128 @@
+use std::{cell::Cell, os::raw::c_void, ptr::null};
+
+use gl::{ARRAY_BUFFER, DYNAMIC_DRAW, FALSE, FLOAT, LINES};
+
+use crate::math::vector3::Vector3;
+
+use super::color::Color;
+
+use super::vertex_array::grow_capacity;
+
+/// Line in world space, for debug drawing
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugLine {
+    pub start: Vector3,
+    pub end: Vector3,
+    pub color: Color,
+}
+
+impl DebugLine {
+    pub fn new(start: Vector3, end: Vector3, color: Color) -> Self {
+        Self { start, end, color }
+    }
+}
+
+/// Colored lines uploaded and drawn all at once
+pub struct LineBatch {
+    // OpenGL ID of the vertex buffer
+    vertex_buffer: u32,
+
+    // OpenGL ID of the vertex array object
+    vertex_array: u32,
+
+    // Vertices the buffer has room for, it only grows
+    vertex_capacity: Cell<isize>,
+}
+
+impl LineBatch {
+    // Position and color, 3 floats each
+    const VERTEX_SIZE: usize = 6;
+
+    pub fn new() -> Self {
+        let mut vertex_array = 0;
+        let mut vertex_buffer = 0;
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vertex_array);
+            gl::BindVertexArray(vertex_array);
+
+            // Filled on every draw
+            gl::GenBuffers(1, &mut vertex_buffer);
+            gl::BindBuffer(ARRAY_BUFFER, vertex_buffer);
+
+            let stride = (size_of::<f32>() * LineBatch::VERTEX_SIZE) as i32;
+
+            // Position is 3 floats
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(0, 3, FLOAT, FALSE, stride, 0 as *const c_void);
+
+            // Color is 3 floats
+            gl::EnableVertexAttribArray(1);
+            gl::VertexAttribPointer(
+                1,
+                3,
+                FLOAT,
+                FALSE,
+                stride,
+                (size_of::<f32>() * 3) as *const c_void,
+            );
+        }
+
+        Self {
+            vertex_buffer,
+            vertex_array,
+            vertex_capacity: Cell::new(0),
+        }
+    }
+
+    pub fn unload(&self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.vertex_buffer);
+            gl::DeleteVertexArrays(1, &self.vertex_array);
+        }
+    }
+
+    /// Expects the line shader to be active
+    pub fn draw(&self, lines: &[DebugLine]) {
+        if lines.is_empty() {
+            return;
+        }
+
+        let data = lines
+            .iter()
+            .flat_map(|line| {
+                let DebugLine { start, end, color } = line;
+                [
+                    start.x, start.y, start.z, color.r, color.g, color.b, end.x, end.y, end.z,
+                    color.r, color.g, color.b,
+                ]
+            })
+            .collect::<Vec<f32>>();
+
+        let num_verts = (lines.len() * 2) as isize;
+        let vertex_bytes = (size_of::<f32>() * LineBatch::VERTEX_SIZE) as isize;
+
+        unsafe {
+            gl::BindVertexArray(self.vertex_array);
+            gl::BindBuffer(ARRAY_BUFFER, self.vertex_buffer);
+            // Reuse the storage of the previous frames while the lines fit
+            if num_verts > self.vertex_capacity.get() {
+                let vertex_capacity = grow_capacity(self.vertex_capacity.get(), num_verts);
+                gl::BufferData(
+                    ARRAY_BUFFER,
+                    vertex_capacity * vertex_bytes,
+                    null(),
+                    DYNAMIC_DRAW,
+                );
+                self.vertex_capacity.set(vertex_capacity);
+            }
+            gl::BufferSubData(
+                ARRAY_BUFFER,
+                0,
+                (data.len() * size_of::<f32>()) as isize,
+                data.as_ptr() as *const c_void,
+            );
+            gl::DrawArrays(LINES, 0, num_verts as i32);
+        }
+    }
+}
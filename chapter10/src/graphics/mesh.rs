@@ -1,135 +1,178 @@
 use std::{path::Path, rc::Rc};
 
-use anyhow::{anyhow, Ok, Result};
 use serde_json::Value;
 
-use crate::{collision::aabb::AABB, math::vector3::Vector3, system::asset_manager::AssetManager};
+use crate::{
+    collision::aabb::AABB,
+    math::vector3::Vector3,
+    system::{asset_manager::AssetManager, engine_error::EngineError},
+};
 
-use super::{texture::Texture, vertex_array::VertexArray};
+use super::{gpmesh::GpMesh, texture::Texture, vertex_array::VertexArray};
 
 pub struct Mesh {
     box_collision: AABB,
     textures: Vec<Rc<Texture>>,
     vertex_array: Option<Rc<VertexArray>>,
+    // Lower detail vertex arrays, from LOD1
+    lods: Vec<Rc<VertexArray>>,
     shader_name: String,
     spec_power: f32,
+    // Below 1 the mesh is blended over what is behind it
+    alpha: f32,
     radius: f32,
 }
 
 impl Mesh {
+    const MAX_LODS: usize = 3;
+    // Half the size of the placeholder cube
+    const PLACEHOLDER_EXTENT: f32 = 50.0;
+
     pub fn new() -> Self {
         Self {
             box_collision: AABB::new(Vector3::INFINITY, Vector3::NEGATIVE_INFINITY),
             textures: vec![],
             vertex_array: None,
+            lods: vec![],
             shader_name: String::new(),
             spec_power: 0.0,
+            alpha: 1.0,
             radius: 0.0,
         }
     }
 
-    pub fn load(&mut self, file_name: &str, asset_manager: &mut AssetManager) -> Result<()> {
-        let path = Path::new(env!("OUT_DIR"))
-            .join("resources")
-            .join("Assets")
-            .join(file_name);
-        let content = std::fs::read_to_string(path)?;
-        let json: Value = serde_json::from_str(&content)?;
-
-        // Check the version
-        let version = &json["version"].as_i64().unwrap();
-        if *version != 1 {
-            return Err(anyhow!("Mesh {} not version 1", file_name));
-        }
+    pub fn load(
+        &mut self,
+        file_name: &str,
+        asset_manager: &mut AssetManager,
+    ) -> Result<(), EngineError> {
+        let json = Mesh::read_json(file_name)?;
+        let gpmesh = GpMesh::parse(&json, file_name)?;
 
-        self.shader_name = json["shader"].as_str().unwrap().to_string();
-
-        // Skip the vertex format/shader for now
-        // (This is changed in a later chapter's code)
-        let vert_size = 8;
-
-        // Load textures
-        let textures = &json["textures"];
-        if !textures.is_array() || textures.as_array().unwrap().len() < 1 {
-            return Err(anyhow!(
-                "Mesh {} has no textures, there should be at least one",
-                file_name
-            ));
-        }
-
-        let textures = textures.as_array().unwrap();
-        for i in 0..textures.len() {
-            // Is this texture already loaded?
-            let texture_name = textures[i].as_str().unwrap();
+        // Load textures, the asset manager keeps the ones already loaded
+        for texture_name in &gpmesh.textures {
             let texture = asset_manager.get_texture(texture_name);
             self.textures.push(texture);
         }
 
-        self.spec_power = json["specularPower"].as_f64().unwrap() as f32;
+        self.shader_name = gpmesh.shader_name;
+        self.spec_power = gpmesh.spec_power;
+        self.alpha = gpmesh.alpha;
+        self.set_geometry(&gpmesh.vertices, &gpmesh.indices);
 
-        // Load in the vertices
-        let verts_json = &json["vertices"];
-        if !verts_json.is_array() || verts_json.as_array().unwrap().len() < 1 {
-            return Err(anyhow!("Mesh {} has no vertices", file_name));
-        }
+        self.load_lods(file_name)?;
+
+        Ok(())
+    }
 
-        let verts_json = verts_json.as_array().unwrap();
+    /// Cube with the placeholder texture, drawn in place of meshes that failed to load
+    pub fn create_placeholder(asset_manager: &mut AssetManager) -> Self {
         let mut vertices = vec![];
-        for i in 0..verts_json.len() {
-            // For now, just assume we have 8 elements
-            let vert = &verts_json[i];
-            if !vert.is_array() || vert.as_array().unwrap().len() != 8 {
-                return Err(anyhow!("Unexpected vertex format for {}", file_name));
+        let mut indices = vec![];
+        // A quad for each side, facing out along the axis
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut normal = [0.0; 3];
+                normal[axis] = sign;
+                let (u_axis, v_axis) = ((axis + 1) % 3, (axis + 2) % 3);
+                let first = (vertices.len() / 8) as u32;
+                for (u, v) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+                    let mut position = normal.map(|n| n * Mesh::PLACEHOLDER_EXTENT);
+                    position[u_axis] = (u * 2.0 - 1.0) * Mesh::PLACEHOLDER_EXTENT;
+                    position[v_axis] = (v * 2.0 - 1.0) * Mesh::PLACEHOLDER_EXTENT;
+                    vertices.extend_from_slice(&position);
+                    vertices.extend_from_slice(&normal);
+                    vertices.extend_from_slice(&[u, v]);
+                }
+                indices.extend_from_slice(&[
+                    first,
+                    first + 1,
+                    first + 2,
+                    first + 2,
+                    first + 3,
+                    first,
+                ]);
             }
+        }
 
-            let vert = vert.as_array().unwrap();
+        let mut mesh = Mesh::new();
+        mesh.shader_name = "BasicMesh".to_string();
+        mesh.spec_power = 100.0;
+        mesh.textures.push(asset_manager.get_default_texture());
+        mesh.set_geometry(&vertices, &indices);
+        mesh
+    }
 
-            let position = Vector3::new(
-                vert[0].as_f64().unwrap() as f32,
-                vert[1].as_f64().unwrap() as f32,
-                vert[2].as_f64().unwrap() as f32,
-            );
+    /// Mesh generated at runtime (e.g. terrain), vertices are 8 floats each
+    pub fn from_geometry(
+        vertices: &[f32],
+        indices: &[u32],
+        texture: Rc<Texture>,
+        shader_name: &str,
+    ) -> Self {
+        let mut mesh = Mesh::new();
+        mesh.shader_name = shader_name.to_string();
+        mesh.spec_power = 100.0;
+        mesh.textures.push(texture);
+        mesh.set_geometry(vertices, indices);
+        mesh
+    }
+
+    /// Bounds and vertex array of the full detail mesh, vertices are 8 floats each
+    fn set_geometry(&mut self, vertices: &[f32], indices: &[u32]) {
+        for vert in vertices.chunks(8) {
+            let position = Vector3::new(vert[0], vert[1], vert[2]);
             self.radius = self.radius.max(position.length_sq());
             self.box_collision.update_min_max(position);
-
-            // Add the floats
-            for i in 0..vert.len() {
-                vertices.push(vert[i].as_f64().unwrap() as f32);
-            }
         }
 
         // We were computing length squared earlier
         self.radius = self.radius.sqrt();
 
-        // Load in the indices
-        let ind_json = &json["indices"];
-        if !ind_json.is_array() || ind_json.as_array().unwrap().len() < 1 {
-            return Err(anyhow!("Mesh {} has no indices", file_name));
-        }
-
-        let ind_json = ind_json.as_array().unwrap();
-        let mut indices = vec![];
-        for i in 0..ind_json.len() {
-            let ind = &ind_json[i];
-            if !ind.is_array() || ind.as_array().unwrap().len() != 3 {
-                return Err(anyhow!("Invalid indices for {}", file_name));
-            }
-
-            let ind = ind.as_array().unwrap();
-            indices.push(ind[0].as_u64().unwrap() as u32);
-            indices.push(ind[1].as_u64().unwrap() as u32);
-            indices.push(ind[2].as_u64().unwrap() as u32);
-        }
-
         // Now create a vertex array
         let vertex_array = VertexArray::new(
-            &vertices,
-            (vertices.len() / vert_size) as isize,
-            &indices,
+            vertices,
+            (vertices.len() / 8) as isize,
+            indices,
             indices.len() as isize,
         );
 
         self.vertex_array = Some(Rc::new(vertex_array));
+    }
+
+    fn read_json(file_name: &str) -> Result<Value, EngineError> {
+        let path = Path::new(env!("OUT_DIR"))
+            .join("resources")
+            .join("Assets")
+            .join(file_name);
+        let content = std::fs::read_to_string(path).map_err(|e| EngineError::io(file_name, e))?;
+        serde_json::from_str(&content).map_err(|e| EngineError::invalid_asset(file_name, e))
+    }
+
+    /// Load the optional lower detail versions <name>_LOD1.gpmesh, <name>_LOD2.gpmesh, ...
+    /// stopping at the first missing one
+    fn load_lods(&mut self, file_name: &str) -> Result<(), EngineError> {
+        let stem = file_name.strip_suffix(".gpmesh").unwrap_or(file_name);
+        for level in 1..=Mesh::MAX_LODS {
+            let lod_name = format!("{}_LOD{}.gpmesh", stem, level);
+            let path = Path::new(env!("OUT_DIR"))
+                .join("resources")
+                .join("Assets")
+                .join(&lod_name);
+            if !path.exists() {
+                break;
+            }
+
+            let json = Mesh::read_json(&lod_name)?;
+            let (vertices, indices) = GpMesh::parse_geometry(&json, &lod_name)?;
+            let vertex_array = VertexArray::new(
+                &vertices,
+                (vertices.len() / 8) as isize,
+                &indices,
+                indices.len() as isize,
+            );
+            self.lods.push(Rc::new(vertex_array));
+        }
 
         Ok(())
     }
@@ -142,6 +185,19 @@ impl Mesh {
         self.vertex_array.clone().unwrap()
     }
 
+    /// Level 0 is the full detail mesh, levels past the last LOD get the last one
+    pub fn get_lod_vertex_array(&self, level: usize) -> Rc<VertexArray> {
+        match level.min(self.lods.len()) {
+            0 => self.get_vertex_array(),
+            level => self.lods[level - 1].clone(),
+        }
+    }
+
+    /// Number of levels including the full detail mesh
+    pub fn get_lod_count(&self) -> usize {
+        self.lods.len() + 1
+    }
+
     pub fn get_texture(&self, index: usize) -> Option<Rc<Texture>> {
         self.textures.get(index).cloned()
     }
@@ -154,6 +210,14 @@ impl Mesh {
         self.spec_power
     }
 
+    pub fn get_alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.alpha < 1.0
+    }
+
     pub fn get_radius(&self) -> f32 {
         self.radius
     }
@@ -1,5 +1,11 @@
+pub mod color;
+pub mod compressed_texture;
 pub mod directional_light;
+pub mod frame_buffer;
+pub mod gpmesh;
+pub mod line_batch;
 pub mod mesh;
+pub mod render_queue;
 pub mod shader;
 pub mod texture;
 pub mod vertex_array;
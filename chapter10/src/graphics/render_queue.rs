@@ -0,0 +1,101 @@
+/// Items of one view in draw order. Opaque items are grouped by shader then texture,
+/// front to back within a group so the depth test rejects hidden pixels early.
+/// Transparent items are back to front so each blends over what is behind it.
+pub struct RenderQueue<T> {
+    opaque: Vec<(u64, T)>,
+    transparent: Vec<(u64, T)>,
+}
+
+impl<T> RenderQueue<T> {
+    // Distance of the far plane, farther items share the last depth step
+    const MAX_DEPTH: f32 = 10000.0;
+    const DEPTH_BITS: u32 = 24;
+    const TEXTURE_BITS: u32 = 24;
+
+    pub fn new() -> Self {
+        Self {
+            opaque: vec![],
+            transparent: vec![],
+        }
+    }
+
+    pub fn push_opaque(&mut self, shader: u32, texture: u32, distance: f32, item: T) {
+        self.opaque
+            .push((Self::opaque_key(shader, texture, distance), item));
+    }
+
+    pub fn push_transparent(&mut self, distance: f32, item: T) {
+        self.transparent
+            .push((Self::transparent_key(distance), item));
+    }
+
+    /// Stable, so items with the same key keep the order they were pushed in
+    pub fn sort(&mut self) {
+        self.opaque.sort_by_key(|(key, _)| *key);
+        self.transparent.sort_by_key(|(key, _)| *key);
+    }
+
+    pub fn get_opaque(&self) -> impl Iterator<Item = &T> {
+        self.opaque.iter().map(|(_, item)| item)
+    }
+
+    pub fn get_transparent(&self) -> impl Iterator<Item = &T> {
+        self.transparent.iter().map(|(_, item)| item)
+    }
+
+    /// Shader in the highest bits, then texture, then depth
+    fn opaque_key(shader: u32, texture: u32, distance: f32) -> u64 {
+        let shader = (shader as u64) & 0xFFFF;
+        let texture = (texture as u64) & ((1 << Self::TEXTURE_BITS) - 1);
+        shader << (Self::TEXTURE_BITS + Self::DEPTH_BITS)
+            | texture << Self::DEPTH_BITS
+            | Self::quantize_depth(distance)
+    }
+
+    /// Only the depth counts, inverted so the farthest comes first
+    fn transparent_key(distance: f32) -> u64 {
+        ((1 << Self::DEPTH_BITS) - 1) - Self::quantize_depth(distance)
+    }
+
+    fn quantize_depth(distance: f32) -> u64 {
+        let max = ((1 << Self::DEPTH_BITS) - 1) as f32;
+        ((distance / Self::MAX_DEPTH).clamp(0.0, 1.0) * max) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenderQueue;
+
+    #[test]
+    fn test_opaque_order() {
+        let mut queue = RenderQueue::new();
+        queue.push_opaque(1, 5, 100.0, "far");
+        queue.push_opaque(1, 5, 10.0, "near");
+        queue.push_opaque(0, 9, 500.0, "other shader");
+        queue.push_opaque(1, 2, 900.0, "other texture");
+        queue.sort();
+
+        // State changes first, then front to back
+        assert_eq!(
+            vec!["other shader", "other texture", "near", "far"],
+            queue.get_opaque().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_transparent_order() {
+        let mut queue = RenderQueue::new();
+        queue.push_transparent(10.0, "near");
+        queue.push_transparent(20000.0, "past far");
+        queue.push_transparent(300.0, "middle");
+        queue.push_transparent(-5.0, "behind");
+        queue.sort();
+
+        assert_eq!(
+            vec!["past far", "middle", "near", "behind"],
+            queue.get_transparent().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(0, queue.get_opaque().count());
+    }
+}
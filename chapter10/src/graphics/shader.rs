@@ -1,12 +1,20 @@
-use std::{ffi::CString, path::Path, ptr::null, ptr::null_mut};
+use std::{
+    cell::RefCell, collections::HashMap, ffi::CString, path::Path, ptr::null, ptr::null_mut,
+};
 
-use anyhow::{anyhow, Ok, Result};
 use gl::{
-    types::{GLenum, GLuint},
+    types::{GLenum, GLint, GLuint},
     COMPILE_STATUS, FRAGMENT_SHADER, LINK_STATUS, TRUE, VERTEX_SHADER,
 };
 
-use crate::math::{matrix4::Matrix4, vector3::Vector3};
+use crate::{
+    graphics::color::Color,
+    math::{matrix4::Matrix4, vector2::Vector2, vector3::Vector3},
+    system::{
+        crash_report::log,
+        engine_error::{check_gl_error, EngineError},
+    },
+};
 
 pub struct Shader {
     // OpenGL IDs of the vertex shader
@@ -17,6 +25,19 @@ pub struct Shader {
 
     // OpenGL IDs of the shader program
     shader_program: GLuint,
+
+    // Vertex/frag file names, for the error messages
+    name: String,
+
+    // Locations already looked up, -1 for missing uniforms so they are reported once
+    uniform_locations: RefCell<HashMap<String, GLint>>,
+}
+
+/// Shader text after the includes are replaced, with where each line comes from
+struct ShaderSource {
+    text: String,
+    // File name and line number (from 1) of each line of text
+    lines: Vec<(String, usize)>,
 }
 
 impl Shader {
@@ -25,10 +46,16 @@ impl Shader {
             vertex_shader: 0,
             frag_shader: 0,
             shader_program: 0,
+            name: String::new(),
+            uniform_locations: RefCell::new(HashMap::new()),
         }
     }
 
-    pub fn load(&mut self, vert_name: &str, frag_name: &str) -> Result<()> {
+    pub fn load(&mut self, vert_name: &str, frag_name: &str) -> Result<(), EngineError> {
+        self.name = format!("{}/{}", vert_name, frag_name);
+        // Locations of the previous program, if any, are no longer valid
+        self.uniform_locations.borrow_mut().clear();
+
         // Compile vertex and pixel shaders
         self.vertex_shader = self.compile_shader(vert_name, VERTEX_SHADER)?;
         self.frag_shader = self.compile_shader(frag_name, FRAGMENT_SHADER)?;
@@ -43,7 +70,7 @@ impl Shader {
 
         self.is_valid_program()?;
 
-        Ok(())
+        check_gl_error(&self.name)
     }
 
     pub fn unload(&mut self) {
@@ -63,8 +90,7 @@ impl Shader {
     pub fn set_matrix_uniform(&self, name: &str, matrix: Matrix4) {
         unsafe {
             // Find the uniform by this name
-            let name = CString::new(name).unwrap();
-            let location_id = gl::GetUniformLocation(self.shader_program, name.as_ptr());
+            let location_id = self.get_uniform_location(name);
             // Send the matrix data to the uniform
             gl::UniformMatrix4fv(location_id, 1, TRUE, matrix.get_as_float_ptr());
         }
@@ -72,28 +98,82 @@ impl Shader {
 
     pub fn set_vector_uniform(&self, name: &str, vector: &Vector3) {
         unsafe {
-            let name = CString::new(name).unwrap();
-            let location_id = gl::GetUniformLocation(self.shader_program, name.as_ptr());
+            let location_id = self.get_uniform_location(name);
             gl::Uniform3fv(location_id, 1, vector.get_as_float_ptr());
         }
     }
 
+    /// For vec4 uniforms
+    pub fn set_color_uniform(&self, name: &str, color: &Color) {
+        unsafe {
+            let location_id = self.get_uniform_location(name);
+            gl::Uniform4f(location_id, color.r, color.g, color.b, color.a);
+        }
+    }
+
+    /// For vec3 uniforms, leaving alpha out
+    pub fn set_rgb_uniform(&self, name: &str, color: &Color) {
+        unsafe {
+            let location_id = self.get_uniform_location(name);
+            gl::Uniform3f(location_id, color.r, color.g, color.b);
+        }
+    }
+
+    pub fn set_vector2_uniform(&self, name: &str, vector: &Vector2) {
+        unsafe {
+            let location_id = self.get_uniform_location(name);
+            gl::Uniform2f(location_id, vector.x, vector.y);
+        }
+    }
+
     pub fn set_float_uniform(&self, name: &str, value: f32) {
         unsafe {
-            let name = CString::new(name).unwrap();
-            let location_id = gl::GetUniformLocation(self.shader_program, name.as_ptr());
+            let location_id = self.get_uniform_location(name);
             gl::Uniform1f(location_id, value);
         }
     }
 
-    fn compile_shader(&mut self, file_name: &str, shader_type: GLenum) -> Result<u32> {
-        // Read all the text into a string
-        let path = Path::new(env!("OUT_DIR"))
-            .join("resources")
-            .join("Shaders")
-            .join(file_name);
-        let contents = std::fs::read_to_string(path)?;
-        let contents = CString::new(contents.as_str())?;
+    pub fn set_int_uniform(&self, name: &str, value: i32) {
+        unsafe {
+            let location_id = self.get_uniform_location(name);
+            gl::Uniform1i(location_id, value);
+        }
+    }
+
+    pub fn set_float_array_uniform(&self, name: &str, values: &[f32]) {
+        unsafe {
+            let location_id = self.get_uniform_location(name);
+            gl::Uniform1fv(location_id, values.len() as i32, values.as_ptr());
+        }
+    }
+
+    /// Location of the uniform, -1 (ignored by GL) if the shader doesn't have it.
+    /// A missing uniform is either misspelled or unused and removed by the GLSL compiler.
+    /// Looked up by name once, then cached
+    fn get_uniform_location(&self, name: &str) -> GLint {
+        if let Some(&location_id) = self.uniform_locations.borrow().get(name) {
+            return location_id;
+        }
+
+        let location_id = match CString::new(name) {
+            Ok(c_name) => unsafe { gl::GetUniformLocation(self.shader_program, c_name.as_ptr()) },
+            Err(_) => -1,
+        };
+        if location_id == -1 {
+            log!("Uniform {} not found in shader {}", name, self.name);
+        }
+        self.uniform_locations
+            .borrow_mut()
+            .insert(name.to_string(), location_id);
+
+        location_id
+    }
+
+    fn compile_shader(&mut self, file_name: &str, shader_type: GLenum) -> Result<u32, EngineError> {
+        // Read all the text into a string, with the included files
+        let source = preprocess(file_name, &read_shader_file)?;
+        let contents = CString::new(source.text.as_str())
+            .map_err(|e| EngineError::invalid_asset(file_name, e))?;
         let contents_char = contents.as_ptr();
         let mut out_shader = 0;
 
@@ -106,42 +186,221 @@ impl Shader {
             gl::CompileShader(out_shader);
         }
 
-        if let Err(error) = self.is_compiled(out_shader) {
-            return Err(anyhow!("Failed to comple shader {}: {}", file_name, error));
+        if let Err(log) = self.is_compiled(out_shader) {
+            return Err(EngineError::ShaderCompile {
+                file_name: file_name.to_string(),
+                log: source.annotate_log(&log),
+            });
         }
 
         Ok(out_shader)
     }
 
-    fn is_compiled(&self, shader: u32) -> Result<()> {
+    /// Err with the info log if the shader failed to compile
+    fn is_compiled(&self, shader: u32) -> Result<(), String> {
         let mut status = 0;
         unsafe {
             gl::GetShaderiv(shader, COMPILE_STATUS, &mut status);
             if status != TRUE as i32 {
-                let mut buffer = [0_i8; 512];
-                gl::GetShaderInfoLog(shader, 511, null_mut(), buffer.as_mut_ptr());
-                let buf = Vec::from(buffer).into_iter().map(|b| b as u8).collect();
-                let error = String::from_utf8(buf)?;
-                return Err(anyhow!("Failed to compile GLSL: {}", error));
+                let mut buffer = [0_u8; 512];
+                gl::GetShaderInfoLog(shader, 511, null_mut(), buffer.as_mut_ptr() as *mut _);
+                return Err(info_log_to_string(&buffer));
             }
         }
 
         Ok(())
     }
 
-    fn is_valid_program(&self) -> Result<()> {
+    fn is_valid_program(&self) -> Result<(), EngineError> {
         let mut status = 0;
         unsafe {
             gl::GetProgramiv(self.shader_program, LINK_STATUS, &mut status);
             if status != TRUE as i32 {
-                let mut buffer = [0_i8; 512];
-                gl::GetShaderInfoLog(self.shader_program, 511, null_mut(), buffer.as_mut_ptr());
-                let buf = Vec::from(buffer).into_iter().map(|b| b as u8).collect();
-                let error = String::from_utf8(buf)?;
-                return Err(anyhow!("GLSL Link status: {}", error));
+                let mut buffer = [0_u8; 512];
+                gl::GetProgramInfoLog(
+                    self.shader_program,
+                    511,
+                    null_mut(),
+                    buffer.as_mut_ptr() as *mut _,
+                );
+                return Err(EngineError::ShaderLink {
+                    shader_name: self.name.clone(),
+                    log: info_log_to_string(&buffer),
+                });
             }
         }
 
         Ok(())
     }
 }
+
+impl ShaderSource {
+    /// Prefix the log lines pointing at a line of text with the file and line it came from
+    fn annotate_log(&self, log: &str) -> String {
+        log.lines()
+            .map(|log_line| {
+                let location = parse_log_line_number(log_line)
+                    .and_then(|line| self.lines.get(line.wrapping_sub(1)));
+                match location {
+                    Some((file_name, line)) => format!("{}:{}: {}", file_name, line, log_line),
+                    None => log_line.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn read_shader_file(file_name: &str) -> Result<String, EngineError> {
+    let path = Path::new(env!("OUT_DIR"))
+        .join("resources")
+        .join("Shaders")
+        .join(file_name);
+    std::fs::read_to_string(path).map_err(|e| EngineError::io(file_name, e))
+}
+
+/// Replace the `#include "File.glsl"` lines with the text of the file, read with read.
+/// Each file is included once, later includes of it are skipped
+fn preprocess(
+    file_name: &str,
+    read: &dyn Fn(&str) -> Result<String, EngineError>,
+) -> Result<ShaderSource, EngineError> {
+    let mut source = ShaderSource {
+        text: String::new(),
+        lines: vec![],
+    };
+    append_file(file_name, read, &mut source, &mut vec![], &mut vec![])?;
+    Ok(source)
+}
+
+fn append_file(
+    file_name: &str,
+    read: &dyn Fn(&str) -> Result<String, EngineError>,
+    source: &mut ShaderSource,
+    included: &mut Vec<String>,
+    // Files being included, from the shader down to this one
+    stack: &mut Vec<String>,
+) -> Result<(), EngineError> {
+    if stack.iter().any(|name| name == file_name) {
+        return Err(EngineError::invalid_asset(file_name, "includes itself"));
+    }
+    if included.iter().any(|name| name == file_name) {
+        return Ok(());
+    }
+    included.push(file_name.to_string());
+    stack.push(file_name.to_string());
+
+    let contents = read(file_name)?;
+    for (index, line) in contents.lines().enumerate() {
+        if let Some(rest) = line.trim().strip_prefix("#include") {
+            let include_name = rest
+                .trim()
+                .strip_prefix('"')
+                .and_then(|rest| rest.strip_suffix('"'));
+            match include_name {
+                Some(include_name) => append_file(include_name, read, source, included, stack)?,
+                None => {
+                    return Err(EngineError::invalid_asset(
+                        file_name,
+                        format!("line {}: expected #include \"File\"", index + 1),
+                    ))
+                }
+            }
+            continue;
+        }
+
+        source.text.push_str(line);
+        source.text.push('\n');
+        source.lines.push((file_name.to_string(), index + 1));
+    }
+
+    stack.pop();
+    Ok(())
+}
+
+/// Line number of a compiler message, after the source string 0 like
+/// "0:12(3): error" (Mesa), "ERROR: 0:12: " (AMD, Intel) or "0(12) : error" (NVIDIA)
+fn parse_log_line_number(log_line: &str) -> Option<usize> {
+    for prefix in ["0:", "0("] {
+        for (index, _) in log_line.match_indices(prefix) {
+            // Not the end of a longer number, e.g. an error code
+            if log_line[..index].ends_with(|c: char| c.is_ascii_digit()) {
+                continue;
+            }
+            let digits = log_line[index + prefix.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect::<String>();
+            if let Ok(line) = digits.parse() {
+                return Some(line);
+            }
+        }
+    }
+    None
+}
+
+/// Text of a GL info log, up to the terminating null
+fn info_log_to_string(buffer: &[u8]) -> String {
+    let length = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..length])
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::system::engine_error::EngineError;
+
+    use super::{parse_log_line_number, preprocess};
+
+    fn read(file_name: &str) -> Result<String, EngineError> {
+        let text = match file_name {
+            "Main.frag" => {
+                "#version 330\n#include \"Light.glsl\"\n#include \"Fog.glsl\"\nvoid main() {}"
+            }
+            "Light.glsl" => "uniform vec3 uLight;\n#include \"Fog.glsl\"",
+            "Fog.glsl" => "uniform float uFog;",
+            "Loop.glsl" => "#include \"Loop.glsl\"",
+            _ => return Err(EngineError::invalid_asset(file_name, "missing")),
+        };
+        Ok(text.to_string())
+    }
+
+    #[test]
+    fn test_preprocess() {
+        let source = preprocess("Main.frag", &read).unwrap();
+        assert_eq!(
+            "#version 330\nuniform vec3 uLight;\nuniform float uFog;\nvoid main() {}\n",
+            source.text
+        );
+        assert_eq!(("Fog.glsl".to_string(), 1), source.lines[2]);
+        assert_eq!(("Main.frag".to_string(), 4), source.lines[3]);
+
+        assert!(preprocess("Loop.glsl", &read).is_err());
+        assert!(preprocess("Missing.frag", &read).is_err());
+
+        // Errors point at the included file
+        let log = source.annotate_log("0:3(5): error: syntax error\nwarning without line");
+        assert_eq!(
+            "Fog.glsl:1: 0:3(5): error: syntax error\nwarning without line",
+            log
+        );
+    }
+
+    #[test]
+    fn test_parse_log_line_number() {
+        assert_eq!(
+            Some(12),
+            parse_log_line_number("0:12(3): error: undeclared")
+        );
+        assert_eq!(
+            Some(7),
+            parse_log_line_number("ERROR: 0:7: 'x' : undeclared")
+        );
+        assert_eq!(
+            Some(4),
+            parse_log_line_number("0(4) : error C0000: syntax error")
+        );
+        assert_eq!(None, parse_log_line_number("Link failed"));
+    }
+}
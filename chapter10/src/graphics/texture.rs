@@ -1,9 +1,102 @@
-use std::{os::raw::c_void, path::Path};
+use std::{fs, os::raw::c_void, path::Path};
 
-use anyhow::{Ok, Result};
-use gl::{LINEAR, RGB, RGBA, TEXTURE_2D, TEXTURE_MAG_FILTER, TEXTURE_MIN_FILTER, UNSIGNED_BYTE};
+use gl::{
+    types::{GLenum, GLfloat},
+    CLAMP_TO_EDGE, LINEAR, LINEAR_MIPMAP_LINEAR, MIRRORED_REPEAT, NEAREST, NEAREST_MIPMAP_NEAREST,
+    NO_ERROR, REPEAT, RGB, RGBA, SRGB8, SRGB8_ALPHA8, TEXTURE_2D, TEXTURE_MAG_FILTER,
+    TEXTURE_MAX_LEVEL, TEXTURE_MIN_FILTER, TEXTURE_WRAP_S, TEXTURE_WRAP_T, UNSIGNED_BYTE,
+};
 use image::{ColorType, ImageReader};
 
+use crate::system::engine_error::{check_gl_error, EngineError};
+
+use super::compressed_texture::{self, CompressedImage};
+
+// From EXT_texture_filter_anisotropic, not in the core profile before 4.6
+const TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FE;
+const MAX_TEXTURE_MAX_ANISOTROPY: GLenum = 0x84FF;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureFilter {
+    // Sharp texels, for pixel art
+    Nearest,
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureWrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+/// Sampler settings and how the image is stored, chosen per texture file
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureOptions {
+    pub filter: TextureFilter,
+    pub wrap: TextureWrap,
+    pub mipmaps: bool,
+    // 1 disables it, clamped to what the GPU supports
+    pub anisotropy: f32,
+    // Colors are stored in sRGB and converted to linear when sampled
+    pub srgb: bool,
+}
+
+impl TextureOptions {
+    /// Bilinear filtering between mipmaps, what most textures want
+    pub fn new() -> Self {
+        Self {
+            filter: TextureFilter::Linear,
+            wrap: TextureWrap::Repeat,
+            mipmaps: true,
+            anisotropy: 1.0,
+            srgb: false,
+        }
+    }
+
+    /// Sprites and HUD images drawn at about their own size
+    pub fn pixel_art() -> Self {
+        Self {
+            filter: TextureFilter::Nearest,
+            wrap: TextureWrap::ClampToEdge,
+            mipmaps: false,
+            ..Self::new()
+        }
+    }
+
+    /// Textures seen at grazing angles, which blur without anisotropic filtering
+    pub fn floor() -> Self {
+        Self {
+            anisotropy: 8.0,
+            ..Self::new()
+        }
+    }
+
+    pub fn get_min_filter(&self, has_mipmaps: bool) -> GLenum {
+        match (self.filter, self.mipmaps && has_mipmaps) {
+            (TextureFilter::Nearest, false) => NEAREST,
+            (TextureFilter::Nearest, true) => NEAREST_MIPMAP_NEAREST,
+            (TextureFilter::Linear, false) => LINEAR,
+            (TextureFilter::Linear, true) => LINEAR_MIPMAP_LINEAR,
+        }
+    }
+
+    pub fn get_mag_filter(&self) -> GLenum {
+        match self.filter {
+            TextureFilter::Nearest => NEAREST,
+            TextureFilter::Linear => LINEAR,
+        }
+    }
+
+    pub fn get_wrap(&self) -> GLenum {
+        match self.wrap {
+            TextureWrap::Repeat => REPEAT,
+            TextureWrap::MirroredRepeat => MIRRORED_REPEAT,
+            TextureWrap::ClampToEdge => CLAMP_TO_EDGE,
+        }
+    }
+}
+
 pub struct Texture {
     // OpenGL ID of this texture
     texture_id: u32,
@@ -22,13 +115,29 @@ impl Texture {
         }
     }
 
-    pub fn load(&mut self, file_name: &str) -> Result<()> {
+    pub fn load(&mut self, file_name: &str) -> Result<(), EngineError> {
+        self.load_with_options(file_name, &TextureOptions::new())
+    }
+
+    /// DDS and KTX files are uploaded compressed, other images through the image crate
+    pub fn load_with_options(
+        &mut self,
+        file_name: &str,
+        options: &TextureOptions,
+    ) -> Result<(), EngineError> {
         let path = Path::new(env!("OUT_DIR"))
             .join("resources")
             .join("Assets")
             .join(file_name);
 
-        let image = ImageReader::open(path)?.decode()?;
+        if compressed_texture::is_compressed_file(file_name) {
+            return self.load_compressed(file_name, &path, options);
+        }
+
+        let image = ImageReader::open(path)
+            .map_err(|e| EngineError::io(file_name, e))?
+            .decode()
+            .map_err(|e| EngineError::invalid_asset(file_name, e))?;
 
         self.width = image.width() as i32;
         self.height = image.height() as i32;
@@ -37,6 +146,11 @@ impl Texture {
             ColorType::Rgba8 | ColorType::Rgba16 | ColorType::Rgba32F => RGBA,
             _ => RGB,
         };
+        let internal_format = match (format, options.srgb) {
+            (RGBA, true) => SRGB8_ALPHA8,
+            (_, true) => SRGB8,
+            _ => format,
+        };
 
         unsafe {
             gl::GenTextures(1, &mut self.texture_id);
@@ -45,7 +159,7 @@ impl Texture {
             gl::TexImage2D(
                 TEXTURE_2D,
                 0,
-                format as i32,
+                internal_format as i32,
                 self.width,
                 self.height,
                 0,
@@ -54,12 +168,183 @@ impl Texture {
                 image.as_bytes().as_ptr() as *const c_void,
             );
 
-            // Enable bilinear filtering
+            if options.mipmaps {
+                gl::GenerateMipmap(TEXTURE_2D);
+            }
+        }
+        Texture::apply_options(options, options.mipmaps);
+
+        check_gl_error(file_name)
+    }
+
+    fn load_compressed(
+        &mut self,
+        file_name: &str,
+        path: &Path,
+        options: &TextureOptions,
+    ) -> Result<(), EngineError> {
+        let bytes = fs::read(path).map_err(|e| EngineError::io(file_name, e))?;
+        let lower = file_name.to_lowercase();
+        let image = if lower.ends_with(".dds") {
+            compressed_texture::parse_dds(&bytes)
+        } else {
+            compressed_texture::parse_ktx(&bytes)
+        }
+        .map_err(|e| EngineError::invalid_asset(file_name, e))?;
+
+        let internal_format = if options.srgb {
+            CompressedImage::to_srgb_format(image.internal_format)
+        } else {
+            image.internal_format
+        };
+        // The file's own mipmaps are used, they can't be generated for compressed data
+        let level_count = if options.mipmaps {
+            image.levels.len()
+        } else {
+            1
+        };
+
+        self.width = image.levels[0].width;
+        self.height = image.levels[0].height;
+
+        unsafe {
+            gl::GenTextures(1, &mut self.texture_id);
+            gl::BindTexture(TEXTURE_2D, self.texture_id);
+
+            for (level, data) in image.levels.iter().take(level_count).enumerate() {
+                let level_bytes = &bytes[data.data.clone()];
+                gl::CompressedTexImage2D(
+                    TEXTURE_2D,
+                    level as i32,
+                    internal_format,
+                    data.width,
+                    data.height,
+                    0,
+                    level_bytes.len() as i32,
+                    level_bytes.as_ptr() as *const c_void,
+                );
+            }
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_MAX_LEVEL, level_count as i32 - 1);
+        }
+        Texture::apply_options(options, level_count > 1);
+
+        check_gl_error(file_name)
+    }
+
+    /// Sampler settings of the bound texture
+    fn apply_options(options: &TextureOptions, has_mipmaps: bool) {
+        unsafe {
+            gl::TexParameteri(
+                TEXTURE_2D,
+                TEXTURE_MIN_FILTER,
+                options.get_min_filter(has_mipmaps) as i32,
+            );
+            gl::TexParameteri(
+                TEXTURE_2D,
+                TEXTURE_MAG_FILTER,
+                options.get_mag_filter() as i32,
+            );
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_WRAP_S, options.get_wrap() as i32);
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_WRAP_T, options.get_wrap() as i32);
+
+            if options.anisotropy > 1.0 {
+                let mut max_anisotropy: GLfloat = 1.0;
+                gl::GetFloatv(MAX_TEXTURE_MAX_ANISOTROPY, &mut max_anisotropy);
+                // Without the extension the query fails, then the texture isn't anisotropic
+                if gl::GetError() == NO_ERROR {
+                    gl::TexParameterf(
+                        TEXTURE_2D,
+                        TEXTURE_MAX_ANISOTROPY,
+                        options.anisotropy.min(max_anisotropy),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Magenta and black checkerboard standing in for textures that failed to load
+    pub fn create_placeholder() -> Self {
+        const SIZE: i32 = 8;
+        let mut pixels = Vec::with_capacity((SIZE * SIZE * 3) as usize);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let color = if (x + y) % 2 == 0 {
+                    [255, 0, 255]
+                } else {
+                    [0, 0, 0]
+                };
+                pixels.extend_from_slice(&color);
+            }
+        }
+
+        let mut texture = Texture::new();
+        texture.width = SIZE;
+        texture.height = SIZE;
+        unsafe {
+            gl::GenTextures(1, &mut texture.texture_id);
+            gl::BindTexture(TEXTURE_2D, texture.texture_id);
+            gl::TexImage2D(
+                TEXTURE_2D,
+                0,
+                RGB as i32,
+                SIZE,
+                SIZE,
+                0,
+                RGB,
+                UNSIGNED_BYTE,
+                pixels.as_ptr() as *const c_void,
+            );
+
+            // Keep the squares sharp
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_MIN_FILTER, NEAREST as i32);
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_MAG_FILTER, NEAREST as i32);
+        }
+
+        texture
+    }
+
+    /// Red, transparent in the middle and opaque towards the edges, stretched over a view
+    pub fn create_vignette() -> Self {
+        const SIZE: i32 = 64;
+        // Fraction of the distance to the corners staying transparent
+        const INNER: f32 = 0.5;
+        let half = SIZE as f32 / 2.0;
+        let mut pixels = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let dx = (x as f32 + 0.5 - half) / half;
+                let dy = (y as f32 + 0.5 - half) / half;
+                let distance = (dx * dx + dy * dy).sqrt() / 2.0_f32.sqrt();
+                let alpha = ((distance - INNER) / (1.0 - INNER)).clamp(0.0, 1.0);
+                pixels.extend_from_slice(&[255, 0, 0, (alpha * 255.0) as u8]);
+            }
+        }
+
+        let mut texture = Texture::new();
+        texture.width = SIZE;
+        texture.height = SIZE;
+        unsafe {
+            gl::GenTextures(1, &mut texture.texture_id);
+            gl::BindTexture(TEXTURE_2D, texture.texture_id);
+            gl::TexImage2D(
+                TEXTURE_2D,
+                0,
+                RGBA as i32,
+                SIZE,
+                SIZE,
+                0,
+                RGBA,
+                UNSIGNED_BYTE,
+                pixels.as_ptr() as *const c_void,
+            );
+
             gl::TexParameteri(TEXTURE_2D, TEXTURE_MIN_FILTER, LINEAR as i32);
             gl::TexParameteri(TEXTURE_2D, TEXTURE_MAG_FILTER, LINEAR as i32);
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_WRAP_S, CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(TEXTURE_2D, TEXTURE_WRAP_T, CLAMP_TO_EDGE as i32);
         }
 
-        Ok(())
+        texture
     }
 
     pub fn unload(&self) {
@@ -74,6 +359,10 @@ impl Texture {
         }
     }
 
+    pub fn get_id(&self) -> u32 {
+        self.texture_id
+    }
+
     pub fn get_width(&self) -> i32 {
         self.width
     }
@@ -82,3 +371,33 @@ impl Texture {
         self.height
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gl::{
+        CLAMP_TO_EDGE, LINEAR, LINEAR_MIPMAP_LINEAR, NEAREST, NEAREST_MIPMAP_NEAREST, REPEAT,
+    };
+
+    use super::{TextureFilter, TextureOptions};
+
+    #[test]
+    fn test_texture_options_filters() {
+        let options = TextureOptions::new();
+        assert_eq!(LINEAR_MIPMAP_LINEAR, options.get_min_filter(true));
+        // No mipmap levels to sample
+        assert_eq!(LINEAR, options.get_min_filter(false));
+        assert_eq!(REPEAT, options.get_wrap());
+
+        let options = TextureOptions::pixel_art();
+        assert_eq!(NEAREST, options.get_min_filter(true));
+        assert_eq!(NEAREST, options.get_mag_filter());
+        assert_eq!(CLAMP_TO_EDGE, options.get_wrap());
+
+        let options = TextureOptions {
+            mipmaps: true,
+            ..TextureOptions::pixel_art()
+        };
+        assert_eq!(TextureFilter::Nearest, options.filter);
+        assert_eq!(NEAREST_MIPMAP_NEAREST, options.get_min_filter(true));
+    }
+}
@@ -1,6 +1,10 @@
-use std::os::raw::c_void;
+use std::{cell::Cell, os::raw::c_void, ptr::null};
 
-use gl::{ARRAY_BUFFER, ELEMENT_ARRAY_BUFFER, FALSE, FLOAT, STATIC_DRAW};
+use gl::{
+    types::GLenum, ARRAY_BUFFER, DYNAMIC_DRAW, ELEMENT_ARRAY_BUFFER, FALSE, FLOAT, STATIC_DRAW,
+};
+
+use crate::math::matrix4::Matrix4;
 
 pub struct VertexArray {
     // How many vertices in the vertex buffer?
@@ -17,10 +21,40 @@ pub struct VertexArray {
 
     // OpenGL ID of the vertex array object
     vertex_array: u32,
+
+    // OpenGL ID of the per-instance world transforms (0 until first used)
+    instance_buffer: Cell<u32>,
+
+    // Vertices/indices the buffers have room for, can be more than used once updated
+    vertex_capacity: isize,
+    index_capacity: isize,
+
+    // STATIC_DRAW, or DYNAMIC_DRAW if rebuilt often
+    usage: GLenum,
 }
 
 impl VertexArray {
+    // Position, normal and texture coordinate
+    const VERTEX_SIZE: usize = 8;
+    // Room of the first allocation of a dynamic array
+    const MIN_CAPACITY: isize = 64;
+
     pub fn new(verts: &[f32], num_verts: isize, indices: &[u32], num_indices: isize) -> Self {
+        VertexArray::create(verts, num_verts, indices, num_indices, STATIC_DRAW)
+    }
+
+    /// Empty, filled with update_vertices/update_indices e.g. every frame
+    pub fn new_dynamic() -> Self {
+        VertexArray::create(&[], 0, &[], 0, DYNAMIC_DRAW)
+    }
+
+    fn create(
+        verts: &[f32],
+        num_verts: isize,
+        indices: &[u32],
+        num_indices: isize,
+        usage: GLenum,
+    ) -> Self {
         let verts = verts.as_ptr();
         let indices = indices.as_ptr();
         let mut vertex_array = 0;
@@ -39,7 +73,7 @@ impl VertexArray {
                 ARRAY_BUFFER,
                 num_verts * 8 * size_of::<f32>() as isize,
                 verts as *const c_void,
-                STATIC_DRAW,
+                usage,
             );
 
             // Create index buffer
@@ -49,7 +83,7 @@ impl VertexArray {
                 ELEMENT_ARRAY_BUFFER,
                 num_indices * size_of::<u32>() as isize,
                 indices as *const c_void,
-                STATIC_DRAW,
+                usage,
             );
 
             // Specify the vertex attributes (For now, assume one vertex format)
@@ -93,6 +127,109 @@ impl VertexArray {
             vertex_buffer,
             index_buffer,
             vertex_array,
+            instance_buffer: Cell::new(0),
+            vertex_capacity: num_verts,
+            index_capacity: num_indices,
+            usage,
+        }
+    }
+
+    /// Replace the vertices (8 floats each) keeping the GL objects.
+    /// The buffer only grows, by doubling, so rebuilding every frame rarely allocates
+    pub fn update_vertices(&mut self, verts: &[f32]) {
+        let num_verts = (verts.len() / VertexArray::VERTEX_SIZE) as isize;
+        let vertex_bytes = (VertexArray::VERTEX_SIZE * size_of::<f32>()) as isize;
+
+        unsafe {
+            gl::BindVertexArray(self.vertex_array);
+            gl::BindBuffer(ARRAY_BUFFER, self.vertex_buffer);
+            if num_verts > self.vertex_capacity {
+                self.vertex_capacity = grow_capacity(self.vertex_capacity, num_verts);
+                gl::BufferData(
+                    ARRAY_BUFFER,
+                    self.vertex_capacity * vertex_bytes,
+                    null(),
+                    self.usage,
+                );
+            }
+            gl::BufferSubData(
+                ARRAY_BUFFER,
+                0,
+                num_verts * vertex_bytes,
+                verts.as_ptr() as *const c_void,
+            );
+        }
+
+        self.num_verts = num_verts;
+    }
+
+    /// Replace the indices keeping the GL objects, growing like update_vertices
+    pub fn update_indices(&mut self, indices: &[u32]) {
+        let num_indices = indices.len() as isize;
+        let index_bytes = size_of::<u32>() as isize;
+
+        unsafe {
+            // The index buffer binding is part of the vertex array
+            gl::BindVertexArray(self.vertex_array);
+            if num_indices > self.index_capacity {
+                self.index_capacity = grow_capacity(self.index_capacity, num_indices);
+                gl::BufferData(
+                    ELEMENT_ARRAY_BUFFER,
+                    self.index_capacity * index_bytes,
+                    null(),
+                    self.usage,
+                );
+            }
+            gl::BufferSubData(
+                ELEMENT_ARRAY_BUFFER,
+                0,
+                num_indices * index_bytes,
+                indices.as_ptr() as *const c_void,
+            );
+        }
+
+        self.num_indices = num_indices;
+    }
+
+    /// Upload one world transform per instance to attributes 3 to 6.
+    /// Expects this vertex array to be active.
+    pub fn set_instance_transforms(&self, world_transforms: &[Matrix4]) {
+        let data = world_transforms
+            .iter()
+            .flat_map(|matrix| matrix.mat.iter().flatten().copied())
+            .collect::<Vec<f32>>();
+
+        unsafe {
+            if self.instance_buffer.get() == 0 {
+                let mut instance_buffer = 0;
+                gl::GenBuffers(1, &mut instance_buffer);
+                gl::BindBuffer(ARRAY_BUFFER, instance_buffer);
+
+                // A mat4 attribute takes 4 locations of 4 floats
+                for row in 0..4 {
+                    let location = 3 + row;
+                    gl::EnableVertexAttribArray(location);
+                    gl::VertexAttribPointer(
+                        location,
+                        4,
+                        FLOAT,
+                        FALSE,
+                        size_of::<f32>() as i32 * 16,
+                        (size_of::<f32>() * 4 * row as usize) as *const c_void,
+                    );
+                    // Advance once per instance instead of once per vertex
+                    gl::VertexAttribDivisor(location, 1);
+                }
+                self.instance_buffer.set(instance_buffer);
+            }
+
+            gl::BindBuffer(ARRAY_BUFFER, self.instance_buffer.get());
+            gl::BufferData(
+                ARRAY_BUFFER,
+                (data.len() * size_of::<f32>()) as isize,
+                data.as_ptr() as *const c_void,
+                DYNAMIC_DRAW,
+            );
         }
     }
 
@@ -111,12 +248,36 @@ impl VertexArray {
     }
 }
 
+/// Room for at least needed elements, doubling the current capacity
+pub fn grow_capacity(capacity: isize, needed: isize) -> isize {
+    let mut capacity = capacity.max(VertexArray::MIN_CAPACITY);
+    while capacity < needed {
+        capacity *= 2;
+    }
+    capacity
+}
+
 impl Drop for VertexArray {
     fn drop(&mut self) {
         unsafe {
             gl::DeleteBuffers(1, &self.vertex_buffer);
             gl::DeleteBuffers(1, &self.index_buffer);
+            gl::DeleteBuffers(1, &self.instance_buffer.get());
             gl::DeleteVertexArrays(1, &self.vertex_array);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::grow_capacity;
+
+    #[test]
+    fn test_grow_capacity() {
+        // Never below the minimum, then doubled until it fits
+        assert_eq!(64, grow_capacity(0, 10));
+        assert_eq!(128, grow_capacity(64, 65));
+        assert_eq!(400, grow_capacity(100, 300));
+        assert_eq!(200, grow_capacity(200, 150));
+    }
+}
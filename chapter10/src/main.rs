@@ -6,12 +6,44 @@ mod graphics;
 mod math;
 mod system;
 
-use crate::game::*;
+use crate::{
+    game::*,
+    math::random,
+    system::{
+        benchmark::BenchConfig,
+        crash_report,
+        determinism::DeterminismOptions,
+        golden_image::{self, GoldenOptions},
+        scratch_pool::CountingAllocator,
+    },
+};
 use anyhow::Result;
 
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
 fn main() -> Result<()> {
+    crash_report::install();
+
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(options) = GoldenOptions::parse(&args) {
+        let passed = golden_image::run(&options)?;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    if let Some(options) = DeterminismOptions::parse(&args) {
+        // Before the game creates its generators
+        random::set_seed(Some(DeterminismOptions::SEED));
+        let mut game = Game::initialize()?;
+        let passed = game.run_determinism(&options)?;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
     let mut game = Game::initialize()?;
-    game.run_loop();
+    match BenchConfig::parse(&args) {
+        Some(config) => game.run_bench(config),
+        None => game.run_loop(),
+    }
 
     Ok(())
 }
@@ -0,0 +1,79 @@
+use super::vector3::Vector3;
+
+/// Where a projectile fired now at projectile_speed meets a target moving at target_vel,
+/// None if the target outruns the projectile
+pub fn solve_intercept(
+    shooter_pos: &Vector3,
+    target_pos: &Vector3,
+    target_vel: &Vector3,
+    projectile_speed: f32,
+) -> Option<Vector3> {
+    // |to_target + target_vel * t| = projectile_speed * t, solved for the earliest t > 0
+    let to_target = target_pos.clone() - shooter_pos.clone();
+    let a = Vector3::dot(target_vel, target_vel) - projectile_speed * projectile_speed;
+    let b = 2.0 * Vector3::dot(&to_target, target_vel);
+    let c = Vector3::dot(&to_target, &to_target);
+
+    let time = if a.abs() < 0.0001 {
+        // Same speed as the target, only catches it when it comes closer
+        if b >= 0.0 {
+            return None;
+        }
+        -c / b
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        let t1 = (-b - root) / (2.0 * a);
+        let t2 = (-b + root) / (2.0 * a);
+        match (t1 > 0.0, t2 > 0.0) {
+            (true, true) => t1.min(t2),
+            (true, false) => t1,
+            (false, true) => t2,
+            (false, false) => return None,
+        }
+    };
+
+    Some(target_pos.clone() + target_vel.clone() * time)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::solve_intercept;
+
+    #[test]
+    fn test_solve_intercept() {
+        let shooter = Vector3::ZERO;
+
+        // Still target is hit where it is
+        let point = solve_intercept(
+            &shooter,
+            &Vector3::new(100.0, 0.0, 0.0),
+            &Vector3::ZERO,
+            50.0,
+        );
+        assert_eq!(Some(Vector3::new(100.0, 0.0, 0.0)), point);
+
+        // Crossing target, the projectile and the target arrive at the same time
+        let target = Vector3::new(300.0, 0.0, 0.0);
+        let velocity = Vector3::new(0.0, 40.0, 0.0);
+        let point = solve_intercept(&shooter, &target, &velocity, 50.0).unwrap();
+        let projectile_time = point.length() / 50.0;
+        let target_time = (point.clone() - target).length() / 40.0;
+        assert_near_eq!(projectile_time, target_time, 0.001);
+        assert!(point.y > 0.0);
+
+        // Running away faster than the projectile
+        let point = solve_intercept(
+            &shooter,
+            &Vector3::new(100.0, 0.0, 0.0),
+            &Vector3::new(80.0, 0.0, 0.0),
+            50.0,
+        );
+        assert_eq!(None, point);
+    }
+}
@@ -1,7 +1,9 @@
 pub mod basic;
+pub mod intercept;
 pub mod matrix3;
 pub mod matrix4;
 pub mod quaternion;
 pub mod random;
+pub mod steering;
 pub mod vector2;
 pub mod vector3;
@@ -1,14 +1,43 @@
+use std::cell::Cell;
+
 use super::{vector2::Vector2, vector3::Vector3};
-use rand::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
+
+thread_local! {
+    // Seed of the next generator created, None to seed them from the OS
+    static NEXT_SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Seed the generators created from now on, each with the seed following the last one,
+/// so runs creating them in the same order get the same numbers. None seeds them from the OS
+pub fn set_seed(seed: Option<u64>) {
+    NEXT_SEED.with(|next_seed| next_seed.set(seed));
+}
 
 pub struct Random {
-    generator: ThreadRng,
+    generator: StdRng,
 }
 
 impl Random {
     pub fn new() -> Self {
-        let rng = thread_rng();
-        Self { generator: rng }
+        let seed = NEXT_SEED.with(|next_seed| {
+            let seed = next_seed.get();
+            next_seed.set(seed.map(|seed| seed.wrapping_add(1)));
+            seed
+        });
+        match seed {
+            Some(seed) => Random::from_seed(seed),
+            None => Self {
+                generator: StdRng::from_entropy(),
+            },
+        }
+    }
+
+    /// Same numbers for the same seed, whatever the platform
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            generator: StdRng::seed_from_u64(seed),
+        }
     }
 
     pub fn get_float(&mut self) -> f32 {
@@ -31,3 +60,29 @@ impl Random {
 
     // TODO: Not yet implemented
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{set_seed, Random};
+
+    fn take_floats(random: &mut Random) -> Vec<f32> {
+        (0..8).map(|_| random.get_float()).collect()
+    }
+
+    #[test]
+    fn test_set_seed() {
+        set_seed(Some(42));
+        let first = take_floats(&mut Random::new());
+        let second = take_floats(&mut Random::new());
+        // Each generator gets its own seed
+        assert_ne!(first, second);
+
+        // Created in the same order, they repeat
+        set_seed(Some(42));
+        assert_eq!(first, take_floats(&mut Random::new()));
+        assert_eq!(second, take_floats(&mut Random::new()));
+        assert_eq!(first, take_floats(&mut Random::from_seed(42)));
+
+        set_seed(None);
+    }
+}
@@ -0,0 +1,255 @@
+use super::{random::Random, vector3::Vector3};
+
+/// What the behaviors know about the agent and its surroundings this frame
+pub struct SteeringInput {
+    pub position: Vector3,
+    pub velocity: Vector3,
+    pub max_speed: f32,
+    // Where to go, for seek and arrive
+    pub target: Option<Vector3>,
+    // What to run from, for flee
+    pub threat: Option<Vector3>,
+    // Positions of the other agents, for separation
+    pub neighbors: Vec<Vector3>,
+}
+
+/// Part of the steering of an agent, returns the velocity it wants
+pub trait SteeringBehavior {
+    fn desired_velocity(&mut self, input: &SteeringInput) -> Vector3;
+}
+
+/// Full speed toward the target
+pub struct Seek;
+
+impl SteeringBehavior for Seek {
+    fn desired_velocity(&mut self, input: &SteeringInput) -> Vector3 {
+        match &input.target {
+            Some(target) => scale_to(target.clone() - input.position.clone(), input.max_speed),
+            None => Vector3::ZERO,
+        }
+    }
+}
+
+/// Full speed away from the threat while it's closer than panic_distance
+pub struct Flee {
+    pub panic_distance: f32,
+}
+
+impl SteeringBehavior for Flee {
+    fn desired_velocity(&mut self, input: &SteeringInput) -> Vector3 {
+        let away = match &input.threat {
+            Some(threat) => input.position.clone() - threat.clone(),
+            None => return Vector3::ZERO,
+        };
+        if away.length() > self.panic_distance {
+            return Vector3::ZERO;
+        }
+        scale_to(away, input.max_speed)
+    }
+}
+
+/// Like seek, but slows down to stop at the target
+pub struct Arrive {
+    pub slowing_distance: f32,
+}
+
+impl SteeringBehavior for Arrive {
+    fn desired_velocity(&mut self, input: &SteeringInput) -> Vector3 {
+        let to_target = match &input.target {
+            Some(target) => target.clone() - input.position.clone(),
+            None => return Vector3::ZERO,
+        };
+        let distance = to_target.length();
+        let speed = input.max_speed * (distance / self.slowing_distance).min(1.0);
+        scale_to(to_target, speed)
+    }
+}
+
+/// Away from the neighbors closer than radius, stronger the closer they are
+pub struct Separation {
+    pub radius: f32,
+}
+
+impl SteeringBehavior for Separation {
+    fn desired_velocity(&mut self, input: &SteeringInput) -> Vector3 {
+        let mut push = Vector3::ZERO;
+        for neighbor in &input.neighbors {
+            let away = input.position.clone() - neighbor.clone();
+            let distance = away.length();
+            if distance >= self.radius {
+                continue;
+            }
+            // Overlapping exactly, any direction will do
+            let direction = if distance > 0.0 {
+                away * (1.0 / distance)
+            } else {
+                Vector3::UNIT_X
+            };
+            push += direction * (1.0 - distance / self.radius);
+        }
+        let strength = push.length().min(1.0);
+        scale_to(push, input.max_speed * strength)
+    }
+}
+
+/// Random heading changing smoothly, from a point moving on a level circle in front of the agent
+pub struct Wander {
+    // Radius of the circle and its distance ahead of the agent
+    radius: f32,
+    distance: f32,
+    // Largest move of the point each frame
+    jitter: f32,
+    // On the circle, relative to its center
+    point: Vector3,
+    random: Random,
+}
+
+impl Wander {
+    pub fn new(radius: f32, distance: f32, jitter: f32) -> Self {
+        Self {
+            radius,
+            distance,
+            jitter,
+            point: Vector3::new(radius, 0.0, 0.0),
+            random: Random::new(),
+        }
+    }
+}
+
+impl SteeringBehavior for Wander {
+    fn desired_velocity(&mut self, input: &SteeringInput) -> Vector3 {
+        // Stays level, agents walk on the floor
+        let jitter = Vector3::new(self.jitter, self.jitter, 0.0);
+        self.point += self.random.get_vector3(-jitter.clone(), jitter);
+        self.point = scale_to(self.point.clone(), self.radius);
+
+        let heading = if input.velocity.length_sq() > 0.0 {
+            input.velocity.normalize()
+        } else {
+            Vector3::UNIT_X
+        };
+        scale_to(
+            heading * self.distance + self.point.clone(),
+            input.max_speed,
+        )
+    }
+}
+
+/// Weighted sum of behaviors, limited to the max speed
+pub struct Steering {
+    behaviors: Vec<(Box<dyn SteeringBehavior>, f32)>,
+}
+
+impl Steering {
+    pub fn new() -> Self {
+        Self { behaviors: vec![] }
+    }
+
+    pub fn with(mut self, behavior: impl SteeringBehavior + 'static, weight: f32) -> Self {
+        self.behaviors.push((Box::new(behavior), weight));
+        self
+    }
+
+    /// Velocity the agent should move at
+    pub fn compute(&mut self, input: &SteeringInput) -> Vector3 {
+        let mut velocity = Vector3::ZERO;
+        for (behavior, weight) in self.behaviors.iter_mut() {
+            velocity += behavior.desired_velocity(input) * *weight;
+        }
+        if velocity.length() > input.max_speed {
+            velocity = scale_to(velocity, input.max_speed);
+        }
+        velocity
+    }
+}
+
+/// Vector in the direction of v with the given length, zero if v is zero
+fn scale_to(v: Vector3, length: f32) -> Vector3 {
+    let current = v.length();
+    if current <= 0.0 {
+        return Vector3::ZERO;
+    }
+    v * (length / current)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_near_eq, math::vector3::Vector3};
+
+    use super::{
+        Arrive, Flee, Seek, Separation, Steering, SteeringBehavior, SteeringInput, Wander,
+    };
+
+    fn input(target: Option<Vector3>, neighbors: Vec<Vector3>) -> SteeringInput {
+        SteeringInput {
+            position: Vector3::ZERO,
+            velocity: Vector3::ZERO,
+            max_speed: 100.0,
+            target: target.clone(),
+            threat: target,
+            neighbors,
+        }
+    }
+
+    #[test]
+    fn test_seek_flee() {
+        let input = input(Some(Vector3::new(0.0, 50.0, 0.0)), vec![]);
+        assert_eq!(Vector3::new(0.0, 100.0, 0.0), Seek.desired_velocity(&input));
+
+        let mut flee = Flee {
+            panic_distance: 100.0,
+        };
+        assert_eq!(
+            Vector3::new(0.0, -100.0, 0.0),
+            flee.desired_velocity(&input)
+        );
+        flee.panic_distance = 10.0;
+        assert_eq!(Vector3::ZERO, flee.desired_velocity(&input));
+    }
+
+    #[test]
+    fn test_arrive() {
+        let mut arrive = Arrive {
+            slowing_distance: 200.0,
+        };
+        let far = input(Some(Vector3::new(400.0, 0.0, 0.0)), vec![]);
+        assert_eq!(Vector3::new(100.0, 0.0, 0.0), arrive.desired_velocity(&far));
+
+        let near = input(Some(Vector3::new(50.0, 0.0, 0.0)), vec![]);
+        assert_near_eq!(25.0, arrive.desired_velocity(&near).x, 0.001);
+
+        let there = input(Some(Vector3::ZERO), vec![]);
+        assert_eq!(Vector3::ZERO, arrive.desired_velocity(&there));
+    }
+
+    #[test]
+    fn test_separation() {
+        let mut separation = Separation { radius: 50.0 };
+        let input = input(
+            None,
+            vec![Vector3::new(25.0, 0.0, 0.0), Vector3::new(0.0, 500.0, 0.0)],
+        );
+        let velocity = separation.desired_velocity(&input);
+        // Away from the close neighbor only, at half strength
+        assert_near_eq!(-50.0, velocity.x, 0.001);
+        assert_near_eq!(0.0, velocity.y, 0.001);
+    }
+
+    #[test]
+    fn test_steering() {
+        // Seeking up while pushed left by a neighbor on the right
+        let mut steering = Steering::new()
+            .with(Seek, 1.0)
+            .with(Separation { radius: 50.0 }, 1.0)
+            .with(Wander::new(10.0, 50.0, 1.0), 0.0);
+        let input = input(
+            Some(Vector3::new(0.0, 500.0, 0.0)),
+            vec![Vector3::new(10.0, 0.0, 0.0)],
+        );
+        let velocity = steering.compute(&input);
+
+        assert!(velocity.x < 0.0);
+        assert!(velocity.y > 0.0);
+        assert_near_eq!(100.0, velocity.length(), 0.001);
+    }
+}
@@ -1,4 +1,6 @@
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Vector2 {
@@ -45,6 +47,29 @@ impl Vector2 {
     pub fn dot(&self, other: &Vector2) -> f32 {
         self.x * other.x + self.y * other.y
     }
+
+    pub fn distance_sq(&self, other: &Vector2) -> f32 {
+        (self.clone() - other.clone()).length_sq()
+    }
+
+    pub fn distance(&self, other: &Vector2) -> f32 {
+        self.distance_sq(other).sqrt()
+    }
+
+    /// Linear interpolation from self (f = 0.0) to other (f = 1.0)
+    pub fn lerp(&self, other: &Vector2, f: f32) -> Vector2 {
+        self.clone() + (other.clone() - self.clone()) * f
+    }
+
+    /// Reflect about the normal, which must be normalized
+    pub fn reflect(&self, normal: &Vector2) -> Vector2 {
+        self.clone() - normal.clone() * 2.0 * self.dot(normal)
+    }
+
+    /// Clamp each component between the ones of min and max
+    pub fn clamp(&self, min: &Vector2, max: &Vector2) -> Vector2 {
+        Vector2::new(self.x.clamp(min.x, max.x), self.y.clamp(min.y, max.y))
+    }
 }
 
 impl Add for Vector2 {
@@ -113,8 +138,63 @@ impl MulAssign<f32> for Vector2 {
     }
 }
 
+/// Scalar division
+impl Div<f32> for Vector2 {
+    type Output = Self;
+
+    fn div(self, rhs: f32) -> Self::Output {
+        Self {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+impl DivAssign<f32> for Vector2 {
+    fn div_assign(&mut self, rhs: f32) {
+        self.x /= rhs;
+        self.y /= rhs;
+    }
+}
+
+impl Neg for Vector2 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+/// Component by index, 0 is x, 1 is y
+impl Index<usize> for Vector2 {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("Vector2 index out of range: {}", index),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vector2 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("Vector2 index out of range: {}", index),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use crate::{
         assert_near_eq,
         math::{self, vector2::Vector2},
@@ -248,4 +328,92 @@ mod tests {
 
         assert_near_eq!(expected, angle, 0.001);
     }
+
+    #[test]
+    fn test_div_scalar() {
+        let expected = Vector2::new(5.0, 3.0);
+
+        let a = Vector2::new(10.0, 6.0);
+        let actual = a / 2.0;
+
+        assert_eq!(expected, actual);
+
+        let mut actual = Vector2::new(10.0, 6.0);
+        actual /= 2.0;
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neg() {
+        let expected = Vector2::new(-5.0, -3.0);
+
+        let actual = -Vector2::new(5.0, 3.0);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut a = Vector2::new(1.0, 2.0);
+        assert_eq!(1.0, a[0]);
+        assert_eq!(2.0, a[1]);
+
+        a[1] = 10.0;
+        assert_eq!(10.0, a.y);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_range() {
+        let _ = Vector2::ZERO[2];
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Vector2::new(1.0, 2.0);
+        let b = Vector2::new(4.0, 6.0);
+
+        assert_eq!(25.0, a.distance_sq(&b));
+        assert_eq!(5.0, a.distance(&b));
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = Vector2::new(0.0, 0.0);
+        let b = Vector2::new(10.0, 20.0);
+
+        assert_eq!(a, a.lerp(&b, 0.0));
+        assert_eq!(Vector2::new(5.0, 10.0), a.lerp(&b, 0.5));
+        assert_eq!(b, a.lerp(&b, 1.0));
+    }
+
+    #[test]
+    fn test_reflect() {
+        let expected = Vector2::new(1.0, 1.0);
+
+        let a = Vector2::new(1.0, -1.0);
+        let actual = a.reflect(&Vector2::UNIT_Y);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let expected = Vector2::new(1.0, -1.0);
+
+        let a = Vector2::new(3.0, -2.0);
+        let actual = a.clamp(&Vector2::new(-1.0, -1.0), &Vector2::new(1.0, 1.0));
+
+        assert_eq!(expected, actual);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_normalize_is_unit_length(x in -1000.0f32..1000.0, y in -1000.0f32..1000.0) {
+            let v = Vector2::new(x, y);
+            prop_assume!(v.length() > 0.001);
+            assert_near_eq!(1.0, v.normalize().length(), 0.0001);
+        }
+    }
 }
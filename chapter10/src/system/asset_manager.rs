@@ -1,42 +1,101 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-use anyhow::{Ok, Result};
+use anyhow::Result;
 
 use crate::{
     components::{
+        billboard_component::BillboardComponent,
         component::{Component, State},
         mesh_component::MeshComponent,
         sprite_component::SpriteComponent,
+        water_component::WaterComponent,
+    },
+    graphics::{
+        color::Color,
+        mesh::Mesh,
+        shader::Shader,
+        texture::{Texture, TextureOptions},
+        vertex_array::VertexArray,
     },
-    graphics::{mesh::Mesh, shader::Shader, texture::Texture, vertex_array::VertexArray},
-    math::{self, matrix4::Matrix4, vector3::Vector3},
+    math::{matrix4::Matrix4, vector3::Vector3},
 };
 
+use super::{crash_report::log, engine_error::EngineError};
+
 pub struct AssetManager {
     textures: HashMap<String, Rc<Texture>>,
+    // Sampler settings of textures that don't use the defaults, by file name
+    texture_options: HashMap<String, TextureOptions>,
     sprites: Vec<Rc<RefCell<dyn SpriteComponent>>>,
     pub sprite_verts: VertexArray,
     pub sprite_shader: Shader,
     meshes: HashMap<String, Rc<Mesh>>,
     pub mesh_shader: Shader,
+    // Unlit views of the meshes
+    pub debug_shader: Shader,
+    // Colored debug lines, e.g. gizmos
+    pub line_shader: Shader,
+    // None if the GPU can't compile it, then meshes are drawn one by one
+    pub instanced_mesh_shader: Option<Shader>,
     mesh_components: Vec<Rc<RefCell<MeshComponent>>>,
+    // None if the GPU can't compile it, then water isn't drawn
+    pub water_shader: Option<Shader>,
+    // Unit square facing +z, scaled by each water component and billboard
+    pub quad_verts: VertexArray,
+    water_components: Vec<Rc<RefCell<WaterComponent>>>,
+    // Textured quads facing the camera
+    pub billboard_shader: Shader,
+    billboards: Vec<Rc<RefCell<BillboardComponent>>>,
+    // Drawn in place of meshes that failed to load
+    placeholder_mesh: Option<Rc<Mesh>>,
+    // Assets already reported as missing, so they are reported once
+    failed_assets: HashSet<String>,
 }
 
 impl AssetManager {
     pub fn new() -> Rc<RefCell<Self>> {
         let this = Self {
             textures: HashMap::new(),
+            texture_options: Self::create_texture_options(),
             sprites: vec![],
             sprite_verts: Self::create_sprite_verts(),
             sprite_shader: Shader::new(),
             meshes: HashMap::new(),
             mesh_shader: Shader::new(),
+            debug_shader: Shader::new(),
+            line_shader: Shader::new(),
+            instanced_mesh_shader: None,
             mesh_components: vec![],
+            water_shader: None,
+            quad_verts: Self::create_quad_verts(),
+            water_components: vec![],
+            billboard_shader: Shader::new(),
+            billboards: vec![],
+            placeholder_mesh: None,
+            failed_assets: HashSet::new(),
         };
 
         Rc::new(RefCell::new(this))
     }
 
+    fn create_texture_options() -> HashMap<String, TextureOptions> {
+        let mut options = HashMap::new();
+        options.insert("Plane.png".to_string(), TextureOptions::floor());
+        for file_name in [
+            "Crosshair.png",
+            "CrosshairRed.png",
+            "Blip.png",
+            "RadarArrow.png",
+        ] {
+            options.insert(file_name.to_string(), TextureOptions::pixel_art());
+        }
+        options
+    }
+
     fn create_sprite_verts() -> VertexArray {
         let vertices = [
             -0.5, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, // top left
@@ -53,11 +112,20 @@ impl AssetManager {
         VertexArray::new(&vertices, 4, &index_buffer, 6)
     }
 
-    pub fn load_shaders(
-        &mut self,
-        screen_width: f32,
-        screen_height: f32,
-    ) -> Result<(Matrix4, Matrix4)> {
+    fn create_quad_verts() -> VertexArray {
+        let vertices = [
+            -0.5, 0.5, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, // top left
+            0.5, 0.5, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, // top right
+            0.5, -0.5, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, // bottom right
+            -0.5, -0.5, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, // bottom left
+        ];
+
+        let index_buffer = [0, 1, 2, 2, 3, 0];
+
+        VertexArray::new(&vertices, 4, &index_buffer, 6)
+    }
+
+    pub fn load_shaders(&mut self, screen_width: f32, screen_height: f32) -> Result<Matrix4> {
         // Create sprite shader
         self.sprite_shader.load("Sprite.vert", "Sprite.frag")?;
         self.sprite_shader.set_active();
@@ -65,51 +133,96 @@ impl AssetManager {
         let view_proj = Matrix4::create_simple_view_proj(screen_width, screen_height);
         self.sprite_shader
             .set_matrix_uniform("uViewProj", view_proj);
+        // Only the tinted or fading sprites change it, and set it back
+        self.sprite_shader.set_color_uniform("uTint", &Color::WHITE);
 
-        // Create basic mesh shader
+        // Create basic mesh shader, the renderer sets its view-projection every frame
         self.mesh_shader.load("Phong.vert", "Phong.frag")?;
-        self.mesh_shader.set_active();
 
         let view = Matrix4::create_look_at(&Vector3::ZERO, &Vector3::UNIT_X, &Vector3::UNIT_Z);
-        let projection = Matrix4::create_perspective_fov(
-            math::basic::to_radians(70.0),
-            screen_width,
-            screen_height,
-            25.0,
-            10000.0,
-        );
-        self.mesh_shader
-            .set_matrix_uniform("uViewProj", view.clone() * projection.clone());
 
-        Ok((view, projection))
+        // Same vertex outputs as the mesh shader
+        self.debug_shader.load("Phong.vert", "Debug.frag")?;
+
+        self.line_shader.load("DebugLine.vert", "DebugLine.frag")?;
+
+        // Same as a sprite, in world space and fading out
+        self.billboard_shader
+            .load("Sprite.vert", "Billboard.frag")?;
+
+        // Same lighting, world transforms per instance
+        let mut instanced_mesh_shader = Shader::new();
+        if let Err(e) = instanced_mesh_shader.load("PhongInstanced.vert", "Phong.frag") {
+            log!("Instanced rendering is disabled: {}", e);
+        } else {
+            self.instanced_mesh_shader = Some(instanced_mesh_shader);
+        }
+
+        let mut water_shader = Shader::new();
+        if let Err(e) = water_shader.load("Water.vert", "Water.frag") {
+            log!("Water is disabled: {}", e);
+        } else {
+            self.water_shader = Some(water_shader);
+        }
+
+        Ok(view)
     }
 
+    /// Texture, or the placeholder if it fails to load
     pub fn get_texture(&mut self, file_name: &str) -> Rc<Texture> {
-        if let Some(texture) = self.textures.get(&file_name.to_string()) {
-            return texture.clone();
+        match self.try_get_texture(file_name) {
+            Ok(texture) => texture,
+            Err(e) => {
+                self.report_failure(file_name, &e);
+                self.get_default_texture()
+            }
         }
+    }
 
-        let mut texture = Texture::new();
-        if texture.load(file_name).is_ok() {
-            let result = Rc::new(texture);
-            self.textures.insert(file_name.to_string(), result.clone());
-            return result;
+    pub fn try_get_texture(&mut self, file_name: &str) -> Result<Rc<Texture>, EngineError> {
+        if let Some(texture) = self.textures.get(file_name) {
+            return Ok(texture.clone());
         }
 
-        self.get_default_texture()
+        let options = self
+            .texture_options
+            .get(file_name)
+            .cloned()
+            .unwrap_or_else(TextureOptions::new);
+        let mut texture = Texture::new();
+        texture.load_with_options(file_name, &options)?;
+        let result = Rc::new(texture);
+        self.textures.insert(file_name.to_string(), result.clone());
+        Ok(result)
     }
 
+    /// Options used the next time the texture is loaded, set before it is first requested
+    pub fn set_texture_options(&mut self, file_name: &str, options: TextureOptions) {
+        self.texture_options.insert(file_name.to_string(), options);
+    }
+
+    /// Default.png, or a checkerboard made in code if even that is missing
     pub fn get_default_texture(&mut self) -> Rc<Texture> {
         let file_name = "Default.png";
-        if let Some(texture) = self.textures.get(&file_name.to_string()) {
+        if let Some(texture) = self.textures.get(file_name) {
             return texture.clone();
         }
 
         let mut texture = Texture::new();
-        texture.load(file_name).unwrap();
+        if let Err(e) = texture.load(file_name) {
+            self.report_failure(file_name, &e);
+            texture = Texture::create_placeholder();
+        }
         let result = Rc::new(texture);
         self.textures.insert(file_name.to_string(), result.clone());
-        return result;
+        result
+    }
+
+    /// Print the error the first time the asset fails, not every time it is requested
+    fn report_failure(&mut self, file_name: &str, error: &EngineError) {
+        if self.failed_assets.insert(file_name.to_string()) {
+            log!("{}, using a placeholder", error);
+        }
     }
 
     pub fn get_sprites(&self) -> &Vec<Rc<RefCell<dyn SpriteComponent>>> {
@@ -137,21 +250,43 @@ impl AssetManager {
     pub fn flush_meshes(&mut self) {
         self.mesh_components
             .retain(|mesh_component| *mesh_component.borrow().get_state() == State::Active);
+        self.water_components
+            .retain(|water_component| *water_component.borrow().get_state() == State::Active);
+        self.billboards
+            .retain(|billboard| *billboard.borrow().get_state() == State::Active);
     }
 
+    /// Mesh, or a placeholder cube if it fails to load
     pub fn get_mesh(&mut self, file_name: &str) -> Rc<Mesh> {
-        if let Some(mesh) = self.meshes.get(&file_name.to_string()) {
-            return mesh.clone();
+        match self.try_get_mesh(file_name) {
+            Ok(mesh) => mesh,
+            Err(e) => {
+                self.report_failure(file_name, &e);
+                self.get_placeholder_mesh()
+            }
+        }
+    }
+
+    pub fn try_get_mesh(&mut self, file_name: &str) -> Result<Rc<Mesh>, EngineError> {
+        if let Some(mesh) = self.meshes.get(file_name) {
+            return Ok(mesh.clone());
         }
 
         let mut mesh = Mesh::new();
-        if mesh.load(file_name, self).is_ok() {
-            let result = Rc::new(mesh);
-            self.meshes.insert(file_name.to_string(), result.clone());
-            return result;
+        mesh.load(file_name, self)?;
+        let result = Rc::new(mesh);
+        self.meshes.insert(file_name.to_string(), result.clone());
+        Ok(result)
+    }
+
+    fn get_placeholder_mesh(&mut self) -> Rc<Mesh> {
+        if let Some(mesh) = &self.placeholder_mesh {
+            return mesh.clone();
         }
 
-        panic!()
+        let result = Rc::new(Mesh::create_placeholder(self));
+        self.placeholder_mesh = Some(result.clone());
+        result
     }
 
     pub fn add_mesh_component(&mut self, mesh: Rc<RefCell<MeshComponent>>) {
@@ -161,4 +296,20 @@ impl AssetManager {
     pub fn get_mesh_components(&self) -> &Vec<Rc<RefCell<MeshComponent>>> {
         &self.mesh_components
     }
+
+    pub fn add_water_component(&mut self, water: Rc<RefCell<WaterComponent>>) {
+        self.water_components.push(water);
+    }
+
+    pub fn get_water_components(&self) -> &Vec<Rc<RefCell<WaterComponent>>> {
+        &self.water_components
+    }
+
+    pub fn add_billboard(&mut self, billboard: Rc<RefCell<BillboardComponent>>) {
+        self.billboards.push(billboard);
+    }
+
+    pub fn get_billboards(&self) -> &Vec<Rc<RefCell<BillboardComponent>>> {
+        &self.billboards
+    }
 }
@@ -6,16 +6,21 @@ use std::{
     sync::atomic::{AtomicU32, Ordering},
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use libfmod::{
-    ffi::{FMOD_INIT_NORMAL, FMOD_STUDIO_INIT_NORMAL},
-    Attributes3d, Bank, Bus, EventDescription, EventInstance, LoadBank, PlaybackState, Studio,
-    System, Vector,
+    ffi::{FMOD_INIT_NORMAL, FMOD_STUDIO_EVENT_CALLBACK_ALL, FMOD_STUDIO_INIT_NORMAL},
+    Attributes3d, Bank, Bus, EventDescription, EventInstance, LoadBank, PlaybackState, StopMode,
+    Studio, System, Vector,
 };
 
 use crate::math::{matrix4::Matrix4, vector3::Vector3};
 
-use super::{asset_manager::AssetManager, sound_event::SoundEvent};
+use super::{
+    asset_manager::AssetManager,
+    config::Config,
+    crash_report::log,
+    sound_event::{self, Fade, OnFadeComplete, PlayingEvent, SoundEvent},
+};
 
 static ID: AtomicU32 = AtomicU32::new(0);
 
@@ -27,37 +32,97 @@ fn generate_id() -> u32 {
 
 pub struct AudioSystem {
     asset_manager: Rc<RefCell<AssetManager>>,
-    system: Studio,
-    low_level_system: System,
+    // None in null-audio mode, when FMOD or the banks failed to load
+    system: Option<Studio>,
+    low_level_system: Option<System>,
     banks: HashMap<String, Bank>,
     events: HashMap<String, EventDescription>,
-    event_instances: HashMap<u32, Rc<RefCell<EventInstance>>>,
+    // Shared with the SoundEvent handles, which look their instance up by id
+    event_instances: Rc<RefCell<HashMap<u32, PlayingEvent>>>,
+    // Stopped instances of each event, started again instead of creating new ones
+    pool: HashMap<String, Vec<EventInstance>>,
+    // Most instances of an event playing at once, events without one are unlimited
+    polyphony_limits: HashMap<String, usize>,
     buses: HashMap<String, Bus>,
+    // Kept without the bus too, so the callbacks still run in null-audio mode
+    bus_fades: HashMap<String, Fade>,
+    // Kept in null-audio mode too, reverb zones check it
+    listener_position: Vector3,
 }
 
 impl AudioSystem {
+    // Events played fast enough to pile up
+    const DEFAULT_POLYPHONY_LIMITS: [(&'static str, usize); 3] = [
+        ("event:/Footstep", 4),
+        ("event:/Shot", 6),
+        ("event:/Explosion2D", 4),
+    ];
+    // Stopped instances kept per event
+    const POOL_SIZE: usize = 8;
+
     pub fn initialize(
         asset_manager: Rc<RefCell<AssetManager>>,
-    ) -> Result<Rc<RefCell<AudioSystem>>> {
-        let system = Studio::create()?;
-        system.initialize(512, FMOD_STUDIO_INIT_NORMAL, FMOD_INIT_NORMAL, None)?;
-
-        let low_level_system = system.get_core_system()?;
-
+        config: &Config,
+    ) -> Rc<RefCell<AudioSystem>> {
         let mut this = Self {
             asset_manager,
-            system,
-            low_level_system,
+            system: None,
+            low_level_system: None,
             banks: HashMap::new(),
             events: HashMap::new(),
-            event_instances: HashMap::new(),
+            event_instances: Rc::new(RefCell::new(HashMap::new())),
+            pool: HashMap::new(),
+            polyphony_limits: AudioSystem::DEFAULT_POLYPHONY_LIMITS
+                .iter()
+                .map(|(name, limit)| (name.to_string(), *limit))
+                .collect(),
             buses: HashMap::new(),
+            bus_fades: HashMap::new(),
+            listener_position: Vector3::ZERO,
         };
 
-        this.load_bank("Master Bank.strings.bank")?;
-        this.load_bank("Master Bank.bank")?;
+        // The game still runs without sound, e.g. without the FMOD banks
+        if let Err(e) = this.start_fmod() {
+            log!("Audio is disabled: {}", e);
+            this.release();
+        }
+
+        this.set_bus_volume("bus:/", config.master_volume);
+
+        Rc::new(RefCell::new(this))
+    }
+
+    fn start_fmod(&mut self) -> Result<()> {
+        let system = Studio::create()?;
+        self.system = Some(system);
+        system.initialize(512, FMOD_STUDIO_INIT_NORMAL, FMOD_INIT_NORMAL, None)?;
+
+        self.low_level_system = Some(system.get_core_system()?);
 
-        Ok(Rc::new(RefCell::new(this)))
+        self.load_bank("Master Bank.strings.bank")?;
+        self.load_bank("Master Bank.bank")?;
+
+        Ok(())
+    }
+
+    /// Back to null audio, where every event is inert
+    fn release(&mut self) {
+        self.event_instances.borrow_mut().clear();
+        self.pool.clear();
+        self.events.clear();
+        self.buses.clear();
+        self.banks.clear();
+        if let Some(system) = self.system.take() {
+            let _ = system.release();
+        }
+        if let Some(low_level_system) = self.low_level_system.take() {
+            let _ = low_level_system.release();
+        }
+    }
+
+    /// False in null-audio mode
+    pub fn is_enabled(&self) -> bool {
+        self.system.is_some()
     }
 
     pub fn load_bank(&mut self, name: &str) -> Result<()> {
@@ -73,54 +138,177 @@ impl AudioSystem {
             .join(name);
         let file_name = path.to_str().unwrap();
 
-        let bank = self.system.load_bank_file(file_name, LoadBank::NORMAL)?;
+        let system = self
+            .system
+            .ok_or_else(|| anyhow!("FMOD is not initialized"))?;
+        let bank = system.load_bank_file(file_name, LoadBank::NORMAL)?;
         self.banks.insert(name.to_string(), bank);
         bank.load_sample_data()?;
 
         let num_events = bank.get_event_count()?;
-        if num_events <= 0 {
-            return Ok(());
+        if num_events > 0 {
+            let events = bank.get_event_list(num_events)?;
+            for event in events {
+                let event_name = event.get_path()?;
+                self.events.insert(event_name, event);
+            }
         }
 
-        let events = bank.get_event_list(num_events)?;
-        for event in events {
-            let event_name = event.get_path()?;
-            self.events.insert(event_name, event);
+        let num_buses = bank.get_bus_count()?;
+        if num_buses > 0 {
+            let buses = bank.get_bus_list(num_buses)?;
+            for bus in buses {
+                let bus_name = bus.get_path()?;
+                self.buses.insert(bus_name, bus);
+            }
         }
 
         Ok(())
     }
 
+    pub fn has_event(&self, name: &str) -> bool {
+        self.events.contains_key(name)
+    }
+
+    /// Over the polyphony limit of the event, the quietest of its instances stops to play it
     pub fn play_event(&mut self, name: &str) -> SoundEvent {
-        let event_description = self.events.get(name).unwrap();
-        let event_instance = event_description.create_instance().unwrap();
+        let event_description = match self.events.get(name) {
+            Some(event_description) => *event_description,
+            None => {
+                // Null audio has no events at all
+                if self.is_enabled() {
+                    log!("Unknown sound event {}", name);
+                }
+                return SoundEvent::inert();
+            }
+        };
+        let event_instance = match self
+            .steal_instance(name)
+            .or_else(|| self.pool.get_mut(name).and_then(|pool| pool.pop()))
+        {
+            Some(event_instance) => event_instance,
+            None => event_description.create_instance().unwrap(),
+        };
         event_instance.start().unwrap();
         let id = generate_id();
-        let result = Rc::new(RefCell::new(event_instance));
-        self.event_instances.insert(id, result.clone());
-        SoundEvent::new(id, result)
+        self.event_instances
+            .borrow_mut()
+            .insert(id, PlayingEvent::new(name, event_instance));
+        SoundEvent::new(id, Rc::downgrade(&self.event_instances))
+    }
+
+    /// At most limit instances of the event play at once, at least one
+    pub fn set_polyphony_limit(&mut self, name: &str, limit: usize) {
+        self.polyphony_limits.insert(name.to_string(), limit.max(1));
+    }
+
+    pub fn remove_polyphony_limit(&mut self, name: &str) {
+        self.polyphony_limits.remove(name);
     }
 
-    pub fn update(&mut self, _delta_time: f32) {
+    /// Stop an instance of the event when it reached its limit, and take it to play again.
+    /// Its handle turns invalid, and its fade ends without calling back
+    fn steal_instance(&mut self, name: &str) -> Option<EventInstance> {
+        let limit = *self.polyphony_limits.get(name)?;
+        let mut event_instances = self.event_instances.borrow_mut();
+        let playing: Vec<(u32, f32)> = event_instances
+            .iter()
+            .filter(|(_, playing_event)| playing_event.name == name)
+            .map(|(id, playing_event)| {
+                let volume = playing_event
+                    .instance
+                    .get_volume()
+                    .map_or(0.0, |volume| volume.1);
+                (*id, volume)
+            })
+            .collect();
+        if playing.len() < limit {
+            return None;
+        }
+
+        let victim = event_instances.remove(&choose_victim(&playing)?)?;
+        let _ = victim.instance.stop(StopMode::Immediate);
+        sound_event::discard_timeline_events(&victim.instance);
+        reset_instance(&victim.instance);
+        Some(victim.instance)
+    }
+
+    /// Keep the stopped instance to play the event again, or release it if the pool is full.
+    /// Parameters keep the values of the last play
+    fn recycle(&mut self, name: String, instance: EventInstance) {
+        let pool = self.pool.entry(name).or_default();
+        if pool.len() >= AudioSystem::POOL_SIZE {
+            let _ = instance.release();
+            return;
+        }
+        reset_instance(&instance);
+        pool.push(instance);
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let mut completed = vec![];
         let mut done = vec![];
-        for (id, instance) in self.event_instances.clone() {
-            let state = instance.borrow().get_playback_state().unwrap();
+        for (id, playing_event) in self.event_instances.borrow_mut().iter_mut() {
+            let instance = playing_event.instance;
+            let state = instance.get_playback_state().unwrap();
             if state == PlaybackState::Stopped {
-                instance.borrow_mut().release().unwrap();
-                done.push(id);
+                sound_event::discard_timeline_events(&instance);
+                done.push(*id);
+                // Stopping early still ends the fade, so chains go on
+                completed.extend(playing_event.fade.take().and_then(Fade::into_on_complete));
+            } else if let Some(fade) = playing_event.fade.as_mut() {
+                let _ = instance.set_volume(fade.step(delta_time));
+                if fade.is_done() {
+                    completed.extend(playing_event.fade.take().and_then(Fade::into_on_complete));
+                }
             }
         }
 
+        // Handles to the stopped instances turn invalid
         for id in done {
-            self.event_instances.remove(&id);
+            let playing_event = self.event_instances.borrow_mut().remove(&id);
+            if let Some(playing_event) = playing_event {
+                self.recycle(playing_event.name, playing_event.instance);
+            }
         }
 
-        self.system.update().unwrap();
+        for (name, fade) in self.bus_fades.iter_mut() {
+            let volume = fade.step(delta_time);
+            if let Some(bus) = self.buses.get(name) {
+                let _ = bus.set_volume(volume);
+            }
+        }
+        let finished: Vec<String> = self
+            .bus_fades
+            .iter()
+            .filter(|(_, fade)| fade.is_done())
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in finished {
+            if let Some(fade) = self.bus_fades.remove(&name) {
+                completed.extend(fade.into_on_complete());
+            }
+        }
+
+        // Called once nothing is borrowed, so they can start other fades
+        for on_complete in completed {
+            on_complete(self);
+        }
+
+        if let Some(system) = self.system {
+            system.update().unwrap();
+        }
     }
 
     pub fn set_listener(&mut self, view_matrix: &Matrix4) {
         let mut inverted_view = view_matrix.clone();
         inverted_view.invert();
+        self.listener_position = inverted_view.get_translation();
+
+        let system = match self.system {
+            Some(system) => system,
+            None => return,
+        };
 
         let attributes = Attributes3d {
             position: AudioSystem::vector_to_fmod(&inverted_view.get_translation()),
@@ -129,9 +317,11 @@ impl AudioSystem {
             velocity: AudioSystem::vector_to_fmod(&Vector3::ZERO),
         };
 
-        self.system
-            .set_listener_attributes(0, attributes, None)
-            .unwrap();
+        system.set_listener_attributes(0, attributes, None).unwrap();
+    }
+
+    pub fn get_listener_position(&self) -> &Vector3 {
+        &self.listener_position
     }
 
     pub fn vector_to_fmod(in_vector: &Vector3) -> Vector {
@@ -153,7 +343,9 @@ impl AudioSystem {
             .is_some_and(|paused| paused)
     }
 
+    /// Cancels the fade of the bus in progress, without calling it back
     pub fn set_bus_volume(&mut self, name: &str, volume: f32) {
+        self.bus_fades.remove(name);
         self.buses
             .get_mut(name)
             .and_then(|bus| bus.set_volume(volume).ok());
@@ -164,11 +356,74 @@ impl AudioSystem {
             .get_mut(name)
             .and_then(|bus| bus.set_paused(pause).ok());
     }
+
+    /// Move the volume of the bus from where it is to the target over the seconds,
+    /// replacing the fade in progress without calling it back
+    pub fn fade_bus_volume(&mut self, name: &str, target: f32, seconds: f32) {
+        self.start_bus_fade(name, target, seconds, None);
+    }
+
+    /// fade_bus_volume, then on_complete once the target is reached.
+    /// Called even without the bus, e.g. in null-audio mode
+    pub fn fade_bus_volume_then(
+        &mut self,
+        name: &str,
+        target: f32,
+        seconds: f32,
+        on_complete: OnFadeComplete,
+    ) {
+        self.start_bus_fade(name, target, seconds, Some(on_complete));
+    }
+
+    fn start_bus_fade(
+        &mut self,
+        name: &str,
+        target: f32,
+        seconds: f32,
+        on_complete: Option<OnFadeComplete>,
+    ) {
+        let volume = self.get_bus_volume(name);
+        self.bus_fades.insert(
+            name.to_string(),
+            Fade::new(volume, target, seconds, on_complete),
+        );
+    }
+}
+
+/// Undo what the handles changed on the last play, except the parameters
+fn reset_instance(instance: &EventInstance) {
+    let _ = instance.set_volume(1.0);
+    let _ = instance.set_pitch(1.0);
+    let _ = instance.set_paused(false);
+    let _ = instance.set_callback(None, FMOD_STUDIO_EVENT_CALLBACK_ALL);
+}
+
+/// Id of the quietest of the instances, the oldest (lowest id) among the equally quiet
+fn choose_victim(instances: &[(u32, f32)]) -> Option<u32> {
+    instances
+        .iter()
+        .min_by(|(a_id, a_volume), (b_id, b_volume)| {
+            a_volume.total_cmp(b_volume).then(a_id.cmp(b_id))
+        })
+        .map(|(id, _)| *id)
 }
 
 impl Drop for AudioSystem {
     fn drop(&mut self) {
-        let _ = self.system.release();
-        let _ = self.low_level_system.release();
+        self.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::choose_victim;
+
+    #[test]
+    fn test_choose_victim() {
+        assert_eq!(None, choose_victim(&[]));
+        assert_eq!(Some(5), choose_victim(&[(3, 1.0), (5, 0.2), (8, 0.6)]));
+
+        // The oldest of the equally quiet
+        assert_eq!(Some(3), choose_victim(&[(8, 1.0), (3, 1.0), (5, 1.0)]));
     }
 }
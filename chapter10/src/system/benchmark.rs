@@ -0,0 +1,257 @@
+use std::{
+    cell::RefCell,
+    f32,
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+use crate::{
+    actors::{
+        actor::{Actor, DefaultActor},
+        ball_actor::BallActor,
+    },
+    components::{
+        billboard_component::{BillboardComponent, BillboardMode},
+        mesh_component::MeshComponent,
+        move_component::{DefaultMoveComponent, MoveComponent},
+    },
+    math::{quaternion::Quaternion, random::Random, vector3::Vector3},
+};
+
+use super::{crash_report::log, engine_context::EngineContext};
+
+/// Settings of a benchmark run, from the command line:
+/// --bench [--actors N] [--projectiles M] [--frames F] [--headless]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchConfig {
+    // Moving actors, half with a mesh and half with a billboard
+    pub actors: usize,
+    // Balls kept flying at any time
+    pub projectiles: usize,
+    pub frames: u32,
+    // Skips drawing, measuring the update alone
+    pub headless: bool,
+}
+
+impl BenchConfig {
+    /// None when the game is not run with --bench
+    pub fn parse(args: &[String]) -> Option<BenchConfig> {
+        if !args.iter().any(|arg| arg == "--bench") {
+            return None;
+        }
+
+        let mut config = BenchConfig {
+            actors: 2000,
+            projectiles: 200,
+            frames: 600,
+            headless: false,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--actors" => parse_value(args.next(), arg, &mut config.actors),
+                "--projectiles" => parse_value(args.next(), arg, &mut config.projectiles),
+                "--frames" => parse_value(args.next(), arg, &mut config.frames),
+                "--headless" => config.headless = true,
+                _ => {}
+            }
+        }
+        Some(config)
+    }
+}
+
+/// Keep the default if the value is missing or not a number
+pub fn parse_value<T: std::str::FromStr>(value: Option<&String>, name: &str, target: &mut T) {
+    match value.map(|value| value.parse::<T>()) {
+        Some(Ok(value)) => *target = value,
+        _ => log!("Ignored {} without a valid number", name),
+    }
+}
+
+/// Durations of the measured frames
+pub struct FrameTimes {
+    times: Vec<Duration>,
+}
+
+impl FrameTimes {
+    pub fn new() -> Self {
+        Self { times: vec![] }
+    }
+
+    pub fn push(&mut self, time: Duration) {
+        self.times.push(time);
+    }
+
+    /// Frame time p percent of the frames were at most, in milliseconds
+    pub fn percentile(&self, p: f32) -> f32 {
+        if self.times.is_empty() {
+            return 0.0;
+        }
+        let mut sorted = self.times.clone();
+        sorted.sort();
+        // Nearest rank
+        let rank = ((p / 100.0) * sorted.len() as f32).ceil() as usize;
+        let index = rank.clamp(1, sorted.len()) - 1;
+        sorted[index].as_secs_f32() * 1000.0
+    }
+
+    pub fn average(&self) -> f32 {
+        if self.times.is_empty() {
+            return 0.0;
+        }
+        let total: Duration = self.times.iter().sum();
+        total.as_secs_f32() * 1000.0 / self.times.len() as f32
+    }
+
+    pub fn report(&self) {
+        log!(
+            "Frame time over {} frames: avg {:.2} ms, p50 {:.2} ms, p90 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+            self.times.len(),
+            self.average(),
+            self.percentile(50.0),
+            self.percentile(90.0),
+            self.percentile(99.0),
+            self.percentile(100.0),
+        );
+    }
+}
+
+/// Actors of a benchmark run, spawned around the arena
+pub struct Benchmark {
+    config: BenchConfig,
+    random: Random,
+    projectiles: Vec<Weak<RefCell<dyn Actor>>>,
+}
+
+impl Benchmark {
+    // Half extents of the area the actors are spawned in
+    const AREA_SIZE: f32 = 1400.0;
+    const MIN_HEIGHT: f32 = 50.0;
+    const MAX_HEIGHT: f32 = 500.0;
+
+    pub fn new(config: BenchConfig) -> Self {
+        Self {
+            config,
+            random: Random::new(),
+            projectiles: vec![],
+        }
+    }
+
+    pub fn get_config(&self) -> &BenchConfig {
+        &self.config
+    }
+
+    /// Actors circling around at random speeds, alternating meshes and billboards
+    pub fn spawn_actors(&mut self, context: &EngineContext) {
+        let mesh = context.asset_manager.borrow_mut().get_mesh("Sphere.gpmesh");
+        let texture = context.asset_manager.borrow_mut().get_texture("Smoke.png");
+
+        for i in 0..self.config.actors {
+            let actor = DefaultActor::new(context);
+            actor.borrow_mut().set_position(self.random_position());
+            actor.borrow_mut().set_rotation(self.random_rotation());
+            actor.borrow_mut().set_scale(0.5);
+
+            if i % 2 == 0 {
+                MeshComponent::new(actor.clone())
+                    .borrow_mut()
+                    .set_mesh(mesh.clone());
+            } else {
+                BillboardComponent::new(actor.clone(), texture.clone(), BillboardMode::Spherical);
+            }
+
+            let move_component = DefaultMoveComponent::new(actor);
+            let mut move_component = move_component.borrow_mut();
+            move_component.set_forward_speed(self.random.get_float_range(50.0, 300.0));
+            move_component.set_angular_speed(self.random.get_float_range(-1.0, 1.0));
+        }
+    }
+
+    /// Replace the projectiles which died since the last frame
+    pub fn update_projectiles(&mut self, context: &EngineContext, player_id: u32) {
+        self.projectiles
+            .retain(|projectile| projectile.upgrade().is_some());
+        while self.projectiles.len() < self.config.projectiles {
+            let projectile = BallActor::spawn(context, player_id);
+            projectile.borrow_mut().set_position(self.random_position());
+            projectile.borrow_mut().set_rotation(self.random_rotation());
+            self.projectiles.push(Rc::downgrade(&projectile));
+        }
+    }
+
+    fn random_position(&mut self) -> Vector3 {
+        self.random.get_vector3(
+            Vector3::new(
+                -Benchmark::AREA_SIZE,
+                -Benchmark::AREA_SIZE,
+                Benchmark::MIN_HEIGHT,
+            ),
+            Vector3::new(
+                Benchmark::AREA_SIZE,
+                Benchmark::AREA_SIZE,
+                Benchmark::MAX_HEIGHT,
+            ),
+        )
+    }
+
+    fn random_rotation(&mut self) -> Quaternion {
+        let angle = self
+            .random
+            .get_float_range(-f32::consts::PI, f32::consts::PI);
+        Quaternion::from_axis_angle(&Vector3::UNIT_Z, angle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::assert_near_eq;
+
+    use super::{BenchConfig, FrameTimes};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(None, BenchConfig::parse(&args("game")));
+
+        let config = BenchConfig::parse(&args("game --bench")).unwrap();
+        assert_eq!(2000, config.actors);
+        assert!(!config.headless);
+
+        let config = BenchConfig::parse(&args(
+            "game --bench --actors 5000 --projectiles 50 --frames 100 --headless",
+        ))
+        .unwrap();
+        assert_eq!(
+            BenchConfig {
+                actors: 5000,
+                projectiles: 50,
+                frames: 100,
+                headless: true,
+            },
+            config
+        );
+
+        // Invalid values keep the defaults
+        let config = BenchConfig::parse(&args("game --bench --frames many")).unwrap();
+        assert_eq!(600, config.frames);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let mut frame_times = FrameTimes::new();
+        assert_eq!(0.0, frame_times.percentile(50.0));
+
+        for ms in (1..=100).rev() {
+            frame_times.push(Duration::from_millis(ms));
+        }
+        assert_near_eq!(50.0, frame_times.percentile(50.0), 0.001);
+        assert_near_eq!(99.0, frame_times.percentile(99.0), 0.001);
+        assert_near_eq!(100.0, frame_times.percentile(100.0), 0.001);
+        assert_near_eq!(50.5, frame_times.average(), 0.001);
+    }
+}
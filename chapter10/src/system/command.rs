@@ -0,0 +1,450 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::actor::{Actor, State},
+    graphics::color::Color,
+    math::{quaternion::Quaternion, vector3::Vector3},
+};
+
+use super::{engine_context::EngineContext, entity_manager::EntityManager, renderer::Renderer};
+
+/// Old actor and the actor spawned again in its place
+pub type Replaced = (Rc<RefCell<dyn Actor>>, Rc<RefCell<dyn Actor>>);
+
+/// Undoable editor operation
+pub trait Command {
+    fn execute(&mut self);
+
+    fn undo(&mut self);
+
+    fn get_name(&self) -> String;
+
+    /// Actor spawned again by the last execute or undo, replacing the old one
+    fn take_replaced(&mut self) -> Option<Replaced> {
+        None
+    }
+
+    /// Follow an actor that was spawned again by another command
+    fn replace_actor(&mut self, _old: &Rc<RefCell<dyn Actor>>, _new: &Rc<RefCell<dyn Actor>>) {}
+}
+
+/// Executed commands that can be undone, and undone commands that can be redone
+pub struct CommandHistory {
+    undo_stack: Vec<Box<dyn Command>>,
+    redo_stack: Vec<Box<dyn Command>>,
+}
+
+impl CommandHistory {
+    // Oldest commands are forgotten past this
+    const MAX_COMMANDS: usize = 100;
+
+    pub fn new() -> Self {
+        Self {
+            undo_stack: vec![],
+            redo_stack: vec![],
+        }
+    }
+
+    /// Execute the command and record it. Anything undone can't be redone anymore
+    pub fn execute(&mut self, mut command: Box<dyn Command>) {
+        command.execute();
+        self.follow_replaced(&mut command);
+
+        self.redo_stack.clear();
+        self.undo_stack.push(command);
+        if self.undo_stack.len() > CommandHistory::MAX_COMMANDS {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// Returns the name of the undone command
+    pub fn undo(&mut self) -> Option<String> {
+        let mut command = self.undo_stack.pop()?;
+        command.undo();
+        self.follow_replaced(&mut command);
+
+        let name = command.get_name();
+        self.redo_stack.push(command);
+        Some(name)
+    }
+
+    /// Returns the name of the redone command
+    pub fn redo(&mut self) -> Option<String> {
+        let mut command = self.redo_stack.pop()?;
+        command.execute();
+        self.follow_replaced(&mut command);
+
+        let name = command.get_name();
+        self.undo_stack.push(command);
+        Some(name)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    fn follow_replaced(&mut self, command: &mut Box<dyn Command>) {
+        let (old, new) = match command.take_replaced() {
+            Some(replaced) => replaced,
+            None => return,
+        };
+        for other in self.undo_stack.iter_mut().chain(self.redo_stack.iter_mut()) {
+            other.replace_actor(&old, &new);
+        }
+    }
+}
+
+/// Position, rotation and scale of an actor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    pub position: Vector3,
+    pub rotation: Quaternion,
+    pub scale: f32,
+}
+
+impl Transform {
+    pub fn of(actor: &dyn Actor) -> Self {
+        Self {
+            position: actor.get_position().clone(),
+            rotation: actor.get_rotation().clone(),
+            scale: actor.get_scale(),
+        }
+    }
+
+    pub fn apply(&self, actor: &mut dyn Actor) {
+        actor.set_position(self.position.clone());
+        actor.set_rotation(self.rotation.clone());
+        actor.set_scale(self.scale);
+        // Actors don't update while editing
+        actor.compute_world_transform();
+    }
+}
+
+/// Move, rotate or scale an actor
+pub struct TransformActor {
+    actor: Rc<RefCell<dyn Actor>>,
+    before: Transform,
+    after: Transform,
+}
+
+impl TransformActor {
+    pub fn new(actor: Rc<RefCell<dyn Actor>>, before: Transform, after: Transform) -> Self {
+        Self {
+            actor,
+            before,
+            after,
+        }
+    }
+}
+
+impl Command for TransformActor {
+    fn execute(&mut self) {
+        self.after.apply(&mut *self.actor.borrow_mut());
+    }
+
+    fn undo(&mut self) {
+        self.before.apply(&mut *self.actor.borrow_mut());
+    }
+
+    fn get_name(&self) -> String {
+        format!("transform {}", self.actor.borrow().get_type_name())
+    }
+
+    fn replace_actor(&mut self, old: &Rc<RefCell<dyn Actor>>, new: &Rc<RefCell<dyn Actor>>) {
+        if Rc::ptr_eq(&self.actor, old) {
+            self.actor = new.clone();
+        }
+    }
+}
+
+/// Directional light direction and ambient light
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lighting {
+    pub direction: Vector3,
+    pub ambient: Color,
+}
+
+impl Lighting {
+    pub fn of(renderer: &Renderer) -> Self {
+        Self {
+            direction: renderer.get_directional_light().direction.clone(),
+            ambient: *renderer.get_ambient_light(),
+        }
+    }
+
+    pub fn apply(&self, renderer: &mut Renderer) {
+        renderer.get_directional_light_mut().direction = self.direction.clone();
+        renderer.set_ambient_light(self.ambient);
+    }
+}
+
+pub struct ChangeLight {
+    renderer: Rc<RefCell<Renderer>>,
+    before: Lighting,
+    after: Lighting,
+}
+
+impl ChangeLight {
+    pub fn new(renderer: Rc<RefCell<Renderer>>, before: Lighting, after: Lighting) -> Self {
+        Self {
+            renderer,
+            before,
+            after,
+        }
+    }
+}
+
+impl Command for ChangeLight {
+    fn execute(&mut self) {
+        self.after.apply(&mut self.renderer.borrow_mut());
+    }
+
+    fn undo(&mut self) {
+        self.before.apply(&mut self.renderer.borrow_mut());
+    }
+
+    fn get_name(&self) -> String {
+        "change light".to_string()
+    }
+}
+
+/// Spawns and removes targets for the commands
+#[derive(Clone)]
+pub struct TargetSpawner {
+    pub context: EngineContext,
+}
+
+impl TargetSpawner {
+    /// Keeps the rotation and scale of a new target if there's no transform
+    fn spawn(&self, position: Vector3, transform: Option<&Transform>) -> Rc<RefCell<dyn Actor>> {
+        let target = EntityManager::spawn_target(&self.context, position);
+        match transform {
+            Some(transform) => transform.apply(&mut *target.borrow_mut()),
+            None => target.borrow_mut().compute_world_transform(),
+        }
+        target
+    }
+
+    fn remove(&self, actor: &Rc<RefCell<dyn Actor>>) {
+        actor.borrow_mut().set_state(State::Dead);
+        self.context.entity_manager.borrow_mut().flush_actors();
+    }
+}
+
+/// Spawn a target. Redoing spawns a new one
+pub struct SpawnTarget {
+    spawner: TargetSpawner,
+    position: Vector3,
+    // Set once undone, edits made after spawning are kept when redoing
+    transform: Option<Transform>,
+    actor: Option<Rc<RefCell<dyn Actor>>>,
+    replaced: Option<Replaced>,
+}
+
+impl SpawnTarget {
+    pub fn new(spawner: TargetSpawner, position: Vector3) -> Self {
+        Self {
+            spawner,
+            position,
+            transform: None,
+            actor: None,
+            replaced: None,
+        }
+    }
+}
+
+impl Command for SpawnTarget {
+    fn execute(&mut self) {
+        let actor = self
+            .spawner
+            .spawn(self.position.clone(), self.transform.as_ref());
+        if let Some(old) = self.actor.replace(actor.clone()) {
+            self.replaced = Some((old, actor));
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(actor) = &self.actor {
+            self.transform = Some(Transform::of(&*actor.borrow()));
+            self.spawner.remove(actor);
+        }
+    }
+
+    fn get_name(&self) -> String {
+        "spawn TargetActor".to_string()
+    }
+
+    fn take_replaced(&mut self) -> Option<Replaced> {
+        self.replaced.take()
+    }
+
+    fn replace_actor(&mut self, old: &Rc<RefCell<dyn Actor>>, new: &Rc<RefCell<dyn Actor>>) {
+        if self
+            .actor
+            .as_ref()
+            .is_some_and(|actor| Rc::ptr_eq(actor, old))
+        {
+            self.actor = Some(new.clone());
+        }
+    }
+}
+
+/// Delete a target. Undoing spawns a new one in its place
+pub struct DeleteTarget {
+    spawner: TargetSpawner,
+    actor: Rc<RefCell<dyn Actor>>,
+    transform: Transform,
+    replaced: Option<Replaced>,
+}
+
+impl DeleteTarget {
+    pub fn new(spawner: TargetSpawner, actor: Rc<RefCell<dyn Actor>>) -> Self {
+        let transform = Transform::of(&*actor.borrow());
+        Self {
+            spawner,
+            actor,
+            transform,
+            replaced: None,
+        }
+    }
+}
+
+impl Command for DeleteTarget {
+    fn execute(&mut self) {
+        self.transform = Transform::of(&*self.actor.borrow());
+        self.spawner.remove(&self.actor);
+    }
+
+    fn undo(&mut self) {
+        let actor = self
+            .spawner
+            .spawn(self.transform.position.clone(), Some(&self.transform));
+        let old = std::mem::replace(&mut self.actor, actor.clone());
+        self.replaced = Some((old, actor));
+    }
+
+    fn get_name(&self) -> String {
+        "delete TargetActor".to_string()
+    }
+
+    fn take_replaced(&mut self) -> Option<Replaced> {
+        self.replaced.take()
+    }
+
+    fn replace_actor(&mut self, old: &Rc<RefCell<dyn Actor>>, new: &Rc<RefCell<dyn Actor>>) {
+        if Rc::ptr_eq(&self.actor, old) {
+            self.actor = new.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        math::vector3::Vector3,
+    };
+
+    use super::{Command, CommandHistory, Transform, TransformActor};
+
+    struct AddCommand {
+        value: Rc<Cell<i32>>,
+        amount: i32,
+    }
+
+    impl Command for AddCommand {
+        fn execute(&mut self) {
+            self.value.set(self.value.get() + self.amount);
+        }
+
+        fn undo(&mut self) {
+            self.value.set(self.value.get() - self.amount);
+        }
+
+        fn get_name(&self) -> String {
+            format!("add {}", self.amount)
+        }
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let value = Rc::new(Cell::new(0));
+        let add = |amount| {
+            Box::new(AddCommand {
+                value: value.clone(),
+                amount,
+            })
+        };
+        let mut history = CommandHistory::new();
+        assert_eq!(None, history.undo());
+
+        history.execute(add(1));
+        history.execute(add(10));
+        assert_eq!(11, value.get());
+
+        assert_eq!(Some("add 10".to_string()), history.undo());
+        assert_eq!(1, value.get());
+        assert!(history.can_redo());
+
+        assert_eq!(Some("add 10".to_string()), history.redo());
+        assert_eq!(11, value.get());
+        assert_eq!(None, history.redo());
+
+        // A new command drops what was undone
+        history.undo();
+        history.execute(add(100));
+        assert!(!history.can_redo());
+        assert_eq!(101, value.get());
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let value = Rc::new(Cell::new(0));
+        let mut history = CommandHistory::new();
+        for _ in 0..CommandHistory::MAX_COMMANDS + 5 {
+            history.execute(Box::new(AddCommand {
+                value: value.clone(),
+                amount: 1,
+            }));
+        }
+
+        while history.undo().is_some() {}
+        assert_eq!(5, value.get());
+    }
+
+    #[test]
+    fn test_transform_actor() {
+        let actor: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let before = Transform::of(&*actor.borrow());
+        let mut after = before.clone();
+        after.position = Vector3::new(10.0, 20.0, 30.0);
+        after.scale = 2.0;
+
+        let mut command = TransformActor::new(actor.clone(), before.clone(), after.clone());
+        command.execute();
+        assert_eq!(after, Transform::of(&*actor.borrow()));
+        command.undo();
+        assert_eq!(before, Transform::of(&*actor.borrow()));
+
+        // Follows an actor spawned again
+        let other: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        command.replace_actor(&actor, &other);
+        command.execute();
+        assert_eq!(after, Transform::of(&*other.borrow()));
+        assert_eq!(before, Transform::of(&*actor.borrow()));
+    }
+}
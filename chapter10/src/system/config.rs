@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use anyhow::Result;
+use sdl2::keyboard::Scancode;
+use serde_json::{json, Value};
+
+use super::crash_report::log;
+
+/// Keys used to move the player
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBindings {
+    pub forward: Scancode,
+    pub back: Scancode,
+    pub left: Scancode,
+    pub right: Scancode,
+}
+
+impl KeyBindings {
+    pub fn new() -> Self {
+        Self {
+            forward: Scancode::W,
+            back: Scancode::S,
+            left: Scancode::A,
+            right: Scancode::D,
+        }
+    }
+}
+
+/// Game settings saved as JSON between runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub window_width: u32,
+    pub window_height: u32,
+    pub fullscreen: bool,
+    // Volume of the master bus in [0.0, 1.0]
+    pub master_volume: f32,
+    // Multiplier of the mouse look speed
+    pub mouse_sensitivity: f32,
+    // Field of view of the 3D views in degrees, without aiming down sights
+    pub fov: f32,
+    pub key_bindings: KeyBindings,
+    // Name of the string table in resources/Strings
+    pub language: String,
+}
+
+impl Config {
+    pub const FILE_NAME: &'static str = "config.json";
+
+    pub fn new() -> Self {
+        Self {
+            window_width: 1024,
+            window_height: 768,
+            fullscreen: false,
+            master_volume: 1.0,
+            mouse_sensitivity: 1.0,
+            fov: 70.0,
+            key_bindings: KeyBindings::new(),
+            language: "en".to_string(),
+        }
+    }
+
+    /// Load settings from the file, falling back to defaults if it's missing or broken
+    pub fn load(path: &Path) -> Self {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Config::new(),
+        };
+
+        match serde_json::from_str::<Value>(&content) {
+            Ok(json) => Config::from_json(&json),
+            Err(e) => {
+                log!("Failed to parse {}: {}", path.display(), e);
+                Config::new()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self.to_json())?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Missing or invalid values keep their defaults
+    fn from_json(json: &Value) -> Self {
+        let mut config = Config::new();
+
+        if let Some(width) = json["windowWidth"].as_u64() {
+            config.window_width = width.max(1) as u32;
+        }
+        if let Some(height) = json["windowHeight"].as_u64() {
+            config.window_height = height.max(1) as u32;
+        }
+        if let Some(fullscreen) = json["fullscreen"].as_bool() {
+            config.fullscreen = fullscreen;
+        }
+        if let Some(volume) = json["masterVolume"].as_f64() {
+            config.master_volume = (volume as f32).clamp(0.0, 1.0);
+        }
+        if let Some(sensitivity) = json["mouseSensitivity"].as_f64() {
+            config.mouse_sensitivity = (sensitivity as f32).max(0.0);
+        }
+        if let Some(fov) = json["fieldOfView"].as_f64() {
+            config.fov = fov as f32;
+        }
+
+        if let Some(language) = json["language"].as_str() {
+            config.language = language.to_string();
+        }
+
+        let bindings = &json["keyBindings"];
+        let key_bindings = &mut config.key_bindings;
+        for (name, key) in [
+            ("forward", &mut key_bindings.forward),
+            ("back", &mut key_bindings.back),
+            ("left", &mut key_bindings.left),
+            ("right", &mut key_bindings.right),
+        ] {
+            if let Some(scancode) = bindings[name].as_str().and_then(Scancode::from_name) {
+                *key = scancode;
+            }
+        }
+
+        config
+    }
+
+    fn to_json(&self) -> Value {
+        let key_bindings = &self.key_bindings;
+        json!({
+            "windowWidth": self.window_width,
+            "windowHeight": self.window_height,
+            "fullscreen": self.fullscreen,
+            "masterVolume": self.master_volume,
+            "mouseSensitivity": self.mouse_sensitivity,
+            "fieldOfView": self.fov,
+            "keyBindings": {
+                "forward": key_bindings.forward.name(),
+                "back": key_bindings.back.name(),
+                "left": key_bindings.left.name(),
+                "right": key_bindings.right.name(),
+            },
+            "language": self.language,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Config;
+
+    #[test]
+    fn test_from_json() {
+        let json = json!({
+            "windowWidth": 1280,
+            "fullscreen": true,
+            "masterVolume": 1.5,
+            "mouseSensitivity": "fast",
+            "fieldOfView": 90.0,
+            "language": "ja",
+        });
+
+        let config = Config::from_json(&json);
+        assert_eq!(1280, config.window_width);
+        assert_eq!(768, config.window_height);
+        assert!(config.fullscreen);
+        // Clamped
+        assert_eq!(1.0, config.master_volume);
+        // Invalid, keeps default
+        assert_eq!(1.0, config.mouse_sensitivity);
+        assert_eq!(90.0, config.fov);
+        assert_eq!(Config::new().key_bindings, config.key_bindings);
+        assert_eq!("ja", config.language);
+    }
+}
@@ -0,0 +1,258 @@
+use std::{cell::RefCell, ops::Range, path::PathBuf, rc::Rc};
+
+use anyhow::{anyhow, Result};
+use sdl2::keyboard::Scancode;
+
+use crate::{actors::actor::Actor, math::vector2::Vector2};
+
+use super::{benchmark, crash_report::log, input_system::InputSystem};
+
+/// Settings of a determinism run, from the command line: --determinism [--update] [--frames F]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeterminismOptions {
+    // Save the hashes as the new reference instead of comparing them
+    pub update: bool,
+    pub frames: u32,
+}
+
+impl DeterminismOptions {
+    // Seed of the random generators, the same for every run
+    pub const SEED: u64 = 0x5EED;
+
+    /// None when the game is not run with --determinism
+    pub fn parse(args: &[String]) -> Option<DeterminismOptions> {
+        if !args.iter().any(|arg| arg == "--determinism") {
+            return None;
+        }
+
+        let mut options = DeterminismOptions {
+            update: false,
+            frames: 600,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--update" => options.update = true,
+                "--frames" => benchmark::parse_value(args.next(), arg, &mut options.frames),
+                _ => {}
+            }
+        }
+        Some(options)
+    }
+}
+
+/// Input held during a span of frames of the script
+struct ScriptStep {
+    frames: Range<u32>,
+    keys: &'static [Scancode],
+    // Relative mouse motion each frame
+    mouse_x: f32,
+    fire: bool,
+}
+
+/// Walks, turns and shoots, so the player, the projectiles and what they hit all move
+const SCRIPT: [ScriptStep; 4] = [
+    ScriptStep {
+        frames: 0..90,
+        keys: &[Scancode::W],
+        mouse_x: 0.0,
+        fire: false,
+    },
+    ScriptStep {
+        frames: 90..150,
+        keys: &[Scancode::W, Scancode::D],
+        mouse_x: 40.0,
+        fire: false,
+    },
+    ScriptStep {
+        frames: 150..240,
+        keys: &[],
+        mouse_x: 0.0,
+        fire: true,
+    },
+    ScriptStep {
+        frames: 240..360,
+        keys: &[Scancode::S, Scancode::A],
+        mouse_x: -30.0,
+        fire: true,
+    },
+];
+
+/// Set the input of the frame from the script, on a cleared input state.
+/// Nothing is pressed after the script ends
+pub fn apply_script(frame: u32, input_system: &mut InputSystem) {
+    for step in SCRIPT.iter().filter(|step| step.frames.contains(&frame)) {
+        for key in step.keys {
+            input_system.set_key(*key, true);
+        }
+        input_system.set_mouse_motion(Vector2::new(step.mouse_x, 0.0));
+        if step.fire {
+            input_system.set_right_trigger(1.0);
+        }
+    }
+}
+
+/// FNV-1a of the ids, positions, rotations and scales of the actors in update order.
+/// Hashes the bits of the floats, so any difference shows
+pub fn hash_transforms(actors: &[Rc<RefCell<dyn Actor>>]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    let mut hash = OFFSET_BASIS;
+    let mut write = |value: u32| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+    for actor in actors {
+        let actor = actor.borrow();
+        let position = actor.get_position();
+        let rotation = actor.get_rotation();
+        write(actor.get_id());
+        for value in [
+            position.x,
+            position.y,
+            position.z,
+            rotation.x,
+            rotation.y,
+            rotation.z,
+            rotation.w,
+            actor.get_scale(),
+        ] {
+            write(value.to_bits());
+        }
+    }
+    hash
+}
+
+/// First frame the hashes differ at, or the end of the shorter stream if one stops early
+pub fn first_divergence(actual: &[u64], expected: &[u64]) -> Option<usize> {
+    match actual.iter().zip(expected).position(|(a, e)| a != e) {
+        Some(frame) => Some(frame),
+        None if actual.len() != expected.len() => Some(actual.len().min(expected.len())),
+        None => None,
+    }
+}
+
+/// One hash per line, in hexadecimal
+pub fn format_hashes(hashes: &[u64]) -> String {
+    hashes
+        .iter()
+        .map(|hash| format!("{:016x}\n", hash))
+        .collect()
+}
+
+pub fn parse_hashes(text: &str) -> Result<Vec<u64>> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            u64::from_str_radix(line.trim(), 16)
+                .map_err(|e| anyhow!("Invalid hash on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+fn get_reference_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join("Determinism")
+        .join("Hashes.txt")
+}
+
+/// Compare the hashes of the run with the reference in resources/Determinism,
+/// or save them as the reference when updating. Returns whether they matched
+pub fn check(hashes: &[u64], options: &DeterminismOptions) -> Result<bool> {
+    let path = get_reference_path();
+    if options.update {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(&path, format_hashes(hashes))?;
+        log!(
+            "Determinism: saved {} frames to {}",
+            hashes.len(),
+            path.display()
+        );
+        return Ok(true);
+    }
+
+    let expected = match std::fs::read_to_string(&path) {
+        Ok(text) => parse_hashes(&text)?,
+        Err(e) => {
+            log!("Determinism: FAILED, no reference ({})", e);
+            return Ok(false);
+        }
+    };
+    match first_divergence(hashes, &expected) {
+        None => {
+            log!("Determinism: ok, {} frames match", hashes.len());
+            Ok(true)
+        }
+        Some(frame) => {
+            log!(
+                "Determinism: FAILED, frame {} differs ({} frames run, {} in the reference)",
+                frame,
+                hashes.len(),
+                expected.len()
+            );
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        math::vector3::Vector3,
+    };
+
+    use super::{
+        first_divergence, format_hashes, hash_transforms, parse_hashes, DeterminismOptions,
+    };
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(None, DeterminismOptions::parse(&args("game --bench")));
+        assert_eq!(
+            Some(DeterminismOptions {
+                update: true,
+                frames: 120,
+            }),
+            DeterminismOptions::parse(&args("game --determinism --update --frames 120"))
+        );
+    }
+
+    #[test]
+    fn test_hash_transforms() {
+        let actor: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let actors = vec![actor.clone()];
+        let hash = hash_transforms(&actors);
+        assert_eq!(hash, hash_transforms(&actors));
+
+        // The smallest move shows
+        actor
+            .borrow_mut()
+            .set_position(Vector3::new(f32::from_bits(1), 0.0, 0.0));
+        assert_ne!(hash, hash_transforms(&actors));
+    }
+
+    #[test]
+    fn test_first_divergence() {
+        assert_eq!(None, first_divergence(&[1, 2, 3], &[1, 2, 3]));
+        assert_eq!(Some(1), first_divergence(&[1, 5, 3], &[1, 2, 3]));
+        assert_eq!(Some(2), first_divergence(&[1, 2], &[1, 2, 3]));
+
+        let hashes = vec![0, u64::MAX, 0x1234_5678_9ABC_DEF0];
+        assert_eq!(hashes, parse_hashes(&format_hashes(&hashes)).unwrap());
+        assert!(parse_hashes("12\nxyz\n").is_err());
+    }
+}
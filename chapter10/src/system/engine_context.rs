@@ -0,0 +1,19 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{
+    asset_manager::AssetManager, audio_system::AudioSystem, entity_manager::EntityManager,
+    phys_world::PhysWorld, renderer::Renderer, script_manager::ScriptManager,
+    timer_manager::TimerManager,
+};
+
+/// Shared systems actors and components are created with
+#[derive(Clone)]
+pub struct EngineContext {
+    pub asset_manager: Rc<RefCell<AssetManager>>,
+    pub entity_manager: Rc<RefCell<EntityManager>>,
+    pub audio_system: Rc<RefCell<AudioSystem>>,
+    pub renderer: Rc<RefCell<Renderer>>,
+    pub phys_world: Rc<RefCell<PhysWorld>>,
+    pub timer_manager: Rc<RefCell<TimerManager>>,
+    pub script_manager: Rc<RefCell<ScriptManager>>,
+}
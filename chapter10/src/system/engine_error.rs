@@ -0,0 +1,120 @@
+use std::{error::Error, fmt};
+
+use gl::types::GLenum;
+
+/// Failures of the GL and asset code. Loading APIs return them, and the asset manager
+/// reports them and falls back to placeholders instead of crashing
+#[derive(Debug)]
+pub enum EngineError {
+    // The file couldn't be read
+    Io {
+        file_name: String,
+        source: std::io::Error,
+    },
+    // The file was read, but its contents can't be used
+    InvalidAsset {
+        file_name: String,
+        reason: String,
+    },
+    ShaderCompile {
+        file_name: String,
+        log: String,
+    },
+    ShaderLink {
+        shader_name: String,
+        log: String,
+    },
+    // Flag raised by a GL call, see glGetError
+    Gl {
+        context: String,
+        code: GLenum,
+    },
+}
+
+impl EngineError {
+    pub fn io(file_name: &str, source: std::io::Error) -> Self {
+        EngineError::Io {
+            file_name: file_name.to_string(),
+            source,
+        }
+    }
+
+    pub fn invalid_asset(file_name: &str, reason: impl fmt::Display) -> Self {
+        EngineError::InvalidAsset {
+            file_name: file_name.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::Io { file_name, source } => {
+                write!(f, "Failed to read {}: {}", file_name, source)
+            }
+            EngineError::InvalidAsset { file_name, reason } => {
+                write!(f, "Invalid asset {}: {}", file_name, reason)
+            }
+            EngineError::ShaderCompile { file_name, log } => {
+                write!(f, "Failed to compile shader {}: {}", file_name, log)
+            }
+            EngineError::ShaderLink { shader_name, log } => {
+                write!(f, "Failed to link shader {}: {}", shader_name, log)
+            }
+            EngineError::Gl { context, code } => {
+                write!(f, "GL error 0x{:04X} in {}", code, context)
+            }
+        }
+    }
+}
+
+impl Error for EngineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EngineError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Error flag raised since the last check, context tells what was being done
+pub fn check_gl_error(context: &str) -> Result<(), EngineError> {
+    let code = unsafe { gl::GetError() };
+    if code == gl::NO_ERROR {
+        return Ok(());
+    }
+
+    // Several flags can be raised, only the first one is reported
+    while unsafe { gl::GetError() } != gl::NO_ERROR {}
+
+    Err(EngineError::Gl {
+        context: context.to_string(),
+        code,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, io::ErrorKind};
+
+    use super::EngineError;
+
+    #[test]
+    fn test_engine_error_message() {
+        let error = EngineError::invalid_asset("Cube.gpmesh", "no vertices");
+        assert_eq!("Invalid asset Cube.gpmesh: no vertices", error.to_string());
+        assert!(error.source().is_none());
+
+        let error = EngineError::Gl {
+            context: "Texture::load".to_string(),
+            code: gl::INVALID_VALUE,
+        };
+        assert_eq!("GL error 0x0501 in Texture::load", error.to_string());
+
+        // The IO error is kept as the cause
+        let error = EngineError::io("Missing.png", ErrorKind::NotFound.into());
+        assert!(error.to_string().starts_with("Failed to read Missing.png"));
+        assert!(error.source().is_some());
+    }
+}
@@ -1,38 +1,87 @@
 use core::f32;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use anyhow::Result;
 
 use crate::{
     actors::{
         actor::{self, Actor, DefaultActor, State as ActorState},
+        actor_builder::ReuseComponent,
+        door_actor::{DoorActor, DoorMotion},
+        elevator_actor::ElevatorActor,
         fps_actor::FPSActor,
+        hover_vehicle_actor::HoverVehicleActor,
+        moving_target_actor::MovingTargetActor,
+        physics_crate_actor::PhysicsCrateActor,
         plane_actor::PlaneActor,
+        pop_up_target_actor::PopUpTargetActor,
+        reverb_zone_actor::ReverbZoneActor,
         target_actor::TargetActor,
+        terrain_actor::TerrainActor,
+        turret_actor::TurretActor,
+    },
+    collision::aabb::AABB,
+    components::{
+        health_bar_component::HealthBarComponent, health_component::HealthComponent,
+        sprite_component::SpriteComponent, water_component::WaterComponent,
+        world_space_sprite_component::WorldSpaceSpriteComponent,
     },
-    components::sprite_component::{DefaultSpriteComponent, SpriteComponent},
-    math::{quaternion::Quaternion, random::Random, vector3::Vector3},
-    system::{asset_manager::AssetManager, renderer::Renderer},
+    graphics::color::Color,
+    math::{quaternion::Quaternion, random::Random, vector2::Vector2, vector3::Vector3},
 };
 
-use super::{audio_system::AudioSystem, phys_world::PhysWorld};
+use super::{
+    crash_report::log,
+    engine_context::EngineContext,
+    hud::HUD,
+    pool::{Pool, PoolStats, Pools},
+    prefab::PrefabRegistry,
+    prefab_node::PrefabNode,
+    tween::Ease,
+    world_bounds::WorldBounds,
+};
 
 pub struct EntityManager {
-    actors: Vec<Rc<RefCell<dyn Actor>>>,
+    // Shared with the frame loops, only copied if it changes while they iterate
+    actors: Rc<Vec<Rc<RefCell<dyn Actor>>>>,
     pending_actors: Vec<Rc<RefCell<dyn Actor>>>,
     updating_actors: bool,
     fps_actor: Option<Rc<RefCell<FPSActor>>>,
-    planes: Vec<Rc<RefCell<PlaneActor>>>,
+    // Actors by tag, dead ones are removed when the actors are flushed
+    tags: HashMap<String, Vec<Rc<RefCell<dyn Actor>>>>,
     random: Random,
+    hud: Option<Rc<RefCell<HUD>>>,
+    // Loaded on first use
+    prefabs: HashMap<String, Rc<PrefabNode>>,
+    prefab_registry: Rc<RefCell<PrefabRegistry>>,
+    // Short lived actors and their components, reset instead of allocated again
+    actor_pools: Pools,
+    component_pools: Pools,
+    // Nothing is kept in without them
+    world_bounds: Option<WorldBounds>,
 }
 
 impl EntityManager {
+    // Targets tracked by the radar and the game mode
+    pub const TARGET_TAG: &str = "target";
+    pub const ENEMY_TAG: &str = "enemy";
+    // Moving geometry the player collides with and stands on, like doors and elevators
+    pub const SOLID_TAG: &str = "solid";
+
     pub fn new() -> Rc<RefCell<Self>> {
         let this = Self {
-            actors: vec![],
+            actors: Rc::new(vec![]),
             pending_actors: vec![],
             updating_actors: false,
             fps_actor: None,
-            planes: vec![],
+            tags: HashMap::new(),
             random: Random::new(),
+            hud: None,
+            prefabs: HashMap::new(),
+            prefab_registry: Rc::new(RefCell::new(PrefabRegistry::new())),
+            actor_pools: Pools::new(),
+            component_pools: Pools::new(),
+            world_bounds: None,
         };
 
         Rc::new(RefCell::new(this))
@@ -42,17 +91,19 @@ impl EntityManager {
         if self.updating_actors {
             self.pending_actors.push(actor);
         } else {
-            self.actors.push(actor);
+            Rc::make_mut(&mut self.actors).push(actor);
         }
     }
 
     pub fn flush_actors(&mut self) {
-        for pending in self.pending_actors.clone() {
-            self.actors.push(pending);
+        let actors = Rc::make_mut(&mut self.actors);
+        actors.append(&mut self.pending_actors);
+
+        for tagged in self.tags.values_mut() {
+            tagged.retain(|actor| *actor.borrow().get_state() != ActorState::Dead);
         }
-        self.pending_actors.clear();
 
-        self.actors.retain(|actor| {
+        actors.retain(|actor| {
             if *actor.borrow().get_state() != ActorState::Dead {
                 true
             } else {
@@ -60,45 +111,101 @@ impl EntityManager {
                 false
             }
         });
+
+        self.actor_pools.collect();
+        self.component_pools.collect();
     }
 
-    pub fn load_data(
-        this: Rc<RefCell<EntityManager>>,
-        asset_manager: Rc<RefCell<AssetManager>>,
-        renderer: Rc<RefCell<Renderer>>,
-        audio_system: Rc<RefCell<AudioSystem>>,
-        phys_world: Rc<RefCell<PhysWorld>>,
-    ) -> Rc<RefCell<FPSActor>> {
-        let mut planes = vec![];
+    /// Dead actors of type T are released once nothing but the pool holds them
+    pub fn get_actor_pool<T: Actor + 'static>(&mut self) -> &mut Pool<T> {
+        self.actor_pools.get(
+            |actor: &Rc<RefCell<T>>| {
+                EntityManager::is_unused(actor) && *actor.borrow().get_state() == ActorState::Dead
+            },
+            |_| {},
+        )
+    }
 
-        // Setup floor
-        let start = -1250.0;
-        let size = 250.0;
-        for i in 0..10 {
-            for j in 0..10 {
-                let p = PlaneActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
-                p.borrow_mut().set_position(Vector3::new(
-                    start + i as f32 * size,
-                    start + j as f32 * size,
-                    -100.0,
-                ));
-                planes.push(p);
+    /// Components are released once nothing but the pool holds them
+    pub fn get_component_pool<C: ReuseComponent>(&mut self) -> &mut Pool<C> {
+        self.component_pools
+            .get(EntityManager::is_unused, C::release)
+    }
+
+    fn is_unused<T: ?Sized>(object: &Rc<RefCell<T>>) -> bool {
+        Rc::strong_count(object) == 1 && Rc::weak_count(object) == 0
+    }
+
+    pub fn set_world_bounds(&mut self, world_bounds: WorldBounds) {
+        self.world_bounds = Some(world_bounds);
+    }
+
+    pub fn get_world_bounds(&self) -> Option<&WorldBounds> {
+        self.world_bounds.as_ref()
+    }
+
+    pub fn get_world_bounds_mut(&mut self) -> Option<&mut WorldBounds> {
+        self.world_bounds.as_mut()
+    }
+
+    /// Destroy, respawn or clamp the actors outside the world bounds, once they all moved.
+    /// Children are skipped, they follow their parent
+    pub fn enforce_world_bounds(&mut self) {
+        let world_bounds = match self.world_bounds.as_mut() {
+            Some(world_bounds) => world_bounds,
+            None => return,
+        };
+
+        if let Some(fps_actor) = &self.fps_actor {
+            world_bounds.update_checkpoint(fps_actor.borrow().get_position());
+        }
+
+        for actor in self.actors.iter() {
+            let mut borrowed_actor = actor.borrow_mut();
+            if *borrowed_actor.get_state() == ActorState::Dead
+                || borrowed_actor.get_parent().is_some()
+            {
+                continue;
+            }
+
+            let position = borrowed_actor.get_position().clone();
+            match world_bounds.resolve(borrowed_actor.get_bounds_policy(), &position) {
+                Some(new_position) if new_position != position => {
+                    borrowed_actor.set_position(new_position)
+                }
+                Some(_) => {}
+                None => borrowed_actor.set_state(ActorState::Dead),
             }
         }
+    }
+
+    pub fn get_pool_stats(&self) -> Vec<(&'static str, PoolStats)> {
+        let mut stats = self.actor_pools.get_stats();
+        stats.extend(self.component_pools.get_stats());
+        stats
+    }
+
+    pub fn load_data(context: &EngineContext) -> Rc<RefCell<FPSActor>> {
+        let this = context.entity_manager.clone();
+
+        // Setup floor, hills rise toward the back wall
+        TerrainActor::new(context, "Terrain.png", Vector3::new(-125.0, -125.0, -100.0));
+
+        let start = -1250.0;
+        let size = 250.0;
 
         // Left/right walls
         let q = Quaternion::from_axis_angle(&Vector3::UNIT_X, std::f32::consts::FRAC_PI_2);
         for i in 0..10 {
-            let p = PlaneActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
+            let p = PlaneActor::new(context);
             p.borrow_mut()
                 .set_position(Vector3::new(start + i as f32 * size, start - size, 0.0));
             p.borrow_mut().set_rotation(q.clone());
 
-            let p = PlaneActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
+            let p = PlaneActor::new(context);
             p.borrow_mut()
                 .set_position(Vector3::new(start + i as f32 * size, -start + size, 0.0));
             p.borrow_mut().set_rotation(q.clone());
-            planes.push(p);
         }
 
         // Forward/back walls
@@ -107,90 +214,541 @@ impl EntityManager {
             &Quaternion::from_axis_angle(&Vector3::UNIT_Z, std::f32::consts::FRAC_PI_2),
         );
         for i in 0..10 {
-            let p = PlaneActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
+            let p = PlaneActor::new(context);
             p.borrow_mut()
                 .set_position(Vector3::new(start - size, start + i as f32 * size, 0.0));
             p.borrow_mut().set_rotation(q.clone());
 
-            let p = PlaneActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
+            let p = PlaneActor::new(context);
             p.borrow_mut()
                 .set_position(Vector3::new(-start + size, start + i as f32 * size, 0.0));
             p.borrow_mut().set_rotation(q.clone());
-            planes.push(p);
         }
 
-        this.borrow_mut().planes = planes;
-
         // Camera actor
-        let fps_actor = FPSActor::new(
-            asset_manager.clone(),
-            this.clone(),
-            audio_system.clone(),
-            renderer.clone(),
-            phys_world.clone(),
-        );
+        let fps_actor = FPSActor::new(context);
         this.borrow_mut().fps_actor = Some(fps_actor.clone());
 
+        // A bit past the walls, falling under the floor respawns where the player started.
+        // The level file may replace them
+        this.borrow_mut().set_world_bounds(WorldBounds::new(
+            AABB::new(
+                Vector3::new(start - size * 1.5, start - size * 1.5, -500.0),
+                Vector3::new(-start + size * 1.5, -start + size * 1.5, 3000.0),
+            ),
+            fps_actor.borrow().get_position().clone(),
+        ));
+
         // Setup lights
         {
-            let mut borrowed_renderer = renderer.borrow_mut();
-            borrowed_renderer.set_ambient_light(Vector3::new(0.2, 0.2, 0.2));
+            let mut borrowed_renderer = context.renderer.borrow_mut();
+            borrowed_renderer.set_ambient_light(Color::rgb(0.2, 0.2, 0.2));
             let directional_light = borrowed_renderer.get_directional_light_mut();
             directional_light.direction = Vector3::new(0.0, -0.707, -0.707);
-            directional_light.diffuse_color = Vector3::new(0.78, 0.88, 1.0);
-            directional_light.spec_color = Vector3::new(0.8, 0.8, 0.8);
+            directional_light.diffuse_color = Color::rgb(0.78, 0.88, 1.0);
+            directional_light.spec_color = Color::rgb(0.8, 0.8, 0.8);
         }
 
+        // The back left corner of the room echoes
+        let reverb_zone = ReverbZoneActor::new(
+            context,
+            "snapshot:/WithReverb",
+            Vector3::new(500.0, 500.0, 500.0),
+        );
+        reverb_zone
+            .borrow_mut()
+            .set_position(Vector3::new(-750.0, -750.0, 0.0));
+
+        // Pool between the hills at the back
+        let pool = DefaultActor::new(context);
+        pool.borrow_mut()
+            .set_position(Vector3::new(-1050.0, -125.0, -60.0));
+        WaterComponent::new(pool, Vector2::new(325.0, 1250.0));
+
         // UI elements
-        let ui = DefaultActor::new(asset_manager.clone(), this.clone());
+        let ui = DefaultActor::new(context);
         ui.borrow_mut()
             .set_position(Vector3::new(-350.0, -350.0, 0.0));
-        let sprite_component = DefaultSpriteComponent::new(ui.clone(), 100);
-        let texture = asset_manager.borrow_mut().get_texture("HealthBar.png");
-        sprite_component.borrow_mut().set_texture(texture);
-
-        let ui = DefaultActor::new(asset_manager.clone(), this.clone());
-        ui.borrow_mut()
-            .set_position(Vector3::new(375.0, -275.0, 0.0));
-        ui.borrow_mut().set_scale(0.75);
-        let sprite_component = DefaultSpriteComponent::new(ui.clone(), 100);
-        let texture = asset_manager.borrow_mut().get_texture("Radar.png");
-        sprite_component.borrow_mut().set_texture(texture);
+        let health_component = fps_actor.borrow().get_health_component().unwrap().clone();
+        let health_bar = HealthBarComponent::new(ui.clone(), 100, health_component);
+        let texture = context
+            .asset_manager
+            .borrow_mut()
+            .get_texture("HealthBar.png");
+        health_bar.borrow_mut().set_texture(texture);
 
         // Create target actors
-        let t = TargetActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
-        t.borrow_mut()
-            .set_position(Vector3::new(1450.0, 0.0, 100.0));
-        let t = TargetActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
-        t.borrow_mut()
-            .set_position(Vector3::new(1450.0, 0.0, 400.0));
-        let t = TargetActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
-        t.borrow_mut()
-            .set_position(Vector3::new(1450.0, -500.0, 200.0));
-        let t = TargetActor::new(asset_manager.clone(), this.clone(), phys_world.clone());
-        t.borrow_mut()
-            .set_position(Vector3::new(1450.0, 500.0, 200.0));
+        for position in [
+            Vector3::new(1450.0, 0.0, 100.0),
+            Vector3::new(1450.0, 0.0, 400.0),
+            Vector3::new(1450.0, -500.0, 200.0),
+            Vector3::new(1450.0, 500.0, 200.0),
+        ] {
+            EntityManager::spawn_target(context, position);
+        }
+
+        // Moving target sliding in front of the wall
+        let moving_target = MovingTargetActor::new(context);
+        moving_target.borrow_mut().set_path(
+            Vector3::new(1200.0, -800.0, 250.0),
+            Vector3::new(1200.0, 800.0, 250.0),
+        );
+        moving_target.borrow_mut().set_speed(300.0);
+        EntityManager::add_health_bar(moving_target, context);
+
+        // Pop-up targets on both sides
+        for (y, up_time) in [(-1000.0, 2.0), (1000.0, 1.5)] {
+            let pop_up_target = PopUpTargetActor::new(context);
+            pop_up_target
+                .borrow_mut()
+                .set_position(Vector3::new(1450.0, y, 150.0));
+            pop_up_target.borrow_mut().set_times(up_time, 1.5);
+            EntityManager::add_health_bar(pop_up_target, context);
+        }
+
+        // Turrets guarding both sides of the player
+        for y in [-600.0, 600.0] {
+            let turret = TurretActor::new(context);
+            turret
+                .borrow_mut()
+                .set_position(Vector3::new(-200.0, y, -50.0));
+        }
+
+        // Door sliding up when the player comes close, and one swinging open when used
+        // that closes again on its own
+        let door = DoorActor::new(context, DoorMotion::Slide(Vector3::new(0.0, 0.0, 190.0)));
+        door.borrow_mut()
+            .set_closed_position(Vector3::new(600.0, -1100.0, 0.0));
+        door.borrow_mut()
+            .set_trigger(Vector3::new(300.0, 300.0, 300.0));
+        let door = DoorActor::new(context, DoorMotion::Swing(std::f32::consts::FRAC_PI_2));
+        door.borrow_mut()
+            .set_closed_position(Vector3::new(600.0, 1100.0, 0.0));
+        door.borrow_mut().set_auto_close(Some(5.0));
+
+        // A target slides out through the swinging door once the first round is going
+        let target = EntityManager::spawn_target(context, Vector3::new(600.0, 1300.0, 150.0));
+        let audio_system = context.audio_system.clone();
+        context
+            .script_manager
+            .borrow_mut()
+            .start(|script| async move {
+                script.wait_seconds(3.0).await;
+                door.borrow_mut().set_used_open(true);
+                script.wait_until(|| door.borrow().is_open()).await;
+                let arrived = script
+                    .move_to(
+                        &*target,
+                        Vector3::new(600.0, 800.0, 150.0),
+                        1.5,
+                        Ease::InOutQuad,
+                    )
+                    .await;
+                if !arrived {
+                    return;
+                }
+                audio_system.borrow_mut().play_event("event:/Ding");
+                script.wait_seconds(1.0).await;
+                door.borrow_mut().set_used_open(false);
+            });
+
+        // Elevator up to the height of the targets
+        let (x, y) = (-600.0, -1100.0);
+        let ground = context
+            .phys_world
+            .borrow()
+            .get_ground_height(x, y)
+            .unwrap_or(-100.0);
+        let elevator = ElevatorActor::new(context);
+        elevator
+            .borrow_mut()
+            .set_floors(x, y, vec![ground + 5.0, ground + 400.0]);
+
+        // Crates to pick up and throw, dropped onto the hill in front of the player
+        for (y, z) in [(-150.0, 100.0), (-150.0, 200.0), (150.0, 100.0)] {
+            let physics_crate = PhysicsCrateActor::new(context);
+            physics_crate
+                .borrow_mut()
+                .set_position(Vector3::new(300.0, y, z));
+        }
+
+        // Hovercraft to drive around in, behind the crates on the right
+        let hover_vehicle = HoverVehicleActor::new(context);
+        hover_vehicle
+            .borrow_mut()
+            .set_position(Vector3::new(0.0, 400.0, 150.0));
+
+        // Burning target above the others
+        if let Err(e) =
+            EntityManager::spawn_prefab(context, "BurningTarget", Vector3::new(1450.0, 0.0, 700.0))
+        {
+            log!("Failed to spawn prefab: {}", e);
+        }
 
         fps_actor
     }
 
+    /// Spawn the actors of resources/Prefabs/<name>.json with the root at position
+    pub fn spawn_prefab(
+        context: &EngineContext,
+        name: &str,
+        position: Vector3,
+    ) -> Result<Rc<RefCell<dyn Actor>>> {
+        let prefab = {
+            let mut this = context.entity_manager.borrow_mut();
+            match this.prefabs.get(name) {
+                Some(prefab) => prefab.clone(),
+                None => {
+                    let prefab = Rc::new(PrefabNode::load(name)?);
+                    this.prefabs.insert(name.to_string(), prefab.clone());
+                    prefab
+                }
+            }
+        };
+        let prefab_registry = context.entity_manager.borrow().prefab_registry.clone();
+        let actor = prefab_registry
+            .borrow()
+            .spawn(context, &prefab, None, &position);
+        actor
+    }
+
+    /// Register actor and component types for prefabs
+    pub fn get_prefab_registry(&self) -> &Rc<RefCell<PrefabRegistry>> {
+        &self.prefab_registry
+    }
+
+    /// Create a target with a health bar above it
+    pub fn spawn_target(context: &EngineContext, position: Vector3) -> Rc<RefCell<TargetActor>> {
+        let target = TargetActor::new(context);
+        target.borrow_mut().set_position(position);
+        EntityManager::add_health_bar(target.clone(), context);
+        target
+    }
+
+    /// Show the health of a target above it
+    pub fn add_health_bar(target: Rc<RefCell<dyn Actor>>, context: &EngineContext) {
+        let health_component = match target.borrow().get_component::<HealthComponent>() {
+            Some(health_component) => health_component,
+            None => return,
+        };
+
+        let health_bar = WorldSpaceSpriteComponent::new(target, 50, context.renderer.clone());
+        let texture = context
+            .asset_manager
+            .borrow_mut()
+            .get_texture("HealthBar.png");
+        let mut borrowed_health_bar = health_bar.borrow_mut();
+        borrowed_health_bar.set_texture(texture);
+        borrowed_health_bar.set_offset(Vector3::new(0.0, 0.0, 150.0));
+        borrowed_health_bar.set_sprite_scale(0.25);
+        borrowed_health_bar.set_health_component(health_component);
+    }
+
     pub fn get_actors(&self) -> &Vec<Rc<RefCell<dyn Actor>>> {
         &self.actors
     }
 
-    pub fn get_pending_actors(&self) -> &Vec<Rc<RefCell<dyn Actor>>> {
-        &self.pending_actors
+    /// The actors as of now, to iterate while they add actors or borrow the manager.
+    /// Costs one reference count, the list is only copied if it changes while held
+    pub fn share_actors(&self) -> Rc<Vec<Rc<RefCell<dyn Actor>>>> {
+        self.actors.clone()
     }
 
-    pub fn get_planes(&self) -> &Vec<Rc<RefCell<PlaneActor>>> {
-        &self.planes
+    /// Actors added during the update, added to the others by the caller or flush_actors
+    pub fn take_pending_actors(&mut self) -> Vec<Rc<RefCell<dyn Actor>>> {
+        std::mem::take(&mut self.pending_actors)
+    }
+
+    pub fn get_fps_actor(&self) -> Option<&Rc<RefCell<FPSActor>>> {
+        self.fps_actor.as_ref()
+    }
+
+    /// Tag the actor, so it is found by actors_with_tag until it dies
+    pub fn add_tag(&mut self, actor: Rc<RefCell<dyn Actor>>, tag: &str) {
+        let tagged = self.tags.entry(tag.to_string()).or_default();
+        if !tagged.iter().any(|other| Rc::ptr_eq(other, &actor)) {
+            tagged.push(actor);
+        }
+    }
+
+    pub fn remove_tag(&mut self, actor: &Rc<RefCell<dyn Actor>>, tag: &str) {
+        if let Some(tagged) = self.tags.get_mut(tag) {
+            tagged.retain(|other| !Rc::ptr_eq(other, actor));
+        }
+    }
+
+    pub fn has_tag(&self, actor: &Rc<RefCell<dyn Actor>>, tag: &str) -> bool {
+        self.actors_with_tag(tag)
+            .iter()
+            .any(|other| Rc::ptr_eq(other, actor))
+    }
+
+    pub fn actors_with_tag(&self, tag: &str) -> &[Rc<RefCell<dyn Actor>>] {
+        match self.tags.get(tag) {
+            Some(tagged) => tagged,
+            None => &[],
+        }
+    }
+
+    /// Actors of type T, except the ones being updated
+    pub fn actors_of_type<T: Actor + 'static>(&self) -> Vec<Rc<RefCell<T>>> {
+        self.actors.iter().filter_map(actor::downcast).collect()
+    }
+
+    /// Living actors within radius of position, except the ones being updated
+    pub fn actors_in_radius(&self, position: &Vector3, radius: f32) -> Vec<Rc<RefCell<dyn Actor>>> {
+        self.actors
+            .iter()
+            .filter(|actor| match actor.try_borrow() {
+                Ok(actor) => {
+                    *actor.get_state() != ActorState::Dead
+                        && (actor.get_world_transform().get_translation() - position.clone())
+                            .length_sq()
+                            <= radius * radius
+                }
+                Err(_) => false,
+            })
+            .cloned()
+            .collect()
     }
 
     pub fn get_random(&mut self) -> &mut Random {
         &mut self.random
     }
 
+    pub fn get_hud(&self) -> Option<&Rc<RefCell<HUD>>> {
+        self.hud.as_ref()
+    }
+
+    pub fn set_hud(&mut self, hud: Rc<RefCell<HUD>>) {
+        self.hud = Some(hud);
+    }
+
     pub fn set_updating_actors(&mut self, updating_actors: bool) {
         self.updating_actors = updating_actors;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor, DefaultActor, State},
+        math::vector3::Vector3,
+    };
+
+    use super::{released::ReleasedComponent, EntityManager};
+
+    #[test]
+    fn test_actor_queries() {
+        let entity_manager = EntityManager::new();
+        let mut entity_manager = entity_manager.borrow_mut();
+        let near: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let far: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        far.borrow_mut().set_position(Vector3::new(500.0, 0.0, 0.0));
+        far.borrow_mut().compute_world_transform();
+        entity_manager.add_actor(near.clone());
+        entity_manager.add_actor(far.clone());
+
+        entity_manager.add_tag(near.clone(), EntityManager::ENEMY_TAG);
+        entity_manager.add_tag(near.clone(), EntityManager::ENEMY_TAG);
+        entity_manager.add_tag(far.clone(), EntityManager::ENEMY_TAG);
+        assert_eq!(
+            2,
+            entity_manager
+                .actors_with_tag(EntityManager::ENEMY_TAG)
+                .len()
+        );
+        assert!(entity_manager.has_tag(&near, EntityManager::ENEMY_TAG));
+        assert!(!entity_manager.has_tag(&near, EntityManager::TARGET_TAG));
+        assert!(entity_manager
+            .actors_with_tag(EntityManager::TARGET_TAG)
+            .is_empty());
+
+        assert_eq!(2, entity_manager.actors_of_type::<TestActor>().len());
+        assert!(entity_manager.actors_of_type::<DefaultActor>().is_empty());
+
+        let in_radius = entity_manager.actors_in_radius(&Vector3::ZERO, 100.0);
+        assert_eq!(1, in_radius.len());
+        assert!(Rc::ptr_eq(&near, &in_radius[0]));
+
+        // Dead actors lose their tags
+        far.borrow_mut().set_state(State::Dead);
+        entity_manager.flush_actors();
+        assert_eq!(
+            1,
+            entity_manager
+                .actors_with_tag(EntityManager::ENEMY_TAG)
+                .len()
+        );
+
+        entity_manager.remove_tag(&near, EntityManager::ENEMY_TAG);
+        assert!(!entity_manager.has_tag(&near, EntityManager::ENEMY_TAG));
+    }
+
+    #[test]
+    fn test_share_actors() {
+        let entity_manager = EntityManager::new();
+        let mut entity_manager = entity_manager.borrow_mut();
+        let first: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        entity_manager.add_actor(first.clone());
+
+        // Spawned during the update, the shared list is not copied
+        let shared = entity_manager.share_actors();
+        entity_manager.set_updating_actors(true);
+        let spawned: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        entity_manager.add_actor(spawned.clone());
+        entity_manager.set_updating_actors(false);
+        assert!(Rc::ptr_eq(&shared, &entity_manager.share_actors()));
+
+        let pending_actors = entity_manager.take_pending_actors();
+        assert_eq!(1, pending_actors.len());
+        for pending in pending_actors {
+            entity_manager.add_actor(pending);
+        }
+        entity_manager.flush_actors();
+
+        // The list held during the update stays as it was
+        assert_eq!(1, shared.len());
+        assert_eq!(2, entity_manager.get_actors().len());
+
+        first.borrow_mut().set_state(State::Dead);
+        drop(shared);
+        entity_manager.flush_actors();
+        assert_eq!(1, entity_manager.get_actors().len());
+        assert!(Rc::ptr_eq(&spawned, &entity_manager.get_actors()[0]));
+    }
+
+    #[test]
+    fn test_release_pooled() {
+        let entity_manager = EntityManager::new();
+        let mut entity_manager = entity_manager.borrow_mut();
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        let releases = Rc::new(std::cell::Cell::new(0));
+        let component = ReleasedComponent::new(owner.clone(), releases.clone());
+        entity_manager
+            .get_component_pool::<ReleasedComponent>()
+            .add(component.clone());
+
+        // Still held by the owner
+        entity_manager.flush_actors();
+        assert_eq!(0, releases.get());
+
+        drop(component);
+        owner.borrow_mut().clear_components();
+        entity_manager.flush_actors();
+        entity_manager.flush_actors();
+        assert_eq!(1, releases.get());
+    }
+
+    #[test]
+    fn test_pooled_actor_held() {
+        let entity_manager = EntityManager::new();
+        let mut entity_manager = entity_manager.borrow_mut();
+        let actor = Rc::new(RefCell::new(TestActor::new()));
+        entity_manager
+            .get_actor_pool::<TestActor>()
+            .add(actor.clone());
+        actor.borrow_mut().set_state(State::Dead);
+
+        // Dead, but something still points to it
+        entity_manager.flush_actors();
+        assert!(entity_manager
+            .get_actor_pool::<TestActor>()
+            .acquire()
+            .is_none());
+
+        drop(actor);
+        entity_manager.flush_actors();
+        assert!(entity_manager
+            .get_actor_pool::<TestActor>()
+            .acquire()
+            .is_some());
+    }
+}
+
+#[cfg(test)]
+mod released {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use crate::{
+        actors::{
+            actor::Actor,
+            actor_builder::{BuildComponent, ReuseComponent},
+        },
+        components::{
+            command_buffer::{CommandBuffer, OwnerSnapshot},
+            component::{self, generate_id, Component, State},
+        },
+        system::engine_context::EngineContext,
+    };
+
+    /// Counts the times the pool released it
+    pub struct ReleasedComponent {
+        id: u32,
+        owner: Rc<RefCell<dyn Actor>>,
+        update_order: i32,
+        state: State,
+        releases: Rc<Cell<u32>>,
+    }
+
+    impl ReleasedComponent {
+        pub fn new(owner: Rc<RefCell<dyn Actor>>, releases: Rc<Cell<u32>>) -> Rc<RefCell<Self>> {
+            let result = Rc::new(RefCell::new(Self {
+                id: generate_id(),
+                owner: owner.clone(),
+                update_order: 100,
+                state: State::Active,
+                releases,
+            }));
+            owner.borrow_mut().add_component(result.clone());
+            result
+        }
+    }
+
+    impl Component for ReleasedComponent {
+        fn update(
+            &mut self,
+            _delta_time: f32,
+            _owner_info: &OwnerSnapshot,
+            _commands: &mut CommandBuffer,
+        ) {
+        }
+
+        component::impl_getters_setters! {}
+    }
+
+    impl BuildComponent for ReleasedComponent {
+        type Params = Rc<Cell<u32>>;
+
+        fn build(
+            owner: Rc<RefCell<dyn Actor>>,
+            _context: &EngineContext,
+            releases: Self::Params,
+        ) -> Rc<RefCell<Self>> {
+            ReleasedComponent::new(owner, releases)
+        }
+    }
+
+    impl ReuseComponent for ReleasedComponent {
+        fn reuse(
+            this: &Rc<RefCell<Self>>,
+            owner: Rc<RefCell<dyn Actor>>,
+            _context: &EngineContext,
+            releases: Self::Params,
+        ) {
+            this.borrow_mut().owner = owner.clone();
+            this.borrow_mut().releases = releases;
+            owner.borrow_mut().add_component(this.clone());
+        }
+
+        fn release(this: &Rc<RefCell<Self>>) {
+            let releases = &this.borrow().releases;
+            releases.set(releases.get() + 1);
+        }
+    }
+}
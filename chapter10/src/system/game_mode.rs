@@ -0,0 +1,308 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    actors::actor::Actor, components::health_component::HealthComponent, math::vector3::Vector3,
+};
+
+use super::{
+    crash_report::log, engine_context::EngineContext, entity_manager::EntityManager,
+    objective_manager::ObjectiveManager, spawn_manager::SpawnManager, timer_manager::TimerHandle,
+};
+
+/// Events sent from actors to the game mode
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    TargetDestroyed { position: Vector3 },
+}
+
+/// Score and timer of a round, independent from the actors
+struct Round {
+    score: u32,
+    time_left: f32,
+    // Ended before the timer ran out, by completing the mission
+    finished: bool,
+}
+
+impl Round {
+    fn new(duration: f32) -> Self {
+        Self {
+            score: 0,
+            time_left: duration,
+            finished: false,
+        }
+    }
+
+    /// Returns whether it scored, the target only comes back then
+    fn on_target_destroyed(&mut self) -> bool {
+        if self.is_over() {
+            return false;
+        }
+        self.score += GameMode::SCORE_PER_TARGET;
+        true
+    }
+
+    fn update(&mut self, delta_time: f32) {
+        if self.is_over() {
+            return;
+        }
+        self.time_left = (self.time_left - delta_time).max(0.0);
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    fn is_over(&self) -> bool {
+        self.finished || self.time_left <= 0.0
+    }
+}
+
+/// Target practice: score destroyed targets before the timer runs out,
+/// or end the round early by completing the mission
+pub struct GameMode {
+    context: EngineContext,
+    // Filled by the actors, drained every update
+    events: Rc<RefCell<Vec<GameEvent>>>,
+    round: Round,
+    // Destroyed targets coming back, some may have fired already
+    respawn_timers: Vec<TimerHandle>,
+    spawn_manager: SpawnManager,
+    objective_manager: ObjectiveManager,
+}
+
+impl GameMode {
+    const ROUND_DURATION: f32 = 60.0;
+    const SCORE_PER_TARGET: u32 = 100;
+    const RESPAWN_DELAY: f32 = 1.0;
+    // Area new targets appear in, on the far wall
+    const SPAWN_MIN: Vector3 = Vector3::new(1450.0, -800.0, 100.0);
+    const SPAWN_MAX: Vector3 = Vector3::new(1450.0, 800.0, 500.0);
+    // Level file with the encounters of the round
+    const LEVEL: &'static str = "Arena";
+    // Objective ids of the mission
+    const DESTROY_TARGETS: &'static str = "destroyTargets";
+    const FIND_KEY: &'static str = "findKey";
+    const MISSION_TARGETS: u32 = 4;
+    // Item of the key pickup of the level
+    const KEY_ITEM: &'static str = "Key";
+
+    pub fn new(context: EngineContext) -> Rc<RefCell<Self>> {
+        let entity_manager = context.entity_manager.clone();
+        let mut spawn_manager = SpawnManager::new(context.clone());
+        if let Err(e) = spawn_manager.load(GameMode::LEVEL) {
+            log!("Failed to load level {}: {}", GameMode::LEVEL, e);
+        }
+        let this = Self {
+            context,
+            events: Rc::new(RefCell::new(vec![])),
+            round: Round::new(GameMode::ROUND_DURATION),
+            respawn_timers: vec![],
+            spawn_manager,
+            objective_manager: ObjectiveManager::new(),
+        };
+
+        for target in entity_manager
+            .borrow()
+            .actors_with_tag(EntityManager::TARGET_TAG)
+        {
+            GameMode::register_target(&this.events, target);
+        }
+
+        Rc::new(RefCell::new(this))
+    }
+
+    /// Returns the final score once, when the timer runs out
+    pub fn update(&mut self, delta_time: f32) -> Option<u32> {
+        if self.round.is_over() {
+            return None;
+        }
+
+        for event in self.events.take() {
+            match event {
+                GameEvent::TargetDestroyed { .. } => {
+                    if self.round.on_target_destroyed() {
+                        self.schedule_respawn();
+                    }
+                    self.objective_manager
+                        .add_progress(GameMode::DESTROY_TARGETS, 1);
+                }
+            }
+        }
+        if self.has_key() {
+            self.objective_manager
+                .complete_objective(GameMode::FIND_KEY);
+        }
+        if self.objective_manager.is_complete() {
+            self.round.finish();
+        }
+
+        self.round.update(delta_time);
+        for actor in self.spawn_manager.update(delta_time) {
+            // Targets of the waves score like the others
+            let is_target = self
+                .context
+                .entity_manager
+                .borrow()
+                .has_tag(&actor, EntityManager::TARGET_TAG);
+            if is_target {
+                GameMode::register_target(&self.events, &actor);
+            }
+        }
+
+        if self.round.is_over() {
+            return Some(self.round.score);
+        }
+        None
+    }
+
+    /// Start a new round, bringing back the targets destroyed in the last one
+    pub fn restart(&mut self) {
+        let mut destroyed = self.events.take().len();
+        {
+            let mut timer_manager = self.context.timer_manager.borrow_mut();
+            for handle in self.respawn_timers.drain(..) {
+                if timer_manager.is_scheduled(handle) {
+                    timer_manager.cancel(handle);
+                    destroyed += 1;
+                }
+            }
+        }
+        for _ in 0..destroyed {
+            GameMode::respawn_target(&self.context, &self.events);
+        }
+        self.round = Round::new(GameMode::ROUND_DURATION);
+        self.spawn_manager.reset();
+        self.start_mission();
+    }
+
+    /// Destroy targets, then pick up the key of the level
+    fn start_mission(&mut self) {
+        if let Some(fps_actor) = self.context.entity_manager.borrow().get_fps_actor() {
+            if let Some(inventory) = fps_actor.borrow().get_inventory_component() {
+                inventory.borrow_mut().clear();
+            }
+        }
+
+        self.objective_manager.clear();
+        self.objective_manager.add_objective(
+            GameMode::DESTROY_TARGETS,
+            "objectiveDestroyTargets",
+            GameMode::MISSION_TARGETS,
+        );
+        self.objective_manager
+            .add_objective(GameMode::FIND_KEY, "objectiveFindKey", 1);
+    }
+
+    fn has_key(&self) -> bool {
+        self.context
+            .entity_manager
+            .borrow()
+            .get_fps_actor()
+            .and_then(|fps_actor| fps_actor.borrow().get_inventory_component().cloned())
+            .is_some_and(|inventory| inventory.borrow().get_item_count(GameMode::KEY_ITEM) > 0)
+    }
+
+    pub fn get_objective_manager(&self) -> &ObjectiveManager {
+        &self.objective_manager
+    }
+
+    pub fn get_objective_manager_mut(&mut self) -> &mut ObjectiveManager {
+        &mut self.objective_manager
+    }
+
+    /// Whether the round ended by completing the mission rather than by the timer
+    pub fn is_mission_complete(&self) -> bool {
+        self.objective_manager.is_complete()
+    }
+
+    pub fn get_score(&self) -> u32 {
+        self.round.score
+    }
+
+    pub fn get_time_left(&self) -> f32 {
+        self.round.time_left
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.round.is_over()
+    }
+
+    /// Bring a target back after the delay
+    fn schedule_respawn(&mut self) {
+        let context = self.context.clone();
+        let events = self.events.clone();
+        let mut timer_manager = self.context.timer_manager.borrow_mut();
+        self.respawn_timers
+            .retain(|handle| timer_manager.is_scheduled(*handle));
+        let handle = timer_manager.schedule(
+            GameMode::RESPAWN_DELAY,
+            None,
+            Box::new(move || GameMode::respawn_target(&context, &events)),
+        );
+        self.respawn_timers.push(handle);
+    }
+
+    fn respawn_target(context: &EngineContext, events: &Rc<RefCell<Vec<GameEvent>>>) {
+        let position = context
+            .entity_manager
+            .borrow_mut()
+            .get_random()
+            .get_vector3(GameMode::SPAWN_MIN, GameMode::SPAWN_MAX);
+        let target = EntityManager::spawn_target(context, position);
+        GameMode::register_target(events, &(target as Rc<RefCell<dyn Actor>>));
+    }
+
+    /// Report the death of the target as an event
+    pub fn register_target(events: &Rc<RefCell<Vec<GameEvent>>>, target: &Rc<RefCell<dyn Actor>>) {
+        let events = events.clone();
+        let weak_target = Rc::downgrade(target);
+        let health_component = match target.borrow().get_component::<HealthComponent>() {
+            Some(health_component) => health_component,
+            None => return,
+        };
+        health_component
+            .borrow_mut()
+            .set_on_death(Box::new(move || {
+                // The target may be borrowed by whoever dealt the damage
+                let position = weak_target
+                    .upgrade()
+                    .and_then(|target| Some(target.try_borrow().ok()?.get_position().clone()))
+                    .unwrap_or(Vector3::ZERO);
+                events
+                    .borrow_mut()
+                    .push(GameEvent::TargetDestroyed { position });
+            }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GameMode, Round};
+
+    #[test]
+    fn test_round() {
+        let mut round = Round::new(10.0);
+
+        assert!(round.on_target_destroyed());
+        assert!(round.on_target_destroyed());
+        assert_eq!(2 * GameMode::SCORE_PER_TARGET, round.score);
+
+        round.update(5.0);
+        assert!(!round.is_over());
+
+        round.update(10.0);
+        assert!(round.is_over());
+        assert_eq!(0.0, round.time_left);
+
+        // Nothing counts after the timer ran out
+        assert!(!round.on_target_destroyed());
+        assert_eq!(2 * GameMode::SCORE_PER_TARGET, round.score);
+
+        // Finished early
+        let mut round = Round::new(10.0);
+        round.finish();
+        assert!(round.is_over());
+        round.update(1.0);
+        assert_eq!(10.0, round.time_left);
+    }
+}
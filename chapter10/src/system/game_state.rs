@@ -0,0 +1,103 @@
+/// Screen the game is on. Paused and GameOver belong to a round in progress,
+/// which is kept and drawn behind their menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameState {
+    MainMenu,
+    Gameplay,
+    Paused,
+    GameOver,
+    Quit,
+}
+
+/// Requests from the menu buttons and the game mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    // Begins a new round
+    Start,
+    Pause,
+    Resume,
+    RoundOver,
+    ToMainMenu,
+    Quit,
+}
+
+impl GameState {
+    /// State after the transition, or None if it doesn't apply to this state
+    pub fn next(self, transition: Transition) -> Option<GameState> {
+        match (self, transition) {
+            (GameState::Quit, _) => None,
+            (_, Transition::Quit) => Some(GameState::Quit),
+            (GameState::MainMenu | GameState::Paused | GameState::GameOver, Transition::Start) => {
+                Some(GameState::Gameplay)
+            }
+            (GameState::Gameplay, Transition::Pause) => Some(GameState::Paused),
+            (GameState::Paused, Transition::Resume) => Some(GameState::Gameplay),
+            (GameState::Gameplay, Transition::RoundOver) => Some(GameState::GameOver),
+            (GameState::Paused | GameState::GameOver, Transition::ToMainMenu) => {
+                Some(GameState::MainMenu)
+            }
+            _ => None,
+        }
+    }
+
+    /// Transition when the menu of the state is closed with Escape
+    pub fn back(self) -> Option<Transition> {
+        match self {
+            GameState::MainMenu => Some(Transition::Quit),
+            GameState::Paused => Some(Transition::Resume),
+            GameState::GameOver => Some(Transition::ToMainMenu),
+            GameState::Gameplay | GameState::Quit => None,
+        }
+    }
+
+    /// Whether the actors and the round are updated
+    pub fn is_playing(self) -> bool {
+        self == GameState::Gameplay
+    }
+
+    pub fn is_running(self) -> bool {
+        self != GameState::Quit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GameState, Transition};
+
+    #[test]
+    fn test_next() {
+        let mut state = GameState::MainMenu;
+        for (transition, expected) in [
+            (Transition::Start, GameState::Gameplay),
+            (Transition::Pause, GameState::Paused),
+            (Transition::Resume, GameState::Gameplay),
+            (Transition::RoundOver, GameState::GameOver),
+            (Transition::Start, GameState::Gameplay),
+            (Transition::Pause, GameState::Paused),
+            (Transition::ToMainMenu, GameState::MainMenu),
+            (Transition::Quit, GameState::Quit),
+        ] {
+            state = state.next(transition).unwrap();
+            assert_eq!(expected, state);
+        }
+    }
+
+    #[test]
+    fn test_next_invalid() {
+        assert_eq!(None, GameState::MainMenu.next(Transition::Pause));
+        assert_eq!(None, GameState::Gameplay.next(Transition::Start));
+        assert_eq!(None, GameState::Gameplay.next(Transition::Resume));
+        assert_eq!(None, GameState::GameOver.next(Transition::Resume));
+        assert_eq!(None, GameState::Paused.next(Transition::RoundOver));
+        assert_eq!(None, GameState::Quit.next(Transition::Start));
+    }
+
+    #[test]
+    fn test_back() {
+        for state in [GameState::MainMenu, GameState::Paused, GameState::GameOver] {
+            let transition = state.back().unwrap();
+            assert!(state.next(transition).is_some());
+        }
+        assert_eq!(None, GameState::Gameplay.back());
+    }
+}
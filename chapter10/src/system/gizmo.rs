@@ -0,0 +1,168 @@
+use std::f32::consts::TAU;
+
+use crate::{
+    graphics::{color::Color, line_batch::DebugLine},
+    math::{vector2::Vector2, vector3::Vector3},
+};
+
+/// Handles drawn around the selected actor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoKind {
+    // An arrow per axis
+    Translate,
+    // A circle around each axis
+    Rotate,
+}
+
+pub const AXES: [Vector3; 3] = [Vector3::UNIT_X, Vector3::UNIT_Y, Vector3::UNIT_Z];
+
+const AXIS_COLORS: [Color; 3] = [
+    Color::rgb(1.0, 0.2, 0.2),
+    Color::rgb(0.2, 1.0, 0.2),
+    Color::rgb(0.2, 0.4, 1.0),
+];
+
+// Color of the axis being dragged
+const ACTIVE_COLOR: Color = Color::YELLOW;
+
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// World space segments making up the handle of the axis
+pub fn handle_segments(
+    kind: GizmoKind,
+    center: &Vector3,
+    size: f32,
+    axis: usize,
+) -> Vec<(Vector3, Vector3)> {
+    match kind {
+        GizmoKind::Translate => {
+            vec![(center.clone(), center.clone() + AXES[axis].clone() * size)]
+        }
+        GizmoKind::Rotate => {
+            // The circle lies in the plane of the two other axes
+            let u = AXES[(axis + 1) % 3].clone();
+            let v = AXES[(axis + 2) % 3].clone();
+            let point = |i: usize| {
+                let angle = TAU * i as f32 / CIRCLE_SEGMENTS as f32;
+                center.clone() + u.clone() * (angle.cos() * size) + v.clone() * (angle.sin() * size)
+            };
+            (0..CIRCLE_SEGMENTS)
+                .map(|i| (point(i), point(i + 1)))
+                .collect()
+        }
+    }
+}
+
+pub fn lines(
+    kind: GizmoKind,
+    center: &Vector3,
+    size: f32,
+    active: Option<usize>,
+) -> Vec<DebugLine> {
+    (0..AXES.len())
+        .flat_map(|axis| {
+            let color = if active == Some(axis) {
+                ACTIVE_COLOR
+            } else {
+                AXIS_COLORS[axis]
+            };
+            handle_segments(kind, center, size, axis)
+                .into_iter()
+                .map(move |(start, end)| DebugLine::new(start, end, color))
+        })
+        .collect()
+}
+
+pub fn distance_to_segment(point: &Vector2, start: &Vector2, end: &Vector2) -> f32 {
+    let segment = end.clone() - start.clone();
+    let length_sq = segment.length_sq();
+    let t = if length_sq > 0.0 {
+        ((point.clone() - start.clone()).dot(&segment) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let closest = start.clone() + segment * t;
+    (point.clone() - closest).length()
+}
+
+/// World distance along an axis for a mouse movement, given the axis handle of
+/// world_length is screen_axis long on screen
+pub fn drag_along(delta: &Vector2, screen_axis: &Vector2, world_length: f32) -> f32 {
+    let length_sq = screen_axis.length_sq();
+    if length_sq < 1.0 {
+        // Pointing at the camera, can't tell the direction
+        return 0.0;
+    }
+    delta.dot(screen_axis) / length_sq * world_length
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        assert_near_eq,
+        math::{vector2::Vector2, vector3::Vector3},
+    };
+
+    use super::{distance_to_segment, drag_along, handle_segments, GizmoKind};
+
+    #[test]
+    fn test_handle_segments() {
+        let center = Vector3::new(10.0, 0.0, 0.0);
+        let arrow = handle_segments(GizmoKind::Translate, &center, 5.0, 2);
+        assert_eq!(vec![(center.clone(), Vector3::new(10.0, 0.0, 5.0))], arrow);
+
+        // Closed circle around the axis
+        let circle = handle_segments(GizmoKind::Rotate, &center, 5.0, 2);
+        let first = &circle.first().unwrap().0;
+        let last = &circle.last().unwrap().1;
+        assert_near_eq!(0.0, (first.clone() - last.clone()).length(), 0.001);
+        for (start, _) in &circle {
+            assert_near_eq!(0.0, start.z, 0.001);
+            assert_near_eq!(5.0, (start.clone() - center.clone()).length(), 0.001);
+        }
+    }
+
+    #[test]
+    fn test_distance_to_segment() {
+        let start = Vector2::new(0.0, 0.0);
+        let end = Vector2::new(10.0, 0.0);
+
+        assert_near_eq!(
+            3.0,
+            distance_to_segment(&Vector2::new(5.0, 3.0), &start, &end),
+            0.001
+        );
+        // Past the ends, distance to the closest end
+        assert_near_eq!(
+            5.0,
+            distance_to_segment(&Vector2::new(13.0, 4.0), &start, &end),
+            0.001
+        );
+        assert_near_eq!(
+            2.0,
+            distance_to_segment(&Vector2::new(0.0, 2.0), &start, &start),
+            0.001
+        );
+    }
+
+    #[test]
+    fn test_drag_along() {
+        // Handle of 100 units is 50 pixels long on screen
+        let screen_axis = Vector2::new(50.0, 0.0);
+        assert_near_eq!(
+            20.0,
+            drag_along(&Vector2::new(10.0, 7.0), &screen_axis, 100.0),
+            0.001
+        );
+        assert_near_eq!(
+            -20.0,
+            drag_along(&Vector2::new(-10.0, 0.0), &screen_axis, 100.0),
+            0.001
+        );
+
+        assert_eq!(
+            0.0,
+            drag_along(&Vector2::new(10.0, 0.0), &Vector2::ZERO, 100.0)
+        );
+    }
+}
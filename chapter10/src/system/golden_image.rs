@@ -0,0 +1,324 @@
+use std::{cell::RefCell, f32, path::PathBuf, rc::Rc};
+
+use anyhow::{anyhow, Result};
+use image::RgbaImage;
+
+use crate::{
+    actors::actor::{Actor, DefaultActor, State},
+    components::{
+        billboard_component::{BillboardComponent, BillboardMode},
+        mesh_component::MeshComponent,
+        sprite_component::{DefaultSpriteComponent, SpriteComponent},
+    },
+    graphics::color::Color,
+    math::{matrix4::Matrix4, quaternion::Quaternion, vector3::Vector3},
+};
+
+use super::{
+    audio_system::AudioSystem, config::Config, crash_report::log, engine_context::EngineContext,
+    entity_manager::EntityManager, phys_world::PhysWorld, renderer::Renderer,
+    script_manager::ScriptManager, timer_manager::TimerManager,
+};
+
+/// Settings of a golden image run, from the command line: --golden [--update]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenOptions {
+    // Save the captures as the new references instead of comparing them
+    pub update: bool,
+}
+
+impl GoldenOptions {
+    /// None when the game is not run with --golden
+    pub fn parse(args: &[String]) -> Option<GoldenOptions> {
+        if !args.iter().any(|arg| arg == "--golden") {
+            return None;
+        }
+        Some(GoldenOptions {
+            update: args.iter().any(|arg| arg == "--update"),
+        })
+    }
+}
+
+/// How far a capture is from its reference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageDiff {
+    // Pixels with a channel off by more than the tolerance
+    pub mismatched: usize,
+    pub max_difference: u8,
+    pub total: usize,
+}
+
+impl ImageDiff {
+    /// Whether few enough pixels differ, drivers round colors a bit differently
+    pub fn passes(&self, max_mismatched_fraction: f32) -> bool {
+        self.mismatched as f32 <= self.total as f32 * max_mismatched_fraction
+    }
+}
+
+/// Compare channel by channel, None if the sizes differ
+pub fn compare_images(
+    actual: &RgbaImage,
+    expected: &RgbaImage,
+    tolerance: u8,
+) -> Option<ImageDiff> {
+    if actual.dimensions() != expected.dimensions() {
+        return None;
+    }
+
+    let mut diff = ImageDiff {
+        mismatched: 0,
+        max_difference: 0,
+        total: (actual.width() * actual.height()) as usize,
+    };
+    for (a, e) in actual.pixels().zip(expected.pixels()) {
+        let difference =
+            a.0.iter()
+                .zip(e.0.iter())
+                .map(|(a, e)| a.abs_diff(*e))
+                .max()
+                .unwrap_or(0);
+        diff.max_difference = diff.max_difference.max(difference);
+        if difference > tolerance {
+            diff.mismatched += 1;
+        }
+    }
+    Some(diff)
+}
+
+/// Known scenes rendered by the golden image run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenScene {
+    Sprite,
+    LitCube,
+    ParticleBurst,
+}
+
+impl GoldenScene {
+    pub const ALL: [GoldenScene; 3] = [
+        GoldenScene::Sprite,
+        GoldenScene::LitCube,
+        GoldenScene::ParticleBurst,
+    ];
+    // Particles of the burst, spread evenly on a sphere
+    const PARTICLE_COUNT: usize = 24;
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            GoldenScene::Sprite => "Sprite",
+            GoldenScene::LitCube => "LitCube",
+            GoldenScene::ParticleBurst => "ParticleBurst",
+        }
+    }
+
+    /// Place the camera, the lights and the actors of the scene
+    fn build(&self, context: &EngineContext) -> Vec<Rc<RefCell<DefaultActor>>> {
+        {
+            let mut renderer = context.renderer.borrow_mut();
+            renderer.set_view_matrix(Matrix4::create_look_at(
+                &Vector3::new(-400.0, 0.0, 200.0),
+                &Vector3::ZERO,
+                &Vector3::UNIT_Z,
+            ));
+            renderer.set_ambient_light(Color::rgb(0.2, 0.2, 0.2));
+            let directional_light = renderer.get_directional_light_mut();
+            directional_light.direction = Vector3::new(0.0, -0.707, -0.707);
+            directional_light.diffuse_color = Color::rgb(0.78, 0.88, 1.0);
+            directional_light.spec_color = Color::rgb(0.8, 0.8, 0.8);
+        }
+
+        match self {
+            GoldenScene::Sprite => {
+                let texture = context.asset_manager.borrow_mut().get_texture("Radar.png");
+                let actor = DefaultActor::new(context);
+                let sprite = DefaultSpriteComponent::new(actor.clone(), 100);
+                sprite.borrow_mut().set_texture(texture);
+                vec![actor]
+            }
+            GoldenScene::LitCube => {
+                let mesh = context.asset_manager.borrow_mut().get_mesh("Cube.gpmesh");
+                let actor = DefaultActor::new(context);
+                actor.borrow_mut().set_scale(100.0);
+                // Turned so three faces catch the light differently
+                actor.borrow_mut().set_rotation(
+                    Quaternion::from_axis_angle(&Vector3::UNIT_Z, f32::consts::FRAC_PI_4)
+                        .concatenate(&Quaternion::from_axis_angle(&Vector3::UNIT_Y, 0.3)),
+                );
+                MeshComponent::new(actor.clone())
+                    .borrow_mut()
+                    .set_mesh(mesh);
+                vec![actor]
+            }
+            GoldenScene::ParticleBurst => {
+                let texture = context.asset_manager.borrow_mut().get_texture("Smoke.png");
+                (0..GoldenScene::PARTICLE_COUNT)
+                    .map(|i| {
+                        // Fibonacci sphere, no randomness so captures are reproducible
+                        let t = (i as f32 + 0.5) / GoldenScene::PARTICLE_COUNT as f32;
+                        let z = 1.0 - 2.0 * t;
+                        let radius = (1.0 - z * z).sqrt();
+                        let angle = i as f32 * f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+                        let direction = Vector3::new(radius * angle.cos(), radius * angle.sin(), z);
+
+                        let actor = DefaultActor::new(context);
+                        actor.borrow_mut().set_position(direction * 120.0);
+                        let billboard = BillboardComponent::new(
+                            actor.clone(),
+                            texture.clone(),
+                            BillboardMode::Spherical,
+                        );
+                        billboard.borrow_mut().set_alpha(1.0 - t * 0.5);
+                        actor
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn get_reference_path(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join("Golden")
+            .join(format!("{}.png", self.get_name()))
+    }
+}
+
+// Size of the captures, references are only valid for this size
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+// Channel difference below which pixels count as equal
+const TOLERANCE: u8 = 8;
+// Fraction of the pixels allowed to differ more, e.g. along edges
+const MAX_MISMATCHED_FRACTION: f32 = 0.001;
+
+/// Render each scene offscreen and compare it with its reference in resources/Golden,
+/// or save it as the reference when updating. Returns whether every scene matched
+pub fn run(options: &GoldenOptions) -> Result<bool> {
+    let mut config = Config::new();
+    config.window_width = WIDTH;
+    config.window_height = HEIGHT;
+
+    let sdl = sdl2::init().map_err(|e| anyhow!(e))?;
+    let video_system = sdl.video().map_err(|e| anyhow!(e))?;
+    let renderer = Renderer::initialize(video_system, &config)?;
+    let asset_manager = renderer.borrow().get_asset_manager().clone();
+    let context = EngineContext {
+        asset_manager: asset_manager.clone(),
+        entity_manager: EntityManager::new(),
+        audio_system: AudioSystem::initialize(asset_manager, &config),
+        renderer,
+        phys_world: PhysWorld::new(),
+        timer_manager: TimerManager::new(),
+        script_manager: ScriptManager::new(),
+    };
+
+    let mut all_passed = true;
+    for scene in GoldenScene::ALL {
+        let actors = scene.build(&context);
+        for actor in &actors {
+            actor.borrow_mut().compute_world_transform();
+        }
+        let capture = context.renderer.borrow().capture(&[], &vec![]);
+
+        // Clear the scene before the next one
+        for actor in &actors {
+            actor.borrow_mut().set_state(State::Dead);
+        }
+        context.entity_manager.borrow_mut().flush_actors();
+        context.asset_manager.borrow_mut().flush_sprites();
+        context.asset_manager.borrow_mut().flush_meshes();
+
+        let capture = capture?;
+        let path = scene.get_reference_path();
+        if options.update {
+            if let Some(directory) = path.parent() {
+                std::fs::create_dir_all(directory)?;
+            }
+            capture.save(&path)?;
+            log!("{}: saved {}", scene.get_name(), path.display());
+            continue;
+        }
+
+        let reference = match image::open(&path) {
+            Ok(reference) => reference.to_rgba8(),
+            Err(e) => {
+                log!("{}: FAILED, no reference ({})", scene.get_name(), e);
+                all_passed = false;
+                continue;
+            }
+        };
+        match compare_images(&capture, &reference, TOLERANCE) {
+            Some(diff) if diff.passes(MAX_MISMATCHED_FRACTION) => {
+                log!("{}: ok", scene.get_name())
+            }
+            Some(diff) => {
+                log!(
+                    "{}: FAILED, {} of {} pixels differ (max difference {})",
+                    scene.get_name(),
+                    diff.mismatched,
+                    diff.total,
+                    diff.max_difference
+                );
+                all_passed = false;
+            }
+            None => {
+                log!(
+                    "{}: FAILED, captured {:?} but the reference is {:?}",
+                    scene.get_name(),
+                    capture.dimensions(),
+                    reference.dimensions()
+                );
+                all_passed = false;
+            }
+        }
+    }
+
+    Ok(all_passed)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::{compare_images, GoldenOptions};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(None, GoldenOptions::parse(&args("game --bench")));
+        assert_eq!(
+            Some(GoldenOptions { update: false }),
+            GoldenOptions::parse(&args("game --golden"))
+        );
+        assert_eq!(
+            Some(GoldenOptions { update: true }),
+            GoldenOptions::parse(&args("game --golden --update"))
+        );
+    }
+
+    #[test]
+    fn test_compare_images() {
+        let expected = RgbaImage::from_pixel(10, 10, Rgba([100, 150, 200, 255]));
+        let mut actual = expected.clone();
+
+        let diff = compare_images(&actual, &expected, 8).unwrap();
+        assert_eq!(0, diff.mismatched);
+        assert!(diff.passes(0.0));
+
+        // Within the tolerance
+        actual.put_pixel(0, 0, Rgba([105, 150, 200, 255]));
+        // Beyond it
+        actual.put_pixel(1, 0, Rgba([100, 150, 230, 255]));
+        let diff = compare_images(&actual, &expected, 8).unwrap();
+        assert_eq!(1, diff.mismatched);
+        assert_eq!(30, diff.max_difference);
+        assert_eq!(100, diff.total);
+        assert!(diff.passes(0.01));
+        assert!(!diff.passes(0.001));
+
+        let smaller = RgbaImage::new(5, 5);
+        assert_eq!(None, compare_images(&smaller, &expected, 8));
+    }
+}
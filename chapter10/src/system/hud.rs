@@ -0,0 +1,366 @@
+use std::{cell::RefCell, ptr::null, rc::Rc};
+
+use gl::{TRIANGLES, UNSIGNED_INT};
+
+use crate::{
+    actors::{actor::Actor, fps_actor::FPSActor},
+    graphics::{color::Color, shader::Shader, texture::Texture},
+    math::{matrix4::Matrix4, vector2::Vector2, vector3::Vector3},
+};
+
+use super::{
+    asset_manager::AssetManager,
+    objective_manager::Objective,
+    tween::{Ease, Tween},
+};
+
+struct DamageIndicator {
+    // World position the damage came from
+    source: Vector3,
+    // Clockwise angle from the top of the screen
+    angle: f32,
+    time_left: f32,
+}
+
+/// Screen space overlay drawn on top of all sprites
+pub struct HUD {
+    crosshair: Rc<Texture>,
+    hit_marker: Rc<Texture>,
+    damage_arrow: Rc<Texture>,
+    radar: Rc<Texture>,
+    radar_arrow: Rc<Texture>,
+    blip: Rc<Texture>,
+    vignette: Texture,
+    hit_marker_time: f32,
+    damage_indicators: Vec<DamageIndicator>,
+    // Blip offsets relative to the radar center
+    blips: Vec<Vector2>,
+    // The player looks at something usable, the cross-hair grows
+    is_interact_focused: bool,
+    // Progress and required steps of the current objective, one pip each
+    objective_progress: Option<(u32, u32)>,
+    // Scale of the cross-hair, punched by hits
+    crosshair_punch: Tween,
+    // Scale of the last pip done, punched when it's done
+    pip_punch: Tween,
+    // Opacity of the red edges, flashed by damage
+    damage_vignette: Tween,
+}
+
+impl HUD {
+    const HIT_MARKER_DURATION: f32 = 0.15;
+    // Cross-hair scale over an interactable
+    const INTERACT_CROSSHAIR_SCALE: f32 = 1.5;
+    const INTERACT_CROSSHAIR_TINT: u32 = 0xFFD24AFF;
+    const DAMAGE_INDICATOR_DURATION: f32 = 1.5;
+    // Distance of damage arrows from screen center
+    const DAMAGE_INDICATOR_RADIUS: f32 = 150.0;
+    // Radar center from the bottom right corner of the view
+    const RADAR_MARGIN: Vector2 = Vector2::new(137.0, 109.0);
+    const RADAR_SCALE: f32 = 0.75;
+    // World distance covered by the radar
+    const RADAR_RANGE: f32 = 2000.0;
+    // Radius of the radar texture in pixels
+    const RADAR_RADIUS: f32 = 92.0 * HUD::RADAR_SCALE;
+    // Objective pips from the top center of the view
+    const OBJECTIVE_MARGIN: f32 = 40.0;
+    const OBJECTIVE_PIP_SPACING: f32 = 24.0;
+    // Scale of the pips of the steps still to do
+    const OBJECTIVE_PIP_TODO_SCALE: f32 = 0.5;
+    // Scales the punches start from, settling back to 1
+    const CROSSHAIR_PUNCH_SCALE: f32 = 1.3;
+    const PIP_PUNCH_SCALE: f32 = 2.0;
+    const PUNCH_TIME: f32 = 0.3;
+    const DAMAGE_VIGNETTE_ALPHA: f32 = 0.6;
+    const DAMAGE_VIGNETTE_TIME: f32 = 1.0;
+
+    pub fn new(asset_manager: Rc<RefCell<AssetManager>>) -> Rc<RefCell<Self>> {
+        let mut borrowed_asset_manager = asset_manager.borrow_mut();
+        let this = Self {
+            crosshair: borrowed_asset_manager.get_texture("Crosshair.png"),
+            hit_marker: borrowed_asset_manager.get_texture("CrosshairRed.png"),
+            damage_arrow: borrowed_asset_manager.get_texture("RadarArrow.png"),
+            radar: borrowed_asset_manager.get_texture("Radar.png"),
+            radar_arrow: borrowed_asset_manager.get_texture("RadarArrow.png"),
+            blip: borrowed_asset_manager.get_texture("Blip.png"),
+            vignette: Texture::create_vignette(),
+            hit_marker_time: 0.0,
+            damage_indicators: vec![],
+            blips: vec![],
+            is_interact_focused: false,
+            objective_progress: None,
+            crosshair_punch: Tween::at(1.0),
+            pip_punch: Tween::at(1.0),
+            damage_vignette: Tween::at(0.0),
+        };
+
+        Rc::new(RefCell::new(this))
+    }
+
+    pub fn update(
+        &mut self,
+        delta_time: f32,
+        fps_actor: &FPSActor,
+        targets: &[Rc<RefCell<dyn Actor>>],
+    ) {
+        self.hit_marker_time = (self.hit_marker_time - delta_time).max(0.0);
+        self.crosshair_punch.update(delta_time);
+        self.pip_punch.update(delta_time);
+        self.damage_vignette.update(delta_time);
+        self.is_interact_focused = fps_actor.get_focused_interactable().is_some();
+
+        for event in fps_actor
+            .get_health_component()
+            .unwrap()
+            .borrow_mut()
+            .take_damage_events()
+        {
+            self.damage_vignette = Tween::new(
+                HUD::DAMAGE_VIGNETTE_ALPHA,
+                0.0,
+                HUD::DAMAGE_VIGNETTE_TIME,
+                Ease::InQuad,
+            );
+            self.damage_indicators.push(DamageIndicator {
+                source: event.source,
+                angle: 0.0,
+                time_left: HUD::DAMAGE_INDICATOR_DURATION,
+            });
+        }
+
+        // Arrows keep pointing at the attacker while the player turns
+        let position = fps_actor.get_position();
+        let forward = fps_actor.get_forward();
+        let right = fps_actor.get_right();
+        for indicator in self.damage_indicators.iter_mut() {
+            indicator.time_left -= delta_time;
+            indicator.angle = compute_damage_angle(position, &forward, &right, &indicator.source);
+        }
+        self.damage_indicators
+            .retain(|indicator| indicator.time_left > 0.0);
+
+        self.blips.clear();
+        for target in targets {
+            let blip = compute_blip_position(
+                position,
+                &forward,
+                &right,
+                target.borrow().get_position(),
+                HUD::RADAR_RANGE,
+                HUD::RADAR_RADIUS,
+            );
+            self.blips.push(blip);
+        }
+    }
+
+    /// Show the progress of the objective, None hides it
+    pub fn set_objective(&mut self, objective: Option<&Objective>) {
+        let objective_progress =
+            objective.map(|objective| (objective.progress, objective.required));
+        if is_step_done(self.objective_progress, objective_progress) {
+            self.pip_punch = HUD::punch(HUD::PIP_PUNCH_SCALE);
+        }
+        self.objective_progress = objective_progress;
+    }
+
+    /// Flash the hit marker (called when a shot hits a target)
+    pub fn notify_hit(&mut self) {
+        self.hit_marker_time = HUD::HIT_MARKER_DURATION;
+        self.crosshair_punch = HUD::punch(HUD::CROSSHAIR_PUNCH_SCALE);
+    }
+
+    fn punch(scale: f32) -> Tween {
+        Tween::new(scale, 1.0, HUD::PUNCH_TIME, Ease::OutBack)
+    }
+
+    /// Expects the sprite shader and sprite verts to be active, with the view of the given size
+    pub fn draw(&self, shader: &Shader, view_size: &Vector2) {
+        // Under the rest, so the cross-hair stays readable
+        let vignette_alpha = self.damage_vignette.get_value();
+        if vignette_alpha > 0.0 {
+            shader.set_color_uniform("uTint", &Color::WHITE.with_alpha(vignette_alpha));
+            shader.set_matrix_uniform(
+                "uWorldTransform",
+                Matrix4::create_scale_xyz(view_size.x, view_size.y, 1.0),
+            );
+            self.vignette.set_active();
+            unsafe {
+                gl::DrawElements(TRIANGLES, 6, UNSIGNED_INT, null());
+            }
+            shader.set_color_uniform("uTint", &Color::WHITE);
+        }
+
+        let mut crosshair_scale = self.crosshair_punch.get_value();
+        if self.is_interact_focused {
+            crosshair_scale *= HUD::INTERACT_CROSSHAIR_SCALE;
+            shader.set_color_uniform("uTint", &Color::from_hex(HUD::INTERACT_CROSSHAIR_TINT));
+        }
+        HUD::draw_texture(
+            shader,
+            &self.crosshair,
+            &Vector2::ZERO,
+            crosshair_scale,
+            0.0,
+        );
+        shader.set_color_uniform("uTint", &Color::WHITE);
+
+        if self.hit_marker_time > 0.0 {
+            HUD::draw_texture(shader, &self.hit_marker, &Vector2::ZERO, 1.0, 0.0);
+        }
+
+        for indicator in &self.damage_indicators {
+            let offset = Vector2::new(indicator.angle.sin(), indicator.angle.cos())
+                * HUD::DAMAGE_INDICATOR_RADIUS;
+            // Arrow texture points up, create_rotation_z rotates counterclockwise
+            HUD::draw_texture(shader, &self.damage_arrow, &offset, 1.0, -indicator.angle);
+        }
+
+        // Radar, with the arrow showing the player in the center
+        let radar_position = Vector2::new(
+            view_size.x / 2.0 - HUD::RADAR_MARGIN.x,
+            HUD::RADAR_MARGIN.y - view_size.y / 2.0,
+        );
+        HUD::draw_texture(shader, &self.radar, &radar_position, HUD::RADAR_SCALE, 0.0);
+        for blip in &self.blips {
+            let offset = radar_position.clone() + blip.clone();
+            HUD::draw_texture(shader, &self.blip, &offset, 1.0, 0.0);
+        }
+        HUD::draw_texture(shader, &self.radar_arrow, &radar_position, 1.0, 0.0);
+
+        // Objective progress, done steps at full size
+        if let Some((progress, required)) = self.objective_progress {
+            let y = view_size.y / 2.0 - HUD::OBJECTIVE_MARGIN;
+            let start_x = -(required as f32 - 1.0) * HUD::OBJECTIVE_PIP_SPACING / 2.0;
+            for i in 0..required {
+                let offset = Vector2::new(start_x + i as f32 * HUD::OBJECTIVE_PIP_SPACING, y);
+                let scale = if i + 1 == progress {
+                    self.pip_punch.get_value()
+                } else if i < progress {
+                    1.0
+                } else {
+                    HUD::OBJECTIVE_PIP_TODO_SCALE
+                };
+                HUD::draw_texture(shader, &self.blip, &offset, scale, 0.0);
+            }
+        }
+    }
+
+    fn draw_texture(shader: &Shader, texture: &Texture, offset: &Vector2, scale: f32, angle: f32) {
+        // Scale the quad by the width/height of texture
+        let scale_mat = Matrix4::create_scale_xyz(
+            texture.get_width() as f32 * scale,
+            texture.get_height() as f32 * scale,
+            1.0,
+        );
+        let rotation = Matrix4::create_rotation_z(angle);
+        // Translate to position on screen
+        let translation = Matrix4::create_translation(&Vector3::new(offset.x, offset.y, 0.0));
+
+        shader.set_matrix_uniform("uWorldTransform", scale_mat * rotation * translation);
+        texture.set_active();
+
+        unsafe {
+            gl::DrawElements(TRIANGLES, 6, UNSIGNED_INT, null());
+        }
+    }
+}
+
+/// The same objective made progress, so its last pip was just done
+fn is_step_done(previous: Option<(u32, u32)>, current: Option<(u32, u32)>) -> bool {
+    match (previous, current) {
+        (Some((previous, previous_required)), Some((progress, required))) => {
+            required == previous_required && progress > previous
+        }
+        _ => false,
+    }
+}
+
+/// Clockwise angle on screen (0 is straight up) from the player towards the damage source
+fn compute_damage_angle(
+    position: &Vector3,
+    forward: &Vector3,
+    right: &Vector3,
+    source: &Vector3,
+) -> f32 {
+    let to_source = source.clone() - position.clone();
+    Vector3::dot(&to_source, right).atan2(Vector3::dot(&to_source, forward))
+}
+
+/// Offset from the radar center (up is the player's forward) of a target.
+/// Targets out of range are clamped to the radar edge.
+fn compute_blip_position(
+    position: &Vector3,
+    forward: &Vector3,
+    right: &Vector3,
+    target: &Vector3,
+    range: f32,
+    radius: f32,
+) -> Vector2 {
+    let to_target = target.clone() - position.clone();
+    let mut blip = Vector2::new(
+        Vector3::dot(&to_target, right),
+        Vector3::dot(&to_target, forward),
+    );
+    if blip.length_sq() > range * range {
+        blip.normalize_mut();
+        blip *= range;
+    }
+    blip * (radius / range)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use crate::{
+        assert_near_eq,
+        math::{vector2::Vector2, vector3::Vector3},
+    };
+
+    use super::{compute_blip_position, compute_damage_angle, is_step_done};
+
+    #[test]
+    fn test_compute_damage_angle() {
+        let position = Vector3::new(100.0, 100.0, 0.0);
+        let forward = Vector3::UNIT_X;
+        let right = Vector3::UNIT_Y;
+
+        let ahead = Vector3::new(500.0, 100.0, 50.0);
+        let angle = compute_damage_angle(&position, &forward, &right, &ahead);
+        assert_near_eq!(0.0, angle, 0.001);
+
+        let to_right = Vector3::new(100.0, 300.0, 0.0);
+        let angle = compute_damage_angle(&position, &forward, &right, &to_right);
+        assert_near_eq!(FRAC_PI_2, angle, 0.001);
+
+        let behind = Vector3::new(-100.0, 100.0, 0.0);
+        let angle = compute_damage_angle(&position, &forward, &right, &behind);
+        assert_near_eq!(PI, angle.abs(), 0.001);
+    }
+
+    #[test]
+    fn test_is_step_done() {
+        assert!(is_step_done(Some((1, 3)), Some((2, 3))));
+        assert!(!is_step_done(Some((2, 3)), Some((2, 3))));
+        // A new objective starts without a punch
+        assert!(!is_step_done(Some((3, 3)), Some((1, 2))));
+        assert!(!is_step_done(None, Some((1, 3))));
+    }
+
+    #[test]
+    fn test_compute_blip_position() {
+        let position = Vector3::new(100.0, 0.0, 0.0);
+        // Facing +y, so +x is on the left
+        let forward = Vector3::UNIT_Y;
+        let right = Vector3::NEGATIVE_UNIT_X;
+
+        let ahead = Vector3::new(100.0, 1000.0, 200.0);
+        let blip = compute_blip_position(&position, &forward, &right, &ahead, 2000.0, 100.0);
+        assert_eq!(Vector2::new(0.0, 50.0), blip);
+
+        let far_left = Vector3::new(10100.0, 0.0, 0.0);
+        let blip = compute_blip_position(&position, &forward, &right, &far_left, 2000.0, 100.0);
+        assert_near_eq!(-100.0, blip.x, 0.001);
+        assert_near_eq!(0.0, blip.y, 0.001);
+    }
+}
@@ -0,0 +1,566 @@
+use std::{cell::RefCell, rc::Rc};
+
+use sdl2::{
+    keyboard::{Mod, Scancode},
+    mouse::MouseButton,
+};
+
+use crate::{
+    actors::actor::{Actor, State},
+    graphics::color::Color,
+    math::{self, quaternion::Quaternion, vector2::Vector2, vector3::Vector3},
+};
+
+use super::{
+    command::{
+        ChangeLight, CommandHistory, DeleteTarget, Lighting, SpawnTarget, TargetSpawner, Transform,
+        TransformActor,
+    },
+    crash_report::log,
+    engine_context::EngineContext,
+    entity_manager::EntityManager,
+    gizmo::{self, GizmoKind},
+    input_system::{ButtonState, InputState},
+    renderer::Renderer,
+};
+
+/// What the arrow keys change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditMode {
+    Position,
+    Rotation,
+    Scale,
+    // Directional light direction, and the ambient light along z
+    Light,
+}
+
+/// Debug editor listing the actors in the console and editing them with the keyboard.
+/// The game is paused while it's open.
+///
+/// PageUp/PageDown select an actor, 1-4 pick the edit mode, Left/Right, Down/Up and
+/// Comma/Period change the x, y and z axes (ten times faster with Shift), Insert spawns
+/// a target in front of the player, Delete removes the selected actor and P prints the list.
+/// Ctrl+Z undoes the last edit and Ctrl+Y redoes it.
+///
+/// Clicking an actor selects it, and dragging the gizmo handles around it moves, rotates
+/// or scales it depending on the edit mode.
+pub struct Inspector {
+    context: EngineContext,
+    // The player can't be deleted
+    player: Rc<RefCell<dyn Actor>>,
+    active: bool,
+    selected: usize,
+    mode: EditMode,
+    drag: Option<Drag>,
+    // Forgotten when closed, the game may have changed the actors since
+    history: CommandHistory,
+}
+
+/// Gizmo handle held with the mouse
+struct Drag {
+    axis: usize,
+    // Screen position on the previous frame
+    last_position: Vector2,
+    // Recorded as one edit on release
+    before: Transform,
+}
+
+impl Inspector {
+    const MOVE_STEP: f32 = 10.0;
+    // Degrees
+    const ROTATE_STEP: f32 = 15.0;
+    const SCALE_STEP: f32 = 0.1;
+    const MIN_SCALE: f32 = 0.1;
+    const LIGHT_STEP: f32 = 0.05;
+    const FAST_MULTIPLIER: f32 = 10.0;
+    // Distance in front of the player new actors appear at
+    const SPAWN_DISTANCE: f32 = 300.0;
+    // World size of the gizmo handles
+    const GIZMO_SIZE: f32 = 150.0;
+    // Pixels from a handle a click still grabs it
+    const PICK_RADIUS: f32 = 8.0;
+    // Degrees per pixel dragged
+    const DRAG_ROTATE_SPEED: f32 = 0.5;
+
+    pub fn new(context: EngineContext, player: Rc<RefCell<dyn Actor>>) -> Self {
+        Self {
+            context,
+            player,
+            active: false,
+            selected: 0,
+            mode: EditMode::Position,
+            drag: None,
+            history: CommandHistory::new(),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+        self.drag = None;
+        self.history.clear();
+        if self.active {
+            self.print_actors();
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn handle_key(&mut self, key: Scancode, keymod: Mod) {
+        let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+        let fast = keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+        let multiplier = if fast {
+            Inspector::FAST_MULTIPLIER
+        } else {
+            1.0
+        };
+
+        match key {
+            Scancode::Z if ctrl => self.undo(),
+            Scancode::Y if ctrl => self.redo(),
+            Scancode::PageUp => self.select(-1),
+            Scancode::PageDown => self.select(1),
+            Scancode::Num1 => self.set_mode(EditMode::Position),
+            Scancode::Num2 => self.set_mode(EditMode::Rotation),
+            Scancode::Num3 => self.set_mode(EditMode::Scale),
+            Scancode::Num4 => self.set_mode(EditMode::Light),
+            Scancode::Insert => self.spawn(),
+            Scancode::Delete => self.delete_selected(),
+            Scancode::P => self.print_actors(),
+            _ => {
+                if let Some(axis) = axis_of_key(key) {
+                    self.edit(&(axis * multiplier));
+                }
+            }
+        }
+    }
+
+    /// Expects the mouse to be in absolute mode
+    pub fn process_mouse(&mut self, state: &InputState) {
+        let position = self
+            .context
+            .renderer
+            .borrow()
+            .window_to_screen(state.mouse.get_position());
+
+        match state.mouse.get_button_state(MouseButton::Left) {
+            ButtonState::Pressed => {
+                // Grab a handle of the selected actor, or select what's under the mouse
+                match self.pick_handle(&position) {
+                    Some(axis) => {
+                        if let Some(actor) = self.selected_actor() {
+                            self.drag = Some(Drag {
+                                axis,
+                                last_position: position,
+                                before: Transform::of(&*actor.borrow()),
+                            });
+                        }
+                    }
+                    None => self.pick_actor(&position),
+                }
+            }
+            ButtonState::Held => self.drag_to(&position),
+            ButtonState::Released => self.end_drag(),
+            ButtonState::None => {}
+        }
+    }
+
+    /// Queue the gizmo of the selected actor for drawing
+    pub fn submit_gizmo(&self) {
+        let kind = match self.gizmo_kind() {
+            Some(kind) => kind,
+            None => return,
+        };
+        let actor = match self.selected_actor() {
+            Some(actor) => actor,
+            None => return,
+        };
+
+        let center = actor.borrow().get_position().clone();
+        let active = self.drag.as_ref().map(|drag| drag.axis);
+        let renderer = self.context.renderer.borrow();
+        for line in gizmo::lines(kind, &center, Inspector::GIZMO_SIZE, active) {
+            renderer.add_debug_line(line);
+        }
+    }
+
+    fn gizmo_kind(&self) -> Option<GizmoKind> {
+        match self.mode {
+            EditMode::Position | EditMode::Scale => Some(GizmoKind::Translate),
+            EditMode::Rotation => Some(GizmoKind::Rotate),
+            EditMode::Light => None,
+        }
+    }
+
+    /// Axis of the gizmo handle under the screen point
+    fn pick_handle(&self, point: &Vector2) -> Option<usize> {
+        let kind = self.gizmo_kind()?;
+        let center = self.selected_actor()?.borrow().get_position().clone();
+        let renderer = self.context.renderer.borrow();
+
+        let mut closest = None;
+        let mut closest_distance = Inspector::PICK_RADIUS;
+        for axis in 0..gizmo::AXES.len() {
+            for (start, end) in gizmo::handle_segments(kind, &center, Inspector::GIZMO_SIZE, axis) {
+                let (start, end) = match (renderer.project(&start), renderer.project(&end)) {
+                    (Some(start), Some(end)) => (start, end),
+                    _ => continue,
+                };
+                let distance = gizmo::distance_to_segment(
+                    point,
+                    &Vector2::new(start.x, start.y),
+                    &Vector2::new(end.x, end.y),
+                );
+                if distance <= closest_distance {
+                    closest_distance = distance;
+                    closest = Some(axis);
+                }
+            }
+        }
+        closest
+    }
+
+    /// Select the actor whose box is under the screen point
+    fn pick_actor(&mut self, point: &Vector2) {
+        let actor_id = match self.context.phys_world.borrow().pick(
+            &self.context.renderer.borrow(),
+            point.x,
+            point.y,
+        ) {
+            Some(info) => info.actor_id,
+            None => return,
+        };
+        if let Some(index) = self
+            .context
+            .entity_manager
+            .borrow()
+            .get_actors()
+            .iter()
+            .position(|actor| actor.borrow().get_id() == actor_id)
+        {
+            self.selected = index;
+        }
+        self.print_selected();
+    }
+
+    fn drag_to(&mut self, position: &Vector2) {
+        let (axis, delta) = match self.drag.as_mut() {
+            Some(drag) => {
+                let delta = position.clone() - drag.last_position.clone();
+                drag.last_position = position.clone();
+                (drag.axis, delta)
+            }
+            None => return,
+        };
+        let actor = match self.selected_actor() {
+            Some(actor) => actor,
+            None => return,
+        };
+
+        let direction = gizmo::AXES[axis].clone();
+        let amount = match self.mode {
+            EditMode::Rotation => delta.x * Inspector::DRAG_ROTATE_SPEED / Inspector::ROTATE_STEP,
+            _ => {
+                // How far the mouse moved along the handle on screen, in world units
+                let center = actor.borrow().get_position().clone();
+                let tip = center.clone() + direction.clone() * Inspector::GIZMO_SIZE;
+                let renderer = self.context.renderer.borrow();
+                let screen_axis = match (renderer.project(&center), renderer.project(&tip)) {
+                    (Some(center), Some(tip)) => Vector2::new(tip.x - center.x, tip.y - center.y),
+                    _ => return,
+                };
+                let distance = gizmo::drag_along(&delta, &screen_axis, Inspector::GIZMO_SIZE);
+                match self.mode {
+                    EditMode::Scale => distance / Inspector::GIZMO_SIZE / Inspector::SCALE_STEP,
+                    _ => distance / Inspector::MOVE_STEP,
+                }
+            }
+        };
+
+        // Same edits as the keys, in fractions of a step
+        edit_actor(&mut *actor.borrow_mut(), self.mode, &(direction * amount));
+        actor.borrow_mut().compute_world_transform();
+    }
+
+    fn end_drag(&mut self) {
+        let drag = match self.drag.take() {
+            Some(drag) => drag,
+            None => return,
+        };
+        if let Some(actor) = self.selected_actor() {
+            let after = Transform::of(&*actor.borrow());
+            if after != drag.before {
+                self.history
+                    .execute(Box::new(TransformActor::new(actor, drag.before, after)));
+            }
+        }
+        self.print_selected();
+    }
+
+    fn undo(&mut self) {
+        match self.history.undo() {
+            Some(name) => log!("Inspector: undid {}", name),
+            None => log!("Inspector: nothing to undo"),
+        }
+        self.after_history_change();
+    }
+
+    fn redo(&mut self) {
+        match self.history.redo() {
+            Some(name) => log!("Inspector: redid {}", name),
+            None => log!("Inspector: nothing to redo"),
+        }
+        self.after_history_change();
+    }
+
+    /// Actors may have been spawned or deleted
+    fn after_history_change(&mut self) {
+        let count = self.context.entity_manager.borrow().get_actors().len();
+        self.selected = self.selected.min(count.saturating_sub(1));
+        self.print_selected();
+    }
+
+    fn target_spawner(&self) -> TargetSpawner {
+        TargetSpawner {
+            context: self.context.clone(),
+        }
+    }
+
+    fn select(&mut self, step: i32) {
+        let count = self.context.entity_manager.borrow().get_actors().len();
+        self.selected = step_selection(self.selected, step, count);
+        self.print_selected();
+    }
+
+    fn set_mode(&mut self, mode: EditMode) {
+        self.mode = mode;
+        log!("Inspector: editing {:?}", mode);
+    }
+
+    fn selected_actor(&self) -> Option<Rc<RefCell<dyn Actor>>> {
+        self.context
+            .entity_manager
+            .borrow()
+            .get_actors()
+            .get(self.selected)
+            .cloned()
+    }
+
+    fn edit(&mut self, axis: &Vector3) {
+        if self.mode == EditMode::Light {
+            let before = Lighting::of(&self.context.renderer.borrow());
+            edit_light(&mut self.context.renderer.borrow_mut(), axis);
+            let after = Lighting::of(&self.context.renderer.borrow());
+            self.history.execute(Box::new(ChangeLight::new(
+                self.context.renderer.clone(),
+                before,
+                after,
+            )));
+
+            let renderer = self.context.renderer.borrow();
+            let direction = &renderer.get_directional_light().direction;
+            log!(
+                "Light: direction {:?}, ambient {:?}",
+                direction,
+                renderer.get_ambient_light()
+            );
+            return;
+        }
+
+        let actor = match self.selected_actor() {
+            Some(actor) => actor,
+            None => return,
+        };
+        let before = Transform::of(&*actor.borrow());
+        edit_actor(&mut *actor.borrow_mut(), self.mode, axis);
+        let after = Transform::of(&*actor.borrow());
+        self.history
+            .execute(Box::new(TransformActor::new(actor, before, after)));
+        self.print_selected();
+    }
+
+    fn spawn(&mut self) {
+        let position = {
+            let player = self.player.borrow();
+            player.get_position().clone() + player.get_forward() * Inspector::SPAWN_DISTANCE
+        };
+        self.history
+            .execute(Box::new(SpawnTarget::new(self.target_spawner(), position)));
+
+        // Select the new actor, added last since actors aren't updating
+        let count = self.context.entity_manager.borrow().get_actors().len();
+        self.selected = count.saturating_sub(1);
+        self.print_selected();
+    }
+
+    fn delete_selected(&mut self) {
+        let actor = match self.selected_actor() {
+            Some(actor) => actor,
+            None => return,
+        };
+        if Rc::ptr_eq(&actor, &self.player) {
+            log!("Inspector: the player can't be deleted");
+            return;
+        }
+
+        log!("Inspector: deleted {}", actor.borrow().get_type_name());
+        let is_target = self
+            .context
+            .entity_manager
+            .borrow()
+            .has_tag(&actor, EntityManager::TARGET_TAG);
+        if is_target {
+            self.history
+                .execute(Box::new(DeleteTarget::new(self.target_spawner(), actor)));
+        } else {
+            // Only targets can be spawned again
+            log!("Inspector: this can't be undone");
+            actor.borrow_mut().set_state(State::Dead);
+            self.context.entity_manager.borrow_mut().flush_actors();
+        }
+
+        let count = self.context.entity_manager.borrow().get_actors().len();
+        self.selected = self.selected.min(count.saturating_sub(1));
+    }
+
+    fn print_actors(&self) {
+        log!("Inspector: {:?} mode", self.mode);
+        let entity_manager = self.context.entity_manager.borrow();
+        for (index, actor) in entity_manager.get_actors().iter().enumerate() {
+            let marker = if index == self.selected { '>' } else { ' ' };
+            log!("{} {}", marker, describe_actor(index, &*actor.borrow()));
+        }
+    }
+
+    fn print_selected(&self) {
+        if let Some(actor) = self.selected_actor() {
+            log!("> {}", describe_actor(self.selected, &*actor.borrow()));
+        }
+    }
+}
+
+/// Unit step along the axis the key edits
+fn axis_of_key(key: Scancode) -> Option<Vector3> {
+    match key {
+        Scancode::Left => Some(Vector3::NEGATIVE_UNIT_X),
+        Scancode::Right => Some(Vector3::UNIT_X),
+        Scancode::Down => Some(Vector3::NEGATIVE_UNIT_Y),
+        Scancode::Up => Some(Vector3::UNIT_Y),
+        Scancode::Comma => Some(Vector3::NEGATIVE_UNIT_Z),
+        Scancode::Period => Some(Vector3::UNIT_Z),
+        _ => None,
+    }
+}
+
+/// Wraps around at both ends
+fn step_selection(selected: usize, step: i32, count: usize) -> usize {
+    if count == 0 {
+        return 0;
+    }
+    (selected as i32 + step).rem_euclid(count as i32) as usize
+}
+
+fn edit_actor(actor: &mut dyn Actor, mode: EditMode, axis: &Vector3) {
+    match mode {
+        EditMode::Position => {
+            let position = actor.get_position().clone() + axis.clone() * Inspector::MOVE_STEP;
+            actor.set_position(position);
+        }
+        EditMode::Rotation => {
+            let angle = axis.length() * math::basic::to_radians(Inspector::ROTATE_STEP);
+            if angle == 0.0 {
+                return;
+            }
+            // Around the world axis
+            let increment = Quaternion::from_axis_angle(&axis.normalize(), angle);
+            let rotation = actor.get_rotation().concatenate(&increment);
+            actor.set_rotation(rotation);
+        }
+        EditMode::Scale => {
+            // Uniform scale, any axis works
+            let amount = axis.x + axis.y + axis.z;
+            let scale = actor.get_scale() + amount * Inspector::SCALE_STEP;
+            actor.set_scale(scale.max(Inspector::MIN_SCALE));
+        }
+        EditMode::Light => {}
+    }
+}
+
+fn edit_light(renderer: &mut Renderer, axis: &Vector3) {
+    // x and y tilt the light, z changes the ambient level
+    let tilt = Vector3::new(axis.x, axis.y, 0.0) * Inspector::LIGHT_STEP;
+    let light = renderer.get_directional_light_mut();
+    light.direction = (light.direction.clone() + tilt).normalize();
+
+    let ambient = renderer.get_ambient_light();
+    let level = (ambient.r + axis.z * Inspector::LIGHT_STEP).clamp(0.0, 1.0);
+    renderer.set_ambient_light(Color::rgb(level, level, level));
+}
+
+fn describe_actor(index: usize, actor: &dyn Actor) -> String {
+    let components = actor
+        .get_cocmponents()
+        .iter()
+        .map(|component| component.borrow().get_type_name())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let position = actor.get_position();
+    let rotation = actor.get_rotation();
+    format!(
+        "{}: {} position ({:.1}, {:.1}, {:.1}) rotation ({:.2}, {:.2}, {:.2}, {:.2}) scale {:.2} [{}]",
+        index,
+        actor.get_type_name(),
+        position.x,
+        position.y,
+        position.z,
+        rotation.x,
+        rotation.y,
+        rotation.z,
+        rotation.w,
+        actor.get_scale(),
+        components
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        assert_near_eq,
+        math::vector3::Vector3,
+    };
+
+    use super::{edit_actor, step_selection, EditMode, Inspector};
+
+    #[test]
+    fn test_step_selection() {
+        assert_eq!(1, step_selection(0, 1, 3));
+        assert_eq!(0, step_selection(2, 1, 3));
+        assert_eq!(2, step_selection(0, -1, 3));
+        assert_eq!(0, step_selection(0, 1, 0));
+    }
+
+    #[test]
+    fn test_edit_actor() {
+        let mut actor = TestActor::new();
+
+        edit_actor(&mut actor, EditMode::Position, &Vector3::UNIT_Z);
+        assert_eq!(
+            Vector3::new(0.0, 0.0, Inspector::MOVE_STEP),
+            *actor.get_position()
+        );
+
+        // Rotate around z until the actor faces +y
+        for _ in 0..6 {
+            edit_actor(&mut actor, EditMode::Rotation, &Vector3::UNIT_Z);
+        }
+        let forward = actor.get_forward();
+        assert_near_eq!(0.0, forward.x, 0.001);
+        assert_near_eq!(1.0, forward.y, 0.001);
+
+        // Never scaled down to nothing
+        edit_actor(&mut actor, EditMode::Scale, &Vector3::new(-100.0, 0.0, 0.0));
+        assert_eq!(Inspector::MIN_SCALE, actor.get_scale());
+    }
+}
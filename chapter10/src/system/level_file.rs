@@ -0,0 +1,298 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::{collision::aabb::AABB, components::powerup::Powerup, math::vector3::Vector3};
+
+/// What a pickup gives to the player
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickupKind {
+    Health(f32),
+    // Rounds for each weapon
+    Ammo(u32),
+    // Powerup and its duration in seconds
+    Powerup(Powerup, f32),
+    // Item added to the inventory, e.g. a key
+    Item(String),
+}
+
+/// When a wave starts spawning
+#[derive(Debug, Clone, PartialEq)]
+pub enum WaveTrigger {
+    // Seconds after the round started
+    Time(f32),
+    // The player enters the box
+    Area(AABB),
+    // Everything the previous wave spawned is dead
+    Cleared,
+}
+
+/// Wave of a level file
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaveDefinition {
+    // Spawned with EntityManager::spawn_prefab
+    pub prefab: String,
+    // Used in turn
+    pub spawn_points: Vec<String>,
+    pub trigger: WaveTrigger,
+    pub count: u32,
+    // No more spawns while this many are alive
+    pub max_alive: u32,
+    // Seconds between two spawns
+    pub interval: f32,
+}
+
+impl WaveDefinition {
+    pub fn parse(json: &Value) -> Result<Self> {
+        let prefab = json["prefab"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Wave has no prefab"))?
+            .to_string();
+        let spawn_points = json["spawnPoints"]
+            .as_array()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| name.as_str().map(str::to_string))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|names| !names.is_empty())
+            .ok_or_else(|| anyhow!("Wave of {} has no spawn points", prefab))?;
+
+        let trigger = &json["trigger"];
+        let trigger = match trigger["type"].as_str() {
+            Some("Time") => WaveTrigger::Time(trigger["delay"].as_f64().unwrap_or(0.0) as f32),
+            Some("Area") => WaveTrigger::Area(AABB::new(
+                parse_vector3(&trigger["min"])?,
+                parse_vector3(&trigger["max"])?,
+            )),
+            Some("Cleared") => WaveTrigger::Cleared,
+            _ => return Err(anyhow!("Unknown trigger of {}: {}", prefab, trigger)),
+        };
+
+        let count = json["count"].as_u64().unwrap_or(1) as u32;
+        Ok(Self {
+            prefab,
+            spawn_points,
+            trigger,
+            count,
+            max_alive: json["maxAlive"].as_u64().map_or(count, |max| max as u32),
+            interval: json["interval"].as_f64().unwrap_or(0.0) as f32,
+        })
+    }
+}
+
+/// Pickup of a level file
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickupDefinition {
+    pub kind: PickupKind,
+    pub position: Vector3,
+    // Seconds until it comes back once picked up, None if it doesn't
+    pub respawn_time: Option<f32>,
+}
+
+impl PickupDefinition {
+    pub fn parse(json: &Value) -> Result<Self> {
+        let amount = json["amount"].as_f64();
+        let kind = match json["type"].as_str() {
+            Some("Health") => PickupKind::Health(amount.unwrap_or(25.0) as f32),
+            Some("Ammo") => PickupKind::Ammo(amount.unwrap_or(10.0) as u32),
+            Some("Powerup") => {
+                let powerup = json["powerup"]
+                    .as_str()
+                    .and_then(Powerup::from_name)
+                    .ok_or_else(|| anyhow!("Unknown powerup: {}", json["powerup"]))?;
+                let duration = json["duration"].as_f64().unwrap_or(10.0) as f32;
+                PickupKind::Powerup(powerup, duration)
+            }
+            Some("Item") => PickupKind::Item(
+                json["item"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("Item pickup has no item"))?
+                    .to_string(),
+            ),
+            _ => return Err(anyhow!("Unknown pickup type: {}", json["type"])),
+        };
+
+        Ok(Self {
+            kind,
+            position: parse_vector3(&json["position"])?,
+            respawn_time: json["respawn"].as_f64().map(|time| time as f32),
+        })
+    }
+}
+
+fn parse_vector3(json: &Value) -> Result<Vector3> {
+    let values = json
+        .as_array()
+        .filter(|values| values.len() == 3)
+        .and_then(|values| values.iter().map(Value::as_f64).collect::<Option<Vec<_>>>())
+        .ok_or_else(|| anyhow!("Expected [x, y, z]: {}", json))?;
+    Ok(Vector3::new(
+        values[0] as f32,
+        values[1] as f32,
+        values[2] as f32,
+    ))
+}
+
+/// Contents of resources/Levels/<name>.json, see SpawnManager::load
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelFile {
+    // Name and position of each spawn point
+    pub spawn_points: Vec<(String, Vector3)>,
+    pub waves: Vec<WaveDefinition>,
+    pub pickups: Vec<PickupDefinition>,
+    // Replaces the default world bounds if there is one
+    pub world_bounds: Option<AABB>,
+    pub checkpoints: Vec<Vector3>,
+}
+
+impl LevelFile {
+    /// Every part is optional, a level without waves is only the arena
+    pub fn parse(json: &Value) -> Result<Self> {
+        let spawn_points = json["spawnPoints"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|spawn_point| {
+                let name = spawn_point["name"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("Spawn point has no name"))?;
+                Ok((name.to_string(), parse_vector3(&spawn_point["position"])?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let waves = json["waves"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(WaveDefinition::parse)
+            .collect::<Result<Vec<_>>>()?;
+        let pickups = json["pickups"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(PickupDefinition::parse)
+            .collect::<Result<Vec<_>>>()?;
+
+        let bounds = &json["worldBounds"];
+        let world_bounds = if bounds.is_null() {
+            None
+        } else {
+            Some(AABB::new(
+                parse_vector3(&bounds["min"])?,
+                parse_vector3(&bounds["max"])?,
+            ))
+        };
+        let checkpoints = json["checkpoints"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(parse_vector3)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            spawn_points,
+            waves,
+            pickups,
+            world_bounds,
+            checkpoints,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{components::powerup::Powerup, math::vector3::Vector3};
+
+    use super::{LevelFile, PickupDefinition, PickupKind, WaveDefinition, WaveTrigger};
+
+    #[test]
+    fn test_parse_wave() {
+        let json = json!({
+            "prefab": "Enemy",
+            "spawnPoints": ["Left", "Right"],
+            "trigger": { "type": "Area", "min": [0.0, -100.0, 0.0], "max": [100.0, 100.0, 50.0] },
+            "count": 4,
+            "interval": 2.0
+        });
+        let definition = WaveDefinition::parse(&json).unwrap();
+        assert_eq!("Enemy", definition.prefab);
+        assert_eq!(vec!["Left", "Right"], definition.spawn_points);
+        match &definition.trigger {
+            WaveTrigger::Area(area) => assert_eq!(Vector3::new(100.0, 100.0, 50.0), area.max),
+            trigger => panic!("Unexpected trigger {:?}", trigger),
+        }
+        // Everything at once if there's no cap
+        assert_eq!(4, definition.max_alive);
+
+        assert!(WaveDefinition::parse(&json!({ "prefab": "Enemy", "spawnPoints": [] })).is_err());
+        assert!(WaveDefinition::parse(&json!({
+            "prefab": "Enemy",
+            "spawnPoints": ["Left"],
+            "trigger": { "type": "Never" }
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_pickup() {
+        let definition = PickupDefinition::parse(&json!({
+            "type": "Powerup",
+            "powerup": "Haste",
+            "duration": 8.0,
+            "position": [0.0, 100.0, 50.0],
+            "respawn": 30.0
+        }))
+        .unwrap();
+        assert_eq!(PickupKind::Powerup(Powerup::Haste, 8.0), definition.kind);
+        assert_eq!(Vector3::new(0.0, 100.0, 50.0), definition.position);
+        assert_eq!(Some(30.0), definition.respawn_time);
+
+        // Gone for good without a respawn time
+        let definition = PickupDefinition::parse(&json!({
+            "type": "Ammo",
+            "amount": 20,
+            "position": [0.0, 0.0, 0.0]
+        }))
+        .unwrap();
+        assert_eq!(PickupKind::Ammo(20), definition.kind);
+        assert_eq!(None, definition.respawn_time);
+
+        assert!(PickupDefinition::parse(&json!({
+            "type": "Powerup",
+            "powerup": "Flight",
+            "position": [0.0, 0.0, 0.0]
+        }))
+        .is_err());
+        assert!(PickupDefinition::parse(&json!({ "type": "Health" })).is_err());
+    }
+
+    #[test]
+    fn test_parse_level() {
+        let level = LevelFile::parse(&json!({
+            "spawnPoints": [{ "name": "Center", "position": [900.0, 0.0, -50.0] }],
+            "waves": [{
+                "prefab": "Enemy",
+                "spawnPoints": ["Center"],
+                "trigger": { "type": "Cleared" }
+            }],
+            "checkpoints": [[-600.0, -900.0, 0.0]]
+        }))
+        .unwrap();
+        assert_eq!(
+            vec![("Center".to_string(), Vector3::new(900.0, 0.0, -50.0))],
+            level.spawn_points
+        );
+        assert_eq!(1, level.waves.len());
+        assert!(level.pickups.is_empty());
+        assert_eq!(None, level.world_bounds);
+        assert_eq!(1, level.checkpoints.len());
+
+        assert!(LevelFile::parse(&json!({})).is_ok());
+        assert!(
+            LevelFile::parse(&json!({ "spawnPoints": [{ "position": [0.0, 0.0, 0.0] }] })).is_err()
+        );
+        assert!(LevelFile::parse(&json!({ "worldBounds": { "min": [0.0, 0.0, 0.0] } })).is_err());
+    }
+}
@@ -1,6 +1,36 @@
 pub mod asset_manager;
 pub mod audio_system;
+pub mod benchmark;
+pub mod command;
+pub mod config;
+pub mod crash_report;
+pub mod determinism;
+pub mod engine_context;
+pub mod engine_error;
 pub mod entity_manager;
+pub mod game_mode;
+pub mod game_state;
+pub mod gizmo;
+pub mod golden_image;
+pub mod hud;
+pub mod input_system;
+pub mod inspector;
+pub mod level_file;
+pub mod music_manager;
+pub mod objective_manager;
 pub mod phys_world;
+pub mod pool;
+pub mod post_process;
+pub mod prefab;
+pub mod prefab_node;
 pub mod renderer;
+pub mod scratch_pool;
+pub mod script_manager;
 pub mod sound_event;
+pub mod spawn_manager;
+pub mod string_table;
+pub mod timer_manager;
+pub mod transform_changes;
+pub mod tween;
+pub mod ui_screen;
+pub mod world_bounds;
@@ -0,0 +1,200 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+
+use super::{audio_system::AudioSystem, crash_report::log, sound_event::SoundEvent};
+
+/// Gameplay state the music follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MusicMood {
+    Explore,
+    Combat,
+}
+
+struct Track {
+    name: String,
+    event: SoundEvent,
+    volume: f32,
+}
+
+/// Plays music events one after another, crossfading whenever the track changes.
+/// Queued tracks play first, then the playlist of the current mood loops.
+pub struct MusicManager {
+    audio_system: Rc<RefCell<AudioSystem>>,
+    playlists: HashMap<MusicMood, Vec<String>>,
+    mood: MusicMood,
+    // Next entry of the playlist
+    playlist_index: usize,
+    queue: VecDeque<String>,
+    current: Option<Track>,
+    // Previous tracks still fading out
+    fading_out: Vec<Track>,
+    // Seconds for a full crossfade
+    fade_time: f32,
+}
+
+impl MusicManager {
+    const DEFAULT_FADE_TIME: f32 = 2.0;
+
+    pub fn new(audio_system: Rc<RefCell<AudioSystem>>) -> Rc<RefCell<Self>> {
+        let this = Self {
+            audio_system,
+            playlists: HashMap::new(),
+            mood: MusicMood::Explore,
+            playlist_index: 0,
+            queue: VecDeque::new(),
+            current: None,
+            fading_out: vec![],
+            fade_time: MusicManager::DEFAULT_FADE_TIME,
+        };
+
+        Rc::new(RefCell::new(this))
+    }
+
+    /// Events missing from the loaded banks are skipped
+    pub fn set_playlist(&mut self, mood: MusicMood, names: &[&str]) {
+        let names = names.iter().map(|name| name.to_string()).collect();
+        self.playlists.insert(mood, names);
+    }
+
+    pub fn set_fade_time(&mut self, fade_time: f32) {
+        self.fade_time = fade_time.max(0.0);
+    }
+
+    /// Play after the current track ends
+    pub fn queue(&mut self, name: &str) {
+        self.queue.push_back(name.to_string());
+    }
+
+    /// Crossfade to the track now
+    pub fn play(&mut self, name: &str) {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|track| track.name == name)
+        {
+            return;
+        }
+        if !self.audio_system.borrow().has_event(name) {
+            log!("Music event {} is not loaded", name);
+            return;
+        }
+
+        let mut event = self.audio_system.borrow_mut().play_event(name);
+        if let Err(e) = event.set_volume(0.0) {
+            log!("Failed to fade in {}: {}", name, e);
+        }
+
+        if let Some(previous) = self.current.take() {
+            self.fading_out.push(previous);
+        }
+        self.current = Some(Track {
+            name: name.to_string(),
+            event,
+            volume: 0.0,
+        });
+    }
+
+    /// Crossfade to the playlist of the mood if it changed
+    pub fn set_mood(&mut self, mood: MusicMood) {
+        if self.mood == mood {
+            return;
+        }
+        self.mood = mood;
+        self.playlist_index = 0;
+        if let Some(name) = self.next_in_playlist() {
+            self.play(&name);
+        }
+    }
+
+    pub fn get_mood(&self) -> MusicMood {
+        self.mood
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let fade_time = self.fade_time;
+
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|track| !track.event.is_valid())
+        {
+            self.current = None;
+        }
+        if self.current.is_none() {
+            if let Some(name) = self.queue.pop_front().or_else(|| self.next_in_playlist()) {
+                self.play(&name);
+            }
+        }
+
+        if let Some(track) = self.current.as_mut() {
+            track.volume = step_volume(track.volume, 1.0, fade_time, delta_time);
+            let _ = track.event.set_volume(track.volume);
+        }
+
+        for track in self.fading_out.iter_mut() {
+            track.volume = step_volume(track.volume, 0.0, fade_time, delta_time);
+            let _ = track.event.set_volume(track.volume);
+            if track.volume <= 0.0 {
+                let _ = track.event.stop(false);
+            }
+        }
+        self.fading_out
+            .retain(|track| track.volume > 0.0 && track.event.is_valid());
+    }
+
+    pub fn stop_all(&mut self) {
+        if let Some(track) = self.current.take() {
+            self.fading_out.push(track);
+        }
+        self.queue.clear();
+    }
+
+    fn next_in_playlist(&mut self) -> Option<String> {
+        let playlist = self.playlists.get(&self.mood)?;
+        let audio_system = self.audio_system.borrow();
+        for _ in 0..playlist.len() {
+            let name = &playlist[self.playlist_index % playlist.len()];
+            self.playlist_index = (self.playlist_index + 1) % playlist.len();
+            if audio_system.has_event(name) {
+                return Some(name.clone());
+            }
+        }
+        None
+    }
+}
+
+/// Move the volume towards the target, a full fade from 0 to 1 taking fade_time seconds
+fn step_volume(volume: f32, target: f32, fade_time: f32, delta_time: f32) -> f32 {
+    if fade_time <= 0.0 {
+        return target;
+    }
+    let step = delta_time / fade_time;
+    if volume < target {
+        (volume + step).min(target)
+    } else {
+        (volume - step).max(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_near_eq;
+
+    use super::step_volume;
+
+    #[test]
+    fn test_step_volume() {
+        assert_near_eq!(0.25, step_volume(0.0, 1.0, 2.0, 0.5), 0.001);
+        assert_near_eq!(0.75, step_volume(1.0, 0.0, 2.0, 0.5), 0.001);
+
+        // Never passes the target
+        assert_eq!(1.0, step_volume(0.9, 1.0, 2.0, 1.0));
+        assert_eq!(0.0, step_volume(0.1, 0.0, 2.0, 1.0));
+
+        // No fade
+        assert_eq!(1.0, step_volume(0.0, 1.0, 0.0, 0.016));
+    }
+}
@@ -0,0 +1,160 @@
+/// What happened to an objective, passed to the listeners
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectiveEvent {
+    Added,
+    Progressed,
+    Completed,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Objective {
+    pub id: String,
+    // Key of the string table
+    pub description: String,
+    pub progress: u32,
+    pub required: u32,
+}
+
+impl Objective {
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.required
+    }
+}
+
+/// Called after an objective changed. Must not borrow the owner of the manager
+pub type ObjectiveListener = Box<dyn FnMut(&Objective, ObjectiveEvent)>;
+
+/// Objectives of a mission, done in the order they were added.
+/// Only the current one, the first not complete, makes progress
+pub struct ObjectiveManager {
+    objectives: Vec<Objective>,
+    listeners: Vec<ObjectiveListener>,
+}
+
+impl ObjectiveManager {
+    pub fn new() -> Self {
+        Self {
+            objectives: vec![],
+            listeners: vec![],
+        }
+    }
+
+    pub fn add_listener(&mut self, listener: ObjectiveListener) {
+        self.listeners.push(listener);
+    }
+
+    /// Objective done after required steps, e.g. 4 for "destroy 4 targets"
+    pub fn add_objective(&mut self, id: &str, description: &str, required: u32) {
+        self.objectives.push(Objective {
+            id: id.to_string(),
+            description: description.to_string(),
+            progress: 0,
+            required: required.max(1),
+        });
+        let index = self.objectives.len() - 1;
+        self.notify(index, ObjectiveEvent::Added);
+    }
+
+    /// Advance the objective if it is the current one
+    pub fn add_progress(&mut self, id: &str, amount: u32) {
+        let index = match self.get_current_index() {
+            Some(index) if self.objectives[index].id == id => index,
+            _ => return,
+        };
+
+        let objective = &mut self.objectives[index];
+        objective.progress = (objective.progress + amount).min(objective.required);
+        if objective.is_complete() {
+            self.notify(index, ObjectiveEvent::Completed);
+        } else {
+            self.notify(index, ObjectiveEvent::Progressed);
+        }
+    }
+
+    /// Complete the objective if it is the current one
+    pub fn complete_objective(&mut self, id: &str) {
+        let remaining = match self.get_current_objective() {
+            Some(objective) if objective.id == id => objective.required - objective.progress,
+            _ => return,
+        };
+        self.add_progress(id, remaining);
+    }
+
+    pub fn get_current_objective(&self) -> Option<&Objective> {
+        self.get_current_index()
+            .map(|index| &self.objectives[index])
+    }
+
+    pub fn get_objectives(&self) -> &[Objective] {
+        &self.objectives
+    }
+
+    /// Whether there were objectives and they are all complete
+    pub fn is_complete(&self) -> bool {
+        !self.objectives.is_empty() && self.get_current_index().is_none()
+    }
+
+    /// Remove the objectives, keeping the listeners
+    pub fn clear(&mut self) {
+        self.objectives.clear();
+    }
+
+    fn get_current_index(&self) -> Option<usize> {
+        self.objectives
+            .iter()
+            .position(|objective| !objective.is_complete())
+    }
+
+    fn notify(&mut self, index: usize, event: ObjectiveEvent) {
+        let objective = &self.objectives[index];
+        for listener in self.listeners.iter_mut() {
+            listener(objective, event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{ObjectiveEvent, ObjectiveManager};
+
+    #[test]
+    fn test_objectives() {
+        let mut objectives = ObjectiveManager::new();
+        let events = Rc::new(RefCell::new(vec![]));
+        let recorded = events.clone();
+        objectives.add_listener(Box::new(move |objective, event| {
+            recorded.borrow_mut().push((objective.id.clone(), event));
+        }));
+        assert!(!objectives.is_complete());
+
+        objectives.add_objective("targets", "objectiveDestroyTargets", 2);
+        objectives.add_objective("key", "objectiveFindKey", 1);
+
+        // Not current yet
+        objectives.complete_objective("key");
+        assert_eq!("targets", objectives.get_current_objective().unwrap().id);
+
+        objectives.add_progress("targets", 1);
+        objectives.add_progress("targets", 1);
+        assert_eq!("key", objectives.get_current_objective().unwrap().id);
+        objectives.complete_objective("key");
+        assert!(objectives.is_complete());
+
+        assert_eq!(
+            vec![
+                ("targets".to_string(), ObjectiveEvent::Added),
+                ("key".to_string(), ObjectiveEvent::Added),
+                ("targets".to_string(), ObjectiveEvent::Progressed),
+                ("targets".to_string(), ObjectiveEvent::Completed),
+                ("key".to_string(), ObjectiveEvent::Completed),
+            ],
+            *events.borrow()
+        );
+
+        objectives.clear();
+        assert!(objectives.get_current_objective().is_none());
+        assert!(!objectives.is_complete());
+    }
+}
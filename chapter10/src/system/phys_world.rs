@@ -1,45 +1,105 @@
 use core::f32;
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
 
 use crate::{
     actors::actor::Actor,
-    collision::{aabb::AABB, line_segment::LineSegment},
+    collision::{aabb::AABB, heightmap::Heightmap, line_segment::LineSegment, sphere::Sphere},
     components::{
-        box_component::BoxComponent,
+        box_component::{BoxComponent, Material},
         component::{Component, State},
     },
-    math::vector3::Vector3,
+    math::{vector2::Vector2, vector3::Vector3},
 };
 
+use super::{renderer::Renderer, transform_changes};
+
 pub struct CollisionInfo {
     // Point of collision
     pub point: Vector3,
     // Normal at collision
     pub normal: Vector3,
-    // Component collided with
-    pub box_component: Rc<RefCell<BoxComponent>>,
+    // Component collided with, None for the terrain
+    pub box_component: Option<Rc<RefCell<BoxComponent>>>,
+    // Material of the surface hit
+    pub material: Material,
     // Owning actor of component
     pub actor: Rc<RefCell<dyn Actor>>,
     pub actor_id: u32,
 }
 
+/// Heightmap registered by the terrain actor
+struct Terrain {
+    heightmap: Rc<Heightmap>,
+    // World position of the heightmap center
+    offset: Vector3,
+    material: Material,
+    actor: Weak<RefCell<dyn Actor>>,
+    actor_id: u32,
+}
+
 pub struct PhysWorld {
+    // Boxes of moving actors
     boxes: Vec<Rc<RefCell<BoxComponent>>>,
+    // Boxes are still in order of min.x from the last sweep and prune
+    boxes_sorted: bool,
+    // Boxes of static actors, kept sorted as they don't move
+    static_boxes: Vec<Rc<RefCell<BoxComponent>>>,
+    static_boxes_sorted: bool,
+    terrain: Option<Terrain>,
 }
 
 impl PhysWorld {
+    // Far plane of the camera
+    const PICK_RANGE: f32 = 10000.0;
+
     pub fn new() -> Rc<RefCell<Self>> {
-        let this = Self { boxes: vec![] };
+        let this = Self {
+            boxes: vec![],
+            boxes_sorted: false,
+            static_boxes: vec![],
+            static_boxes_sorted: false,
+            terrain: None,
+        };
         Rc::new(RefCell::new(this))
     }
 
-    /// Test a line segment against boxes
-    /// Returns Some(CollisionInfo) if it collides against a box
+    /// Test a line segment against boxes and the terrain
+    /// Returns Some(CollisionInfo) if it collides against a box or the terrain
     pub fn segment_cast(&self, line: &LineSegment) -> Option<CollisionInfo> {
+        self.segment_cast_filtered(line, None)
+    }
+
+    /// Box under a screen space point, e.g. the mouse converted with Renderer::window_to_screen
+    pub fn pick(&self, renderer: &Renderer, screen_x: f32, screen_y: f32) -> Option<CollisionInfo> {
+        let (start, dir) = renderer.get_screen_direction(&Vector2::new(screen_x, screen_y));
+        let end = start.clone() + dir * PhysWorld::PICK_RANGE;
+        self.segment_cast(&LineSegment::new(start, end))
+    }
+
+    /// Same as segment_cast, but ignores boxes (and terrain) owned by the actor with ignore_id
+    pub fn segment_cast_ignoring(
+        &self,
+        line: &LineSegment,
+        ignore_id: u32,
+    ) -> Option<CollisionInfo> {
+        self.segment_cast_filtered(line, Some(ignore_id))
+    }
+
+    fn segment_cast_filtered(
+        &self,
+        line: &LineSegment,
+        ignore_id: Option<u32>,
+    ) -> Option<CollisionInfo> {
         let mut closest_t = f32::INFINITY;
         let mut result = None;
 
-        for b in &self.boxes {
+        for b in self.all_boxes() {
+            if ignore_id.is_some_and(|id| id == b.borrow().get_owner_id()) {
+                continue;
+            }
             if let Some((t, normal)) = LineSegment::intersect_aabb(line, b.borrow().get_world_box())
             {
                 if t < closest_t {
@@ -47,7 +107,8 @@ impl PhysWorld {
                     let collision_info = CollisionInfo {
                         point: line.point_on_segment(t),
                         normal,
-                        box_component: b.clone(),
+                        box_component: Some(b.clone()),
+                        material: b.borrow().get_material(),
                         actor: b.borrow().get_owner().clone(),
                         actor_id: b.borrow().get_owner_id(),
                     };
@@ -56,16 +117,98 @@ impl PhysWorld {
             }
         }
 
+        if let Some(terrain) = &self.terrain {
+            if ignore_id != Some(terrain.actor_id) {
+                // Heightmap space, only translated
+                let local_line = LineSegment::new(
+                    line.point_on_segment(0.0) - terrain.offset.clone(),
+                    line.point_on_segment(1.0) - terrain.offset.clone(),
+                );
+                if let (Some((t, normal)), Some(actor)) = (
+                    LineSegment::intersect_heightmap(&local_line, &terrain.heightmap),
+                    terrain.actor.upgrade(),
+                ) {
+                    if t < closest_t {
+                        result = Some(CollisionInfo {
+                            point: line.point_on_segment(t),
+                            normal,
+                            box_component: None,
+                            material: terrain.material,
+                            actor,
+                            actor_id: terrain.actor_id,
+                        });
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Height of the terrain under the world (x, y), None without terrain or outside of it
+    pub fn get_ground_height(&self, x: f32, y: f32) -> Option<f32> {
+        let terrain = self.terrain.as_ref()?;
+        terrain
+            .heightmap
+            .height_at(x - terrain.offset.x, y - terrain.offset.y)
+            .map(|height| height + terrain.offset.z)
+    }
+
+    /// There is a single terrain, this replaces any previous one
+    pub fn set_terrain(
+        &mut self,
+        heightmap: Rc<Heightmap>,
+        offset: Vector3,
+        material: Material,
+        actor: &Rc<RefCell<dyn Actor>>,
+    ) {
+        let actor_id = actor.borrow().get_id();
+        self.terrain = Some(Terrain {
+            heightmap,
+            offset,
+            material,
+            actor: Rc::downgrade(actor),
+            actor_id,
+        });
+    }
+
+    /// Actors owning a box overlapping the sphere, each actor listed once
+    pub fn query_sphere(&self, center: &Vector3, radius: f32) -> Vec<Rc<RefCell<dyn Actor>>> {
+        let sphere = Sphere::new(center.clone(), radius);
+        let mut ids = vec![];
+        let mut result = vec![];
+
+        for b in self.all_boxes() {
+            let borrowed_box = b.borrow();
+            if !sphere.intersect_aabb(borrowed_box.get_world_box()) {
+                continue;
+            }
+            let id = borrowed_box.get_owner_id();
+            if !ids.contains(&id) {
+                ids.push(id);
+                result.push(borrowed_box.get_owner().clone());
+            }
+        }
+
         result
     }
 
+    /// World boxes overlapping aabb, except those of the actor with ignore_id
+    pub fn get_overlapping_boxes(&self, aabb: &AABB, ignore_id: u32) -> Vec<AABB> {
+        self.all_boxes()
+            .map(|b| b.borrow())
+            .filter(|b| b.get_owner_id() != ignore_id && AABB::intersect(aabb, b.get_world_box()))
+            .map(|b| b.get_world_box().clone())
+            .collect()
+    }
+
     #[deprecated = "Naive implementation O(n^2). Not effecient..."]
     pub fn test_pairwise(&self, f: fn(Rc<RefCell<dyn Actor>>, Rc<RefCell<dyn Actor>>)) {
         for i in 0..self.boxes.len() {
-            // Don't need to test vs itself and any previous i values
-            for j in (i + 1)..self.boxes.len() {
+            // Don't need to test vs itself and any previous i values,
+            // nor static boxes against each other
+            for b in self.boxes[(i + 1)..].iter().chain(&self.static_boxes) {
                 let a = &self.boxes[i];
-                let b = &self.boxes[j];
                 if AABB::intersect(a.borrow().get_world_box(), b.borrow().get_world_box()) {
                     // Call supplied function to handle intersection
                     f(
@@ -77,29 +220,33 @@ impl PhysWorld {
         }
     }
 
-    /// Test collisions using sweep and prune
+    /// Test collisions using sweep and prune. Static boxes are only tested against moving ones
     pub fn test_sweep_and_prune(&mut self, f: fn(Rc<RefCell<dyn Actor>>, Rc<RefCell<dyn Actor>>)) {
-        // Sort by min.x
-        self.boxes.sort_by(|a, b| {
-            a.borrow()
-                .get_world_box()
-                .min
-                .x
-                .partial_cmp(&b.borrow().get_world_box().min.x)
-                .unwrap()
-        });
+        // Sort by min.x, unless no box moved since the last sort
+        self.check_moved_boxes();
+        if !self.boxes_sorted {
+            PhysWorld::sort_boxes(&mut self.boxes);
+            self.boxes_sorted = true;
+        }
+        if !self.static_boxes_sorted {
+            PhysWorld::sort_boxes(&mut self.static_boxes);
+            self.static_boxes_sorted = true;
+        }
 
         for i in 0..self.boxes.len() {
             // Get max.x for current box
             let a = &self.boxes[i];
             let max = a.borrow().get_world_box().max.x;
-            for j in (i + 1)..self.boxes.len() {
-                // If AABB[j] min is past the max bounds of AABB[i],
-                // then there aren't any other possible intersections against AABB[i]
-                let b = &self.boxes[j];
-                if b.borrow().get_world_box().min.x > max {
-                    break;
-                }
+            // If AABB[j] min is past the max bounds of AABB[i],
+            // then there aren't any other possible intersections against AABB[i]
+            let others = self.boxes[(i + 1)..]
+                .iter()
+                .take_while(|b| b.borrow().get_world_box().min.x <= max);
+            let static_others = self
+                .static_boxes
+                .iter()
+                .take_while(|b| b.borrow().get_world_box().min.x <= max);
+            for b in others.chain(static_others) {
                 if AABB::intersect(a.borrow().get_world_box(), b.borrow().get_world_box()) {
                     f(
                         a.borrow().get_owner().clone(),
@@ -110,19 +257,142 @@ impl PhysWorld {
         }
     }
 
+    fn sort_boxes(boxes: &mut [Rc<RefCell<BoxComponent>>]) {
+        boxes.sort_by(|a, b| {
+            a.borrow()
+                .get_world_box()
+                .min
+                .x
+                .partial_cmp(&b.borrow().get_world_box().min.x)
+                .unwrap()
+        });
+    }
+
     /// Add box components from world
     pub fn add_box(&mut self, box_component: Rc<RefCell<BoxComponent>>) {
-        self.boxes.push(box_component);
+        if box_component.borrow().is_static() {
+            self.static_boxes.push(box_component);
+            self.static_boxes_sorted = false;
+        } else {
+            self.boxes.push(box_component);
+            self.boxes_sorted = false;
+        }
     }
 
     /// Remove box components from world
     pub fn remove_box(&mut self, box_component: &Rc<RefCell<BoxComponent>>) {
         let id = box_component.borrow().get_id();
         self.boxes.retain(|b| b.borrow().get_id() != id);
+        self.static_boxes.retain(|b| b.borrow().get_id() != id);
     }
 
+    /// Called once per frame, before the changed transforms are cleared
     pub fn flush_boxes(&mut self) {
+        self.check_moved_boxes();
         self.boxes
             .retain(|b| *b.borrow().get_state() == State::Active);
+        self.static_boxes
+            .retain(|b| *b.borrow().get_state() == State::Active);
+        // Terrain actor was removed
+        if self
+            .terrain
+            .as_ref()
+            .is_some_and(|terrain| terrain.actor.strong_count() == 0)
+        {
+            self.terrain = None;
+        }
+    }
+
+    /// Boxes of the actors moved this frame may be out of order.
+    /// Static actors only move when placed, e.g. by the level or the inspector
+    fn check_moved_boxes(&mut self) {
+        let moved = |boxes: &[Rc<RefCell<BoxComponent>>]| {
+            boxes
+                .iter()
+                .any(|b| transform_changes::was_changed(b.borrow().get_owner_id()))
+        };
+        if self.boxes_sorted && moved(&self.boxes) {
+            self.boxes_sorted = false;
+        }
+        if self.static_boxes_sorted && moved(&self.static_boxes) {
+            self.static_boxes_sorted = false;
+        }
+    }
+
+    fn all_boxes(&self) -> impl Iterator<Item = &Rc<RefCell<BoxComponent>>> {
+        self.boxes.iter().chain(&self.static_boxes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor},
+        collision::aabb::AABB,
+        components::{box_component::BoxComponent, component::Component},
+        math::{quaternion::Quaternion, vector3::Vector3},
+    };
+
+    use super::PhysWorld;
+
+    fn add_box(phys_world: &Rc<RefCell<PhysWorld>>, position: Vector3) -> u32 {
+        add_box_of(phys_world, TestActor::new(), position)
+    }
+
+    fn add_box_of(phys_world: &Rc<RefCell<PhysWorld>>, owner: TestActor, position: Vector3) -> u32 {
+        let owner: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(owner));
+        let box_component = BoxComponent::new(owner.clone(), phys_world.clone());
+        let mut borrowed_box = box_component.borrow_mut();
+        borrowed_box.set_object_box(AABB::new(
+            Vector3::new(-10.0, -10.0, -10.0),
+            Vector3::new(10.0, 10.0, 10.0),
+        ));
+        borrowed_box.on_update_world_transform(&(position, 1.0, Quaternion::new()));
+        let id = owner.borrow().get_id();
+        id
+    }
+
+    #[test]
+    fn test_query_sphere() {
+        let phys_world = PhysWorld::new();
+        let near = add_box(&phys_world, Vector3::new(100.0, 0.0, 0.0));
+        add_box(&phys_world, Vector3::new(300.0, 0.0, 0.0));
+
+        let actors = phys_world.borrow().query_sphere(&Vector3::ZERO, 95.0);
+        assert_eq!(1, actors.len());
+        assert_eq!(near, actors[0].borrow().get_id());
+
+        let actors = phys_world.borrow().query_sphere(&Vector3::ZERO, 85.0);
+        assert!(actors.is_empty());
+    }
+
+    thread_local! {
+        static PAIRS: RefCell<Vec<(u32, u32)>> = RefCell::new(vec![]);
+    }
+
+    fn record_pair(a: Rc<RefCell<dyn Actor>>, b: Rc<RefCell<dyn Actor>>) {
+        let pair = (a.borrow().get_id(), b.borrow().get_id());
+        PAIRS.with(|pairs| pairs.borrow_mut().push(pair));
+    }
+
+    #[test]
+    fn test_sweep_and_prune() {
+        let phys_world = PhysWorld::new();
+        // Two overlapping walls and a crate touching one of them
+        let wall0 = add_box_of(&phys_world, TestActor::new_static(), Vector3::ZERO);
+        add_box_of(
+            &phys_world,
+            TestActor::new_static(),
+            Vector3::new(15.0, 0.0, 0.0),
+        );
+        let moving = add_box(&phys_world, Vector3::new(-15.0, 0.0, 0.0));
+        add_box(&phys_world, Vector3::new(200.0, 0.0, 0.0));
+
+        phys_world.borrow_mut().test_sweep_and_prune(record_pair);
+
+        // The walls touch, but static boxes aren't tested against each other
+        PAIRS.with(|pairs| assert_eq!(vec![(moving, wall0)], *pairs.borrow()));
     }
 }
@@ -0,0 +1,243 @@
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+/// How many objects a pool created and how many it handed out again
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PoolStats {
+    pub created: u32,
+    pub reused: u32,
+}
+
+impl PoolStats {
+    /// Fraction of the objects handed out without allocating
+    pub fn get_reuse_rate(&self) -> f32 {
+        let total = self.created + self.reused;
+        if total == 0 {
+            return 0.0;
+        }
+        self.reused as f32 / total as f32
+    }
+}
+
+/// Objects kept once released, to be reset and handed out again instead of dropped
+pub struct Pool<T> {
+    in_use: Vec<Rc<RefCell<T>>>,
+    free: Vec<Rc<RefCell<T>>>,
+    // Whether the user is done with an object in use
+    is_released: fn(&Rc<RefCell<T>>) -> bool,
+    // Called once on each object as it is released
+    on_release: fn(&Rc<RefCell<T>>),
+    stats: PoolStats,
+}
+
+impl<T> Pool<T> {
+    // Released objects kept, the rest are dropped
+    const MAX_FREE: usize = 64;
+
+    pub fn new(is_released: fn(&Rc<RefCell<T>>) -> bool, on_release: fn(&Rc<RefCell<T>>)) -> Self {
+        Self {
+            in_use: vec![],
+            free: vec![],
+            is_released,
+            on_release,
+            stats: PoolStats::default(),
+        }
+    }
+
+    /// Released object to reset, or None if a new one has to be created and added
+    pub fn acquire(&mut self) -> Option<Rc<RefCell<T>>> {
+        let object = self.free.pop()?;
+        self.stats.reused += 1;
+        self.in_use.push(object.clone());
+        Some(object)
+    }
+
+    /// Track a newly created object
+    pub fn add(&mut self, object: Rc<RefCell<T>>) {
+        self.stats.created += 1;
+        self.in_use.push(object);
+    }
+
+    /// Move the released objects to the free list
+    pub fn collect(&mut self) {
+        let is_released = self.is_released;
+        let (released, in_use): (Vec<_>, Vec<_>) = std::mem::take(&mut self.in_use)
+            .into_iter()
+            .partition(is_released);
+        self.in_use = in_use;
+        released.iter().for_each(self.on_release);
+
+        let room = Pool::<T>::MAX_FREE.saturating_sub(self.free.len());
+        self.free.extend(released.into_iter().take(room));
+    }
+
+    pub fn get_stats(&self) -> &PoolStats {
+        &self.stats
+    }
+}
+
+struct Entry {
+    // Pool<T>
+    pool: Box<dyn Any>,
+    name: &'static str,
+    collect: fn(&mut dyn Any),
+    stats: fn(&dyn Any) -> PoolStats,
+}
+
+/// One pool per type of object
+pub struct Pools {
+    entries: HashMap<TypeId, Entry>,
+}
+
+impl Pools {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Pool of T, created with is_released and on_release on first use
+    pub fn get<T: 'static>(
+        &mut self,
+        is_released: fn(&Rc<RefCell<T>>) -> bool,
+        on_release: fn(&Rc<RefCell<T>>),
+    ) -> &mut Pool<T> {
+        self.entries
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Entry {
+                pool: Box::new(Pool::new(is_released, on_release)),
+                name: std::any::type_name::<T>().rsplit("::").next().unwrap(),
+                collect: Pools::collect_pool::<T>,
+                stats: Pools::pool_stats::<T>,
+            })
+            .pool
+            .downcast_mut::<Pool<T>>()
+            .unwrap()
+    }
+
+    pub fn collect(&mut self) {
+        for entry in self.entries.values_mut() {
+            (entry.collect)(entry.pool.as_mut());
+        }
+    }
+
+    /// Statistics of every pool with the name of its type
+    pub fn get_stats(&self) -> Vec<(&'static str, PoolStats)> {
+        let mut stats: Vec<_> = self
+            .entries
+            .values()
+            .map(|entry| (entry.name, (entry.stats)(entry.pool.as_ref())))
+            .collect();
+        stats.sort_by_key(|(name, _)| *name);
+        stats
+    }
+
+    fn collect_pool<T: 'static>(pool: &mut dyn Any) {
+        if let Some(pool) = pool.downcast_mut::<Pool<T>>() {
+            pool.collect();
+        }
+    }
+
+    fn pool_stats<T: 'static>(pool: &dyn Any) -> PoolStats {
+        pool.downcast_ref::<Pool<T>>()
+            .map(|pool| *pool.get_stats())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::assert_near_eq;
+
+    use super::{Pool, PoolStats, Pools};
+
+    fn is_unused(object: &Rc<RefCell<u32>>) -> bool {
+        Rc::strong_count(object) == 1
+    }
+
+    fn keep(_object: &Rc<RefCell<u32>>) {}
+
+    fn reset(object: &Rc<RefCell<u32>>) {
+        *object.borrow_mut() += 100;
+    }
+
+    #[test]
+    fn test_acquire_released() {
+        let mut pool = Pool::new(is_unused, keep);
+        assert!(pool.acquire().is_none());
+
+        let object = Rc::new(RefCell::new(1));
+        pool.add(object.clone());
+
+        // Still held
+        pool.collect();
+        assert!(pool.acquire().is_none());
+
+        drop(object);
+        pool.collect();
+        let reused = pool.acquire().unwrap();
+        assert_eq!(1, *reused.borrow());
+        assert!(pool.acquire().is_none());
+
+        assert_eq!(
+            PoolStats {
+                created: 1,
+                reused: 1
+            },
+            *pool.get_stats()
+        );
+    }
+
+    #[test]
+    fn test_on_release() {
+        let mut pool = Pool::new(is_unused, reset);
+        let object = Rc::new(RefCell::new(1));
+        pool.add(object.clone());
+
+        pool.collect();
+        assert_eq!(1, *object.borrow());
+
+        let weak = Rc::downgrade(&object);
+        drop(object);
+        pool.collect();
+        pool.collect();
+        // Once, when it was released, not again while it waits
+        assert_eq!(101, *weak.upgrade().unwrap().borrow());
+    }
+
+    #[test]
+    fn test_max_free() {
+        let mut pool = Pool::new(is_unused, keep);
+        for i in 0..100 {
+            pool.add(Rc::new(RefCell::new(i)));
+        }
+        pool.collect();
+
+        let mut count = 0;
+        while pool.acquire().is_some() {
+            count += 1;
+        }
+        assert_eq!(Pool::<u32>::MAX_FREE, count);
+    }
+
+    #[test]
+    fn test_pools_stats() {
+        let mut pools = Pools::new();
+        pools.get(is_unused, keep).add(Rc::new(RefCell::new(1)));
+        pools.get(is_unused, keep).add(Rc::new(RefCell::new(2)));
+        pools.collect();
+        assert!(pools.get(is_unused, keep).acquire().is_some());
+
+        let stats = pools.get_stats();
+        assert_eq!(1, stats.len());
+        assert_eq!("u32", stats[0].0);
+        assert_near_eq!(1.0 / 3.0, stats[0].1.get_reuse_rate(), 0.001);
+        assert_eq!(0.0, PoolStats::default().get_reuse_rate());
+    }
+}
@@ -0,0 +1,251 @@
+use std::{collections::HashSet, ptr::null};
+
+use anyhow::Result;
+use gl::{BLEND, COLOR_BUFFER_BIT, DEPTH_BUFFER_BIT, DEPTH_TEST, TRIANGLES, UNSIGNED_INT};
+
+use crate::graphics::{
+    color::Color, frame_buffer::FrameBuffer, shader::Shader, vertex_array::VertexArray,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PostEffect {
+    // Meshes fade into the fog color with the distance from the camera
+    Fog,
+    // Bright parts of the scene glow
+    Bloom,
+    // Map HDR colors back into [0, 1] with the exposure
+    Tonemap,
+    Gamma,
+}
+
+/// Which effects are on and how strong they are
+pub struct PostSettings {
+    effects: HashSet<PostEffect>,
+    pub fog_color: Color,
+    pub fog_start: f32,
+    pub fog_end: f32,
+    pub bloom_threshold: f32,
+    pub bloom_intensity: f32,
+    pub exposure: f32,
+    pub gamma: f32,
+}
+
+impl PostSettings {
+    pub fn new() -> Self {
+        Self {
+            effects: HashSet::new(),
+            fog_color: Color::rgb(0.5, 0.5, 0.55),
+            fog_start: 500.0,
+            fog_end: 3000.0,
+            bloom_threshold: 1.0,
+            bloom_intensity: 1.0,
+            exposure: 1.0,
+            gamma: 2.2,
+        }
+    }
+
+    pub fn set_effect(&mut self, effect: PostEffect, enabled: bool) {
+        if enabled {
+            self.effects.insert(effect);
+        } else {
+            self.effects.remove(&effect);
+        }
+    }
+
+    pub fn is_enabled(&self, effect: PostEffect) -> bool {
+        self.effects.contains(&effect)
+    }
+
+    /// Fog is done by the mesh shader, the others need the scene drawn offscreen first
+    pub fn needs_offscreen(&self) -> bool {
+        [PostEffect::Bloom, PostEffect::Tonemap, PostEffect::Gamma]
+            .iter()
+            .any(|effect| self.is_enabled(*effect))
+    }
+
+    /// Expects the shader to be active
+    pub fn set_fog_uniforms(&self, shader: &Shader) {
+        shader.set_int_uniform("uFogEnabled", self.is_enabled(PostEffect::Fog) as i32);
+        shader.set_rgb_uniform("uFogColor", &self.fog_color);
+        shader.set_float_uniform("uFogStart", self.fog_start);
+        shader.set_float_uniform("uFogEnd", self.fog_end);
+    }
+}
+
+/// Offscreen HDR scene buffer and the passes drawing it to the window
+pub struct PostProcess {
+    scene_buffer: FrameBuffer,
+    // Half resolution, the blur goes back and forth between them
+    bloom_buffers: [FrameBuffer; 2],
+    bright_pass_shader: Shader,
+    blur_shader: Shader,
+    post_process_shader: Shader,
+    screen_width: i32,
+    screen_height: i32,
+}
+
+impl PostProcess {
+    const BLUR_PASSES: usize = 2;
+    const BLUR_SIGMA: f32 = 2.0;
+
+    pub fn new(screen_width: i32, screen_height: i32) -> Result<Self> {
+        let scene_buffer = FrameBuffer::new(screen_width, screen_height, true)?;
+        let bloom_width = (screen_width / 2).max(1);
+        let bloom_height = (screen_height / 2).max(1);
+        let bloom_buffers = [
+            FrameBuffer::new(bloom_width, bloom_height, false)?,
+            FrameBuffer::new(bloom_width, bloom_height, false)?,
+        ];
+
+        let mut bright_pass_shader = Shader::new();
+        bright_pass_shader.load("Fullscreen.vert", "BrightPass.frag")?;
+        let mut blur_shader = Shader::new();
+        blur_shader.load("Fullscreen.vert", "Blur.frag")?;
+        let mut post_process_shader = Shader::new();
+        post_process_shader.load("Fullscreen.vert", "PostProcess.frag")?;
+
+        Ok(Self {
+            scene_buffer,
+            bloom_buffers,
+            bright_pass_shader,
+            blur_shader,
+            post_process_shader,
+            screen_width,
+            screen_height,
+        })
+    }
+
+    /// Draw into the HDR scene buffer until apply
+    pub fn begin_scene(&self) {
+        self.scene_buffer.set_active();
+        unsafe {
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(COLOR_BUFFER_BIT | DEPTH_BUFFER_BIT);
+        }
+    }
+
+    /// Draw the scene buffer to the window with the effects.
+    /// Leaves the window frame buffer bound and the depth test off.
+    pub fn apply(&self, settings: &PostSettings, quad: &VertexArray) {
+        unsafe {
+            gl::Disable(DEPTH_TEST);
+            gl::Disable(BLEND);
+        }
+        quad.set_active();
+
+        let bloom = settings.is_enabled(PostEffect::Bloom);
+        if bloom {
+            self.draw_bloom(settings, quad);
+        }
+
+        FrameBuffer::set_default_active(self.screen_width, self.screen_height);
+
+        let shader = &self.post_process_shader;
+        shader.set_active();
+        shader.set_int_uniform("uScene", 0);
+        shader.set_int_uniform("uBloom", 1);
+        shader.set_int_uniform("uBloomEnabled", bloom as i32);
+        shader.set_int_uniform(
+            "uTonemapEnabled",
+            settings.is_enabled(PostEffect::Tonemap) as i32,
+        );
+        shader.set_int_uniform(
+            "uGammaEnabled",
+            settings.is_enabled(PostEffect::Gamma) as i32,
+        );
+        shader.set_float_uniform("uBloomIntensity", settings.bloom_intensity);
+        shader.set_float_uniform("uExposure", settings.exposure);
+        shader.set_float_uniform("uGamma", settings.gamma);
+        self.scene_buffer.set_texture_active(0);
+        self.bloom_buffers[0].set_texture_active(1);
+        PostProcess::draw_quad(quad);
+    }
+
+    /// Bright parts of the scene end up blurred in the first bloom buffer
+    fn draw_bloom(&self, settings: &PostSettings, quad: &VertexArray) {
+        self.bloom_buffers[0].set_active();
+        self.bright_pass_shader.set_active();
+        self.bright_pass_shader.set_int_uniform("uScene", 0);
+        self.bright_pass_shader
+            .set_float_uniform("uThreshold", settings.bloom_threshold);
+        self.scene_buffer.set_texture_active(0);
+        PostProcess::draw_quad(quad);
+
+        self.blur_shader.set_active();
+        self.blur_shader.set_int_uniform("uImage", 0);
+        self.blur_shader
+            .set_float_array_uniform("uWeights", &gaussian_weights(5, PostProcess::BLUR_SIGMA));
+        for _ in 0..PostProcess::BLUR_PASSES {
+            for (source, target, horizontal) in [(0, 1, true), (1, 0, false)] {
+                self.bloom_buffers[target].set_active();
+                self.blur_shader
+                    .set_int_uniform("uHorizontal", horizontal as i32);
+                self.bloom_buffers[source].set_texture_active(0);
+                PostProcess::draw_quad(quad);
+            }
+        }
+    }
+
+    fn draw_quad(quad: &VertexArray) {
+        unsafe {
+            gl::DrawElements(
+                TRIANGLES,
+                quad.get_num_indices() as i32,
+                UNSIGNED_INT,
+                null(),
+            );
+        }
+    }
+
+    pub fn unload(&mut self) {
+        self.scene_buffer.unload();
+        self.bloom_buffers.iter().for_each(|buffer| buffer.unload());
+        self.bright_pass_shader.unload();
+        self.blur_shader.unload();
+        self.post_process_shader.unload();
+    }
+}
+
+/// Weights of the center texel and the count - 1 texels on each side of it, summing up to 1
+fn gaussian_weights(count: usize, sigma: f32) -> Vec<f32> {
+    let weights = (0..count)
+        .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+        .collect::<Vec<_>>();
+    // Every weight but the center one is used on both sides
+    let total = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+    weights.into_iter().map(|weight| weight / total).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_near_eq;
+
+    use super::{gaussian_weights, PostEffect, PostSettings};
+
+    #[test]
+    fn test_gaussian_weights() {
+        let weights = gaussian_weights(5, 2.0);
+
+        assert_eq!(5, weights.len());
+        let total = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+        assert_near_eq!(1.0, total, 0.001);
+
+        // Falls off away from the center
+        assert!(weights.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn test_needs_offscreen() {
+        let mut settings = PostSettings::new();
+        assert!(!settings.needs_offscreen());
+
+        // Fog alone is drawn by the mesh shader
+        settings.set_effect(PostEffect::Fog, true);
+        assert!(!settings.needs_offscreen());
+
+        settings.set_effect(PostEffect::Bloom, true);
+        assert!(settings.needs_offscreen());
+        settings.set_effect(PostEffect::Bloom, false);
+        assert!(!settings.needs_offscreen());
+    }
+}
@@ -0,0 +1,177 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::{
+    actors::{
+        actor::{self, Actor, DefaultActor},
+        actor_builder::BuildComponent,
+        enemy_actor::EnemyActor,
+        hover_vehicle_actor::HoverVehicleActor,
+        physics_crate_actor::PhysicsCrateActor,
+        pop_up_target_actor::PopUpTargetActor,
+        target_actor::TargetActor,
+    },
+    components::{
+        audio_component::AudioComponent,
+        box_component::{BoxComponent, Material},
+        mesh_component::MeshComponent,
+    },
+    math::{self, vector3::Vector3},
+};
+
+use super::{
+    engine_context::EngineContext, entity_manager::EntityManager, prefab_node::PrefabNode,
+};
+
+pub type ActorFactory = fn(&EngineContext, &Value) -> Result<Rc<RefCell<dyn Actor>>>;
+
+pub type ComponentFactory = fn(&EngineContext, Rc<RefCell<dyn Actor>>, &Value) -> Result<()>;
+
+/// Actor and component types prefabs can use, by the name in the JSON
+pub struct PrefabRegistry {
+    actor_types: HashMap<String, ActorFactory>,
+    component_types: HashMap<String, ComponentFactory>,
+}
+
+impl PrefabRegistry {
+    pub fn new() -> Self {
+        let mut this = Self {
+            actor_types: HashMap::new(),
+            component_types: HashMap::new(),
+        };
+
+        this.register_actor("Actor", |context, _| Ok(DefaultActor::new(context)));
+        this.register_actor("Target", |context, _| Ok(TargetActor::new(context)));
+        this.register_actor("HoverVehicle", |context, _| {
+            Ok(HoverVehicleActor::new(context))
+        });
+        this.register_actor("PhysicsCrate", |context, _| {
+            Ok(PhysicsCrateActor::new(context))
+        });
+        this.register_actor("Enemy", |context, params| {
+            let player = context
+                .entity_manager
+                .borrow()
+                .get_fps_actor()
+                .cloned()
+                .ok_or_else(|| anyhow!("Enemy has no player to chase"))?;
+            let enemy = EnemyActor::new(context, player);
+            {
+                let borrowed_enemy = enemy.borrow();
+                let mut perception = borrowed_enemy.get_perception_component().borrow_mut();
+                if let Some(distance) = params["viewDistance"].as_f64() {
+                    perception.set_view_distance(distance as f32);
+                }
+                // Degrees
+                if let Some(angle) = params["viewAngle"].as_f64() {
+                    perception.set_view_angle(math::basic::to_radians(angle as f32));
+                }
+            }
+            Ok(enemy)
+        });
+        this.register_actor("PopUpTarget", |context, params| {
+            let target = PopUpTargetActor::new(context);
+            let up_time = params["upTime"].as_f64().unwrap_or(2.0) as f32;
+            let down_time = params["downTime"].as_f64().unwrap_or(1.5) as f32;
+            target.borrow_mut().set_times(up_time, down_time);
+            Ok(target)
+        });
+
+        this.register_component("Mesh", |context, owner, params| {
+            let file_name = params["mesh"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Mesh component has no mesh"))?;
+            let mesh = context.asset_manager.borrow_mut().get_mesh(file_name);
+            MeshComponent::build(owner, context, mesh);
+            Ok(())
+        });
+        this.register_component("Box", |context, owner, params| {
+            // Fits the mesh
+            let file_name = params["mesh"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Box component has no mesh"))?;
+            let mesh = context.asset_manager.borrow_mut().get_mesh(file_name);
+            let box_component = BoxComponent::build(owner, context, mesh.get_box().clone());
+            if let Some(name) = params["material"].as_str() {
+                let material = Material::from_name(name)
+                    .ok_or_else(|| anyhow!("Unknown material {}", name))?;
+                box_component.borrow_mut().set_material(material);
+            }
+            Ok(())
+        });
+        this.register_component("HealthBar", |context, owner, _| {
+            EntityManager::add_health_bar(owner, context);
+            Ok(())
+        });
+        this.register_component("Audio", |context, owner, params| {
+            let world_transform = owner.borrow().get_world_transform().clone();
+            let audio_component = AudioComponent::build(owner, context, ());
+            // Played from the start, usually a loop
+            if let Some(event) = params["event"].as_str() {
+                audio_component
+                    .borrow_mut()
+                    .play_event(event, &world_transform);
+            }
+            Ok(())
+        });
+
+        this
+    }
+
+    pub fn register_actor(&mut self, name: &str, factory: ActorFactory) {
+        self.actor_types.insert(name.to_string(), factory);
+    }
+
+    pub fn register_component(&mut self, name: &str, factory: ComponentFactory) {
+        self.component_types.insert(name.to_string(), factory);
+    }
+
+    /// Spawn the actor of the node and its children. The position of the node is
+    /// relative to the parent, or to offset if there is none
+    pub fn spawn(
+        &self,
+        context: &EngineContext,
+        node: &PrefabNode,
+        parent: Option<Rc<RefCell<dyn Actor>>>,
+        offset: &Vector3,
+    ) -> Result<Rc<RefCell<dyn Actor>>> {
+        let factory = self
+            .actor_types
+            .get(&node.actor_type)
+            .ok_or_else(|| anyhow!("Unknown actor type {}", node.actor_type))?;
+        let actor = factory(context, &node.params)?;
+
+        {
+            let mut borrowed_actor = actor.borrow_mut();
+            borrowed_actor.set_position(offset.clone() + node.position.clone());
+            if let Some(rotation) = &node.rotation {
+                borrowed_actor.set_rotation(rotation.clone());
+            }
+            borrowed_actor.set_scale(node.scale);
+        }
+        if let Some(parent) = parent {
+            actor::attach(parent, actor.clone());
+        }
+        // Components like audio need the world transform right away
+        actor.borrow_mut().compute_world_transform();
+
+        for component in &node.components {
+            let component_type = component["type"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Component of {} has no type", node.actor_type))?;
+            let factory = self
+                .component_types
+                .get(component_type)
+                .ok_or_else(|| anyhow!("Unknown component type {}", component_type))?;
+            factory(context, actor.clone(), component)?;
+        }
+
+        for child in &node.children {
+            self.spawn(context, child, Some(actor.clone()), &Vector3::ZERO)?;
+        }
+
+        Ok(actor)
+    }
+}
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::math::{self, quaternion::Quaternion, vector3::Vector3};
+
+/// Actor of a prefab with its components and child actors
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefabNode {
+    pub actor_type: String,
+    // Relative to the parent, or to the spawn position for the root
+    pub position: Vector3,
+    // Keeps the rotation the actor type starts with if there is none
+    pub rotation: Option<Quaternion>,
+    pub scale: f32,
+    // Whole JSON object, actor types read their own parameters from it
+    pub params: Value,
+    pub components: Vec<Value>,
+    pub children: Vec<PrefabNode>,
+}
+
+impl PrefabNode {
+    /// Loads resources/Prefabs/<name>.json
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Path::new(env!("OUT_DIR"))
+            .join("resources")
+            .join("Prefabs")
+            .join(format!("{}.json", name));
+        let content = std::fs::read_to_string(path)?;
+        let json: Value = serde_json::from_str(&content)?;
+        PrefabNode::parse(&json).map_err(|e| anyhow!("Prefab {}: {}", name, e))
+    }
+
+    pub fn parse(json: &Value) -> Result<Self> {
+        let actor_type = json["type"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Actor has no type"))?
+            .to_string();
+
+        let position = match json.get("position") {
+            Some(position) => parse_vector3(position)?,
+            None => Vector3::ZERO,
+        };
+        // Degrees around the z axis
+        let rotation = json["yaw"].as_f64().map(|yaw| {
+            Quaternion::from_axis_angle(&Vector3::UNIT_Z, math::basic::to_radians(yaw as f32))
+        });
+        let scale = json["scale"].as_f64().unwrap_or(1.0) as f32;
+
+        let components = match json.get("components") {
+            Some(Value::Array(components)) => components.clone(),
+            Some(_) => return Err(anyhow!("Components of {} are not an array", actor_type)),
+            None => vec![],
+        };
+        let children = match json.get("children") {
+            Some(Value::Array(children)) => children
+                .iter()
+                .map(PrefabNode::parse)
+                .collect::<Result<Vec<_>>>()?,
+            Some(_) => return Err(anyhow!("Children of {} are not an array", actor_type)),
+            None => vec![],
+        };
+
+        Ok(Self {
+            actor_type,
+            position,
+            rotation,
+            scale,
+            params: json.clone(),
+            components,
+            children,
+        })
+    }
+}
+
+fn parse_vector3(json: &Value) -> Result<Vector3> {
+    let values = json
+        .as_array()
+        .filter(|values| values.len() == 3)
+        .ok_or_else(|| anyhow!("Expected [x, y, z]: {}", json))?;
+    let value = |i: usize| {
+        values[i]
+            .as_f64()
+            .ok_or_else(|| anyhow!("Expected a number: {}", values[i]))
+    };
+    Ok(Vector3::new(
+        value(0)? as f32,
+        value(1)? as f32,
+        value(2)? as f32,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::math::{self, quaternion::Quaternion, vector3::Vector3};
+
+    use super::PrefabNode;
+
+    #[test]
+    fn test_parse() {
+        let json = json!({
+            "type": "Target",
+            "position": [0.0, 0.0, 100.0],
+            "components": [{ "type": "HealthBar" }],
+            "children": [{
+                "type": "Actor",
+                "yaw": 90.0,
+                "scale": 0.5,
+                "components": [{ "type": "Mesh", "mesh": "Sphere.gpmesh" }]
+            }]
+        });
+        let node = PrefabNode::parse(&json).unwrap();
+
+        assert_eq!("Target", node.actor_type);
+        assert_eq!(Vector3::new(0.0, 0.0, 100.0), node.position);
+        assert_eq!(None, node.rotation);
+        assert_eq!(1.0, node.scale);
+        assert_eq!(1, node.components.len());
+
+        let child = &node.children[0];
+        assert_eq!("Actor", child.actor_type);
+        assert_eq!(Vector3::ZERO, child.position);
+        assert_eq!(
+            Some(Quaternion::from_axis_angle(
+                &Vector3::UNIT_Z,
+                math::basic::to_radians(90.0)
+            )),
+            child.rotation
+        );
+        assert_eq!(0.5, child.scale);
+        assert_eq!("Sphere.gpmesh", child.components[0]["mesh"]);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(PrefabNode::parse(&json!({ "position": [0.0, 0.0, 0.0] })).is_err());
+        assert!(PrefabNode::parse(&json!({ "type": "Actor", "position": [0.0, 0.0] })).is_err());
+        assert!(PrefabNode::parse(&json!({ "type": "Actor", "children": [{}] })).is_err());
+    }
+}
@@ -1,34 +1,220 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use anyhow::{anyhow, Result};
-use gl::{BLEND, DEPTH_TEST, FUNC_ADD, ONE, ONE_MINUS_SRC_ALPHA, SRC_ALPHA, ZERO};
+use gl::{
+    BLEND, CLIP_DISTANCE0, COLOR_BUFFER_BIT, DEPTH_BUFFER_BIT, DEPTH_TEST, FALSE, FILL,
+    FRONT_AND_BACK, FUNC_ADD, LINE, ONE, ONE_MINUS_SRC_ALPHA, SRC_ALPHA, TRUE, ZERO,
+};
+use image::RgbaImage;
 use sdl2::{
     video::{GLContext, Window},
     VideoSubsystem,
 };
 
 use crate::{
-    graphics::{directional_light::DirectionalLight, shader::Shader},
-    math::{matrix4::Matrix4, vector3::Vector3},
+    components::{
+        component::Component,
+        mesh_component::{self, MeshComponent},
+    },
+    graphics::{
+        color::Color,
+        directional_light::DirectionalLight,
+        frame_buffer::FrameBuffer,
+        line_batch::{DebugLine, LineBatch},
+        render_queue::RenderQueue,
+        shader::Shader,
+    },
+    math::{self, matrix4::Matrix4, vector2::Vector2, vector3::Vector3},
 };
 
-use super::asset_manager::AssetManager;
+use super::{
+    asset_manager::AssetManager,
+    config::Config,
+    crash_report::log,
+    hud::HUD,
+    post_process::{PostEffect, PostProcess, PostSettings},
+    transform_changes,
+    ui_screen::UIScreen,
+};
 
-pub struct Renderer {
-    asset_manager: Rc<RefCell<AssetManager>>,
+/// How meshes are drawn, to diagnose mesh and lighting issues
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugView {
+    Lit,
+    Wireframe,
+    // Texture color without lighting
+    Albedo,
+    Normals,
+    // Brighter where more layers are drawn on top of each other
+    Overdraw,
+}
+
+impl DebugView {
+    pub fn next(self) -> Self {
+        match self {
+            DebugView::Lit => DebugView::Wireframe,
+            DebugView::Wireframe => DebugView::Albedo,
+            DebugView::Albedo => DebugView::Normals,
+            DebugView::Normals => DebugView::Overdraw,
+            DebugView::Overdraw => DebugView::Lit,
+        }
+    }
+
+    /// Mode of Debug.frag, None if drawn with the usual shader
+    fn shader_mode(self) -> Option<i32> {
+        match self {
+            DebugView::Lit | DebugView::Wireframe => None,
+            DebugView::Albedo => Some(1),
+            DebugView::Normals => Some(2),
+            DebugView::Overdraw => Some(3),
+        }
+    }
+}
+
+/// Rectangle of the window in window coordinates, from the bottom left like gl::Viewport
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    /// Set as the GL viewport, scale converts window coordinates to drawable pixels
+    fn set_active(&self, scale: &Vector2) {
+        unsafe {
+            gl::Viewport(
+                (self.x * scale.x) as i32,
+                (self.y * scale.y) as i32,
+                (self.width * scale.x) as i32,
+                (self.height * scale.y) as i32,
+            );
+        }
+    }
+
+    pub fn get_size(&self) -> Vector2 {
+        Vector2::new(self.width, self.height)
+    }
+}
+
+/// How the window is shared between the cameras, for co-op
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitScreen {
+    // One camera fills the window
+    Full,
+    // Side by side, the first view on the left
+    Vertical,
+    // One above the other, the first view on top
+    Horizontal,
+}
+
+impl SplitScreen {
+    pub fn next(self) -> Self {
+        match self {
+            SplitScreen::Full => SplitScreen::Vertical,
+            SplitScreen::Vertical => SplitScreen::Horizontal,
+            SplitScreen::Horizontal => SplitScreen::Full,
+        }
+    }
+
+    /// Viewport of each camera in a window of the given size
+    pub fn get_viewports(self, width: f32, height: f32) -> Vec<Viewport> {
+        let viewport = |x, y, width, height| Viewport {
+            x,
+            y,
+            width,
+            height,
+        };
+        match self {
+            SplitScreen::Full => vec![viewport(0.0, 0.0, width, height)],
+            SplitScreen::Vertical => vec![
+                viewport(0.0, 0.0, width / 2.0, height),
+                viewport(width / 2.0, 0.0, width / 2.0, height),
+            ],
+            SplitScreen::Horizontal => vec![
+                viewport(0.0, height / 2.0, width, height / 2.0),
+                viewport(0.0, 0.0, width, height / 2.0),
+            ],
+        }
+    }
+}
 
-    // View/projection for 3D shaders
+/// Camera drawing to a part of the window
+struct View {
+    viewport: Viewport,
     view: Matrix4,
+    // Follows the aspect ratio of the viewport
     projection: Matrix4,
+}
+
+impl View {
+    fn new(viewport: Viewport, view: Matrix4, fov: f32) -> Self {
+        let projection = create_projection(fov, viewport.width, viewport.height);
+        Self {
+            viewport,
+            view,
+            projection,
+        }
+    }
+
+    fn get_view_proj(&self) -> Matrix4 {
+        self.view.clone() * self.projection.clone()
+    }
+
+    /// Camera position is from inverted view
+    fn get_camera_position(&self) -> Vector3 {
+        self.get_inverted_view().get_translation()
+    }
+
+    /// Camera axes and position in world space
+    fn get_inverted_view(&self) -> Matrix4 {
+        let mut inverted_view = self.view.clone();
+        inverted_view.invert();
+        inverted_view
+    }
+}
+
+pub struct Renderer {
+    asset_manager: Rc<RefCell<AssetManager>>,
+
+    // Cameras for 3D shaders, the first one is the main camera used for picking
+    views: Vec<View>,
+    split_screen: SplitScreen,
 
     // Width/height of screen
     screen_width: f32,
     screen_height: f32,
 
     // Lighting data
-    ambient_light: Vector3,
+    ambient_light: Color,
     directional_light: DirectionalLight,
 
+    // Field of view in degrees without zoom
+    fov: f32,
+    // The field of view is multiplied by it, below 1 while aiming down sights
+    zoom: f32,
+    target_zoom: f32,
+
+    // Draw meshes shared by several components with one draw call
+    use_instancing: bool,
+
+    // None if the GPU can't draw offscreen, then only fog works
+    post_process: Option<PostProcess>,
+    post_settings: PostSettings,
+
+    // Scene mirrored across the water, None if the water only reflects the sky
+    water_reflection: Option<FrameBuffer>,
+
+    debug_view: DebugView,
+
+    // Camera position the levels of detail were last picked from, with a single view
+    lod_camera: RefCell<Option<Vector3>>,
+
+    // Drawn over the meshes on the next frame, then cleared
+    debug_lines: RefCell<Vec<DebugLine>>,
+    line_batch: LineBatch,
+
     // Window
     window: Window,
 
@@ -37,11 +223,22 @@ pub struct Renderer {
 }
 
 impl Renderer {
-    pub fn initialize(
-        video_system: VideoSubsystem,
-        screen_width_height: (f32, f32),
-    ) -> Result<Rc<RefCell<Self>>> {
-        let (screen_width, screen_height) = screen_width_height;
+    const MIN_INSTANCES: usize = 3;
+    // Reflected by the water where nothing else is
+    const SKY_COLOR: Color = Color::rgb(0.45, 0.6, 0.75);
+    const WATER_COLOR: Color = Color::rgb(0.05, 0.2, 0.25);
+    // How far the waves bend the reflection, in pixels
+    const WATER_DISTORTION: f32 = 20.0;
+
+    // Limits of the field of view setting, in degrees
+    const MIN_FOV: f32 = 50.0;
+    const MAX_FOV: f32 = 110.0;
+    // Fraction of the way to the target zoom per second
+    const ZOOM_SPEED: f32 = 12.0;
+
+    pub fn initialize(video_system: VideoSubsystem, config: &Config) -> Result<Rc<RefCell<Self>>> {
+        let screen_width = config.window_width as f32;
+        let screen_height = config.window_height as f32;
 
         let gl_attr = video_system.gl_attr();
         gl_attr.set_context_profile(sdl2::video::GLProfile::Core);
@@ -54,28 +251,61 @@ impl Renderer {
         gl_attr.set_accelerated_visual(true);
         gl_attr.set_depth_size(24);
 
-        let window = video_system
-            .window("Rust Game", screen_width as u32, screen_height as u32)
+        let mut window_builder =
+            video_system.window("Rust Game", config.window_width, config.window_height);
+        // The drawable can be bigger than the window on high-DPI displays
+        window_builder
             .position(100, 100)
             .opengl()
-            .build()?;
+            .resizable()
+            .allow_highdpi();
+        if config.fullscreen {
+            window_builder.fullscreen();
+        }
+        let window = window_builder.build()?;
 
         let context = window.gl_create_context().map_err(|e| anyhow!(e))?;
         gl::load_with(|name| video_system.gl_get_proc_address(name) as *const _);
 
         let asset_manager = AssetManager::new();
-        let (view, projection) = asset_manager
+        let view = asset_manager
             .borrow_mut()
-            .load_shaders(screen_width_height.0, screen_width_height.1)?;
+            .load_shaders(screen_width, screen_height)?;
+        let fov = config.fov.clamp(Renderer::MIN_FOV, Renderer::MAX_FOV);
+        let views = SplitScreen::Full
+            .get_viewports(screen_width, screen_height)
+            .into_iter()
+            .map(|viewport| View::new(viewport, view.clone(), fov))
+            .collect();
+
+        let (drawable_width, drawable_height) = window.drawable_size();
+        let post_process = match PostProcess::new(drawable_width as i32, drawable_height as i32) {
+            Ok(post_process) => Some(post_process),
+            Err(e) => {
+                log!("Post-processing is disabled: {}", e);
+                None
+            }
+        };
 
         let this = Self {
             asset_manager,
-            view,
-            projection,
+            views,
+            split_screen: SplitScreen::Full,
             screen_width,
             screen_height,
-            ambient_light: Vector3::ZERO,
+            ambient_light: Color::BLACK,
             directional_light: DirectionalLight::new(),
+            fov,
+            zoom: 1.0,
+            target_zoom: 1.0,
+            use_instancing: true,
+            post_process,
+            post_settings: PostSettings::new(),
+            water_reflection: None,
+            debug_view: DebugView::Lit,
+            lod_camera: RefCell::new(None),
+            debug_lines: RefCell::new(vec![]),
+            line_batch: LineBatch::new(),
             window,
             context,
         };
@@ -83,7 +313,35 @@ impl Renderer {
         Ok(Rc::new(RefCell::new(this)))
     }
 
-    pub fn draw(&mut self) {
+    /// Draws every view, each view with the HUD of the same index if any
+    pub fn draw(&self, huds: &[&HUD], ui_stack: &Vec<UIScreen>) {
+        self.draw_frame(huds, ui_stack);
+
+        // Swap the buffers
+        self.window.gl_swap_window();
+    }
+
+    /// Draws a frame offscreen at the size of the window and reads it back.
+    /// The post effects and the water reflection draw into their own buffers, so they must be off
+    pub fn capture(&self, huds: &[&HUD], ui_stack: &Vec<UIScreen>) -> Result<RgbaImage> {
+        if self.post_settings.needs_offscreen() || self.water_reflection.is_some() {
+            return Err(anyhow!(
+                "Can't capture with post effects or the water reflection enabled"
+            ));
+        }
+
+        let (width, height) = self.window.drawable_size();
+        let frame_buffer = FrameBuffer::new(width as i32, height as i32, true)?;
+        frame_buffer.set_active();
+        self.draw_frame(huds, ui_stack);
+        let image = frame_buffer.read_pixels();
+
+        FrameBuffer::set_default_active(width as i32, height as i32);
+        frame_buffer.unload();
+        Ok(image)
+    }
+
+    fn draw_frame(&self, huds: &[&HUD], ui_stack: &Vec<UIScreen>) {
         unsafe {
             gl::ClearColor(0.0, 0.0, 0.0, 1.0);
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
@@ -93,21 +351,87 @@ impl Renderer {
             gl::Disable(BLEND);
         }
 
-        // Set the mesh shader active
         let asset_manager = self.asset_manager.borrow_mut();
-        asset_manager.mesh_shader.set_active();
+        let drawable_scale = self.get_drawable_scale();
 
-        // Update view-projection matrix
-        asset_manager
-            .mesh_shader
-            .set_matrix_uniform("uViewProj", self.view.clone() * self.projection.clone());
+        // The water samples the mirrored scene, so it's drawn first
+        if let Some(water_reflection) = &self.water_reflection {
+            self.draw_water_reflection(&asset_manager, water_reflection, &drawable_scale);
+        }
 
-        // Update lighting uniforms
-        self.set_light_uniforms(&asset_manager.mesh_shader);
+        // Meshes go to the HDR buffer first if an effect needs it
+        let post_process = self
+            .post_process
+            .as_ref()
+            .filter(|_| self.post_settings.needs_offscreen());
+        if let Some(post_process) = post_process {
+            post_process.begin_scene();
+        }
 
-        // Draw mesh components
-        for mesh_component in asset_manager.get_mesh_components() {
-            mesh_component.borrow().draw(&asset_manager.mesh_shader);
+        for view in &self.views {
+            view.viewport.set_active(&drawable_scale);
+
+            // Pick the level of detail of each mesh before grouping them. Only the meshes
+            // which moved need it, unless the camera moved or several views share the levels
+            let camera_position = view.get_camera_position();
+            let camera_moved = self.views.len() > 1
+                || self.lod_camera.replace(Some(camera_position.clone()))
+                    != Some(camera_position.clone());
+            for mesh_component in asset_manager.get_mesh_components() {
+                let mesh_component = mesh_component.borrow();
+                if camera_moved
+                    || transform_changes::was_changed(mesh_component.get_owner().borrow().get_id())
+                {
+                    mesh_component.update_lod(&camera_position);
+                }
+            }
+
+            let render_queue = self.build_render_queue(&asset_manager, view);
+
+            // The debug shader has no instanced version
+            self.begin_debug_view();
+            match asset_manager.instanced_mesh_shader.as_ref() {
+                Some(instanced_mesh_shader)
+                    if self.use_instancing && self.debug_view.shader_mode().is_none() =>
+                {
+                    self.draw_meshes_instanced(
+                        &asset_manager,
+                        instanced_mesh_shader,
+                        view,
+                        &render_queue,
+                    )
+                }
+                _ => self.draw_meshes(&asset_manager, view, &render_queue),
+            }
+            self.end_debug_view();
+
+            // Over the opaque meshes, which show through it
+            if self.debug_view == DebugView::Lit {
+                self.draw_water(&asset_manager, view);
+                self.draw_transparent_meshes(&asset_manager, view, &render_queue);
+                self.draw_billboards(&asset_manager, view);
+            }
+        }
+
+        // Sprites, the HUD and the UI are drawn without the effects
+        if let Some(post_process) = post_process {
+            post_process.apply(&self.post_settings, &asset_manager.sprite_verts);
+        }
+
+        // Debug lines are always visible
+        let debug_lines = self.debug_lines.take();
+        if !debug_lines.is_empty() {
+            unsafe {
+                gl::Disable(DEPTH_TEST);
+            }
+            asset_manager.line_shader.set_active();
+            for view in &self.views {
+                view.viewport.set_active(&drawable_scale);
+                asset_manager
+                    .line_shader
+                    .set_matrix_uniform("uViewProj", view.get_view_proj());
+                self.line_batch.draw(&debug_lines);
+            }
         }
 
         unsafe {
@@ -122,58 +446,823 @@ impl Renderer {
         asset_manager.sprite_shader.set_active();
         asset_manager.sprite_verts.set_active();
 
+        let window_viewport =
+            SplitScreen::Full.get_viewports(self.screen_width, self.screen_height);
+        window_viewport[0].set_active(&drawable_scale);
         for sprite in asset_manager.get_sprites() {
             sprite.borrow().draw(&asset_manager.sprite_shader);
         }
 
-        // Swap the buffers
-        self.window.gl_swap_window();
+        // Draw the HUD on top of everything, in the middle of its view
+        for (view, hud) in self.views.iter().zip(huds) {
+            let view_size = view.viewport.get_size();
+            view.viewport.set_active(&drawable_scale);
+            asset_manager.sprite_shader.set_matrix_uniform(
+                "uViewProj",
+                Matrix4::create_simple_view_proj(view_size.x, view_size.y),
+            );
+            hud.draw(&asset_manager.sprite_shader, &view_size);
+        }
+        window_viewport[0].set_active(&drawable_scale);
+        asset_manager.sprite_shader.set_matrix_uniform(
+            "uViewProj",
+            Matrix4::create_simple_view_proj(self.screen_width, self.screen_height),
+        );
+
+        // Then the UI screens, from the bottom of the stack
+        for ui_screen in ui_stack {
+            ui_screen.draw(&asset_manager.sprite_shader);
+        }
     }
 
-    pub fn set_light_uniforms(&self, shader: &Shader) {
-        // Camera position is from inverted view
-        let mut inverted_view = self.view.clone();
-        inverted_view.invert();
-        shader.set_vector_uniform("uCameraPos", &inverted_view.get_translation());
+    /// Mesh components of the view in draw order. Only the lit view blends the
+    /// transparent meshes, the debug views draw them with the opaque ones
+    fn build_render_queue(
+        &self,
+        asset_manager: &AssetManager,
+        view: &View,
+    ) -> RenderQueue<Rc<RefCell<MeshComponent>>> {
+        let camera_position = view.get_camera_position();
+        let blend_transparent = self.debug_view == DebugView::Lit;
+        // Small ids for the shader names, in order of first appearance
+        let mut shader_ids: HashMap<&str, u32> = HashMap::new();
+
+        let mut render_queue = RenderQueue::new();
+        let mesh_components = asset_manager.get_mesh_components();
+        let meshes = mesh_components
+            .iter()
+            .map(|mesh_component| mesh_component.borrow().get_mesh().cloned())
+            .collect::<Vec<_>>();
+        for (mesh_component, mesh) in mesh_components.iter().zip(&meshes) {
+            // Nothing to draw
+            let mesh = match mesh {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+            let component = mesh_component.borrow();
+            if !component.is_visible() {
+                continue;
+            }
+            let distance = (component.get_world_center() - camera_position.clone()).length();
+            if blend_transparent && mesh.is_transparent() {
+                render_queue.push_transparent(distance, mesh_component.clone());
+                continue;
+            }
+
+            let next_id = shader_ids.len() as u32;
+            let shader = *shader_ids
+                .entry(mesh.get_shader_name().as_str())
+                .or_insert(next_id);
+            let texture = mesh
+                .get_texture(component.get_texture_index())
+                .map_or(0, |texture| texture.get_id());
+            render_queue.push_opaque(shader, texture, distance, mesh_component.clone());
+        }
+        render_queue.sort();
+        render_queue
+    }
+
+    /// Fallback path, a draw call per mesh component
+    fn draw_meshes(
+        &self,
+        asset_manager: &AssetManager,
+        view: &View,
+        render_queue: &RenderQueue<Rc<RefCell<MeshComponent>>>,
+    ) {
+        // Set the mesh shader active (or the debug one)
+        let debug_mode = self.debug_view.shader_mode();
+        let mesh_shader = match debug_mode {
+            Some(_) => &asset_manager.debug_shader,
+            None => &asset_manager.mesh_shader,
+        };
+        mesh_shader.set_active();
+        match debug_mode {
+            Some(mode) => mesh_shader.set_int_uniform("uDebugView", mode),
+            None => mesh_shader.set_float_uniform("uAlpha", 1.0),
+        }
+
+        // Update view-projection matrix
+        mesh_shader.set_matrix_uniform("uViewProj", view.get_view_proj());
+
+        // Update lighting uniforms
+        self.set_light_uniforms(mesh_shader, &view.get_camera_position());
+
+        // Draw mesh components
+        for mesh_component in render_queue.get_opaque() {
+            mesh_component.borrow().draw(mesh_shader);
+        }
+    }
+
+    fn draw_meshes_instanced(
+        &self,
+        asset_manager: &AssetManager,
+        instanced_mesh_shader: &Shader,
+        view: &View,
+        render_queue: &RenderQueue<Rc<RefCell<MeshComponent>>>,
+    ) {
+        // Groups are in the order of their first component, the nearest of each state
+        let opaque = render_queue.get_opaque().cloned().collect::<Vec<_>>();
+        let groups = mesh_component::group_by_mesh(&opaque);
+        let (singles, shared): (Vec<_>, Vec<_>) = groups
+            .into_iter()
+            .partition(|group| group.len() < Renderer::MIN_INSTANCES);
+
+        // Meshes used only once or twice aren't worth the upload
+        let camera_position = view.get_camera_position();
+        asset_manager.mesh_shader.set_active();
+        asset_manager
+            .mesh_shader
+            .set_matrix_uniform("uViewProj", view.get_view_proj());
+        asset_manager.mesh_shader.set_float_uniform("uAlpha", 1.0);
+        self.set_light_uniforms(&asset_manager.mesh_shader, &camera_position);
+        for mesh_component in singles.iter().flatten() {
+            mesh_component.borrow().draw(&asset_manager.mesh_shader);
+        }
+
+        instanced_mesh_shader.set_active();
+        instanced_mesh_shader.set_matrix_uniform("uViewProj", view.get_view_proj());
+        instanced_mesh_shader.set_float_uniform("uAlpha", 1.0);
+        self.set_light_uniforms(instanced_mesh_shader, &camera_position);
+        for group in shared {
+            let first = group[0].borrow();
+            let mesh = first.get_mesh().unwrap();
+            let world_transforms = group
+                .iter()
+                .map(|mesh_component| {
+                    mesh_component
+                        .borrow()
+                        .get_owner()
+                        .borrow()
+                        .get_world_transform()
+                        .clone()
+                })
+                .collect::<Vec<_>>();
+            MeshComponent::draw_instanced(
+                instanced_mesh_shader,
+                mesh,
+                first.get_texture_index(),
+                first.get_lod(),
+                &world_transforms,
+            );
+        }
+    }
+
+    /// Meshes above the first water surface, mirrored across it, for every view
+    fn draw_water_reflection(
+        &self,
+        asset_manager: &AssetManager,
+        water_reflection: &FrameBuffer,
+        drawable_scale: &Vector2,
+    ) {
+        // Other surfaces reflect the same scene, fine as long as they are about as high
+        let height = match asset_manager.get_water_components().first() {
+            Some(water) => water.borrow().get_height(),
+            None => return,
+        };
+
+        water_reflection.set_active();
+        unsafe {
+            let sky_color = &Renderer::SKY_COLOR;
+            gl::ClearColor(sky_color.r, sky_color.g, sky_color.b, sky_color.a);
+            gl::Clear(COLOR_BUFFER_BIT | DEPTH_BUFFER_BIT);
+            // What is under the water would show up above it
+            gl::Enable(CLIP_DISTANCE0);
+        }
+
+        let mesh_shader = &asset_manager.mesh_shader;
+        mesh_shader.set_active();
+        mesh_shader.set_float_uniform("uClipHeight", height);
+        mesh_shader.set_float_uniform("uAlpha", 1.0);
+        for view in &self.views {
+            // Same pixels as the view, so the water finds its reflection under it
+            view.viewport.set_active(drawable_scale);
+            mesh_shader
+                .set_matrix_uniform("uViewProj", create_mirror_z(height) * view.get_view_proj());
+            self.set_light_uniforms(mesh_shader, &view.get_camera_position());
+            for mesh_component in asset_manager.get_mesh_components() {
+                mesh_component.borrow().draw(mesh_shader);
+            }
+        }
+
+        unsafe {
+            gl::Disable(CLIP_DISTANCE0);
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+        }
+        FrameBuffer::set_default_active(
+            water_reflection.get_width(),
+            water_reflection.get_height(),
+        );
+    }
+
+    /// Water surfaces blended over the meshes of the view
+    fn draw_water(&self, asset_manager: &AssetManager, view: &View) {
+        let water_shader = match asset_manager.water_shader.as_ref() {
+            Some(water_shader) => water_shader,
+            None => return,
+        };
+        if asset_manager.get_water_components().is_empty() {
+            return;
+        }
+
+        unsafe {
+            // Keep the depth of what's under the surface, e.g. for other water behind
+            gl::Enable(BLEND);
+            gl::BlendEquation(FUNC_ADD);
+            gl::BlendFunc(SRC_ALPHA, ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(FALSE);
+        }
+
+        water_shader.set_active();
+        water_shader.set_matrix_uniform("uViewProj", view.get_view_proj());
+        self.set_light_uniforms(water_shader, &view.get_camera_position());
+        water_shader.set_rgb_uniform("uWaterColor", &Renderer::WATER_COLOR);
+        water_shader.set_rgb_uniform("uSkyColor", &Renderer::SKY_COLOR);
+        water_shader.set_float_uniform("uDistortion", Renderer::WATER_DISTORTION);
+        water_shader.set_int_uniform("uNormalMap", 0);
+        water_shader.set_int_uniform("uReflection", 1);
+        water_shader.set_int_uniform("uReflectionEnabled", self.water_reflection.is_some() as i32);
+        if let Some(water_reflection) = &self.water_reflection {
+            water_reflection.set_texture_active(1);
+            water_shader.set_vector2_uniform(
+                "uScreenSize",
+                &Vector2::new(
+                    water_reflection.get_width() as f32,
+                    water_reflection.get_height() as f32,
+                ),
+            );
+        }
+
+        for water in asset_manager.get_water_components() {
+            water.borrow().draw(water_shader, &asset_manager.quad_verts);
+        }
+
+        unsafe {
+            gl::DepthMask(TRUE);
+            gl::Disable(BLEND);
+        }
+    }
+
+    /// Meshes with an alpha below 1 from back to front, after everything opaque
+    fn draw_transparent_meshes(
+        &self,
+        asset_manager: &AssetManager,
+        view: &View,
+        render_queue: &RenderQueue<Rc<RefCell<MeshComponent>>>,
+    ) {
+        if render_queue.get_transparent().next().is_none() {
+            return;
+        }
+
+        unsafe {
+            // Tested against the depth of the opaque meshes, without hiding each other
+            gl::Enable(BLEND);
+            gl::BlendEquation(FUNC_ADD);
+            gl::BlendFunc(SRC_ALPHA, ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(FALSE);
+        }
+
+        let mesh_shader = &asset_manager.mesh_shader;
+        mesh_shader.set_active();
+        mesh_shader.set_matrix_uniform("uViewProj", view.get_view_proj());
+        self.set_light_uniforms(mesh_shader, &view.get_camera_position());
+        for mesh_component in render_queue.get_transparent() {
+            let mesh_component = mesh_component.borrow();
+            if let Some(mesh) = mesh_component.get_mesh() {
+                mesh_shader.set_float_uniform("uAlpha", mesh.get_alpha());
+            }
+            mesh_component.draw(mesh_shader);
+        }
+        mesh_shader.set_float_uniform("uAlpha", 1.0);
+
+        unsafe {
+            gl::DepthMask(TRUE);
+            gl::Disable(BLEND);
+        }
+    }
+
+    /// Billboards from back to front, over the transparent meshes
+    fn draw_billboards(&self, asset_manager: &AssetManager, view: &View) {
+        let billboards = asset_manager.get_billboards();
+        if billboards.is_empty() {
+            return;
+        }
+
+        let inverted_view = view.get_inverted_view();
+        let camera_position = inverted_view.get_translation();
+        let mut render_queue = RenderQueue::new();
+        for billboard in billboards {
+            let distance =
+                (billboard.borrow().get_world_position() - camera_position.clone()).length();
+            render_queue.push_transparent(distance, billboard);
+        }
+        render_queue.sort();
+
+        unsafe {
+            gl::Enable(BLEND);
+            gl::BlendEquation(FUNC_ADD);
+            gl::BlendFunc(SRC_ALPHA, ONE_MINUS_SRC_ALPHA);
+            gl::DepthMask(FALSE);
+        }
+
+        let billboard_shader = &asset_manager.billboard_shader;
+        billboard_shader.set_active();
+        billboard_shader.set_matrix_uniform("uViewProj", view.get_view_proj());
+        for billboard in render_queue.get_transparent() {
+            billboard.borrow().draw(
+                billboard_shader,
+                &asset_manager.quad_verts,
+                &camera_position,
+                &inverted_view,
+            );
+        }
+
+        unsafe {
+            gl::DepthMask(TRUE);
+            gl::Disable(BLEND);
+        }
+    }
+
+    /// Rebuild everything depending on the window size. Width and height are in window
+    /// coordinates, used by the projections and the UI. The viewport and the offscreen
+    /// buffers follow the drawable size, which differs on high-DPI displays.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            // Minimized
+            return;
+        }
+        self.screen_width = width as f32;
+        self.screen_height = height as f32;
+
+        let (drawable_width, drawable_height) = self.window.drawable_size();
+        unsafe {
+            gl::Viewport(0, 0, drawable_width as i32, drawable_height as i32);
+        }
+
+        self.rebuild_views();
+
+        if self.water_reflection.is_some() {
+            self.set_water_reflection(true);
+        }
+
+        let asset_manager = self.asset_manager.borrow();
+        asset_manager.sprite_shader.set_active();
+        asset_manager.sprite_shader.set_matrix_uniform(
+            "uViewProj",
+            Matrix4::create_simple_view_proj(self.screen_width, self.screen_height),
+        );
+
+        if let Some(mut post_process) = self.post_process.take() {
+            post_process.unload();
+            self.post_process =
+                match PostProcess::new(drawable_width as i32, drawable_height as i32) {
+                    Ok(post_process) => Some(post_process),
+                    Err(e) => {
+                        log!("Post-processing is disabled: {}", e);
+                        None
+                    }
+                };
+        }
+    }
+
+    /// Share the window between the cameras. New views start with the main camera
+    pub fn set_split_screen(&mut self, split_screen: SplitScreen) {
+        self.split_screen = split_screen;
+        self.rebuild_views();
+    }
+
+    pub fn get_split_screen(&self) -> SplitScreen {
+        self.split_screen
+    }
+
+    pub fn get_view_count(&self) -> usize {
+        self.views.len()
+    }
+
+    /// Fit the views to the split screen layout and the window size, keeping the cameras
+    fn rebuild_views(&mut self) {
+        let viewports = self
+            .split_screen
+            .get_viewports(self.screen_width, self.screen_height);
+        self.views = viewports
+            .into_iter()
+            .enumerate()
+            .map(|(index, viewport)| {
+                let view = self.views.get(index).unwrap_or(&self.views[0]);
+                View::new(viewport, view.view.clone(), self.fov * self.zoom)
+            })
+            .collect();
+    }
+
+    /// Field of view in degrees, clamped to a comfortable range
+    pub fn set_fov(&mut self, degrees: f32) {
+        self.fov = degrees.clamp(Renderer::MIN_FOV, Renderer::MAX_FOV);
+        self.rebuild_projections();
+    }
+
+    pub fn get_fov(&self) -> f32 {
+        self.fov
+    }
+
+    /// Zoom reached smoothly by update_zoom, e.g. 0.6 while aiming down sights
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.target_zoom = zoom;
+    }
+
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn update_zoom(&mut self, delta_time: f32) {
+        if self.zoom == self.target_zoom {
+            return;
+        }
+        self.zoom = approach_zoom(
+            self.zoom,
+            self.target_zoom,
+            Renderer::ZOOM_SPEED * delta_time,
+        );
+        self.rebuild_projections();
+    }
+
+    fn rebuild_projections(&mut self) {
+        let fov = self.fov * self.zoom;
+        for view in &mut self.views {
+            view.projection = create_projection(fov, view.viewport.width, view.viewport.height);
+        }
+    }
+
+    /// Window coordinates to drawable pixels, not 1 on high-DPI displays
+    fn get_drawable_scale(&self) -> Vector2 {
+        let (drawable_width, drawable_height) = self.window.drawable_size();
+        Vector2::new(
+            drawable_width as f32 / self.screen_width,
+            drawable_height as f32 / self.screen_height,
+        )
+    }
+
+    pub fn set_use_instancing(&mut self, use_instancing: bool) {
+        self.use_instancing = use_instancing;
+    }
+
+    pub fn set_post_effect(&mut self, effect: PostEffect, enabled: bool) {
+        self.post_settings.set_effect(effect, enabled);
+    }
+
+    /// Reflect the meshes in the water, at the cost of drawing them once more
+    pub fn set_water_reflection(&mut self, enabled: bool) {
+        if let Some(water_reflection) = self.water_reflection.take() {
+            water_reflection.unload();
+        }
+        if !enabled {
+            return;
+        }
+
+        let (drawable_width, drawable_height) = self.window.drawable_size();
+        match FrameBuffer::new(drawable_width as i32, drawable_height as i32, true) {
+            Ok(water_reflection) => self.water_reflection = Some(water_reflection),
+            Err(e) => log!("Water reflection is disabled: {}", e),
+        }
+    }
+
+    pub fn has_water_reflection(&self) -> bool {
+        self.water_reflection.is_some()
+    }
+
+    pub fn get_post_settings_mut(&mut self) -> &mut PostSettings {
+        &mut self.post_settings
+    }
+
+    /// Draw a line in world space on the next frame
+    pub fn add_debug_line(&self, line: DebugLine) {
+        self.debug_lines.borrow_mut().push(line);
+    }
+
+    pub fn set_debug_view(&mut self, debug_view: DebugView) {
+        self.debug_view = debug_view;
+    }
+
+    pub fn get_debug_view(&self) -> DebugView {
+        self.debug_view
+    }
+
+    /// Set the render state of the debug view, undone by end_debug_view
+    fn begin_debug_view(&self) {
+        unsafe {
+            match self.debug_view {
+                DebugView::Wireframe => gl::PolygonMode(FRONT_AND_BACK, LINE),
+                DebugView::Overdraw => {
+                    // Hidden layers have to count too
+                    gl::Disable(DEPTH_TEST);
+                    gl::Enable(BLEND);
+                    gl::BlendEquation(FUNC_ADD);
+                    gl::BlendFunc(ONE, ONE);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn end_debug_view(&self) {
+        unsafe {
+            gl::PolygonMode(FRONT_AND_BACK, FILL);
+        }
+    }
+
+    pub fn set_light_uniforms(&self, shader: &Shader, camera_position: &Vector3) {
+        shader.set_vector_uniform("uCameraPos", camera_position);
 
         // Ambient light
-        shader.set_vector_uniform("uAmbientLight", &self.ambient_light);
+        shader.set_rgb_uniform("uAmbientLight", &self.ambient_light);
 
         // Directional light
         shader.set_vector_uniform("uDirLight.mDirection", &self.directional_light.direction);
-        shader.set_vector_uniform(
+        shader.set_rgb_uniform(
             "uDirLight.mDiffuseColor",
             &self.directional_light.diffuse_color,
         );
-        shader.set_vector_uniform("uDirLight.mSpecColor", &self.directional_light.spec_color);
+        shader.set_rgb_uniform("uDirLight.mSpecColor", &self.directional_light.spec_color);
+
+        // Fog is part of the lighting pass
+        self.post_settings.set_fog_uniforms(shader);
     }
 
+    /// Screen space is relative to the center of the main view
     pub fn unproject(&self, screen_point: Vector3) -> Vector3 {
+        let main_view = &self.views[0];
+
         // Convert screenPoint to device coordinates (between -1 and +1)
         let mut device_coord = screen_point;
-        device_coord.x /= self.screen_width * 0.5;
-        device_coord.y /= self.screen_height * 0.5;
+        device_coord.x /= main_view.viewport.width * 0.5;
+        device_coord.y /= main_view.viewport.height * 0.5;
 
         // Transform vector by unprojection matrix
-        let mut unprojection = self.view.clone() * self.projection.clone();
+        let mut unprojection = main_view.get_view_proj();
         unprojection.invert();
 
         Vector3::transform_with_pers_div(&device_coord, unprojection, None)
     }
 
-    pub fn set_ambient_light(&mut self, ambient_light: Vector3) {
+    /// Converts window coordinates of the mouse to screen space
+    pub fn window_to_screen(&self, window_point: &Vector2) -> Vector2 {
+        window_to_screen(window_point, self.screen_width, self.screen_height)
+    }
+
+    /// Start on the near plane and direction into the screen through a screen space point
+    /// return: (0:out_start, 1:out_dir)
+    pub fn get_screen_direction(&self, screen_point: &Vector2) -> (Vector3, Vector3) {
+        let out_start = self.unproject(Vector3::new(screen_point.x, screen_point.y, 0.0));
+        // Between near and far
+        let end = self.unproject(Vector3::new(screen_point.x, screen_point.y, 0.9));
+        let mut out_dir = end - out_start.clone();
+        out_dir.normalize_mut();
+
+        (out_start, out_dir)
+    }
+
+    /// Converts a world position to screen space (origin at the center of the main view).
+    /// Returns None if the position is behind the camera
+    pub fn project(&self, world_point: &Vector3) -> Option<Vector3> {
+        let main_view = &self.views[0];
+        project_point(
+            world_point,
+            &main_view.view,
+            &main_view.projection,
+            main_view.viewport.width,
+            main_view.viewport.height,
+        )
+    }
+
+    /// Whether a screen space point is visible in the main view, allowing margin pixels outside
+    pub fn is_on_screen(&self, screen_point: &Vector3, margin: f32) -> bool {
+        let main_view = &self.views[0];
+        screen_point.x.abs() <= main_view.viewport.width * 0.5 + margin
+            && screen_point.y.abs() <= main_view.viewport.height * 0.5 + margin
+    }
+
+    pub fn set_ambient_light(&mut self, ambient_light: Color) {
         self.ambient_light = ambient_light;
     }
 
+    pub fn get_ambient_light(&self) -> &Color {
+        &self.ambient_light
+    }
+
+    pub fn get_directional_light(&self) -> &DirectionalLight {
+        &self.directional_light
+    }
+
     pub fn get_directional_light_mut(&mut self) -> &mut DirectionalLight {
         &mut self.directional_light
     }
 
+    pub fn get_screen_size(&self) -> Vector2 {
+        Vector2::new(self.screen_width, self.screen_height)
+    }
+
     pub fn get_asset_manager(&self) -> &Rc<RefCell<AssetManager>> {
         &self.asset_manager
     }
 
+    /// Camera of the main view
     pub fn set_view_matrix(&mut self, view: Matrix4) {
-        self.view = view;
+        self.views[0].view = view;
+    }
+
+    /// Camera of another view, ignored if the split screen has no such view
+    pub fn set_view_matrix_at(&mut self, index: usize, view: Matrix4) {
+        if let Some(target) = self.views.get_mut(index) {
+            target.view = view;
+        }
+    }
+}
+
+fn project_point(
+    world_point: &Vector3,
+    view: &Matrix4,
+    projection: &Matrix4,
+    screen_width: f32,
+    screen_height: f32,
+) -> Option<Vector3> {
+    // View space z is the distance along the camera forward
+    let view_point = Vector3::transform_with_pers_div(world_point, view.clone(), None);
+    if view_point.z <= 0.0 {
+        return None;
+    }
+
+    // Transform to device coordinates (between -1 and +1), then to screen
+    let mut screen_point =
+        Vector3::transform_with_pers_div(world_point, view.clone() * projection.clone(), None);
+    screen_point.x *= screen_width * 0.5;
+    screen_point.y *= screen_height * 0.5;
+
+    Some(screen_point)
+}
+
+/// Mirror of world positions across the horizontal plane at height
+fn create_mirror_z(height: f32) -> Matrix4 {
+    Matrix4::create_scale_xyz(1.0, 1.0, -1.0)
+        * Matrix4::create_translation(&Vector3::new(0.0, 0.0, 2.0 * height))
+}
+
+/// Window coordinates (origin at top left, y down) to screen space (origin at the center, y up)
+/// Perspective projection of the 3D views, rebuilt when the size or field of view changes
+fn create_projection(fov: f32, width: f32, height: f32) -> Matrix4 {
+    Matrix4::create_perspective_fov(math::basic::to_radians(fov), width, height, 25.0, 10000.0)
+}
+
+/// Fraction of the way from current to target, snapping once close enough
+fn approach_zoom(current: f32, target: f32, fraction: f32) -> f32 {
+    let zoom = current + (target - current) * fraction.min(1.0);
+    if (target - zoom).abs() < 0.001 {
+        target
+    } else {
+        zoom
+    }
+}
+
+fn window_to_screen(window_point: &Vector2, screen_width: f32, screen_height: f32) -> Vector2 {
+    Vector2::new(
+        window_point.x - screen_width / 2.0,
+        screen_height / 2.0 - window_point.y,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        assert_near_eq,
+        math::{self, matrix4::Matrix4, vector2::Vector2, vector3::Vector3},
+    };
+
+    use super::{
+        approach_zoom, create_mirror_z, project_point, window_to_screen, DebugView, SplitScreen,
+        Viewport,
+    };
+
+    #[test]
+    fn test_project_point() {
+        let view = Matrix4::create_look_at(&Vector3::ZERO, &Vector3::UNIT_X, &Vector3::UNIT_Z);
+        let projection = Matrix4::create_perspective_fov(
+            math::basic::to_radians(90.0),
+            1024.0,
+            768.0,
+            25.0,
+            10000.0,
+        );
+
+        // Straight ahead is the center of the screen
+        let center = project_point(
+            &Vector3::new(500.0, 0.0, 0.0),
+            &view,
+            &projection,
+            1024.0,
+            768.0,
+        )
+        .unwrap();
+        assert_near_eq!(0.0, center.x, 0.001);
+        assert_near_eq!(0.0, center.y, 0.001);
+
+        // Up in the world is up on the screen
+        let above = project_point(
+            &Vector3::new(500.0, 0.0, 500.0),
+            &view,
+            &projection,
+            1024.0,
+            768.0,
+        )
+        .unwrap();
+        assert_near_eq!(0.0, above.x, 0.001);
+        assert_near_eq!(384.0, above.y, 0.01);
+
+        assert!(project_point(
+            &Vector3::new(-500.0, 0.0, 0.0),
+            &view,
+            &projection,
+            1024.0,
+            768.0
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_window_to_screen() {
+        let center = window_to_screen(&Vector2::new(512.0, 384.0), 1024.0, 768.0);
+        assert_eq!(Vector2::ZERO, center);
+
+        let top_left = window_to_screen(&Vector2::ZERO, 1024.0, 768.0);
+        assert_eq!(Vector2::new(-512.0, 384.0), top_left);
+    }
+
+    #[test]
+    fn test_debug_view_next() {
+        let mut debug_view = DebugView::Lit;
+        let mut seen = vec![];
+        for _ in 0..5 {
+            seen.push(debug_view);
+            debug_view = debug_view.next();
+        }
+
+        // Every view once, then back to the lit one
+        assert_eq!(DebugView::Lit, debug_view);
+        for view in [
+            DebugView::Wireframe,
+            DebugView::Albedo,
+            DebugView::Normals,
+            DebugView::Overdraw,
+        ] {
+            assert!(seen.contains(&view));
+        }
+    }
+
+    #[test]
+    fn test_split_screen_viewports() {
+        let full = SplitScreen::Full.get_viewports(1024.0, 768.0);
+        assert_eq!(1, full.len());
+        assert_eq!(Vector2::new(1024.0, 768.0), full[0].get_size());
+
+        let vertical = SplitScreen::Vertical.get_viewports(1024.0, 768.0);
+        assert_eq!(
+            vec![
+                Viewport {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 512.0,
+                    height: 768.0
+                },
+                Viewport {
+                    x: 512.0,
+                    y: 0.0,
+                    width: 512.0,
+                    height: 768.0
+                },
+            ],
+            vertical
+        );
+
+        // The first view is on top, and GL viewports start at the bottom
+        let horizontal = SplitScreen::Horizontal.get_viewports(1024.0, 768.0);
+        assert_eq!(384.0, horizontal[0].y);
+        assert_eq!(0.0, horizontal[1].y);
+        for viewport in horizontal {
+            assert_eq!(Vector2::new(1024.0, 384.0), viewport.get_size());
+        }
+
+        assert_eq!(SplitScreen::Full, SplitScreen::Horizontal.next());
+    }
+
+    #[test]
+    fn test_create_mirror_z() {
+        let mirror = create_mirror_z(-60.0);
+        let point = Vector3::transform_with_pers_div(&Vector3::new(10.0, 20.0, 40.0), mirror, None);
+        assert_eq!(Vector3::new(10.0, 20.0, -160.0), point);
+    }
+
+    #[test]
+    fn test_approach_zoom() {
+        assert_near_eq!(0.8, approach_zoom(1.0, 0.6, 0.5), 0.001);
+        // Never overshoots on long frames
+        assert_near_eq!(0.6, approach_zoom(1.0, 0.6, 3.0), 0.001);
+        // Snaps to the target at the end
+        assert_eq!(1.0, approach_zoom(0.9995, 1.0, 0.1));
     }
 }
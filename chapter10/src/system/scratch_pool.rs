@@ -0,0 +1,197 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+thread_local! {
+    // Per thread, so the game loop isn't counting what other threads allocate
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Global allocator that counts every heap allocation
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn count() {
+        // The thread may be torn down already
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        CountingAllocator::count();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        CountingAllocator::count();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Number of heap allocations made by this thread since it started
+pub fn allocation_count() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+thread_local! {
+    static SCRATCH_POOL: RefCell<ScratchPool> = RefCell::new(ScratchPool::new());
+}
+
+struct Pool {
+    // Vec<Vec<T>>
+    buffers: Box<dyn Any>,
+    // Drops surplus buffers of the pool
+    trim: fn(&mut dyn Any),
+}
+
+/// Pool of scratch Vecs per element type, handed out and given back instead of allocated.
+/// Buffers keep their capacity, so after warming up no allocation happens.
+/// Not a bump arena: a Vec can't take its memory from one on stable Rust without a crate,
+/// so reset only drops the surplus buffers instead of freeing everything at once.
+struct ScratchPool {
+    pools: HashMap<TypeId, Pool>,
+}
+
+impl ScratchPool {
+    // Buffers bigger than this are released instead of being kept around
+    const MAX_RETAINED_CAPACITY: usize = 4096;
+    // Buffers kept per type when the frame is reset
+    const MAX_RETAINED_BUFFERS: usize = 4;
+
+    fn new() -> Self {
+        Self {
+            pools: HashMap::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        for pool in self.pools.values_mut() {
+            (pool.trim)(pool.buffers.as_mut());
+        }
+    }
+
+    fn take<T: 'static>(&mut self) -> Vec<T> {
+        self.pool::<T>().pop().unwrap_or_default()
+    }
+
+    fn give_back<T: 'static>(&mut self, mut buffer: Vec<T>) {
+        buffer.clear();
+        self.pool::<T>().push(buffer);
+    }
+
+    fn pool<T: 'static>(&mut self) -> &mut Vec<Vec<T>> {
+        self.pools
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Pool {
+                buffers: Box::new(Vec::<Vec<T>>::new()),
+                trim: ScratchPool::trim::<T>,
+            })
+            .buffers
+            .downcast_mut::<Vec<Vec<T>>>()
+            .unwrap()
+    }
+
+    fn trim<T: 'static>(buffers: &mut dyn Any) {
+        if let Some(buffers) = buffers.downcast_mut::<Vec<Vec<T>>>() {
+            buffers.truncate(ScratchPool::MAX_RETAINED_BUFFERS);
+        }
+    }
+}
+
+/// Run f with an empty scratch Vec<T> borrowed from the pool.
+/// Nested calls get distinct buffers.
+pub fn with_scratch<T: 'static, R>(f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+    let mut buffer = SCRATCH_POOL.with(|pool| pool.borrow_mut().take::<T>());
+    let result = f(&mut buffer);
+    if buffer.capacity() <= ScratchPool::MAX_RETAINED_CAPACITY {
+        SCRATCH_POOL.with(|pool| pool.borrow_mut().give_back(buffer));
+    }
+    result
+}
+
+/// Called once per frame to release buffers only needed by a spike of nested calls
+pub fn reset() {
+    SCRATCH_POOL.with(|pool| pool.borrow_mut().reset());
+}
+
+/// Allocation statistics gathered once per frame
+pub struct FrameStats {
+    frames: u64,
+    allocations: usize,
+    last_count: usize,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self {
+            frames: 0,
+            allocations: 0,
+            last_count: allocation_count(),
+        }
+    }
+
+    /// Returns number of allocations made during the frame
+    pub fn end_frame(&mut self) -> usize {
+        let count = allocation_count();
+        let frame_allocations = count - self.last_count;
+        self.last_count = count;
+        self.frames += 1;
+        self.allocations += frame_allocations;
+        frame_allocations
+    }
+
+    pub fn get_average_allocations(&self) -> f32 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.allocations as f32 / self.frames as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::with_scratch;
+
+    #[test]
+    fn test_with_scratch_reuses_buffer() {
+        with_scratch(|buffer: &mut Vec<u64>| buffer.extend(0..100));
+
+        with_scratch(|buffer: &mut Vec<u64>| {
+            assert!(buffer.is_empty());
+            assert!(buffer.capacity() >= 100);
+        });
+    }
+
+    #[test]
+    fn test_with_scratch_nested() {
+        with_scratch(|outer: &mut Vec<i32>| {
+            outer.push(1);
+            with_scratch(|inner: &mut Vec<i32>| {
+                assert!(inner.is_empty());
+                inner.push(2);
+            });
+            assert_eq!(vec![1], *outer);
+        });
+    }
+
+    #[test]
+    fn test_with_scratch_releases_values() {
+        // Actors copied for an update must not be kept alive by the pool
+        let actor = Rc::new(0);
+        with_scratch(|actors: &mut Vec<Rc<i32>>| {
+            actors.push(actor.clone());
+            assert_eq!(2, Rc::strong_count(&actor));
+        });
+        assert_eq!(1, Rc::strong_count(&actor));
+    }
+}
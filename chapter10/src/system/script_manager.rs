@@ -0,0 +1,282 @@
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    actors::actor::{Actor, State},
+    math::vector3::Vector3,
+};
+
+use super::tween::{Ease, Tween};
+
+/// Identifies a running script, to stop it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScriptHandle(u32);
+
+/// Given to every script, to wait on the game clock.
+/// Awaiting is the only way a script gives the frame back
+#[derive(Clone)]
+pub struct ScriptContext {
+    // Seconds of the frame being stepped, shared by all the scripts
+    delta_time: Rc<Cell<f32>>,
+}
+
+impl ScriptContext {
+    pub fn get_delta_time(&self) -> f32 {
+        self.delta_time.get()
+    }
+
+    /// Resume on the next frame
+    pub fn next_frame(&self) -> NextFrame {
+        NextFrame { yielded: false }
+    }
+
+    pub async fn wait_seconds(&self, seconds: f32) {
+        let mut time_left = seconds;
+        while time_left > 0.0 {
+            self.next_frame().await;
+            time_left -= self.get_delta_time();
+        }
+    }
+
+    /// Checked once per frame, starting with this one
+    pub async fn wait_until(&self, mut condition: impl FnMut() -> bool) {
+        while !condition() {
+            self.next_frame().await;
+        }
+    }
+
+    /// Move the actor to the position over duration seconds.
+    /// Returns false if it died on the way, the script should give up on it then
+    pub async fn move_to<T: Actor + ?Sized>(
+        &self,
+        actor: &RefCell<T>,
+        position: Vector3,
+        duration: f32,
+        ease: Ease,
+    ) -> bool {
+        let start = actor.borrow().get_position().clone();
+        let mut progress = Tween::new(0.0, 1.0, duration, ease);
+        loop {
+            if *actor.borrow().get_state() == State::Dead {
+                return false;
+            }
+            let f = progress.get_value();
+            actor
+                .borrow_mut()
+                .set_position(start.clone() + (position.clone() - start.clone()) * f);
+            if progress.is_done() {
+                return true;
+            }
+            self.next_frame().await;
+            progress.update(self.get_delta_time());
+        }
+    }
+}
+
+/// Pending the first time it is polled, ready the next
+pub struct NextFrame {
+    yielded: bool,
+}
+
+impl Future for NextFrame {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        Poll::Pending
+    }
+}
+
+struct Script {
+    handle: ScriptHandle,
+    future: Pin<Box<dyn Future<Output = ()>>>,
+}
+
+/// Runs sequences written as async blocks, e.g. open a door, wait, move a target out.
+/// Each script is stepped once per frame while the game is playing, up to its next await.
+/// The scripts run with the manager unborrowed, so they can start and stop scripts
+pub struct ScriptManager {
+    scripts: Vec<Script>,
+    next_id: u32,
+    delta_time: Rc<Cell<f32>>,
+    // Stopped during an update, while their scripts are out of the list
+    stopped: Vec<ScriptHandle>,
+}
+
+impl ScriptManager {
+    pub fn new() -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            scripts: vec![],
+            next_id: 0,
+            delta_time: Rc::new(Cell::new(0.0)),
+            stopped: vec![],
+        }))
+    }
+
+    /// Run the script from the next update on, until it returns or is stopped
+    pub fn start<F, Fut>(&mut self, script: F) -> ScriptHandle
+    where
+        F: FnOnce(ScriptContext) -> Fut,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let handle = ScriptHandle(self.next_id);
+        self.next_id += 1;
+        let context = ScriptContext {
+            delta_time: self.delta_time.clone(),
+        };
+        self.scripts.push(Script {
+            handle,
+            future: Box::pin(script(context)),
+        });
+        handle
+    }
+
+    /// Drop the script where it waits, nothing happens if it already ended
+    pub fn stop(&mut self, handle: ScriptHandle) {
+        self.scripts.retain(|script| script.handle != handle);
+        self.stopped.push(handle);
+    }
+
+    pub fn is_running(&self, handle: ScriptHandle) -> bool {
+        self.scripts.iter().any(|script| script.handle == handle)
+    }
+
+    /// Step every script up to its next await, in the order they were started
+    pub fn update(script_manager: &Rc<RefCell<ScriptManager>>, delta_time: f32) {
+        let scripts = {
+            let mut this = script_manager.borrow_mut();
+            this.delta_time.set(delta_time);
+            std::mem::take(&mut this.scripts)
+        };
+
+        // Nothing wakes a script up, they are all polled every frame
+        let mut context = Context::from_waker(Waker::noop());
+        let mut running = vec![];
+        for mut script in scripts {
+            // An earlier script may have stopped it
+            if script_manager.borrow().stopped.contains(&script.handle) {
+                continue;
+            }
+            if script.future.as_mut().poll(&mut context).is_pending() {
+                running.push(script);
+            }
+        }
+
+        let mut this = script_manager.borrow_mut();
+        running.retain(|script| !this.stopped.contains(&script.handle));
+        // Started during the update, they run from the next one
+        running.append(&mut this.scripts);
+        this.scripts = running;
+        this.stopped.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor, State},
+        assert_near_eq,
+        math::vector3::Vector3,
+        system::tween::Ease,
+    };
+
+    use super::ScriptManager;
+
+    #[test]
+    fn test_sequence() {
+        let script_manager = ScriptManager::new();
+        let steps = Rc::new(RefCell::new(vec![]));
+        let flag = Rc::new(RefCell::new(false));
+
+        let (log, condition) = (steps.clone(), flag.clone());
+        let handle = script_manager.borrow_mut().start(|script| async move {
+            log.borrow_mut().push("start");
+            script.wait_seconds(1.0).await;
+            log.borrow_mut().push("waited");
+            script.wait_until(|| *condition.borrow()).await;
+            log.borrow_mut().push("done");
+        });
+        assert!(steps.borrow().is_empty());
+
+        ScriptManager::update(&script_manager, 0.5);
+        assert_eq!(vec!["start"], *steps.borrow());
+        ScriptManager::update(&script_manager, 0.5);
+        ScriptManager::update(&script_manager, 0.5);
+        assert_eq!(vec!["start", "waited"], *steps.borrow());
+
+        *flag.borrow_mut() = true;
+        ScriptManager::update(&script_manager, 0.5);
+        assert_eq!(vec!["start", "waited", "done"], *steps.borrow());
+        assert!(!script_manager.borrow().is_running(handle));
+    }
+
+    #[test]
+    fn test_stop() {
+        let script_manager = ScriptManager::new();
+        let count = Rc::new(RefCell::new(0));
+
+        let counter = count.clone();
+        let handle = script_manager.borrow_mut().start(|script| async move {
+            loop {
+                *counter.borrow_mut() += 1;
+                script.next_frame().await;
+            }
+        });
+        ScriptManager::update(&script_manager, 0.1);
+        ScriptManager::update(&script_manager, 0.1);
+        assert_eq!(2, *count.borrow());
+
+        script_manager.borrow_mut().stop(handle);
+        ScriptManager::update(&script_manager, 0.1);
+        assert_eq!(2, *count.borrow());
+        assert!(!script_manager.borrow().is_running(handle));
+    }
+
+    #[test]
+    fn test_move_to() {
+        let script_manager = ScriptManager::new();
+        let actor = Rc::new(RefCell::new(TestActor::new()));
+        let reached = Rc::new(RefCell::new(None));
+
+        let (mover, result) = (actor.clone(), reached.clone());
+        script_manager.borrow_mut().start(|script| async move {
+            let arrived = script
+                .move_to(&*mover, Vector3::new(100.0, 0.0, 0.0), 1.0, Ease::Linear)
+                .await;
+            *result.borrow_mut() = Some(arrived);
+        });
+
+        ScriptManager::update(&script_manager, 0.25);
+        ScriptManager::update(&script_manager, 0.25);
+        assert_near_eq!(25.0, actor.borrow().get_position().x, 0.001);
+        for _ in 0..4 {
+            ScriptManager::update(&script_manager, 0.25);
+        }
+        assert_eq!(100.0, actor.borrow().get_position().x);
+        assert_eq!(Some(true), *reached.borrow());
+
+        // Gives up once the actor is dead
+        let mover = actor.clone();
+        let result = reached.clone();
+        script_manager.borrow_mut().start(|script| async move {
+            let arrived = script
+                .move_to(&*mover, Vector3::ZERO, 1.0, Ease::Linear)
+                .await;
+            *result.borrow_mut() = Some(arrived);
+        });
+        ScriptManager::update(&script_manager, 0.25);
+        actor.borrow_mut().set_state(State::Dead);
+        ScriptManager::update(&script_manager, 0.25);
+        assert_eq!(Some(false), *reached.borrow());
+    }
+}
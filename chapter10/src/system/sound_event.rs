@@ -1,100 +1,442 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{c_void, CStr},
+    rc::Weak,
+    sync::Mutex,
+};
 
+use anyhow::Result;
 use libfmod::{
-    ffi::{FMOD_STUDIO_STOP_ALLOWFADEOUT, FMOD_STUDIO_STOP_IMMEDIATE},
+    ffi::{
+        FMOD_OK, FMOD_RESULT, FMOD_STUDIO_EVENTINSTANCE, FMOD_STUDIO_EVENT_CALLBACK_TIMELINE_BEAT,
+        FMOD_STUDIO_EVENT_CALLBACK_TIMELINE_MARKER, FMOD_STUDIO_EVENT_CALLBACK_TYPE,
+        FMOD_STUDIO_STOP_ALLOWFADEOUT, FMOD_STUDIO_STOP_IMMEDIATE,
+        FMOD_STUDIO_TIMELINE_BEAT_PROPERTIES, FMOD_STUDIO_TIMELINE_MARKER_PROPERTIES,
+    },
     Attributes3d, EventInstance, PlaybackState, StopMode,
 };
 
-use crate::math::{matrix4::Matrix4, vector3::Vector3};
+use crate::math::{basic, matrix4::Matrix4, vector3::Vector3};
 
 use super::audio_system::AudioSystem;
 
+/// Marker or beat reached on the timeline of a playing event
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimelineEvent {
+    Marker {
+        name: String,
+        // Milliseconds from the start of the timeline
+        position: i32,
+    },
+    Beat {
+        bar: i32,
+        beat: i32,
+        position: i32,
+        tempo: f32,
+    },
+}
+
+// FMOD calls back from its own update thread, so events wait here
+// (keyed by the event instance address) until their SoundEvent takes them
+static TIMELINE_EVENTS: Mutex<Vec<(usize, TimelineEvent)>> = Mutex::new(vec![]);
+
+fn push_timeline_event(key: usize, event: TimelineEvent) {
+    if let Ok(mut events) = TIMELINE_EVENTS.lock() {
+        events.push((key, event));
+    }
+}
+
+fn take_timeline_events_of(key: usize) -> Vec<TimelineEvent> {
+    let mut events = match TIMELINE_EVENTS.lock() {
+        Ok(events) => events,
+        Err(_) => return vec![],
+    };
+    let (taken, rest) = events.drain(..).partition(|(k, _)| *k == key);
+    *events = rest;
+    taken.into_iter().map(|(_, event)| event).collect()
+}
+
+/// Drop timeline events nobody took, e.g. when the instance is released
+pub fn discard_timeline_events(instance: &EventInstance) {
+    take_timeline_events_of(instance.as_mut_ptr() as usize);
+}
+
+unsafe extern "C" fn timeline_callback(
+    callback_type: FMOD_STUDIO_EVENT_CALLBACK_TYPE,
+    event: *mut FMOD_STUDIO_EVENTINSTANCE,
+    parameters: *mut c_void,
+) -> FMOD_RESULT {
+    if parameters.is_null() {
+        return FMOD_OK;
+    }
+
+    let timeline_event = if callback_type == FMOD_STUDIO_EVENT_CALLBACK_TIMELINE_MARKER {
+        let properties = &*(parameters as *const FMOD_STUDIO_TIMELINE_MARKER_PROPERTIES);
+        let name = if properties.name.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(properties.name)
+                .to_string_lossy()
+                .into_owned()
+        };
+        TimelineEvent::Marker {
+            name,
+            position: properties.position,
+        }
+    } else if callback_type == FMOD_STUDIO_EVENT_CALLBACK_TIMELINE_BEAT {
+        let properties = &*(parameters as *const FMOD_STUDIO_TIMELINE_BEAT_PROPERTIES);
+        TimelineEvent::Beat {
+            bar: properties.bar,
+            beat: properties.beat,
+            position: properties.position,
+            tempo: properties.tempo,
+        }
+    } else {
+        return FMOD_OK;
+    };
+
+    push_timeline_event(event as usize, timeline_event);
+    FMOD_OK
+}
+
+/// Called when a fade ends, with the audio system to chain another fade or event
+pub type OnFadeComplete = Box<dyn FnOnce(&mut AudioSystem)>;
+
+/// Volume moving linearly to a target, stepped on AudioSystem::update
+pub struct Fade {
+    from: f32,
+    target: f32,
+    duration: f32,
+    elapsed: f32,
+    on_complete: Option<OnFadeComplete>,
+}
+
+impl Fade {
+    pub fn new(from: f32, target: f32, seconds: f32, on_complete: Option<OnFadeComplete>) -> Self {
+        Self {
+            from,
+            target,
+            duration: seconds.max(0.0),
+            elapsed: 0.0,
+            on_complete,
+        }
+    }
+
+    /// Volume after delta_time more seconds, never past the target
+    pub fn step(&mut self, delta_time: f32) -> f32 {
+        self.elapsed = (self.elapsed + delta_time).min(self.duration);
+        if self.duration <= 0.0 {
+            return self.target;
+        }
+        basic::lerp(self.from, self.target, self.elapsed / self.duration)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    pub fn into_on_complete(self) -> Option<OnFadeComplete> {
+        self.on_complete
+    }
+}
+
+/// Instance of a playing event and its fade, shared by AudioSystem and the handles
+pub struct PlayingEvent {
+    // Path of the event, to count its instances
+    pub name: String,
+    pub instance: EventInstance,
+    pub fade: Option<Fade>,
+}
+
+impl PlayingEvent {
+    pub fn new(name: &str, instance: EventInstance) -> Self {
+        Self {
+            name: name.to_string(),
+            instance,
+            fade: None,
+        }
+    }
+}
+
+/// Handle to a playing event. The instance is looked up by id in the instances of
+/// AudioSystem, so the handle turns invalid once the instance is released, and every
+/// call on an invalid handle does nothing
 pub struct SoundEvent {
     id: u32,
-    event_instance: Rc<RefCell<EventInstance>>,
+    event_instances: Weak<RefCell<HashMap<u32, PlayingEvent>>>,
 }
 
 impl SoundEvent {
-    pub fn new(id: u32, event_instance: Rc<RefCell<EventInstance>>) -> Self {
-        Self { id, event_instance }
+    pub fn new(id: u32, event_instances: Weak<RefCell<HashMap<u32, PlayingEvent>>>) -> Self {
+        Self {
+            id,
+            event_instances,
+        }
+    }
+
+    /// Plays nothing, never valid
+    pub fn inert() -> Self {
+        Self::new(0, Weak::new())
+    }
+
+    /// None once AudioSystem released the instance, or dropped
+    fn get_event_instance(&self) -> Option<EventInstance> {
+        let event_instances = self.event_instances.upgrade()?;
+        let event_instance = event_instances
+            .borrow()
+            .get(&self.id)
+            .map(|playing_event| playing_event.instance);
+        event_instance
     }
 
+    /// Replace the fade of the instance, if it wasn't released
+    fn set_fade(&mut self, fade: Option<Fade>) {
+        if let Some(event_instances) = self.event_instances.upgrade() {
+            if let Some(playing_event) = event_instances.borrow_mut().get_mut(&self.id) {
+                playing_event.fade = fade;
+            }
+        }
+    }
+
+    /// False once the event stopped or the instance was released
     pub fn is_valid(&self) -> bool {
-        let state = self.event_instance.borrow().get_playback_state().unwrap();
-        state != PlaybackState::Stopped
+        self.get_event_instance().is_some_and(|event_instance| {
+            event_instance
+                .get_playback_state()
+                .is_ok_and(|state| state != PlaybackState::Stopped)
+        })
     }
 
-    pub fn restart(&mut self) {
-        self.event_instance.borrow_mut().start().unwrap();
+    pub fn restart(&mut self) -> Result<()> {
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.start()?;
+        }
+        Ok(())
     }
 
-    pub fn stop(&mut self, allow_fade_out: bool) {
+    pub fn stop(&mut self, allow_fade_out: bool) -> Result<()> {
         let mode = if allow_fade_out {
             StopMode::AllowFadeout
         } else {
             StopMode::Immediate
         };
-        self.event_instance.borrow_mut().stop(mode).unwrap();
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.stop(mode)?;
+        }
+        Ok(())
     }
 
-    pub fn set_paused(&mut self, pause: bool) {
-        self.event_instance.borrow_mut().set_paused(pause).unwrap();
+    pub fn set_paused(&mut self, pause: bool) -> Result<()> {
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.set_paused(pause)?;
+        }
+        Ok(())
     }
 
-    pub fn set_volume(&mut self, value: f32) {
-        self.event_instance.borrow_mut().set_volume(value).unwrap();
+    /// Cancels the fade in progress, without calling it back
+    pub fn set_volume(&mut self, value: f32) -> Result<()> {
+        self.set_fade(None);
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.set_volume(value)?;
+        }
+        Ok(())
     }
 
-    pub fn set_pitch(&mut self, value: f32) {
-        self.event_instance.borrow_mut().set_pitch(value).unwrap();
+    /// Move the volume from where it is to the target over the seconds,
+    /// replacing the fade in progress without calling it back
+    pub fn fade_volume(&mut self, target: f32, seconds: f32) -> Result<()> {
+        self.start_fade(target, seconds, None)
     }
 
-    pub fn set_parameter(&mut self, name: &str, value: f32) {
-        self.event_instance
-            .borrow_mut()
-            .set_parameter_by_name(name, value, false)
-            .unwrap();
+    /// fade_volume, then on_complete once the target is reached or the event stopped.
+    /// Never called on an invalid handle
+    pub fn fade_volume_then(
+        &mut self,
+        target: f32,
+        seconds: f32,
+        on_complete: OnFadeComplete,
+    ) -> Result<()> {
+        self.start_fade(target, seconds, Some(on_complete))
     }
 
-    pub fn get_paused(&self) -> bool {
-        self.event_instance.borrow().get_paused().unwrap()
+    fn start_fade(
+        &mut self,
+        target: f32,
+        seconds: f32,
+        on_complete: Option<OnFadeComplete>,
+    ) -> Result<()> {
+        if let Some(event_instance) = self.get_event_instance() {
+            let volume = event_instance.get_volume()?.0;
+            self.set_fade(Some(Fade::new(volume, target, seconds, on_complete)));
+        }
+        Ok(())
     }
 
-    pub fn get_volume(&self) -> f32 {
-        self.event_instance.borrow().get_volume().unwrap().0
+    pub fn set_pitch(&mut self, value: f32) -> Result<()> {
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.set_pitch(value)?;
+        }
+        Ok(())
     }
 
-    pub fn get_pitch(&self) -> f32 {
-        self.event_instance.borrow().get_pitch().unwrap().0
+    pub fn set_parameter(&mut self, name: &str, value: f32) -> Result<()> {
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.set_parameter_by_name(name, value, false)?;
+        }
+        Ok(())
     }
 
-    pub fn get_parameter(&self, name: &str) -> f32 {
-        self.event_instance
-            .borrow()
-            .get_parameter_by_name(name)
-            .unwrap()
-            .0
+    pub fn get_paused(&self) -> Result<bool> {
+        match self.get_event_instance() {
+            Some(event_instance) => Ok(event_instance.get_paused()?),
+            None => Ok(false),
+        }
+    }
+
+    /// Invalid handles are silent
+    pub fn get_volume(&self) -> Result<f32> {
+        match self.get_event_instance() {
+            Some(event_instance) => Ok(event_instance.get_volume()?.0),
+            None => Ok(0.0),
+        }
+    }
+
+    pub fn get_pitch(&self) -> Result<f32> {
+        match self.get_event_instance() {
+            Some(event_instance) => Ok(event_instance.get_pitch()?.0),
+            None => Ok(1.0),
+        }
+    }
+
+    pub fn get_parameter(&self, name: &str) -> Result<f32> {
+        match self.get_event_instance() {
+            Some(event_instance) => Ok(event_instance.get_parameter_by_name(name)?.0),
+            None => Ok(0.0),
+        }
     }
 
     pub fn is_3d(&self) -> bool {
-        self.event_instance
-            .borrow()
-            .get_description()
-            .and_then(|description| description.is_3d())
-            .is_ok_and(|is_3d| is_3d)
+        self.get_event_instance().is_some_and(|event_instance| {
+            event_instance
+                .get_description()
+                .and_then(|description| description.is_3d())
+                .is_ok_and(|is_3d| is_3d)
+        })
     }
 
-    pub fn set_3d_attributes(&mut self, world_trans: &Matrix4) {
+    /// Velocity (units per second) is used for the Doppler effect
+    pub fn set_3d_attributes(&mut self, world_trans: &Matrix4, velocity: &Vector3) -> Result<()> {
+        let event_instance = match self.get_event_instance() {
+            Some(event_instance) => event_instance,
+            None => return Ok(()),
+        };
+
         let attributes = Attributes3d {
             position: AudioSystem::vector_to_fmod(&world_trans.get_translation()),
             forward: AudioSystem::vector_to_fmod(&world_trans.get_x_axis()),
             up: AudioSystem::vector_to_fmod(&world_trans.get_z_axis()),
-            velocity: AudioSystem::vector_to_fmod(&Vector3::ZERO),
+            velocity: AudioSystem::vector_to_fmod(velocity),
+        };
+
+        event_instance.set_3d_attributes(attributes)?;
+        Ok(())
+    }
+
+    /// Start collecting timeline markers and beats, read them with take_timeline_events
+    pub fn subscribe_timeline(&mut self) -> Result<()> {
+        if let Some(event_instance) = self.get_event_instance() {
+            event_instance.set_callback(
+                Some(timeline_callback),
+                FMOD_STUDIO_EVENT_CALLBACK_TIMELINE_MARKER
+                    | FMOD_STUDIO_EVENT_CALLBACK_TIMELINE_BEAT,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Markers and beats reached since the last call (filled on AudioSystem::update)
+    pub fn take_timeline_events(&mut self) -> Vec<TimelineEvent> {
+        match self.get_event_instance() {
+            Some(event_instance) => take_timeline_events_of(event_instance.as_mut_ptr() as usize),
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    use crate::assert_near_eq;
+
+    use super::{push_timeline_event, take_timeline_events_of, Fade, SoundEvent, TimelineEvent};
+
+    #[test]
+    fn test_take_timeline_events_of() {
+        let marker = TimelineEvent::Marker {
+            name: "Chorus".to_string(),
+            position: 1200,
+        };
+        let beat = TimelineEvent::Beat {
+            bar: 2,
+            beat: 1,
+            position: 2000,
+            tempo: 120.0,
         };
+        push_timeline_event(1, marker.clone());
+        push_timeline_event(2, beat.clone());
+        push_timeline_event(1, beat.clone());
+
+        // In order, and only the ones of the instance
+        assert_eq!(vec![marker, beat.clone()], take_timeline_events_of(1));
+        assert!(take_timeline_events_of(1).is_empty());
+        assert_eq!(vec![beat], take_timeline_events_of(2));
+    }
+
+    #[test]
+    fn test_inert_sound_event() {
+        let mut event = SoundEvent::inert();
+        assert!(!event.is_valid());
+
+        // Every call succeeds and does nothing
+        assert!(event.set_volume(0.5).is_ok());
+        assert!(event.fade_volume(1.0, 2.0).is_ok());
+        assert!(event.stop(true).is_ok());
+        assert_eq!(0.0, event.get_volume().unwrap());
+        assert!(event.take_timeline_events().is_empty());
+    }
+
+    #[test]
+    fn test_fade() {
+        let mut fade = Fade::new(1.0, 0.2, 2.0, None);
+        assert_near_eq!(0.8, fade.step(0.5), 0.001);
+        assert!(!fade.is_done());
+
+        // Stops at the target
+        assert_near_eq!(0.2, fade.step(5.0), 0.001);
+        assert!(fade.is_done());
+
+        // No duration reaches the target on the first step
+        let mut fade = Fade::new(0.0, 1.0, 0.0, Some(Box::new(|_| {})));
+        assert_eq!(1.0, fade.step(0.016));
+        assert!(fade.is_done());
+        assert!(fade.into_on_complete().is_some());
+    }
+
+    #[test]
+    fn test_released_sound_event() {
+        let event_instances = Rc::new(RefCell::new(HashMap::new()));
+        let mut event = SoundEvent::new(7, Rc::downgrade(&event_instances));
+
+        // Released by AudioSystem::update, or never played
+        assert!(event.get_event_instance().is_none());
+        assert!(!event.is_valid());
+        assert!(event.stop(true).is_ok());
+        assert!(event.set_parameter("Surface", 0.5).is_ok());
 
-        self.event_instance
-            .borrow_mut()
-            .set_3d_attributes(attributes)
-            .unwrap();
+        // The handle doesn't keep the instances alive
+        drop(event_instances);
+        assert!(event.get_event_instance().is_none());
     }
 }
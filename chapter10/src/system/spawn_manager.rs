@@ -0,0 +1,304 @@
+use std::{
+    cell::RefCell,
+    path::Path,
+    rc::{Rc, Weak},
+};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::{
+    actors::{
+        actor::{Actor, State},
+        pickup_actor::PickupActor,
+        spawn_point_actor::SpawnPointActor,
+    },
+    collision::aabb::AABB,
+    math::vector3::Vector3,
+};
+
+use super::{
+    crash_report::log,
+    engine_context::EngineContext,
+    entity_manager::EntityManager,
+    level_file::{LevelFile, PickupDefinition, WaveDefinition, WaveTrigger},
+    world_bounds::WorldBounds,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WaveState {
+    Waiting,
+    Spawning,
+    // Everything was spawned, some may still be alive
+    Done,
+}
+
+struct Wave {
+    definition: WaveDefinition,
+    state: WaveState,
+    spawned: u32,
+    spawn_timer: f32,
+    alive: Vec<Weak<RefCell<dyn Actor>>>,
+}
+
+impl Wave {
+    fn new(definition: WaveDefinition) -> Self {
+        Self {
+            definition,
+            state: WaveState::Waiting,
+            spawned: 0,
+            spawn_timer: 0.0,
+            alive: vec![],
+        }
+    }
+
+    /// Whether to spawn the next actor now
+    fn update(&mut self, delta_time: f32, is_triggered: bool) -> bool {
+        self.alive.retain(|actor| {
+            actor
+                .upgrade()
+                .is_some_and(|actor| *actor.borrow().get_state() == State::Active)
+        });
+
+        match self.state {
+            WaveState::Waiting if is_triggered => self.state = WaveState::Spawning,
+            WaveState::Waiting | WaveState::Done => return false,
+            WaveState::Spawning => {}
+        }
+
+        self.spawn_timer -= delta_time;
+        self.spawn_timer <= 0.0 && (self.alive.len() as u32) < self.definition.max_alive
+    }
+
+    /// Name of the spawn point for the next actor
+    fn next_spawn_point(&self) -> &str {
+        let spawn_points = &self.definition.spawn_points;
+        &spawn_points[self.spawned as usize % spawn_points.len()]
+    }
+
+    fn on_spawned(&mut self, actor: &Rc<RefCell<dyn Actor>>) {
+        self.alive.push(Rc::downgrade(actor));
+        self.spawned += 1;
+        self.spawn_timer = self.definition.interval;
+        if self.spawned >= self.definition.count {
+            self.state = WaveState::Done;
+        }
+    }
+
+    fn is_cleared(&self) -> bool {
+        self.state == WaveState::Done && self.alive.is_empty()
+    }
+}
+
+/// Spawns the waves of a level file as their triggers fire, and places its pickups
+pub struct SpawnManager {
+    context: EngineContext,
+    spawn_points: Vec<Rc<RefCell<SpawnPointActor>>>,
+    waves: Vec<Wave>,
+    pickup_definitions: Vec<PickupDefinition>,
+    pickups: Vec<Rc<RefCell<PickupActor>>>,
+    // Seconds since the round started
+    elapsed: f32,
+}
+
+impl SpawnManager {
+    pub fn new(context: EngineContext) -> Self {
+        Self {
+            context,
+            spawn_points: vec![],
+            waves: vec![],
+            pickup_definitions: vec![],
+            pickups: vec![],
+            elapsed: 0.0,
+        }
+    }
+
+    /// Loads the spawn points, waves and pickups of resources/Levels/<name>.json
+    pub fn load(&mut self, name: &str) -> Result<()> {
+        let path = Path::new(env!("OUT_DIR"))
+            .join("resources")
+            .join("Levels")
+            .join(format!("{}.json", name));
+        let content = std::fs::read_to_string(path)?;
+        let json: Value = serde_json::from_str(&content)?;
+        let level = LevelFile::parse(&json).map_err(|e| anyhow!("Level {}: {}", name, e))?;
+
+        for (spawn_point_name, position) in level.spawn_points {
+            let actor = SpawnPointActor::new(&self.context, &spawn_point_name);
+            actor.borrow_mut().set_position(position);
+            self.spawn_points.push(actor);
+        }
+
+        self.waves.extend(level.waves.into_iter().map(Wave::new));
+
+        self.load_world_bounds(level.world_bounds, level.checkpoints);
+
+        self.pickup_definitions.extend(level.pickups);
+        self.spawn_pickups();
+
+        Ok(())
+    }
+
+    /// Returns the actors spawned this frame
+    pub fn update(&mut self, delta_time: f32) -> Vec<Rc<RefCell<dyn Actor>>> {
+        self.elapsed += delta_time;
+
+        let player_position = self
+            .context
+            .entity_manager
+            .borrow()
+            .get_fps_actor()
+            .map(|player| player.borrow().get_position().clone());
+
+        let mut spawned = vec![];
+        let mut previous_cleared = true;
+        for i in 0..self.waves.len() {
+            let is_triggered = match &self.waves[i].definition.trigger {
+                WaveTrigger::Time(delay) => self.elapsed >= *delay,
+                WaveTrigger::Area(area) => player_position
+                    .as_ref()
+                    .is_some_and(|position| area.contains(position)),
+                WaveTrigger::Cleared => previous_cleared,
+            };
+
+            if self.waves[i].update(delta_time, is_triggered) {
+                spawned.extend(self.spawn(i));
+            }
+            previous_cleared = self.waves[i].is_cleared();
+        }
+
+        spawned
+    }
+
+    /// Remove what the waves spawned and wait for the triggers again
+    pub fn reset(&mut self) {
+        for wave in self.waves.iter_mut() {
+            for actor in wave.alive.iter().filter_map(Weak::upgrade) {
+                actor.borrow_mut().set_state(State::Dead);
+            }
+            *wave = Wave::new(wave.definition.clone());
+        }
+        self.elapsed = 0.0;
+
+        // Pickups taken for good come back with the others
+        for pickup in self.pickups.drain(..) {
+            pickup.borrow_mut().set_state(State::Dead);
+        }
+        self.spawn_pickups();
+    }
+
+    /// World bounds and checkpoints replace the default ones of the entity manager
+    fn load_world_bounds(&self, bounds: Option<AABB>, checkpoints: Vec<Vector3>) {
+        let mut entity_manager = self.context.entity_manager.borrow_mut();
+        if let Some(bounds) = bounds {
+            match entity_manager.get_world_bounds_mut() {
+                Some(world_bounds) => world_bounds.set_bounds(bounds),
+                None => entity_manager.set_world_bounds(WorldBounds::new(bounds, Vector3::ZERO)),
+            }
+        }
+
+        if let Some(world_bounds) = entity_manager.get_world_bounds_mut() {
+            for checkpoint in checkpoints {
+                world_bounds.add_checkpoint(checkpoint);
+            }
+        }
+    }
+
+    fn spawn_pickups(&mut self) {
+        for definition in &self.pickup_definitions {
+            let pickup = PickupActor::new(&self.context, definition.kind.clone());
+            pickup.borrow_mut().set_home(definition.position.clone());
+            pickup
+                .borrow_mut()
+                .set_respawn_time(definition.respawn_time);
+            self.pickups.push(pickup);
+        }
+    }
+
+    fn spawn(&mut self, wave_index: usize) -> Option<Rc<RefCell<dyn Actor>>> {
+        let wave = &mut self.waves[wave_index];
+        let spawn_point_name = wave.next_spawn_point();
+        let position = match self
+            .spawn_points
+            .iter()
+            .find(|spawn_point| spawn_point.borrow().get_name() == spawn_point_name)
+        {
+            Some(spawn_point) => spawn_point.borrow().get_position().clone(),
+            None => {
+                log!("Unknown spawn point {}", spawn_point_name);
+                Vector3::ZERO
+            }
+        };
+
+        match EntityManager::spawn_prefab(&self.context, &wave.definition.prefab, position) {
+            Ok(actor) => {
+                wave.on_spawned(&actor);
+                Some(actor)
+            }
+            Err(e) => {
+                log!("Failed to spawn {}: {}", wave.definition.prefab, e);
+                // Don't try again every frame
+                wave.state = WaveState::Done;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        actors::actor::{test::TestActor, Actor, State},
+        system::level_file::{WaveDefinition, WaveTrigger},
+    };
+
+    use super::Wave;
+
+    fn spawn(wave: &mut Wave) -> Rc<RefCell<dyn Actor>> {
+        let actor: Rc<RefCell<dyn Actor>> = Rc::new(RefCell::new(TestActor::new()));
+        wave.on_spawned(&actor);
+        actor
+    }
+
+    #[test]
+    fn test_wave_spawning() {
+        let mut wave = Wave::new(WaveDefinition {
+            prefab: "Enemy".to_string(),
+            spawn_points: vec!["Left".to_string(), "Right".to_string()],
+            trigger: WaveTrigger::Cleared,
+            count: 3,
+            max_alive: 2,
+            interval: 1.0,
+        });
+        assert!(!wave.update(1.0, false));
+
+        let mut actors: Vec<Rc<RefCell<dyn Actor>>> = vec![];
+
+        assert!(wave.update(0.0, true));
+        assert_eq!("Left", wave.next_spawn_point());
+        actors.push(spawn(&mut wave));
+
+        // Waits for the interval
+        assert!(!wave.update(0.5, false));
+        assert!(wave.update(0.5, false));
+        assert_eq!("Right", wave.next_spawn_point());
+        actors.push(spawn(&mut wave));
+
+        // Two alive is the cap
+        assert!(!wave.update(5.0, false));
+        actors[0].borrow_mut().set_state(State::Dead);
+        assert!(wave.update(0.0, false));
+        actors.push(spawn(&mut wave));
+
+        // All spawned
+        assert!(!wave.update(5.0, false));
+        assert!(!wave.is_cleared());
+        for actor in &actors {
+            actor.borrow_mut().set_state(State::Dead);
+        }
+        wave.update(0.0, false);
+        assert!(wave.is_cleared());
+    }
+}
@@ -0,0 +1,98 @@
+use std::{cell::RefCell, collections::HashMap, path::Path, rc::Rc};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// Localized UI text loaded from resources/Strings/<language>.json
+pub struct StringTable {
+    language: String,
+    strings: HashMap<String, String>,
+    // Incremented whenever the language changes, so cached text can be re-rendered
+    revision: u32,
+}
+
+impl StringTable {
+    pub const LANGUAGES: [&'static str; 2] = ["en", "ja"];
+
+    pub fn new() -> Rc<RefCell<Self>> {
+        let this = Self {
+            language: String::new(),
+            strings: HashMap::new(),
+            revision: 0,
+        };
+
+        Rc::new(RefCell::new(this))
+    }
+
+    pub fn load(&mut self, language: &str) -> Result<()> {
+        let path = Path::new(env!("OUT_DIR"))
+            .join("resources")
+            .join("Strings")
+            .join(format!("{}.json", language));
+        let content = std::fs::read_to_string(path)?;
+        let json: Value = serde_json::from_str(&content)?;
+
+        self.strings = StringTable::parse(&json)
+            .ok_or_else(|| anyhow!("String table {} is not an object", language))?;
+        self.language = language.to_string();
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    /// Switch to the next language in LANGUAGES
+    pub fn cycle_language(&mut self) -> Result<()> {
+        let index = StringTable::LANGUAGES
+            .iter()
+            .position(|language| *language == self.language)
+            .map_or(0, |index| (index + 1) % StringTable::LANGUAGES.len());
+        self.load(StringTable::LANGUAGES[index])
+    }
+
+    /// Returns the key itself if there is no text for it
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).map_or(key, |text| text.as_str())
+    }
+
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn get_revision(&self) -> u32 {
+        self.revision
+    }
+
+    fn parse(json: &Value) -> Option<HashMap<String, String>> {
+        let strings = json
+            .as_object()?
+            .iter()
+            .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+            .collect();
+        Some(strings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::StringTable;
+
+    #[test]
+    fn test_text() {
+        let string_table = StringTable::new();
+        let mut string_table = string_table.borrow_mut();
+        string_table.strings = StringTable::parse(&json!({
+            "chat": "チャット",
+            "count": 3,
+        }))
+        .unwrap();
+
+        assert_eq!("チャット", string_table.text("chat"));
+        // Missing and non string entries fall back to the key
+        assert_eq!("count", string_table.text("count"));
+        assert_eq!("missing", string_table.text("missing"));
+
+        assert!(StringTable::parse(&json!(["chat"])).is_none());
+    }
+}
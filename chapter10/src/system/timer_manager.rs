@@ -0,0 +1,211 @@
+use std::{cell::RefCell, rc::Rc};
+
+/// Identifies a scheduled timer, to cancel it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerHandle(u32);
+
+struct Timer {
+    handle: TimerHandle,
+    time_left: f32,
+    // Interval it is scheduled again with after firing, None to fire once
+    repeat: Option<f32>,
+    callback: Box<dyn FnMut()>,
+}
+
+/// Game clock calling back after delays, advanced only while the game is playing.
+/// The callbacks run with the manager unborrowed, so they can schedule and cancel timers
+pub struct TimerManager {
+    timers: Vec<Timer>,
+    next_id: u32,
+    // Seconds of gameplay since the start
+    time: f32,
+    // Cancelled during an update, while their timers are out of the list
+    cancelled: Vec<TimerHandle>,
+}
+
+impl TimerManager {
+    pub fn new() -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            timers: vec![],
+            next_id: 0,
+            time: 0.0,
+            cancelled: vec![],
+        }))
+    }
+
+    /// Call back after delay seconds, then every repeat seconds until cancelled if there is one
+    pub fn schedule(
+        &mut self,
+        delay: f32,
+        repeat: Option<f32>,
+        callback: Box<dyn FnMut()>,
+    ) -> TimerHandle {
+        let handle = TimerHandle(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer {
+            handle,
+            time_left: delay,
+            // A repeat of 0.0 would fire forever within one update
+            repeat: repeat.map(|repeat| repeat.max(f32::EPSILON)),
+            callback,
+        });
+        handle
+    }
+
+    /// Nothing happens if it already fired or was cancelled
+    pub fn cancel(&mut self, handle: TimerHandle) {
+        self.timers.retain(|timer| timer.handle != handle);
+        self.cancelled.push(handle);
+    }
+
+    /// Whether it is still to fire, always for a repeating one until cancelled
+    pub fn is_scheduled(&self, handle: TimerHandle) -> bool {
+        self.timers.iter().any(|timer| timer.handle == handle)
+    }
+
+    pub fn get_time_left(&self, handle: TimerHandle) -> Option<f32> {
+        self.timers
+            .iter()
+            .find(|timer| timer.handle == handle)
+            .map(|timer| timer.time_left.max(0.0))
+    }
+
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+
+    /// Advance the clock, calling back the timers that are due in the order they were due
+    pub fn update(timer_manager: &Rc<RefCell<TimerManager>>, delta_time: f32) {
+        let mut due = {
+            let mut this = timer_manager.borrow_mut();
+            this.time += delta_time;
+            let mut due = vec![];
+            let mut i = 0;
+            while i < this.timers.len() {
+                this.timers[i].time_left -= delta_time;
+                if this.timers[i].time_left <= 0.0 {
+                    due.push(this.timers.remove(i));
+                } else {
+                    i += 1;
+                }
+            }
+            due
+        };
+        // Stable, so timers due at once keep the order they were scheduled in
+        due.sort_by(|a, b| a.time_left.total_cmp(&b.time_left));
+
+        let mut rescheduled = vec![];
+        for mut timer in due {
+            // An earlier callback may have cancelled it
+            if timer_manager.borrow().cancelled.contains(&timer.handle) {
+                continue;
+            }
+            (timer.callback)();
+            if let Some(repeat) = timer.repeat {
+                // Keeps the overshoot, so the interval doesn't drift with the frame rate
+                timer.time_left += repeat;
+                rescheduled.push(timer);
+            }
+        }
+
+        let mut this = timer_manager.borrow_mut();
+        for timer in rescheduled {
+            // Cancelled from its own callback or a later one
+            if !this.cancelled.contains(&timer.handle) {
+                this.timers.push(timer);
+            }
+        }
+        this.cancelled.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::assert_near_eq;
+
+    use super::TimerManager;
+
+    #[test]
+    fn test_schedule() {
+        let timer_manager = TimerManager::new();
+        let fired = Rc::new(RefCell::new(vec![]));
+
+        let log = fired.clone();
+        timer_manager.borrow_mut().schedule(
+            1.0,
+            None,
+            Box::new(move || log.borrow_mut().push("once")),
+        );
+        let log = fired.clone();
+        let repeating = timer_manager.borrow_mut().schedule(
+            0.5,
+            Some(0.5),
+            Box::new(move || log.borrow_mut().push("repeat")),
+        );
+
+        TimerManager::update(&timer_manager, 0.25);
+        assert!(fired.borrow().is_empty());
+        assert_eq!(Some(0.25), timer_manager.borrow().get_time_left(repeating));
+
+        // Due first fires first
+        TimerManager::update(&timer_manager, 0.8);
+        assert_eq!(vec!["repeat", "once"], *fired.borrow());
+        assert!(timer_manager.borrow().is_scheduled(repeating));
+
+        TimerManager::update(&timer_manager, 0.5);
+        assert_eq!(vec!["repeat", "once", "repeat"], *fired.borrow());
+
+        timer_manager.borrow_mut().cancel(repeating);
+        TimerManager::update(&timer_manager, 10.0);
+        assert_eq!(3, fired.borrow().len());
+        assert!(!timer_manager.borrow().is_scheduled(repeating));
+        assert_near_eq!(11.55, timer_manager.borrow().get_time(), 0.001);
+    }
+
+    #[test]
+    fn test_cancel_from_callback() {
+        let timer_manager = TimerManager::new();
+        let fired = Rc::new(RefCell::new(0));
+
+        // The first cancels the second, due in the same update
+        let second = Rc::new(RefCell::new(None));
+        let (manager, handle) = (timer_manager.clone(), second.clone());
+        timer_manager.borrow_mut().schedule(
+            0.1,
+            None,
+            Box::new(move || {
+                if let Some(handle) = handle.borrow_mut().take() {
+                    manager.borrow_mut().cancel(handle);
+                }
+            }),
+        );
+        let counter = fired.clone();
+        *second.borrow_mut() = Some(timer_manager.borrow_mut().schedule(
+            0.2,
+            None,
+            Box::new(move || *counter.borrow_mut() += 1),
+        ));
+
+        // Scheduling from a callback waits for the next update
+        let (manager, counter) = (timer_manager.clone(), fired.clone());
+        timer_manager.borrow_mut().schedule(
+            0.1,
+            None,
+            Box::new(move || {
+                let counter = counter.clone();
+                manager.borrow_mut().schedule(
+                    0.0,
+                    None,
+                    Box::new(move || *counter.borrow_mut() += 10),
+                );
+            }),
+        );
+
+        TimerManager::update(&timer_manager, 0.5);
+        assert_eq!(0, *fired.borrow());
+        TimerManager::update(&timer_manager, 0.0);
+        assert_eq!(10, *fired.borrow());
+    }
+}
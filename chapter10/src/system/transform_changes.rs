@@ -0,0 +1,25 @@
+use std::{cell::RefCell, collections::HashSet};
+
+thread_local! {
+    static CHANGED: RefCell<HashSet<u32>> = RefCell::new(HashSet::new());
+}
+
+/// Called when the world transform of the actor is recomputed
+pub fn record(actor_id: u32) {
+    CHANGED.with(|changed| changed.borrow_mut().insert(actor_id));
+}
+
+/// Whether the actor moved since the list was last cleared
+pub fn was_changed(actor_id: u32) -> bool {
+    CHANGED.with(|changed| changed.borrow().contains(&actor_id))
+}
+
+pub fn get_changed_count() -> usize {
+    CHANGED.with(|changed| changed.borrow().len())
+}
+
+/// Called once per frame, after the physics and the renderer read the list.
+/// The set keeps its capacity, so recording doesn't allocate after the first frames
+pub fn clear() {
+    CHANGED.with(|changed| changed.borrow_mut().clear());
+}
@@ -0,0 +1,145 @@
+use crate::math::basic;
+
+/// Shape of a tween over its duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ease {
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    // Overshoots the end a little, then settles on it
+    OutBack,
+}
+
+impl Ease {
+    const BACK_OVERSHOOT: f32 = 1.70158;
+
+    /// Eased progress of t in [0, 1], 0 at the start and 1 at the end
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::InQuad => t * t,
+            Ease::OutQuad => t * (2.0 - t),
+            Ease::InOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Ease::OutBack => {
+                let c = Ease::BACK_OVERSHOOT;
+                let u = t - 1.0;
+                1.0 + (c + 1.0) * u.powi(3) + c * u.powi(2)
+            }
+        }
+    }
+}
+
+/// Value going from one end to the other over a duration, along an easing curve.
+/// Updated by its owner every frame
+pub struct Tween {
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+    ease: Ease,
+    on_complete: Option<Box<dyn FnOnce()>>,
+}
+
+impl Tween {
+    pub fn new(from: f32, to: f32, duration: f32, ease: Ease) -> Self {
+        Self {
+            from,
+            to,
+            duration: duration.max(0.0),
+            elapsed: 0.0,
+            ease,
+            on_complete: None,
+        }
+    }
+
+    /// Resting at the value until the next tween replaces it
+    pub fn at(value: f32) -> Self {
+        Tween::new(value, value, 0.0, Ease::Linear)
+    }
+
+    /// Called once, on the update reaching the end
+    pub fn with_on_complete(mut self, on_complete: Box<dyn FnOnce()>) -> Self {
+        self.on_complete = Some(on_complete);
+        self
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.elapsed = (self.elapsed + delta_time).min(self.duration);
+        if self.is_done() {
+            if let Some(on_complete) = self.on_complete.take() {
+                on_complete();
+            }
+        }
+    }
+
+    pub fn get_value(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return self.to;
+        }
+        basic::lerp(
+            self.from,
+            self.to,
+            self.ease.apply(self.elapsed / self.duration),
+        )
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::assert_near_eq;
+
+    use super::{Ease, Tween};
+
+    #[test]
+    fn test_ease() {
+        for ease in [
+            Ease::Linear,
+            Ease::InQuad,
+            Ease::OutQuad,
+            Ease::InOutQuad,
+            Ease::OutBack,
+        ] {
+            assert_near_eq!(0.0, ease.apply(0.0), 0.001);
+            assert_near_eq!(1.0, ease.apply(1.0), 0.001);
+        }
+        assert!(Ease::InQuad.apply(0.5) < 0.5);
+        assert!(Ease::OutQuad.apply(0.5) > 0.5);
+        assert_near_eq!(0.5, Ease::InOutQuad.apply(0.5), 0.001);
+        assert!(Ease::OutBack.apply(0.8) > 1.0);
+    }
+
+    #[test]
+    fn test_tween() {
+        let completed = Rc::new(Cell::new(0));
+        let counter = completed.clone();
+        let mut tween = Tween::new(10.0, 20.0, 2.0, Ease::Linear)
+            .with_on_complete(Box::new(move || counter.set(counter.get() + 1)));
+
+        tween.update(0.5);
+        assert_near_eq!(12.5, tween.get_value(), 0.001);
+        assert!(!tween.is_done());
+
+        // Stops at the end, calling back once
+        tween.update(5.0);
+        tween.update(1.0);
+        assert_eq!(20.0, tween.get_value());
+        assert!(tween.is_done());
+        assert_eq!(1, completed.get());
+
+        assert_eq!(3.0, Tween::at(3.0).get_value());
+    }
+}
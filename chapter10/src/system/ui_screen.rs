@@ -0,0 +1,399 @@
+use std::{ptr::null, rc::Rc};
+
+use gl::{TRIANGLES, UNSIGNED_INT};
+use sdl2::{controller, keyboard::Scancode, mouse::MouseButton};
+
+use crate::{
+    graphics::{shader::Shader, texture::Texture},
+    math::{matrix4::Matrix4, vector2::Vector2, vector3::Vector3},
+};
+
+use super::{
+    input_system::{ButtonState, InputState},
+    tween::{Ease, Tween},
+};
+
+/// Clickable button, positioned in screen space with the origin at the center
+pub struct Button {
+    // Pre-rendered text drawn over the button, if the texture was found
+    label: Option<Rc<Texture>>,
+    position: Vector2,
+    dimensions: Vector2,
+    on_click: Box<dyn Fn()>,
+    highlighted: bool,
+    pressed: bool,
+}
+
+impl Button {
+    pub fn new(
+        label: Option<Rc<Texture>>,
+        position: Vector2,
+        dimensions: Vector2,
+        on_click: Box<dyn Fn()>,
+    ) -> Self {
+        Self {
+            label,
+            position,
+            dimensions,
+            on_click,
+            highlighted: false,
+            pressed: false,
+        }
+    }
+
+    fn contains_point(&self, point: &Vector2) -> bool {
+        let half_width = self.dimensions.x / 2.0;
+        let half_height = self.dimensions.y / 2.0;
+        (point.x - self.position.x).abs() <= half_width
+            && (point.y - self.position.y).abs() <= half_height
+    }
+
+    pub fn on_click(&self) {
+        (self.on_click)();
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UIState {
+    Active,
+    Closing,
+}
+
+/// Screen of the UI stack (e.g. the pause menu). Only the top screen gets input.
+/// Focus moves with the mouse, the arrow keys or the d-pad, and the focused button
+/// is clicked with the left mouse button, Enter/Space or the A button.
+pub struct UIScreen {
+    // Pre-rendered title drawn above the buttons, if the texture was found
+    title: Option<Rc<Texture>>,
+    buttons: Vec<Button>,
+    focus: Option<usize>,
+    state: UIState,
+    button_on: Rc<Texture>,
+    button_off: Rc<Texture>,
+    screen_size: Vector2,
+    next_button_position: Vector2,
+    // Hovering only moves the focus when the mouse moves, so it doesn't fight the keys
+    last_mouse_position: Vector2,
+    // Vertical offset of the buttons, sliding them in when the screen opens
+    slide: Tween,
+}
+
+impl UIScreen {
+    const BUTTON_SPACING: f32 = 75.0;
+    const TITLE_POSITION: Vector2 = Vector2::new(0.0, 250.0);
+    // Scale of the button texture while it's held down
+    const PRESSED_SCALE: f32 = 0.95;
+    // The buttons slide up from this far below their place
+    const SLIDE_DISTANCE: f32 = 300.0;
+    const SLIDE_TIME: f32 = 0.3;
+
+    pub fn new(
+        title: Option<Rc<Texture>>,
+        button_on: Rc<Texture>,
+        button_off: Rc<Texture>,
+        screen_size: Vector2,
+    ) -> Self {
+        Self {
+            title,
+            buttons: vec![],
+            focus: None,
+            state: UIState::Active,
+            button_on,
+            button_off,
+            screen_size,
+            next_button_position: Vector2::new(0.0, 100.0),
+            last_mouse_position: Vector2::ZERO,
+            slide: Tween::new(
+                -UIScreen::SLIDE_DISTANCE,
+                0.0,
+                UIScreen::SLIDE_TIME,
+                Ease::OutBack,
+            ),
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.slide.update(delta_time);
+    }
+
+    /// Add a button below the previous one. The first button gets the focus.
+    pub fn add_button(&mut self, label: Option<Rc<Texture>>, on_click: Box<dyn Fn()>) {
+        let dimensions = Vector2::new(
+            self.button_off.get_width() as f32,
+            self.button_off.get_height() as f32,
+        );
+        let height = dimensions.y;
+        self.buttons.push(Button::new(
+            label,
+            self.next_button_position.clone(),
+            dimensions,
+            on_click,
+        ));
+        self.next_button_position.y -= height + UIScreen::BUTTON_SPACING;
+
+        if self.focus.is_none() {
+            self.set_focus(Some(0));
+        }
+    }
+
+    /// Expects the mouse to be in absolute mode
+    pub fn process_input(&mut self, state: &InputState) {
+        let mouse_position = self.to_screen_point(state.mouse.get_position());
+        if mouse_position != self.last_mouse_position {
+            self.last_mouse_position = mouse_position.clone();
+            if let Some(index) = self.find_button(&mouse_position) {
+                self.set_focus(Some(index));
+            }
+        }
+
+        let keyboard = &state.keyboard;
+        let controller = &state.controller;
+
+        if keyboard.get_key_state(Scancode::Escape) == ButtonState::Pressed
+            || controller.get_button_state(controller::Button::B) == ButtonState::Pressed
+            || controller.get_button_state(controller::Button::Start) == ButtonState::Pressed
+        {
+            self.close();
+            return;
+        }
+
+        if keyboard.get_key_state(Scancode::Up) == ButtonState::Pressed
+            || controller.get_button_state(controller::Button::DPadUp) == ButtonState::Pressed
+        {
+            self.move_focus(-1);
+        }
+        if keyboard.get_key_state(Scancode::Down) == ButtonState::Pressed
+            || controller.get_button_state(controller::Button::DPadDown) == ButtonState::Pressed
+        {
+            self.move_focus(1);
+        }
+
+        let activate = [
+            keyboard.get_key_state(Scancode::Return),
+            keyboard.get_key_state(Scancode::Space),
+            controller.get_button_state(controller::Button::A),
+        ];
+        if activate.contains(&ButtonState::Pressed) {
+            self.press();
+        }
+        if activate.contains(&ButtonState::Released) {
+            self.release();
+        }
+
+        let hovered = self.find_button(&mouse_position);
+        match state.mouse.get_button_state(MouseButton::Left) {
+            ButtonState::Pressed if hovered.is_some() => {
+                self.set_focus(hovered);
+                self.press();
+            }
+            ButtonState::Released if hovered.is_some() && hovered == self.focus => {
+                self.release();
+            }
+            ButtonState::Released => {
+                // Dragged off the button, so cancel the click
+                self.buttons
+                    .iter_mut()
+                    .for_each(|button| button.pressed = false);
+            }
+            _ => {}
+        }
+    }
+
+    /// Expects the sprite shader and sprite verts to be active
+    pub fn draw(&self, shader: &Shader) {
+        if let Some(title) = &self.title {
+            UIScreen::draw_texture(shader, title, &UIScreen::TITLE_POSITION, 1.0);
+        }
+
+        for button in &self.buttons {
+            let texture = if button.highlighted {
+                &self.button_on
+            } else {
+                &self.button_off
+            };
+            let scale = if button.pressed {
+                UIScreen::PRESSED_SCALE
+            } else {
+                1.0
+            };
+            let position = Vector2::new(
+                button.position.x,
+                button.position.y + self.slide.get_value(),
+            );
+
+            UIScreen::draw_texture(shader, texture, &position, scale);
+            if let Some(label) = &button.label {
+                UIScreen::draw_texture(shader, label, &position, scale);
+            }
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.state = UIState::Closing;
+    }
+
+    pub fn get_state(&self) -> &UIState {
+        &self.state
+    }
+
+    /// Buttons are placed from the center, so only the mouse mapping changes
+    pub fn set_screen_size(&mut self, screen_size: Vector2) {
+        self.screen_size = screen_size;
+    }
+
+    /// Move the focus by step buttons, wrapping around at both ends
+    fn move_focus(&mut self, step: i32) {
+        if self.buttons.is_empty() {
+            return;
+        }
+        let count = self.buttons.len() as i32;
+        let index = match self.focus {
+            Some(index) => (index as i32 + step).rem_euclid(count),
+            None => 0,
+        };
+        self.set_focus(Some(index as usize));
+    }
+
+    fn set_focus(&mut self, focus: Option<usize>) {
+        if self.focus != focus {
+            // A click only counts on the button it started on
+            self.buttons
+                .iter_mut()
+                .for_each(|button| button.pressed = false);
+        }
+        self.focus = focus;
+        for (index, button) in self.buttons.iter_mut().enumerate() {
+            button.highlighted = Some(index) == focus;
+        }
+    }
+
+    fn press(&mut self) {
+        if let Some(button) = self.focus.and_then(|index| self.buttons.get_mut(index)) {
+            button.pressed = true;
+        }
+    }
+
+    fn release(&mut self) {
+        if let Some(button) = self.focus.and_then(|index| self.buttons.get_mut(index)) {
+            if button.pressed {
+                button.pressed = false;
+                button.on_click();
+            }
+        }
+    }
+
+    /// Hit where the buttons are drawn, while they slide too
+    fn find_button(&self, point: &Vector2) -> Option<usize> {
+        let point = Vector2::new(point.x, point.y - self.slide.get_value());
+        self.buttons
+            .iter()
+            .position(|button| button.contains_point(&point))
+    }
+
+    /// Centered on position, at the texture's own size times scale
+    fn draw_texture(shader: &Shader, texture: &Texture, position: &Vector2, scale: f32) {
+        let scale_mat = Matrix4::create_scale_xyz(
+            texture.get_width() as f32 * scale,
+            texture.get_height() as f32 * scale,
+            1.0,
+        );
+        let translation = Matrix4::create_translation(&Vector3::new(position.x, position.y, 0.0));
+
+        shader.set_matrix_uniform("uWorldTransform", scale_mat * translation);
+        texture.set_active();
+
+        unsafe {
+            gl::DrawElements(TRIANGLES, 6, UNSIGNED_INT, null());
+        }
+    }
+
+    /// Window coordinates (origin at top left, y down) to UI coordinates
+    fn to_screen_point(&self, mouse_position: &Vector2) -> Vector2 {
+        Vector2::new(
+            mouse_position.x - self.screen_size.x / 2.0,
+            self.screen_size.y / 2.0 - mouse_position.y,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{graphics::texture::Texture, math::vector2::Vector2};
+
+    use super::{Button, UIScreen};
+
+    #[test]
+    fn test_contains_point() {
+        let button = Button::new(
+            None,
+            Vector2::new(0.0, 100.0),
+            Vector2::new(200.0, 50.0),
+            Box::new(|| {}),
+        );
+
+        assert!(button.contains_point(&Vector2::new(-100.0, 125.0)));
+        assert!(!button.contains_point(&Vector2::new(0.0, 50.0)));
+    }
+
+    #[test]
+    fn test_slide_in() {
+        let texture = Rc::new(Texture::new());
+        let mut screen = UIScreen::new(None, texture.clone(), texture, Vector2::new(1024.0, 768.0));
+        screen.buttons.push(Button::new(
+            None,
+            Vector2::new(0.0, 100.0),
+            Vector2::new(200.0, 50.0),
+            Box::new(|| {}),
+        ));
+
+        // Starts below its place
+        assert_eq!(None, screen.find_button(&Vector2::new(0.0, 100.0)));
+        assert_eq!(
+            Some(0),
+            screen.find_button(&Vector2::new(0.0, 100.0 - UIScreen::SLIDE_DISTANCE))
+        );
+
+        screen.update(UIScreen::SLIDE_TIME);
+        assert_eq!(Some(0), screen.find_button(&Vector2::new(0.0, 100.0)));
+    }
+
+    #[test]
+    fn test_focus_and_click() {
+        let texture = Rc::new(Texture::new());
+        let mut screen = UIScreen::new(None, texture.clone(), texture, Vector2::new(1024.0, 768.0));
+        let clicked = Rc::new(RefCell::new(vec![]));
+        for text in ["resume", "quit"] {
+            let clicked = clicked.clone();
+            screen.add_button(None, Box::new(move || clicked.borrow_mut().push(text)));
+        }
+
+        assert_eq!(Some(0), screen.focus);
+        assert!(screen.buttons[0].highlighted);
+
+        // Wraps around in both directions
+        screen.move_focus(1);
+        assert_eq!(Some(1), screen.focus);
+        screen.move_focus(1);
+        assert_eq!(Some(0), screen.focus);
+        screen.move_focus(-1);
+        assert_eq!(Some(1), screen.focus);
+        assert!(!screen.buttons[0].highlighted);
+
+        // Releasing without pressing first doesn't click
+        screen.release();
+        assert!(clicked.borrow().is_empty());
+
+        screen.press();
+        assert!(screen.buttons[1].pressed);
+        screen.release();
+        assert_eq!(vec!["quit"], *clicked.borrow());
+
+        // Moving the focus cancels the press
+        screen.press();
+        screen.move_focus(1);
+        screen.release();
+        assert_eq!(vec!["quit"], *clicked.borrow());
+    }
+}
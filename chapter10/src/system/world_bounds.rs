@@ -0,0 +1,132 @@
+use crate::{collision::aabb::AABB, math::vector3::Vector3};
+
+/// What happens to an actor leaving the world bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsPolicy {
+    // Removed, e.g. projectiles
+    Destroy,
+    // Back to the active checkpoint when below the kill-Z, kept inside otherwise, e.g. the player
+    Respawn,
+    // Kept inside, e.g. walking enemies and cameras
+    Clamp,
+}
+
+/// Box the actors live in, its bottom is the kill-Z, with the checkpoints players respawn at
+pub struct WorldBounds {
+    bounds: AABB,
+    checkpoints: Vec<Vector3>,
+    // Last checkpoint the player came close to
+    active_checkpoint: usize,
+}
+
+impl WorldBounds {
+    // Horizontal distance a checkpoint is reached within, whatever the height of the ground
+    const CHECKPOINT_RADIUS: f32 = 150.0;
+
+    /// The first checkpoint is active until the player reaches another one
+    pub fn new(bounds: AABB, start: Vector3) -> Self {
+        Self {
+            bounds,
+            checkpoints: vec![start],
+            active_checkpoint: 0,
+        }
+    }
+
+    pub fn get_bounds(&self) -> &AABB {
+        &self.bounds
+    }
+
+    pub fn set_bounds(&mut self, bounds: AABB) {
+        self.bounds = bounds;
+    }
+
+    pub fn add_checkpoint(&mut self, position: Vector3) {
+        self.checkpoints.push(position);
+    }
+
+    pub fn get_active_checkpoint(&self) -> &Vector3 {
+        &self.checkpoints[self.active_checkpoint]
+    }
+
+    /// Activate the checkpoint the player stands at, if any
+    pub fn update_checkpoint(&mut self, player_position: &Vector3) {
+        let radius_sq = WorldBounds::CHECKPOINT_RADIUS * WorldBounds::CHECKPOINT_RADIUS;
+        if let Some(index) = self.checkpoints.iter().position(|checkpoint| {
+            let dx = checkpoint.x - player_position.x;
+            let dy = checkpoint.y - player_position.y;
+            dx * dx + dy * dy <= radius_sq
+        }) {
+            self.active_checkpoint = index;
+        }
+    }
+
+    /// Where an actor at position goes, None to destroy it
+    pub fn resolve(&self, policy: BoundsPolicy, position: &Vector3) -> Option<Vector3> {
+        if self.bounds.contains(position) {
+            return Some(position.clone());
+        }
+
+        let clamped = Vector3::new(
+            position.x.clamp(self.bounds.min.x, self.bounds.max.x),
+            position.y.clamp(self.bounds.min.y, self.bounds.max.y),
+            position.z.clamp(self.bounds.min.z, self.bounds.max.z),
+        );
+        match policy {
+            BoundsPolicy::Destroy => None,
+            BoundsPolicy::Respawn if position.z < self.bounds.min.z => {
+                Some(self.get_active_checkpoint().clone())
+            }
+            BoundsPolicy::Respawn | BoundsPolicy::Clamp => Some(clamped),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{collision::aabb::AABB, math::vector3::Vector3};
+
+    use super::{BoundsPolicy, WorldBounds};
+
+    #[test]
+    fn test_resolve() {
+        let mut world_bounds = WorldBounds::new(
+            AABB::new(
+                Vector3::new(-1000.0, -1000.0, -500.0),
+                Vector3::new(1000.0, 1000.0, 1000.0),
+            ),
+            Vector3::ZERO,
+        );
+        let inside = Vector3::new(100.0, 200.0, 0.0);
+        let fallen = Vector3::new(100.0, 200.0, -600.0);
+        let outside = Vector3::new(1200.0, 0.0, 0.0);
+
+        assert_eq!(
+            Some(inside.clone()),
+            world_bounds.resolve(BoundsPolicy::Destroy, &inside)
+        );
+        assert_eq!(None, world_bounds.resolve(BoundsPolicy::Destroy, &fallen));
+        assert_eq!(
+            Some(Vector3::new(100.0, 200.0, -500.0)),
+            world_bounds.resolve(BoundsPolicy::Clamp, &fallen)
+        );
+
+        // Falling goes back to the checkpoint, leaving through a side is blocked
+        world_bounds.add_checkpoint(Vector3::new(500.0, 500.0, 0.0));
+        world_bounds.update_checkpoint(&Vector3::new(450.0, 500.0, 0.0));
+        assert_eq!(
+            Some(Vector3::new(500.0, 500.0, 0.0)),
+            world_bounds.resolve(BoundsPolicy::Respawn, &fallen)
+        );
+        assert_eq!(
+            Some(Vector3::new(1000.0, 0.0, 0.0)),
+            world_bounds.resolve(BoundsPolicy::Respawn, &outside)
+        );
+
+        // Far from any checkpoint, the active one stays
+        world_bounds.update_checkpoint(&Vector3::new(-800.0, 0.0, 0.0));
+        assert_eq!(
+            &Vector3::new(500.0, 500.0, 0.0),
+            world_bounds.get_active_checkpoint()
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+};
+
+use anyhow::Result;
+
+/// Game of a chapter, built as its own binary in the workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chapter {
+    // Package and binary name
+    pub name: &'static str,
+    pub title: &'static str,
+}
+
+pub const CHAPTERS: [Chapter; 10] = [
+    Chapter::new("chapter01", "Pong"),
+    Chapter::new("chapter02", "Side scrolling ship"),
+    Chapter::new("chapter03", "Asteroids"),
+    Chapter::new("chapter04", "Tower defense"),
+    Chapter::new("chapter05", "Asteroids with OpenGL"),
+    Chapter::new("chapter06", "3D graphics"),
+    Chapter::new("chapter07", "Audio"),
+    Chapter::new("chapter08", "Input"),
+    Chapter::new("chapter09", "Cameras"),
+    Chapter::new("chapter10", "Collision detection"),
+];
+
+impl Chapter {
+    const fn new(name: &'static str, title: &'static str) -> Self {
+        Self { name, title }
+    }
+
+    /// Chapter by its number or package name, e.g. "3", "03" or "chapter03"
+    pub fn find(selection: &str) -> Option<Chapter> {
+        let selection = selection.trim();
+        let number = selection.strip_prefix("chapter").unwrap_or(selection);
+        let number: usize = number.parse().ok()?;
+        CHAPTERS.get(number.checked_sub(1)?).copied()
+    }
+
+    /// Run the game and wait until its window is closed. Every chapter is started
+    /// from the same directory, so they share the settings saved there
+    pub fn run(&self, work_dir: &Path) -> Result<ExitStatus> {
+        let mut command = match self.find_executable() {
+            Some(executable) => Command::new(executable),
+            None => {
+                // Not built yet, let cargo build it
+                let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+                let manifest = Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("..")
+                    .join("Cargo.toml");
+                let mut command = Command::new(cargo);
+                command
+                    .arg("run")
+                    .arg("--manifest-path")
+                    .arg(manifest)
+                    .args(["--package", self.name]);
+                command
+            }
+        };
+        Ok(command.current_dir(work_dir).status()?)
+    }
+
+    /// Binary next to the launcher, built by the same cargo profile
+    fn find_executable(&self) -> Option<PathBuf> {
+        let executable = env::current_exe().ok()?.parent()?.join(format!(
+            "{}{}",
+            self.name,
+            env::consts::EXE_SUFFIX
+        ));
+        executable.is_file().then_some(executable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chapter, CHAPTERS};
+
+    #[test]
+    fn test_find() {
+        assert_eq!(Some(CHAPTERS[2]), Chapter::find("3"));
+        assert_eq!(Some(CHAPTERS[2]), Chapter::find(" 03\n"));
+        assert_eq!(Some(CHAPTERS[9]), Chapter::find("chapter10"));
+        assert_eq!(None, Chapter::find("0"));
+        assert_eq!(None, Chapter::find("11"));
+        assert_eq!(None, Chapter::find("pong"));
+    }
+}
@@ -0,0 +1,48 @@
+mod chapter;
+
+use std::{
+    env,
+    io::{self, BufRead, Write},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::chapter::{Chapter, CHAPTERS};
+
+fn main() -> Result<()> {
+    let work_dir = env::current_dir()?;
+
+    // Start the chapter right away if one is given, e.g. `launcher 10`
+    if let Some(selection) = env::args().nth(1) {
+        let chapter =
+            Chapter::find(&selection).ok_or_else(|| anyhow!("Unknown chapter {}", selection))?;
+        chapter.run(&work_dir)?;
+        return Ok(());
+    }
+
+    // Back to the list each time a game is closed
+    let stdin = io::stdin();
+    loop {
+        for (i, chapter) in CHAPTERS.iter().enumerate() {
+            println!("{:>2}: {}", i + 1, chapter.title);
+        }
+        print!("Select a chapter (q to quit): ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 || line.trim() == "q" {
+            return Ok(());
+        }
+
+        match Chapter::find(&line) {
+            Some(chapter) => match chapter.run(&work_dir) {
+                Ok(status) if !status.success() => {
+                    println!("{} exited with {}", chapter.name, status)
+                }
+                Ok(_) => {}
+                Err(e) => println!("Failed to start {}: {}", chapter.name, e),
+            },
+            None => println!("Unknown chapter {}", line.trim()),
+        }
+    }
+}